@@ -109,6 +109,11 @@ impl<'a> Writer<'a> {
     pub fn mut_used_slice(&mut self) -> &mut [u8] {
         &mut self.buf[..self.offs]
     }
+
+    /// Drop what has been written, e.g. to replace a response with an error.
+    pub fn clear(&mut self) {
+        self.offs = 0;
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -171,7 +176,7 @@ impl Codec for u16 {
 
 // Make a distinct type for u24, even though it's a u32 underneath
 #[allow(non_camel_case_types)]
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub struct u24(u32);
 
 impl u24 {
@@ -180,6 +185,15 @@ impl u24 {
         u24(v)
     }
 
+    /// Like new, but return None instead of panicking if v does not fit in 24 bits.
+    pub fn try_new(v: u32) -> Option<u24> {
+        if v >> 24 != 0 {
+            None
+        } else {
+            Some(u24(v))
+        }
+    }
+
     pub fn get(&self) -> u32 {
         self.0
     }
@@ -204,6 +218,77 @@ impl Codec for u24 {
     }
 }
 
+impl From<u24> for u32 {
+    fn from(v: u24) -> u32 {
+        v.0
+    }
+}
+
+// The integer Codec impls are little endian, as every SPDM field is.
+// Fields of other specifications embedded in SPDM messages, e.g. DER
+// lengths, use the big endian types below, so the byte order of a field
+// is visible in its type.
+
+/// Big endian u16.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct u16be(u16);
+
+impl u16be {
+    pub fn new(v: u16) -> u16be {
+        u16be(v)
+    }
+
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+}
+
+impl Codec for u16be {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, EncodeErr> {
+        bytes
+            .extend_from_slice(&self.0.to_be_bytes())
+            .ok_or(EncodeErr)?;
+        Ok(2)
+    }
+
+    fn read(r: &mut Reader) -> Option<u16be> {
+        let mut v = [0u8; mem::size_of::<u16>()];
+        v.copy_from_slice(r.take(mem::size_of::<u16>())?);
+        Some(u16be(u16::from_be_bytes(v)))
+    }
+}
+
+/// Big endian u32.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct u32be(u32);
+
+impl u32be {
+    pub fn new(v: u32) -> u32be {
+        u32be(v)
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Codec for u32be {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, EncodeErr> {
+        bytes
+            .extend_from_slice(&self.0.to_be_bytes())
+            .ok_or(EncodeErr)?;
+        Ok(4)
+    }
+
+    fn read(r: &mut Reader) -> Option<u32be> {
+        let mut v = [0u8; mem::size_of::<u32>()];
+        v.copy_from_slice(r.take(mem::size_of::<u32>())?);
+        Some(u32be(u32::from_be_bytes(v)))
+    }
+}
+
 pub fn decode_u32(bytes: &[u8]) -> Option<u32> {
     Some(
         u32::from(bytes[0])
@@ -284,7 +369,7 @@ impl Codec for u128 {
 mod tests {
     use crate::codec::Codec;
     use crate::codec::{Reader, Writer};
-    use crate::u24;
+    use crate::{u16be, u24, u32be};
 
     #[test]
     fn test_u64() {
@@ -338,6 +423,28 @@ mod tests {
         let _ = u24::new(1 << 24);
     }
     #[test]
+    fn test_u24_try_new() {
+        assert_eq!(u24::try_new(1 << 24), None);
+        assert_eq!(u24::try_new(0xFF_FF_FF).map(u32::from), Some(0xFF_FF_FF));
+    }
+    #[test]
+    fn test_big_endian() {
+        let u8_slice = &mut [0u8; 6];
+        let mut witer = Writer::init(u8_slice);
+        assert_eq!(u16be::new(0x0102).encode(&mut witer), Ok(2));
+        assert_eq!(u32be::new(0x03040506).encode(&mut witer), Ok(4));
+        assert_eq!(u8_slice, &[1, 2, 3, 4, 5, 6]);
+
+        let mut reader = Reader::init(u8_slice);
+        assert_eq!(u16be::read(&mut reader).unwrap().get(), 0x0102);
+        assert_eq!(u32be::read(&mut reader).unwrap().get(), 0x03040506);
+        assert_eq!(reader.left(), 0);
+
+        // the same bytes read as little endian
+        let mut reader = Reader::init(u8_slice);
+        assert_eq!(u16::read(&mut reader).unwrap(), 0x0201);
+    }
+    #[test]
     fn test_u8() {
         let u8_slice = &mut [0u8; 4];
         let mut witer = Writer::init(u8_slice);
@@ -395,4 +502,14 @@ mod tests {
         };
         assert_eq!(reader.sub(4).is_none(), true);
     }
+    #[test]
+    fn test_case0_clear() {
+        let u8_slice = &mut [0u8; 4];
+        let mut witer = Writer::init(u8_slice);
+        assert_eq!(0xAA5555AAu32.encode(&mut witer), Ok(4));
+        witer.clear();
+        assert_eq!(witer.used(), 0);
+        assert_eq!(0x55u8.encode(&mut witer), Ok(1));
+        assert_eq!(witer.used_slice(), [0x55]);
+    }
 }
@@ -6,7 +6,7 @@
 
 use super::*;
 use crate::spdmlib::error::SPDM_STATUS_SEND_FAIL;
-use spdmlib_test::common::device_io::SharedBuffer;
+use spdmlib_testutils::device_io::SharedBuffer;
 
 pub struct FakeSpdmDeviceIoReceve<'a> {
     data: &'a SharedBuffer,
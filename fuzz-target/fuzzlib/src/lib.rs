@@ -9,11 +9,11 @@ pub use fake_device_io::{
     FakeSpdmDeviceIoReceve, FuzzSpdmDeviceIoReceve, FuzzTmpSpdmDeviceIoReceve,
 };
 
-pub use spdmlib_test::common::crypto_callback::*;
-pub use spdmlib_test::common::device_io::SharedBuffer;
-pub use spdmlib_test::common::secret_callback::*;
-pub use spdmlib_test::common::transport::PciDoeTransportEncap;
-pub use spdmlib_test::common::util::{get_rsp_cert_chain_buff, req_create_info, rsp_create_info};
+pub use spdmlib_testutils::crypto_callback::*;
+pub use spdmlib_testutils::device_io::SharedBuffer;
+pub use spdmlib_testutils::secret_callback::*;
+pub use spdmlib_testutils::transport::PciDoeTransportEncap;
+pub use spdmlib_testutils::util::{get_rsp_cert_chain_buff, req_create_info, rsp_create_info};
 
 pub use spdmlib;
 pub use spdmlib::common::{SpdmDeviceIo, SpdmTransportEncap};
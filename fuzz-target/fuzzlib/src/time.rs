@@ -3,9 +3,20 @@
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
 use spdmlib::time::SpdmTime;
-pub static SPDM_TIME_IMPL: SpdmTime = SpdmTime {
-    sleep_cb: |time: usize| {
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Microseconds since the first call.
+fn now_us() -> u64 {
+    static START: Mutex<Option<Instant>> = Mutex::new(None);
+    let mut start = START.lock().unwrap();
+    start.get_or_insert_with(Instant::now).elapsed().as_micros() as u64
+}
+
+pub static SPDM_TIME_IMPL: SpdmTime = SpdmTime::new(
+    |time: usize| {
         use std::{thread, time::Duration};
-        thread::sleep(Duration::from_millis(time as u64));
+        thread::sleep(Duration::from_micros(time as u64));
     },
-};
+    now_us,
+);
@@ -31,6 +31,8 @@ impl Default for MctpMessageType {
     }
 }
 
+pub const MCTP_MESSAGE_HEADER_SIZE: usize = 1;
+
 #[derive(Debug, Copy, Clone, Default)]
 pub struct MctpMessageHeader {
     pub r#type: MctpMessageType,
@@ -161,6 +163,33 @@ impl SpdmTransportEncap for MctpTransportEncap {
         Ok((payload_size, is_app_mesaage))
     }
 
+    fn get_header_size(&mut self, _secured_message: bool) -> usize {
+        MCTP_MESSAGE_HEADER_SIZE
+    }
+
+    fn encap_in_place(
+        &mut self,
+        transport_buffer: &mut [u8],
+        spdm_size: usize,
+        secured_message: bool,
+    ) -> SpdmResult<usize> {
+        if transport_buffer.len() < MCTP_MESSAGE_HEADER_SIZE + spdm_size {
+            return Err(SPDM_STATUS_ENCAP_FAIL);
+        }
+        let mut writer = Writer::init(&mut transport_buffer[..MCTP_MESSAGE_HEADER_SIZE]);
+        let mctp_header = MctpMessageHeader {
+            r#type: if secured_message {
+                MctpMessageType::MctpMessageTypeSecuredMctp
+            } else {
+                MctpMessageType::MctpMessageTypeSpdm
+            },
+        };
+        mctp_header
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_ENCAP_FAIL)?;
+        Ok(MCTP_MESSAGE_HEADER_SIZE + spdm_size)
+    }
+
     fn get_sequence_number_count(&mut self) -> u8 {
         2
     }
@@ -216,6 +245,31 @@ mod tests {
         assert!(status);
     }
     #[test]
+    fn test_case0_encap_in_place() {
+        let mut mctp_transport_encap = MctpTransportEncap {};
+        let spdm_buffer = [0x5au8; 100];
+        let mut expected_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let expected_size = mctp_transport_encap
+            .encap(&spdm_buffer, &mut expected_buffer, false)
+            .unwrap();
+
+        let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let header_size = mctp_transport_encap.get_header_size(false);
+        transport_buffer[header_size..(header_size + spdm_buffer.len())]
+            .copy_from_slice(&spdm_buffer);
+        let size = mctp_transport_encap
+            .encap_in_place(&mut transport_buffer, spdm_buffer.len(), false)
+            .unwrap();
+        assert_eq!(size, expected_size);
+        assert_eq!(transport_buffer[..size], expected_buffer[..expected_size]);
+
+        let mut transport_buffer = [0u8; 10];
+        let status = mctp_transport_encap
+            .encap_in_place(&mut transport_buffer, spdm_buffer.len(), false)
+            .is_err();
+        assert!(status);
+    }
+    #[test]
     fn test_case0_decap() {
         let mut mctp_transport_encap = MctpTransportEncap {};
 
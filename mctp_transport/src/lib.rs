@@ -8,4 +8,7 @@
 mod header;
 pub use header::*;
 
+mod smbus;
+pub use smbus::*;
+
 extern crate codec;
@@ -0,0 +1,352 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use spdmlib::common::SpdmDeviceIo;
+use spdmlib::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_SEND_FAIL};
+
+/// SMBus command code of MCTP packets (DSP0237).
+pub const MCTP_SMBUS_COMMAND_CODE: u8 = 0x0F;
+pub const MCTP_HEADER_VERSION: u8 = 0x01;
+/// Baseline transmission unit, the payload size of one MCTP packet.
+pub const MCTP_SMBUS_BASELINE_MTU: usize = 64;
+/// Command code, byte count, source address and MCTP transport header.
+pub const MCTP_SMBUS_PACKET_HEADER_SIZE: usize = 3 + 4;
+pub const MCTP_SMBUS_MAX_PACKET_SIZE: usize =
+    MCTP_SMBUS_PACKET_HEADER_SIZE + MCTP_SMBUS_BASELINE_MTU + 1;
+
+const MCTP_FLAG_SOM: u8 = 0x80;
+const MCTP_FLAG_EOM: u8 = 0x40;
+const MCTP_FLAG_TO: u8 = 0x08;
+const MCTP_PKT_SEQ_SHIFT: u8 = 4;
+const MCTP_PKT_SEQ_MASK: u8 = 0x3;
+const MCTP_MSG_TAG_MASK: u8 = 0x7;
+
+/// SMBus/I2C controller used by MctpSmbusDeviceIo.
+///
+/// Both endpoints act as master when sending an MCTP packet, so a packet is
+/// sent as a block write to the peer and received as a block write from it.
+pub trait MctpSmbusMaster {
+    /// Block write data, starting with the command code, to the 7-bit slave address.
+    fn block_write(&mut self, slave_addr: u8, data: &[u8]) -> SpdmResult;
+
+    /// Wait for a block write to our own slave address and copy it to data,
+    /// starting with the command code. Return the size, None on timeout.
+    fn receive_block_write(&mut self, data: &mut [u8], timeout: usize) -> Option<usize>;
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MctpSmbusConfig {
+    /// 7-bit SMBus addresses.
+    pub local_addr: u8,
+    pub remote_addr: u8,
+    pub local_eid: u8,
+    pub remote_eid: u8,
+    /// Append and check the packet error code.
+    pub pec: bool,
+    /// The requester owns the message tags, the responder echoes them.
+    pub tag_owner: bool,
+}
+
+/// SpdmDeviceIo sending MCTP messages (MctpTransportEncap output) over SMBus,
+/// split into MCTP_SMBUS_BASELINE_MTU packets and reassembled on receive.
+pub struct MctpSmbusDeviceIo<T: MctpSmbusMaster> {
+    pub smbus: T,
+    config: MctpSmbusConfig,
+    msg_tag: u8,
+}
+
+/// CRC-8 with polynomial x^8 + x^2 + x + 1, the SMBus PEC.
+pub fn smbus_pec(crc: u8, data: &[u8]) -> u8 {
+    let mut crc = crc;
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+impl<T: MctpSmbusMaster> MctpSmbusDeviceIo<T> {
+    pub fn new(smbus: T, config: MctpSmbusConfig) -> SpdmResult<Self> {
+        if config.local_addr > 0x7F || config.remote_addr > 0x7F {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        Ok(MctpSmbusDeviceIo {
+            smbus,
+            config,
+            msg_tag: 0,
+        })
+    }
+
+    // PEC covers the destination address byte and the whole block write.
+    fn pec(&self, dest_addr: u8, packet: &[u8]) -> u8 {
+        smbus_pec(smbus_pec(0, &[dest_addr << 1]), packet)
+    }
+
+    /// Check one received packet, return (flags, payload offset, payload size).
+    fn parse_packet(&self, packet: &[u8]) -> Option<(u8, usize, usize)> {
+        let pec_size = self.config.pec as usize;
+        if packet.len() < MCTP_SMBUS_PACKET_HEADER_SIZE + pec_size {
+            return None;
+        }
+        let byte_count = packet[1] as usize;
+        if packet[0] != MCTP_SMBUS_COMMAND_CODE || byte_count + 2 + pec_size != packet.len() {
+            return None;
+        }
+        if self.config.pec {
+            let (data, pec) = packet.split_at(packet.len() - 1);
+            if self.pec(self.config.local_addr, data) != pec[0] {
+                return None;
+            }
+        }
+        if packet[2] != (self.config.remote_addr << 1) | 1 || packet[3] & 0xF != MCTP_HEADER_VERSION
+        {
+            return None;
+        }
+        if packet[4] != self.config.local_eid && packet[4] != 0 {
+            return None;
+        }
+        if packet[5] != self.config.remote_eid {
+            return None;
+        }
+        Some((
+            packet[6],
+            MCTP_SMBUS_PACKET_HEADER_SIZE,
+            packet.len() - MCTP_SMBUS_PACKET_HEADER_SIZE - pec_size,
+        ))
+    }
+}
+
+impl<T: MctpSmbusMaster> SpdmDeviceIo for MctpSmbusDeviceIo<T> {
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        if buffer.is_empty() {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        let tag = if self.config.tag_owner {
+            self.msg_tag = (self.msg_tag + 1) & MCTP_MSG_TAG_MASK;
+            self.msg_tag | MCTP_FLAG_TO
+        } else {
+            self.msg_tag
+        };
+
+        let chunk_count = (buffer.len() + MCTP_SMBUS_BASELINE_MTU - 1) / MCTP_SMBUS_BASELINE_MTU;
+        for (seq, chunk) in buffer.chunks(MCTP_SMBUS_BASELINE_MTU).enumerate() {
+            let mut flags = tag | (((seq as u8) & MCTP_PKT_SEQ_MASK) << MCTP_PKT_SEQ_SHIFT);
+            if seq == 0 {
+                flags |= MCTP_FLAG_SOM;
+            }
+            if seq == chunk_count - 1 {
+                flags |= MCTP_FLAG_EOM;
+            }
+
+            let packet = &mut [0u8; MCTP_SMBUS_MAX_PACKET_SIZE];
+            packet[0] = MCTP_SMBUS_COMMAND_CODE;
+            packet[1] = (1 + 4 + chunk.len()) as u8;
+            packet[2] = (self.config.local_addr << 1) | 1;
+            packet[3] = MCTP_HEADER_VERSION;
+            packet[4] = self.config.remote_eid;
+            packet[5] = self.config.local_eid;
+            packet[6] = flags;
+            let mut size = MCTP_SMBUS_PACKET_HEADER_SIZE;
+            packet[size..size + chunk.len()].copy_from_slice(chunk);
+            size += chunk.len();
+            if self.config.pec {
+                packet[size] = self.pec(self.config.remote_addr, &packet[..size]);
+                size += 1;
+            }
+            self.smbus
+                .block_write(self.config.remote_addr, &packet[..size])
+                .map_err(|_| SPDM_STATUS_SEND_FAIL)?;
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: usize) -> Result<usize, usize> {
+        let mut used = 0usize;
+        let mut expected_seq = 0u8;
+        let mut started = false;
+        loop {
+            let packet = &mut [0u8; MCTP_SMBUS_MAX_PACKET_SIZE];
+            let size = self
+                .smbus
+                .receive_block_write(packet, timeout)
+                .ok_or(0usize)?;
+            let (flags, offset, payload_size) = self.parse_packet(&packet[..size]).ok_or(0usize)?;
+
+            let seq = (flags >> MCTP_PKT_SEQ_SHIFT) & MCTP_PKT_SEQ_MASK;
+            if flags & MCTP_FLAG_SOM != 0 {
+                // a new message drops the one being reassembled
+                used = 0;
+                started = true;
+                if self.config.tag_owner {
+                    // responses must echo our tag with TO cleared
+                    if flags & (MCTP_FLAG_TO | MCTP_MSG_TAG_MASK) != self.msg_tag {
+                        return Err(0);
+                    }
+                } else {
+                    self.msg_tag = flags & MCTP_MSG_TAG_MASK;
+                }
+            } else if !started || seq != expected_seq {
+                return Err(0);
+            }
+            expected_seq = (seq + 1) & MCTP_PKT_SEQ_MASK;
+
+            if used + payload_size <= buffer.len() {
+                buffer[used..used + payload_size]
+                    .copy_from_slice(&packet[offset..offset + payload_size]);
+            }
+            used += payload_size;
+
+            if flags & MCTP_FLAG_EOM != 0 {
+                return if used > buffer.len() {
+                    Err(used)
+                } else {
+                    Ok(used)
+                };
+            }
+        }
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+}
+
+#[cfg(all(test,))]
+mod tests_smbus {
+    use super::*;
+
+    const QUEUE_SIZE: usize = 8;
+
+    struct FakeSmbus {
+        packets: [[u8; MCTP_SMBUS_MAX_PACKET_SIZE]; QUEUE_SIZE],
+        sizes: [usize; QUEUE_SIZE],
+        head: usize,
+        tail: usize,
+    }
+
+    impl FakeSmbus {
+        fn new() -> Self {
+            FakeSmbus {
+                packets: [[0u8; MCTP_SMBUS_MAX_PACKET_SIZE]; QUEUE_SIZE],
+                sizes: [0usize; QUEUE_SIZE],
+                head: 0,
+                tail: 0,
+            }
+        }
+    }
+
+    impl MctpSmbusMaster for FakeSmbus {
+        fn block_write(&mut self, _slave_addr: u8, data: &[u8]) -> SpdmResult {
+            if self.tail == QUEUE_SIZE {
+                return Err(SPDM_STATUS_SEND_FAIL);
+            }
+            self.packets[self.tail][..data.len()].copy_from_slice(data);
+            self.sizes[self.tail] = data.len();
+            self.tail += 1;
+            Ok(())
+        }
+
+        fn receive_block_write(&mut self, data: &mut [u8], _timeout: usize) -> Option<usize> {
+            if self.head == self.tail {
+                return None;
+            }
+            let size = self.sizes[self.head];
+            data[..size].copy_from_slice(&self.packets[self.head][..size]);
+            self.head += 1;
+            Some(size)
+        }
+    }
+
+    fn new_pair() -> (MctpSmbusDeviceIo<FakeSmbus>, MctpSmbusDeviceIo<FakeSmbus>) {
+        let requester = MctpSmbusConfig {
+            local_addr: 0x10,
+            remote_addr: 0x20,
+            local_eid: 8,
+            remote_eid: 9,
+            pec: true,
+            tag_owner: true,
+        };
+        let responder = MctpSmbusConfig {
+            local_addr: 0x20,
+            remote_addr: 0x10,
+            local_eid: 9,
+            remote_eid: 8,
+            pec: true,
+            tag_owner: false,
+        };
+        (
+            MctpSmbusDeviceIo::new(FakeSmbus::new(), requester).unwrap(),
+            MctpSmbusDeviceIo::new(FakeSmbus::new(), responder).unwrap(),
+        )
+    }
+
+    fn transfer(from: &mut MctpSmbusDeviceIo<FakeSmbus>, to: &mut MctpSmbusDeviceIo<FakeSmbus>) {
+        core::mem::swap(&mut from.smbus, &mut to.smbus);
+        from.smbus = FakeSmbus::new();
+    }
+
+    #[test]
+    fn test_case0_smbus_pec() {
+        assert_eq!(smbus_pec(0, b"123456789"), 0xF4);
+    }
+
+    #[test]
+    fn test_case0_smbus_fragmentation() {
+        let (mut requester, mut responder) = new_pair();
+        let message = &mut [0u8; 200];
+        for (i, byte) in message.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        assert!(requester.send(message).is_ok());
+        assert_eq!(requester.smbus.tail, 4);
+        transfer(&mut requester, &mut responder);
+        let buffer = &mut [0u8; 256];
+        assert_eq!(responder.receive(buffer, 0), Ok(200));
+        assert_eq!(&buffer[..200], &message[..]);
+
+        assert!(responder.send(&message[..10]).is_ok());
+        transfer(&mut responder, &mut requester);
+        assert_eq!(requester.receive(buffer, 0), Ok(10));
+
+        assert!(requester.send(message).is_ok());
+        transfer(&mut requester, &mut responder);
+        assert_eq!(responder.receive(&mut buffer[..100], 0), Err(200));
+    }
+
+    #[test]
+    fn test_case1_smbus_receive_errors() {
+        let (mut requester, mut responder) = new_pair();
+        let buffer = &mut [0u8; 256];
+
+        assert!(requester.send(&[1u8; 10]).is_ok());
+        transfer(&mut requester, &mut responder);
+        responder.smbus.packets[0][8] ^= 0x1;
+        assert_eq!(responder.receive(buffer, 0), Err(0));
+
+        assert!(requester.send(&[1u8; 100]).is_ok());
+        transfer(&mut requester, &mut responder);
+        responder.smbus.head = 1;
+        assert_eq!(responder.receive(buffer, 0), Err(0));
+
+        assert!(requester.send(&[1u8; 10]).is_ok());
+        assert!(requester.send(&[1u8; 10]).is_ok());
+        transfer(&mut requester, &mut responder);
+        responder.smbus.head = 1;
+        assert_eq!(responder.receive(buffer, 0), Ok(10));
+        // the response echoes the second tag, not the first
+        requester.msg_tag = (requester.msg_tag + 7) & MCTP_MSG_TAG_MASK;
+        assert!(responder.send(&[2u8; 10]).is_ok());
+        transfer(&mut responder, &mut requester);
+        assert_eq!(requester.receive(buffer, 0), Err(0));
+
+        assert_eq!(responder.receive(buffer, 0), Err(0));
+    }
+}
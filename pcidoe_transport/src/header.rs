@@ -4,14 +4,16 @@
 
 use codec::enum_builder;
 use codec::{Codec, Reader, Writer};
-use spdmlib::common::SpdmTransportEncap;
+use spdmlib::common::{SpdmDeviceIoEvent, SpdmTransportEncap};
 use spdmlib::error::{SpdmResult, SPDM_STATUS_DECAP_FAIL, SPDM_STATUS_ENCAP_FAIL};
 
 enum_builder! {
     @U16
     EnumName: PciDoeVendorId;
     EnumVal{
-        PciDoeVendorIdPciSig => 0x0001
+        PciDoeVendorIdPciSig => 0x0001,
+        // CXL specific data objects, e.g. CDAT table access
+        PciDoeVendorIdCxl => 0x1E98
     }
 }
 impl Default for PciDoeVendorId {
@@ -35,6 +37,8 @@ impl Default for PciDoeDataObjectType {
     }
 }
 
+pub const PCI_DOE_MESSAGE_HEADER_SIZE: usize = 8;
+
 #[derive(Debug, Copy, Clone, Default)]
 pub struct PciDoeMessageHeader {
     pub vendor_id: PciDoeVendorId,
@@ -80,9 +84,40 @@ impl Codec for PciDoeMessageHeader {
     }
 }
 
+/// DOE Status register bits, PCIe 6.0 7.9.24.5.
+pub const PCI_DOE_STATUS_BUSY: u32 = 0x0000_0001;
+pub const PCI_DOE_STATUS_INTERRUPT: u32 = 0x0000_0002;
+pub const PCI_DOE_STATUS_ERROR: u32 = 0x0000_0004;
+pub const PCI_DOE_STATUS_ASYNC_MESSAGE: u32 = 0x0000_0008;
+pub const PCI_DOE_STATUS_DATA_OBJECT_READY: u32 = 0x8000_0000;
+
+/// Async messages use the same data object types as responses, only the
+/// DOE Async Message Status bit tells them apart.
 #[derive(Debug, Copy, Clone, Default)]
 pub struct PciDoeTransportEncap {}
 
+impl PciDoeTransportEncap {
+    /// Map a DOE Status register value to the event SpdmDeviceIo::poll_event
+    /// reports. Reading the register does not consume the pending data object,
+    /// so a device IO may call this as often as it polls.
+    /// A function which is gone, e.g. after surprise removal, reads all ones.
+    pub fn doe_status_event(doe_status: u32) -> SpdmDeviceIoEvent {
+        if doe_status == u32::MAX {
+            SpdmDeviceIoEvent::PeerDisconnected
+        } else if doe_status & PCI_DOE_STATUS_ASYNC_MESSAGE != 0
+            && doe_status & PCI_DOE_STATUS_DATA_OBJECT_READY != 0
+        {
+            SpdmDeviceIoEvent::AsyncMessagePending
+        } else {
+            SpdmDeviceIoEvent::None
+        }
+    }
+}
+
+/// SPDM over the CXL.io DOE mailbox uses the PCI-SIG SPDM data objects, the
+/// CXL vendor ID only appears in the CXL_IDE_KM vendor defined messages.
+pub type CxlDoeTransportEncap = PciDoeTransportEncap;
+
 impl SpdmTransportEncap for PciDoeTransportEncap {
     fn encap(
         &mut self,
@@ -162,6 +197,42 @@ impl SpdmTransportEncap for PciDoeTransportEncap {
         Ok((app_buffer.len(), false))
     }
 
+    fn get_header_size(&mut self, _secured_message: bool) -> usize {
+        PCI_DOE_MESSAGE_HEADER_SIZE
+    }
+
+    fn encap_in_place(
+        &mut self,
+        transport_buffer: &mut [u8],
+        spdm_size: usize,
+        secured_message: bool,
+    ) -> SpdmResult<usize> {
+        let aligned_payload_len = (spdm_size + 3) / 4 * 4;
+        if transport_buffer.len() < PCI_DOE_MESSAGE_HEADER_SIZE + aligned_payload_len {
+            return Err(SPDM_STATUS_ENCAP_FAIL);
+        }
+        for d in transport_buffer[(PCI_DOE_MESSAGE_HEADER_SIZE + spdm_size)
+            ..(PCI_DOE_MESSAGE_HEADER_SIZE + aligned_payload_len)]
+            .iter_mut()
+        {
+            *d = 0;
+        }
+        let mut writer = Writer::init(&mut transport_buffer[..PCI_DOE_MESSAGE_HEADER_SIZE]);
+        let pcidoe_header = PciDoeMessageHeader {
+            vendor_id: PciDoeVendorId::PciDoeVendorIdPciSig,
+            data_object_type: if secured_message {
+                PciDoeDataObjectType::PciDoeDataObjectTypeSecuredSpdm
+            } else {
+                PciDoeDataObjectType::PciDoeDataObjectTypeSpdm
+            },
+            payload_length: aligned_payload_len as u32,
+        };
+        pcidoe_header
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_ENCAP_FAIL)?;
+        Ok(PCI_DOE_MESSAGE_HEADER_SIZE + aligned_payload_len)
+    }
+
     fn get_sequence_number_count(&mut self) -> u8 {
         0
     }
@@ -247,6 +318,53 @@ mod tests_header {
         assert_eq!(pcidoemessageheader.is_none(), true);
     }
     #[test]
+    fn test_case0_doe_status_event() {
+        assert_eq!(
+            PciDoeTransportEncap::doe_status_event(0),
+            SpdmDeviceIoEvent::None
+        );
+        assert_eq!(
+            PciDoeTransportEncap::doe_status_event(PCI_DOE_STATUS_DATA_OBJECT_READY),
+            SpdmDeviceIoEvent::None
+        );
+        assert_eq!(
+            PciDoeTransportEncap::doe_status_event(
+                PCI_DOE_STATUS_ASYNC_MESSAGE | PCI_DOE_STATUS_DATA_OBJECT_READY
+            ),
+            SpdmDeviceIoEvent::AsyncMessagePending
+        );
+        assert_eq!(
+            PciDoeTransportEncap::doe_status_event(0xFFFF_FFFF),
+            SpdmDeviceIoEvent::PeerDisconnected
+        );
+    }
+    #[test]
+    fn test_case0_encap_in_place() {
+        let mut pcidoe_transport_encap = PciDoeTransportEncap {};
+        let spdm_buffer = [0x5au8; 101];
+        let mut expected_buffer = [0u8; 256];
+        let expected_size = pcidoe_transport_encap
+            .encap(&spdm_buffer, &mut expected_buffer, true)
+            .unwrap();
+
+        // stale bytes in the alignment padding must be cleared
+        let mut transport_buffer = [0xffu8; 256];
+        let header_size = pcidoe_transport_encap.get_header_size(true);
+        transport_buffer[header_size..(header_size + spdm_buffer.len())]
+            .copy_from_slice(&spdm_buffer);
+        let size = pcidoe_transport_encap
+            .encap_in_place(&mut transport_buffer, spdm_buffer.len(), true)
+            .unwrap();
+        assert_eq!(size, expected_size);
+        assert_eq!(transport_buffer[..size], expected_buffer[..expected_size]);
+
+        let mut transport_buffer = [0u8; 64];
+        let status = pcidoe_transport_encap
+            .encap_in_place(&mut transport_buffer, spdm_buffer.len(), true)
+            .is_err();
+        assert!(status);
+    }
+    #[test]
     #[should_panic]
     fn test_case4_mctpmessageheader() {
         let u8_slice = &mut [0u8; 8];
@@ -102,6 +102,15 @@ build() {
     echo "Building Rust-SPDM with spdm-ring,hashed-transcript-data,mut-auth feature..."
     echo_command cargo build --release --no-default-features --features=spdm-ring,hashed-transcript-data,mut-auth
 
+    echo "Building Rust-SPDM with spdm-ring,serde feature..."
+    echo_command cargo build --release --no-default-features --features=spdm-ring,serde
+
+    echo "Building Rust-SPDM with spdm-rustcrypto,hashed-transcript-data feature..."
+    echo_command cargo build --release --no-default-features --features=spdm-rustcrypto,hashed-transcript-data
+
+    echo "Building Rust-SPDM with openssl,hashed-transcript-data feature..."
+    echo_command cargo build --release --no-default-features --features=openssl,hashed-transcript-data
+
     if [ -z "$RUSTFLAGS" ]; then
         echo "Building Rust-SPDM in no std with no-default-features..."
         echo_command cargo build -Z build-std=core,alloc,compiler_builtins --target x86_64-unknown-none --release --no-default-features
@@ -114,6 +123,12 @@ build() {
     
         echo "Building Rust-SPDM in no std with spdm-ring,hashed-transcript-data,mut-auth feature..."
         echo_command cargo build -Z build-std=core,alloc,compiler_builtins --target x86_64-unknown-none --release --no-default-features --features="spdm-ring,hashed-transcript-data,mut-auth"
+
+        echo "Building Rust-SPDM in no std with spdm-ring,serde feature..."
+        echo_command cargo build -Z build-std=core,alloc,compiler_builtins --target x86_64-unknown-none --release --no-default-features --features="spdm-ring,serde"
+
+        echo "Building Rust-SPDM in no std with spdm-rustcrypto feature..."
+        echo_command cargo build -Z build-std=core,alloc,compiler_builtins --target x86_64-unknown-none --release --no-default-features --features="spdm-rustcrypto"
     fi
 
     popd
@@ -170,6 +185,7 @@ run_basic_test() {
     echo "Running basic tests..."
     echo_command cargo test -- --test-threads=1
     echo_command cargo test --no-default-features --features "spdmlib/std,spdmlib/spdm-ring" -- --test-threads=1
+    echo_command cargo test -p spdmlib --features=serde -- --test-threads=1
     echo "Running basic tests finished..."
 
     echo "Running spdmlib-test..."
@@ -196,10 +212,28 @@ run_rust_spdm_emu_mut_auth() {
     cleanup
 }
 
+run_rust_spdm_emu_ecp256() {
+    echo "Running requester and responder with P-256/SHA-256/AES-128-GCM..."
+    echo_command cargo run -p spdm-responder-emu --no-default-features --features="$RUN_REQUESTER_FEATURES,ecp256" &
+    sleep 5
+    echo_command cargo run -p spdm-requester-emu --no-default-features --features="$RUN_RESPONDER_FEATURES,ecp256"
+    cleanup
+}
+
+run_rust_spdm_emu_chacha20() {
+    echo "Running requester and responder with P-256/SHA-256/ChaCha20-Poly1305..."
+    echo_command cargo run -p spdm-responder-emu --no-default-features --features="$RUN_REQUESTER_FEATURES,chacha20" &
+    sleep 5
+    echo_command cargo run -p spdm-requester-emu --no-default-features --features="$RUN_RESPONDER_FEATURES,chacha20"
+    cleanup
+}
+
 run() {
     run_basic_test
     run_rust_spdm_emu
     run_rust_spdm_emu_mut_auth
+    run_rust_spdm_emu_ecp256
+    run_rust_spdm_emu_chacha20
 }
 
 CHECK_OPTION=false
@@ -0,0 +1,159 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::{ManagedBuffer12Sign, SpdmContext};
+use crate::crypto;
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_INVALID_MSG_FIELD,
+    SPDM_STATUS_INVALID_STATE_LOCAL,
+};
+use crate::protocol::*;
+use codec::{u24, Codec, Reader, Writer};
+
+pub const SPDM_ATTESTATION_REPORT_VERSION: u8 = 1;
+
+/// Measurements, endpoint info and nonce covered by one responder signature.
+///
+/// This is a Rust-SPDM extension, carried e.g. in a VENDOR_DEFINED_RESPONSE,
+/// for devices whose asymmetric engine is too slow to sign GET_MEASUREMENTS
+/// and GET_ENDPOINT_INFO separately. The encoded report is:
+/// version(1) slot_id(1) reserved(2) nonce(32) endpoint_info_length(2)
+/// endpoint_info measurement_record_length(3) measurement_record signature.
+/// The signature covers the SPDM 1.2 signing prefix,
+/// SPDM_ATTESTATION_REPORT_SIGN_CONTEXT and the hash of all preceding fields.
+#[derive(Debug, Clone)]
+pub struct SpdmAttestationReport<'r> {
+    pub slot_id: u8,
+    pub nonce: SpdmNonceStruct,
+    pub endpoint_info: &'r [u8],
+    pub measurement_record: &'r [u8],
+}
+
+impl<'r> SpdmAttestationReport<'r> {
+    /// Encode all fields but the signature.
+    pub fn encode_body(&self, writer: &mut Writer) -> SpdmResult<usize> {
+        if self.endpoint_info.len() > u16::MAX as usize {
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        let measurement_record_length = u24::try_new(self.measurement_record.len() as u32)
+            .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+
+        let mut cnt = 0usize;
+        cnt += SPDM_ATTESTATION_REPORT_VERSION
+            .encode(writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += self
+            .slot_id
+            .encode(writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += 0u16.encode(writer).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // reserved
+        cnt += self
+            .nonce
+            .encode(writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += (self.endpoint_info.len() as u16)
+            .encode(writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += writer
+            .extend_from_slice(self.endpoint_info)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        cnt += measurement_record_length
+            .encode(writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += writer
+            .extend_from_slice(self.measurement_record)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    /// Read all fields but the signature, return the report and its size.
+    pub fn read_body(bytes: &'r [u8]) -> Option<(SpdmAttestationReport<'r>, usize)> {
+        let mut r = Reader::init(bytes);
+        let version = u8::read(&mut r)?;
+        if version != SPDM_ATTESTATION_REPORT_VERSION {
+            return None;
+        }
+        let slot_id = u8::read(&mut r)?;
+        u16::read(&mut r)?; // reserved
+        let nonce = SpdmNonceStruct::read(&mut r)?;
+        let endpoint_info_length = u16::read(&mut r)? as usize;
+        let offset = r.used();
+        let endpoint_info = bytes.get(offset..offset + endpoint_info_length)?;
+
+        let offset = offset + endpoint_info_length;
+        let mut r = Reader::init(&bytes[offset..]);
+        let measurement_record_length = u24::read(&mut r)?.get() as usize;
+        let offset = offset + r.used();
+        let measurement_record = bytes.get(offset..offset + measurement_record_length)?;
+
+        Some((
+            SpdmAttestationReport {
+                slot_id,
+                nonce,
+                endpoint_info,
+                measurement_record,
+            },
+            offset + measurement_record_length,
+        ))
+    }
+}
+
+impl<'a> SpdmContext<'a> {
+    /// Return the hash of the report body and the data to sign or verify.
+    pub(crate) fn attestation_report_sign_data(
+        &self,
+        report_body: &[u8],
+    ) -> SpdmResult<(SpdmDigestStruct, ManagedBuffer12Sign)> {
+        if self.negotiate_info.spdm_version_sel.get_u8() < SpdmVersion::SpdmVersion12.get_u8() {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        let report_hash = crypto::hash::hash_all(self.negotiate_info.base_hash_sel, report_body)
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+
+        let mut message_sign = ManagedBuffer12Sign::default();
+        message_sign
+            .append_message(
+                self.negotiate_info
+                    .spdm_version_sel
+                    .get_signing_prefix_context(),
+            )
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        message_sign
+            .append_message(&SPDM_ATTESTATION_REPORT_SIGN_CONTEXT)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        message_sign
+            .append_message(report_hash.as_ref())
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok((report_hash, message_sign))
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_attestation_report_body() {
+        let report = SpdmAttestationReport {
+            slot_id: 1,
+            nonce: SpdmNonceStruct { data: [0x5Au8; 32] },
+            endpoint_info: &[1, 2, 3],
+            measurement_record: &[4, 5, 6, 7],
+        };
+        let buffer = &mut [0u8; 64];
+        let used = report.encode_body(&mut Writer::init(buffer)).unwrap();
+        assert_eq!(used, 4 + 32 + 2 + 3 + 3 + 4);
+
+        let (read, read_used) = SpdmAttestationReport::read_body(&buffer[..used + 8]).unwrap();
+        assert_eq!(read_used, used);
+        assert_eq!(read.slot_id, 1);
+        assert_eq!(read.nonce.data, [0x5Au8; 32]);
+        assert_eq!(read.endpoint_info, &[1, 2, 3]);
+        assert_eq!(read.measurement_record, &[4, 5, 6, 7]);
+
+        assert!(SpdmAttestationReport::read_body(&buffer[..used - 1]).is_none());
+        buffer[0] = 2;
+        assert!(SpdmAttestationReport::read_body(&buffer[..used]).is_none());
+    }
+}
@@ -0,0 +1,420 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Hash-chained audit log of the VCA negotiation.
+//!
+//! The log is independent of the transcripts used for signing. Every raw
+//! GET_VERSION/VERSION/GET_CAPABILITIES/CAPABILITIES/NEGOTIATE_ALGORITHMS/ALGORITHMS
+//! message is folded into a chain, and the chain is closed over the selected
+//! version, capabilities and algorithms:
+//!
+//!   link[0] = H(SPDM_NEGOTIATION_AUDIT_LOG_LABEL)
+//!   link[i] = H(link[i-1] || u32le(len(message[i])) || message[i])
+//!   head    = H(link[n] || u32le(len(selection)) || selection)
+//!
+//! A log exported from a connection can be persisted with its Codec and later
+//! checked against raw captures with verify_negotiation_audit_log().
+
+use super::*;
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_INVALID_PARAMETER,
+    SPDM_STATUS_INVALID_STATE_LOCAL, SPDM_STATUS_VERIF_FAIL,
+};
+use crate::protocol::*;
+use codec::{Codec, Reader, Writer};
+
+/// GET_VERSION, VERSION, GET_CAPABILITIES, CAPABILITIES, NEGOTIATE_ALGORITHMS and ALGORITHMS.
+pub const MAX_SPDM_VCA_MESSAGE_COUNT: usize = 6;
+
+pub const SPDM_NEGOTIATION_AUDIT_LOG_LABEL: &[u8] = b"spdm negotiation audit log";
+
+const SPDM_NEGOTIATION_SELECTION_SIZE: usize = 49;
+const MAX_AUDIT_LINK_INPUT_SIZE: usize = SPDM_MAX_HASH_SIZE + 4 + MAX_MANAGED_BUFFER_A_SIZE;
+
+/// End offsets of each VCA message inside message_a.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpdmVcaMessageBoundary {
+    count: usize,
+    end: [u16; MAX_SPDM_VCA_MESSAGE_COUNT],
+    overflow: bool,
+}
+
+impl SpdmVcaMessageBoundary {
+    /// A message which cannot be recorded makes the boundaries unusable
+    /// until reset, the audit log cannot be exported for this connection.
+    pub fn record(&mut self, end: usize) -> Option<()> {
+        if self.count >= MAX_SPDM_VCA_MESSAGE_COUNT || end > u16::MAX as usize {
+            self.overflow = true;
+            return None;
+        }
+        self.end[self.count] = end as u16;
+        self.count += 1;
+        Some(())
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.overflow = false;
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_overflow(&self) -> bool {
+        self.overflow
+    }
+
+    /// Split message_a back into the individual VCA messages.
+    pub fn split<'a>(&self, message_a: &'a [u8]) -> Option<[&'a [u8]; MAX_SPDM_VCA_MESSAGE_COUNT]> {
+        let mut messages: [&[u8]; MAX_SPDM_VCA_MESSAGE_COUNT] = [&[]; MAX_SPDM_VCA_MESSAGE_COUNT];
+        let mut start = 0usize;
+        for (message, end) in messages.iter_mut().zip(self.end.iter()).take(self.count) {
+            let end = *end as usize;
+            if end < start || end > message_a.len() {
+                return None;
+            }
+            *message = &message_a[start..end];
+            start = end;
+        }
+        Some(messages)
+    }
+}
+
+/// Version, capabilities and algorithms selected by the VCA negotiation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmNegotiationSelection {
+    pub spdm_version_sel: SpdmVersion,
+    pub req_capabilities_sel: SpdmRequestCapabilityFlags,
+    pub rsp_capabilities_sel: SpdmResponseCapabilityFlags,
+    pub req_ct_exponent_sel: u8,
+    pub rsp_ct_exponent_sel: u8,
+    pub measurement_specification_sel: SpdmMeasurementSpecification,
+    pub measurement_hash_sel: SpdmMeasurementHashAlgo,
+    pub base_hash_sel: SpdmBaseHashAlgo,
+    pub base_asym_sel: SpdmBaseAsymAlgo,
+    pub dhe_sel: SpdmDheAlgo,
+    pub aead_sel: SpdmAeadAlgo,
+    pub req_asym_sel: SpdmReqAsymAlgo,
+    pub key_schedule_sel: SpdmKeyScheduleAlgo,
+    pub opaque_data_support: SpdmOpaqueSupport,
+    pub req_data_transfer_size_sel: u32,
+    pub req_max_spdm_msg_size_sel: u32,
+    pub rsp_data_transfer_size_sel: u32,
+    pub rsp_max_spdm_msg_size_sel: u32,
+}
+
+impl From<&SpdmNegotiateInfo> for SpdmNegotiationSelection {
+    fn from(negotiate_info: &SpdmNegotiateInfo) -> Self {
+        SpdmNegotiationSelection {
+            spdm_version_sel: negotiate_info.spdm_version_sel,
+            req_capabilities_sel: negotiate_info.req_capabilities_sel,
+            rsp_capabilities_sel: negotiate_info.rsp_capabilities_sel,
+            req_ct_exponent_sel: negotiate_info.req_ct_exponent_sel,
+            rsp_ct_exponent_sel: negotiate_info.rsp_ct_exponent_sel,
+            measurement_specification_sel: negotiate_info.measurement_specification_sel,
+            measurement_hash_sel: negotiate_info.measurement_hash_sel,
+            base_hash_sel: negotiate_info.base_hash_sel,
+            base_asym_sel: negotiate_info.base_asym_sel,
+            dhe_sel: negotiate_info.dhe_sel,
+            aead_sel: negotiate_info.aead_sel,
+            req_asym_sel: negotiate_info.req_asym_sel,
+            key_schedule_sel: negotiate_info.key_schedule_sel,
+            opaque_data_support: negotiate_info.opaque_data_support,
+            req_data_transfer_size_sel: negotiate_info.req_data_transfer_size_sel,
+            req_max_spdm_msg_size_sel: negotiate_info.req_max_spdm_msg_size_sel,
+            rsp_data_transfer_size_sel: negotiate_info.rsp_data_transfer_size_sel,
+            rsp_max_spdm_msg_size_sel: negotiate_info.rsp_max_spdm_msg_size_sel,
+        }
+    }
+}
+
+impl Codec for SpdmNegotiationSelection {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.spdm_version_sel.encode(bytes)?;
+        cnt += self.req_capabilities_sel.encode(bytes)?;
+        cnt += self.rsp_capabilities_sel.encode(bytes)?;
+        cnt += self.req_ct_exponent_sel.encode(bytes)?;
+        cnt += self.rsp_ct_exponent_sel.encode(bytes)?;
+        cnt += self.measurement_specification_sel.encode(bytes)?;
+        cnt += self.measurement_hash_sel.encode(bytes)?;
+        cnt += self.base_hash_sel.encode(bytes)?;
+        cnt += self.base_asym_sel.encode(bytes)?;
+        cnt += self.dhe_sel.encode(bytes)?;
+        cnt += self.aead_sel.encode(bytes)?;
+        cnt += self.req_asym_sel.encode(bytes)?;
+        cnt += self.key_schedule_sel.encode(bytes)?;
+        cnt += self.opaque_data_support.encode(bytes)?;
+        cnt += self.req_data_transfer_size_sel.encode(bytes)?;
+        cnt += self.req_max_spdm_msg_size_sel.encode(bytes)?;
+        cnt += self.rsp_data_transfer_size_sel.encode(bytes)?;
+        cnt += self.rsp_max_spdm_msg_size_sel.encode(bytes)?;
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmNegotiationSelection> {
+        Some(SpdmNegotiationSelection {
+            spdm_version_sel: SpdmVersion::read(r)?,
+            req_capabilities_sel: SpdmRequestCapabilityFlags::read(r)?,
+            rsp_capabilities_sel: SpdmResponseCapabilityFlags::read(r)?,
+            req_ct_exponent_sel: u8::read(r)?,
+            rsp_ct_exponent_sel: u8::read(r)?,
+            measurement_specification_sel: SpdmMeasurementSpecification::read(r)?,
+            measurement_hash_sel: SpdmMeasurementHashAlgo::read(r)?,
+            base_hash_sel: SpdmBaseHashAlgo::read(r)?,
+            base_asym_sel: SpdmBaseAsymAlgo::read(r)?,
+            dhe_sel: SpdmDheAlgo::read(r)?,
+            aead_sel: SpdmAeadAlgo::read(r)?,
+            req_asym_sel: SpdmReqAsymAlgo::read(r)?,
+            key_schedule_sel: SpdmKeyScheduleAlgo::read(r)?,
+            opaque_data_support: SpdmOpaqueSupport::read(r)?,
+            req_data_transfer_size_sel: u32::read(r)?,
+            req_max_spdm_msg_size_sel: u32::read(r)?,
+            rsp_data_transfer_size_sel: u32::read(r)?,
+            rsp_max_spdm_msg_size_sel: u32::read(r)?,
+        })
+    }
+}
+
+/// Immutable, hash-chained record of one VCA negotiation.
+/// It can only be produced by SpdmContext::export_negotiation_audit_log() or read back
+/// from its persisted encoding.
+#[derive(Debug, Clone)]
+pub struct SpdmNegotiationAuditLog {
+    hash_algo: SpdmBaseHashAlgo,
+    selection: SpdmNegotiationSelection,
+    message_count: u8,
+    links: [SpdmDigestStruct; MAX_SPDM_VCA_MESSAGE_COUNT],
+    head: SpdmDigestStruct,
+}
+
+impl SpdmNegotiationAuditLog {
+    fn generate(
+        hash_algo: SpdmBaseHashAlgo,
+        selection: &SpdmNegotiationSelection,
+        messages: &[&[u8]],
+    ) -> SpdmResult<Self> {
+        if messages.len() > MAX_SPDM_VCA_MESSAGE_COUNT {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        let mut links: [SpdmDigestStruct; MAX_SPDM_VCA_MESSAGE_COUNT] = Default::default();
+        let mut link = crypto::hash::hash_all(hash_algo, SPDM_NEGOTIATION_AUDIT_LOG_LABEL)
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+        for (slot, message) in links.iter_mut().zip(messages.iter()) {
+            link = chain_link(hash_algo, &link, message)?;
+            *slot = link.clone();
+        }
+
+        let mut selection_buffer = [0u8; SPDM_NEGOTIATION_SELECTION_SIZE];
+        let mut writer = Writer::init(&mut selection_buffer);
+        selection
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        let head = chain_link(hash_algo, &link, writer.used_slice())?;
+
+        Ok(SpdmNegotiationAuditLog {
+            hash_algo,
+            selection: *selection,
+            message_count: messages.len() as u8,
+            links,
+            head,
+        })
+    }
+
+    pub fn hash_algo(&self) -> SpdmBaseHashAlgo {
+        self.hash_algo
+    }
+
+    pub fn selection(&self) -> &SpdmNegotiationSelection {
+        &self.selection
+    }
+
+    pub fn message_count(&self) -> usize {
+        self.message_count as usize
+    }
+
+    /// Chain value after folding in the index-th VCA message.
+    pub fn link(&self, index: usize) -> Option<&[u8]> {
+        if index < self.message_count() {
+            Some(self.links[index].as_ref())
+        } else {
+            None
+        }
+    }
+
+    /// Chain value closed over the negotiated selection.
+    pub fn head(&self) -> &[u8] {
+        self.head.as_ref()
+    }
+}
+
+impl Codec for SpdmNegotiationAuditLog {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.hash_algo.encode(bytes)?;
+        cnt += self.selection.encode(bytes)?;
+        cnt += self.message_count.encode(bytes)?;
+        for link in self.links.iter().take(self.message_count()) {
+            cnt += bytes
+                .extend_from_slice(link.as_ref())
+                .ok_or(codec::EncodeErr)?;
+        }
+        cnt += bytes
+            .extend_from_slice(self.head.as_ref())
+            .ok_or(codec::EncodeErr)?;
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmNegotiationAuditLog> {
+        let hash_algo = SpdmBaseHashAlgo::read(r)?;
+        if hash_algo.is_empty() || !hash_algo.is_no_more_than_one_selected() {
+            return None;
+        }
+        let hash_size = hash_algo.get_size() as usize;
+        let selection = SpdmNegotiationSelection::read(r)?;
+        let message_count = u8::read(r)?;
+        if message_count as usize > MAX_SPDM_VCA_MESSAGE_COUNT {
+            return None;
+        }
+        let mut links: [SpdmDigestStruct; MAX_SPDM_VCA_MESSAGE_COUNT] = Default::default();
+        for link in links.iter_mut().take(message_count as usize) {
+            *link = SpdmDigestStruct::from(r.take(hash_size)?);
+        }
+        let head = SpdmDigestStruct::from(r.take(hash_size)?);
+        Some(SpdmNegotiationAuditLog {
+            hash_algo,
+            selection,
+            message_count,
+            links,
+            head,
+        })
+    }
+}
+
+fn chain_link(
+    hash_algo: SpdmBaseHashAlgo,
+    prev: &SpdmDigestStruct,
+    data: &[u8],
+) -> SpdmResult<SpdmDigestStruct> {
+    let mut buffer = [0u8; MAX_AUDIT_LINK_INPUT_SIZE];
+    let mut writer = Writer::init(&mut buffer);
+    writer
+        .extend_from_slice(prev.as_ref())
+        .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+    (data.len() as u32)
+        .encode(&mut writer)
+        .map_err(|_| SPDM_STATUS_INVALID_PARAMETER)?;
+    writer
+        .extend_from_slice(data)
+        .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+    crypto::hash::hash_all(hash_algo, writer.used_slice()).ok_or(SPDM_STATUS_CRYPTO_ERROR)
+}
+
+/// Recompute the chain of an exported log from the raw VCA captures, in the
+/// order they were exchanged, and check every link as well as the head.
+pub fn verify_negotiation_audit_log(
+    log: &SpdmNegotiationAuditLog,
+    raw_messages: &[&[u8]],
+) -> SpdmResult {
+    if raw_messages.len() != log.message_count() {
+        return Err(SPDM_STATUS_INVALID_PARAMETER);
+    }
+    let expected = SpdmNegotiationAuditLog::generate(log.hash_algo, &log.selection, raw_messages)?;
+    for index in 0..log.message_count() {
+        if log.link(index) != expected.link(index) {
+            error!("!!! negotiation audit log : link {} mismatch !!!\n", index);
+            return Err(SPDM_STATUS_VERIF_FAIL);
+        }
+    }
+    if log.head() != expected.head() {
+        error!("!!! negotiation audit log : head mismatch !!!\n");
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    Ok(())
+}
+
+impl<'a> SpdmContext<'a> {
+    /// Export the audit log of the VCA negotiation of this connection.
+    /// The chain is computed with the negotiated base hash algorithm.
+    ///
+    /// The requester does not track the connection state, the negotiation
+    /// is complete once all the VCA messages are recorded.
+    pub fn export_negotiation_audit_log(&self) -> SpdmResult<SpdmNegotiationAuditLog> {
+        let boundary = &self.runtime_info.vca_boundary;
+        if boundary.is_overflow() || boundary.count() != MAX_SPDM_VCA_MESSAGE_COUNT {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        let messages = boundary
+            .split(self.runtime_info.message_a.as_ref())
+            .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+        SpdmNegotiationAuditLog::generate(
+            self.negotiate_info.base_hash_sel,
+            &SpdmNegotiationSelection::from(&self.negotiate_info),
+            &messages[..boundary.count()],
+        )
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_vca_message_boundary() {
+        let message_a = [1u8, 2, 3, 4, 5, 6, 7];
+        let mut boundary = SpdmVcaMessageBoundary::default();
+        assert!(boundary.record(2).is_some());
+        assert!(boundary.record(3).is_some());
+        assert!(boundary.record(7).is_some());
+        let messages = boundary.split(&message_a).unwrap();
+        assert_eq!(messages[0], &[1u8, 2]);
+        assert_eq!(messages[1], &[3u8]);
+        assert_eq!(messages[2], &[4u8, 5, 6, 7]);
+        assert!(boundary.split(&message_a[..5]).is_none());
+
+        for _ in 3..MAX_SPDM_VCA_MESSAGE_COUNT {
+            assert!(boundary.record(7).is_some());
+        }
+        assert!(!boundary.is_overflow());
+        assert!(boundary.record(7).is_none());
+        assert!(boundary.is_overflow());
+
+        boundary.reset();
+        assert_eq!(boundary.count(), 0);
+        assert!(!boundary.is_overflow());
+    }
+
+    #[test]
+    fn test_case0_negotiation_selection_codec() {
+        let selection = SpdmNegotiationSelection {
+            spdm_version_sel: SpdmVersion::SpdmVersion12,
+            req_capabilities_sel: SpdmRequestCapabilityFlags::CERT_CAP,
+            rsp_capabilities_sel: SpdmResponseCapabilityFlags::CERT_CAP
+                | SpdmResponseCapabilityFlags::CHAL_CAP,
+            rsp_ct_exponent_sel: 16,
+            measurement_specification_sel: SpdmMeasurementSpecification::DMTF,
+            measurement_hash_sel: SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+            base_hash_sel: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            base_asym_sel: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            dhe_sel: SpdmDheAlgo::SECP_384_R1,
+            aead_sel: SpdmAeadAlgo::AES_256_GCM,
+            req_asym_sel: SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            key_schedule_sel: SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+            opaque_data_support: SpdmOpaqueSupport::OPAQUE_DATA_FMT1,
+            req_data_transfer_size_sel: 0x1200,
+            rsp_max_spdm_msg_size_sel: 0x1200,
+            ..Default::default()
+        };
+        let mut buffer = [0u8; SPDM_NEGOTIATION_SELECTION_SIZE];
+        let mut writer = Writer::init(&mut buffer);
+        assert_eq!(
+            selection.encode(&mut writer),
+            Ok(SPDM_NEGOTIATION_SELECTION_SIZE)
+        );
+        let mut reader = Reader::init(&buffer);
+        assert_eq!(SpdmNegotiationSelection::read(&mut reader), Some(selection));
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Runtime buffer sizes. config::MAX_SPDM_MSG_SIZE, SENDER_BUFFER_SIZE and
+//! RECEIVER_BUFFER_SIZE are the ceilings of a build, SpdmBufferConfig picks
+//! the sizes of one context below them, e.g. a small MCTP endpoint and a
+//! large DOE transfer served by the same spdmlib.
+//!
+//! Message handlers borrow their buffers from the SpdmBufferPool of the
+//! context instead of placing multi-KB arrays on the stack.
+
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::config;
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER};
+use zeroize::{Zeroize, Zeroizing};
+
+/// session_id (4) + len (2) + app_len (2) + mac (16), as in build.rs
+pub const SPDM_MIN_TRANSPORT_OVERHEAD: usize = 24;
+
+/// Smallest SPDM message size without chunking.
+pub const SPDM_MIN_MSG_SIZE: usize = 42;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpdmBufferConfig {
+    pub max_spdm_msg_size: usize,
+    pub sender_buffer_size: usize,
+    pub receiver_buffer_size: usize,
+}
+
+impl Default for SpdmBufferConfig {
+    fn default() -> Self {
+        SpdmBufferConfig {
+            max_spdm_msg_size: config::MAX_SPDM_MSG_SIZE,
+            sender_buffer_size: config::SENDER_BUFFER_SIZE,
+            receiver_buffer_size: config::RECEIVER_BUFFER_SIZE,
+        }
+    }
+}
+
+impl SpdmBufferConfig {
+    /// Transport buffers of max_spdm_msg_size plus transport_overhead.
+    pub fn with_msg_size(max_spdm_msg_size: usize, transport_overhead: usize) -> Self {
+        let transport_overhead = transport_overhead.max(SPDM_MIN_TRANSPORT_OVERHEAD + 1);
+        SpdmBufferConfig {
+            max_spdm_msg_size,
+            sender_buffer_size: max_spdm_msg_size + transport_overhead,
+            receiver_buffer_size: max_spdm_msg_size + transport_overhead,
+        }
+    }
+
+    /// The build.rs checks, plus the sizes may not exceed the ceilings.
+    pub fn validate(&self) -> SpdmResult {
+        if self.max_spdm_msg_size < SPDM_MIN_MSG_SIZE
+            || self.max_spdm_msg_size > config::MAX_SPDM_MSG_SIZE
+            || self.sender_buffer_size <= self.max_spdm_msg_size + SPDM_MIN_TRANSPORT_OVERHEAD
+            || self.sender_buffer_size > config::SENDER_BUFFER_SIZE
+            || self.receiver_buffer_size <= self.max_spdm_msg_size + SPDM_MIN_TRANSPORT_OVERHEAD
+            || self.receiver_buffer_size > config::RECEIVER_BUFFER_SIZE
+        {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        Ok(())
+    }
+}
+
+/// Source of the transport and message buffers of a context.
+pub trait SpdmBufferProvider {
+    /// Return a zeroed buffer of size bytes.
+    fn alloc_buffer(&self, size: usize) -> Vec<u8>;
+}
+
+/// Heap allocation of every buffer.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SpdmAllocBufferProvider;
+
+impl SpdmBufferProvider for SpdmAllocBufferProvider {
+    fn alloc_buffer(&self, size: usize) -> Vec<u8> {
+        vec![0u8; size]
+    }
+}
+
+pub fn default_buffer_provider() -> Box<dyn SpdmBufferProvider> {
+    Box::new(SpdmAllocBufferProvider)
+}
+
+/// Free buffers kept for reuse, a request/response exchange holds at most
+/// a send, a receive and two transport buffers at a time.
+pub const SPDM_MAX_POOLED_BUFFER_COUNT: usize = 6;
+
+/// A buffer borrowed from the pool. It is zeroized when dropped, so a
+/// buffer not given back, e.g. on an error path, leaves no plain text
+/// behind.
+pub type SpdmPoolBuffer = Zeroizing<Vec<u8>>;
+
+/// Scratch buffers of a context. A buffer not given back is zeroized on
+/// drop and the next alloc goes to the provider again.
+#[derive(Default)]
+pub struct SpdmBufferPool {
+    free: Vec<Vec<u8>>,
+}
+
+impl SpdmBufferPool {
+    /// Return a zeroed buffer of size bytes, reusing a free one if possible.
+    pub fn alloc(&mut self, provider: &dyn SpdmBufferProvider, size: usize) -> SpdmPoolBuffer {
+        let buffer = match self
+            .free
+            .iter()
+            .position(|buffer| buffer.capacity() >= size)
+        {
+            Some(index) => {
+                let mut buffer = self.free.swap_remove(index);
+                buffer.resize(size, 0);
+                buffer
+            }
+            None => provider.alloc_buffer(size),
+        };
+        Zeroizing::new(buffer)
+    }
+
+    /// Give a buffer back. It is zeroized first, as it may hold secured
+    /// message plain text.
+    pub fn free(&mut self, mut buffer: SpdmPoolBuffer) {
+        let mut buffer = core::mem::take(&mut *buffer);
+        buffer.zeroize();
+        if self.free.len() < SPDM_MAX_POOLED_BUFFER_COUNT {
+            self.free.push(buffer);
+        }
+    }
+
+    /// Drop the free buffers, e.g. after switching to smaller sizes.
+    pub fn clear(&mut self) {
+        self.free.clear();
+    }
+
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_buffer_config() {
+        assert!(SpdmBufferConfig::default().validate().is_ok());
+
+        let config = SpdmBufferConfig::with_msg_size(64, 0);
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.sender_buffer_size,
+            64 + SPDM_MIN_TRANSPORT_OVERHEAD + 1
+        );
+
+        assert!(SpdmBufferConfig::with_msg_size(SPDM_MIN_MSG_SIZE - 1, 0)
+            .validate()
+            .is_err());
+        assert!(
+            SpdmBufferConfig::with_msg_size(config::MAX_SPDM_MSG_SIZE + 1, 0)
+                .validate()
+                .is_err()
+        );
+        let config = SpdmBufferConfig {
+            receiver_buffer_size: 64 + SPDM_MIN_TRANSPORT_OVERHEAD,
+            ..SpdmBufferConfig::with_msg_size(64, 0)
+        };
+        assert!(config.validate().is_err());
+
+        assert_eq!(SpdmAllocBufferProvider.alloc_buffer(16), [0u8; 16]);
+    }
+
+    #[test]
+    fn test_case0_buffer_pool() {
+        let mut pool = SpdmBufferPool::default();
+        let mut buffer = pool.alloc(&SpdmAllocBufferProvider, 64);
+        assert_eq!(buffer.len(), 64);
+        buffer[0] = 0x5a;
+        let ptr = buffer.as_ptr();
+        pool.free(buffer);
+        assert_eq!(pool.free_count(), 1);
+
+        // reused, zeroed and resized
+        let buffer = pool.alloc(&SpdmAllocBufferProvider, 32);
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert_eq!(*buffer, [0u8; 32]);
+        assert_eq!(pool.free_count(), 0);
+        pool.free(buffer);
+
+        // too small to be reused
+        let buffer = pool.alloc(&SpdmAllocBufferProvider, 128);
+        assert_eq!(buffer.len(), 128);
+        assert_eq!(pool.free_count(), 1);
+        pool.free(buffer);
+
+        for _ in 0..SPDM_MAX_POOLED_BUFFER_COUNT + 1 {
+            pool.free(Zeroizing::new(SpdmAllocBufferProvider.alloc_buffer(8)));
+        }
+        assert_eq!(pool.free_count(), SPDM_MAX_POOLED_BUFFER_COUNT);
+        pool.clear();
+        assert_eq!(pool.free_count(), 0);
+    }
+}
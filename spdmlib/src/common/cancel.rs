@@ -0,0 +1,50 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Cancellation of the exchange in flight. The host keeps a clone of the
+//! token set in the context, e.g. in another thread or in the SpdmDeviceIo
+//! to abort a blocking receive, and cancels it to tear down a stuck
+//! connection.
+
+extern crate alloc;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmCancelToken(Arc<AtomicBool>);
+
+impl SpdmCancelToken {
+    pub fn new() -> Self {
+        SpdmCancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Allow the context to be used again after a cancellation.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_cancel_token() {
+        let token = SpdmCancelToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+        clone.cancel();
+        assert!(token.is_cancelled());
+        token.reset();
+        assert!(!clone.is_cancelled());
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Capture of the plain text SPDM messages of a context, before the
+//! transport encapsulation or secured message encryption of a sent message
+//! and after the decapsulation or decryption of a received one. Application
+//! messages of a session are not captured.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmMessageDirection {
+    Sent,
+    Received,
+}
+
+/// Receives every captured message, set it with SpdmContext::set_message_capture.
+pub trait SpdmMessageCapture {
+    /// session_id is None for a message sent in the clear.
+    fn capture(&mut self, direction: SpdmMessageDirection, session_id: Option<u32>, message: &[u8]);
+}
+
+impl<F> SpdmMessageCapture for F
+where
+    F: FnMut(SpdmMessageDirection, Option<u32>, &[u8]),
+{
+    fn capture(
+        &mut self,
+        direction: SpdmMessageDirection,
+        session_id: Option<u32>,
+        message: &[u8],
+    ) {
+        self(direction, session_id, message)
+    }
+}
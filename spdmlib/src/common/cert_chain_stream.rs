@@ -0,0 +1,278 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Verification of a cert chain as its GET_CERTIFICATE portions arrive, for
+//! chains larger than SpdmCertChainBuffer. The chain is hashed on the fly
+//! and only the certificate being received and its issuer are kept.
+
+use super::*;
+use crate::error::SPDM_STATUS_INVALID_CERT;
+
+/// What is kept of a peer cert chain verified in portions.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmStreamedCertChain {
+    /// Hash of the whole cert chain, including the SPDM cert chain header
+    /// and root hash, as in DIGESTS and the transcripts.
+    pub cert_chain_hash: SpdmDigestStruct,
+    pub leaf_cert: Vec<u8>,
+    pub cert_count: usize,
+}
+
+/// id-kp-serverAuth, the leaf certificate EKU checked by verify_cert_chain.
+#[cfg(feature = "hashed-transcript-data")]
+const OID_EKU_SPDM_RESPONDER_AUTH: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01];
+
+#[cfg(feature = "hashed-transcript-data")]
+pub struct SpdmCertChainStreamVerifier {
+    base_hash_algo: SpdmBaseHashAlgo,
+    hash_ctx: crypto::hash::SpdmHashCtx,
+    peer_root_cert: Option<Vec<u8>>,
+    // SPDM cert chain header and root hash
+    header: Vec<u8>,
+    total_size: usize,
+    received_size: usize,
+    // certificate being received, then its issuer
+    cert: Vec<u8>,
+    issuer: Option<Vec<u8>>,
+    cert_count: usize,
+}
+
+#[cfg(feature = "hashed-transcript-data")]
+impl SpdmCertChainStreamVerifier {
+    /// peer_root_cert is the provisioned root certificate the chain must
+    /// start with, if any.
+    pub fn new(
+        base_hash_algo: SpdmBaseHashAlgo,
+        peer_root_cert: Option<&[u8]>,
+    ) -> SpdmResult<Self> {
+        Ok(SpdmCertChainStreamVerifier {
+            base_hash_algo,
+            hash_ctx: crypto::hash::hash_ctx_init(base_hash_algo)
+                .ok_or(SPDM_STATUS_CRYPTO_ERROR)?,
+            peer_root_cert: peer_root_cert.map(|cert| cert.to_vec()),
+            header: Vec::new(),
+            total_size: 0,
+            received_size: 0,
+            cert: Vec::new(),
+            issuer: None,
+            cert_count: 0,
+        })
+    }
+
+    /// Size of the cert chain from its header, 0 until the header is received.
+    pub fn total_size(&self) -> usize {
+        self.total_size
+    }
+
+    /// Feed the next portion of the cert chain.
+    pub fn update(&mut self, mut portion: &[u8]) -> SpdmResult {
+        crypto::hash::hash_ctx_update(&mut self.hash_ctx, portion)?;
+        self.received_size += portion.len();
+
+        let header_size = 4 + self.base_hash_algo.get_size() as usize;
+        if self.header.len() < header_size {
+            let size = core::cmp::min(header_size - self.header.len(), portion.len());
+            self.header.extend_from_slice(&portion[..size]);
+            portion = &portion[size..];
+            if self.header.len() == header_size {
+                self.total_size =
+                    u16::read_bytes(&self.header[..2]).ok_or(SPDM_STATUS_INVALID_CERT)? as usize;
+                if self.total_size <= header_size {
+                    return Err(SPDM_STATUS_INVALID_CERT);
+                }
+            }
+        }
+        if self.total_size != 0 && self.received_size > self.total_size {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+
+        while !portion.is_empty() {
+            // the DER header is read a byte at a time, then the rest at once
+            let wanted = match Self::get_der_size(&self.cert)? {
+                Some(cert_size) => cert_size - self.cert.len(),
+                None => 1,
+            };
+            let size = core::cmp::min(wanted, portion.len());
+            self.cert.extend_from_slice(&portion[..size]);
+            portion = &portion[size..];
+            if Self::get_der_size(&self.cert)? == Some(self.cert.len()) {
+                self.verify_cert()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the chain is complete, the leaf certificate is returned with
+    /// the hash of the whole chain.
+    pub fn finish(self) -> SpdmResult<SpdmStreamedCertChain> {
+        if self.total_size == 0 || self.received_size != self.total_size || !self.cert.is_empty() {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        let leaf_cert = self.issuer.ok_or(SPDM_STATUS_INVALID_CERT)?;
+        let leaf_info = crypto::get_cert_info(&leaf_cert).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+        if !leaf_info.extended_key_usage.is_empty()
+            && !leaf_info
+                .extended_key_usage
+                .iter()
+                .any(|oid| *oid == OID_EKU_SPDM_RESPONDER_AUTH)
+        {
+            error!("!!! leaf cert extended key usage - fail !!!\n");
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+
+        let cert_chain_hash =
+            crypto::hash::hash_ctx_finalize(self.hash_ctx).ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+        Ok(SpdmStreamedCertChain {
+            cert_chain_hash,
+            leaf_cert,
+            cert_count: self.cert_count,
+        })
+    }
+
+    // Size of the DER element starting buf, None until its header is complete.
+    fn get_der_size(buf: &[u8]) -> SpdmResult<Option<usize>> {
+        if buf.len() < 2 {
+            return Ok(None);
+        }
+        if buf[0] != 0x30 {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        if buf[1] < 0x80 {
+            return Ok(Some(2 + buf[1] as usize));
+        }
+        let length_size = (buf[1] & 0x7f) as usize;
+        if length_size == 0 || length_size > 3 {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        if buf.len() < 2 + length_size {
+            return Ok(None);
+        }
+        let length = buf[2..2 + length_size]
+            .iter()
+            .fold(0usize, |length, byte| (length << 8) | *byte as usize);
+        Ok(Some(2 + length_size + length))
+    }
+
+    fn verify_cert(&mut self) -> SpdmResult {
+        let cert = core::mem::take(&mut self.cert);
+        let cert_info = crypto::get_cert_info(&cert).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+
+        match self.issuer.as_ref() {
+            None => {
+                let root_hash = crypto::hash::hash_all(self.base_hash_algo, &cert)
+                    .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+                if root_hash.as_ref() != &self.header[4..] {
+                    error!("root_hash - fail!\n");
+                    return Err(SPDM_STATUS_INVALID_CERT);
+                }
+                if let Some(peer_root_cert) = self.peer_root_cert.as_ref() {
+                    if cert != *peer_root_cert {
+                        error!("root_cert data - fail!\n");
+                        return Err(SPDM_STATUS_INVALID_CERT);
+                    }
+                }
+                crypto::cert_operation::verify_cert(&cert, &cert)?;
+            }
+            Some(issuer) => {
+                let issuer_info =
+                    crypto::get_cert_info(issuer).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+                if !issuer_info.is_ca || issuer_info.subject != cert_info.issuer {
+                    error!("cert {} issuer - fail!\n", self.cert_count);
+                    return Err(SPDM_STATUS_INVALID_CERT);
+                }
+                crypto::cert_operation::verify_cert(&cert, issuer)?;
+            }
+        }
+
+        info!("cert {} is verified!\n", self.cert_count);
+        self.cert_count += 1;
+        self.issuer = Some(cert);
+        Ok(())
+    }
+}
+
+#[cfg(all(test,))]
+#[cfg(feature = "hashed-transcript-data")]
+mod tests {
+    use super::*;
+
+    fn new_cert_chain() -> Vec<u8> {
+        let certs = &include_bytes!("../../../test_key/ecp384/bundle_responder.certchain.der")[..];
+        let (root_begin, root_end) =
+            crypto::cert_operation::get_cert_from_cert_chain(certs, 0).unwrap();
+        let root_hash = crypto::hash::hash_all(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            &certs[root_begin..root_end],
+        )
+        .unwrap();
+        let mut cert_chain = Vec::new();
+        cert_chain
+            .extend_from_slice(&((4 + SHA384_DIGEST_SIZE + certs.len()) as u16).to_le_bytes());
+        cert_chain.extend_from_slice(&[0, 0]);
+        cert_chain.extend_from_slice(root_hash.as_ref());
+        cert_chain.extend_from_slice(certs);
+        cert_chain
+    }
+
+    #[test]
+    fn test_case0_cert_chain_stream_verifier() {
+        let cert_chain = new_cert_chain();
+        for portion_size in [1, 7, 64, 1024, cert_chain.len()] {
+            let mut verifier =
+                SpdmCertChainStreamVerifier::new(SpdmBaseHashAlgo::TPM_ALG_SHA_384, None).unwrap();
+            for portion in cert_chain.chunks(portion_size) {
+                assert!(verifier.update(portion).is_ok());
+            }
+            assert_eq!(verifier.total_size(), cert_chain.len());
+            let streamed = verifier.finish().unwrap();
+            assert_eq!(streamed.cert_count, 3);
+            assert_eq!(
+                streamed.cert_chain_hash.as_ref(),
+                crypto::hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, &cert_chain)
+                    .unwrap()
+                    .as_ref()
+            );
+            let (leaf_begin, leaf_end) =
+                crypto::cert_operation::get_cert_from_cert_chain(&cert_chain[52..], -1).unwrap();
+            assert_eq!(
+                streamed.leaf_cert,
+                &cert_chain[52 + leaf_begin..52 + leaf_end]
+            );
+        }
+    }
+
+    #[test]
+    fn test_case1_cert_chain_stream_verifier() {
+        let cert_chain = new_cert_chain();
+
+        // truncated chain
+        let mut verifier =
+            SpdmCertChainStreamVerifier::new(SpdmBaseHashAlgo::TPM_ALG_SHA_384, None).unwrap();
+        assert!(verifier.update(&cert_chain[..cert_chain.len() - 1]).is_ok());
+        assert!(verifier.finish().is_err());
+
+        // root hash mismatch
+        let mut tampered = cert_chain.clone();
+        tampered[4] ^= 0xFF;
+        let mut verifier =
+            SpdmCertChainStreamVerifier::new(SpdmBaseHashAlgo::TPM_ALG_SHA_384, None).unwrap();
+        assert!(verifier.update(&tampered).is_err());
+
+        // tampered signature of the leaf certificate
+        let mut tampered = cert_chain.clone();
+        let last = tampered.len() - 4;
+        tampered[last] ^= 0xFE;
+        let mut verifier =
+            SpdmCertChainStreamVerifier::new(SpdmBaseHashAlgo::TPM_ALG_SHA_384, None).unwrap();
+        assert!(verifier.update(&tampered).is_err());
+
+        // provisioned root cert mismatch
+        let mut verifier = SpdmCertChainStreamVerifier::new(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            Some(&cert_chain[52..60]),
+        )
+        .unwrap();
+        assert!(verifier.update(&cert_chain).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Policy applied to the peer cert chains, on top of the cryptographic
+//! verification of the crypto backend. Set it with
+//! SpdmContext::set_cert_policy.
+
+use super::*;
+use crate::crypto::{SpdmCertInfo, SpdmX509KeyUsage, OID_DICE_MULTI_TCB_INFO, OID_DICE_TCB_INFO};
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_CERT};
+
+pub trait SpdmCertPolicy {
+    /// Accept certificates out of their validity period, e.g. for lab
+    /// devices. The chain is then verified at a time all its certificates
+    /// are valid at.
+    fn allow_expired(&self) -> bool {
+        false
+    }
+
+    /// Check the parsed certificates of a cryptographically verified chain,
+    /// root first and leaf last.
+    fn check_cert_chain(&self, certs: &[SpdmCertInfo]) -> SpdmResult;
+}
+
+/// Critical extensions spdmlib knows of, see SpdmCertInfo. DICE
+/// certificates usually mark their TCB extensions critical.
+const SPDM_KNOWN_CRITICAL_EXTENSIONS: [&[u8]; 6] = [
+    &[0x55, 0x1d, 0x0f],
+    &[0x55, 0x1d, 0x11],
+    &[0x55, 0x1d, 0x13],
+    &[0x55, 0x1d, 0x25],
+    OID_DICE_TCB_INFO,
+    OID_DICE_MULTI_TCB_INFO,
+];
+
+/// Configurable policy covering the usual requirements.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmBasicCertPolicy {
+    /// Key usages the leaf certificate must have, if it has the extension.
+    pub required_leaf_key_usage: SpdmX509KeyUsage,
+    pub forbidden_leaf_key_usage: SpdmX509KeyUsage,
+    /// Maximum number of intermediate certificates, None for no limit.
+    pub max_path_length: Option<usize>,
+    /// Enforce the pathLenConstraint of the BasicConstraints extensions.
+    pub enforce_path_len_constraint: bool,
+    /// Reject critical extensions which are neither known by spdmlib nor
+    /// listed in known_critical_extensions.
+    pub reject_unknown_critical_extensions: bool,
+    pub known_critical_extensions: Vec<&'static [u8]>,
+    pub allow_expired: bool,
+}
+
+impl SpdmCertPolicy for SpdmBasicCertPolicy {
+    fn allow_expired(&self) -> bool {
+        self.allow_expired
+    }
+
+    fn check_cert_chain(&self, certs: &[SpdmCertInfo]) -> SpdmResult {
+        let leaf = certs.last().ok_or(SPDM_STATUS_INVALID_CERT)?;
+        if let Some(key_usage) = leaf.key_usage {
+            if !key_usage.contains(self.required_leaf_key_usage)
+                || key_usage.intersects(self.forbidden_leaf_key_usage)
+            {
+                error!("!!! leaf cert key usage rejected by policy !!!\n");
+                return Err(SPDM_STATUS_INVALID_CERT);
+            }
+        }
+
+        let intermediate_count = certs.len().saturating_sub(2);
+        if let Some(max_path_length) = self.max_path_length {
+            if intermediate_count > max_path_length {
+                error!("!!! cert chain path length rejected by policy !!!\n");
+                return Err(SPDM_STATUS_INVALID_CERT);
+            }
+        }
+
+        for (index, cert) in certs.iter().enumerate() {
+            if self.enforce_path_len_constraint && index + 1 < certs.len() {
+                // intermediate certificates below this one
+                let below = certs.len() - index - 2;
+                if let Some(path_len_constraint) = cert.path_len_constraint {
+                    if below > path_len_constraint as usize {
+                        error!("!!! cert {} pathLenConstraint exceeded !!!\n", index);
+                        return Err(SPDM_STATUS_INVALID_CERT);
+                    }
+                }
+            }
+
+            if self.reject_unknown_critical_extensions {
+                let unknown = cert.extensions.iter().any(|extension| {
+                    extension.critical
+                        && !SPDM_KNOWN_CRITICAL_EXTENSIONS
+                            .iter()
+                            .chain(self.known_critical_extensions.iter())
+                            .any(|oid| *oid == extension.oid)
+                });
+                if unknown {
+                    error!("!!! cert {} has unknown critical extension !!!\n", index);
+                    return Err(SPDM_STATUS_INVALID_CERT);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> SpdmContext<'a> {
+    /// Verify a peer chain of DER certificates, root first, with the crypto
+    /// backend then with the cert policy if any.
+    pub fn verify_peer_cert_chain(&self, cert_chain: &[u8]) -> SpdmResult {
+        let cert_policy = match self.cert_policy.as_ref() {
+            Some(cert_policy) => cert_policy,
+            None => return crypto::cert_operation::verify_cert_chain(cert_chain),
+        };
+
+        if cert_policy.allow_expired() {
+            let (not_before, not_after) = crypto::get_cert_chain_validity(cert_chain)
+                .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+            if not_before > not_after {
+                return Err(SPDM_STATUS_INVALID_CERT);
+            }
+            crypto::cert_operation::verify_cert_chain_at(cert_chain, not_before)?;
+        } else {
+            crypto::cert_operation::verify_cert_chain(cert_chain)?;
+        }
+
+        let mut certs = Vec::new();
+        let cert_chain_iter = SpdmCertChainIter {
+            cert_chain,
+            index: 0,
+        };
+        for cert in cert_chain_iter {
+            certs.push(crypto::get_cert_info(cert).map_err(|_| SPDM_STATUS_INVALID_CERT)?);
+        }
+        cert_policy.check_cert_chain(&certs)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::crypto::{SpdmCertExtension, SpdmX509KeyUsage};
+
+    fn new_cert_info(key_usage: Option<SpdmX509KeyUsage>) -> SpdmCertInfo<'static> {
+        SpdmCertInfo {
+            serial_number: &[1],
+            issuer: &[],
+            subject: &[],
+            subject_common_name: None,
+            not_before: 0,
+            not_after: u64::MAX,
+            subject_public_key_info: &[],
+            key_usage,
+            is_ca: key_usage.is_none(),
+            path_len_constraint: None,
+            subject_alt_names: Vec::new(),
+            extended_key_usage: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_case0_basic_cert_policy() {
+        let leaf = new_cert_info(Some(
+            SpdmX509KeyUsage::DIGITAL_SIGNATURE | SpdmX509KeyUsage::KEY_ENCIPHERMENT,
+        ));
+        let mut root = new_cert_info(None);
+        let inter = new_cert_info(None);
+
+        let policy = SpdmBasicCertPolicy {
+            required_leaf_key_usage: SpdmX509KeyUsage::DIGITAL_SIGNATURE,
+            ..Default::default()
+        };
+        let certs = [root.clone(), inter.clone(), leaf.clone()];
+        assert!(policy.check_cert_chain(&certs).is_ok());
+        assert!(!policy.allow_expired());
+
+        let policy = SpdmBasicCertPolicy {
+            forbidden_leaf_key_usage: SpdmX509KeyUsage::KEY_ENCIPHERMENT,
+            ..Default::default()
+        };
+        assert!(policy.check_cert_chain(&certs).is_err());
+
+        let policy = SpdmBasicCertPolicy {
+            max_path_length: Some(0),
+            ..Default::default()
+        };
+        assert!(policy.check_cert_chain(&certs).is_err());
+        assert!(policy.check_cert_chain(&certs[1..]).is_ok());
+
+        root.path_len_constraint = Some(0);
+        let certs = [root.clone(), inter.clone(), leaf.clone()];
+        let policy = SpdmBasicCertPolicy {
+            enforce_path_len_constraint: true,
+            ..Default::default()
+        };
+        assert!(policy.check_cert_chain(&certs).is_err());
+        assert!(SpdmBasicCertPolicy::default()
+            .check_cert_chain(&certs)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_case1_basic_cert_policy() {
+        const OID_VENDOR: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0x01];
+        let mut leaf = new_cert_info(None);
+        leaf.extensions.push(SpdmCertExtension {
+            oid: &[0x55, 0x1d, 0x13],
+            critical: true,
+            value: &[0x30, 0x00],
+        });
+        let policy = SpdmBasicCertPolicy {
+            reject_unknown_critical_extensions: true,
+            ..Default::default()
+        };
+        assert!(policy.check_cert_chain(&[leaf.clone()]).is_ok());
+
+        leaf.extensions.push(SpdmCertExtension {
+            oid: OID_DICE_TCB_INFO,
+            critical: true,
+            value: &[0x30, 0x00],
+        });
+        assert!(policy.check_cert_chain(&[leaf.clone()]).is_ok());
+
+        leaf.extensions.push(SpdmCertExtension {
+            oid: OID_VENDOR,
+            critical: true,
+            value: &[0x05, 0x00],
+        });
+        assert!(policy.check_cert_chain(&[leaf.clone()]).is_err());
+        let policy = SpdmBasicCertPolicy {
+            reject_unknown_critical_extensions: true,
+            known_critical_extensions: alloc::vec![OID_VENDOR],
+            ..Default::default()
+        };
+        assert!(policy.check_cert_chain(&[leaf]).is_ok());
+        assert!(policy.check_cert_chain(&[]).is_err());
+    }
+}
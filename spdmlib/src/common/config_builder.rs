@@ -0,0 +1,652 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Builders of SpdmConfigInfo and SpdmProvisionInfo. build() checks the
+//! capabilities against the algorithms, so a misconfiguration fails before
+//! the first message instead of in the middle of the negotiation.
+
+use super::session::SpdmSessionEvictionPolicy;
+use super::{
+    SpdmCertChainAlgo, SpdmConfigInfo, SpdmOpaqueSupport, SpdmProvisionInfo, INITIAL_SESSION_ID,
+};
+use crate::config;
+use crate::error::{SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_PARAMETER};
+use crate::protocol::*;
+
+/// Smallest DataTransferSize allowed by SPDM 1.2.
+pub const SPDM_MIN_DATA_TRANSFER_SIZE: u32 = 42;
+
+#[derive(Debug, Default)]
+pub struct SpdmConfigInfoBuilder {
+    config_info: SpdmConfigInfo,
+}
+
+impl SpdmConfigInfoBuilder {
+    pub fn new() -> Self {
+        SpdmConfigInfoBuilder::default()
+    }
+
+    /// Supported versions, unused entries are left Unknown.
+    pub fn spdm_versions(mut self, versions: &[SpdmVersion]) -> Self {
+        let mut spdm_version = [SpdmVersion::default(); MAX_SPDM_VERSION_COUNT];
+        for (to, from) in spdm_version.iter_mut().zip(versions.iter()) {
+            *to = *from;
+        }
+        self.config_info.spdm_version = spdm_version;
+        self
+    }
+
+    pub fn req_capabilities(mut self, req_capabilities: SpdmRequestCapabilityFlags) -> Self {
+        self.config_info.req_capabilities = req_capabilities;
+        self
+    }
+
+    pub fn rsp_capabilities(mut self, rsp_capabilities: SpdmResponseCapabilityFlags) -> Self {
+        self.config_info.rsp_capabilities = rsp_capabilities;
+        self
+    }
+
+    pub fn req_ct_exponent(mut self, req_ct_exponent: u8) -> Self {
+        self.config_info.req_ct_exponent = req_ct_exponent;
+        self
+    }
+
+    pub fn rsp_ct_exponent(mut self, rsp_ct_exponent: u8) -> Self {
+        self.config_info.rsp_ct_exponent = rsp_ct_exponent;
+        self
+    }
+
+    pub fn measurement(
+        mut self,
+        measurement_specification: SpdmMeasurementSpecification,
+        measurement_hash_algo: SpdmMeasurementHashAlgo,
+    ) -> Self {
+        self.config_info.measurement_specification = measurement_specification;
+        self.config_info.measurement_hash_algo = measurement_hash_algo;
+        self
+    }
+
+    pub fn base_hash_algo(mut self, base_hash_algo: SpdmBaseHashAlgo) -> Self {
+        self.config_info.base_hash_algo = base_hash_algo;
+        self
+    }
+
+    pub fn base_asym_algo(mut self, base_asym_algo: SpdmBaseAsymAlgo) -> Self {
+        self.config_info.base_asym_algo = base_asym_algo;
+        self
+    }
+
+    pub fn req_asym_algo(mut self, req_asym_algo: SpdmReqAsymAlgo) -> Self {
+        self.config_info.req_asym_algo = req_asym_algo;
+        self
+    }
+
+    pub fn dhe_algo(mut self, dhe_algo: SpdmDheAlgo) -> Self {
+        self.config_info.dhe_algo = dhe_algo;
+        self
+    }
+
+    pub fn aead_algo(mut self, aead_algo: SpdmAeadAlgo) -> Self {
+        self.config_info.aead_algo = aead_algo;
+        self
+    }
+
+    pub fn key_schedule_algo(mut self, key_schedule_algo: SpdmKeyScheduleAlgo) -> Self {
+        self.config_info.key_schedule_algo = key_schedule_algo;
+        self
+    }
+
+    pub fn opaque_support(mut self, opaque_support: SpdmOpaqueSupport) -> Self {
+        self.config_info.opaque_support = opaque_support;
+        self
+    }
+
+    pub fn session_policy(mut self, session_policy: u8) -> Self {
+        self.config_info.session_policy = session_policy;
+        self
+    }
+
+    pub fn runtime_content_change_support(mut self, support: bool) -> Self {
+        self.config_info.runtime_content_change_support = support;
+        self
+    }
+
+    pub fn transfer_size(mut self, data_transfer_size: u32, max_spdm_msg_size: u32) -> Self {
+        self.config_info.data_transfer_size = data_transfer_size;
+        self.config_info.max_spdm_msg_size = max_spdm_msg_size;
+        self
+    }
+
+    pub fn heartbeat_period(mut self, heartbeat_period: u8) -> Self {
+        self.config_info.heartbeat_period = heartbeat_period;
+        self
+    }
+
+    pub fn secure_spdm_versions(mut self, versions: &[u8]) -> Self {
+        let mut secure_spdm_version = [0u8; MAX_SECURE_SPDM_VERSION_COUNT];
+        for (to, from) in secure_spdm_version.iter_mut().zip(versions.iter()) {
+            *to = *from;
+        }
+        self.config_info.secure_spdm_version = secure_spdm_version;
+        self
+    }
+
+    pub fn max_outstanding_requests(mut self, max_outstanding_requests: u8) -> Self {
+        self.config_info.max_outstanding_requests = max_outstanding_requests;
+        self
+    }
+
+    pub fn max_session_mac_failure_count(mut self, count: u32) -> Self {
+        self.config_info.max_session_mac_failure_count = count;
+        self
+    }
+
+    pub fn enforce_peer_capabilities(mut self, enforce: bool) -> Self {
+        self.config_info.enforce_peer_capabilities = enforce;
+        self
+    }
+
+    /// Session slots allocated by the context, 0 means config::MAX_SPDM_SESSION_COUNT.
+    pub fn max_session_count(mut self, max_session_count: usize) -> Self {
+        self.config_info.max_session_count = max_session_count;
+        self
+    }
+
+    pub fn session_eviction_policy(mut self, policy: SpdmSessionEvictionPolicy) -> Self {
+        self.config_info.session_eviction_policy = policy;
+        self
+    }
+
+    /// Measurement indices returned as raw bit stream when the requester
+    /// asks for it, the others are always returned as digest. Only a
+    /// measurement provider set with ResponderContext::set_measurement_provider
+    /// honors them, the secret::measurement callbacks are not told about the
+    /// request and return whatever they collect.
+    pub fn raw_bit_stream_measurement_indices(mut self, indices: &[u8]) -> Self {
+        let mut raw_bit_stream_measurement_indices = [0u8; 32];
+        for index in indices {
+            raw_bit_stream_measurement_indices[*index as usize / 8] |= 1 << (index % 8);
+        }
+        self.config_info.raw_bit_stream_measurement_indices = raw_bit_stream_measurement_indices;
+        self
+    }
+
+    /// Accept SET_CERTIFICATE outside a session, where the requester is not
+    /// authenticated, e.g. for provisioning in a trusted environment.
+    pub fn allow_set_certificate_out_of_session(mut self, allow: bool) -> Self {
+        self.config_info.allow_set_certificate_out_of_session = allow;
+        self
+    }
+
+    /// Check the configuration, return SPDM_STATUS_INVALID_PARAMETER on the
+    /// first inconsistency found.
+    pub fn build(self) -> SpdmResult<SpdmConfigInfo> {
+        check_config_info(&self.config_info)?;
+        Ok(self.config_info)
+    }
+}
+
+fn check(condition: bool, reason: &str) -> SpdmResult {
+    if condition {
+        Ok(())
+    } else {
+        error!("invalid config_info: {}\n", reason);
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    }
+}
+
+/// The checks of SpdmConfigInfoBuilder::build, for a hand filled config.
+pub fn check_config_info(config_info: &SpdmConfigInfo) -> SpdmResult {
+    let req_cap = config_info.req_capabilities;
+    let rsp_cap = config_info.rsp_capabilities;
+
+    check(
+        config_info
+            .spdm_version
+            .iter()
+            .any(|version| *version != SpdmVersion::default()),
+        "no spdm_version",
+    )?;
+
+    // measurements
+    let meas_cap = rsp_cap.intersects(
+        SpdmResponseCapabilityFlags::MEAS_CAP_SIG | SpdmResponseCapabilityFlags::MEAS_CAP_NO_SIG,
+    );
+    check(
+        !rsp_cap.contains(
+            SpdmResponseCapabilityFlags::MEAS_CAP_SIG
+                | SpdmResponseCapabilityFlags::MEAS_CAP_NO_SIG,
+        ),
+        "MEAS_CAP_SIG and MEAS_CAP_NO_SIG",
+    )?;
+    check(
+        meas_cap || !rsp_cap.contains(SpdmResponseCapabilityFlags::MEAS_FRESH_CAP),
+        "MEAS_FRESH_CAP without MEAS_CAP",
+    )?;
+    check(
+        !meas_cap || !config_info.measurement_hash_algo.is_empty(),
+        "MEAS_CAP without measurement_hash_algo",
+    )?;
+    check(
+        !meas_cap || !config_info.measurement_specification.is_empty(),
+        "MEAS_CAP without measurement_specification",
+    )?;
+
+    // asymmetric
+    let rsp_key_ex = rsp_cap.contains(SpdmResponseCapabilityFlags::KEY_EX_CAP);
+    let rsp_asym = rsp_key_ex
+        || rsp_cap.intersects(
+            SpdmResponseCapabilityFlags::CERT_CAP
+                | SpdmResponseCapabilityFlags::CHAL_CAP
+                | SpdmResponseCapabilityFlags::MEAS_CAP_SIG
+                | SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP,
+        );
+    check(
+        !rsp_asym || !config_info.base_asym_algo.is_empty(),
+        "responder authentication without base_asym_algo",
+    )?;
+    check(
+        !(rsp_cap.contains(SpdmResponseCapabilityFlags::CERT_CAP)
+            && rsp_cap.contains(SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP)),
+        "CERT_CAP and PUB_KEY_ID_CAP",
+    )?;
+    let req_asym = req_cap.contains(SpdmRequestCapabilityFlags::MUT_AUTH_CAP)
+        || req_cap.intersects(
+            SpdmRequestCapabilityFlags::CERT_CAP | SpdmRequestCapabilityFlags::CHAL_CAP,
+        );
+    check(
+        !req_asym || !config_info.req_asym_algo.is_empty(),
+        "requester authentication without req_asym_algo",
+    )?;
+    check(
+        !req_cap.contains(SpdmRequestCapabilityFlags::MUT_AUTH_CAP)
+            || req_cap.contains(SpdmRequestCapabilityFlags::ENCAP_CAP),
+        "MUT_AUTH_CAP without ENCAP_CAP",
+    )?;
+    check(
+        (!rsp_asym && !req_asym && !meas_cap) || !config_info.base_hash_algo.is_empty(),
+        "no base_hash_algo",
+    )?;
+
+    // sessions
+    let rsp_psk = rsp_cap.intersects(
+        SpdmResponseCapabilityFlags::PSK_CAP_WITHOUT_CONTEXT
+            | SpdmResponseCapabilityFlags::PSK_CAP_WITH_CONTEXT,
+    );
+    check(
+        !rsp_cap.contains(
+            SpdmResponseCapabilityFlags::PSK_CAP_WITHOUT_CONTEXT
+                | SpdmResponseCapabilityFlags::PSK_CAP_WITH_CONTEXT,
+        ),
+        "PSK_CAP_WITHOUT_CONTEXT and PSK_CAP_WITH_CONTEXT",
+    )?;
+    let req_session = req_cap
+        .intersects(SpdmRequestCapabilityFlags::KEY_EX_CAP | SpdmRequestCapabilityFlags::PSK_CAP);
+    let rsp_session = rsp_key_ex || rsp_psk;
+    let req_secure = req_cap
+        .intersects(SpdmRequestCapabilityFlags::ENCRYPT_CAP | SpdmRequestCapabilityFlags::MAC_CAP);
+    let rsp_secure = rsp_cap.intersects(
+        SpdmResponseCapabilityFlags::ENCRYPT_CAP | SpdmResponseCapabilityFlags::MAC_CAP,
+    );
+    check(
+        req_session == req_secure,
+        "requester KEY_EX_CAP or PSK_CAP without ENCRYPT_CAP or MAC_CAP",
+    )?;
+    check(
+        rsp_session == rsp_secure,
+        "responder KEY_EX_CAP or PSK_CAP without ENCRYPT_CAP or MAC_CAP",
+    )?;
+    let session = req_session || rsp_session;
+    check(
+        !session || !config_info.aead_algo.is_empty(),
+        "session without aead_algo",
+    )?;
+    check(
+        !session || !config_info.key_schedule_algo.is_empty(),
+        "session without key_schedule_algo",
+    )?;
+    check(
+        !session || !config_info.base_hash_algo.is_empty(),
+        "session without base_hash_algo",
+    )?;
+    let key_ex = rsp_key_ex || req_cap.contains(SpdmRequestCapabilityFlags::KEY_EX_CAP);
+    check(
+        !key_ex || !config_info.dhe_algo.is_empty(),
+        "KEY_EX_CAP without dhe_algo",
+    )?;
+
+    // each slot has its own half session ID, counting down from INITIAL_SESSION_ID
+    check(
+        config_info.max_session_count < INITIAL_SESSION_ID as usize,
+        "max_session_count larger than the half session IDs",
+    )?;
+
+    // transport
+    if config_info.data_transfer_size != 0 || config_info.max_spdm_msg_size != 0 {
+        check(
+            config_info.data_transfer_size >= SPDM_MIN_DATA_TRANSFER_SIZE,
+            "data_transfer_size too small",
+        )?;
+        check(
+            config_info.max_spdm_msg_size >= config_info.data_transfer_size,
+            "max_spdm_msg_size smaller than data_transfer_size",
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct SpdmProvisionInfoBuilder {
+    provision_info: SpdmProvisionInfo,
+    status: SpdmResult,
+}
+
+fn cert_chain_data(data: &[u8]) -> SpdmResult<SpdmCertChainData> {
+    if data.is_empty() || data.len() > config::MAX_SPDM_CERT_CHAIN_DATA_SIZE {
+        return Err(SPDM_STATUS_BUFFER_FULL);
+    }
+    let mut cert_chain_data = SpdmCertChainData {
+        data_size: data.len() as u16,
+        ..Default::default()
+    };
+    cert_chain_data.data[..data.len()].copy_from_slice(data);
+    Ok(cert_chain_data)
+}
+
+impl Default for SpdmProvisionInfoBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpdmProvisionInfoBuilder {
+    pub fn new() -> Self {
+        SpdmProvisionInfoBuilder {
+            provision_info: SpdmProvisionInfo::default(),
+            status: Ok(()),
+        }
+    }
+
+    fn set<T>(mut self, data: SpdmResult<T>, f: impl FnOnce(&mut SpdmProvisionInfo, T)) -> Self {
+        match data {
+            Ok(data) => f(&mut self.provision_info, data),
+            Err(status) => {
+                if self.status.is_ok() {
+                    self.status = Err(status);
+                }
+            }
+        }
+        self
+    }
+
+    /// DER certificates of my chain in slot_id, root first.
+    pub fn my_cert_chain(self, slot_id: u8, cert_chain: &[u8]) -> Self {
+        let data = if slot_id as usize >= SPDM_MAX_SLOT_NUMBER {
+            Err(SPDM_STATUS_INVALID_PARAMETER)
+        } else {
+            cert_chain_data(cert_chain)
+        };
+        self.set(data, |provision_info, data| {
+            provision_info.my_cert_chain_data[slot_id as usize] = Some(data)
+        })
+    }
+
+    /// Use my chain in slot_id only when these algorithms are negotiated.
+    pub fn my_cert_chain_algo(
+        self,
+        slot_id: u8,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        base_hash_algo: SpdmBaseHashAlgo,
+    ) -> Self {
+        let data = if slot_id as usize >= SPDM_MAX_SLOT_NUMBER {
+            Err(SPDM_STATUS_INVALID_PARAMETER)
+        } else {
+            Ok(SpdmCertChainAlgo {
+                base_asym_algo,
+                base_hash_algo,
+            })
+        };
+        self.set(data, |provision_info, data| {
+            provision_info.my_cert_chain_algo[slot_id as usize] = Some(data)
+        })
+    }
+
+    /// DER root certificate the peer chain is verified against.
+    pub fn peer_root_cert(self, root_cert: &[u8]) -> Self {
+        self.set(cert_chain_data(root_cert), |provision_info, data| {
+            provision_info.peer_root_cert_data = Some(data)
+        })
+    }
+
+    /// DER SubjectPublicKeyInfo of my key, for PUB_KEY_ID_CAP.
+    pub fn my_pub_key(self, pub_key: &[u8]) -> Self {
+        self.set(cert_chain_data(pub_key), |provision_info, data| {
+            provision_info.my_pub_key = Some(data)
+        })
+    }
+
+    /// DER SubjectPublicKeyInfo of the peer key, for PUB_KEY_ID_CAP.
+    pub fn peer_pub_key(self, pub_key: &[u8]) -> Self {
+        self.set(cert_chain_data(pub_key), |provision_info, data| {
+            provision_info.peer_pub_key = Some(data)
+        })
+    }
+
+    /// Return the first error of the setters.
+    pub fn build(self) -> SpdmResult<SpdmProvisionInfo> {
+        self.status?;
+        Ok(self.provision_info)
+    }
+
+    /// Also check the provision against the capabilities of my role: a
+    /// responder with CERT_CAP needs a chain in some slot, one with
+    /// PUB_KEY_ID_CAP needs my_pub_key.
+    pub fn build_for(
+        self,
+        config_info: &SpdmConfigInfo,
+        is_requester: bool,
+    ) -> SpdmResult<SpdmProvisionInfo> {
+        let provision_info = self.build()?;
+        let (cert_cap, pub_key_id_cap) = if is_requester {
+            (
+                config_info
+                    .req_capabilities
+                    .contains(SpdmRequestCapabilityFlags::CERT_CAP),
+                config_info
+                    .req_capabilities
+                    .contains(SpdmRequestCapabilityFlags::PUB_KEY_ID_CAP),
+            )
+        } else {
+            (
+                config_info
+                    .rsp_capabilities
+                    .contains(SpdmResponseCapabilityFlags::CERT_CAP),
+                config_info
+                    .rsp_capabilities
+                    .contains(SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP),
+            )
+        };
+        check(
+            !cert_cap
+                || provision_info
+                    .my_cert_chain_data
+                    .iter()
+                    .any(Option::is_some),
+            "CERT_CAP without my_cert_chain_data",
+        )?;
+        check(
+            !pub_key_id_cap || provision_info.my_pub_key.is_some(),
+            "PUB_KEY_ID_CAP without my_pub_key",
+        )?;
+        Ok(provision_info)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    fn responder_builder() -> SpdmConfigInfoBuilder {
+        SpdmConfigInfoBuilder::new()
+            .spdm_versions(&[SpdmVersion::SpdmVersion12])
+            .rsp_capabilities(
+                SpdmResponseCapabilityFlags::CERT_CAP
+                    | SpdmResponseCapabilityFlags::CHAL_CAP
+                    | SpdmResponseCapabilityFlags::MEAS_CAP_SIG
+                    | SpdmResponseCapabilityFlags::ENCRYPT_CAP
+                    | SpdmResponseCapabilityFlags::MAC_CAP
+                    | SpdmResponseCapabilityFlags::KEY_EX_CAP,
+            )
+            .measurement(
+                SpdmMeasurementSpecification::DMTF,
+                SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+            )
+            .base_hash_algo(SpdmBaseHashAlgo::TPM_ALG_SHA_384)
+            .base_asym_algo(SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384)
+            .dhe_algo(SpdmDheAlgo::SECP_384_R1)
+            .aead_algo(SpdmAeadAlgo::AES_256_GCM)
+            .key_schedule_algo(SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE)
+            .transfer_size(0x1200, 0x1200)
+    }
+
+    #[test]
+    fn test_case0_config_info_builder() {
+        let config_info = responder_builder().build().unwrap();
+        assert_eq!(config_info.spdm_version[0], SpdmVersion::SpdmVersion12);
+        assert_eq!(config_info.spdm_version[1], SpdmVersion::default());
+        assert_eq!(config_info.dhe_algo, SpdmDheAlgo::SECP_384_R1);
+
+        assert_eq!(
+            SpdmConfigInfoBuilder::new().build().err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        assert_eq!(
+            responder_builder()
+                .measurement(
+                    SpdmMeasurementSpecification::DMTF,
+                    SpdmMeasurementHashAlgo::empty()
+                )
+                .build()
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        assert_eq!(
+            responder_builder()
+                .dhe_algo(SpdmDheAlgo::empty())
+                .build()
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        assert_eq!(
+            responder_builder()
+                .rsp_capabilities(
+                    SpdmResponseCapabilityFlags::CERT_CAP | SpdmResponseCapabilityFlags::KEY_EX_CAP
+                )
+                .build()
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        assert_eq!(
+            responder_builder()
+                .transfer_size(0x1200, 0x100)
+                .build()
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        assert!(check_config_info(&config_info).is_ok());
+    }
+
+    #[test]
+    fn test_case1_config_info_builder() {
+        let config_info = responder_builder()
+            .raw_bit_stream_measurement_indices(&[1, 9, 0xFE])
+            .build()
+            .unwrap();
+        assert!(config_info.is_raw_bit_stream_measurement_index(1));
+        assert!(config_info.is_raw_bit_stream_measurement_index(9));
+        assert!(config_info.is_raw_bit_stream_measurement_index(0xFE));
+        assert!(!config_info.is_raw_bit_stream_measurement_index(0));
+        assert!(!config_info.is_raw_bit_stream_measurement_index(0xFF));
+    }
+
+    #[test]
+    fn test_case0_provision_info_builder() {
+        let config_info = responder_builder().build().unwrap();
+        let cert_chain = [0x30u8; 16];
+
+        assert_eq!(
+            SpdmProvisionInfoBuilder::new()
+                .build_for(&config_info, false)
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        let provision_info = SpdmProvisionInfoBuilder::new()
+            .my_cert_chain(1, &cert_chain)
+            .peer_root_cert(&cert_chain[..8])
+            .build_for(&config_info, false)
+            .unwrap();
+        assert!(provision_info.my_cert_chain_data[0].is_none());
+        assert_eq!(
+            provision_info.my_cert_chain_data[1]
+                .as_ref()
+                .unwrap()
+                .as_ref(),
+            &cert_chain
+        );
+        assert_eq!(
+            provision_info
+                .peer_root_cert_data
+                .as_ref()
+                .unwrap()
+                .as_ref(),
+            &cert_chain[..8]
+        );
+
+        assert_eq!(
+            SpdmProvisionInfoBuilder::new()
+                .my_cert_chain(SPDM_MAX_SLOT_NUMBER as u8, &cert_chain)
+                .build()
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+
+        let provision_info = SpdmProvisionInfoBuilder::new()
+            .my_cert_chain(0, &cert_chain)
+            .my_cert_chain_algo(
+                0,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+                SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            provision_info.my_cert_chain_algo[0],
+            Some(SpdmCertChainAlgo {
+                base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+                base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+            })
+        );
+        assert!(provision_info.my_cert_chain_algo[1].is_none());
+        assert_eq!(
+            SpdmProvisionInfoBuilder::new()
+                .my_cert_chain_algo(
+                    SPDM_MAX_SLOT_NUMBER as u8,
+                    SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+                    SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+                )
+                .build()
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        assert_eq!(
+            SpdmProvisionInfoBuilder::new()
+                .peer_root_cert(&[])
+                .my_cert_chain(SPDM_MAX_SLOT_NUMBER as u8, &cert_chain)
+                .build()
+                .err(),
+            Some(SPDM_STATUS_BUFFER_FULL)
+        );
+    }
+}
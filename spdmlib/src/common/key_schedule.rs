@@ -6,7 +6,7 @@ use crate::crypto;
 use crate::protocol::*;
 use codec::{Codec, Writer};
 extern crate alloc;
-use crate::secret;
+use crate::secret::{self, SpdmSecretPsk};
 use alloc::boxed::Box;
 
 const MAX_BIN_CONCAT_BUF_SIZE: usize = 2 + 8 + 12 + SPDM_MAX_HASH_SIZE;
@@ -21,12 +21,149 @@ const BIN_STR6_LABEL: &[u8] = b"iv";
 const BIN_STR7_LABEL: &[u8] = b"finished";
 const BIN_STR8_LABEL: &[u8] = b"exp master";
 const BIN_STR9_LABEL: &[u8] = b"traffic upd";
+/// Maximum total size of the label and context of an exported key.
+pub const MAX_SPDM_EXPORTER_LABEL_CONTEXT_SIZE: usize = 64;
+const MAX_EXPORTER_INFO_SIZE: usize = 2 + 2 + MAX_SPDM_EXPORTER_LABEL_CONTEXT_SIZE;
+const MAX_EXPORTER_BIN_CONCAT_BUF_SIZE: usize = 2 + 8 + MAX_EXPORTER_INFO_SIZE;
 const SPDM_VERSION_VALUE: &[u8; 8] = b"spdm .  ";
 const SPDM_VERSION_VALUE_MAJOR_INDEX: usize = 4;
 const SPDM_VERSION_VALUE_MINOR_INDEX: usize = 6;
 
+#[cfg(feature = "key-schedule-trace")]
+pub const MAX_SPDM_KEY_SCHEDULE_TRACE_ENTRIES: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpdmKeyScheduleStep {
+    Extract,
+    Expand,
+}
+
+/// One HKDF step of the key schedule.
+///
+/// Secrets are recorded as hashes only, unless feature debug-keys is enabled.
+#[cfg(feature = "key-schedule-trace")]
+#[derive(Clone, Copy, Debug)]
+pub struct SpdmKeyScheduleTraceEntry {
+    pub step: SpdmKeyScheduleStep,
+    pub label: &'static [u8],
+    /// Expand: the bin_str info.
+    /// Extract: hash of the salt, or the salt itself with debug-keys.
+    pub input_size: u16,
+    pub input: [u8; MAX_BIN_CONCAT_BUF_SIZE],
+    pub output_size: u16,
+    pub output_hash_size: u16,
+    pub output_hash: [u8; SPDM_MAX_HASH_SIZE],
+    #[cfg(feature = "debug-keys")]
+    pub output: [u8; SPDM_MAX_HASH_SIZE],
+}
+
+#[cfg(feature = "key-schedule-trace")]
+impl SpdmKeyScheduleTraceEntry {
+    const EMPTY: SpdmKeyScheduleTraceEntry = SpdmKeyScheduleTraceEntry {
+        step: SpdmKeyScheduleStep::Extract,
+        label: &[],
+        input_size: 0,
+        input: [0u8; MAX_BIN_CONCAT_BUF_SIZE],
+        output_size: 0,
+        output_hash_size: 0,
+        output_hash: [0u8; SPDM_MAX_HASH_SIZE],
+        #[cfg(feature = "debug-keys")]
+        output: [0u8; SPDM_MAX_HASH_SIZE],
+    };
+
+    pub fn get_input(&self) -> &[u8] {
+        &self.input[..self.input_size as usize]
+    }
+
+    pub fn get_output_hash(&self) -> &[u8] {
+        &self.output_hash[..self.output_hash_size as usize]
+    }
+
+    #[cfg(feature = "debug-keys")]
+    pub fn get_output(&self) -> &[u8] {
+        &self.output[..self.output_size as usize]
+    }
+}
+
+/// Key derivation trace of one session, in derivation order.
+/// Steps beyond MAX_SPDM_KEY_SCHEDULE_TRACE_ENTRIES are counted in dropped_count only.
+#[cfg(feature = "key-schedule-trace")]
 #[derive(Clone, Debug)]
-pub struct SpdmKeySchedule;
+pub struct SpdmKeyScheduleTrace {
+    pub entry_count: usize,
+    pub dropped_count: usize,
+    pub entries: [SpdmKeyScheduleTraceEntry; MAX_SPDM_KEY_SCHEDULE_TRACE_ENTRIES],
+}
+
+#[cfg(feature = "key-schedule-trace")]
+impl Default for SpdmKeyScheduleTrace {
+    fn default() -> Self {
+        SpdmKeyScheduleTrace {
+            entry_count: 0,
+            dropped_count: 0,
+            entries: [SpdmKeyScheduleTraceEntry::EMPTY; MAX_SPDM_KEY_SCHEDULE_TRACE_ENTRIES],
+        }
+    }
+}
+
+#[cfg(feature = "key-schedule-trace")]
+impl SpdmKeyScheduleTrace {
+    pub fn get_entries(&self) -> &[SpdmKeyScheduleTraceEntry] {
+        &self.entries[..self.entry_count]
+    }
+
+    fn push(
+        &mut self,
+        step: SpdmKeyScheduleStep,
+        hash_algo: SpdmBaseHashAlgo,
+        label: &'static [u8],
+        input: &[u8],
+        output: &[u8],
+    ) {
+        if self.entry_count >= MAX_SPDM_KEY_SCHEDULE_TRACE_ENTRIES {
+            self.dropped_count += 1;
+            return;
+        }
+
+        let mut entry = SpdmKeyScheduleTraceEntry {
+            step,
+            label,
+            output_size: output.len() as u16,
+            ..SpdmKeyScheduleTraceEntry::EMPTY
+        };
+
+        let input_hash;
+        let input = if step == SpdmKeyScheduleStep::Extract && !cfg!(feature = "debug-keys") {
+            input_hash = crypto::hash::hash_all(hash_algo, input);
+            input_hash.as_ref().map_or(&[][..], |h| h.as_ref())
+        } else {
+            input
+        };
+        let input_size = input.len().min(MAX_BIN_CONCAT_BUF_SIZE);
+        entry.input[..input_size].copy_from_slice(&input[..input_size]);
+        entry.input_size = input_size as u16;
+
+        if let Some(output_hash) = crypto::hash::hash_all(hash_algo, output) {
+            entry.output_hash[..output_hash.data_size as usize]
+                .copy_from_slice(output_hash.as_ref());
+            entry.output_hash_size = output_hash.data_size;
+        }
+        #[cfg(feature = "debug-keys")]
+        {
+            let output_size = output.len().min(SPDM_MAX_HASH_SIZE);
+            entry.output[..output_size].copy_from_slice(&output[..output_size]);
+        }
+
+        self.entries[self.entry_count] = entry;
+        self.entry_count += 1;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpdmKeySchedule {
+    #[cfg(feature = "key-schedule-trace")]
+    trace: SpdmKeyScheduleTrace,
+}
 
 impl Default for SpdmKeySchedule {
     fn default() -> Self {
@@ -36,11 +173,42 @@ impl Default for SpdmKeySchedule {
 
 impl SpdmKeySchedule {
     pub fn new() -> Self {
-        SpdmKeySchedule {}
+        SpdmKeySchedule {
+            #[cfg(feature = "key-schedule-trace")]
+            trace: SpdmKeyScheduleTrace::default(),
+        }
+    }
+
+    #[cfg(feature = "key-schedule-trace")]
+    pub fn get_trace(&self) -> &SpdmKeyScheduleTrace {
+        &self.trace
+    }
+
+    #[cfg(feature = "key-schedule-trace")]
+    fn trace_step(
+        &mut self,
+        step: SpdmKeyScheduleStep,
+        hash_algo: SpdmBaseHashAlgo,
+        label: &'static [u8],
+        input: &[u8],
+        output: &[u8],
+    ) {
+        self.trace.push(step, hash_algo, label, input, output);
+    }
+
+    #[cfg(not(feature = "key-schedule-trace"))]
+    fn trace_step(
+        &mut self,
+        _step: SpdmKeyScheduleStep,
+        _hash_algo: SpdmBaseHashAlgo,
+        _label: &'static [u8],
+        _input: &[u8],
+        _output: &[u8],
+    ) {
     }
 
     pub fn derive_handshake_secret(
-        &self,
+        &mut self,
         _spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: &SpdmDheFinalKeyStruct,
@@ -50,11 +218,18 @@ impl SpdmKeySchedule {
             &SALT_0[0..hash_algo.get_size() as usize],
             &SpdmHkdfInputKeyingMaterial::SpdmDheFinalKey(key),
         )?;
+        self.trace_step(
+            SpdmKeyScheduleStep::Extract,
+            hash_algo,
+            b"",
+            &SALT_0[0..hash_algo.get_size() as usize],
+            prk.as_ref(),
+        );
         SpdmHandshakeSecretStruct::from_spdm_hkdf_prk(prk)
     }
 
     pub fn derive_master_secret(
-        &self,
+        &mut self,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: &SpdmHandshakeSecretStruct,
@@ -76,6 +251,13 @@ impl SpdmKeySchedule {
             hash_algo.get_size(),
         )?;
         debug!("salt_1 - {:02x?}", salt_1.as_ref());
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR0_LABEL,
+            bin_str0,
+            salt_1.as_ref(),
+        );
 
         let prk = crypto::hkdf::hkdf_extract(
             hash_algo,
@@ -85,17 +267,26 @@ impl SpdmKeySchedule {
                 data: Box::new([0u8; SPDM_MAX_HASH_SIZE]),
             }),
         )?;
+        self.trace_step(
+            SpdmKeyScheduleStep::Extract,
+            hash_algo,
+            b"",
+            salt_1.as_ref(),
+            prk.as_ref(),
+        );
 
         SpdmMasterSecretStruct::from_spdm_hkdf_prk(prk)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn derive_request_handshake_secret(
-        &self,
+        &mut self,
         use_psk: bool,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: Option<&SpdmHandshakeSecretStruct>,
         psk_hint: Option<&SpdmPskHintStruct>,
+        secret_psk: Option<&SpdmSecretPsk>,
         th1: &[u8],
     ) -> Option<SpdmDirectionHandshakeSecretStruct> {
         let buffer = &mut [0; MAX_BIN_CONCAT_BUF_SIZE];
@@ -120,24 +311,34 @@ impl SpdmKeySchedule {
                 return None;
             }
         } else {
-            secret::psk::handshake_secret_hkdf_expand(
+            secret::psk::handshake_secret_hkdf_expand_with(
+                secret_psk,
                 spdm_version,
                 hash_algo,
                 psk_hint.unwrap(),
                 bin_str1,
             )?
         };
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR1_LABEL,
+            bin_str1,
+            okm.as_ref(),
+        );
 
         SpdmDirectionHandshakeSecretStruct::from_spdm_hkdf_okm(okm)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn derive_response_handshake_secret(
-        &self,
+        &mut self,
         use_psk: bool,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: Option<&SpdmHandshakeSecretStruct>,
         psk_hint: Option<&SpdmPskHintStruct>,
+        secret_psk: Option<&SpdmSecretPsk>,
         th1: &[u8],
     ) -> Option<SpdmDirectionHandshakeSecretStruct> {
         let buffer = &mut [0; MAX_BIN_CONCAT_BUF_SIZE];
@@ -162,19 +363,27 @@ impl SpdmKeySchedule {
                 return None;
             }
         } else {
-            secret::psk::handshake_secret_hkdf_expand(
+            secret::psk::handshake_secret_hkdf_expand_with(
+                secret_psk,
                 spdm_version,
                 hash_algo,
                 psk_hint.unwrap(),
                 bin_str2,
             )?
         };
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR2_LABEL,
+            bin_str2,
+            okm.as_ref(),
+        );
 
         SpdmDirectionHandshakeSecretStruct::from_spdm_hkdf_okm(okm)
     }
 
     pub fn derive_finished_key(
-        &self,
+        &mut self,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: &SpdmDirectionHandshakeSecretStruct,
@@ -195,12 +404,19 @@ impl SpdmKeySchedule {
             bin_str7,
             hash_algo.get_size(),
         )?;
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR7_LABEL,
+            bin_str7,
+            okm.as_ref(),
+        );
 
         SpdmFinishedKeyStruct::from_spdm_hkdf_okm(okm)
     }
 
     pub fn derive_aead_key_iv(
-        &self,
+        &mut self,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         aead_algo: SpdmAeadAlgo,
@@ -232,6 +448,13 @@ impl SpdmKeySchedule {
                 SPDM_MAX_AEAD_KEY_SIZE as u16,
             )?,
         };
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR5_LABEL,
+            bin_str5,
+            okm.as_ref(),
+        );
         let encrypt_key = SpdmAeadKeyStruct::from_spdm_hkdf_okm(okm)?;
 
         let bin_str6 = self.binconcat(
@@ -259,18 +482,27 @@ impl SpdmKeySchedule {
                 SPDM_MAX_AEAD_IV_SIZE as u16,
             )?,
         };
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR6_LABEL,
+            bin_str6,
+            okm.as_ref(),
+        );
         let iv = SpdmAeadIvStruct::from_spdm_hkdf_okm(okm)?;
 
         Some((encrypt_key, iv))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn derive_request_data_secret(
-        &self,
+        &mut self,
         use_psk: bool,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: Option<&SpdmMasterSecretStruct>,
         psk_hint: Option<&SpdmPskHintStruct>,
+        secret_psk: Option<&SpdmSecretPsk>,
         th2: &[u8],
     ) -> Option<SpdmDirectionDataSecretStruct> {
         let buffer = &mut [0; MAX_BIN_CONCAT_BUF_SIZE];
@@ -295,24 +527,34 @@ impl SpdmKeySchedule {
                 return None;
             }
         } else {
-            secret::psk::master_secret_hkdf_expand(
+            secret::psk::master_secret_hkdf_expand_with(
+                secret_psk,
                 spdm_version,
                 hash_algo,
                 psk_hint.unwrap(),
                 bin_str3,
             )?
         };
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR3_LABEL,
+            bin_str3,
+            okm.as_ref(),
+        );
 
         SpdmDirectionDataSecretStruct::from_spdm_hkdf_okm(okm)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn derive_response_data_secret(
-        &self,
+        &mut self,
         use_psk: bool,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: Option<&SpdmMasterSecretStruct>,
         psk_hint: Option<&SpdmPskHintStruct>,
+        secret_psk: Option<&SpdmSecretPsk>,
         th2: &[u8],
     ) -> Option<SpdmDirectionDataSecretStruct> {
         let buffer = &mut [0; MAX_BIN_CONCAT_BUF_SIZE];
@@ -337,24 +579,33 @@ impl SpdmKeySchedule {
                 return None;
             }
         } else {
-            secret::psk::master_secret_hkdf_expand(
+            secret::psk::master_secret_hkdf_expand_with(
+                secret_psk,
                 spdm_version,
                 hash_algo,
                 psk_hint.unwrap(),
                 bin_str4,
             )?
         };
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR4_LABEL,
+            bin_str4,
+            okm.as_ref(),
+        );
 
         SpdmDirectionDataSecretStruct::from_spdm_hkdf_okm(okm)
     }
 
     pub fn derive_export_master_secret(
-        &self,
+        &mut self,
         use_psk: bool,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
         key: Option<&SpdmMasterSecretStruct>,
         psk_hint: Option<&SpdmPskHintStruct>,
+        secret_psk: Option<&SpdmSecretPsk>,
     ) -> Option<SpdmExportMasterSecretStruct> {
         let buffer = &mut [0; MAX_BIN_CONCAT_BUF_SIZE];
         let bin_str8 = self.binconcat(
@@ -378,21 +629,64 @@ impl SpdmKeySchedule {
                 return None;
             }
         } else {
-            secret::psk::master_secret_hkdf_expand(
+            secret::psk::master_secret_hkdf_expand_with(
+                secret_psk,
                 spdm_version,
                 hash_algo,
                 psk_hint.unwrap(),
                 bin_str8,
             )?
         };
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR8_LABEL,
+            bin_str8,
+            okm.as_ref(),
+        );
 
         SpdmExportMasterSecretStruct::from_spdm_hkdf_okm(okm)
     }
 
-    pub fn derive_update_secret(
+    /// Derive an application key from the export master secret with
+    /// HKDF-Expand(export_master_secret, bin_concat(out_size, version,
+    /// label_len || label || context_len || context)).
+    ///
+    /// The lengths (u16 each) keep label and context apart, so that e.g.
+    /// ("ab", "c") and ("a", "bc") derive different keys.
+    pub fn derive_exported_key(
         &self,
         spdm_version: SpdmVersion,
         hash_algo: SpdmBaseHashAlgo,
+        key: &SpdmExportMasterSecretStruct,
+        label: &[u8],
+        context: &[u8],
+        out_size: u16,
+    ) -> Option<SpdmHkdfOutputKeyingMaterial> {
+        let info = &mut [0; MAX_EXPORTER_INFO_SIZE];
+        let mut writer = Writer::init(info);
+        (label.len() as u16).encode(&mut writer).ok()?;
+        writer.extend_from_slice(label)?;
+        (context.len() as u16).encode(&mut writer).ok()?;
+        writer.extend_from_slice(context)?;
+        let info_len = writer.used();
+
+        let buffer = &mut [0; MAX_EXPORTER_BIN_CONCAT_BUF_SIZE];
+        let bin_str = self.binconcat(out_size, spdm_version, &info[..info_len], None, buffer)?;
+        crypto::hkdf::hkdf_expand(
+            hash_algo,
+            &SpdmHkdfPseudoRandomKey::from_input_keying_material(
+                &SpdmHkdfInputKeyingMaterial::SpdmExportMasterSecret(key),
+            )?,
+            bin_str,
+            out_size,
+        )
+    }
+
+    pub fn derive_update_secret(
+        &mut self,
+        spdm_version: SpdmVersion,
+        hash_algo: SpdmBaseHashAlgo,
         key: &SpdmDirectionDataSecretStruct,
     ) -> Option<SpdmDirectionDataSecretStruct> {
         let buffer = &mut [0; MAX_BIN_CONCAT_BUF_SIZE];
@@ -411,6 +705,13 @@ impl SpdmKeySchedule {
             bin_str9,
             hash_algo.get_size(),
         )?;
+        self.trace_step(
+            SpdmKeyScheduleStep::Expand,
+            hash_algo,
+            BIN_STR9_LABEL,
+            bin_str9,
+            okm.as_ref(),
+        );
 
         SpdmDirectionDataSecretStruct::from_spdm_hkdf_okm(okm)
     }
@@ -448,3 +749,190 @@ impl SpdmKeySchedule {
         Some(&buffer[0..len])
     }
 }
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    // Vectors for SPDM 1.2, SHA-384, AES-256-GCM.
+    // DHE secret is 0x5a * 48, TH1 is 0x11 * 48, TH2 is 0x22 * 48.
+    const HANDSHAKE_SECRET: [u8; 48] = [
+        0xc3, 0x15, 0x17, 0xb2, 0x11, 0x98, 0x73, 0xde, 0x43, 0x09, 0x1f, 0x95, 0xa2, 0xb0, 0x40,
+        0x5d, 0x8f, 0x6a, 0x62, 0x07, 0x60, 0xf1, 0x7e, 0x22, 0xed, 0x79, 0x6e, 0x74, 0xa2, 0x9d,
+        0xfb, 0x0d, 0x76, 0x50, 0x27, 0x57, 0x67, 0xb3, 0x58, 0xe4, 0x46, 0x5d, 0x54, 0x57, 0xe4,
+        0x92, 0x96, 0x9c,
+    ];
+    const MASTER_SECRET: [u8; 48] = [
+        0xb0, 0x8e, 0x8d, 0xb3, 0x98, 0x9f, 0x67, 0x7c, 0x03, 0x68, 0x75, 0xe0, 0xa6, 0xc5, 0xad,
+        0x95, 0xc6, 0x73, 0x55, 0xfa, 0x8d, 0x47, 0x05, 0x90, 0x85, 0x6f, 0xcf, 0x49, 0x74, 0x91,
+        0x25, 0x79, 0x3c, 0x9b, 0xd3, 0x4b, 0xf3, 0xb4, 0x68, 0xc5, 0xd8, 0x7c, 0xfe, 0x7a, 0x69,
+        0xd4, 0x96, 0x0a,
+    ];
+    const REQUEST_HANDSHAKE_SECRET: [u8; 48] = [
+        0xf8, 0xa7, 0xc9, 0x16, 0x5a, 0x71, 0x69, 0x6c, 0x91, 0x24, 0x31, 0x0c, 0xde, 0x5b, 0x54,
+        0x5c, 0xef, 0xde, 0x22, 0xd7, 0x1d, 0x9c, 0x25, 0xbc, 0xfc, 0x1d, 0x23, 0x12, 0x2d, 0x60,
+        0x01, 0x54, 0xae, 0x2b, 0xfe, 0x3d, 0xf4, 0xbe, 0x49, 0xf2, 0x19, 0x72, 0x7d, 0xa6, 0xd8,
+        0x49, 0x40, 0x51,
+    ];
+    const REQUEST_FINISHED_KEY: [u8; 48] = [
+        0x0f, 0xbf, 0x9b, 0x96, 0x4c, 0x96, 0x43, 0x98, 0xd2, 0x49, 0xaa, 0xf8, 0x28, 0xcc, 0x9c,
+        0xf0, 0x1d, 0xa6, 0xd5, 0x95, 0x8d, 0x7c, 0x23, 0xc3, 0xe2, 0x54, 0x0f, 0xa3, 0x38, 0x63,
+        0x50, 0x10, 0x2d, 0x9f, 0xcf, 0x0a, 0x4d, 0x7b, 0x58, 0xe0, 0x43, 0xa1, 0x25, 0x41, 0xf0,
+        0x0d, 0x48, 0xa6,
+    ];
+    const REQUEST_HANDSHAKE_KEY: [u8; 32] = [
+        0x6b, 0xf5, 0xea, 0xe0, 0xcd, 0xf8, 0x34, 0x8d, 0x1c, 0x76, 0x39, 0xcd, 0x69, 0xa2, 0x99,
+        0xa2, 0x2b, 0x05, 0xd4, 0xf4, 0xae, 0xcf, 0x31, 0xbc, 0x78, 0x5d, 0x42, 0xfb, 0x0c, 0xee,
+        0x2a, 0x0f,
+    ];
+    const REQUEST_HANDSHAKE_IV: [u8; 12] = [
+        0x62, 0x0a, 0x0a, 0x6f, 0xbe, 0x6e, 0x5c, 0xb1, 0x1f, 0xd6, 0xa1, 0x89,
+    ];
+    const REQUEST_DATA_SECRET: [u8; 48] = [
+        0xba, 0xcb, 0x64, 0xb2, 0x78, 0x48, 0x1c, 0xa9, 0x4a, 0xb0, 0x22, 0x7a, 0x2d, 0x1e, 0x06,
+        0x04, 0xc8, 0xad, 0x17, 0xf4, 0x65, 0x93, 0x12, 0xc7, 0x5c, 0x0a, 0x64, 0x90, 0x9d, 0x62,
+        0xaa, 0x38, 0xba, 0x4a, 0x78, 0x52, 0x27, 0x25, 0x10, 0x14, 0xf5, 0x3b, 0xdb, 0x86, 0xd9,
+        0xfe, 0x6c, 0xce,
+    ];
+    const UPDATED_REQUEST_DATA_SECRET: [u8; 48] = [
+        0xab, 0xfb, 0x6d, 0xbc, 0x57, 0x40, 0x4e, 0x70, 0xc3, 0x83, 0x17, 0x74, 0x29, 0x4e, 0x88,
+        0xca, 0x7d, 0xfa, 0xc7, 0x1c, 0x22, 0x37, 0x95, 0x85, 0x7f, 0x91, 0x9d, 0x20, 0x33, 0xb0,
+        0x53, 0xf2, 0x12, 0x6a, 0x0d, 0x5e, 0x54, 0xc7, 0x9e, 0xc7, 0x89, 0xec, 0xb3, 0x68, 0x67,
+        0xb9, 0x4e, 0x09,
+    ];
+
+    #[test]
+    fn test_case0_binconcat() {
+        let key_schedule = SpdmKeySchedule::new();
+        let buffer = &mut [0u8; MAX_BIN_CONCAT_BUF_SIZE];
+        let bin_str = key_schedule
+            .binconcat(
+                48,
+                SpdmVersion::SpdmVersion12,
+                BIN_STR1_LABEL,
+                Some(&[0xAAu8; 2]),
+                buffer,
+            )
+            .unwrap();
+        assert_eq!(bin_str, b"\x30\x00spdm1.2 req hs data\xaa\xaa");
+
+        let buffer = &mut [0u8; MAX_BIN_CONCAT_BUF_SIZE];
+        let bin_str = key_schedule
+            .binconcat(12, SpdmVersion::SpdmVersion11, BIN_STR6_LABEL, None, buffer)
+            .unwrap();
+        assert_eq!(bin_str, b"\x0c\x00spdm1.1 iv");
+    }
+
+    #[test]
+    fn test_case0_key_schedule_vectors() {
+        let version = SpdmVersion::SpdmVersion12;
+        let hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        let aead_algo = SpdmAeadAlgo::AES_256_GCM;
+        let mut key_schedule = SpdmKeySchedule::new();
+
+        let dhe_secret = SpdmDheFinalKeyStruct::from(&[0x5au8; 48][..]);
+        let handshake_secret = key_schedule
+            .derive_handshake_secret(version, hash_algo, &dhe_secret)
+            .unwrap();
+        assert_eq!(handshake_secret.as_ref(), &HANDSHAKE_SECRET[..]);
+
+        let master_secret = key_schedule
+            .derive_master_secret(version, hash_algo, &handshake_secret)
+            .unwrap();
+        assert_eq!(master_secret.as_ref(), &MASTER_SECRET[..]);
+
+        let request_handshake_secret = key_schedule
+            .derive_request_handshake_secret(
+                false,
+                version,
+                hash_algo,
+                Some(&handshake_secret),
+                None,
+                None,
+                &[0x11u8; 48],
+            )
+            .unwrap();
+        assert_eq!(
+            request_handshake_secret.as_ref(),
+            &REQUEST_HANDSHAKE_SECRET[..]
+        );
+
+        let request_finished_key = key_schedule
+            .derive_finished_key(version, hash_algo, &request_handshake_secret)
+            .unwrap();
+        assert_eq!(request_finished_key.as_ref(), &REQUEST_FINISHED_KEY[..]);
+
+        let (key, iv) = key_schedule
+            .derive_aead_key_iv(
+                version,
+                hash_algo,
+                aead_algo,
+                &SpdmMajorSecret::SpdmDirectionHandshakeSecret(&request_handshake_secret),
+            )
+            .unwrap();
+        assert_eq!(key.as_ref(), &REQUEST_HANDSHAKE_KEY[..]);
+        assert_eq!(iv.as_ref(), &REQUEST_HANDSHAKE_IV[..]);
+
+        let request_data_secret = key_schedule
+            .derive_request_data_secret(
+                false,
+                version,
+                hash_algo,
+                Some(&master_secret),
+                None,
+                None,
+                &[0x22u8; 48],
+            )
+            .unwrap();
+        assert_eq!(request_data_secret.as_ref(), &REQUEST_DATA_SECRET[..]);
+
+        let updated_request_data_secret = key_schedule
+            .derive_update_secret(version, hash_algo, &request_data_secret)
+            .unwrap();
+        assert_eq!(
+            updated_request_data_secret.as_ref(),
+            &UPDATED_REQUEST_DATA_SECRET[..]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "key-schedule-trace")]
+    fn test_case0_key_schedule_trace() {
+        let version = SpdmVersion::SpdmVersion12;
+        let hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        let mut key_schedule = SpdmKeySchedule::new();
+
+        let dhe_secret = SpdmDheFinalKeyStruct::from(&[0x5au8; 48][..]);
+        let handshake_secret = key_schedule
+            .derive_handshake_secret(version, hash_algo, &dhe_secret)
+            .unwrap();
+        let _ = key_schedule
+            .derive_master_secret(version, hash_algo, &handshake_secret)
+            .unwrap();
+        let _ = key_schedule
+            .derive_request_handshake_secret(
+                false,
+                version,
+                hash_algo,
+                Some(&handshake_secret),
+                None,
+                None,
+                &[0x11u8; 48],
+            )
+            .unwrap();
+
+        let entries = key_schedule.get_trace().get_entries();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].step, SpdmKeyScheduleStep::Extract);
+        assert_eq!(entries[1].step, SpdmKeyScheduleStep::Expand);
+        assert_eq!(entries[1].label, BIN_STR0_LABEL);
+        assert_eq!(entries[1].get_input(), b"\x30\x00spdm1.2 derived");
+        assert_eq!(entries[2].step, SpdmKeyScheduleStep::Extract);
+        assert_eq!(entries[3].label, BIN_STR1_LABEL);
+        assert_eq!(entries[3].get_input().len(), 2 + 8 + 11 + 48);
+        assert_eq!(entries[3].output_size, 48);
+
+        let output_hash = crypto::hash::hash_all(hash_algo, &REQUEST_HANDSHAKE_SECRET[..]).unwrap();
+        assert_eq!(entries[3].get_output_hash(), output_hash.as_ref());
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::provision_store::SpdmProvisionStore;
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_PARAMETER,
+    SPDM_STATUS_INVALID_STATE_LOCAL,
+};
+use codec::{Codec, Reader, Writer};
+
+pub const MAX_SPDM_MEASUREMENT_COMPONENT_COUNT: usize = 16;
+pub const MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE: usize = 32;
+/// Highest index assigned to a component.
+/// 0xF0..=0xFC are reserved, 0xFD and 0xFE are the manifest and the device mode.
+pub const SPDM_MEASUREMENT_COMPONENT_INDEX_MAX: u8 = 0xEF;
+/// Size of the encoded map written to SpdmProvisionStore.
+pub const MAX_SPDM_MEASUREMENT_INDEX_MAP_SIZE: usize =
+    2 + MAX_SPDM_MEASUREMENT_COMPONENT_COUNT * (2 + MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpdmMeasurementComponent {
+    pub index: u8,
+    name_size: u8,
+    name: [u8; MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE],
+}
+
+impl SpdmMeasurementComponent {
+    pub fn get_name(&self) -> &[u8] {
+        &self.name[..self.name_size as usize]
+    }
+}
+
+impl Codec for SpdmMeasurementComponent {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.index.encode(bytes)?;
+        cnt += self.name_size.encode(bytes)?;
+        cnt += bytes
+            .extend_from_slice(self.get_name())
+            .ok_or(codec::EncodeErr)?;
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmMeasurementComponent> {
+        let index = u8::read(r)?;
+        if index == 0 || index > SPDM_MEASUREMENT_COMPONENT_INDEX_MAX {
+            return None;
+        }
+        let name_size = u8::read(r)?;
+        if name_size == 0 || name_size as usize > MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE {
+            return None;
+        }
+        let mut name = [0u8; MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE];
+        name[..name_size as usize].copy_from_slice(r.take(name_size as usize)?);
+        Some(SpdmMeasurementComponent {
+            index,
+            name_size,
+            name,
+        })
+    }
+}
+
+/// Stable assignment of SPDM measurement indices to named measurement sources.
+///
+/// A component keeps its index while it is registered, and an index is never
+/// handed out twice: a removed component frees its slot for a new component,
+/// which gets an index above all the ones used before, so verifiers comparing
+/// measurements by index are not confused when firmware components come and go.
+/// The map is persisted with SpdmProvisionStore across reboots and updates.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmMeasurementIndexMap {
+    // highest index ever assigned
+    last_index: u8,
+    components: [Option<SpdmMeasurementComponent>; MAX_SPDM_MEASUREMENT_COMPONENT_COUNT],
+}
+
+impl SpdmMeasurementIndexMap {
+    fn find(&self, name: &[u8]) -> Option<usize> {
+        self.components
+            .iter()
+            .position(|component| match component {
+                Some(component) => component.get_name() == name,
+                None => false,
+            })
+    }
+
+    /// Index of name, assigning a new one if the component is not registered.
+    pub fn assign(&mut self, name: &[u8]) -> SpdmResult<u8> {
+        if name.is_empty() || name.len() > MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        if let Some(pos) = self.find(name) {
+            let component = self.components[pos]
+                .as_ref()
+                .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+            return Ok(component.index);
+        }
+
+        let index = self
+            .last_index
+            .checked_add(1)
+            .filter(|index| *index <= SPDM_MEASUREMENT_COMPONENT_INDEX_MAX)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        let slot = self
+            .components
+            .iter_mut()
+            .find(|component| component.is_none())
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        let mut component = SpdmMeasurementComponent {
+            index,
+            name_size: name.len() as u8,
+            name: [0u8; MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE],
+        };
+        component.name[..name.len()].copy_from_slice(name);
+        *slot = Some(component);
+        self.last_index = index;
+        Ok(index)
+    }
+
+    /// Unregister name and free its slot, its index is not reused.
+    pub fn remove(&mut self, name: &[u8]) {
+        if let Some(pos) = self.find(name) {
+            self.components[pos] = None;
+        }
+    }
+
+    /// Assign indices to the current components and remove the others.
+    pub fn update(&mut self, names: &[&[u8]]) -> SpdmResult {
+        for component in self.components.iter_mut() {
+            if let Some(registered) = component {
+                if !names.iter().any(|name| *name == registered.get_name()) {
+                    *component = None;
+                }
+            }
+        }
+        for name in names {
+            self.assign(name)?;
+        }
+        Ok(())
+    }
+
+    /// Index of a registered component.
+    pub fn get_index(&self, name: &[u8]) -> Option<u8> {
+        let component = self.components[self.find(name)?].as_ref()?;
+        Some(component.index)
+    }
+
+    /// Component measured at index, for measurement_collection_cb.
+    pub fn get_component(&self, index: u8) -> Option<&SpdmMeasurementComponent> {
+        self.components
+            .iter()
+            .flatten()
+            .find(|component| component.index == index)
+    }
+
+    /// Registered components in index order.
+    pub fn iter_present(&self) -> impl Iterator<Item = &SpdmMeasurementComponent> {
+        let mut components = [None; MAX_SPDM_MEASUREMENT_COMPONENT_COUNT];
+        for (i, component) in self.components.iter().flatten().enumerate() {
+            components[i] = Some(component);
+        }
+        components.sort_unstable_by_key(|component| match component {
+            Some(component) => component.index,
+            None => u8::MAX,
+        });
+        IntoIterator::into_iter(components).flatten()
+    }
+
+    /// Number of registered components, i.e. the number of measurement blocks.
+    pub fn present_count(&self) -> usize {
+        self.components.iter().flatten().count()
+    }
+
+    pub fn load_from(store: &mut dyn SpdmProvisionStore) -> SpdmResult<SpdmMeasurementIndexMap> {
+        let buffer = &mut [0u8; MAX_SPDM_MEASUREMENT_INDEX_MAP_SIZE];
+        let used = store.load_measurement_index_map(buffer)?;
+        if used == 0 {
+            return Ok(SpdmMeasurementIndexMap::default());
+        }
+        let mut reader = Reader::init(&buffer[..used]);
+        SpdmMeasurementIndexMap::read(&mut reader).ok_or(SPDM_STATUS_INVALID_PARAMETER)
+    }
+
+    pub fn store_to(&self, store: &mut dyn SpdmProvisionStore) -> SpdmResult {
+        let buffer = &mut [0u8; MAX_SPDM_MEASUREMENT_INDEX_MAP_SIZE];
+        let mut writer = Writer::init(buffer);
+        let used = self
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        store.store_measurement_index_map(&buffer[..used])
+    }
+}
+
+impl Codec for SpdmMeasurementIndexMap {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.last_index.encode(bytes)?;
+        cnt += (self.components.iter().flatten().count() as u8).encode(bytes)?;
+        for component in self.components.iter().flatten() {
+            cnt += component.encode(bytes)?;
+        }
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmMeasurementIndexMap> {
+        let last_index = u8::read(r)?;
+        if last_index > SPDM_MEASUREMENT_COMPONENT_INDEX_MAX {
+            return None;
+        }
+        let count = u8::read(r)? as usize;
+        if count > MAX_SPDM_MEASUREMENT_COMPONENT_COUNT {
+            return None;
+        }
+        let mut map = SpdmMeasurementIndexMap {
+            last_index,
+            ..Default::default()
+        };
+        for i in 0..count {
+            let component = SpdmMeasurementComponent::read(r)?;
+            if component.index > last_index
+                || map.find(component.get_name()).is_some()
+                || map
+                    .components
+                    .iter()
+                    .flatten()
+                    .any(|c| c.index == component.index)
+            {
+                return None;
+            }
+            map.components[i] = Some(component);
+        }
+        Some(map)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::common::provision_store::SpdmMemoryProvisionStore;
+
+    #[test]
+    fn test_case0_measurement_index_map() {
+        let mut map = SpdmMeasurementIndexMap::default();
+        let components: [&[u8]; 3] = [b"bootloader", b"firmware", b"config"];
+        assert!(map.update(&components).is_ok());
+        assert_eq!(map.get_index(b"bootloader"), Some(1));
+        assert_eq!(map.get_index(b"firmware"), Some(2));
+        assert_eq!(map.get_index(b"config"), Some(3));
+        assert_eq!(map.present_count(), 3);
+
+        // firmware is removed, a new component does not take its index
+        let components: [&[u8]; 3] = [b"bootloader", b"config", b"option rom"];
+        assert!(map.update(&components).is_ok());
+        assert_eq!(map.get_index(b"firmware"), None);
+        assert!(map.get_component(2).is_none());
+        assert_eq!(map.get_index(b"config"), Some(3));
+        assert_eq!(map.get_index(b"option rom"), Some(4));
+        assert_eq!(map.get_component(4).unwrap().get_name(), b"option rom");
+        let indices: [u8; 3] = {
+            let mut indices = [0u8; 3];
+            for (i, component) in map.iter_present().enumerate() {
+                indices[i] = component.index;
+            }
+            indices
+        };
+        assert_eq!(indices, [1, 3, 4]);
+
+        // firmware comes back with a new index
+        assert_eq!(map.assign(b"firmware"), Ok(5));
+        assert_eq!(map.present_count(), 4);
+
+        assert_eq!(map.assign(b""), Err(SPDM_STATUS_INVALID_PARAMETER));
+        assert_eq!(
+            map.assign(&[b'a'; MAX_SPDM_MEASUREMENT_COMPONENT_NAME_SIZE + 1]),
+            Err(SPDM_STATUS_INVALID_PARAMETER)
+        );
+    }
+
+    #[test]
+    fn test_case0_measurement_index_map_full() {
+        let mut map = SpdmMeasurementIndexMap::default();
+        for i in 0..MAX_SPDM_MEASUREMENT_COMPONENT_COUNT {
+            assert_eq!(map.assign(&[b'a' + i as u8]), Ok(i as u8 + 1));
+        }
+        assert_eq!(map.assign(b"new"), Err(SPDM_STATUS_BUFFER_FULL));
+        // the slot is freed, the index is not reused
+        map.remove(b"a");
+        assert_eq!(map.get_index(b"a"), None);
+        assert_eq!(
+            map.assign(b"new"),
+            Ok(MAX_SPDM_MEASUREMENT_COMPONENT_COUNT as u8 + 1)
+        );
+        assert!(map.get_component(1).is_none());
+
+        let mut map = SpdmMeasurementIndexMap {
+            last_index: SPDM_MEASUREMENT_COMPONENT_INDEX_MAX,
+            ..Default::default()
+        };
+        assert_eq!(map.assign(b"new"), Err(SPDM_STATUS_BUFFER_FULL));
+    }
+
+    #[test]
+    fn test_case0_measurement_index_map_store() {
+        let mut store = SpdmMemoryProvisionStore::new(&[]).unwrap();
+        let map = SpdmMeasurementIndexMap::load_from(&mut store).unwrap();
+        assert_eq!(map.present_count(), 0);
+
+        let mut map = SpdmMeasurementIndexMap::default();
+        let components: [&[u8]; 2] = [b"bootloader", b"firmware"];
+        assert!(map.update(&components).is_ok());
+        assert!(map.update(&components[1..]).is_ok());
+        assert!(map.store_to(&mut store).is_ok());
+
+        let mut map = SpdmMeasurementIndexMap::load_from(&mut store).unwrap();
+        assert_eq!(map.get_index(b"bootloader"), None);
+        assert_eq!(map.get_index(b"firmware"), Some(2));
+        assert_eq!(map.assign(b"config"), Ok(3));
+        assert_eq!(map.assign(b"bootloader"), Ok(4));
+
+        // duplicated index
+        assert!(store
+            .store_measurement_index_map(&[1, 2, 1, 1, b'a', 1, 1, b'b'])
+            .is_ok());
+        assert!(SpdmMeasurementIndexMap::load_from(&mut store).is_err());
+        // index above the last assigned one
+        assert!(store
+            .store_measurement_index_map(&[1, 1, 2, 1, b'a'])
+            .is_ok());
+        assert!(SpdmMeasurementIndexMap::load_from(&mut store).is_err());
+    }
+}
@@ -2,28 +2,59 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
+pub mod attestation_report;
+pub mod audit_log;
+pub mod buffer;
+pub mod cancel;
+pub mod capture;
+pub mod cert_chain_stream;
+pub mod cert_policy;
+pub mod config_builder;
 pub mod key_schedule;
+pub mod measurement_index_map;
 pub mod opaque;
+pub mod opaque_provider;
+pub mod provision_store;
 pub mod session;
 pub mod spdm_codec;
+pub mod timing;
+pub mod transcript;
+pub mod transport_registry;
 
-use crate::message::SpdmRequestResponseCode;
+use crate::message::{
+    SpdmKeyPairInfo, SpdmRequestResponseCode, SpdmVendorDefinedError, SPDM_MAX_KEY_PAIR_NUMBER,
+};
+use crate::secret::SpdmSecretProvider;
 use crate::{crypto, protocol::*};
 
+pub use buffer::{SpdmBufferConfig, SpdmBufferPool, SpdmBufferProvider, SpdmPoolBuffer};
+pub use cancel::SpdmCancelToken;
+pub use capture::{SpdmMessageCapture, SpdmMessageDirection};
+#[cfg(feature = "hashed-transcript-data")]
+pub use cert_chain_stream::SpdmCertChainStreamVerifier;
+pub use cert_chain_stream::SpdmStreamedCertChain;
+pub use cert_policy::{SpdmBasicCertPolicy, SpdmCertPolicy};
+pub use config_builder::{SpdmConfigInfoBuilder, SpdmProvisionInfoBuilder};
 pub use opaque::*;
+pub use provision_store::SpdmProvisionStore;
 pub use spdm_codec::SpdmCodec;
+pub use transcript::SpdmTranscript;
 
 use crate::config::{self, MAX_SPDM_SESSION_COUNT};
 use crate::error::{
-    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_DECAP_FAIL,
-    SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_INVALID_STATE_LOCAL,
-    SPDM_STATUS_SESSION_NUMBER_EXCEED,
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CANCELLED, SPDM_STATUS_CRYPTO_ERROR,
+    SPDM_STATUS_DECAP_FAIL, SPDM_STATUS_ENCAP_FAIL, SPDM_STATUS_INVALID_PARAMETER,
+    SPDM_STATUS_INVALID_STATE_LOCAL, SPDM_STATUS_SESSION_NUMBER_EXCEED,
 };
 
 use codec::enum_builder;
 use codec::{Codec, Reader, Writer};
 use session::*;
 
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
 enum_builder! {
     @U8
     EnumName: SpdmConnectionState;
@@ -72,13 +103,86 @@ pub const INITIAL_SESSION_ID: u16 = 0xFFFD;
 pub const INVALID_HALF_SESSION_ID: u16 = 0x0;
 pub const INVALID_SESSION_ID: u32 = 0x0;
 
+/// Transport level event reported by SpdmDeviceIo::poll_event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmDeviceIoEvent {
+    None,
+    /// The peer is gone, e.g. surprise removal of the device or a closed socket.
+    PeerDisconnected,
+    /// The peer has a message to deliver without a request, e.g. PCI DOE
+    /// async message status is set by the device.
+    AsyncMessagePending,
+}
+
 pub trait SpdmDeviceIo {
     fn send(&mut self, buffer: &[u8]) -> SpdmResult;
 
+    /// Return Err(size), with size larger than buffer, if the received message
+    /// does not fit in buffer. The message is dropped.
     fn receive(&mut self, buffer: &mut [u8], timeout: usize) -> Result<usize, usize>;
 
     fn flush_all(&mut self) -> SpdmResult;
 
+    /// Report a transport event, if the transport is able to detect it.
+    fn poll_event(&mut self) -> SpdmDeviceIoEvent {
+        SpdmDeviceIoEvent::None
+    }
+
+    /// Return true if a message is ready to be received, else wake waker once
+    /// one is. The async-io streams are Pending until then. The default
+    /// reports ready always, so that receive() waits for the message.
+    fn poll_receive_ready(&mut self, _waker: &core::task::Waker) -> bool {
+        true
+    }
+
+    /// Send a message which is not a response to a request. The transport
+    /// signals the peer that a message is pending, e.g. PCI DOE async message.
+    fn send_async(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.send(buffer)
+    }
+
+    /// Send the concatenation of buffers as one message, e.g. transport header
+    /// and SPDM message placed in different DMA descriptors. The default
+    /// implementation gathers them into a contiguous buffer.
+    fn send_vectored(&mut self, buffers: &[&[u8]]) -> SpdmResult {
+        let size = buffers.iter().map(|buffer| buffer.len()).sum();
+        let mut gathered = Vec::with_capacity(size);
+        for buffer in buffers {
+            gathered.extend_from_slice(buffer);
+        }
+        self.send(&gathered)
+    }
+
+    /// Receive one message scattered over buffers in order, return the total
+    /// size. Err(size) as receive() if the message does not fit in all of them.
+    /// The default implementation receives into a contiguous buffer.
+    fn receive_vectored(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        timeout: usize,
+    ) -> Result<usize, usize> {
+        let size = buffers.iter().map(|buffer| buffer.len()).sum();
+        let mut contiguous = alloc::vec![0u8; size];
+        let used = self.receive(&mut contiguous, timeout)?;
+        let mut offset = 0;
+        for buffer in buffers.iter_mut() {
+            if offset >= used {
+                break;
+            }
+            let len = buffer.len().min(used - offset);
+            buffer[..len].copy_from_slice(&contiguous[offset..(offset + len)]);
+            offset += len;
+        }
+        Ok(used)
+    }
+
+    /// Whether send_vectored and receive_vectored place the buffers without
+    /// gathering them. The library then sends and receives the transport
+    /// header and the message in buffers of their own.
+    fn is_vectored(&self) -> bool {
+        false
+    }
+
     #[cfg(feature = "downcast")]
     fn as_any(&mut self) -> &mut dyn Any;
 }
@@ -115,6 +219,31 @@ pub trait SpdmTransportEncap {
     fn decap_app(&mut self, app_buffer: &[u8], spdm_buffer: &mut [u8])
         -> SpdmResult<(usize, bool)>;
 
+    /// Size of the transport header in front of the SPDM message.
+    fn get_header_size(&mut self, _secured_message: bool) -> usize {
+        0
+    }
+
+    /// Encapsulate the spdm_size bytes message which is already placed right after
+    /// get_header_size() bytes of transport_buffer, without copying the message.
+    /// The default implementation falls back to encap() through a temporary buffer.
+    fn encap_in_place(
+        &mut self,
+        transport_buffer: &mut [u8],
+        spdm_size: usize,
+        secured_message: bool,
+    ) -> SpdmResult<usize> {
+        let header_size = self.get_header_size(secured_message);
+        if spdm_size > config::MAX_SPDM_MSG_SIZE || transport_buffer.len() < header_size + spdm_size
+        {
+            return Err(SPDM_STATUS_ENCAP_FAIL);
+        }
+        let mut spdm_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        spdm_buffer[..spdm_size]
+            .copy_from_slice(&transport_buffer[header_size..(header_size + spdm_size)]);
+        self.encap(&spdm_buffer[..spdm_size], transport_buffer, secured_message)
+    }
+
     // for session
     fn get_sequence_number_count(&mut self) -> u8;
     fn get_max_random_count(&mut self) -> u16;
@@ -126,6 +255,18 @@ impl Debug for dyn SpdmTransportEncap {
     }
 }
 
+/// A session taken out of its slot, see SpdmContext::evict_session.
+pub struct SpdmEvictedSession {
+    index: usize,
+    session: Box<SpdmSession>,
+}
+
+impl SpdmEvictedSession {
+    pub fn get_session_id(&self) -> u32 {
+        self.session.get_session_id()
+    }
+}
+
 pub struct SpdmContext<'a> {
     pub device_io: &'a mut dyn SpdmDeviceIo,
     pub transport_encap: &'a mut dyn SpdmTransportEncap,
@@ -140,7 +281,26 @@ pub struct SpdmContext<'a> {
     #[cfg(feature = "mut-auth")]
     pub encap_context: SpdmEncapContext,
 
-    pub session: [SpdmSession; config::MAX_SPDM_SESSION_COUNT],
+    // one slot per session, see get_session_capacity
+    pub session: Vec<SpdmSession>,
+
+    // secret callbacks of this context, see SpdmSecretProvider
+    pub secret_provider: SpdmSecretProvider,
+    // None falls back to the registered opaque provider
+    pub opaque_provider: Option<opaque_provider::SpdmOpaqueProvider>,
+
+    // runtime buffer sizes, see set_buffer_config
+    pub buffer_config: SpdmBufferConfig,
+    pub buffer_provider: Box<dyn SpdmBufferProvider>,
+    pub buffer_pool: SpdmBufferPool,
+    pub cancel_token: Option<SpdmCancelToken>,
+    // incremented on each session message, see mark_session_used
+    pub session_use_counter: u64,
+    pub message_capture: Option<Box<dyn SpdmMessageCapture>>,
+    // checks on top of verify_cert_chain, see verify_peer_cert_chain
+    pub cert_policy: Option<Box<dyn SpdmCertPolicy>>,
+    // provisioned data is written back to it after each update, see store_cert_chain
+    pub provision_store: Option<Box<dyn SpdmProvisionStore>>,
 }
 
 impl<'a> SpdmContext<'a> {
@@ -150,6 +310,10 @@ impl<'a> SpdmContext<'a> {
         config_info: SpdmConfigInfo,
         provision_info: SpdmProvisionInfo,
     ) -> Self {
+        let session_count = match config_info.max_session_count {
+            0 => MAX_SPDM_SESSION_COUNT,
+            count => count,
+        };
         SpdmContext {
             device_io,
             transport_encap,
@@ -160,8 +324,133 @@ impl<'a> SpdmContext<'a> {
             peer_info: SpdmPeerInfo::default(),
             #[cfg(feature = "mut-auth")]
             encap_context: SpdmEncapContext::default(),
-            session: gen_array(config::MAX_SPDM_SESSION_COUNT),
+            session: (0..session_count).map(|_| SpdmSession::default()).collect(),
+            secret_provider: SpdmSecretProvider::default(),
+            opaque_provider: None,
+            buffer_config: SpdmBufferConfig::default(),
+            buffer_provider: buffer::default_buffer_provider(),
+            buffer_pool: SpdmBufferPool::default(),
+            cancel_token: None,
+            session_use_counter: 0,
+            message_capture: None,
+            cert_policy: None,
+            provision_store: None,
+        }
+    }
+
+    /// Send a transport message through device_io, with the transport header
+    /// in a buffer of its own if device_io is vectored.
+    pub fn device_io_send(
+        &mut self,
+        transport_message: &[u8],
+        secured_message: bool,
+    ) -> SpdmResult {
+        if !self.device_io.is_vectored() {
+            return self.device_io.send(transport_message);
+        }
+        let header_size = self
+            .transport_encap
+            .get_header_size(secured_message)
+            .min(transport_message.len());
+        let (header, message) = transport_message.split_at(header_size);
+        self.device_io.send_vectored(&[header, message])
+    }
+
+    /// Receive a transport message through device_io, with the transport
+    /// header in a buffer of its own if device_io is vectored.
+    pub fn device_io_receive(&mut self, buffer: &mut [u8], timeout: usize) -> Result<usize, usize> {
+        if !self.device_io.is_vectored() {
+            return self.device_io.receive(buffer, timeout);
+        }
+        let header_size = self
+            .transport_encap
+            .get_header_size(false)
+            .min(buffer.len());
+        let (header, message) = buffer.split_at_mut(header_size);
+        self.device_io
+            .receive_vectored(&mut [header, message], timeout)
+    }
+
+    /// Use smaller buffers than the config ceilings. Also caps the
+    /// max_spdm_msg_size reported in GET_CAPABILITIES/CAPABILITIES.
+    pub fn set_buffer_config(&mut self, buffer_config: SpdmBufferConfig) -> SpdmResult {
+        buffer_config.validate()?;
+        if self.config_info.max_spdm_msg_size as usize > buffer_config.max_spdm_msg_size {
+            self.config_info.max_spdm_msg_size = buffer_config.max_spdm_msg_size as u32;
+        }
+        if self.config_info.data_transfer_size > self.config_info.max_spdm_msg_size {
+            self.config_info.data_transfer_size = self.config_info.max_spdm_msg_size;
         }
+        self.buffer_config = buffer_config;
+        self.buffer_pool.clear();
+        Ok(())
+    }
+
+    pub fn set_buffer_provider(&mut self, buffer_provider: Box<dyn SpdmBufferProvider>) {
+        self.buffer_provider = buffer_provider;
+        self.buffer_pool.clear();
+    }
+
+    pub fn set_cancel_token(&mut self, cancel_token: Option<SpdmCancelToken>) {
+        self.cancel_token = cancel_token;
+    }
+
+    pub fn set_message_capture(&mut self, message_capture: Option<Box<dyn SpdmMessageCapture>>) {
+        self.message_capture = message_capture;
+    }
+
+    pub fn set_cert_policy(&mut self, cert_policy: Option<Box<dyn SpdmCertPolicy>>) {
+        self.cert_policy = cert_policy;
+    }
+
+    pub fn set_provision_store(&mut self, provision_store: Option<Box<dyn SpdmProvisionStore>>) {
+        self.provision_store = provision_store;
+    }
+
+    pub fn capture_message(
+        &mut self,
+        direction: SpdmMessageDirection,
+        session_id: Option<u32>,
+        message: &[u8],
+    ) {
+        if let Some(message_capture) = self.message_capture.as_mut() {
+            message_capture.capture(direction, session_id, message);
+        }
+    }
+
+    /// Return SPDM_STATUS_CANCELLED, after tearing down the connection, if
+    /// the cancel token is cancelled.
+    pub fn check_cancelled(&mut self) -> SpdmResult {
+        match &self.cancel_token {
+            Some(cancel_token) if cancel_token.is_cancelled() => {
+                info!("cancelled, tear down connection\n");
+                self.handle_peer_disconnect();
+                Err(SPDM_STATUS_CANCELLED)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Borrow a buffer from the pool, give it back with free_buffer. A buffer
+    /// dropped instead, e.g. on an error path, is still zeroized.
+    pub fn alloc_buffer(&mut self, size: usize) -> SpdmPoolBuffer {
+        self.buffer_pool.alloc(self.buffer_provider.as_ref(), size)
+    }
+
+    pub fn alloc_sender_buffer(&mut self) -> SpdmPoolBuffer {
+        self.alloc_buffer(self.buffer_config.sender_buffer_size)
+    }
+
+    pub fn alloc_receiver_buffer(&mut self) -> SpdmPoolBuffer {
+        self.alloc_buffer(self.buffer_config.receiver_buffer_size)
+    }
+
+    pub fn alloc_message_buffer(&mut self) -> SpdmPoolBuffer {
+        self.alloc_buffer(self.buffer_config.max_spdm_msg_size)
+    }
+
+    pub fn free_buffer(&mut self, buffer: SpdmPoolBuffer) {
+        self.buffer_pool.free(buffer);
     }
 
     pub fn get_hash_size(&self) -> u16 {
@@ -196,6 +485,17 @@ impl<'a> SpdmContext<'a> {
         }
     }
 
+    /// Drop all sessions and connection state tied to the peer, without END_SESSION.
+    pub fn handle_peer_disconnect(&mut self) {
+        info!("peer disconnected, tear down connection\n");
+        // session key material is zeroized when the session is reset
+        self.reset_context();
+        #[cfg(feature = "mut-auth")]
+        {
+            self.encap_context = SpdmEncapContext::default();
+        }
+    }
+
     pub fn get_immutable_session_via_id(&self, session_id: u32) -> Option<&SpdmSession> {
         self.session
             .iter()
@@ -208,28 +508,113 @@ impl<'a> SpdmContext<'a> {
             .find(|session| session.get_session_id() == session_id)
     }
 
-    pub fn get_next_avaiable_session(&mut self) -> Option<&mut SpdmSession> {
-        self.get_session_via_id(0)
+    /// True if both sides selected HANDSHAKE_IN_THE_CLEAR_CAP and the session is still
+    /// handshaking, so its handshake messages are sent without secured message encoding.
+    pub fn is_session_handshake_in_clear(&self, session_id: u32) -> bool {
+        self.negotiate_info
+            .req_capabilities_sel
+            .contains(SpdmRequestCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP)
+            && self
+                .negotiate_info
+                .rsp_capabilities_sel
+                .contains(SpdmResponseCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP)
+            && self
+                .get_immutable_session_via_id(session_id)
+                .map(|session| {
+                    session.get_session_state() == SpdmSessionState::SpdmSessionHandshaking
+                })
+                .unwrap_or(false)
+    }
+
+    /// Number of session slots, config_info.max_session_count when the context
+    /// is created, or config::MAX_SPDM_SESSION_COUNT if it is 0.
+    pub fn get_session_capacity(&self) -> usize {
+        self.session.len()
     }
 
-    pub fn get_session_status(&self) -> [(u32, SpdmSessionState); config::MAX_SPDM_SESSION_COUNT] {
-        let mut status =
-            [(0u32, SpdmSessionState::SpdmSessionNotStarted); config::MAX_SPDM_SESSION_COUNT];
-        for (i, it) in status
+    pub fn get_next_avaiable_session(&mut self) -> Option<&mut SpdmSession> {
+        self.session_use_counter += 1;
+        let last_used = self.session_use_counter;
+        let session = self
+            .session
             .iter_mut()
-            .enumerate()
-            .take(config::MAX_SPDM_SESSION_COUNT)
+            .find(|session| session.get_session_id() == INVALID_SESSION_ID)?;
+        session.set_last_used(last_used);
+        Some(session)
+    }
+
+    /// Record a message of the session, for SpdmSessionEvictionPolicy::EvictLeastRecentlyUsedHandshaking.
+    pub fn mark_session_used(&mut self, session_id: u32) {
+        self.session_use_counter += 1;
+        let last_used = self.session_use_counter;
+        if let Some(session) = self.get_session_via_id(session_id) {
+            session.set_last_used(last_used);
+        }
+    }
+
+    /// Take a session out of its slot according to config_info.session_eviction_policy
+    /// if all of them are in use, so that a new session can be set up in the slot.
+    /// Only a session still handshaking is evicted, never an established one.
+    /// The evicted session is only terminated by complete_session_eviction.
+    pub fn evict_session(&mut self) -> Option<SpdmEvictedSession> {
+        let sessions = &self.session[..];
+        if sessions
+            .iter()
+            .any(|session| session.get_session_id() == INVALID_SESSION_ID)
+        {
+            return None;
+        }
+        let evictable = |session: &SpdmSession| {
+            session.get_session_state() != SpdmSessionState::SpdmSessionEstablished
+        };
+        let index = match self.config_info.session_eviction_policy {
+            SpdmSessionEvictionPolicy::RejectNew => None,
+            SpdmSessionEvictionPolicy::EvictLeastRecentlyUsedHandshaking => sessions
+                .iter()
+                .enumerate()
+                .filter(|(_, session)| evictable(session))
+                .min_by_key(|(_, session)| session.get_last_used())
+                .map(|(index, _)| index),
+            SpdmSessionEvictionPolicy::Custom(select) => select(sessions),
+        }
+        .filter(|index| *index < sessions.len() && evictable(&sessions[*index]))?;
+
+        let session = Box::new(core::mem::take(&mut self.session[index]));
+        info!(
+            "session slots full, evict session {:x?}\n",
+            session.get_session_id()
+        );
+        Some(SpdmEvictedSession { index, session })
+    }
+
+    /// Terminate the evicted session if a new session is set up in its slot,
+    /// return its id. Otherwise the evicted session is put back.
+    pub fn complete_session_eviction(&mut self, evicted: SpdmEvictedSession) -> Option<u32> {
+        let slot = &mut self.session[evicted.index];
+        if slot.get_session_id() != INVALID_SESSION_ID
+            && slot.get_session_state() != SpdmSessionState::SpdmSessionNotStarted
         {
-            it.0 = self.session[i].get_session_id();
-            it.1 = self.session[i].get_session_state();
+            return Some(evicted.session.get_session_id());
         }
-        status
+        info!(
+            "no new session, keep session {:x?}\n",
+            evicted.session.get_session_id()
+        );
+        *slot = *evicted.session;
+        None
+    }
+
+    pub fn get_session_status(&self) -> Vec<(u32, SpdmSessionState)> {
+        self.session
+            .iter()
+            .map(|session| (session.get_session_id(), session.get_session_state()))
+            .collect()
     }
 
     pub fn get_next_half_session_id(&self, is_requester: bool) -> SpdmResult<u16> {
         let shift = if is_requester { 0 } else { 16 };
 
-        for (index, s) in self.session.iter().enumerate().take(MAX_SPDM_SESSION_COUNT) {
+        for (index, s) in self.session.iter().enumerate() {
             if ((s.get_session_id() & (0xFFFF << shift)) >> shift) as u16 == INVALID_HALF_SESSION_ID
             {
                 return Ok(INITIAL_SESSION_ID - index as u16);
@@ -239,8 +624,35 @@ impl<'a> SpdmContext<'a> {
         Err(SPDM_STATUS_SESSION_NUMBER_EXCEED)
     }
 
+    /// True if my cert chain in slot_id is usable with the negotiated
+    /// algorithms, base_asym_sel or req_asym_sel for a requester.
+    pub fn is_my_cert_chain_algo_negotiated(&self, slot_id: u8, is_requester: bool) -> bool {
+        match self.provision_info.my_cert_chain_algo.get(slot_id as usize) {
+            Some(Some(cert_chain_algo)) => {
+                let asym_sel = if is_requester {
+                    self.negotiate_info.req_asym_sel.bits() as u32
+                } else {
+                    self.negotiate_info.base_asym_sel.bits()
+                };
+                cert_chain_algo.base_hash_algo == self.negotiate_info.base_hash_sel
+                    && cert_chain_algo.base_asym_algo.bits() == asym_sel
+            }
+            _ => true,
+        }
+    }
+
     pub fn construct_my_cert_chain(&mut self) -> SpdmResult {
+        self.construct_my_cert_chain_as(false)
+    }
+
+    /// Build my cert chains usable with the negotiated algorithms, the
+    /// others are left out until the next negotiation.
+    pub fn construct_my_cert_chain_as(&mut self, is_requester: bool) -> SpdmResult {
         for slot_id in 0..SPDM_MAX_SLOT_NUMBER {
+            if !self.is_my_cert_chain_algo_negotiated(slot_id as u8, is_requester) {
+                self.provision_info.my_cert_chain[slot_id] = None;
+                continue;
+            }
             if self.provision_info.my_cert_chain[slot_id].is_none()
                 && self.provision_info.my_cert_chain_data[slot_id].is_some()
             {
@@ -260,8 +672,7 @@ impl<'a> SpdmContext<'a> {
                     let data_size = 4 + root_hash.data_size + cert_chain.data_size;
                     let mut data =
                         [0u8; 4 + SPDM_MAX_HASH_SIZE + config::MAX_SPDM_CERT_CHAIN_DATA_SIZE];
-                    data[0] = (data_size & 0xFF) as u8;
-                    data[1] = (data_size >> 8) as u8;
+                    codec::put_u16(data_size, &mut data[0..2]);
                     data[4..(4 + root_hash.data_size as usize)]
                         .copy_from_slice(&root_hash.data[..(root_hash.data_size as usize)]);
                     data[(4 + root_hash.data_size as usize)..(data_size as usize)]
@@ -283,10 +694,20 @@ impl<'a> SpdmContext<'a> {
             .message_a
             .append_message(new_message)
             .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        // only the audit log export depends on the boundaries
+        if self
+            .runtime_info
+            .vca_boundary
+            .record(self.runtime_info.message_a.as_ref().len())
+            .is_none()
+        {
+            debug!("VCA message boundary not recorded, no negotiation audit log\n");
+        }
         Ok(())
     }
     pub fn reset_message_a(&mut self) {
         self.runtime_info.message_a.reset_message();
+        self.runtime_info.vca_boundary.reset();
     }
 
     pub fn append_message_b(&mut self, new_message: &[u8]) -> SpdmResult {
@@ -584,31 +1005,22 @@ impl<'a> SpdmContext<'a> {
                 && !session.get_mut_auth_requested().is_empty()
             {
                 if is_requester {
-                    let slot_id = self.runtime_info.get_local_used_cert_chain_slot_id();
-                    if let Some(cert_chain) = &self.provision_info.my_cert_chain[slot_id as usize] {
-                        Some(
-                            crypto::hash::hash_all(
-                                self.negotiate_info.base_hash_sel,
-                                &cert_chain.data[..cert_chain.data_size as usize],
-                            )
-                            .ok_or(SPDM_STATUS_CRYPTO_ERROR)?,
+                    let cert_chain_data = self
+                        .get_local_cert_chain_data(
+                            self.runtime_info.get_local_used_cert_chain_slot_id(),
                         )
-                    } else {
-                        return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
-                    }
-                } else {
-                    let slot_id = self.runtime_info.get_peer_used_cert_chain_slot_id();
-                    if let Some(cert_chain) = &self.peer_info.peer_cert_chain[slot_id as usize] {
-                        Some(
-                            crypto::hash::hash_all(
-                                self.negotiate_info.base_hash_sel,
-                                cert_chain.as_ref(),
-                            )
+                        .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+                    Some(
+                        crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data)
                             .ok_or(SPDM_STATUS_CRYPTO_ERROR)?,
+                    )
+                } else {
+                    Some(
+                        self.get_peer_cert_chain_hash(
+                            self.runtime_info.get_peer_used_cert_chain_slot_id(),
                         )
-                    } else {
-                        return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
-                    }
+                        .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?,
+                    )
                 }
             } else {
                 None
@@ -663,18 +1075,10 @@ impl<'a> SpdmContext<'a> {
         debug!("message_a - {:02x?}", self.runtime_info.message_a.as_ref());
 
         if !use_psk {
-            if self.peer_info.peer_cert_chain[slot_id as usize].is_none() {
+            let cert_chain_data = self.get_peer_cert_chain_data(slot_id).ok_or_else(|| {
                 error!("peer_cert_chain is not populated!\n");
-                return Err(SPDM_STATUS_INVALID_PARAMETER);
-            }
-
-            let cert_chain_data = &self.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data[..(self.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
             let cert_chain_hash =
                 crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data)
                     .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
@@ -690,18 +1094,10 @@ impl<'a> SpdmContext<'a> {
 
         if !use_psk && is_mut_auth {
             let slot_id = self.runtime_info.get_local_used_cert_chain_slot_id();
-            if self.provision_info.my_cert_chain[slot_id as usize].is_none() {
+            let cert_chain_data = self.get_local_cert_chain_data(slot_id).ok_or_else(|| {
                 error!("mut cert_chain is not populated!\n");
-                return Err(SPDM_STATUS_INVALID_PARAMETER);
-            }
-
-            let cert_chain_data = &self.provision_info.my_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data[..(self.provision_info.my_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
             let cert_chain_hash =
                 crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data)
                     .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
@@ -736,15 +1132,10 @@ impl<'a> SpdmContext<'a> {
             .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         debug!("message_a - {:02x?}", self.runtime_info.message_a.as_ref());
         if !use_psk {
-            if self.provision_info.my_cert_chain[slot_id as usize].is_none() {
+            let cert_chain_data = self.get_local_cert_chain_data(slot_id).ok_or_else(|| {
                 error!("my_cert_chain is not populated!\n");
-                return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
-            }
-
-            let my_cert_chain_data = self.provision_info.my_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
-            let cert_chain_data = my_cert_chain_data.as_ref();
+                SPDM_STATUS_INVALID_STATE_LOCAL
+            })?;
             let cert_chain_hash =
                 crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data)
                     .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
@@ -761,15 +1152,10 @@ impl<'a> SpdmContext<'a> {
 
         if !use_psk && is_mut_auth {
             let slot_id = self.runtime_info.get_peer_used_cert_chain_slot_id();
-            if self.peer_info.peer_cert_chain[slot_id as usize].is_none() {
+            let cert_chain_data = self.get_peer_cert_chain_data(slot_id).ok_or_else(|| {
                 error!("peer_cert_chain is not populated!\n");
-                return Err(SPDM_STATUS_INVALID_PARAMETER);
-            }
-
-            let cert_chain_data = &self.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .as_ref();
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
             let cert_chain_hash =
                 crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data)
                     .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
@@ -873,21 +1259,12 @@ impl<'a> SpdmContext<'a> {
         slot_id: usize,
     ) -> Option<SpdmDigestStruct> {
         if !use_psk {
-            if self.provision_info.my_cert_chain[slot_id].is_none() {
+            let cert_chain_data = self.get_local_cert_chain_data(slot_id as u8);
+            if cert_chain_data.is_none() {
                 error!("my_cert_chain is not populated!\n");
                 return None;
             }
-
-            let my_cert_chain_data = self.provision_info.my_cert_chain[slot_id].as_ref()?;
-            let cert_chain_data = my_cert_chain_data.as_ref();
-            let cert_chain_hash =
-                crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data)
-                    .ok_or(None::<SpdmDigestStruct>);
-            if let Ok(hash) = cert_chain_hash {
-                Some(SpdmDigestStruct::from(hash.as_ref()))
-            } else {
-                None
-            }
+            crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data?)
         } else {
             None
         }
@@ -899,25 +1276,151 @@ impl<'a> SpdmContext<'a> {
         slot_id: usize,
     ) -> Option<SpdmDigestStruct> {
         if !use_psk {
-            if self.peer_info.peer_cert_chain[slot_id].is_none() {
+            let cert_chain_hash = self.get_peer_cert_chain_hash(slot_id as u8);
+            if cert_chain_hash.is_none() {
                 error!("peer_cert_chain is not populated!\n");
-                return None;
             }
+            cert_chain_hash
+        } else {
+            None
+        }
+    }
 
-            let cert_chain_data = &self.peer_info.peer_cert_chain[slot_id].as_ref()?.data
-                [..(self.peer_info.peer_cert_chain[slot_id].as_ref()?.data_size as usize)];
-            let cert_chain_hash =
-                crypto::hash::hash_all(self.negotiate_info.base_hash_sel, cert_chain_data)
-                    .ok_or(None::<SpdmDigestStruct>);
+    /// Local cert chain in slot_id as hashed into transcripts, or the
+    /// provisioned public key for SPDM_PUB_KEY_SLOT_ID.
+    pub fn get_local_cert_chain_data(&self, slot_id: u8) -> Option<&[u8]> {
+        if slot_id == SPDM_PUB_KEY_SLOT_ID {
+            return self
+                .provision_info
+                .my_pub_key
+                .as_ref()
+                .map(|key| key.as_ref());
+        }
+        self.provision_info
+            .my_cert_chain
+            .get(slot_id as usize)?
+            .as_ref()
+            .map(|cert_chain| cert_chain.as_ref())
+    }
+
+    /// Peer cert chain in slot_id as hashed into transcripts, or the
+    /// provisioned public key for SPDM_PUB_KEY_SLOT_ID.
+    pub fn get_peer_cert_chain_data(&self, slot_id: u8) -> Option<&[u8]> {
+        if slot_id == SPDM_PUB_KEY_SLOT_ID {
+            return self
+                .provision_info
+                .peer_pub_key
+                .as_ref()
+                .map(|key| key.as_ref());
+        }
+        self.peer_info
+            .peer_cert_chain
+            .get(slot_id as usize)?
+            .as_ref()
+            .map(|cert_chain| cert_chain.as_ref())
+    }
+
+    /// Hash of the peer cert chain in slot_id as in the transcripts, also for
+    /// a chain verified in portions.
+    pub fn get_peer_cert_chain_hash(&self, slot_id: u8) -> Option<SpdmDigestStruct> {
+        if let Some(Some(streamed)) = self
+            .peer_info
+            .peer_cert_chain_streamed
+            .get(slot_id as usize)
+        {
+            return Some(streamed.cert_chain_hash.clone());
+        }
+        crypto::hash::hash_all(
+            self.negotiate_info.base_hash_sel,
+            self.get_peer_cert_chain_data(slot_id)?,
+        )
+    }
 
-            if let Ok(hash) = cert_chain_hash {
-                Some(SpdmDigestStruct::from(hash.as_ref()))
-            } else {
-                None
-            }
+    /// DER certificates of the local cert chain in slot_id, without the SPDM
+    /// cert chain header and root hash, or the provisioned public key for
+    /// SPDM_PUB_KEY_SLOT_ID.
+    pub fn get_local_public_cert_der(&self, slot_id: u8) -> Option<&[u8]> {
+        let cert_chain_data = self.get_local_cert_chain_data(slot_id)?;
+        if slot_id == SPDM_PUB_KEY_SLOT_ID {
+            return Some(cert_chain_data);
+        }
+        cert_chain_data.get((4usize + self.negotiate_info.base_hash_sel.get_size() as usize)..)
+    }
+
+    /// DER certificates of the peer cert chain in slot_id, without the SPDM
+    /// cert chain header and root hash, or the provisioned public key for
+    /// SPDM_PUB_KEY_SLOT_ID. Only the leaf certificate is left of a chain
+    /// verified in portions.
+    pub fn get_peer_public_cert_der(&self, slot_id: u8) -> Option<&[u8]> {
+        if let Some(Some(streamed)) = self
+            .peer_info
+            .peer_cert_chain_streamed
+            .get(slot_id as usize)
+        {
+            return Some(&streamed.leaf_cert);
+        }
+        let cert_chain_data = self.get_peer_cert_chain_data(slot_id)?;
+        if slot_id == SPDM_PUB_KEY_SLOT_ID {
+            return Some(cert_chain_data);
+        }
+        cert_chain_data.get((4usize + self.negotiate_info.base_hash_sel.get_size() as usize)..)
+    }
+
+    /// Verify a signature with the leaf certificate of public_cert_der, or
+    /// with the public key itself for SPDM_PUB_KEY_SLOT_ID.
+    pub fn verify_asym_signature(
+        &self,
+        slot_id: u8,
+        public_cert_der: &[u8],
+        data: &[u8],
+        signature: &SpdmSignatureStruct,
+    ) -> SpdmResult {
+        if slot_id == SPDM_PUB_KEY_SLOT_ID {
+            crypto::pub_key_verify::verify(
+                self.negotiate_info.base_hash_sel,
+                self.negotiate_info.base_asym_sel,
+                public_cert_der,
+                data,
+                signature,
+            )
         } else {
-            None
+            crypto::asym_verify::verify(
+                self.negotiate_info.base_hash_sel,
+                self.negotiate_info.base_asym_sel,
+                public_cert_der,
+                data,
+                signature,
+            )
+        }
+    }
+
+    /// Whether slot_id may select a responder key: a certificate slot, or
+    /// SPDM_PUB_KEY_SLOT_ID if the responder negotiated PUB_KEY_ID_CAP.
+    pub fn is_rsp_slot_id_valid(&self, slot_id: u8) -> bool {
+        if slot_id == SPDM_PUB_KEY_SLOT_ID {
+            self.negotiate_info
+                .rsp_capabilities_sel
+                .contains(SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP)
+        } else {
+            (slot_id as usize) < SPDM_MAX_SLOT_NUMBER
+        }
+    }
+
+    /// Whether the connection is a SPDM 1.3 multi-key connection, in which
+    /// DIGESTS reports the key pair, certificate info and key usage of each
+    /// slot. MULTI_KEY_CAP_NEG needs the requester to support it as well.
+    pub fn is_multi_key_conn(&self) -> bool {
+        if self.negotiate_info.spdm_version_sel.get_u8() < SpdmVersion::SpdmVersion13.get_u8() {
+            return false;
         }
+        let rsp_capabilities = self.negotiate_info.rsp_capabilities_sel;
+        let req_capabilities = self.negotiate_info.req_capabilities_sel;
+        rsp_capabilities.contains(SpdmResponseCapabilityFlags::MULTI_KEY_CAP_ONLY)
+            || (rsp_capabilities.contains(SpdmResponseCapabilityFlags::MULTI_KEY_CAP_NEG)
+                && req_capabilities.intersects(
+                    SpdmRequestCapabilityFlags::MULTI_KEY_CAP_ONLY
+                        | SpdmRequestCapabilityFlags::MULTI_KEY_CAP_NEG,
+                ))
     }
 
     pub fn reset_buffer_via_request_code(
@@ -952,10 +1455,24 @@ impl<'a> SpdmContext<'a> {
     }
 
     pub fn encap(&mut self, send_buffer: &[u8], transport_buffer: &mut [u8]) -> SpdmResult<usize> {
+        self.capture_message(SpdmMessageDirection::Sent, None, send_buffer);
         self.transport_encap
             .encap(send_buffer, transport_buffer, false)
     }
 
+    pub fn encap_in_place(
+        &mut self,
+        transport_buffer: &mut [u8],
+        spdm_size: usize,
+    ) -> SpdmResult<usize> {
+        let header_size = self.transport_encap.get_header_size(false);
+        if let Some(message) = transport_buffer.get(header_size..(header_size + spdm_size)) {
+            self.capture_message(SpdmMessageDirection::Sent, None, message);
+        }
+        self.transport_encap
+            .encap_in_place(transport_buffer, spdm_size, false)
+    }
+
     pub fn encode_secured_message(
         &mut self,
         session_id: u32,
@@ -964,24 +1481,72 @@ impl<'a> SpdmContext<'a> {
         is_requester: bool,
         is_app_message: bool,
     ) -> SpdmResult<usize> {
-        let mut app_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        if !is_app_message {
+            self.capture_message(SpdmMessageDirection::Sent, Some(session_id), send_buffer);
+        }
+        let mut app_buffer = self.alloc_sender_buffer();
         let used = self
             .transport_encap
             .encap_app(send_buffer, &mut app_buffer, is_app_message)?;
 
+        let mut encoded_send_buffer = self.alloc_sender_buffer();
         let spdm_session = self
             .get_session_via_id(session_id)
             .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
 
-        let mut encoded_send_buffer = [0u8; config::SENDER_BUFFER_SIZE];
         let encode_size = spdm_session.encode_spdm_secured_message(
             &app_buffer[0..used],
             &mut encoded_send_buffer,
             is_requester,
         )?;
 
+        let result =
+            self.transport_encap
+                .encap(&encoded_send_buffer[..encode_size], transport_buffer, true);
+        self.free_buffer(encoded_send_buffer);
+        self.free_buffer(app_buffer);
+        result
+    }
+
+    /// Encode the send_size bytes message at the start of message_buffer as a
+    /// secured message into transport_buffer. message_buffer is reused as
+    /// scratch space, so no further sender buffer is allocated.
+    pub fn encode_secured_message_in_place(
+        &mut self,
+        session_id: u32,
+        message_buffer: &mut [u8],
+        send_size: usize,
+        transport_buffer: &mut [u8],
+        is_requester: bool,
+        is_app_message: bool,
+    ) -> SpdmResult<usize> {
+        if send_size > message_buffer.len() {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+        if !is_app_message {
+            self.capture_message(
+                SpdmMessageDirection::Sent,
+                Some(session_id),
+                &message_buffer[..send_size],
+            );
+        }
+        let used = self.transport_encap.encap_app(
+            &message_buffer[..send_size],
+            transport_buffer,
+            is_app_message,
+        )?;
+
+        let spdm_session = self
+            .get_session_via_id(session_id)
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        let encode_size = spdm_session.encode_spdm_secured_message(
+            &transport_buffer[..used],
+            message_buffer,
+            is_requester,
+        )?;
+
         self.transport_encap
-            .encap(&encoded_send_buffer[..encode_size], transport_buffer, true)
+            .encap(&message_buffer[..encode_size], transport_buffer, true)
     }
 
     pub fn decap(
@@ -997,6 +1562,11 @@ impl<'a> SpdmContext<'a> {
             return Err(SPDM_STATUS_DECAP_FAIL); //need check
         }
 
+        self.capture_message(
+            SpdmMessageDirection::Received,
+            None,
+            &receive_buffer[..used],
+        );
         Ok(used)
     }
 
@@ -1006,7 +1576,7 @@ impl<'a> SpdmContext<'a> {
         transport_buffer: &[u8],
         receive_buffer: &mut [u8],
     ) -> SpdmResult<usize> {
-        let mut encoded_receive_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+        let mut encoded_receive_buffer = self.alloc_receiver_buffer();
         let (used, secured_message) = self
             .transport_encap
             .decap(transport_buffer, &mut encoded_receive_buffer)?;
@@ -1015,22 +1585,32 @@ impl<'a> SpdmContext<'a> {
             return Err(SPDM_STATUS_DECAP_FAIL);
         }
 
+        let mut app_buffer = self.alloc_receiver_buffer();
         let spdm_session = self
             .get_session_via_id(session_id)
             .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
 
-        let mut app_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
         let decode_size = spdm_session.decode_spdm_secured_message(
             &encoded_receive_buffer[..used],
             &mut app_buffer,
             false,
         )?;
 
-        let used = self
+        let result = self
             .transport_encap
-            .decap_app(&app_buffer[0..decode_size], receive_buffer)?;
-
-        Ok(used.0)
+            .decap_app(&app_buffer[0..decode_size], receive_buffer);
+        self.free_buffer(app_buffer);
+        self.free_buffer(encoded_receive_buffer);
+
+        let (used, is_app_message) = result?;
+        if !is_app_message {
+            self.capture_message(
+                SpdmMessageDirection::Received,
+                Some(session_id),
+                &receive_buffer[..used],
+            );
+        }
+        Ok(used)
     }
 }
 
@@ -1056,9 +1636,23 @@ pub struct SpdmConfigInfo {
     pub max_spdm_msg_size: u32,
     pub heartbeat_period: u8, // used by responder only
     pub secure_spdm_version: [u8; MAX_SECURE_SPDM_VERSION_COUNT], // used by responder only
+    pub max_outstanding_requests: u8, // used by requester only, 0 or 1 means no pipelining
+    pub max_session_mac_failure_count: u32, // used by responder only, 0 means never terminate the session
+    pub enforce_peer_capabilities: bool,    // used by requester only
+    pub max_session_count: usize,           // 0 means config::MAX_SPDM_SESSION_COUNT
+    pub session_eviction_policy: SpdmSessionEvictionPolicy, // used by responder only
+    pub raw_bit_stream_measurement_indices: [u8; 32], // used by responder only, bit n set returns index n as raw bit stream when requested
+    pub allow_set_certificate_out_of_session: bool, // used by responder only, SET_CERTIFICATE is session only by default
+}
+
+impl SpdmConfigInfo {
+    pub fn is_raw_bit_stream_measurement_index(&self, index: u8) -> bool {
+        self.raw_bit_stream_measurement_indices[index as usize / 8] & (1 << (index % 8)) != 0
+    }
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmNegotiateInfo {
     pub spdm_version_sel: SpdmVersion,
     pub req_capabilities_sel: SpdmRequestCapabilityFlags,
@@ -1411,9 +2005,15 @@ pub struct SpdmRuntimeInfo {
     pub need_measurement_summary_hash: bool,
     pub need_measurement_signature: bool,
     pub message_a: ManagedBufferA,
+    pub vca_boundary: audit_log::SpdmVcaMessageBoundary, // for the negotiation audit log
     pub message_b: ManagedBufferB,
     pub message_c: ManagedBufferC,
     pub message_m: ManagedBufferM,
+    // used by requester, transcripts of the last verified signatures, see transcript.rs
+    pub last_verified_m1m2: Option<SpdmTranscript>,
+    pub last_verified_l1l2: Option<SpdmTranscript>,
+    pub last_verified_measurement_nonce: Option<SpdmNonceStruct>, // requester nonce in last_verified_l1l2
+    pub last_verified_measurement_signature: Option<SpdmSignatureStruct>,
     pub content_changed: SpdmMeasurementContentChanged, // used by responder, set when content changed and spdm version is 1.2.
                                                         // used by requester, consume when measurement response report content changed.
 }
@@ -1428,8 +2028,14 @@ pub struct SpdmRuntimeInfo {
     pub need_measurement_summary_hash: bool,
     pub need_measurement_signature: bool,
     pub message_a: ManagedBufferA,
-    pub digest_context_m1m2: Option<SpdmHashCtx>, // for M1/M2
+    pub vca_boundary: audit_log::SpdmVcaMessageBoundary, // for the negotiation audit log
+    pub digest_context_m1m2: Option<SpdmHashCtx>,        // for M1/M2
     pub digest_context_l1l2: Option<SpdmHashCtx>, // for out of session get measurement/measurement
+    // used by requester, transcripts of the last verified signatures, see transcript.rs
+    pub last_verified_m1m2: Option<SpdmTranscript>,
+    pub last_verified_l1l2: Option<SpdmTranscript>,
+    pub last_verified_measurement_nonce: Option<SpdmNonceStruct>, // requester nonce in last_verified_l1l2
+    pub last_verified_measurement_signature: Option<SpdmSignatureStruct>,
     pub content_changed: SpdmMeasurementContentChanged, // used by responder, set when content changed and spdm version is 1.2.
                                                         // used by requester, consume when measurement response report content changed.
 }
@@ -1473,12 +2079,156 @@ pub struct SpdmProvisionInfo {
     pub my_cert_chain_data: [Option<SpdmCertChainData>; SPDM_MAX_SLOT_NUMBER],
     pub my_cert_chain: [Option<SpdmCertChainBuffer>; SPDM_MAX_SLOT_NUMBER],
     pub peer_root_cert_data: Option<SpdmCertChainData>,
+    // SPDM 1.3 multi-key, indexed by key pair ID - 1
+    pub key_pair_info: [Option<SpdmKeyPairInfo>; SPDM_MAX_KEY_PAIR_NUMBER],
+    // PUB_KEY_ID_CAP, DER SubjectPublicKeyInfo used with SPDM_PUB_KEY_SLOT_ID
+    pub my_pub_key: Option<SpdmCertChainData>,
+    pub peer_pub_key: Option<SpdmCertChainData>,
+    // algorithms of the cert chain in each slot, None for any negotiated ones
+    pub my_cert_chain_algo: [Option<SpdmCertChainAlgo>; SPDM_MAX_SLOT_NUMBER],
+}
+
+/// Algorithms a provisioned cert chain is used with, for devices holding
+/// chains of several algorithms in different slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpdmCertChainAlgo {
+    pub base_asym_algo: SpdmBaseAsymAlgo,
+    pub base_hash_algo: SpdmBaseHashAlgo,
+}
+
+impl SpdmProvisionInfo {
+    /// TotalKeyPairs reported in KEY_PAIR_INFO.
+    pub fn get_total_key_pairs(&self) -> u8 {
+        self.key_pair_info
+            .iter()
+            .rposition(|key_pair_info| key_pair_info.is_some())
+            .map_or(0, |index| index as u8 + 1)
+    }
+
+    pub fn get_key_pair_info(&self, key_pair_id: u8) -> Option<&SpdmKeyPairInfo> {
+        if key_pair_id == 0 {
+            return None;
+        }
+        self.key_pair_info.get(key_pair_id as usize - 1)?.as_ref()
+    }
+
+    /// Key pair the cert chain in slot_id belongs to.
+    pub fn get_slot_key_pair_id(&self, slot_id: u8) -> Option<u8> {
+        self.key_pair_info
+            .iter()
+            .position(|key_pair_info| match key_pair_info {
+                Some(key_pair_info) => key_pair_info.assoc_cert_slot_mask & (1 << slot_id) != 0,
+                None => false,
+            })
+            .map(|index| index as u8 + 1)
+    }
+
+    /// Associate the cert chain in slot_id with key_pair_id only, 0 removes the association.
+    pub fn set_slot_key_pair_id(&mut self, slot_id: u8, key_pair_id: u8) {
+        for (index, key_pair_info) in self.key_pair_info.iter_mut().enumerate() {
+            if let Some(key_pair_info) = key_pair_info {
+                if index + 1 == key_pair_id as usize {
+                    key_pair_info.assoc_cert_slot_mask |= 1 << slot_id;
+                } else {
+                    key_pair_info.assoc_cert_slot_mask &= !(1 << slot_id);
+                }
+            }
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct SpdmPeerInfo {
     pub peer_cert_chain: [Option<SpdmCertChainBuffer>; SPDM_MAX_SLOT_NUMBER],
+    pub peer_digest_slot_mask: u8, // slot mask of the last DIGESTS received from peer
+    pub peer_digests: [SpdmDigestStruct; SPDM_MAX_SLOT_NUMBER], // indexed by slot id
+    // per slot info of the last DIGESTS of a multi-key connection, indexed by slot id
+    pub peer_key_pair_id: [u8; SPDM_MAX_SLOT_NUMBER],
+    pub peer_cert_model: [SpdmCertificateModelType; SPDM_MAX_SLOT_NUMBER],
+    pub peer_key_usage_mask: [SpdmKeyUsageMask; SPDM_MAX_SLOT_NUMBER],
     pub peer_cert_chain_temp: Option<SpdmCertChainBuffer>,
+    // chains verified in portions, only the leaf is kept, see SpdmCertChainStreamVerifier
+    pub peer_cert_chain_streamed: [Option<SpdmStreamedCertChain>; SPDM_MAX_SLOT_NUMBER],
+    pub peer_vendor_error: Option<SpdmVendorDefinedError>, // last ERROR(VendorDefined) received from peer
+    pub peer_csr_tracking_tag: u8, // CSRTrackingTag of the last ERROR(ResetRequired) to GET_CSR
+}
+
+impl SpdmPeerInfo {
+    /// Verified peer cert chain in slot_id exactly as presented, including
+    /// the SPDM cert chain header and root hash.
+    pub fn get_peer_cert_chain(&self, slot_id: u8) -> Option<&[u8]> {
+        self.peer_cert_chain
+            .get(slot_id as usize)?
+            .as_ref()
+            .map(|cert_chain| cert_chain.as_ref())
+    }
+
+    /// DER certificates of the verified peer cert chain in slot_id, root
+    /// first. base_hash_algo is the negotiated hash, sizing the root hash.
+    pub fn get_peer_cert_chain_der(
+        &self,
+        slot_id: u8,
+        base_hash_algo: SpdmBaseHashAlgo,
+    ) -> Option<&[u8]> {
+        self.get_peer_cert_chain(slot_id)?
+            .get((4usize + base_hash_algo.get_size() as usize)..)
+    }
+
+    /// Each DER certificate of the verified peer cert chain in slot_id,
+    /// root first and leaf last.
+    pub fn get_peer_certs(
+        &self,
+        slot_id: u8,
+        base_hash_algo: SpdmBaseHashAlgo,
+    ) -> Option<SpdmCertChainIter<'_>> {
+        Some(SpdmCertChainIter {
+            cert_chain: self.get_peer_cert_chain_der(slot_id, base_hash_algo)?,
+            index: 0,
+        })
+    }
+
+    /// Parsed fields of the leaf certificate of the verified peer cert chain
+    /// in slot_id, for device identity policies.
+    pub fn get_peer_leaf_cert_info(
+        &self,
+        slot_id: u8,
+        base_hash_algo: SpdmBaseHashAlgo,
+    ) -> Option<crypto::SpdmCertInfo<'_>> {
+        let cert_chain = self.get_peer_cert_chain_der(slot_id, base_hash_algo)?;
+        let (leaf_begin, leaf_end) =
+            crypto::cert_operation::get_cert_from_cert_chain(cert_chain, -1).ok()?;
+        crypto::get_cert_info(&cert_chain[leaf_begin..leaf_end]).ok()
+    }
+
+    /// DICE TcbInfo of the leaf certificate of the verified peer cert chain
+    /// in slot_id, e.g. the FWIDs of an alias certificate. Empty if the
+    /// leaf has no DICE extension.
+    pub fn get_peer_leaf_dice_tcb_info(
+        &self,
+        slot_id: u8,
+        base_hash_algo: SpdmBaseHashAlgo,
+    ) -> Option<Vec<crypto::SpdmDiceTcbInfo<'_>>> {
+        self.get_peer_leaf_cert_info(slot_id, base_hash_algo)?
+            .get_dice_tcb_info()
+            .ok()
+    }
+}
+
+/// Iterator over the DER certificates of a cert chain.
+pub struct SpdmCertChainIter<'a> {
+    cert_chain: &'a [u8],
+    index: isize,
+}
+
+impl<'a> Iterator for SpdmCertChainIter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (cert_begin, cert_end) =
+            crypto::cert_operation::get_cert_from_cert_chain(self.cert_chain, self.index).ok()?;
+        self.index += 1;
+        Some(&self.cert_chain[cert_begin..cert_end])
+    }
 }
 
 #[cfg(feature = "mut-auth")]
@@ -577,13 +577,17 @@ impl SpdmOpaqueStruct {
         &self,
         context: &mut SpdmContext,
     ) -> Option<SecuredMessageVersionList> {
+        // the supported version list is the first element, others may follow
         let mut r = Reader::init(&self.data[0..self.data_size as usize]);
-        let secured_message_dmtf_supported_version =
-            SecuredMessageDMTFSupportedVersion::spdm_read(context, &mut r)?;
+        let header = SecuredMessageGeneralOpaqueDataHeader::spdm_read(context, &mut r)?;
+        if header.total_elements == 0 {
+            return None;
+        }
+        let opaque_element_dmtf_supported_version =
+            OpaqueElementDMTFSupportedVersion::spdm_read(context, &mut r)?;
 
         Some(SecuredMessageVersionList {
-            ..secured_message_dmtf_supported_version.opaque_element_dmtf_supported_version_list[0]
-                .secured_msg_vers
+            ..opaque_element_dmtf_supported_version.secured_msg_vers
         })
     }
 
@@ -591,13 +595,17 @@ impl SpdmOpaqueStruct {
         &self,
         context: &mut SpdmContext,
     ) -> Option<SecuredMessageVersion> {
+        // the version selection is the first element, others may follow
         let mut r = Reader::init(&self.data[0..self.data_size as usize]);
-        let secured_message_dmtf_version_selection =
-            SecuredMessageDMTFVersionSelection::spdm_read(context, &mut r)?;
+        let header = SecuredMessageGeneralOpaqueDataHeader::spdm_read(context, &mut r)?;
+        if header.total_elements == 0 {
+            return None;
+        }
+        let opaque_element_dmtf_version_selection =
+            OpaqueElementDMTFVersionSelection::spdm_read(context, &mut r)?;
 
         Some(SecuredMessageVersion {
-            ..secured_message_dmtf_version_selection.opaque_element_dmtf_version_selection_list[0]
-                .selected_version
+            ..opaque_element_dmtf_version_selection.selected_version
         })
     }
 }
@@ -0,0 +1,306 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::opaque::{
+    SecuredMessageGeneralOpaqueDataHeader, SpdmOpaqueStruct, DMTF_ID, MAX_SPDM_OPAQUE_SIZE,
+};
+use crate::common::{SpdmCodec, SpdmContext};
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_MSG_FIELD,
+    SPDM_STATUS_INVALID_STATE_LOCAL,
+};
+use crate::message::{SpdmRequestResponseCode, MAX_SPDM_VENDOR_DEFINED_VENDOR_ID_LEN};
+use crate::protocol::SpdmVersion;
+use codec::{Codec, Reader, Writer};
+
+use conquer_once::spin::OnceCell;
+
+/// Opaque elements collected for one response, without the general opaque data header.
+/// Each element is ID, VendorLen, VendorID, OpaqueElementDataLen, data and AlignPadding.
+#[derive(Debug, Clone)]
+pub struct SpdmOpaqueElementList {
+    pub element_count: u8,
+    pub data_size: u16,
+    pub data: [u8; MAX_SPDM_OPAQUE_SIZE],
+}
+
+impl Default for SpdmOpaqueElementList {
+    fn default() -> SpdmOpaqueElementList {
+        SpdmOpaqueElementList {
+            element_count: 0,
+            data_size: 0,
+            data: [0u8; MAX_SPDM_OPAQUE_SIZE],
+        }
+    }
+}
+
+impl SpdmOpaqueElementList {
+    /// Append one opaque element, the element header and padding are added here.
+    pub fn push(&mut self, id: u8, vendor_id: &[u8], element_data: &[u8]) -> SpdmResult {
+        if vendor_id.len() > MAX_SPDM_VENDOR_DEFINED_VENDOR_ID_LEN
+            || element_data.len() > u16::MAX as usize
+            || self.element_count == u8::MAX
+        {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+
+        let start = self.data_size as usize;
+        let mut writer = Writer::init(&mut self.data[start..]);
+        id.encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        (vendor_id.len() as u8)
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        writer
+            .extend_from_slice(vendor_id)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        (element_data.len() as u16)
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        writer
+            .extend_from_slice(element_data)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        let filled = writer.used();
+        let align_padding = ((filled + 3) & !3) - filled;
+        for _i in 0..align_padding {
+            0u8.encode(&mut writer)
+                .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        }
+
+        self.data_size += writer.used() as u16;
+        self.element_count += 1;
+        Ok(())
+    }
+
+    fn extend(&mut self, element_count: u8, elements: &[u8]) -> SpdmResult {
+        let start = self.data_size as usize;
+        if elements.len() > MAX_SPDM_OPAQUE_SIZE - start {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+        self.data[start..start + elements.len()].copy_from_slice(elements);
+        self.data_size += elements.len() as u16;
+        self.element_count = self
+            .element_count
+            .checked_add(element_count)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok(())
+    }
+}
+
+/// Application hook supplying opaque elements for KEY_EXCHANGE,
+/// KEY_EXCHANGE_RSP, MEASUREMENTS and CHALLENGE_AUTH, and receiving the
+/// ones of the peer in KEY_EXCHANGE, KEY_EXCHANGE_RSP and CHALLENGE_AUTH.
+/// The library wraps the elements with the general opaque data header
+/// matching the negotiated version and opaque data format.
+/// It may also be set per context in `SpdmContext::opaque_provider`.
+#[derive(Clone, Copy)]
+pub struct SpdmOpaqueProvider {
+    pub opaque_elements_cb: fn(
+        spdm_version: SpdmVersion,
+        request_response_code: SpdmRequestResponseCode,
+        elements: &mut SpdmOpaqueElementList,
+    ) -> SpdmResult,
+    /// Called for each element of the peer but the DMTF ones, which the
+    /// library handles. An error rejects the message.
+    pub opaque_element_received_cb: fn(
+        spdm_version: SpdmVersion,
+        request_response_code: SpdmRequestResponseCode,
+        id: u8,
+        vendor_id: &[u8],
+        element_data: &[u8],
+    ) -> SpdmResult,
+}
+
+static OPAQUE_PROVIDER: OnceCell<SpdmOpaqueProvider> = OnceCell::uninit();
+
+pub fn register_opaque_provider(context: SpdmOpaqueProvider) -> bool {
+    OPAQUE_PROVIDER.try_init_once(|| context).is_ok()
+}
+
+pub fn opaque_elements(
+    spdm_version: SpdmVersion,
+    request_response_code: SpdmRequestResponseCode,
+    elements: &mut SpdmOpaqueElementList,
+) -> SpdmResult {
+    match OPAQUE_PROVIDER.try_get() {
+        Ok(provider) => {
+            (provider.opaque_elements_cb)(spdm_version, request_response_code, elements)
+        }
+        Err(_) => Ok(()),
+    }
+}
+
+pub fn opaque_element_received(
+    spdm_version: SpdmVersion,
+    request_response_code: SpdmRequestResponseCode,
+    id: u8,
+    vendor_id: &[u8],
+    element_data: &[u8],
+) -> SpdmResult {
+    match OPAQUE_PROVIDER.try_get() {
+        Ok(provider) => (provider.opaque_element_received_cb)(
+            spdm_version,
+            request_response_code,
+            id,
+            vendor_id,
+            element_data,
+        ),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Call f with the ID, VendorID and data of each element of a general
+/// opaque data field. Ok(false) if opaque is not in that format.
+fn for_each_opaque_element(
+    context: &mut SpdmContext,
+    opaque: &SpdmOpaqueStruct,
+    mut f: impl FnMut(u8, &[u8], &[u8]) -> SpdmResult,
+) -> SpdmResult<bool> {
+    let mut reader = Reader::init(&opaque.data[..opaque.data_size as usize]);
+    let header = match SecuredMessageGeneralOpaqueDataHeader::spdm_read(context, &mut reader) {
+        Some(header) => header,
+        None => return Ok(false),
+    };
+    for _ in 0..header.total_elements {
+        let start = reader.used();
+        let id = u8::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        let vendor_len = u8::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        let vendor_id = reader
+            .take(vendor_len as usize)
+            .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        let data_len = u16::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        let element_data = reader
+            .take(data_len as usize)
+            .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        f(id, vendor_id, element_data)?;
+
+        let filled = reader.used() - start;
+        let align_padding = ((filled + 3) & !3) - filled;
+        reader
+            .take(align_padding)
+            .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+    }
+    Ok(true)
+}
+
+impl<'a> SpdmContext<'a> {
+    fn get_opaque_provider(&self) -> Option<SpdmOpaqueProvider> {
+        self.opaque_provider
+            .or_else(|| OPAQUE_PROVIDER.try_get().ok().copied())
+    }
+
+    /// Pass the opaque elements of a peer message to the opaque provider.
+    /// Opaque data not in the general opaque data format is ignored.
+    pub(crate) fn receive_opaque_elements(
+        &mut self,
+        request_response_code: SpdmRequestResponseCode,
+        opaque: &SpdmOpaqueStruct,
+    ) -> SpdmResult {
+        let provider = match self.get_opaque_provider() {
+            Some(provider) if opaque.data_size != 0 => provider,
+            _ => return Ok(()),
+        };
+        let spdm_version = self.negotiate_info.spdm_version_sel;
+        let general = for_each_opaque_element(self, opaque, |id, vendor_id, element_data| {
+            if id == DMTF_ID {
+                return Ok(());
+            }
+            (provider.opaque_element_received_cb)(
+                spdm_version,
+                request_response_code,
+                id,
+                vendor_id,
+                element_data,
+            )
+        })?;
+        if !general {
+            debug!("no general opaque data format, opaque elements not received\n");
+        }
+        Ok(())
+    }
+
+    /// Merge the provided opaque elements into the opaque field of the message.
+    /// Elements already in opaque (e.g. secured message version selection) come first.
+    /// The opaque field is left untouched if nothing is provided or if no
+    /// general opaque data format is negotiated.
+    pub(crate) fn append_provided_opaque_elements(
+        &mut self,
+        request_response_code: SpdmRequestResponseCode,
+        opaque: &mut SpdmOpaqueStruct,
+    ) -> SpdmResult {
+        let provider = match self.get_opaque_provider() {
+            Some(provider) => provider,
+            None => return Ok(()),
+        };
+        let mut provided = SpdmOpaqueElementList::default();
+        (provider.opaque_elements_cb)(
+            self.negotiate_info.spdm_version_sel,
+            request_response_code,
+            &mut provided,
+        )?;
+        if provided.element_count == 0 {
+            return Ok(());
+        }
+
+        let mut elements = SpdmOpaqueElementList::default();
+        if opaque.data_size != 0 {
+            let mut reader = Reader::init(&opaque.data[..opaque.data_size as usize]);
+            let header = SecuredMessageGeneralOpaqueDataHeader::spdm_read(self, &mut reader)
+                .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+            let used = reader.used();
+            elements.extend(
+                header.total_elements,
+                &opaque.data[used..opaque.data_size as usize],
+            )?;
+        }
+        elements.extend(
+            provided.element_count,
+            &provided.data[..provided.data_size as usize],
+        )?;
+
+        let mut data = [0u8; MAX_SPDM_OPAQUE_SIZE];
+        let mut writer = Writer::init(&mut data);
+        let header = SecuredMessageGeneralOpaqueDataHeader {
+            total_elements: elements.element_count,
+        };
+        if header.spdm_encode(self, &mut writer).is_err() {
+            debug!("no general opaque data format, provided opaque elements dropped\n");
+            return Ok(());
+        }
+        writer
+            .extend_from_slice(&elements.data[..elements.data_size as usize])
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+
+        opaque.data_size = writer.used() as u16;
+        opaque.data = data;
+        Ok(())
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_opaque_element_list_push() {
+        let mut elements = SpdmOpaqueElementList::default();
+        assert!(elements.push(0x01, &[0xAA, 0xBB], &[1, 2, 3]).is_ok());
+        assert_eq!(elements.element_count, 1);
+        assert_eq!(elements.data_size, 12);
+        assert_eq!(
+            elements.data[..12],
+            [0x01, 0x02, 0xAA, 0xBB, 0x03, 0x00, 1, 2, 3, 0, 0, 0]
+        );
+
+        assert!(elements.push(0x00, &[], &[4, 5, 6, 7]).is_ok());
+        assert_eq!(elements.element_count, 2);
+        assert_eq!(elements.data_size, 20);
+        assert_eq!(elements.data[12..20], [0x00, 0x00, 0x04, 0x00, 4, 5, 6, 7]);
+
+        let large = [0u8; MAX_SPDM_OPAQUE_SIZE];
+        assert!(elements.push(0x00, &[], &large).is_err());
+        assert_eq!(elements.element_count, 2);
+        assert_eq!(elements.data_size, 20);
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::measurement_index_map::MAX_SPDM_MEASUREMENT_INDEX_MAP_SIZE;
+use super::{SpdmContext, SpdmProvisionInfo};
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_BUFFER_TOO_SMALL,
+    SPDM_STATUS_INVALID_PARAMETER,
+};
+use crate::protocol::{SpdmCertChainData, SpdmPskHintStruct, SPDM_MAX_SLOT_NUMBER};
+
+use zeroize::Zeroize;
+
+pub const MAX_SPDM_PROVISIONED_PSK_SIZE: usize = 64;
+pub const MAX_SPDM_PROVISIONED_PSK_ENTRIES: usize = 4;
+pub const MAX_SPDM_IMMUTABLE_CONFIG_SIZE: usize = 256;
+
+/// Persistent storage of provisioned data.
+///
+/// SpdmProvisionInfo is loaded from the store at start up, and written back
+/// when the provisioned data is updated, e.g. by SET_CERTIFICATE.
+pub trait SpdmProvisionStore {
+    /// Cert chain (DER certificates, without the SPDM cert chain header) of slot_id,
+    /// None if the slot is not provisioned.
+    fn load_cert_chain(&mut self, slot_id: u8) -> SpdmResult<Option<SpdmCertChainData>>;
+
+    /// Write back the cert chain of slot_id, None erases the slot.
+    fn store_cert_chain(
+        &mut self,
+        slot_id: u8,
+        cert_chain: Option<&SpdmCertChainData>,
+    ) -> SpdmResult;
+
+    fn load_peer_root_cert(&mut self) -> SpdmResult<Option<SpdmCertChainData>>;
+
+    fn store_peer_root_cert(&mut self, root_cert: Option<&SpdmCertChainData>) -> SpdmResult;
+
+    /// Copy the PSK identified by psk_hint into psk, return the PSK size.
+    fn load_psk(&mut self, psk_hint: &SpdmPskHintStruct, psk: &mut [u8]) -> SpdmResult<usize>;
+
+    fn store_psk(&mut self, psk_hint: &SpdmPskHintStruct, psk: &[u8]) -> SpdmResult;
+
+    /// Copy the immutable configuration blob into data, return its size.
+    /// Immutable configuration is provisioned at manufacturing and is never written back.
+    fn load_immutable_config(&mut self, data: &mut [u8]) -> SpdmResult<usize>;
+
+    /// Copy the encoded measurement index map into data, return its size, 0 if never stored.
+    fn load_measurement_index_map(&mut self, data: &mut [u8]) -> SpdmResult<usize>;
+
+    fn store_measurement_index_map(&mut self, data: &[u8]) -> SpdmResult;
+}
+
+impl SpdmProvisionInfo {
+    /// Populate cert chains and the peer root cert from store.
+    /// my_cert_chain is left empty, it is generated from my_cert_chain_data
+    /// once the hash algorithm is negotiated.
+    pub fn load_from(store: &mut dyn SpdmProvisionStore) -> SpdmResult<SpdmProvisionInfo> {
+        let mut provision_info = SpdmProvisionInfo::default();
+        for (slot_id, cert_chain_data) in provision_info.my_cert_chain_data.iter_mut().enumerate() {
+            *cert_chain_data = store.load_cert_chain(slot_id as u8)?;
+        }
+        provision_info.peer_root_cert_data = store.load_peer_root_cert()?;
+        Ok(provision_info)
+    }
+
+    /// Write cert chains and the peer root cert back to store.
+    pub fn store_to(&self, store: &mut dyn SpdmProvisionStore) -> SpdmResult {
+        for (slot_id, cert_chain_data) in self.my_cert_chain_data.iter().enumerate() {
+            store.store_cert_chain(slot_id as u8, cert_chain_data.as_ref())?;
+        }
+        store.store_peer_root_cert(self.peer_root_cert_data.as_ref())
+    }
+}
+
+impl<'a> SpdmContext<'a> {
+    /// Write the cert chain of slot_id back to provision_store, if any, once
+    /// it is updated, e.g. by SET_CERTIFICATE. None erases the slot.
+    pub fn store_cert_chain(
+        &mut self,
+        slot_id: u8,
+        cert_chain: Option<&SpdmCertChainData>,
+    ) -> SpdmResult {
+        match self.provision_store.as_mut() {
+            Some(provision_store) => provision_store.store_cert_chain(slot_id, cert_chain),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct SpdmProvisionedPsk {
+    psk_hint: SpdmPskHintStruct,
+    psk_size: u16,
+    psk: [u8; MAX_SPDM_PROVISIONED_PSK_SIZE],
+}
+
+impl Drop for SpdmProvisionedPsk {
+    fn drop(&mut self) {
+        self.psk.zeroize();
+    }
+}
+
+/// Volatile SpdmProvisionStore, for tests and for integrators
+/// whose provisioned data is rebuilt on every boot.
+#[derive(Clone)]
+pub struct SpdmMemoryProvisionStore {
+    cert_chain: [Option<SpdmCertChainData>; SPDM_MAX_SLOT_NUMBER],
+    peer_root_cert: Option<SpdmCertChainData>,
+    psk: [Option<SpdmProvisionedPsk>; MAX_SPDM_PROVISIONED_PSK_ENTRIES],
+    immutable_config_size: u16,
+    immutable_config: [u8; MAX_SPDM_IMMUTABLE_CONFIG_SIZE],
+    measurement_index_map_size: u16,
+    measurement_index_map: [u8; MAX_SPDM_MEASUREMENT_INDEX_MAP_SIZE],
+}
+
+impl SpdmMemoryProvisionStore {
+    pub fn new(immutable_config: &[u8]) -> SpdmResult<Self> {
+        if immutable_config.len() > MAX_SPDM_IMMUTABLE_CONFIG_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+        let mut store = SpdmMemoryProvisionStore {
+            cert_chain: Default::default(),
+            peer_root_cert: None,
+            psk: Default::default(),
+            immutable_config_size: immutable_config.len() as u16,
+            immutable_config: [0u8; MAX_SPDM_IMMUTABLE_CONFIG_SIZE],
+            measurement_index_map_size: 0,
+            measurement_index_map: [0u8; MAX_SPDM_MEASUREMENT_INDEX_MAP_SIZE],
+        };
+        store.immutable_config[..immutable_config.len()].copy_from_slice(immutable_config);
+        Ok(store)
+    }
+
+    fn find_psk(&self, psk_hint: &SpdmPskHintStruct) -> Option<usize> {
+        self.psk.iter().position(|entry| match entry {
+            Some(entry) => entry.psk_hint.as_ref() == psk_hint.as_ref(),
+            None => false,
+        })
+    }
+}
+
+impl SpdmProvisionStore for SpdmMemoryProvisionStore {
+    fn load_cert_chain(&mut self, slot_id: u8) -> SpdmResult<Option<SpdmCertChainData>> {
+        let cert_chain = self
+            .cert_chain
+            .get(slot_id as usize)
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        Ok(cert_chain.clone())
+    }
+
+    fn store_cert_chain(
+        &mut self,
+        slot_id: u8,
+        cert_chain: Option<&SpdmCertChainData>,
+    ) -> SpdmResult {
+        let slot = self
+            .cert_chain
+            .get_mut(slot_id as usize)
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        *slot = cert_chain.cloned();
+        Ok(())
+    }
+
+    fn load_peer_root_cert(&mut self) -> SpdmResult<Option<SpdmCertChainData>> {
+        Ok(self.peer_root_cert.clone())
+    }
+
+    fn store_peer_root_cert(&mut self, root_cert: Option<&SpdmCertChainData>) -> SpdmResult {
+        self.peer_root_cert = root_cert.cloned();
+        Ok(())
+    }
+
+    fn load_psk(&mut self, psk_hint: &SpdmPskHintStruct, psk: &mut [u8]) -> SpdmResult<usize> {
+        let index = self
+            .find_psk(psk_hint)
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        let entry = self.psk[index]
+            .as_ref()
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        let psk_size = entry.psk_size as usize;
+        if psk.len() < psk_size {
+            return Err(SPDM_STATUS_BUFFER_TOO_SMALL);
+        }
+        psk[..psk_size].copy_from_slice(&entry.psk[..psk_size]);
+        Ok(psk_size)
+    }
+
+    fn store_psk(&mut self, psk_hint: &SpdmPskHintStruct, psk: &[u8]) -> SpdmResult {
+        if psk.len() > MAX_SPDM_PROVISIONED_PSK_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+        let index = match self.find_psk(psk_hint) {
+            Some(index) => index,
+            None => self
+                .psk
+                .iter()
+                .position(|entry| entry.is_none())
+                .ok_or(SPDM_STATUS_BUFFER_FULL)?,
+        };
+        let mut entry = SpdmProvisionedPsk {
+            psk_hint: psk_hint.clone(),
+            psk_size: psk.len() as u16,
+            psk: [0u8; MAX_SPDM_PROVISIONED_PSK_SIZE],
+        };
+        entry.psk[..psk.len()].copy_from_slice(psk);
+        self.psk[index] = Some(entry);
+        Ok(())
+    }
+
+    fn load_immutable_config(&mut self, data: &mut [u8]) -> SpdmResult<usize> {
+        let size = self.immutable_config_size as usize;
+        if data.len() < size {
+            return Err(SPDM_STATUS_BUFFER_TOO_SMALL);
+        }
+        data[..size].copy_from_slice(&self.immutable_config[..size]);
+        Ok(size)
+    }
+
+    fn load_measurement_index_map(&mut self, data: &mut [u8]) -> SpdmResult<usize> {
+        let size = self.measurement_index_map_size as usize;
+        if data.len() < size {
+            return Err(SPDM_STATUS_BUFFER_TOO_SMALL);
+        }
+        data[..size].copy_from_slice(&self.measurement_index_map[..size]);
+        Ok(size)
+    }
+
+    fn store_measurement_index_map(&mut self, data: &[u8]) -> SpdmResult {
+        if data.len() > MAX_SPDM_MEASUREMENT_INDEX_MAP_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+        self.measurement_index_map[..data.len()].copy_from_slice(data);
+        self.measurement_index_map_size = data.len() as u16;
+        Ok(())
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    fn cert_chain_data(value: u8, size: usize) -> SpdmCertChainData {
+        let mut cert_chain_data = SpdmCertChainData {
+            data_size: size as u16,
+            ..Default::default()
+        };
+        cert_chain_data.data[..size]
+            .iter_mut()
+            .for_each(|d| *d = value);
+        cert_chain_data
+    }
+
+    #[test]
+    fn test_case0_memory_provision_store_cert_chain() {
+        let mut store = SpdmMemoryProvisionStore::new(&[]).unwrap();
+        let mut provision_info = SpdmProvisionInfo::load_from(&mut store).unwrap();
+        assert!(provision_info
+            .my_cert_chain_data
+            .iter()
+            .all(|c| c.is_none()));
+        assert!(provision_info.peer_root_cert_data.is_none());
+
+        provision_info.my_cert_chain_data[0] = Some(cert_chain_data(0xAA, 16));
+        provision_info.my_cert_chain_data[3] = Some(cert_chain_data(0xBB, 32));
+        provision_info.peer_root_cert_data = Some(cert_chain_data(0xCC, 8));
+        assert!(provision_info.store_to(&mut store).is_ok());
+
+        let provision_info = SpdmProvisionInfo::load_from(&mut store).unwrap();
+        assert_eq!(
+            provision_info.my_cert_chain_data[0]
+                .as_ref()
+                .unwrap()
+                .as_ref(),
+            &[0xAA; 16]
+        );
+        assert_eq!(
+            provision_info.my_cert_chain_data[3]
+                .as_ref()
+                .unwrap()
+                .as_ref(),
+            &[0xBB; 32]
+        );
+        assert!(provision_info.my_cert_chain_data[1].is_none());
+        assert_eq!(
+            provision_info
+                .peer_root_cert_data
+                .as_ref()
+                .unwrap()
+                .as_ref(),
+            &[0xCC; 8]
+        );
+
+        assert!(store.store_cert_chain(3, None).is_ok());
+        assert!(store.load_cert_chain(3).unwrap().is_none());
+        assert_eq!(
+            store.load_cert_chain(SPDM_MAX_SLOT_NUMBER as u8).err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+    }
+
+    #[test]
+    fn test_case0_memory_provision_store_psk() {
+        let mut store = SpdmMemoryProvisionStore::new(&[1, 2, 3]).unwrap();
+        let mut psk_hint = SpdmPskHintStruct {
+            data_size: 4,
+            ..Default::default()
+        };
+        psk_hint.data[..4].copy_from_slice(b"hint");
+
+        let psk = &mut [0u8; MAX_SPDM_PROVISIONED_PSK_SIZE];
+        assert!(store.load_psk(&psk_hint, psk).is_err());
+        assert!(store.store_psk(&psk_hint, &[0x5A; 32]).is_ok());
+        assert_eq!(store.load_psk(&psk_hint, psk), Ok(32));
+        assert_eq!(psk[..32], [0x5A; 32]);
+        assert_eq!(
+            store.load_psk(&psk_hint, &mut [0u8; 16]),
+            Err(SPDM_STATUS_BUFFER_TOO_SMALL)
+        );
+
+        // the same hint replaces the PSK
+        assert!(store.store_psk(&psk_hint, &[0xA5; 48]).is_ok());
+        assert_eq!(store.load_psk(&psk_hint, psk), Ok(48));
+        assert_eq!(psk[..48], [0xA5; 48]);
+
+        let config = &mut [0u8; MAX_SPDM_IMMUTABLE_CONFIG_SIZE];
+        assert_eq!(store.load_immutable_config(config), Ok(3));
+        assert_eq!(config[..3], [1, 2, 3]);
+    }
+}
@@ -2,16 +2,24 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
-use super::key_schedule::SpdmKeySchedule;
+#[cfg(feature = "key-schedule-trace")]
+use super::key_schedule::SpdmKeyScheduleTrace;
+use super::key_schedule::{SpdmKeySchedule, MAX_SPDM_EXPORTER_LABEL_CONTEXT_SIZE};
 use crate::config;
 use crate::crypto;
 use crate::error::SpdmResult;
 use crate::error::SPDM_STATUS_BUFFER_TOO_SMALL;
 use crate::error::SPDM_STATUS_CRYPTO_ERROR;
-use crate::error::SPDM_STATUS_DECODE_AEAD_FAIL;
+use crate::error::SPDM_STATUS_INVALID_MSG_FIELD;
+use crate::error::SPDM_STATUS_INVALID_MSG_SIZE;
+use crate::error::SPDM_STATUS_INVALID_PARAMETER;
 use crate::error::SPDM_STATUS_INVALID_STATE_LOCAL;
+use crate::error::SPDM_STATUS_SEQUENCE_NUMBER_MISMATCH;
 use crate::error::SPDM_STATUS_SEQUENCE_NUMBER_OVERFLOW;
+use crate::error::SPDM_STATUS_VERIF_FAIL;
 use crate::message::SpdmKeyExchangeMutAuthAttributes;
+use crate::secret::SpdmSecretPsk;
+use crate::time;
 
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -39,6 +47,36 @@ impl Default for SpdmSessionState {
     }
 }
 
+// Number of secured messages received after KEY_UPDATE which may still be
+// decrypted with the old data key, so that messages already in flight when
+// the key is updated are not dropped.
+pub const SPDM_KEY_UPDATE_OLD_KEY_WINDOW: u8 = 8;
+
+// HeartbeatPeriod is in seconds.
+pub const SPDM_HEARTBEAT_PERIOD_UNIT_US: u64 = 1_000_000;
+
+/// What the responder does with KEY_EXCHANGE or PSK_EXCHANGE when all the
+/// session slots are in use. Established sessions are never terminated for
+/// these requests, which are not authenticated, only handshaking ones are.
+/// If all the sessions are established the request is answered with
+/// ERROR(SessionLimitExceeded) whatever the policy.
+#[derive(Debug, Clone, Copy)]
+pub enum SpdmSessionEvictionPolicy {
+    /// Answer ERROR(SessionLimitExceeded).
+    RejectNew,
+    /// Terminate the handshaking session which received a message least recently.
+    EvictLeastRecentlyUsedHandshaking,
+    /// Return the index of the handshaking session to terminate, None to reject.
+    /// An index of an established session is rejected.
+    Custom(fn(&[SpdmSession]) -> Option<usize>),
+}
+
+impl Default for SpdmSessionEvictionPolicy {
+    fn default() -> Self {
+        SpdmSessionEvictionPolicy::RejectNew
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SpdmSessionCryptoParam {
     pub base_hash_algo: SpdmBaseHashAlgo,
@@ -61,6 +99,23 @@ pub struct SpdmSessionSecretParam {
     pub sequence_number: u64,
 }
 
+// A direction whose sequence number reached this value is exhausted, it is
+// never used as AEAD nonce. Only KEY_UPDATE, which restarts the sequence
+// number of the new key at 0, or a new session can continue.
+pub const SPDM_MAX_SEQUENCE_NUMBER: u64 = u64::MAX;
+
+impl SpdmSessionSecretParam {
+    pub fn is_sequence_number_exhausted(&self) -> bool {
+        self.sequence_number >= SPDM_MAX_SEQUENCE_NUMBER
+    }
+
+    // Saturating, an exhausted direction stays exhausted instead of wrapping
+    // to a nonce already used.
+    fn advance_sequence_number(&mut self) {
+        self.sequence_number = self.sequence_number.saturating_add(1);
+    }
+}
+
 #[derive(Debug, Clone, Default, Zeroize, ZeroizeOnDrop)]
 pub struct SpdmSessionHandshakeSecret {
     pub request_handshake_secret: SpdmDirectionHandshakeSecretStruct,
@@ -80,6 +135,87 @@ pub struct SpdmSessionAppliationSecret {
     pub export_master_secret: SpdmExportMasterSecretStruct,
 }
 
+/// Received secured messages which failed to decode, by cause.
+///
+/// decode_spdm_secured_message returns
+/// SPDM_STATUS_VERIF_FAIL when the AEAD tag does not verify,
+/// SPDM_STATUS_SEQUENCE_NUMBER_MISMATCH for an unexpected sequence number,
+/// SPDM_STATUS_INVALID_MSG_FIELD for another session ID and
+/// SPDM_STATUS_INVALID_MSG_SIZE for a truncated message or a wrong length field.
+/// Records carry no version, a secured message version mismatch fails the MAC check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmSecuredMessageDecodeStats {
+    pub mac_failure_count: u32,
+    /// MAC failures since the last message decoded successfully.
+    pub consecutive_mac_failure_count: u32,
+    pub sequence_number_mismatch_count: u32,
+    pub session_id_mismatch_count: u32,
+    pub length_error_count: u32,
+}
+
+impl SpdmSecuredMessageDecodeStats {
+    fn record(&mut self, result: &SpdmResult<usize>) {
+        match result {
+            Ok(_) => self.consecutive_mac_failure_count = 0,
+            Err(SPDM_STATUS_VERIF_FAIL) => {
+                self.mac_failure_count = self.mac_failure_count.saturating_add(1);
+                self.consecutive_mac_failure_count =
+                    self.consecutive_mac_failure_count.saturating_add(1);
+            }
+            Err(SPDM_STATUS_SEQUENCE_NUMBER_MISMATCH) => {
+                self.sequence_number_mismatch_count =
+                    self.sequence_number_mismatch_count.saturating_add(1)
+            }
+            Err(SPDM_STATUS_INVALID_MSG_FIELD) => {
+                self.session_id_mismatch_count = self.session_id_mismatch_count.saturating_add(1)
+            }
+            Err(SPDM_STATUS_INVALID_MSG_SIZE) => {
+                self.length_error_count = self.length_error_count.saturating_add(1)
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Traffic of a session, for monitoring the link. Only messages encoded or
+/// decoded successfully are counted, bytes are the plain text application
+/// data. last_activity_us is set from the registered SpdmTime, or by
+/// SpdmSession::touch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmSessionStats {
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub bytes_encrypted: u64,
+    pub bytes_decrypted: u64,
+    /// KEY_UPDATE activated with the new key, each direction counts.
+    pub key_update_count: u32,
+    pub last_activity_us: Option<u64>,
+}
+
+impl SpdmSessionStats {
+    fn record_sent(&mut self, result: &SpdmResult<usize>, size: usize) {
+        if result.is_ok() {
+            self.messages_sent = self.messages_sent.saturating_add(1);
+            self.bytes_encrypted = self.bytes_encrypted.saturating_add(size as u64);
+            self.record_activity();
+        }
+    }
+
+    fn record_received(&mut self, result: &SpdmResult<usize>) {
+        if let Ok(size) = result {
+            self.messages_received = self.messages_received.saturating_add(1);
+            self.bytes_decrypted = self.bytes_decrypted.saturating_add(*size as u64);
+            self.record_activity();
+        }
+    }
+
+    fn record_activity(&mut self) {
+        if time::is_registered() {
+            self.last_activity_us = Some(time::now_us());
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SpdmSessionTransportParam {
     pub sequence_number_count: u8,
@@ -114,6 +250,7 @@ pub struct SpdmSessionRuntimeInfo {
 pub struct SpdmSession {
     session_id: u32,
     use_psk: bool,
+    secret_psk: Option<SpdmSecretPsk>,
     mut_auth_requested: SpdmKeyExchangeMutAuthAttributes,
     session_state: SpdmSessionState,
     crypto_param: SpdmSessionCryptoParam,
@@ -121,11 +258,20 @@ pub struct SpdmSession {
     handshake_secret: SpdmSessionHandshakeSecret,
     application_secret: SpdmSessionAppliationSecret,
     application_secret_backup: SpdmSessionAppliationSecret,
+    // remaining messages accepted with the backup key, per direction
+    request_old_key_window: u8,
+    response_old_key_window: u8,
     transport_param: SpdmSessionTransportParam,
+    decode_stats: SpdmSecuredMessageDecodeStats,
+    stats: SpdmSessionStats,
     pub runtime_info: SpdmSessionRuntimeInfo,
     key_schedule: SpdmKeySchedule,
     slot_id: u8,
     pub heartbeat_period: u8, // valid only when HEARTBEAT cap set
+    // time of the last message received in the session, for the heartbeat watchdog
+    last_message_us: Option<u64>,
+    // SpdmContext use counter value of the last message, for LRU eviction
+    last_used: u64,
     pub secure_spdm_version_sel: u8,
 }
 
@@ -140,17 +286,24 @@ impl SpdmSession {
         SpdmSession {
             session_id: INVALID_SESSION_ID,
             use_psk: false,
+            secret_psk: None,
             session_state: SpdmSessionState::default(),
             crypto_param: SpdmSessionCryptoParam::default(),
             dhe_secret_root: SpdmSessionDheSecretRoot::default(),
             handshake_secret: SpdmSessionHandshakeSecret::default(),
             application_secret: SpdmSessionAppliationSecret::default(),
             application_secret_backup: SpdmSessionAppliationSecret::default(),
+            request_old_key_window: 0,
+            response_old_key_window: 0,
             transport_param: SpdmSessionTransportParam::default(),
+            decode_stats: SpdmSecuredMessageDecodeStats::default(),
+            stats: SpdmSessionStats::default(),
             runtime_info: SpdmSessionRuntimeInfo::default(),
             key_schedule: SpdmKeySchedule::new(),
             slot_id: 0,
             heartbeat_period: 0,
+            last_message_us: None,
+            last_used: 0,
             secure_spdm_version_sel: DMTF_SECURE_SPDM_VERSION_11,
             mut_auth_requested: SpdmKeyExchangeMutAuthAttributes::default(),
         }
@@ -172,19 +325,43 @@ impl SpdmSession {
         self.application_secret.response_direction.sequence_number
     }
 
+    /// Messages which can still be encoded in the current phase, in the
+    /// request direction for the requester, the response one otherwise.
+    /// Secured messages fail with SPDM_STATUS_SEQUENCE_NUMBER_OVERFLOW at 0.
+    pub fn get_remaining_sequence_number_count(&self, is_requester: bool) -> u64 {
+        let secret_param = match (self.session_state, is_requester) {
+            (SpdmSessionState::SpdmSessionHandshaking, true) => {
+                &self.handshake_secret.request_direction
+            }
+            (SpdmSessionState::SpdmSessionHandshaking, false) => {
+                &self.handshake_secret.response_direction
+            }
+            (_, true) => &self.application_secret.request_direction,
+            (_, false) => &self.application_secret.response_direction,
+        };
+        SPDM_MAX_SEQUENCE_NUMBER.saturating_sub(secret_param.sequence_number)
+    }
+
     pub fn set_default(&mut self) {
         self.session_id = INVALID_SESSION_ID;
         self.use_psk = false;
+        self.secret_psk = None;
         self.session_state = SpdmSessionState::default();
         self.crypto_param = SpdmSessionCryptoParam::default();
         self.dhe_secret_root = SpdmSessionDheSecretRoot::default();
         self.handshake_secret = SpdmSessionHandshakeSecret::default();
         self.application_secret = SpdmSessionAppliationSecret::default();
         self.application_secret_backup = SpdmSessionAppliationSecret::default();
+        self.request_old_key_window = 0;
+        self.response_old_key_window = 0;
         self.transport_param = SpdmSessionTransportParam::default();
+        self.decode_stats = SpdmSecuredMessageDecodeStats::default();
+        self.stats = SpdmSessionStats::default();
         self.runtime_info = SpdmSessionRuntimeInfo::default();
         self.key_schedule = SpdmKeySchedule::default();
         self.heartbeat_period = 0;
+        self.last_message_us = None;
+        self.last_used = 0;
         self.secure_spdm_version_sel = DMTF_SECURE_SPDM_VERSION_11;
         self.mut_auth_requested = SpdmKeyExchangeMutAuthAttributes::empty();
     }
@@ -193,6 +370,43 @@ impl SpdmSession {
         self.session_id
     }
 
+    /// Record a message received in the session at now_us.
+    pub fn touch(&mut self, now_us: u64) {
+        self.last_message_us = Some(now_us);
+        self.stats.last_activity_us = Some(now_us);
+    }
+
+    pub fn get_last_message_us(&self) -> Option<u64> {
+        self.last_message_us
+    }
+
+    pub fn set_last_used(&mut self, last_used: u64) {
+        self.last_used = last_used;
+    }
+
+    pub fn get_last_used(&self) -> u64 {
+        self.last_used
+    }
+
+    /// True if no message was received within twice the HeartbeatPeriod,
+    /// the window after which the responder terminates the session.
+    pub fn is_heartbeat_expired(&self, now_us: u64) -> bool {
+        if self.heartbeat_period == 0 {
+            return false;
+        }
+        let window_us = 2 * self.heartbeat_period as u64 * SPDM_HEARTBEAT_PERIOD_UNIT_US;
+        match self.last_message_us {
+            Some(last_message_us) => now_us.saturating_sub(last_message_us) > window_us,
+            None => false,
+        }
+    }
+
+    /// Key derivation steps of this session, cleared on teardown.
+    #[cfg(feature = "key-schedule-trace")]
+    pub fn get_key_schedule_trace(&self) -> &SpdmKeyScheduleTrace {
+        self.key_schedule.get_trace()
+    }
+
     pub fn setup(&mut self, session_id: u32) -> SpdmResult {
         if self.session_id == INVALID_SESSION_ID {
             self.set_default();
@@ -220,6 +434,11 @@ impl SpdmSession {
         self.use_psk
     }
 
+    /// PSK callbacks used instead of the globally registered ones.
+    pub fn set_secret_psk(&mut self, secret_psk: Option<SpdmSecretPsk>) {
+        self.secret_psk = secret_psk;
+    }
+
     pub fn set_slot_id(&mut self, slot_id: u8) {
         self.slot_id = slot_id;
     }
@@ -293,6 +512,35 @@ impl SpdmSession {
         self.transport_param.max_random_count = max_random_count;
     }
 
+    pub fn get_decode_stats(&self) -> &SpdmSecuredMessageDecodeStats {
+        &self.decode_stats
+    }
+
+    pub fn get_stats(&self) -> &SpdmSessionStats {
+        &self.stats
+    }
+
+    pub fn get_base_hash_algo(&self) -> SpdmBaseHashAlgo {
+        self.crypto_param.base_hash_algo
+    }
+
+    pub fn get_dhe_algo(&self) -> SpdmDheAlgo {
+        self.crypto_param.dhe_algo
+    }
+
+    pub fn get_aead_algo(&self) -> SpdmAeadAlgo {
+        self.crypto_param.aead_algo
+    }
+
+    pub fn get_key_schedule_algo(&self) -> SpdmKeyScheduleAlgo {
+        self.crypto_param.key_schedule_algo
+    }
+
+    /// Secured message version (DSP0277) selected for the session.
+    pub fn get_secure_spdm_version(&self) -> u8 {
+        self.secure_spdm_version_sel
+    }
+
     pub fn set_session_state(&mut self, session_state: SpdmSessionState) {
         self.session_state = session_state;
     }
@@ -330,6 +578,7 @@ impl SpdmSession {
                     Some(&self.dhe_secret_root.handshake_secret)
                 },
                 self.runtime_info.psk_hint.as_ref(),
+                self.secret_psk.as_ref(),
                 th1.as_ref(),
             ) {
             rhs
@@ -351,6 +600,7 @@ impl SpdmSession {
                     Some(&self.dhe_secret_root.handshake_secret)
                 },
                 self.runtime_info.psk_hint.as_ref(),
+                self.secret_psk.as_ref(),
                 th1.as_ref(),
             ) {
             rhs
@@ -467,6 +717,7 @@ impl SpdmSession {
                     Some(&self.dhe_secret_root.master_secret)
                 },
                 self.runtime_info.psk_hint.as_ref(),
+                self.secret_psk.as_ref(),
                 th2.as_ref(),
             ) {
             rds
@@ -484,6 +735,7 @@ impl SpdmSession {
                     Some(&self.dhe_secret_root.master_secret)
                 },
                 self.runtime_info.psk_hint.as_ref(),
+                self.secret_psk.as_ref(),
                 th2.as_ref(),
             ) {
             rds
@@ -560,6 +812,7 @@ impl SpdmSession {
                     Some(&self.dhe_secret_root.master_secret)
                 },
                 self.runtime_info.psk_hint.as_ref(),
+                self.secret_psk.as_ref(),
             ) {
             ems
         } else {
@@ -587,6 +840,7 @@ impl SpdmSession {
                 self.application_secret.request_data_secret.clone();
             self.application_secret_backup.request_direction =
                 self.application_secret.request_direction.clone();
+            self.request_old_key_window = SPDM_KEY_UPDATE_OLD_KEY_WINDOW;
 
             self.application_secret.request_data_secret = if let Some(us) =
                 self.key_schedule.derive_update_secret(
@@ -636,6 +890,7 @@ impl SpdmSession {
                 self.application_secret.response_data_secret.clone();
             self.application_secret_backup.response_direction =
                 self.application_secret.response_direction.clone();
+            self.response_old_key_window = SPDM_KEY_UPDATE_OLD_KEY_WINDOW;
 
             self.application_secret.response_data_secret = if let Some(us) =
                 self.key_schedule.derive_update_secret(
@@ -695,25 +950,37 @@ impl SpdmSession {
                     self.application_secret_backup.request_data_secret.clone();
                 self.application_secret.request_direction =
                     self.application_secret_backup.request_direction.clone();
+                self.request_old_key_window = 0;
             }
             if update_responder {
                 self.application_secret.response_data_secret =
                     self.application_secret_backup.response_data_secret.clone();
                 self.application_secret.response_direction =
                     self.application_secret_backup.response_direction.clone();
+                self.response_old_key_window = 0;
             }
         } else {
+            self.stats.key_update_count = self
+                .stats
+                .key_update_count
+                .saturating_add(update_requester as u32 + update_responder as u32);
+            // The backup key of the direction is kept until its window is
+            // used up, see decode_application_msg.
             if update_requester {
                 self.application_secret_backup.request_data_secret =
                     SpdmDirectionDataSecretStruct::default();
-                self.application_secret_backup.request_direction =
-                    SpdmSessionSecretParam::default();
+                if self.request_old_key_window == 0 {
+                    self.application_secret_backup.request_direction =
+                        SpdmSessionSecretParam::default();
+                }
             }
             if update_responder {
                 self.application_secret_backup.response_data_secret =
                     SpdmDirectionDataSecretStruct::default();
-                self.application_secret_backup.response_direction =
-                    SpdmSessionSecretParam::default();
+                if self.response_old_key_window == 0 {
+                    self.application_secret_backup.response_direction =
+                        SpdmSessionSecretParam::default();
+                }
             }
         }
         Ok(())
@@ -792,13 +1059,45 @@ impl SpdmSession {
         )
     }
 
+    /// Derive out_size bytes of keying material bound to this session for an
+    /// upper-layer protocol, from the export master secret with label and context.
+    /// Available once the data secret is generated.
+    pub fn export_keying_material(
+        &self,
+        spdm_version: SpdmVersion,
+        label: &[u8],
+        context: &[u8],
+        out_size: u16,
+    ) -> SpdmResult<SpdmHkdfOutputKeyingMaterial> {
+        if self.application_secret.export_master_secret.data_size == 0 {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        if out_size == 0
+            || out_size as usize > SPDM_MAX_HKDF_OKM_SIZE
+            || label.len() + context.len() > MAX_SPDM_EXPORTER_LABEL_CONTEXT_SIZE
+        {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        self.key_schedule
+            .derive_exported_key(
+                spdm_version,
+                self.crypto_param.base_hash_algo,
+                &self.application_secret.export_master_secret,
+                label,
+                context,
+                out_size,
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+    }
+
     pub fn encode_spdm_secured_message(
         &mut self,
         app_buffer: &[u8],
         secured_buffer: &mut [u8],
         is_requester: bool,
     ) -> SpdmResult<usize> {
-        match self.session_state {
+        let r = match self.session_state {
             SpdmSessionState::SpdmSessionNotStarted => Err(SPDM_STATUS_INVALID_STATE_LOCAL),
             SpdmSessionState::SpdmSessionHandshaking => {
                 if is_requester {
@@ -807,7 +1106,9 @@ impl SpdmSession {
                         secured_buffer,
                         &self.handshake_secret.request_direction,
                     );
-                    self.handshake_secret.request_direction.sequence_number += 1;
+                    self.handshake_secret
+                        .request_direction
+                        .advance_sequence_number();
                     r
                 } else {
                     let r = self.encode_msg(
@@ -815,7 +1116,9 @@ impl SpdmSession {
                         secured_buffer,
                         &self.handshake_secret.response_direction,
                     );
-                    self.handshake_secret.response_direction.sequence_number += 1;
+                    self.handshake_secret
+                        .response_direction
+                        .advance_sequence_number();
                     r
                 }
             }
@@ -826,7 +1129,9 @@ impl SpdmSession {
                         secured_buffer,
                         &self.application_secret.request_direction,
                     );
-                    self.application_secret.request_direction.sequence_number += 1;
+                    self.application_secret
+                        .request_direction
+                        .advance_sequence_number();
                     r
                 } else {
                     let r = self.encode_msg(
@@ -834,12 +1139,16 @@ impl SpdmSession {
                         secured_buffer,
                         &self.application_secret.response_direction,
                     );
-                    self.application_secret.response_direction.sequence_number += 1;
+                    self.application_secret
+                        .response_direction
+                        .advance_sequence_number();
                     r
                 }
             }
             _ => panic!("unknown session state"),
-        }
+        };
+        self.stats.record_sent(&r, app_buffer.len());
+        r
     }
 
     pub fn decode_spdm_secured_message(
@@ -848,7 +1157,7 @@ impl SpdmSession {
         app_buffer: &mut [u8],
         is_requester: bool,
     ) -> SpdmResult<usize> {
-        match self.session_state {
+        let r = match self.session_state {
             SpdmSessionState::SpdmSessionNotStarted => Err(SPDM_STATUS_INVALID_STATE_LOCAL),
             SpdmSessionState::SpdmSessionHandshaking => {
                 if is_requester {
@@ -857,7 +1166,9 @@ impl SpdmSession {
                         app_buffer,
                         &self.handshake_secret.request_direction,
                     );
-                    self.handshake_secret.request_direction.sequence_number += 1;
+                    self.handshake_secret
+                        .request_direction
+                        .advance_sequence_number();
                     r
                 } else {
                     let r = self.decode_msg(
@@ -865,31 +1176,82 @@ impl SpdmSession {
                         app_buffer,
                         &self.handshake_secret.response_direction,
                     );
-                    self.handshake_secret.response_direction.sequence_number += 1;
+                    self.handshake_secret
+                        .response_direction
+                        .advance_sequence_number();
                     r
                 }
             }
             SpdmSessionState::SpdmSessionEstablished => {
-                if is_requester {
-                    let r = self.decode_msg(
-                        secured_buffer,
-                        app_buffer,
-                        &self.application_secret.request_direction,
-                    );
-                    self.application_secret.request_direction.sequence_number += 1;
-                    r
-                } else {
-                    let r = self.decode_msg(
-                        secured_buffer,
-                        app_buffer,
-                        &self.application_secret.response_direction,
-                    );
-                    self.application_secret.response_direction.sequence_number += 1;
-                    r
-                }
+                self.decode_application_msg(secured_buffer, app_buffer, is_requester)
             }
             _ => Err(SPDM_STATUS_INVALID_STATE_LOCAL),
+        };
+        self.decode_stats.record(&r);
+        self.stats.record_received(&r);
+        r
+    }
+
+    /// Decode with the current data key. Within the window after a
+    /// KEY_UPDATE, a message failing with the current key is retried with
+    /// the old key, which keeps its own sequence number.
+    fn decode_application_msg(
+        &mut self,
+        secured_buffer: &[u8],
+        app_buffer: &mut [u8],
+        is_requester: bool,
+    ) -> SpdmResult<usize> {
+        let (current, old, old_key_window) = if is_requester {
+            (
+                &self.application_secret.request_direction,
+                &self.application_secret_backup.request_direction,
+                self.request_old_key_window,
+            )
+        } else {
+            (
+                &self.application_secret.response_direction,
+                &self.application_secret_backup.response_direction,
+                self.response_old_key_window,
+            )
+        };
+
+        let mut r = self.decode_msg(secured_buffer, app_buffer, current);
+        let mut old_key_used = false;
+        if r.is_err() && old_key_window != 0 {
+            let r_old = self.decode_msg(secured_buffer, app_buffer, old);
+            if r_old.is_ok() {
+                old_key_used = true;
+                r = r_old;
+            }
+        }
+
+        let (current, old, old_key_window) = if is_requester {
+            (
+                &mut self.application_secret.request_direction,
+                &mut self.application_secret_backup.request_direction,
+                &mut self.request_old_key_window,
+            )
+        } else {
+            (
+                &mut self.application_secret.response_direction,
+                &mut self.application_secret_backup.response_direction,
+                &mut self.response_old_key_window,
+            )
+        };
+        if old_key_used {
+            debug!("!!! decode with old key: {:?} !!!\n", old.sequence_number);
+            old.advance_sequence_number();
+        } else {
+            current.advance_sequence_number();
+        }
+        if *old_key_window != 0 {
+            *old_key_window -= 1;
+            if *old_key_window == 0 {
+                *old = SpdmSessionSecretParam::default();
+            }
         }
+
+        r
     }
 
     fn encode_msg(
@@ -902,15 +1264,33 @@ impl SpdmSession {
         let aead_algo = self.crypto_param.aead_algo;
         let transport_param = &self.transport_param;
 
-        let cipher_text_size = app_buffer.len() + 2;
         let tag_size = aead_algo.get_tag_size() as usize;
+        let aad_size = 6 + transport_param.sequence_number_count as usize;
+
+        // DSP0277 random data after the application data hides the message size,
+        // its size is picked at random up to the transport limit and what fits.
+        let room = secured_buffer
+            .len()
+            .saturating_sub(aad_size + tag_size)
+            .min(config::SENDER_BUFFER_SIZE)
+            .checked_sub(2 + app_buffer.len())
+            .ok_or(SPDM_STATUS_BUFFER_TOO_SMALL)?;
+        let max_random_count = room.min(transport_param.max_random_count as usize);
+        let random_count = if max_random_count != 0 {
+            let mut random_count = [0u8; 2];
+            crypto::rand::get_random(&mut random_count)?;
+            u16::from_le_bytes(random_count) as usize % (max_random_count + 1)
+        } else {
+            0
+        };
+        let cipher_text_size = app_buffer.len() + 2 + random_count;
 
         let mut aad_buffer = [0u8; 6 + 8];
         let mut writer = Writer::init(&mut aad_buffer);
         let app_length = app_buffer.len() as u16;
         let length = cipher_text_size as u16 + tag_size as u16;
 
-        if secret_param.sequence_number == 0xFFFFFFFFFFFFFFFFu64 {
+        if secret_param.is_sequence_number_exhausted() {
             return Err(SPDM_STATUS_SEQUENCE_NUMBER_OVERFLOW);
         }
 
@@ -928,8 +1308,7 @@ impl SpdmSession {
         length
             .encode(&mut writer)
             .map_err(|_| SPDM_STATUS_BUFFER_TOO_SMALL)?;
-        let aad_size = writer.used();
-        assert_eq!(aad_size, 6 + transport_param.sequence_number_count as usize);
+        assert_eq!(writer.used(), aad_size);
 
         let mut plain_text_buf = [0; config::SENDER_BUFFER_SIZE];
         let mut writer = Writer::init(&mut plain_text_buf);
@@ -939,6 +1318,11 @@ impl SpdmSession {
         let head_size = writer.used();
         assert_eq!(head_size, 2);
         plain_text_buf[head_size..(head_size + app_buffer.len())].copy_from_slice(app_buffer);
+        if random_count != 0 {
+            crypto::rand::get_random(
+                &mut plain_text_buf[(head_size + app_buffer.len())..cipher_text_size],
+            )?;
+        }
 
         let mut tag_buffer = [0u8; 16];
 
@@ -983,37 +1367,37 @@ impl SpdmSession {
         let transport_param = &self.transport_param;
         let tag_size = aead_algo.get_tag_size() as usize;
 
-        if secret_param.sequence_number == 0xFFFFFFFFFFFFFFFFu64 {
+        if secret_param.is_sequence_number_exhausted() {
             return Err(SPDM_STATUS_SEQUENCE_NUMBER_OVERFLOW);
         }
 
         let mut reader = Reader::init(secured_buffer);
-        let read_session_id = u32::read(&mut reader).ok_or(SPDM_STATUS_DECODE_AEAD_FAIL)?;
+        let read_session_id = u32::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_SIZE)?;
         if read_session_id != session_id {
             error!("session_id mismatch!\n");
-            return Err(SPDM_STATUS_DECODE_AEAD_FAIL);
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
         }
         if transport_param.sequence_number_count != 0 {
             let sequence_number = secret_param.sequence_number;
             for i in 0..transport_param.sequence_number_count {
-                let s = u8::read(&mut reader).ok_or(SPDM_STATUS_DECODE_AEAD_FAIL)?;
+                let s = u8::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_SIZE)?;
                 if s != ((sequence_number >> (8 * i)) & 0xFF) as u8 {
                     info!("sequence_num mismatch!\n");
-                    return Err(SPDM_STATUS_DECODE_AEAD_FAIL);
+                    return Err(SPDM_STATUS_SEQUENCE_NUMBER_MISMATCH);
                 }
             }
         }
-        let length = u16::read(&mut reader).ok_or(SPDM_STATUS_DECODE_AEAD_FAIL)?;
+        let length = u16::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_SIZE)?;
         let aad_size = reader.used();
         assert_eq!(aad_size, 6 + transport_param.sequence_number_count as usize);
 
         // secure buffer might be bigger for alignment
         if secured_buffer.len() < length as usize + aad_size {
-            return Err(SPDM_STATUS_DECODE_AEAD_FAIL);
+            return Err(SPDM_STATUS_INVALID_MSG_SIZE);
         }
 
         if (length as usize) < tag_size {
-            return Err(SPDM_STATUS_DECODE_AEAD_FAIL);
+            return Err(SPDM_STATUS_INVALID_MSG_SIZE);
         }
 
         let cipher_text_size = length as usize - tag_size;
@@ -1040,12 +1424,13 @@ impl SpdmSession {
             &secured_buffer
                 [(aad_size + cipher_text_size)..(aad_size + cipher_text_size + tag_size)],
             &mut plain_text_buf[..cipher_text_size],
-        )?;
+        )
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
 
         let mut reader = Reader::init(&plain_text_buf);
-        let app_length = u16::read(&mut reader).ok_or(SPDM_STATUS_DECODE_AEAD_FAIL)? as usize;
+        let app_length = u16::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_SIZE)? as usize;
         if ret_plain_text_size < app_length + 2 {
-            return Err(SPDM_STATUS_DECODE_AEAD_FAIL);
+            return Err(SPDM_STATUS_INVALID_MSG_SIZE);
         }
 
         app_buffer[..app_length].copy_from_slice(&plain_text_buf[2..(app_length + 2)]);
@@ -1169,6 +1554,374 @@ mod tests_session {
             .is_ok();
         assert!(status);
     }
+
+    #[test]
+    fn test_case0_sequence_number_exhausted() {
+        let mut session = SpdmSession::default();
+        let session_id = 4294901758u32;
+        let send_buffer = [100u8; 0x40];
+        let mut encoded_send_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let mut decoded_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+
+        session.setup(session_id).unwrap();
+        session.set_crypto_param(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmDheAlgo::SECP_384_R1,
+            SpdmAeadAlgo::AES_256_GCM,
+            SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+        );
+        session.set_session_state(SpdmSessionState::SpdmSessionHandshaking);
+        assert!(session
+            .set_dhe_secret(
+                SpdmVersion::SpdmVersion12,
+                SpdmDheFinalKeyStruct {
+                    data_size: 5,
+                    data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+                }
+            )
+            .is_ok());
+        assert!(session
+            .generate_handshake_secret(
+                SpdmVersion::SpdmVersion12,
+                &SpdmDigestStruct {
+                    data_size: 5,
+                    data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+                }
+            )
+            .is_ok());
+
+        // the last usable sequence number
+        session.handshake_secret.request_direction.sequence_number = SPDM_MAX_SEQUENCE_NUMBER - 1;
+        assert_eq!(session.get_remaining_sequence_number_count(true), 1);
+        let used = session
+            .encode_spdm_secured_message(&send_buffer, &mut encoded_send_buffer, true)
+            .unwrap();
+        assert_eq!(session.get_remaining_sequence_number_count(true), 0);
+
+        // exhausted, nothing is encoded and the sequence number does not wrap
+        assert_eq!(
+            session.encode_spdm_secured_message(&send_buffer, &mut encoded_send_buffer, true),
+            Err(SPDM_STATUS_SEQUENCE_NUMBER_OVERFLOW)
+        );
+        assert_eq!(
+            session.handshake_secret.request_direction.sequence_number,
+            SPDM_MAX_SEQUENCE_NUMBER
+        );
+
+        // the peer decodes the last message, then refuses any further one
+        session.handshake_secret.request_direction.sequence_number = SPDM_MAX_SEQUENCE_NUMBER - 1;
+        assert_eq!(
+            session.decode_spdm_secured_message(
+                &encoded_send_buffer[..used],
+                &mut decoded_buffer,
+                true
+            ),
+            Ok(send_buffer.len())
+        );
+        assert_eq!(
+            session.decode_spdm_secured_message(
+                &encoded_send_buffer[..used],
+                &mut decoded_buffer,
+                true
+            ),
+            Err(SPDM_STATUS_SEQUENCE_NUMBER_OVERFLOW)
+        );
+        assert_eq!(
+            session.handshake_secret.request_direction.sequence_number,
+            SPDM_MAX_SEQUENCE_NUMBER
+        );
+    }
+
+    #[test]
+    fn test_case0_decode_with_old_key_after_key_update() {
+        let mut session = SpdmSession::default();
+        let app_buffer = [100u8; 16];
+        let mut secured_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let mut decoded_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+
+        session.setup(4294901758u32).unwrap();
+        session.set_crypto_param(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmDheAlgo::SECP_384_R1,
+            SpdmAeadAlgo::AES_256_GCM,
+            SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+        );
+        session.transport_param.sequence_number_count = 1;
+        let digest = SpdmDigestStruct {
+            data_size: 48,
+            data: Box::new([100u8; SPDM_MAX_HASH_SIZE]),
+        };
+        assert!(session
+            .set_dhe_secret(
+                SpdmVersion::SpdmVersion12,
+                SpdmDheFinalKeyStruct {
+                    data_size: 48,
+                    data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+                }
+            )
+            .is_ok());
+        assert!(session
+            .generate_handshake_secret(SpdmVersion::SpdmVersion12, &digest)
+            .is_ok());
+        assert!(session
+            .generate_data_secret(SpdmVersion::SpdmVersion12, &digest)
+            .is_ok());
+        session.set_session_state(SpdmSessionState::SpdmSessionEstablished);
+
+        let old_key = session.application_secret.request_direction.clone();
+        assert!(session
+            .create_data_secret_update(SpdmVersion::SpdmVersion12, true, false)
+            .is_ok());
+
+        // In flight with the old key.
+        let used = session
+            .encode_msg(&app_buffer, &mut secured_buffer, &old_key)
+            .unwrap();
+        assert_eq!(
+            session.decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true),
+            Ok(app_buffer.len())
+        );
+        assert_eq!(decoded_buffer[..app_buffer.len()], app_buffer);
+
+        for _ in 1..SPDM_KEY_UPDATE_OLD_KEY_WINDOW {
+            let used = session
+                .encode_spdm_secured_message(&app_buffer, &mut secured_buffer, true)
+                .unwrap();
+            assert!(session
+                .decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true)
+                .is_ok());
+        }
+
+        // The window is used up.
+        let mut old_key = old_key;
+        old_key.sequence_number = 1;
+        let used = session
+            .encode_msg(&app_buffer, &mut secured_buffer, &old_key)
+            .unwrap();
+        assert!(session
+            .decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true)
+            .is_err());
+    }
+    #[test]
+    fn test_case0_encode_random_data() {
+        let mut session = SpdmSession::default();
+        let app_buffer = [100u8; 16];
+        let mut secured_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let mut decoded_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+
+        session.setup(4294901758u32).unwrap();
+        session.set_crypto_param(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmDheAlgo::SECP_384_R1,
+            SpdmAeadAlgo::AES_256_GCM,
+            SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+        );
+        session.set_transport_param(1, 32);
+        assert!(session
+            .set_dhe_secret(
+                SpdmVersion::SpdmVersion12,
+                SpdmDheFinalKeyStruct {
+                    data_size: 48,
+                    data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+                }
+            )
+            .is_ok());
+        assert!(session
+            .generate_handshake_secret(
+                SpdmVersion::SpdmVersion12,
+                &SpdmDigestStruct {
+                    data_size: 48,
+                    data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+                }
+            )
+            .is_ok());
+        session.set_session_state(SpdmSessionState::SpdmSessionHandshaking);
+
+        let min_size = 6 + 1 + 2 + app_buffer.len() + 16;
+        let mut sizes = [false; 32 + 1];
+        for _ in 0..64 {
+            let used = session
+                .encode_spdm_secured_message(&app_buffer, &mut secured_buffer, true)
+                .unwrap();
+            assert!(used >= min_size && used <= min_size + 32);
+            sizes[used - min_size] = true;
+            assert_eq!(
+                session.decode_spdm_secured_message(
+                    &secured_buffer[..used],
+                    &mut decoded_buffer,
+                    true
+                ),
+                Ok(app_buffer.len())
+            );
+            assert_eq!(decoded_buffer[..app_buffer.len()], app_buffer);
+        }
+        assert!(sizes.iter().filter(|&&seen| seen).count() > 1);
+
+        // No room for random data.
+        let used = session
+            .encode_spdm_secured_message(&app_buffer, &mut secured_buffer[..min_size], true)
+            .unwrap();
+        assert_eq!(used, min_size);
+        assert!(session
+            .encode_spdm_secured_message(&app_buffer, &mut secured_buffer[..min_size - 1], true)
+            .is_err());
+    }
+    #[test]
+    fn test_case0_decode_error_classification() {
+        let mut session = SpdmSession::default();
+        let app_buffer = [100u8; 16];
+        let mut secured_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let mut decoded_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+
+        session.setup(4294901758u32).unwrap();
+        session.set_crypto_param(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmDheAlgo::SECP_384_R1,
+            SpdmAeadAlgo::AES_256_GCM,
+            SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+        );
+        session.transport_param.sequence_number_count = 1;
+        let digest = SpdmDigestStruct {
+            data_size: 48,
+            data: Box::new([100u8; SPDM_MAX_HASH_SIZE]),
+        };
+        assert!(session
+            .set_dhe_secret(
+                SpdmVersion::SpdmVersion12,
+                SpdmDheFinalKeyStruct {
+                    data_size: 48,
+                    data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+                }
+            )
+            .is_ok());
+        assert!(session
+            .generate_handshake_secret(SpdmVersion::SpdmVersion12, &digest)
+            .is_ok());
+        assert!(session
+            .generate_data_secret(SpdmVersion::SpdmVersion12, &digest)
+            .is_ok());
+        session.set_session_state(SpdmSessionState::SpdmSessionEstablished);
+        let mut key = session.application_secret.request_direction.clone();
+
+        // tampered tag, sequence number 0
+        let used = session
+            .encode_msg(&app_buffer, &mut secured_buffer, &key)
+            .unwrap();
+        secured_buffer[used - 1] ^= 0xFF;
+        assert_eq!(
+            session.decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        assert_eq!(session.get_decode_stats().consecutive_mac_failure_count, 1);
+
+        key.sequence_number = 1;
+        let used = session
+            .encode_msg(&app_buffer, &mut secured_buffer, &key)
+            .unwrap();
+        assert_eq!(
+            session.decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true),
+            Ok(app_buffer.len())
+        );
+        assert_eq!(session.get_decode_stats().consecutive_mac_failure_count, 0);
+
+        // replayed, sequence number 2 is expected
+        assert_eq!(
+            session.decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true),
+            Err(SPDM_STATUS_SEQUENCE_NUMBER_MISMATCH)
+        );
+
+        key.sequence_number = 3;
+        let used = session
+            .encode_msg(&app_buffer, &mut secured_buffer, &key)
+            .unwrap();
+        secured_buffer[0] ^= 0xFF;
+        assert_eq!(
+            session.decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true),
+            Err(SPDM_STATUS_INVALID_MSG_FIELD)
+        );
+        secured_buffer[0] ^= 0xFF;
+        assert_eq!(
+            session.decode_spdm_secured_message(&secured_buffer[..4], &mut decoded_buffer, true),
+            Err(SPDM_STATUS_INVALID_MSG_SIZE)
+        );
+
+        assert_eq!(
+            *session.get_decode_stats(),
+            SpdmSecuredMessageDecodeStats {
+                mac_failure_count: 1,
+                consecutive_mac_failure_count: 0,
+                sequence_number_mismatch_count: 1,
+                session_id_mismatch_count: 1,
+                length_error_count: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_case0_session_stats() {
+        let mut session = SpdmSession::default();
+        let app_buffer = [100u8; 16];
+        let mut secured_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let mut decoded_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+
+        session.setup(4294901758u32).unwrap();
+        session.set_crypto_param(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmDheAlgo::SECP_384_R1,
+            SpdmAeadAlgo::AES_256_GCM,
+            SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+        );
+        session.transport_param.sequence_number_count = 1;
+        let digest = SpdmDigestStruct {
+            data_size: 48,
+            data: Box::new([100u8; SPDM_MAX_HASH_SIZE]),
+        };
+        assert!(session
+            .set_dhe_secret(
+                SpdmVersion::SpdmVersion12,
+                SpdmDheFinalKeyStruct {
+                    data_size: 48,
+                    data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+                }
+            )
+            .is_ok());
+        assert!(session
+            .generate_handshake_secret(SpdmVersion::SpdmVersion12, &digest)
+            .is_ok());
+        assert!(session
+            .generate_data_secret(SpdmVersion::SpdmVersion12, &digest)
+            .is_ok());
+        session.set_session_state(SpdmSessionState::SpdmSessionEstablished);
+        assert_eq!(session.get_aead_algo(), SpdmAeadAlgo::AES_256_GCM);
+        let mut peer = session.clone();
+
+        let used = session
+            .encode_spdm_secured_message(&app_buffer, &mut secured_buffer, true)
+            .unwrap();
+        assert_eq!(
+            peer.decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true),
+            Ok(app_buffer.len())
+        );
+        // a replay is not counted
+        assert!(peer
+            .decode_spdm_secured_message(&secured_buffer[..used], &mut decoded_buffer, true)
+            .is_err());
+        assert!(session
+            .activate_data_secret_update(SpdmVersion::SpdmVersion12, true, true, true)
+            .is_ok());
+
+        assert_eq!(session.get_stats().messages_sent, 1);
+        assert_eq!(session.get_stats().bytes_encrypted, app_buffer.len() as u64);
+        assert_eq!(session.get_stats().key_update_count, 2);
+        assert_eq!(peer.get_stats().messages_received, 1);
+        assert_eq!(peer.get_stats().bytes_decrypted, app_buffer.len() as u64);
+
+        session.touch(10);
+        assert_eq!(session.get_stats().last_activity_us, Some(10));
+        session.set_default();
+        assert_eq!(*session.get_stats(), SpdmSessionStats::default());
+    }
+
     #[test]
     #[should_panic]
     fn test_case0_setup() {
@@ -1185,4 +1938,64 @@ mod tests_session {
         let session_id = 4294901758u32;
         let _ = session.teardown(session_id).is_err();
     }
+
+    #[test]
+    fn test_case0_export_keying_material() {
+        let mut session = SpdmSession::default();
+        session.crypto_param.base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        let version = SpdmVersion::SpdmVersion12;
+
+        assert_eq!(
+            session
+                .export_keying_material(version, b"ide key", &[0u8; 4], 32)
+                .err(),
+            Some(SPDM_STATUS_INVALID_STATE_LOCAL)
+        );
+
+        session.application_secret.export_master_secret =
+            SpdmExportMasterSecretStruct::from(&[0x5au8; 48][..]);
+        let key0 = session
+            .export_keying_material(version, b"ide key", &[0u8; 4], 32)
+            .unwrap();
+        assert_eq!(key0.as_ref().len(), 32);
+        let key1 = session
+            .export_keying_material(version, b"ide key", &[0u8; 4], 32)
+            .unwrap();
+        assert_eq!(key0.as_ref(), key1.as_ref());
+        let key2 = session
+            .export_keying_material(version, b"ide key", &[1u8; 4], 32)
+            .unwrap();
+        assert_ne!(key0.as_ref(), key2.as_ref());
+        let key3 = session
+            .export_keying_material(version, b"ide iv", &[0u8; 4], 32)
+            .unwrap();
+        assert_ne!(key0.as_ref(), key3.as_ref());
+
+        // the same bytes split differently between label and context
+        let key4 = session
+            .export_keying_material(version, b"ab", b"c", 32)
+            .unwrap();
+        let key5 = session
+            .export_keying_material(version, b"a", b"bc", 32)
+            .unwrap();
+        assert_ne!(key4.as_ref(), key5.as_ref());
+
+        assert_eq!(
+            session
+                .export_keying_material(version, b"ide key", &[0u8; 4], 49)
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+        assert_eq!(
+            session
+                .export_keying_material(
+                    version,
+                    b"ide key",
+                    &[0u8; MAX_SPDM_EXPORTER_LABEL_CONTEXT_SIZE],
+                    32
+                )
+                .err(),
+            Some(SPDM_STATUS_INVALID_PARAMETER)
+        );
+    }
 }
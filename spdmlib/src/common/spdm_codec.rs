@@ -107,6 +107,11 @@ impl SpdmCodec for SpdmMeasurementRecordStructure {
         _context: &mut SpdmContext,
         bytes: &mut Writer,
     ) -> Result<usize, SpdmStatus> {
+        if self.measurement_record_length.get() as usize > config::MAX_SPDM_MEASUREMENT_RECORD_SIZE
+        {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+
         let mut cnt = 0usize;
         cnt += self
             .number_of_blocks
@@ -0,0 +1,138 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! CT and RDT helpers. Both are 2^exponent microseconds (DSP0274), RDT
+//! additionally scaled by RDTM in ERROR(ResponseNotReady).
+
+/// 2^25 us, about 34 seconds. A larger peer CT exponent is accepted but
+/// warned about, since the requester waits 2 * CT for a crypto response.
+pub const SPDM_MAX_SANE_CT_EXPONENT: u8 = 25;
+
+/// Return 2^exponent microseconds, saturating.
+pub fn exponent_to_us(exponent: u8) -> u64 {
+    1u64.checked_shl(exponent as u32).unwrap_or(u64::MAX)
+}
+
+/// Return the smallest exponent with 2^exponent >= latency_us.
+pub fn exponent_from_latency(latency_us: u64) -> u8 {
+    if latency_us <= 1 {
+        return 0;
+    }
+    (64 - (latency_us - 1).leading_zeros()) as u8
+}
+
+/// Return (rdt_exponent, rdtm) of ERROR(ResponseNotReady) with
+/// rdtm * 2^rdt_exponent >= latency_us, keeping rdtm as small as possible
+/// without losing more than a factor of two.
+pub fn rdt_from_latency(latency_us: u64) -> (u8, u8) {
+    let exponent = exponent_from_latency(latency_us);
+    // one exponent step is the granularity, the multiplier rounds it up
+    let rdt_exponent = exponent.saturating_sub(1);
+    let unit = exponent_to_us(rdt_exponent);
+    let rdtm = (latency_us / unit + (latency_us % unit != 0) as u64).clamp(1, u8::MAX as u64) as u8;
+    (rdt_exponent, rdtm)
+}
+
+/// Requester timeout for a crypto response, 2 * CT, saturating.
+pub fn ct_timeout_us(ct_exponent: u8) -> usize {
+    let timeout = exponent_to_us(ct_exponent).saturating_mul(2);
+    if timeout > usize::MAX as u64 {
+        usize::MAX
+    } else {
+        timeout as usize
+    }
+}
+
+/// Return false, with a warning, if the peer CT exponent exceeds
+/// SPDM_MAX_SANE_CT_EXPONENT.
+pub fn check_peer_ct_exponent(ct_exponent: u8) -> bool {
+    if ct_exponent > SPDM_MAX_SANE_CT_EXPONENT {
+        warn!(
+            "peer ct_exponent {} makes a crypto response timeout of {} us\n",
+            ct_exponent,
+            ct_timeout_us(ct_exponent)
+        );
+        return false;
+    }
+    true
+}
+
+/// Latencies of the cryptographic request handlers, e.g. CHALLENGE or signed
+/// GET_MEASUREMENTS, see ResponderContext::crypto_latency. They size
+/// rsp_ct_exponent and the RDT of SpdmResponseReadiness::NotReadyAsRecorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmLatencyStats {
+    pub count: u32,
+    pub max_us: u64,
+    pub total_us: u64,
+}
+
+impl SpdmLatencyStats {
+    pub fn record(&mut self, latency_us: u64) {
+        self.count = self.count.saturating_add(1);
+        self.max_us = self.max_us.max(latency_us);
+        self.total_us = self.total_us.saturating_add(latency_us);
+    }
+
+    pub fn average_us(&self) -> u64 {
+        if self.count == 0 {
+            0
+        } else {
+            self.total_us / self.count as u64
+        }
+    }
+
+    /// CT exponent covering the worst latency seen, with margin_percent
+    /// headroom, e.g. 50 for 1.5 times the worst latency.
+    pub fn suggest_ct_exponent(&self, margin_percent: u32) -> u8 {
+        let latency = self.max_us.saturating_mul(100 + margin_percent as u64) / 100;
+        exponent_from_latency(latency)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_exponent_from_latency() {
+        assert_eq!(exponent_from_latency(0), 0);
+        assert_eq!(exponent_from_latency(1), 0);
+        assert_eq!(exponent_from_latency(2), 1);
+        assert_eq!(exponent_from_latency(1000), 10);
+        assert_eq!(exponent_from_latency(1024), 10);
+        assert_eq!(exponent_from_latency(1025), 11);
+        assert_eq!(exponent_from_latency(u64::MAX), 64);
+        assert_eq!(exponent_to_us(10), 1024);
+        assert_eq!(exponent_to_us(64), u64::MAX);
+        assert_eq!(ct_timeout_us(20), 2 << 20);
+        assert_eq!(ct_timeout_us(255), usize::MAX);
+    }
+
+    #[test]
+    fn test_case0_rdt_from_latency() {
+        for latency in [1u64, 3, 100, 1000, 1025, 5_000_000] {
+            let (rdt_exponent, rdtm) = rdt_from_latency(latency);
+            let rdt = exponent_to_us(rdt_exponent) * rdtm as u64;
+            assert!(rdt >= latency);
+            assert!(rdt < latency * 2 + 2);
+        }
+        assert_eq!(rdt_from_latency(u64::MAX), (63, 2));
+    }
+
+    #[test]
+    fn test_case0_latency_stats() {
+        let mut stats = SpdmLatencyStats::default();
+        assert_eq!(stats.average_us(), 0);
+        stats.record(1000);
+        stats.record(3000);
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.max_us, 3000);
+        assert_eq!(stats.average_us(), 2000);
+        assert_eq!(stats.suggest_ct_exponent(0), 12);
+        assert_eq!(stats.suggest_ct_exponent(50), 13);
+        assert!(check_peer_ct_exponent(SPDM_MAX_SANE_CT_EXPONENT));
+        assert!(!check_peer_ct_exponent(SPDM_MAX_SANE_CT_EXPONENT + 1));
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Export of the transcripts signed by the responder, so that a verifier can
+//! check CHALLENGE_AUTH and MEASUREMENTS signatures on its own.
+//!
+//! Without hashed-transcript-data the messages are exported, with it only the
+//! running hash is kept and its finalized value is exported. The transcripts
+//! are reset once a signature is verified, the requester keeps the one of the
+//! last verified signature in SpdmRuntimeInfo.
+
+use super::*;
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER};
+#[cfg(feature = "hashed-transcript-data")]
+use crate::error::{SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_INVALID_STATE_LOCAL};
+use crate::protocol::*;
+
+#[derive(Debug, Clone)]
+pub enum SpdmTranscript {
+    /// The concatenated messages.
+    Data(Vec<u8>),
+    /// The hash of the concatenated messages.
+    Hash(SpdmDigestStruct),
+}
+
+impl SpdmTranscript {
+    /// The hash which is signed, whichever way the transcript was exported.
+    pub fn hash(&self, base_hash_algo: SpdmBaseHashAlgo) -> Option<SpdmDigestStruct> {
+        match self {
+            SpdmTranscript::Data(data) => crypto::hash::hash_all(base_hash_algo, data),
+            SpdmTranscript::Hash(digest) => Some(digest.clone()),
+        }
+    }
+}
+
+impl<'a> SpdmContext<'a> {
+    /// VCA, GET_VERSION to ALGORITHMS. It is kept even with
+    /// hashed-transcript-data since every other transcript starts with it.
+    pub fn export_message_a(&self) -> &[u8] {
+        self.runtime_info.message_a.as_ref()
+    }
+
+    /// M1/M2 = A || B || C, signed by CHALLENGE_AUTH.
+    #[cfg(not(feature = "hashed-transcript-data"))]
+    pub fn export_message_m1m2(&self) -> SpdmResult<SpdmTranscript> {
+        let mut data = Vec::new();
+        data.extend_from_slice(self.runtime_info.message_a.as_ref());
+        data.extend_from_slice(self.runtime_info.message_b.as_ref());
+        data.extend_from_slice(self.runtime_info.message_c.as_ref());
+        Ok(SpdmTranscript::Data(data))
+    }
+
+    #[cfg(feature = "hashed-transcript-data")]
+    pub fn export_message_m1m2(&self) -> SpdmResult<SpdmTranscript> {
+        let ctx = self
+            .runtime_info
+            .digest_context_m1m2
+            .as_ref()
+            .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+        finalize_copy(ctx)
+    }
+
+    /// L1/L2 = A || M for SPDM 1.2 and later, M before, signed by
+    /// MEASUREMENTS. session_id selects the transcript of the session.
+    #[cfg(not(feature = "hashed-transcript-data"))]
+    pub fn export_message_l1l2(&self, session_id: Option<u32>) -> SpdmResult<SpdmTranscript> {
+        let message_m = match session_id {
+            None => &self.runtime_info.message_m,
+            Some(session_id) => {
+                &self
+                    .get_immutable_session_via_id(session_id)
+                    .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
+                    .runtime_info
+                    .message_m
+            }
+        };
+        let mut data = Vec::new();
+        if self.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion12.get_u8() {
+            data.extend_from_slice(self.runtime_info.message_a.as_ref());
+        }
+        data.extend_from_slice(message_m.as_ref());
+        Ok(SpdmTranscript::Data(data))
+    }
+
+    #[cfg(feature = "hashed-transcript-data")]
+    pub fn export_message_l1l2(&self, session_id: Option<u32>) -> SpdmResult<SpdmTranscript> {
+        let ctx = match session_id {
+            None => &self.runtime_info.digest_context_l1l2,
+            Some(session_id) => {
+                &self
+                    .get_immutable_session_via_id(session_id)
+                    .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
+                    .runtime_info
+                    .digest_context_l1l2
+            }
+        };
+        finalize_copy(ctx.as_ref().ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?)
+    }
+}
+
+#[cfg(feature = "hashed-transcript-data")]
+fn finalize_copy(ctx: &SpdmHashCtx) -> SpdmResult<SpdmTranscript> {
+    let ctx = crypto::hash::hash_ctx_dup(ctx).ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+    let digest = crypto::hash::hash_ctx_finalize(ctx).ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+    Ok(SpdmTranscript::Hash(digest))
+}
@@ -0,0 +1,235 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+extern crate alloc;
+use alloc::boxed::Box;
+
+use crate::common::SpdmTransportEncap;
+use crate::error::{SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_UNSUPPORTED_CAP};
+use codec::{Codec, Reader, Writer};
+
+enum_builder! {
+    @U8
+    EnumName: SpdmTransportType;
+    EnumVal{
+        // DSP0275
+        SpdmTransportMctp => 0x1,
+        // PCIe DOE, DSP0276 for secured messages
+        SpdmTransportPciDoe => 0x2,
+        // DSP0287
+        SpdmTransportTcp => 0x3,
+        // DSP0286
+        SpdmTransportStorage => 0x4,
+        // CXL.io DOE, same data objects as PCIe DOE
+        SpdmTransportCxlDoe => 0x5
+    }
+}
+impl Default for SpdmTransportType {
+    fn default() -> SpdmTransportType {
+        SpdmTransportType::Unknown(0)
+    }
+}
+
+/// Number of transport bindings a registry can hold.
+pub const MAX_SPDM_TRANSPORT_FACTORY_COUNT: usize = 8;
+
+/// Create the encap of one connection. connection_id is transport specific,
+/// e.g. the storage connection id, and is ignored by the other bindings.
+/// Return SPDM_STATUS_INVALID_PARAMETER for an invalid connection_id.
+pub type SpdmTransportFactory =
+    fn(connection_id: u8) -> SpdmResult<Box<dyn SpdmTransportEncap + Send>>;
+
+/// Transport bindings supported by one binary, to pick the encap of each
+/// connection at runtime. The created encap outlives the context built on it:
+///
+/// let mut encap = registry.create(transport_type, 0)?;
+/// let mut context = RequesterContext::new(device_io, &mut *encap, ...);
+#[derive(Clone, Copy, Default)]
+pub struct SpdmTransportRegistry {
+    entries: [Option<(SpdmTransportType, SpdmTransportFactory)>; MAX_SPDM_TRANSPORT_FACTORY_COUNT],
+}
+
+impl SpdmTransportRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register factory for transport_type, replacing a previous one.
+    pub fn register(
+        &mut self,
+        transport_type: SpdmTransportType,
+        factory: SpdmTransportFactory,
+    ) -> SpdmResult {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .flatten()
+            .find(|(t, _)| *t == transport_type)
+        {
+            entry.1 = factory;
+            return Ok(());
+        }
+        let slot = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.is_none())
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        *slot = Some((transport_type, factory));
+        Ok(())
+    }
+
+    /// Return true if a factory was registered for transport_type.
+    pub fn unregister(&mut self, transport_type: SpdmTransportType) -> bool {
+        for entry in self.entries.iter_mut() {
+            if matches!(entry, Some((t, _)) if *t == transport_type) {
+                *entry = None;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn is_registered(&self, transport_type: SpdmTransportType) -> bool {
+        self.entries
+            .iter()
+            .flatten()
+            .any(|(t, _)| *t == transport_type)
+    }
+
+    pub fn iter_types(&self) -> impl Iterator<Item = SpdmTransportType> + '_ {
+        self.entries.iter().flatten().map(|(t, _)| *t)
+    }
+
+    pub fn create(
+        &self,
+        transport_type: SpdmTransportType,
+        connection_id: u8,
+    ) -> SpdmResult<Box<dyn SpdmTransportEncap + Send>> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|(t, _)| *t == transport_type)
+            .ok_or(SPDM_STATUS_UNSUPPORTED_CAP)
+            .and_then(|(_, factory)| factory(connection_id))
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    /// Prepends a 4 byte header holding the message size.
+    struct DummyTransportEncap;
+
+    impl SpdmTransportEncap for DummyTransportEncap {
+        fn encap(
+            &mut self,
+            spdm_buffer: &[u8],
+            transport_buffer: &mut [u8],
+            _secured_message: bool,
+        ) -> SpdmResult<usize> {
+            let size = spdm_buffer.len() + 4;
+            if transport_buffer.len() < size {
+                return Err(SPDM_STATUS_BUFFER_FULL);
+            }
+            transport_buffer[..4].copy_from_slice(&(spdm_buffer.len() as u32).to_le_bytes());
+            transport_buffer[4..size].copy_from_slice(spdm_buffer);
+            Ok(size)
+        }
+
+        fn decap(
+            &mut self,
+            transport_buffer: &[u8],
+            spdm_buffer: &mut [u8],
+        ) -> SpdmResult<(usize, bool)> {
+            let size = transport_buffer.len() - 4;
+            spdm_buffer[..size].copy_from_slice(&transport_buffer[4..]);
+            Ok((size, false))
+        }
+
+        fn encap_app(
+            &mut self,
+            spdm_buffer: &[u8],
+            app_buffer: &mut [u8],
+            _is_app_message: bool,
+        ) -> SpdmResult<usize> {
+            app_buffer[..spdm_buffer.len()].copy_from_slice(spdm_buffer);
+            Ok(spdm_buffer.len())
+        }
+
+        fn decap_app(
+            &mut self,
+            app_buffer: &[u8],
+            spdm_buffer: &mut [u8],
+        ) -> SpdmResult<(usize, bool)> {
+            spdm_buffer[..app_buffer.len()].copy_from_slice(app_buffer);
+            Ok((app_buffer.len(), false))
+        }
+
+        fn get_header_size(&mut self, _secured_message: bool) -> usize {
+            4
+        }
+
+        fn get_sequence_number_count(&mut self) -> u8 {
+            0
+        }
+
+        fn get_max_random_count(&mut self) -> u16 {
+            0
+        }
+    }
+
+    fn dummy_factory(_connection_id: u8) -> SpdmResult<Box<dyn SpdmTransportEncap + Send>> {
+        Ok(Box::new(DummyTransportEncap))
+    }
+
+    #[test]
+    fn test_case0_transport_registry() {
+        let mut registry = SpdmTransportRegistry::new();
+        assert!(registry
+            .create(SpdmTransportType::SpdmTransportPciDoe, 0)
+            .is_err());
+
+        assert!(registry
+            .register(SpdmTransportType::SpdmTransportPciDoe, dummy_factory)
+            .is_ok());
+        assert!(registry.is_registered(SpdmTransportType::SpdmTransportPciDoe));
+        let mut encap = registry
+            .create(SpdmTransportType::SpdmTransportPciDoe, 0)
+            .unwrap();
+        let transport_buffer = &mut [0u8; 16];
+        assert_eq!(encap.encap(&[1, 2, 3, 4], transport_buffer, false), Ok(8));
+        assert_eq!(&transport_buffer[..8], &[4, 0, 0, 0, 1, 2, 3, 4]);
+        assert_eq!(
+            registry
+                .create(SpdmTransportType::SpdmTransportMctp, 0)
+                .err(),
+            Some(SPDM_STATUS_UNSUPPORTED_CAP)
+        );
+
+        // re-registering a type keeps one entry
+        assert!(registry
+            .register(SpdmTransportType::SpdmTransportPciDoe, dummy_factory)
+            .is_ok());
+        for transport_type in 0x10..0x10 + MAX_SPDM_TRANSPORT_FACTORY_COUNT as u8 - 1 {
+            assert!(registry
+                .register(SpdmTransportType::Unknown(transport_type), dummy_factory)
+                .is_ok());
+        }
+        assert_eq!(
+            registry.register(SpdmTransportType::SpdmTransportMctp, dummy_factory),
+            Err(SPDM_STATUS_BUFFER_FULL)
+        );
+        assert_eq!(
+            registry.iter_types().count(),
+            MAX_SPDM_TRANSPORT_FACTORY_COUNT
+        );
+
+        assert!(registry.unregister(SpdmTransportType::SpdmTransportPciDoe));
+        assert!(!registry.unregister(SpdmTransportType::SpdmTransportPciDoe));
+        assert!(registry
+            .register(SpdmTransportType::SpdmTransportMctp, dummy_factory)
+            .is_ok());
+    }
+}
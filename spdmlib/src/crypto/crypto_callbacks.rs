@@ -30,6 +30,19 @@ pub struct SpdmHash {
     pub hash_ctx_dup_cb: fn(ctx: usize) -> Option<usize>,
 }
 
+/// Hash engine of the device, keeping transcripts in hardware instead of
+/// the software contexts of SpdmHash. The contexts are opaque handles of the
+/// engine. hash_ctx_init_cb returns None for an unsupported algorithm or when
+/// no engine context is free, a software context is used then.
+#[cfg(feature = "hashed-transcript-data")]
+#[derive(Clone)]
+pub struct SpdmHwHash {
+    pub hash_ctx_init_cb: fn(base_hash_algo: SpdmBaseHashAlgo) -> Option<usize>,
+    pub hash_ctx_update_cb: fn(ctx: usize, data: &[u8]) -> SpdmResult,
+    pub hash_ctx_finalize_cb: fn(ctx: usize) -> Option<SpdmDigestStruct>,
+    pub hash_ctx_dup_cb: fn(ctx: usize) -> Option<usize>,
+}
+
 #[derive(Clone)]
 pub struct SpdmHmac {
     pub hmac_cb:
@@ -81,6 +94,17 @@ pub struct SpdmAsymVerify {
     ) -> SpdmResult,
 }
 
+#[derive(Clone)]
+pub struct SpdmPubKeyVerify {
+    pub verify_cb: fn(
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        public_key_der: &[u8],
+        data: &[u8],
+        signature: &SpdmSignatureStruct,
+    ) -> SpdmResult,
+}
+
 #[derive(Clone)]
 pub struct SpdmHkdf {
     pub hkdf_extract_cb: fn(
@@ -102,7 +126,12 @@ type GetCertFromCertChainCb = fn(cert_chain: &[u8], index: isize) -> SpdmResult<
 pub struct SpdmCertOperation {
     pub get_cert_from_cert_chain_cb: GetCertFromCertChainCb,
 
-    pub verify_cert_chain_cb: fn(cert_chain: &[u8]) -> SpdmResult,
+    /// time is in seconds since unix epoch, None for the current time.
+    pub verify_cert_chain_cb: fn(cert_chain: &[u8], time: Option<u64>) -> SpdmResult,
+
+    /// Verify the signature of a single DER certificate with the public key
+    /// of issuer_cert, and its validity period at time.
+    pub verify_cert_cb: fn(cert: &[u8], issuer_cert: &[u8], time: Option<u64>) -> SpdmResult,
 }
 
 type GenerateKeyPairCb =
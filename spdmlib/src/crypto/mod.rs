@@ -7,23 +7,36 @@ mod crypto_callbacks;
 mod x509v3;
 pub use x509v3::*;
 
+/// OpenSSL implementations of the callbacks for host tools, registered with
+/// spdm_openssl::register_handles().
+#[cfg(feature = "openssl")]
+pub mod spdm_openssl;
 #[cfg(feature = "spdm-ring")]
 mod spdm_ring;
+/// RustCrypto implementations of the callbacks, the defaults when
+/// spdm-ring is not enabled.
+#[cfg(feature = "spdm-rustcrypto")]
+pub mod spdm_rustcrypto;
 
 pub use crypto_callbacks::{
     SpdmAead, SpdmAsymVerify, SpdmCertOperation, SpdmCryptoRandom, SpdmDhe, SpdmDheKeyExchange,
-    SpdmHash, SpdmHkdf, SpdmHmac,
+    SpdmHash, SpdmHkdf, SpdmHmac, SpdmPubKeyVerify,
 };
 
 #[cfg(feature = "hashed-transcript-data")]
 pub use self::hash::SpdmHashCtx;
+#[cfg(feature = "hashed-transcript-data")]
+pub use crypto_callbacks::SpdmHwHash;
 
 use conquer_once::spin::OnceCell;
 
 static CRYPTO_HASH: OnceCell<SpdmHash> = OnceCell::uninit();
+#[cfg(feature = "hashed-transcript-data")]
+static CRYPTO_HW_HASH: OnceCell<SpdmHwHash> = OnceCell::uninit();
 static CRYPTO_HMAC: OnceCell<SpdmHmac> = OnceCell::uninit();
 static CRYPTO_AEAD: OnceCell<SpdmAead> = OnceCell::uninit();
 static CRYPTO_ASYM_VERIFY: OnceCell<SpdmAsymVerify> = OnceCell::uninit();
+static CRYPTO_PUB_KEY_VERIFY: OnceCell<SpdmPubKeyVerify> = OnceCell::uninit();
 static CRYPTO_DHE: OnceCell<SpdmDhe> = OnceCell::uninit();
 static CRYPTO_CERT_OPERATION: OnceCell<SpdmCertOperation> = OnceCell::uninit();
 static CRYPTO_HKDF: OnceCell<SpdmHkdf> = OnceCell::uninit();
@@ -34,9 +47,9 @@ pub mod hash {
     use crate::crypto::SpdmHash;
     use crate::protocol::{SpdmBaseHashAlgo, SpdmDigestStruct};
 
-    // -ring -transcript
+    // -ring -rustcrypto -transcript
     #[cfg(all(
-        not(any(feature = "spdm-ring")),
+        not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")),
         not(feature = "hashed-transcript-data")
     ))]
     static DEFAULT: SpdmHash = SpdmHash {
@@ -47,6 +60,13 @@ pub mod hash {
     // +ring -transcript
     #[cfg(all(feature = "spdm-ring", not(feature = "hashed-transcript-data")))]
     use super::spdm_ring::hash_impl::DEFAULT;
+    // +rustcrypto -transcript
+    #[cfg(all(
+        feature = "spdm-rustcrypto",
+        not(feature = "spdm-ring"),
+        not(feature = "hashed-transcript-data")
+    ))]
+    use super::spdm_rustcrypto::hash_impl::DEFAULT;
 
     // +-ring +transcript
     #[cfg(feature = "hashed-transcript-data")]
@@ -66,62 +86,115 @@ pub mod hash {
     #[cfg(feature = "hashed-transcript-data")]
     mod hash_ext {
         use super::{SpdmBaseHashAlgo, SpdmDigestStruct, CRYPTO_HASH};
+        use crate::crypto::{SpdmHwHash, CRYPTO_HW_HASH};
         use crate::error::SpdmResult;
+
+        /// A software context of SpdmHash, or an engine context of SpdmHwHash.
         #[derive(Ord, PartialEq, PartialOrd, Eq, Debug, Default)]
-        pub struct SpdmHashCtx(usize);
+        pub struct SpdmHashCtx {
+            handle: usize,
+            hw: bool,
+        }
+
+        impl SpdmHashCtx {
+            /// True if the context lives in the hash engine.
+            pub fn is_hw(&self) -> bool {
+                self.hw
+            }
+        }
 
+        /// Prefer hash_ctx_dup, which fails when the engine has no context
+        /// left. The clone is an invalid context then, its update and
+        /// finalization fail.
         impl Clone for SpdmHashCtx {
             fn clone(&self) -> Self {
-                hash_ctx_dup(self).expect("Out of resource")
+                hash_ctx_dup(self).unwrap_or_default()
             }
         }
 
         impl Drop for SpdmHashCtx {
             fn drop(&mut self) {
-                if self.0 != 0 {
-                    hash_ctx_finalize(SpdmHashCtx(self.0));
+                if self.handle != 0 {
+                    hash_ctx_finalize(SpdmHashCtx {
+                        handle: self.handle,
+                        hw: self.hw,
+                    });
                 }
             }
         }
 
+        /// Register the hash engine, new contexts are created in it first.
+        pub fn register_hw(context: SpdmHwHash) -> bool {
+            CRYPTO_HW_HASH.try_init_once(|| context).is_ok()
+        }
+
         pub fn hash_ctx_init(base_hash_algo: SpdmBaseHashAlgo) -> Option<SpdmHashCtx> {
-            let ret = (CRYPTO_HASH
+            if let Some(hw_hash) = CRYPTO_HW_HASH.get() {
+                if let Some(handle) = (hw_hash.hash_ctx_init_cb)(base_hash_algo) {
+                    return Some(SpdmHashCtx { handle, hw: true });
+                }
+            }
+            let handle = (CRYPTO_HASH
                 .try_get_or_init(|| DEFAULT.clone())
                 .ok()?
                 .hash_ctx_init_cb)(base_hash_algo)?;
-            Some(SpdmHashCtx(ret))
+            Some(SpdmHashCtx { handle, hw: false })
         }
 
         pub fn hash_ctx_update(ctx: &mut SpdmHashCtx, data: &[u8]) -> SpdmResult {
-            use crate::error::SPDM_STATUS_INVALID_STATE_LOCAL;
+            use crate::error::{SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_INVALID_STATE_LOCAL};
 
+            if ctx.handle == 0 {
+                return Err(SPDM_STATUS_CRYPTO_ERROR);
+            }
+            if ctx.hw {
+                let hw_hash = CRYPTO_HW_HASH
+                    .get()
+                    .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+                return (hw_hash.hash_ctx_update_cb)(ctx.handle, data);
+            }
             (CRYPTO_HASH
                 .try_get_or_init(|| DEFAULT.clone())
                 .map_err(|_| SPDM_STATUS_INVALID_STATE_LOCAL)?
-                .hash_ctx_update_cb)(ctx.0, data)
+                .hash_ctx_update_cb)(ctx.handle, data)
         }
 
         pub fn hash_ctx_finalize(mut ctx: SpdmHashCtx) -> Option<SpdmDigestStruct> {
-            let handle = ctx.0;
-            ctx.0 = 0;
+            let handle = ctx.handle;
+            ctx.handle = 0;
+            if handle == 0 {
+                return None;
+            }
+            if ctx.hw {
+                return (CRYPTO_HW_HASH.get()?.hash_ctx_finalize_cb)(handle);
+            }
             (CRYPTO_HASH
                 .try_get_or_init(|| DEFAULT.clone())
                 .ok()?
                 .hash_ctx_finalize_cb)(handle)
         }
 
+        /// The copy stays in the engine of ctx, it fails if the engine has
+        /// no context left.
         pub fn hash_ctx_dup(ctx: &SpdmHashCtx) -> Option<SpdmHashCtx> {
-            let ret = (CRYPTO_HASH
+            if ctx.handle == 0 {
+                return None;
+            }
+            if ctx.hw {
+                let handle = (CRYPTO_HW_HASH.get()?.hash_ctx_dup_cb)(ctx.handle)?;
+                return Some(SpdmHashCtx { handle, hw: true });
+            }
+            let handle = (CRYPTO_HASH
                 .try_get_or_init(|| DEFAULT.clone())
                 .expect("Functions should be registered before using")
-                .hash_ctx_dup_cb)(ctx.0)?;
-            Some(SpdmHashCtx(ret))
+                .hash_ctx_dup_cb)(ctx.handle)?;
+            Some(SpdmHashCtx { handle, hw: false })
         }
 
-        // - ring +transcript
-        #[cfg(not(feature = "spdm-ring"))]
+        // - ring - rustcrypto +transcript
+        #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
         use super::SpdmHash;
-        #[cfg(not(feature = "spdm-ring"))]
+        #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
         pub static DEFAULT: SpdmHash = SpdmHash {
             hash_all_cb: |_base_hash_algo: SpdmBaseHashAlgo,
                           _data: &[u8]|
@@ -137,11 +210,15 @@ pub mod hash {
         // + ring +transcript
         #[cfg(all(feature = "spdm-ring"))]
         pub use crate::crypto::spdm_ring::hash_impl::DEFAULT;
+
+        // + rustcrypto +transcript
+        #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+        pub use crate::crypto::spdm_rustcrypto::hash_impl::DEFAULT;
     }
 
     #[cfg(feature = "hashed-transcript-data")]
     pub use self::hash_ext::{
-        hash_ctx_dup, hash_ctx_finalize, hash_ctx_init, hash_ctx_update, SpdmHashCtx,
+        hash_ctx_dup, hash_ctx_finalize, hash_ctx_init, hash_ctx_update, register_hw, SpdmHashCtx,
     };
 }
 
@@ -151,7 +228,7 @@ pub mod hmac {
     use crate::error::{SpdmResult, SPDM_STATUS_VERIF_FAIL};
     use crate::protocol::{SpdmBaseHashAlgo, SpdmDigestStruct};
 
-    #[cfg(not(any(feature = "spdm-ring")))]
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
     static DEFAULT: SpdmHmac = SpdmHmac {
         hmac_cb: |_base_hash_algo: SpdmBaseHashAlgo,
                   _key: &[u8],
@@ -166,6 +243,8 @@ pub mod hmac {
 
     #[cfg(feature = "spdm-ring")]
     use super::spdm_ring::hmac_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::hmac_impl::DEFAULT;
 
     pub fn register(context: SpdmHmac) -> bool {
         CRYPTO_HMAC.try_init_once(|| context).is_ok()
@@ -201,7 +280,7 @@ pub mod asym_verify {
     use crate::error::{SpdmResult, SPDM_STATUS_INVALID_STATE_LOCAL};
     use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct};
 
-    #[cfg(not(any(feature = "spdm-ring")))]
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
     static DEFAULT: SpdmAsymVerify = SpdmAsymVerify {
         verify_cb: |_base_hash_algo: SpdmBaseHashAlgo,
                     _base_asym_algo: SpdmBaseAsymAlgo,
@@ -213,6 +292,8 @@ pub mod asym_verify {
 
     #[cfg(feature = "spdm-ring")]
     use super::spdm_ring::asym_verify_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::asym_verify_impl::DEFAULT;
 
     pub fn register(context: SpdmAsymVerify) -> bool {
         CRYPTO_ASYM_VERIFY.try_get_or_init(|| context).is_ok()
@@ -238,6 +319,54 @@ pub mod asym_verify {
     }
 }
 
+/// Signature verification against a provisioned public key (PUB_KEY_ID_CAP)
+/// instead of a certificate chain.
+pub mod pub_key_verify {
+    use super::CRYPTO_PUB_KEY_VERIFY;
+    use crate::crypto::SpdmPubKeyVerify;
+    use crate::error::{SpdmResult, SPDM_STATUS_INVALID_STATE_LOCAL};
+    use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct};
+
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
+    static DEFAULT: SpdmPubKeyVerify = SpdmPubKeyVerify {
+        verify_cb: |_base_hash_algo: SpdmBaseHashAlgo,
+                    _base_asym_algo: SpdmBaseAsymAlgo,
+                    _public_key_der: &[u8],
+                    _data: &[u8],
+                    _signature: &SpdmSignatureStruct|
+         -> SpdmResult { unimplemented!() },
+    };
+
+    #[cfg(feature = "spdm-ring")]
+    use super::spdm_ring::pub_key_verify_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::pub_key_verify_impl::DEFAULT;
+
+    pub fn register(context: SpdmPubKeyVerify) -> bool {
+        CRYPTO_PUB_KEY_VERIFY.try_get_or_init(|| context).is_ok()
+    }
+
+    /// public_key_der is a DER SubjectPublicKeyInfo.
+    pub fn verify(
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        public_key_der: &[u8],
+        data: &[u8],
+        signature: &SpdmSignatureStruct,
+    ) -> SpdmResult {
+        (CRYPTO_PUB_KEY_VERIFY
+            .try_get_or_init(|| DEFAULT.clone())
+            .map_err(|_| SPDM_STATUS_INVALID_STATE_LOCAL)?
+            .verify_cb)(
+            base_hash_algo,
+            base_asym_algo,
+            public_key_der,
+            data,
+            signature,
+        )
+    }
+}
+
 pub mod dhe {
     extern crate alloc;
     use alloc::boxed::Box;
@@ -246,7 +375,7 @@ pub mod dhe {
     use crate::crypto::{SpdmDhe, SpdmDheKeyExchange};
     use crate::protocol::{SpdmDheAlgo, SpdmDheExchangeStruct};
 
-    #[cfg(not(any(feature = "spdm-ring")))]
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
     static DEFAULT: SpdmDhe =
         SpdmDhe {
             generate_key_pair_cb: |_dhe_algo: SpdmDheAlgo| -> Option<(
@@ -256,6 +385,8 @@ pub mod dhe {
         };
     #[cfg(feature = "spdm-ring")]
     use super::spdm_ring::dhe_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::dhe_impl::DEFAULT;
 
     pub fn register(context: SpdmDhe) -> bool {
         CRYPTO_DHE.try_init_once(|| context).is_ok()
@@ -276,16 +407,23 @@ pub mod cert_operation {
     use crate::crypto::SpdmCertOperation;
     use crate::error::{SpdmResult, SPDM_STATUS_INVALID_STATE_LOCAL};
 
-    #[cfg(not(any(feature = "spdm-ring")))]
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
     static DEFAULT: SpdmCertOperation = SpdmCertOperation {
         get_cert_from_cert_chain_cb: |_cert_chain: &[u8],
                                       _index: isize|
          -> SpdmResult<(usize, usize)> { unimplemented!() },
-        verify_cert_chain_cb: |_cert_chain: &[u8]| -> SpdmResult { unimplemented!() },
+        verify_cert_chain_cb: |_cert_chain: &[u8], _time: Option<u64>| -> SpdmResult {
+            unimplemented!()
+        },
+        verify_cert_cb: |_cert: &[u8], _issuer_cert: &[u8], _time: Option<u64>| -> SpdmResult {
+            unimplemented!()
+        },
     };
 
     #[cfg(feature = "spdm-ring")]
     use super::spdm_ring::cert_operation_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::cert_operation_impl::DEFAULT;
 
     pub fn register(context: SpdmCertOperation) -> bool {
         CRYPTO_CERT_OPERATION.try_init_once(|| context).is_ok()
@@ -302,7 +440,25 @@ pub mod cert_operation {
         (CRYPTO_CERT_OPERATION
             .try_get_or_init(|| DEFAULT.clone())
             .map_err(|_| SPDM_STATUS_INVALID_STATE_LOCAL)?
-            .verify_cert_chain_cb)(cert_chain)
+            .verify_cert_chain_cb)(cert_chain, None)
+    }
+
+    /// Verify the chain as if the current time was time, in seconds since
+    /// unix epoch.
+    pub fn verify_cert_chain_at(cert_chain: &[u8], time: u64) -> SpdmResult {
+        (CRYPTO_CERT_OPERATION
+            .try_get_or_init(|| DEFAULT.clone())
+            .map_err(|_| SPDM_STATUS_INVALID_STATE_LOCAL)?
+            .verify_cert_chain_cb)(cert_chain, Some(time))
+    }
+
+    /// Verify one certificate of a chain against its issuer, for chains
+    /// which are not held in memory at once.
+    pub fn verify_cert(cert: &[u8], issuer_cert: &[u8]) -> SpdmResult {
+        (CRYPTO_CERT_OPERATION
+            .try_get_or_init(|| DEFAULT.clone())
+            .map_err(|_| SPDM_STATUS_INVALID_STATE_LOCAL)?
+            .verify_cert_cb)(cert, issuer_cert, None)
     }
 }
 
@@ -314,7 +470,7 @@ pub mod hkdf {
         SpdmHkdfPseudoRandomKey,
     };
 
-    #[cfg(not(any(feature = "spdm-ring")))]
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
     static DEFAULT: SpdmHkdf = SpdmHkdf {
         hkdf_extract_cb: |_hash_algo: SpdmBaseHashAlgo,
                           _salt: &[u8],
@@ -329,6 +485,8 @@ pub mod hkdf {
 
     #[cfg(feature = "spdm-ring")]
     use super::spdm_ring::hkdf_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::hkdf_impl::DEFAULT;
 
     pub fn register(context: SpdmHkdf) -> bool {
         CRYPTO_HKDF.try_init_once(|| context).is_ok()
@@ -364,7 +522,7 @@ pub mod aead {
     use crate::error::{SpdmResult, SPDM_STATUS_INVALID_STATE_LOCAL};
     use crate::protocol::{SpdmAeadAlgo, SpdmAeadIvStruct, SpdmAeadKeyStruct};
 
-    #[cfg(not(any(feature = "spdm-ring")))]
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
     static DEFAULT: SpdmAead = SpdmAead {
         encrypt_cb: |_aead_algo: SpdmAeadAlgo,
                      _key: &SpdmAeadKeyStruct,
@@ -386,6 +544,8 @@ pub mod aead {
 
     #[cfg(feature = "spdm-ring")]
     use super::spdm_ring::aead_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::aead_impl::DEFAULT;
 
     pub fn register(context: SpdmAead) -> bool {
         CRYPTO_AEAD.try_init_once(|| context).is_ok()
@@ -427,13 +587,15 @@ pub mod rand {
     use crate::crypto::SpdmCryptoRandom;
     use crate::error::{SpdmResult, SPDM_STATUS_INVALID_STATE_LOCAL};
 
-    #[cfg(not(any(feature = "spdm-ring")))]
+    #[cfg(not(any(feature = "spdm-ring", feature = "spdm-rustcrypto")))]
     static DEFAULT: SpdmCryptoRandom = SpdmCryptoRandom {
         get_random_cb: |_data: &mut [u8]| -> SpdmResult<usize> { unimplemented!() },
     };
 
     #[cfg(feature = "spdm-ring")]
     use super::spdm_ring::rand_impl::DEFAULT;
+    #[cfg(all(feature = "spdm-rustcrypto", not(feature = "spdm-ring")))]
+    use super::spdm_rustcrypto::rand_impl::DEFAULT;
 
     pub fn register(context: SpdmCryptoRandom) -> bool {
         CRYPTO_RAND.try_init_once(|| context).is_ok()
@@ -0,0 +1,202 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::SpdmAead;
+use crate::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR};
+
+use crate::protocol::{SpdmAeadAlgo, SpdmAeadIvStruct, SpdmAeadKeyStruct};
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use zeroize::Zeroize;
+
+pub static DEFAULT: SpdmAead = SpdmAead {
+    encrypt_cb: encrypt,
+    decrypt_cb: decrypt,
+};
+
+fn cipher(aead_algo: SpdmAeadAlgo) -> SpdmResult<Cipher> {
+    match aead_algo {
+        SpdmAeadAlgo::AES_128_GCM => Ok(Cipher::aes_128_gcm()),
+        SpdmAeadAlgo::AES_256_GCM => Ok(Cipher::aes_256_gcm()),
+        SpdmAeadAlgo::CHACHA20_POLY1305 => Ok(Cipher::chacha20_poly1305()),
+        _ => Err(SPDM_STATUS_CRYPTO_ERROR),
+    }
+}
+
+fn encrypt(
+    aead_algo: SpdmAeadAlgo,
+    key: &SpdmAeadKeyStruct,
+    iv: &SpdmAeadIvStruct,
+    aad: &[u8],
+    plain_text: &[u8],
+    tag: &mut [u8],
+    cipher_text: &mut [u8],
+) -> SpdmResult<(usize, usize)> {
+    check_sizes(aead_algo, key, iv, tag.len())?;
+    let plain_text_size = plain_text.len();
+    if cipher_text.len() != plain_text_size {
+        error!("cipher_text len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+
+    let encrypted = encrypt_aead(
+        cipher(aead_algo)?,
+        key.as_ref(),
+        Some(iv.as_ref()),
+        aad,
+        plain_text,
+        tag,
+    )
+    .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?;
+    if encrypted.len() != plain_text_size {
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+    cipher_text.copy_from_slice(&encrypted);
+    Ok((plain_text_size, tag.len()))
+}
+
+fn decrypt(
+    aead_algo: SpdmAeadAlgo,
+    key: &SpdmAeadKeyStruct,
+    iv: &SpdmAeadIvStruct,
+    aad: &[u8],
+    cipher_text: &[u8],
+    tag: &[u8],
+    plain_text: &mut [u8],
+) -> SpdmResult<usize> {
+    check_sizes(aead_algo, key, iv, tag.len())?;
+    let cipher_text_size = cipher_text.len();
+    if plain_text.len() != cipher_text_size {
+        error!("plain_text len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+
+    // no plain text is returned if the tag does not match
+    let res = decrypt_aead(
+        cipher(aead_algo)?,
+        key.as_ref(),
+        Some(iv.as_ref()),
+        aad,
+        cipher_text,
+        tag,
+    );
+    match res {
+        Ok(mut decrypted) if decrypted.len() == cipher_text_size => {
+            plain_text.copy_from_slice(&decrypted);
+            decrypted.zeroize();
+            Ok(cipher_text_size)
+        }
+        Ok(mut decrypted) => {
+            decrypted.zeroize();
+            plain_text.fill(0);
+            Err(SPDM_STATUS_CRYPTO_ERROR)
+        }
+        Err(_) => {
+            plain_text.fill(0);
+            Err(SPDM_STATUS_CRYPTO_ERROR)
+        }
+    }
+}
+
+fn check_sizes(
+    aead_algo: SpdmAeadAlgo,
+    key: &SpdmAeadKeyStruct,
+    iv: &SpdmAeadIvStruct,
+    tag_size: usize,
+) -> SpdmResult {
+    // unsupported algorithms have no cipher
+    cipher(aead_algo)?;
+    if key.data_size != aead_algo.get_key_size() {
+        error!("key len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+    if iv.data_size != aead_algo.get_iv_size() {
+        error!("iv len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+    if tag_size != aead_algo.get_tag_size() as usize {
+        error!("tag len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+    Ok(())
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::protocol::*;
+
+    #[test]
+    fn test_case0_encrypt_decrypt() {
+        for (aead_algo, key_size) in [
+            (SpdmAeadAlgo::AES_128_GCM, 16),
+            (SpdmAeadAlgo::AES_256_GCM, 32),
+            (SpdmAeadAlgo::CHACHA20_POLY1305, 32),
+        ] {
+            let key = &SpdmAeadKeyStruct {
+                data_size: key_size,
+                data: Box::new([100u8; SPDM_MAX_AEAD_KEY_SIZE]),
+            };
+            let iv = &SpdmAeadIvStruct {
+                data_size: 12,
+                data: Box::new([100u8; SPDM_MAX_AEAD_IV_SIZE]),
+            };
+            let plain_text = &[10u8; 16];
+            let aad = &[20u8; 16];
+            let tag = &mut [0u8; 16];
+            let cipher_text = &mut [0u8; 16];
+
+            assert_eq!(
+                encrypt(aead_algo, key, iv, aad, plain_text, tag, cipher_text),
+                Ok((16, 16))
+            );
+            assert_ne!(cipher_text, plain_text);
+
+            let decrypted = &mut [0u8; 16];
+            assert_eq!(
+                decrypt(aead_algo, key, iv, aad, cipher_text, tag, decrypted),
+                Ok(16)
+            );
+            assert_eq!(decrypted, plain_text);
+
+            tag[0] ^= 0x01;
+            assert!(decrypt(aead_algo, key, iv, aad, cipher_text, tag, decrypted).is_err());
+            assert_eq!(decrypted, &[0u8; 16]);
+        }
+    }
+    #[test]
+    fn test_case1_encrypt() {
+        let key = &SpdmAeadKeyStruct {
+            data_size: 16,
+            data: Box::new([100u8; SPDM_MAX_AEAD_KEY_SIZE]),
+        };
+        let iv = &SpdmAeadIvStruct {
+            data_size: 12,
+            data: Box::new([100u8; SPDM_MAX_AEAD_IV_SIZE]),
+        };
+        let plain_text = &[10u8; 16];
+        let tag = &mut [0u8; 16];
+        let cipher_text = &mut [0u8; 8];
+
+        assert!(encrypt(
+            SpdmAeadAlgo::AES_128_GCM,
+            key,
+            iv,
+            &[],
+            plain_text,
+            tag,
+            cipher_text
+        )
+        .is_err());
+        assert!(encrypt(
+            SpdmAeadAlgo::AES_256_GCM,
+            key,
+            iv,
+            &[],
+            plain_text,
+            tag,
+            &mut [0u8; 16]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::{x509v3, SpdmAsymVerify};
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_CERT};
+use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct};
+
+pub static DEFAULT: SpdmAsymVerify = SpdmAsymVerify {
+    verify_cb: asym_verify,
+};
+
+fn asym_verify(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    public_cert_der: &[u8],
+    data: &[u8],
+    signature: &SpdmSignatureStruct,
+) -> SpdmResult {
+    x509v3::check_cert_chain_format(public_cert_der, base_asym_algo)?;
+
+    let (leaf_begin, leaf_end) =
+        (super::cert_operation_impl::DEFAULT.get_cert_from_cert_chain_cb)(public_cert_der, -1)?;
+    let leaf_cert_info = x509v3::get_cert_info(&public_cert_der[leaf_begin..leaf_end])
+        .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+
+    (super::pub_key_verify_impl::DEFAULT.verify_cb)(
+        base_hash_algo,
+        base_asym_algo,
+        leaf_cert_info.subject_public_key_info,
+        data,
+        signature,
+    )
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::error::SPDM_STATUS_VERIF_FAIL;
+
+    #[test]
+    fn test_case0_asym_verify() {
+        let cert_chain = std::fs::read("../test_key/ecp384/bundle_responder.certchain.der")
+            .expect("unable to read cert chain!");
+        let signature = SpdmSignatureStruct {
+            data_size: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384.get_size(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            asym_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &cert_chain,
+                b"hello, world",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        assert!(asym_verify(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            &cert_chain[..cert_chain.len() - 1],
+            b"hello, world",
+            &signature,
+        )
+        .is_err());
+    }
+}
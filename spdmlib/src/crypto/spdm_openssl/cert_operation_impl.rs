@@ -0,0 +1,134 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::x509v3::{self, get_cert_from_cert_chain};
+use crate::crypto::SpdmCertOperation;
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_CERT, SPDM_STATUS_INVALID_STATE_LOCAL};
+use openssl::x509::X509;
+
+pub static DEFAULT: SpdmCertOperation = SpdmCertOperation {
+    get_cert_from_cert_chain_cb: get_cert_from_cert_chain,
+    verify_cert_chain_cb: verify_cert_chain,
+    verify_cert_cb: verify_cert,
+};
+
+// The first certificate is the trust anchor, each following one is
+// verified against the one before it.
+fn verify_cert_chain(cert_chain: &[u8], time: Option<u64>) -> SpdmResult {
+    x509v3::verify_cert_chain_with(cert_chain, get_timestamp(time)?, verify_cert)
+}
+
+fn get_timestamp(time: Option<u64>) -> SpdmResult<u64> {
+    if let Some(time) = time {
+        return Ok(time);
+    }
+    if let Ok(ds) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        Ok(ds.as_secs())
+    } else {
+        Err(SPDM_STATUS_INVALID_STATE_LOCAL)
+    }
+}
+
+fn verify_cert(cert: &[u8], issuer_cert: &[u8], time: Option<u64>) -> SpdmResult {
+    let timestamp = get_timestamp(time)?;
+    let cert_info = x509v3::get_cert_info(cert)?;
+    if timestamp < cert_info.not_before || timestamp > cert_info.not_after {
+        error!("Cert validity period check Fail\n");
+        return Err(SPDM_STATUS_INVALID_CERT);
+    }
+
+    let cert = X509::from_der(cert).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+    // only the subjectPublicKeyInfo of the issuer is used
+    let issuer_key = X509::from_der(issuer_cert)
+        .and_then(|issuer_cert| issuer_cert.public_key())
+        .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+    match cert.verify(&issuer_key) {
+        Ok(true) => Ok(()),
+        _ => {
+            error!("Cert signature verification Fail\n");
+            Err(SPDM_STATUS_INVALID_CERT)
+        }
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_cert_from_cert_chain() {
+        let cert_chain = std::fs::read("../test_key/ecp384/bundle_responder.certchain.der")
+            .expect("unable to read cert chain!");
+        assert!(get_cert_from_cert_chain(&cert_chain, -1).is_ok());
+        assert!(get_cert_from_cert_chain(&cert_chain, 0).is_ok());
+        assert!(get_cert_from_cert_chain(&cert_chain, 1).is_ok());
+
+        let cert_chain = &mut [0x1u8; 4096];
+        cert_chain[0] = 0x00;
+        cert_chain[1] = 0x00;
+        assert!(get_cert_from_cert_chain(cert_chain, 0).is_err());
+        assert!(get_cert_from_cert_chain(&[0x11u8; 3], 0).is_err());
+    }
+
+    #[test]
+    fn test_verify_cert_chain_case1() {
+        for path in [
+            "../test_key/crypto_chains/ca_selfsigned.crt.der",
+            "../test_key/crypto_chains/bundle_two_level_cert.der",
+            "../test_key/ecp256/bundle_responder.certchain.der",
+            "../test_key/ecp384/bundle_requester.certchain.der",
+            "../test_key/rsa2048/bundle_responder.certchain.der",
+            "../test_key/rsa3072/bundle_responder.certchain.der",
+        ] {
+            let cert_chain = std::fs::read(path).expect("unable to read cert chain!");
+            assert!(verify_cert_chain(&cert_chain, None).is_ok(), "{}", path);
+        }
+
+        let bundle_certs_der = std::fs::read("../test_key/crypto_chains/bundle_cert.der")
+            .expect("unable to read cert chain!");
+        assert!(verify_cert_chain(&bundle_certs_der, None).is_ok());
+
+        // Flipping bits to test signature hash is invalid.
+        let mut cert_chain = bundle_certs_der.clone();
+        // offset 3140 is in signature range.
+        cert_chain[3140] ^= 0xFE;
+        assert!(verify_cert_chain(&cert_chain, None).is_err());
+
+        // Invalid Intermediate cert
+        let mut cert_chain = bundle_certs_der.clone();
+        // Change intermediate cert data
+        cert_chain[1380] = 0xFF;
+        assert!(verify_cert_chain(&cert_chain, None).is_err());
+
+        // expired
+        assert!(verify_cert_chain(&bundle_certs_der, Some(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_verify_cert_case1() {
+        let cert_chain = std::fs::read("../test_key/ecp384/bundle_responder.certchain.der")
+            .expect("unable to read cert chain!");
+        let mut certs = Vec::new();
+        let mut index = 0;
+        while let Ok((begin, end)) = get_cert_from_cert_chain(&cert_chain, index) {
+            certs.push(&cert_chain[begin..end]);
+            index += 1;
+        }
+        assert!(certs.len() > 2);
+
+        // the root is self signed
+        assert!(verify_cert(certs[0], certs[0], None).is_ok());
+        for pair in certs.windows(2) {
+            assert!(verify_cert(pair[1], pair[0], None).is_ok());
+        }
+        assert!(verify_cert(certs[2], certs[0], None).is_err());
+        assert!(verify_cert(certs[1], certs[0], Some(0)).is_err());
+
+        let mut cert = certs[1].to_vec();
+        // the last bytes are in the signature.
+        let last = cert.len() - 4;
+        cert[last] ^= 0xFE;
+        assert!(verify_cert(&cert, certs[0], None).is_err());
+    }
+}
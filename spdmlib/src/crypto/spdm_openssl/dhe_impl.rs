@@ -0,0 +1,119 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::{SpdmDhe, SpdmDheKeyExchange};
+use crate::protocol::{SpdmDheAlgo, SpdmDheExchangeStruct, SpdmDheFinalKeyStruct};
+use bytes::BytesMut;
+use openssl::bn::BigNumContext;
+use openssl::derive::Deriver;
+use openssl::ec::{EcGroup, EcKey, EcPoint, PointConversionForm};
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use zeroize::Zeroize;
+
+pub static DEFAULT: SpdmDhe = SpdmDhe {
+    generate_key_pair_cb: generate_key_pair,
+};
+
+fn generate_key_pair(
+    dhe_algo: SpdmDheAlgo,
+) -> Option<(SpdmDheExchangeStruct, Box<dyn SpdmDheKeyExchange>)> {
+    let curve = match dhe_algo {
+        SpdmDheAlgo::SECP_256_R1 => Nid::X9_62_PRIME256V1,
+        SpdmDheAlgo::SECP_384_R1 => Nid::SECP384R1,
+        _ => return None,
+    };
+    SpdmDheKeyExchangeEc::generate_key_pair(dhe_algo, curve)
+}
+
+struct SpdmDheKeyExchangeEc {
+    group: EcGroup,
+    private_key: PKey<Private>,
+    exchange_size: usize,
+}
+
+impl SpdmDheKeyExchange for SpdmDheKeyExchangeEc {
+    fn compute_final_key(
+        self: Box<Self>,
+        peer_pub_key: &SpdmDheExchangeStruct,
+    ) -> Option<SpdmDheFinalKeyStruct> {
+        if peer_pub_key.data_size as usize != self.exchange_size {
+            return None;
+        }
+        // the exchange data is the uncompressed point without the 0x04 prefix
+        let mut peer_point = Vec::with_capacity(1 + self.exchange_size);
+        peer_point.push(0x4u8);
+        peer_point.extend_from_slice(peer_pub_key.as_ref());
+
+        let mut bn_ctx = BigNumContext::new().ok()?;
+        let point = EcPoint::from_bytes(&self.group, &peer_point, &mut bn_ctx).ok()?;
+        let peer_key = EcKey::from_public_key(&self.group, &point).ok()?;
+        peer_key.check_key().ok()?;
+        let peer_key = PKey::from_ec_key(peer_key).ok()?;
+
+        let mut deriver = Deriver::new(&self.private_key).ok()?;
+        deriver.set_peer(&peer_key).ok()?;
+        let mut shared_secret = deriver.derive_to_vec().ok()?;
+        let final_key = SpdmDheFinalKeyStruct::from(shared_secret.as_slice());
+        shared_secret.zeroize();
+        Some(final_key)
+    }
+}
+
+impl SpdmDheKeyExchangeEc {
+    fn generate_key_pair(
+        dhe_algo: SpdmDheAlgo,
+        curve: Nid,
+    ) -> Option<(SpdmDheExchangeStruct, Box<dyn SpdmDheKeyExchange>)> {
+        let group = EcGroup::from_curve_name(curve).ok()?;
+        let private_key = EcKey::generate(&group).ok()?;
+
+        let mut bn_ctx = BigNumContext::new().ok()?;
+        let public_key_point = private_key
+            .public_key()
+            .to_bytes(&group, PointConversionForm::UNCOMPRESSED, &mut bn_ctx)
+            .ok()?;
+        let exchange_size = dhe_algo.get_size() as usize;
+        if public_key_point.len() != 1 + exchange_size {
+            return None;
+        }
+        let public_key = BytesMut::from(&public_key_point[1..]);
+
+        let res: Box<dyn SpdmDheKeyExchange> = Box::new(Self {
+            group,
+            private_key: PKey::from_ec_key(private_key).ok()?,
+            exchange_size,
+        });
+
+        Some((SpdmDheExchangeStruct::from(public_key), res))
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_dhe() {
+        for dhe_algo in [SpdmDheAlgo::SECP_256_R1, SpdmDheAlgo::SECP_384_R1].iter() {
+            let (exchange1, private1) = generate_key_pair(*dhe_algo).unwrap();
+            let (exchange2, private2) = generate_key_pair(*dhe_algo).unwrap();
+            assert_eq!(exchange1.data_size, dhe_algo.get_size());
+
+            let peer1 = private1.compute_final_key(&exchange2).unwrap();
+            let peer2 = private2.compute_final_key(&exchange1).unwrap();
+
+            assert_eq!(peer1.as_ref(), peer2.as_ref());
+        }
+    }
+    #[test]
+    fn test_case1_dhe() {
+        assert!(generate_key_pair(SpdmDheAlgo::empty()).is_none());
+
+        let (_, private) = generate_key_pair(SpdmDheAlgo::SECP_256_R1).unwrap();
+        assert!(private
+            .compute_final_key(&SpdmDheExchangeStruct::default())
+            .is_none());
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::SpdmHash;
+
+use crate::protocol::{SpdmBaseHashAlgo, SpdmDigestStruct};
+use openssl::hash::{hash, MessageDigest};
+
+#[cfg(not(feature = "hashed-transcript-data"))]
+pub static DEFAULT: SpdmHash = SpdmHash {
+    hash_all_cb: hash_all,
+};
+#[cfg(feature = "hashed-transcript-data")]
+pub static DEFAULT: SpdmHash = SpdmHash {
+    hash_all_cb: hash_all,
+    hash_ctx_init_cb: hash_ext::hash_ctx_init,
+    hash_ctx_update_cb: hash_ext::hash_ctx_update,
+    hash_ctx_finalize_cb: hash_ext::hash_ctx_finalize,
+    hash_ctx_dup_cb: hash_ext::hash_ctx_dup,
+};
+
+pub(super) fn message_digest(base_hash_algo: SpdmBaseHashAlgo) -> Option<MessageDigest> {
+    match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => Some(MessageDigest::sha256()),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => Some(MessageDigest::sha384()),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => Some(MessageDigest::sha512()),
+        _ => None,
+    }
+}
+
+fn hash_all(base_hash_algo: SpdmBaseHashAlgo, data: &[u8]) -> Option<SpdmDigestStruct> {
+    let digest = hash(message_digest(base_hash_algo)?, data).ok()?;
+    Some(SpdmDigestStruct::from(digest.as_ref()))
+}
+
+#[cfg(feature = "hashed-transcript-data")]
+mod hash_ext {
+    use super::*;
+    use crate::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR};
+    use lazy_static::lazy_static;
+    use openssl::hash::Hasher;
+    use spin::Mutex;
+    use std::collections::BTreeMap;
+
+    lazy_static! {
+        static ref HASH_CTX_TABLE: Mutex<BTreeMap<usize, Box<Hasher>>> =
+            Mutex::new(BTreeMap::new());
+    }
+
+    pub fn hash_ctx_update(handle: usize, data: &[u8]) -> SpdmResult {
+        let mut table = HASH_CTX_TABLE.lock();
+        let ctx = table.get_mut(&handle).ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+        ctx.update(data).map_err(|_| SPDM_STATUS_CRYPTO_ERROR)
+    }
+
+    pub fn hash_ctx_finalize(handle: usize) -> Option<SpdmDigestStruct> {
+        let mut ctx = HASH_CTX_TABLE.lock().remove(&handle)?;
+        let digest = ctx.finish().ok()?;
+        Some(SpdmDigestStruct::from(digest.as_ref()))
+    }
+
+    pub fn hash_ctx_dup(handle: usize) -> Option<usize> {
+        let ctx_new = {
+            let table = HASH_CTX_TABLE.lock();
+            let ctx = table.get(&handle)?;
+            ctx.clone()
+        };
+        let new_handle = insert_to_table(ctx_new);
+        Some(new_handle)
+    }
+
+    pub fn hash_ctx_init(base_hash_algo: SpdmBaseHashAlgo) -> Option<usize> {
+        let ctx = Hasher::new(message_digest(base_hash_algo)?).ok()?;
+        Some(insert_to_table(Box::new(ctx)))
+    }
+
+    fn insert_to_table(value: Box<Hasher>) -> usize {
+        let handle_ptr: *const Hasher = &*value;
+        let handle = handle_ptr as usize;
+        HASH_CTX_TABLE.lock().insert(handle, value);
+        handle
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_hash_all() {
+        let base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_512;
+        let data = &mut [0u8; 64];
+
+        let hash_all = hash_all(base_hash_algo, data).unwrap();
+        assert_eq!(hash_all.data_size, 64);
+    }
+    #[test]
+    fn test_case1_hash_all() {
+        let base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_256;
+        let data = &b"hello"[..];
+
+        let hash_all = hash_all(base_hash_algo, data).unwrap();
+        assert_eq!(
+            hash_all.as_ref(),
+            [
+                0x2c, 0xf2, 0x4d, 0xba, 0x5f, 0xb0, 0xa3, 0x0e, 0x26, 0xe8, 0x3b, 0x2a, 0xc5, 0xb9,
+                0xe2, 0x9e, 0x1b, 0x16, 0x1e, 0x5c, 0x1f, 0xa7, 0x42, 0x5e, 0x73, 0x04, 0x33, 0x62,
+                0x93, 0x8b, 0x98, 0x24
+            ]
+        );
+    }
+    #[test]
+    fn test_case2_hash_all() {
+        let base_hash_algo = SpdmBaseHashAlgo::empty();
+        let data = &mut [0u8; 64];
+
+        let hash_all = hash_all(base_hash_algo, data);
+        assert!(hash_all.is_none());
+    }
+    #[cfg(feature = "hashed-transcript-data")]
+    #[test]
+    fn test_case0_hash_update() {
+        let helloworld = hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, b"hello, world").unwrap();
+        let hellobuddy = hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, b"hello, buddy").unwrap();
+        let ctx = hash_ext::hash_ctx_init(SpdmBaseHashAlgo::TPM_ALG_SHA_384).unwrap();
+        hash_ext::hash_ctx_update(ctx, b"hello").unwrap();
+        hash_ext::hash_ctx_update(ctx, b", ").unwrap();
+        let ctx_d = hash_ext::hash_ctx_dup(ctx).unwrap();
+        hash_ext::hash_ctx_update(ctx_d, b"buddy").unwrap();
+        hash_ext::hash_ctx_update(ctx, b"world").unwrap();
+        assert_eq!(
+            hash_ext::hash_ctx_finalize(ctx).unwrap().as_ref(),
+            helloworld.as_ref()
+        );
+        assert_eq!(
+            hash_ext::hash_ctx_finalize(ctx_d).unwrap().as_ref(),
+            hellobuddy.as_ref()
+        );
+        assert!(hash_ext::hash_ctx_finalize(ctx).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::SpdmHkdf;
+use crate::protocol::{
+    SpdmBaseHashAlgo, SpdmHkdfInputKeyingMaterial, SpdmHkdfOutputKeyingMaterial,
+    SpdmHkdfPseudoRandomKey, SPDM_MAX_HKDF_OKM_SIZE,
+};
+use openssl::md::{Md, MdRef};
+use openssl::pkey::Id;
+use openssl::pkey_ctx::{HkdfMode, PkeyCtx};
+
+pub static DEFAULT: SpdmHkdf = SpdmHkdf {
+    hkdf_extract_cb: hkdf_extract,
+    hkdf_expand_cb: hkdf_expand,
+};
+
+fn md(hash_algo: SpdmBaseHashAlgo) -> Option<&'static MdRef> {
+    match hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => Some(Md::sha256()),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => Some(Md::sha384()),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => Some(Md::sha512()),
+        _ => None,
+    }
+}
+
+fn hkdf_ctx(hash_algo: SpdmBaseHashAlgo, mode: HkdfMode, key: &[u8]) -> Option<PkeyCtx<()>> {
+    let md = md(hash_algo)?;
+    let mut ctx = PkeyCtx::new_id(Id::HKDF).ok()?;
+    ctx.derive_init().ok()?;
+    ctx.set_hkdf_md(md).ok()?;
+    ctx.set_hkdf_mode(mode).ok()?;
+    ctx.set_hkdf_key(key).ok()?;
+    Some(ctx)
+}
+
+fn hkdf_extract(
+    hash_algo: SpdmBaseHashAlgo,
+    salt: &[u8],
+    ikm: &SpdmHkdfInputKeyingMaterial,
+) -> Option<SpdmHkdfPseudoRandomKey> {
+    let mut ctx = hkdf_ctx(hash_algo, HkdfMode::EXTRACT_ONLY, ikm.as_ref())?;
+    ctx.set_hkdf_salt(salt).ok()?;
+
+    let mut prk = SpdmHkdfPseudoRandomKey::default();
+    let prk_size = hash_algo.get_size() as usize;
+    if ctx.derive(Some(&mut prk.data[..prk_size])).ok()? != prk_size {
+        return None;
+    }
+    prk.data_size = prk_size as u16;
+    Some(prk)
+}
+
+fn hkdf_expand(
+    hash_algo: SpdmBaseHashAlgo,
+    prk: &SpdmHkdfPseudoRandomKey,
+    info: &[u8],
+    out_size: u16,
+) -> Option<SpdmHkdfOutputKeyingMaterial> {
+    if out_size as usize > SPDM_MAX_HKDF_OKM_SIZE {
+        return None;
+    }
+    // according to https://www.rfc-editor.org/rfc/rfc5869 prk.len is hashlen
+    md(hash_algo)?;
+    if prk.data_size != hash_algo.get_size() {
+        return None;
+    }
+
+    let mut ctx = hkdf_ctx(hash_algo, HkdfMode::EXPAND_ONLY, prk.as_ref())?;
+    ctx.add_hkdf_info(info).ok()?;
+
+    let mut okm = SpdmHkdfOutputKeyingMaterial::default();
+    if ctx.derive(Some(&mut okm.data[..out_size as usize])).ok()? != out_size as usize {
+        return None;
+    }
+    okm.data_size = out_size;
+    Some(okm)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::protocol::SpdmDigestStruct;
+
+    #[test]
+    fn test_case0_hkdf_rfc5869_1() {
+        let ikm = SpdmDigestStruct::from(&[0x0bu8; 22][..]);
+        let salt = &[
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+        ];
+        let info = &[0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+        let okm: &[u8] = &[
+            0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f, 0x64, 0xd0, 0x36,
+            0x2f, 0x2a, 0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a, 0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56,
+            0xec, 0xc4, 0xc5, 0xbf, 0x34, 0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65,
+        ];
+
+        let prk = hkdf_extract(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+            salt,
+            &SpdmHkdfInputKeyingMaterial::SpdmDigest(&ikm),
+        )
+        .unwrap();
+        assert_eq!(prk.data_size, 32);
+        let res = hkdf_expand(SpdmBaseHashAlgo::TPM_ALG_SHA_256, &prk, info, 42).unwrap();
+        assert_eq!(res.as_ref(), okm);
+    }
+    #[test]
+    fn test_case1_hkdf_expand() {
+        let prk = SpdmHkdfPseudoRandomKey::from(&[100u8; 64][..]);
+        let info = &mut [100u8; 64];
+        assert!(hkdf_expand(SpdmBaseHashAlgo::empty(), &prk, info, 64).is_none());
+        // prk of the wrong size
+        assert!(hkdf_expand(SpdmBaseHashAlgo::TPM_ALG_SHA_256, &prk, info, 64).is_none());
+        assert!(hkdf_expand(SpdmBaseHashAlgo::TPM_ALG_SHA_512, &prk, info, 64).is_some());
+    }
+}
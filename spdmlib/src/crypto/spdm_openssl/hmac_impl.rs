@@ -0,0 +1,70 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::hash_impl::message_digest;
+use crate::crypto::SpdmHmac;
+use crate::error::{SpdmResult, SPDM_STATUS_VERIF_FAIL};
+use crate::protocol::{SpdmBaseHashAlgo, SpdmDigestStruct};
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+
+pub static DEFAULT: SpdmHmac = SpdmHmac {
+    hmac_cb: hmac,
+    hmac_verify_cb: hmac_verify,
+};
+
+fn hmac(base_hash_algo: SpdmBaseHashAlgo, key: &[u8], data: &[u8]) -> Option<SpdmDigestStruct> {
+    let md = message_digest(base_hash_algo)?;
+    let key = PKey::hmac(key).ok()?;
+    let mut signer = Signer::new(md, &key).ok()?;
+    signer.update(data).ok()?;
+    let mac = signer.sign_to_vec().ok()?;
+    Some(SpdmDigestStruct::from(mac.as_slice()))
+}
+
+// the comparison is constant time
+fn hmac_verify(
+    base_hash_algo: SpdmBaseHashAlgo,
+    key: &[u8],
+    data: &[u8],
+    hmac: &SpdmDigestStruct,
+) -> SpdmResult {
+    let computed = self::hmac(base_hash_algo, key, data).ok_or(SPDM_STATUS_VERIF_FAIL)?;
+    if computed.data_size == hmac.data_size && openssl::memcmp::eq(computed.as_ref(), hmac.as_ref())
+    {
+        Ok(())
+    } else {
+        Err(SPDM_STATUS_VERIF_FAIL)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_rfc4231_2() {
+        let key = &[0x4a, 0x65, 0x66, 0x65];
+        let data: &[u8] = &[
+            0x77, 0x68, 0x61, 0x74, 0x20, 0x64, 0x6f, 0x20, 0x79, 0x61, 0x20, 0x77, 0x61, 0x6e,
+            0x74, 0x20, 0x66, 0x6f, 0x72, 0x20, 0x6e, 0x6f, 0x74, 0x68, 0x69, 0x6e, 0x67, 0x3f,
+        ][..];
+        let hmac_256: &[u8] = &[
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+            0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+            0x64, 0xec, 0x38, 0x43,
+        ][..];
+
+        let base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_256;
+        let spdm_digest = hmac(base_hash_algo, key, data).unwrap();
+        assert_eq!(spdm_digest.as_ref(), hmac_256);
+        assert!(hmac_verify(base_hash_algo, key, data, &spdm_digest).is_ok());
+
+        let spdm_digest = hmac(SpdmBaseHashAlgo::TPM_ALG_SHA_512, key, data).unwrap();
+        assert_eq!(spdm_digest.data_size, 64);
+        assert!(hmac_verify(base_hash_algo, key, data, &spdm_digest).is_err());
+        assert!(hmac_verify(base_hash_algo, key, &data[1..], &spdm_digest).is_err());
+        assert!(hmac(SpdmBaseHashAlgo::empty(), key, data).is_none());
+    }
+}
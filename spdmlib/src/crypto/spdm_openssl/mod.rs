@@ -0,0 +1,28 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+pub mod aead_impl;
+pub mod asym_verify_impl;
+pub mod cert_operation_impl;
+pub mod dhe_impl;
+pub mod hash_impl;
+pub mod hkdf_impl;
+pub mod hmac_impl;
+pub mod pub_key_verify_impl;
+pub mod rand_impl;
+
+/// Register the OpenSSL implementations of all the crypto callbacks.
+/// Returns false if any callback was already registered.
+pub fn register_handles() -> bool {
+    let mut registered = super::aead::register(aead_impl::DEFAULT.clone());
+    registered &= super::asym_verify::register(asym_verify_impl::DEFAULT.clone());
+    registered &= super::pub_key_verify::register(pub_key_verify_impl::DEFAULT.clone());
+    registered &= super::cert_operation::register(cert_operation_impl::DEFAULT.clone());
+    registered &= super::dhe::register(dhe_impl::DEFAULT.clone());
+    registered &= super::hash::register(hash_impl::DEFAULT.clone());
+    registered &= super::hkdf::register(hkdf_impl::DEFAULT.clone());
+    registered &= super::hmac::register(hmac_impl::DEFAULT.clone());
+    registered &= super::rand::register(rand_impl::DEFAULT.clone());
+    registered
+}
@@ -0,0 +1,175 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::hash_impl::message_digest;
+use crate::crypto::SpdmPubKeyVerify;
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_VERIF_FAIL};
+use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct};
+use openssl::bn::BigNum;
+use openssl::ecdsa::EcdsaSig;
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, PKeyRef, Public};
+use openssl::rsa::Padding;
+use openssl::sign::{RsaPssSaltlen, Verifier};
+
+pub static DEFAULT: SpdmPubKeyVerify = SpdmPubKeyVerify {
+    verify_cb: pub_key_verify,
+};
+
+fn pub_key_verify(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    public_key_der: &[u8],
+    data: &[u8],
+    signature: &SpdmSignatureStruct,
+) -> SpdmResult {
+    if signature.data_size != base_asym_algo.get_size() {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let md = message_digest(base_hash_algo).ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+    let public_key =
+        PKey::public_key_from_der(public_key_der).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+
+    match base_asym_algo {
+        SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256
+        | SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => {
+            let curve = if base_asym_algo == SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256 {
+                Nid::X9_62_PRIME256V1
+            } else {
+                Nid::SECP384R1
+            };
+            // the key must be on the negotiated curve
+            let ec_key = public_key.ec_key().map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+            if ec_key.group().curve_name() != Some(curve) {
+                return Err(SPDM_STATUS_VERIF_FAIL);
+            }
+            let der_signature = ecdsa_signature_to_der(signature.as_ref())?;
+            verify(md, &public_key, data, &der_signature, None)
+        }
+        SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => {
+            // the modulus size must match the negotiated algorithm
+            let rsa = public_key.rsa().map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+            if rsa.size() != base_asym_algo.get_size() as u32 {
+                return Err(SPDM_STATUS_VERIF_FAIL);
+            }
+            let padding = match base_asym_algo {
+                SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048
+                | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072
+                | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => Padding::PKCS1_PSS,
+                _ => Padding::PKCS1,
+            };
+            verify(md, &public_key, data, signature.as_ref(), Some(padding))
+        }
+        _ => Err(SPDM_STATUS_INVALID_PARAMETER),
+    }
+}
+
+// the SPDM ECDSA signature is r || s
+fn ecdsa_signature_to_der(signature: &[u8]) -> SpdmResult<Vec<u8>> {
+    let half_size = signature.len() / 2;
+    let r = BigNum::from_slice(&signature[..half_size]).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    let s = BigNum::from_slice(&signature[half_size..]).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    EcdsaSig::from_private_components(r, s)
+        .and_then(|signature| signature.to_der())
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)
+}
+
+/// RSA-PSS signatures use a salt of the digest size.
+fn verify(
+    md: MessageDigest,
+    public_key: &PKeyRef<Public>,
+    data: &[u8],
+    signature: &[u8],
+    rsa_padding: Option<Padding>,
+) -> SpdmResult {
+    let mut verifier = Verifier::new(md, public_key).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    if let Some(padding) = rsa_padding {
+        verifier
+            .set_rsa_padding(padding)
+            .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+        if padding == Padding::PKCS1_PSS {
+            verifier
+                .set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)
+                .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+        }
+    }
+    verifier.update(data).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    match verifier.verify(signature) {
+        Ok(true) => Ok(()),
+        _ => Err(SPDM_STATUS_VERIF_FAIL),
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    // ECDSA P-384 SHA-384 signature of "hello, world" by
+    // test_key/ecp384/end_responder.key
+    const SIGNATURE_P384: [u8; 96] = [
+        0x42, 0xfb, 0x14, 0x77, 0x76, 0x5d, 0x14, 0x81, 0x40, 0xd1, 0x65, 0xcc, 0x8c, 0x2c, 0x4e,
+        0x89, 0xfe, 0xff, 0x8c, 0xbc, 0x6d, 0x29, 0x2c, 0x85, 0x2d, 0x6a, 0xfe, 0x0e, 0x8e, 0xb1,
+        0xc0, 0x01, 0x1b, 0x0f, 0x77, 0x3d, 0x41, 0xc9, 0x1a, 0xe5, 0xc6, 0xdd, 0xd9, 0x53, 0xe1,
+        0x8c, 0x62, 0xbc, 0x2d, 0xa1, 0x05, 0xcc, 0x9f, 0x17, 0x01, 0x3c, 0xc4, 0x01, 0xca, 0x26,
+        0xd7, 0x66, 0xe2, 0xf5, 0x6f, 0x2c, 0xf6, 0x3c, 0x99, 0x55, 0xe9, 0xf4, 0xdd, 0xb3, 0x88,
+        0xdd, 0xba, 0xd2, 0xe0, 0x75, 0x1a, 0x27, 0x75, 0xbf, 0x2b, 0xe4, 0x51, 0xca, 0xe5, 0x48,
+        0x4f, 0x97, 0xd8, 0x80, 0x25, 0xa2,
+    ];
+
+    #[test]
+    fn test_case0_pub_key_verify() {
+        let spki = std::fs::read("../test_key/ecp384/end_responder.key.pub.der")
+            .expect("unable to read public key!");
+        let mut signature = SpdmSignatureStruct::default();
+        signature.data_size = SIGNATURE_P384.len() as u16;
+        signature.data[..SIGNATURE_P384.len()].copy_from_slice(&SIGNATURE_P384);
+
+        assert!(pub_key_verify(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            &spki,
+            b"hello, world",
+            &signature,
+        )
+        .is_ok());
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &spki,
+                b"hello, buddy",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        // not a SubjectPublicKeyInfo
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &[0x04u8; 97],
+                b"hello, world",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        // wrong signature size
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+                &spki,
+                b"hello, world",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+    }
+}
@@ -7,13 +7,15 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::convert::TryFrom;
 
-use crate::crypto::SpdmCertOperation;
+use crate::crypto::{x509v3, SpdmCertOperation};
 use crate::error::{SpdmResult, SPDM_STATUS_INVALID_CERT, SPDM_STATUS_INVALID_STATE_LOCAL};
+use codec::{u16be, Codec};
 use ring::io::der;
 
 pub static DEFAULT: SpdmCertOperation = SpdmCertOperation {
     get_cert_from_cert_chain_cb: get_cert_from_cert_chain,
     verify_cert_chain_cb: verify_cert_chain,
+    verify_cert_cb: verify_cert,
 };
 
 fn get_cert_from_cert_chain(cert_chain: &[u8], index: isize) -> SpdmResult<(usize, usize)> {
@@ -27,8 +29,11 @@ fn get_cert_from_cert_chain(cert_chain: &[u8], index: isize) -> SpdmResult<(usiz
         if cert_chain[offset] != 0x30 || cert_chain[offset + 1] != 0x82 {
             return Err(SPDM_STATUS_INVALID_CERT);
         }
-        let this_cert_len =
-            ((cert_chain[offset + 2] as usize) << 8) + (cert_chain[offset + 3] as usize) + 4;
+        // DER length is big endian
+        let this_cert_len = u16be::read_bytes(&cert_chain[(offset + 2)..(offset + 4)])
+            .ok_or(SPDM_STATUS_INVALID_CERT)?
+            .get() as usize
+            + 4;
         if this_cert_len > cert_chain_size - offset {
             return Err(SPDM_STATUS_INVALID_CERT);
         }
@@ -45,7 +50,7 @@ fn get_cert_from_cert_chain(cert_chain: &[u8], index: isize) -> SpdmResult<(usiz
     }
 }
 
-fn verify_cert_chain(cert_chain: &[u8]) -> SpdmResult {
+fn verify_cert_chain(cert_chain: &[u8], time: Option<u64>) -> SpdmResult {
     static EKU_SPDM_RESPONDER_AUTH: &[u8] = &[40 + 3, 6, 1, 5, 5, 7, 3, 1];
 
     static ALL_SIGALGS: &[&webpki::SignatureAlgorithm] = &[
@@ -90,24 +95,7 @@ fn verify_cert_chain(cert_chain: &[u8]) -> SpdmResult {
         return Err(SPDM_STATUS_INVALID_CERT);
     };
 
-    #[cfg(any(target_os = "uefi", target_os = "none"))]
-    let timestamp = {
-        if let Some(ts) = sys_time::get_sys_time() {
-            ts as u64
-        } else {
-            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
-        }
-    };
-    #[cfg(not(any(target_os = "uefi", target_os = "none")))]
-    let timestamp = {
-        extern crate std;
-        if let Ok(ds) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
-            ds.as_secs()
-        } else {
-            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
-        }
-    };
-    let time = webpki::Time::from_seconds_since_unix_epoch(timestamp);
+    let time = webpki::Time::from_seconds_since_unix_epoch(get_timestamp(time)?);
 
     let cert = if let Ok(eec) = webpki::EndEntityCert::try_from(ee) {
         eec
@@ -134,6 +122,111 @@ fn verify_cert_chain(cert_chain: &[u8]) -> SpdmResult {
         Err(SPDM_STATUS_INVALID_CERT)
     }
 }
+fn get_timestamp(time: Option<u64>) -> SpdmResult<u64> {
+    if let Some(time) = time {
+        return Ok(time);
+    }
+    #[cfg(any(target_os = "uefi", target_os = "none"))]
+    {
+        if let Some(ts) = sys_time::get_sys_time() {
+            Ok(ts as u64)
+        } else {
+            Err(SPDM_STATUS_INVALID_STATE_LOCAL)
+        }
+    }
+    #[cfg(not(any(target_os = "uefi", target_os = "none")))]
+    {
+        extern crate std;
+        if let Ok(ds) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(ds.as_secs())
+        } else {
+            Err(SPDM_STATUS_INVALID_STATE_LOCAL)
+        }
+    }
+}
+
+fn verify_cert(cert: &[u8], issuer_cert: &[u8], time: Option<u64>) -> SpdmResult {
+    // ecdsa-with-SHA256, ecdsa-with-SHA384
+    const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+    const OID_ECDSA_WITH_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+    // sha256WithRSAEncryption, sha384WithRSAEncryption, sha512WithRSAEncryption
+    const OID_SHA256_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+    const OID_SHA384_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c];
+    const OID_SHA512_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d];
+
+    let timestamp = get_timestamp(time)?;
+    let cert_info = crate::crypto::get_cert_info(cert)?;
+    if timestamp < cert_info.not_before || timestamp > cert_info.not_after {
+        error!("Cert validity period check Fail\n");
+        return Err(SPDM_STATUS_INVALID_CERT);
+    }
+
+    // a CA certificate which is not self issued counts against the
+    // pathLenConstraint of the issuer
+    let issuer_info = crate::crypto::get_cert_info(issuer_cert)?;
+    let path_len = (cert_info.is_ca && cert_info.subject != cert_info.issuer) as u32;
+    x509v3::check_issuer(&issuer_info, path_len)?;
+
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+    let (tbs, signature_oid, signature) =
+        untrusted::Input::from(cert).read_all(SPDM_STATUS_INVALID_CERT, |reader| {
+            der::nested(
+                reader,
+                der::Tag::Sequence,
+                SPDM_STATUS_INVALID_CERT,
+                |reader| {
+                    let (tbs, _) = reader
+                        .read_partial(|reader| {
+                            der::expect_tag_and_get_value(reader, der::Tag::Sequence)
+                        })
+                        .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+                    let signature_oid = der::nested(
+                        reader,
+                        der::Tag::Sequence,
+                        SPDM_STATUS_INVALID_CERT,
+                        |reader| {
+                            let oid = der::expect_tag_and_get_value(reader, der::Tag::OID)
+                                .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+                            // the parameters, if any, are implied by the OIDs above
+                            reader.skip_to_end();
+                            Ok(oid)
+                        },
+                    )?;
+                    let signature = der::bit_string_with_no_unused_bits(reader)
+                        .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+                    Ok((tbs, signature_oid, signature))
+                },
+            )
+        })?;
+
+    let algorithms: &[&webpki::SignatureAlgorithm] = match signature_oid.as_slice_less_safe() {
+        OID_ECDSA_WITH_SHA256 => &[&webpki::ECDSA_P256_SHA256, &webpki::ECDSA_P384_SHA256],
+        OID_ECDSA_WITH_SHA384 => &[&webpki::ECDSA_P256_SHA384, &webpki::ECDSA_P384_SHA384],
+        OID_SHA256_WITH_RSA => &[&webpki::RSA_PKCS1_2048_8192_SHA256],
+        OID_SHA384_WITH_RSA => &[&webpki::RSA_PKCS1_2048_8192_SHA384],
+        OID_SHA512_WITH_RSA => &[&webpki::RSA_PKCS1_2048_8192_SHA512],
+        _ => return Err(SPDM_STATUS_INVALID_CERT),
+    };
+
+    // only the subjectPublicKeyInfo of the issuer is used
+    let issuer =
+        webpki::EndEntityCert::try_from(issuer_cert).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+    if algorithms.iter().any(|algorithm| {
+        issuer
+            .verify_signature(
+                algorithm,
+                tbs.as_slice_less_safe(),
+                signature.as_slice_less_safe(),
+            )
+            .is_ok()
+    }) {
+        Ok(())
+    } else {
+        error!("Cert signature verification Fail\n");
+        Err(SPDM_STATUS_INVALID_CERT)
+    }
+}
+
 #[cfg(all(test,))]
 mod tests {
     use super::*;
@@ -177,7 +270,7 @@ mod tests {
         let status = get_cert_from_cert_chain(cert_chain, -1).is_ok();
         assert!(status);
 
-        let status = verify_cert_chain(cert_chain).is_ok();
+        let status = verify_cert_chain(cert_chain, None).is_ok();
         assert!(status);
     }
 
@@ -186,30 +279,57 @@ mod tests {
     fn test_verify_cert_chain_case1() {
         let bundle_certs_der =
             &include_bytes!("../../../../test_key/crypto_chains/ca_selfsigned.crt.der")[..];
-        assert!(verify_cert_chain(bundle_certs_der).is_ok());
+        assert!(verify_cert_chain(bundle_certs_der, None).is_ok());
 
         let bundle_certs_der =
             &include_bytes!("../../../../test_key/crypto_chains/bundle_two_level_cert.der")[..];
-        assert!(verify_cert_chain(bundle_certs_der).is_ok());
+        assert!(verify_cert_chain(bundle_certs_der, None).is_ok());
 
         let bundle_certs_der =
             &include_bytes!("../../../../test_key/ecp384/bundle_requester.certchain.der")[..];
-        assert!(verify_cert_chain(bundle_certs_der).is_ok());
+        assert!(verify_cert_chain(bundle_certs_der, None).is_ok());
 
         let bundle_certs_der =
             &include_bytes!("../../../../test_key/crypto_chains/bundle_cert.der")[..];
-        assert!(verify_cert_chain(bundle_certs_der).is_ok());
+        assert!(verify_cert_chain(bundle_certs_der, None).is_ok());
 
         // Flipping bits to test signature hash is invalid.
         let mut cert_chain = bundle_certs_der.to_vec();
         // offset 3140 is in signature range.
         cert_chain[3140] ^= 0xFE;
-        assert!(verify_cert_chain(&cert_chain).is_err());
+        assert!(verify_cert_chain(&cert_chain, None).is_err());
 
         // Invalid Intermediate cert
         let mut cert_chain = bundle_certs_der.to_vec();
         // Change intermediate cert data
         cert_chain[1380] = 0xFF;
-        assert!(verify_cert_chain(&cert_chain).is_err());
+        assert!(verify_cert_chain(&cert_chain, None).is_err());
+    }
+
+    #[test]
+    fn test_verify_cert_case1() {
+        let cert_chain =
+            &include_bytes!("../../../../test_key/ecp384/bundle_responder.certchain.der")[..];
+        let mut certs = Vec::new();
+        let mut index = 0;
+        while let Ok((begin, end)) = get_cert_from_cert_chain(cert_chain, index) {
+            certs.push(&cert_chain[begin..end]);
+            index += 1;
+        }
+        assert!(certs.len() > 2);
+
+        // the root is self signed
+        assert!(verify_cert(certs[0], certs[0], None).is_ok());
+        for pair in certs.windows(2) {
+            assert!(verify_cert(pair[1], pair[0], None).is_ok());
+        }
+        assert!(verify_cert(certs[2], certs[0], None).is_err());
+        assert!(verify_cert(certs[1], certs[0], Some(0)).is_err());
+
+        let mut cert = certs[1].to_vec();
+        // the last bytes are in the signature.
+        let last = cert.len() - 4;
+        cert[last] ^= 0xFE;
+        assert!(verify_cert(&cert, certs[0], None).is_err());
     }
 }
@@ -9,4 +9,5 @@ pub mod dhe_impl;
 pub mod hash_impl;
 pub mod hkdf_impl;
 pub mod hmac_impl;
+pub mod pub_key_verify_impl;
 pub mod rand_impl;
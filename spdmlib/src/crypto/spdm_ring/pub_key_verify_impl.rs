@@ -0,0 +1,113 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::{x509v3, SpdmPubKeyVerify};
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_VERIF_FAIL};
+use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct};
+
+pub static DEFAULT: SpdmPubKeyVerify = SpdmPubKeyVerify {
+    verify_cb: pub_key_verify,
+};
+
+fn pub_key_verify(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    public_key_der: &[u8],
+    data: &[u8],
+    signature: &SpdmSignatureStruct,
+) -> SpdmResult {
+    if signature.data_size != base_asym_algo.get_size() {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+
+    // ring takes the fixed size ECDSA signature as is, no DER translation needed.
+    let algorithm: &dyn ring::signature::VerificationAlgorithm =
+        match (base_hash_algo, base_asym_algo) {
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256) => {
+                &ring::signature::ECDSA_P256_SHA256_FIXED
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384) => {
+                &ring::signature::ECDSA_P384_SHA256_FIXED
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256) => {
+                &ring::signature::ECDSA_P256_SHA384_FIXED
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384) => {
+                &ring::signature::ECDSA_P384_SHA384_FIXED
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096) => {
+                &ring::signature::RSA_PKCS1_2048_8192_SHA256
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096) => {
+                &ring::signature::RSA_PSS_2048_8192_SHA256
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096) => {
+                &ring::signature::RSA_PKCS1_2048_8192_SHA384
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_384, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096) => {
+                &ring::signature::RSA_PSS_2048_8192_SHA384
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_512, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_512, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_512, SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096) => {
+                &ring::signature::RSA_PKCS1_2048_8192_SHA512
+            }
+            (SpdmBaseHashAlgo::TPM_ALG_SHA_512, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_512, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072)
+            | (SpdmBaseHashAlgo::TPM_ALG_SHA_512, SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096) => {
+                &ring::signature::RSA_PSS_2048_8192_SHA512
+            }
+            _ => {
+                return Err(SPDM_STATUS_INVALID_PARAMETER);
+            }
+        };
+
+    let public_key = x509v3::get_public_key_from_spki(public_key_der)?;
+    ring::signature::UnparsedPublicKey::new(algorithm, public_key)
+        .verify(data, signature.as_ref())
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_pub_key_verify() {
+        let signature = SpdmSignatureStruct {
+            data_size: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384.get_size(),
+            ..Default::default()
+        };
+        // not a SubjectPublicKeyInfo
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &[0x04u8; 97],
+                b"data",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        // wrong signature size
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+                &[],
+                b"data",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+    }
+}
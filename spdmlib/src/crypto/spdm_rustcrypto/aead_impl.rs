@@ -0,0 +1,218 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::SpdmAead;
+use crate::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR};
+
+use crate::protocol::{SpdmAeadAlgo, SpdmAeadIvStruct, SpdmAeadKeyStruct};
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes128Gcm, Aes256Gcm, AesGcm};
+use chacha20poly1305::ChaCha20Poly1305;
+use sm4::Sm4;
+
+/// SM4 in GCM mode with a 96-bit nonce, GCM works with any 128-bit block
+/// cipher.
+type Sm4Gcm = AesGcm<Sm4, U12>;
+
+pub static DEFAULT: SpdmAead = SpdmAead {
+    encrypt_cb: encrypt,
+    decrypt_cb: decrypt,
+};
+
+fn encrypt(
+    aead_algo: SpdmAeadAlgo,
+    key: &SpdmAeadKeyStruct,
+    iv: &SpdmAeadIvStruct,
+    aad: &[u8],
+    plain_text: &[u8],
+    tag: &mut [u8],
+    cipher_text: &mut [u8],
+) -> SpdmResult<(usize, usize)> {
+    check_sizes(aead_algo, key, iv, tag.len())?;
+    let plain_text_size = plain_text.len();
+    if cipher_text.len() != plain_text_size {
+        error!("cipher_text len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+
+    let nonce = GenericArray::from_slice(iv.as_ref());
+    cipher_text.copy_from_slice(plain_text);
+    let res = match aead_algo {
+        SpdmAeadAlgo::AES_128_GCM => Aes128Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .encrypt_in_place_detached(nonce, aad, cipher_text),
+        SpdmAeadAlgo::AES_256_GCM => Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .encrypt_in_place_detached(nonce, aad, cipher_text),
+        SpdmAeadAlgo::CHACHA20_POLY1305 => ChaCha20Poly1305::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .encrypt_in_place_detached(nonce, aad, cipher_text),
+        SpdmAeadAlgo::SM4_GCM => Sm4Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .encrypt_in_place_detached(nonce, aad, cipher_text),
+        _ => return Err(SPDM_STATUS_CRYPTO_ERROR),
+    };
+    match res {
+        Ok(computed_tag) => {
+            tag.copy_from_slice(computed_tag.as_slice());
+            Ok((plain_text_size, tag.len()))
+        }
+        Err(_) => Err(SPDM_STATUS_CRYPTO_ERROR),
+    }
+}
+
+fn decrypt(
+    aead_algo: SpdmAeadAlgo,
+    key: &SpdmAeadKeyStruct,
+    iv: &SpdmAeadIvStruct,
+    aad: &[u8],
+    cipher_text: &[u8],
+    tag: &[u8],
+    plain_text: &mut [u8],
+) -> SpdmResult<usize> {
+    check_sizes(aead_algo, key, iv, tag.len())?;
+    let cipher_text_size = cipher_text.len();
+    if plain_text.len() != cipher_text_size {
+        error!("plain_text len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+
+    let nonce = GenericArray::from_slice(iv.as_ref());
+    let tag = GenericArray::from_slice(tag);
+    // the tag is checked before the cipher text is decrypted
+    plain_text.copy_from_slice(cipher_text);
+    let res = match aead_algo {
+        SpdmAeadAlgo::AES_128_GCM => Aes128Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .decrypt_in_place_detached(nonce, aad, plain_text, tag),
+        SpdmAeadAlgo::AES_256_GCM => Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .decrypt_in_place_detached(nonce, aad, plain_text, tag),
+        SpdmAeadAlgo::CHACHA20_POLY1305 => ChaCha20Poly1305::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .decrypt_in_place_detached(nonce, aad, plain_text, tag),
+        SpdmAeadAlgo::SM4_GCM => Sm4Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?
+            .decrypt_in_place_detached(nonce, aad, plain_text, tag),
+        _ => return Err(SPDM_STATUS_CRYPTO_ERROR),
+    };
+    match res {
+        Ok(()) => Ok(cipher_text_size),
+        Err(_) => {
+            plain_text.fill(0);
+            Err(SPDM_STATUS_CRYPTO_ERROR)
+        }
+    }
+}
+
+fn check_sizes(
+    aead_algo: SpdmAeadAlgo,
+    key: &SpdmAeadKeyStruct,
+    iv: &SpdmAeadIvStruct,
+    tag_size: usize,
+) -> SpdmResult {
+    match aead_algo {
+        SpdmAeadAlgo::AES_128_GCM
+        | SpdmAeadAlgo::AES_256_GCM
+        | SpdmAeadAlgo::CHACHA20_POLY1305
+        | SpdmAeadAlgo::SM4_GCM => {}
+        _ => return Err(SPDM_STATUS_CRYPTO_ERROR),
+    }
+    if key.data_size != aead_algo.get_key_size() {
+        error!("key len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+    if iv.data_size != aead_algo.get_iv_size() {
+        error!("iv len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+    if tag_size != aead_algo.get_tag_size() as usize {
+        error!("tag len invalid");
+        return Err(SPDM_STATUS_CRYPTO_ERROR);
+    }
+    Ok(())
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::protocol::*;
+
+    #[test]
+    fn test_case0_encrypt_decrypt() {
+        for (aead_algo, key_size) in [
+            (SpdmAeadAlgo::AES_128_GCM, 16),
+            (SpdmAeadAlgo::AES_256_GCM, 32),
+            (SpdmAeadAlgo::CHACHA20_POLY1305, 32),
+            (SpdmAeadAlgo::SM4_GCM, 16),
+        ] {
+            let key = &SpdmAeadKeyStruct {
+                data_size: key_size,
+                data: Box::new([100u8; SPDM_MAX_AEAD_KEY_SIZE]),
+            };
+            let iv = &SpdmAeadIvStruct {
+                data_size: 12,
+                data: Box::new([100u8; SPDM_MAX_AEAD_IV_SIZE]),
+            };
+            let plain_text = &[10u8; 16];
+            let aad = &[20u8; 16];
+            let tag = &mut [0u8; 16];
+            let cipher_text = &mut [0u8; 16];
+
+            assert_eq!(
+                encrypt(aead_algo, key, iv, aad, plain_text, tag, cipher_text),
+                Ok((16, 16))
+            );
+            assert_ne!(cipher_text, plain_text);
+
+            let decrypted = &mut [0u8; 16];
+            assert_eq!(
+                decrypt(aead_algo, key, iv, aad, cipher_text, tag, decrypted),
+                Ok(16)
+            );
+            assert_eq!(decrypted, plain_text);
+
+            tag[0] ^= 0x01;
+            assert!(decrypt(aead_algo, key, iv, aad, cipher_text, tag, decrypted).is_err());
+            assert_eq!(decrypted, &[0u8; 16]);
+        }
+    }
+    #[test]
+    fn test_case1_encrypt() {
+        let key = &SpdmAeadKeyStruct {
+            data_size: 16,
+            data: Box::new([100u8; SPDM_MAX_AEAD_KEY_SIZE]),
+        };
+        let iv = &SpdmAeadIvStruct {
+            data_size: 12,
+            data: Box::new([100u8; SPDM_MAX_AEAD_IV_SIZE]),
+        };
+        let plain_text = &[10u8; 16];
+        let tag = &mut [0u8; 16];
+        let cipher_text = &mut [0u8; 8];
+
+        assert!(encrypt(
+            SpdmAeadAlgo::AES_128_GCM,
+            key,
+            iv,
+            &[],
+            plain_text,
+            tag,
+            cipher_text
+        )
+        .is_err());
+        assert!(encrypt(
+            SpdmAeadAlgo::AES_256_GCM,
+            key,
+            iv,
+            &[],
+            plain_text,
+            tag,
+            &mut [0u8; 16]
+        )
+        .is_err());
+    }
+}
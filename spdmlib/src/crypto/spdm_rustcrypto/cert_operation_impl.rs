@@ -0,0 +1,188 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::hash_impl::hash_all;
+use super::pub_key_verify_impl::{ecdsa_verify_prehash, rsa_verify_prehash, sm2_verify};
+use crate::crypto::x509v3::{self, get_cert_from_cert_chain};
+use crate::crypto::SpdmCertOperation;
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_CERT, SPDM_STATUS_INVALID_STATE_LOCAL};
+use crate::protocol::SpdmBaseHashAlgo;
+
+pub static DEFAULT: SpdmCertOperation = SpdmCertOperation {
+    get_cert_from_cert_chain_cb: get_cert_from_cert_chain,
+    verify_cert_chain_cb: verify_cert_chain,
+    verify_cert_cb: verify_cert,
+};
+
+// The first certificate is the trust anchor, each following one is
+// verified against the one before it.
+fn verify_cert_chain(cert_chain: &[u8], time: Option<u64>) -> SpdmResult {
+    x509v3::verify_cert_chain_with(cert_chain, get_timestamp(time)?, verify_cert)
+}
+
+fn get_timestamp(time: Option<u64>) -> SpdmResult<u64> {
+    if let Some(time) = time {
+        return Ok(time);
+    }
+    #[cfg(any(target_os = "uefi", target_os = "none"))]
+    {
+        if let Some(ts) = sys_time::get_sys_time() {
+            Ok(ts as u64)
+        } else {
+            Err(SPDM_STATUS_INVALID_STATE_LOCAL)
+        }
+    }
+    #[cfg(not(any(target_os = "uefi", target_os = "none")))]
+    {
+        extern crate std;
+        if let Ok(ds) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(ds.as_secs())
+        } else {
+            Err(SPDM_STATUS_INVALID_STATE_LOCAL)
+        }
+    }
+}
+
+fn verify_cert(cert: &[u8], issuer_cert: &[u8], time: Option<u64>) -> SpdmResult {
+    // ecdsa-with-SHA256, ecdsa-with-SHA384
+    const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+    const OID_ECDSA_WITH_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+    // sha256WithRSAEncryption, sha384WithRSAEncryption, sha512WithRSAEncryption
+    const OID_SHA256_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+    const OID_SHA384_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c];
+    const OID_SHA512_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d];
+    // SM2-with-SM3
+    const OID_SM2_WITH_SM3: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x75];
+
+    let timestamp = get_timestamp(time)?;
+    let cert_info = x509v3::get_cert_info(cert)?;
+    if timestamp < cert_info.not_before || timestamp > cert_info.not_after {
+        error!("Cert validity period check Fail\n");
+        return Err(SPDM_STATUS_INVALID_CERT);
+    }
+
+    let (tbs, signature_oid, signature) =
+        x509v3::get_cert_signature(cert).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+    // only the subjectPublicKeyInfo of the issuer is used
+    let issuer_info = x509v3::get_cert_info(issuer_cert).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+    let public_key = x509v3::get_public_key_from_spki(issuer_info.subject_public_key_info)
+        .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+
+    // SM2 hashes the tbsCertificate itself
+    if signature_oid == OID_SM2_WITH_SM3 {
+        return sm2_verify(public_key, tbs, signature, true).map_err(|_| {
+            error!("Cert signature verification Fail\n");
+            SPDM_STATUS_INVALID_CERT
+        });
+    }
+
+    let (base_hash_algo, ecdsa) = match signature_oid {
+        OID_ECDSA_WITH_SHA256 => (SpdmBaseHashAlgo::TPM_ALG_SHA_256, true),
+        OID_ECDSA_WITH_SHA384 => (SpdmBaseHashAlgo::TPM_ALG_SHA_384, true),
+        OID_SHA256_WITH_RSA => (SpdmBaseHashAlgo::TPM_ALG_SHA_256, false),
+        OID_SHA384_WITH_RSA => (SpdmBaseHashAlgo::TPM_ALG_SHA_384, false),
+        OID_SHA512_WITH_RSA => (SpdmBaseHashAlgo::TPM_ALG_SHA_512, false),
+        _ => return Err(SPDM_STATUS_INVALID_CERT),
+    };
+    let digest = hash_all(base_hash_algo, tbs).ok_or(SPDM_STATUS_INVALID_CERT)?;
+
+    let res = if ecdsa {
+        ecdsa_verify_prehash(public_key, digest.as_ref(), signature, true)
+    } else {
+        rsa_verify_prehash(
+            public_key,
+            base_hash_algo,
+            digest.as_ref(),
+            signature,
+            false,
+        )
+    };
+    res.map_err(|_| {
+        error!("Cert signature verification Fail\n");
+        SPDM_STATUS_INVALID_CERT
+    })
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_case0_cert_from_cert_chain() {
+        let cert_chain = std::fs::read("../test_key/ecp384/bundle_responder.certchain.der")
+            .expect("unable to read cert chain!");
+        assert!(get_cert_from_cert_chain(&cert_chain, -1).is_ok());
+        assert!(get_cert_from_cert_chain(&cert_chain, 0).is_ok());
+        assert!(get_cert_from_cert_chain(&cert_chain, 1).is_ok());
+
+        let cert_chain = &mut [0x1u8; 4096];
+        cert_chain[0] = 0x00;
+        cert_chain[1] = 0x00;
+        assert!(get_cert_from_cert_chain(cert_chain, 0).is_err());
+        assert!(get_cert_from_cert_chain(&[0x11u8; 3], 0).is_err());
+    }
+
+    #[test]
+    fn test_verify_cert_chain_case1() {
+        for path in [
+            "../test_key/crypto_chains/ca_selfsigned.crt.der",
+            "../test_key/crypto_chains/bundle_two_level_cert.der",
+            "../test_key/ecp256/bundle_responder.certchain.der",
+            "../test_key/ecp384/bundle_requester.certchain.der",
+            "../test_key/rsa2048/bundle_responder.certchain.der",
+            "../test_key/rsa3072/bundle_responder.certchain.der",
+        ] {
+            let cert_chain = std::fs::read(path).expect("unable to read cert chain!");
+            assert!(verify_cert_chain(&cert_chain, None).is_ok(), "{}", path);
+        }
+
+        let bundle_certs_der = std::fs::read("../test_key/crypto_chains/bundle_cert.der")
+            .expect("unable to read cert chain!");
+        assert!(verify_cert_chain(&bundle_certs_der, None).is_ok());
+
+        // Flipping bits to test signature hash is invalid.
+        let mut cert_chain = bundle_certs_der.clone();
+        // offset 3140 is in signature range.
+        cert_chain[3140] ^= 0xFE;
+        assert!(verify_cert_chain(&cert_chain, None).is_err());
+
+        // Invalid Intermediate cert
+        let mut cert_chain = bundle_certs_der.clone();
+        // Change intermediate cert data
+        cert_chain[1380] = 0xFF;
+        assert!(verify_cert_chain(&cert_chain, None).is_err());
+
+        // expired
+        assert!(verify_cert_chain(&bundle_certs_der, Some(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_verify_cert_case1() {
+        let cert_chain = std::fs::read("../test_key/ecp384/bundle_responder.certchain.der")
+            .expect("unable to read cert chain!");
+        let mut certs = Vec::new();
+        let mut index = 0;
+        while let Ok((begin, end)) = get_cert_from_cert_chain(&cert_chain, index) {
+            certs.push(&cert_chain[begin..end]);
+            index += 1;
+        }
+        assert!(certs.len() > 2);
+
+        // the root is self signed
+        assert!(verify_cert(certs[0], certs[0], None).is_ok());
+        for pair in certs.windows(2) {
+            assert!(verify_cert(pair[1], pair[0], None).is_ok());
+        }
+        assert!(verify_cert(certs[2], certs[0], None).is_err());
+        assert!(verify_cert(certs[1], certs[0], Some(0)).is_err());
+
+        let mut cert = certs[1].to_vec();
+        // the last bytes are in the signature.
+        let last = cert.len() - 4;
+        cert[last] ^= 0xFE;
+        assert!(verify_cert(&cert, certs[0], None).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+extern crate alloc;
+use alloc::boxed::Box;
+
+use super::rand_impl::SpdmCryptoRng;
+use crate::crypto::{SpdmDhe, SpdmDheKeyExchange};
+use crate::protocol::{SpdmDheAlgo, SpdmDheExchangeStruct, SpdmDheFinalKeyStruct};
+use bytes::{BufMut, BytesMut};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+pub static DEFAULT: SpdmDhe = SpdmDhe {
+    generate_key_pair_cb: generate_key_pair,
+};
+
+fn generate_key_pair(
+    dhe_algo: SpdmDheAlgo,
+) -> Option<(SpdmDheExchangeStruct, Box<dyn SpdmDheKeyExchange>)> {
+    match dhe_algo {
+        SpdmDheAlgo::SECP_256_R1 => SpdmDheKeyExchangeP256::generate_key_pair(),
+        SpdmDheAlgo::SECP_384_R1 => SpdmDheKeyExchangeP384::generate_key_pair(),
+        _ => None,
+    }
+}
+
+// the exchange data is the uncompressed point without the 0x04 prefix
+fn peer_point(peer_pub_key: &SpdmDheExchangeStruct) -> BytesMut {
+    let mut pubkey = BytesMut::new();
+    pubkey.put_u8(0x4u8);
+    pubkey.extend_from_slice(peer_pub_key.as_ref());
+    pubkey
+}
+
+struct SpdmDheKeyExchangeP256(p256::ecdh::EphemeralSecret);
+
+impl SpdmDheKeyExchange for SpdmDheKeyExchangeP256 {
+    fn compute_final_key(
+        self: Box<Self>,
+        peer_pub_key: &SpdmDheExchangeStruct,
+    ) -> Option<SpdmDheFinalKeyStruct> {
+        let peer_public_key = p256::PublicKey::from_sec1_bytes(&peer_point(peer_pub_key)).ok()?;
+        let shared_secret = self.0.diffie_hellman(&peer_public_key);
+        Some(SpdmDheFinalKeyStruct::from(
+            shared_secret.raw_secret_bytes().as_slice(),
+        ))
+    }
+}
+
+impl SpdmDheKeyExchangeP256 {
+    fn generate_key_pair() -> Option<(SpdmDheExchangeStruct, Box<dyn SpdmDheKeyExchange>)> {
+        let private_key = p256::ecdh::EphemeralSecret::random(&mut SpdmCryptoRng);
+        let public_key_point = private_key.public_key().to_encoded_point(false);
+        let public_key = BytesMut::from(&public_key_point.as_bytes()[1..]);
+
+        let res: Box<dyn SpdmDheKeyExchange> = Box::new(Self(private_key));
+
+        Some((SpdmDheExchangeStruct::from(public_key), res))
+    }
+}
+
+struct SpdmDheKeyExchangeP384(p384::ecdh::EphemeralSecret);
+
+impl SpdmDheKeyExchange for SpdmDheKeyExchangeP384 {
+    fn compute_final_key(
+        self: Box<Self>,
+        peer_pub_key: &SpdmDheExchangeStruct,
+    ) -> Option<SpdmDheFinalKeyStruct> {
+        let peer_public_key = p384::PublicKey::from_sec1_bytes(&peer_point(peer_pub_key)).ok()?;
+        let shared_secret = self.0.diffie_hellman(&peer_public_key);
+        Some(SpdmDheFinalKeyStruct::from(
+            shared_secret.raw_secret_bytes().as_slice(),
+        ))
+    }
+}
+
+impl SpdmDheKeyExchangeP384 {
+    fn generate_key_pair() -> Option<(SpdmDheExchangeStruct, Box<dyn SpdmDheKeyExchange>)> {
+        let private_key = p384::ecdh::EphemeralSecret::random(&mut SpdmCryptoRng);
+        let public_key_point = private_key.public_key().to_encoded_point(false);
+        let public_key = BytesMut::from(&public_key_point.as_bytes()[1..]);
+
+        let res: Box<dyn SpdmDheKeyExchange> = Box::new(Self(private_key));
+
+        Some((SpdmDheExchangeStruct::from(public_key), res))
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_dhe() {
+        for dhe_algo in [SpdmDheAlgo::SECP_256_R1, SpdmDheAlgo::SECP_384_R1].iter() {
+            let (exchange1, private1) = generate_key_pair(*dhe_algo).unwrap();
+            let (exchange2, private2) = generate_key_pair(*dhe_algo).unwrap();
+            assert_eq!(exchange1.data_size, dhe_algo.get_size());
+
+            let peer1 = private1.compute_final_key(&exchange2).unwrap();
+            let peer2 = private2.compute_final_key(&exchange1).unwrap();
+
+            assert_eq!(peer1.as_ref(), peer2.as_ref());
+        }
+    }
+    #[test]
+    fn test_case1_dhe() {
+        assert!(generate_key_pair(SpdmDheAlgo::empty()).is_none());
+
+        let (_, private) = generate_key_pair(SpdmDheAlgo::SECP_256_R1).unwrap();
+        assert!(private
+            .compute_final_key(&SpdmDheExchangeStruct::default())
+            .is_none());
+    }
+}
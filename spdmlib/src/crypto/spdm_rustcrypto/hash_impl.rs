@@ -0,0 +1,187 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+extern crate alloc;
+
+use crate::crypto::SpdmHash;
+
+use crate::protocol::{SpdmBaseHashAlgo, SpdmDigestStruct};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use sm3::Sm3;
+
+#[cfg(not(feature = "hashed-transcript-data"))]
+pub static DEFAULT: SpdmHash = SpdmHash {
+    hash_all_cb: hash_all,
+};
+#[cfg(feature = "hashed-transcript-data")]
+pub static DEFAULT: SpdmHash = SpdmHash {
+    hash_all_cb: hash_all,
+    hash_ctx_init_cb: hash_ext::hash_ctx_init,
+    hash_ctx_update_cb: hash_ext::hash_ctx_update,
+    hash_ctx_finalize_cb: hash_ext::hash_ctx_finalize,
+    hash_ctx_dup_cb: hash_ext::hash_ctx_dup,
+};
+
+pub(super) fn hash_all(base_hash_algo: SpdmBaseHashAlgo, data: &[u8]) -> Option<SpdmDigestStruct> {
+    match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => {
+            Some(SpdmDigestStruct::from(Sha256::digest(data).as_slice()))
+        }
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => {
+            Some(SpdmDigestStruct::from(Sha384::digest(data).as_slice()))
+        }
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => {
+            Some(SpdmDigestStruct::from(Sha512::digest(data).as_slice()))
+        }
+        SpdmBaseHashAlgo::TPM_ALG_SM3_256 => {
+            Some(SpdmDigestStruct::from(Sm3::digest(data).as_slice()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(feature = "hashed-transcript-data")]
+mod hash_ext {
+    use super::*;
+    use alloc::boxed::Box;
+    use alloc::collections::BTreeMap;
+    use lazy_static::lazy_static;
+    use spin::Mutex;
+
+    #[derive(Clone)]
+    pub enum HashCtxConcrete {
+        Sha256(Sha256),
+        Sha384(Sha384),
+        Sha512(Sha512),
+        Sm3(Sm3),
+    }
+
+    lazy_static! {
+        static ref HASH_CTX_TABLE: Mutex<BTreeMap<usize, Box<HashCtxConcrete>>> =
+            Mutex::new(BTreeMap::new());
+    }
+    use crate::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR};
+
+    pub fn hash_ctx_update(handle: usize, data: &[u8]) -> SpdmResult {
+        let mut table = HASH_CTX_TABLE.lock();
+        let ctx = table.get_mut(&handle).ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+        match ctx.as_mut() {
+            HashCtxConcrete::Sha256(ctx) => ctx.update(data),
+            HashCtxConcrete::Sha384(ctx) => ctx.update(data),
+            HashCtxConcrete::Sha512(ctx) => ctx.update(data),
+            HashCtxConcrete::Sm3(ctx) => ctx.update(data),
+        }
+        Ok(())
+    }
+
+    pub fn hash_ctx_finalize(handle: usize) -> Option<SpdmDigestStruct> {
+        let ctx = HASH_CTX_TABLE.lock().remove(&handle)?;
+        let digest = match *ctx {
+            HashCtxConcrete::Sha256(ctx) => SpdmDigestStruct::from(ctx.finalize().as_slice()),
+            HashCtxConcrete::Sha384(ctx) => SpdmDigestStruct::from(ctx.finalize().as_slice()),
+            HashCtxConcrete::Sha512(ctx) => SpdmDigestStruct::from(ctx.finalize().as_slice()),
+            HashCtxConcrete::Sm3(ctx) => SpdmDigestStruct::from(ctx.finalize().as_slice()),
+        };
+        Some(digest)
+    }
+
+    pub fn hash_ctx_dup(handle: usize) -> Option<usize> {
+        let ctx_new = {
+            let table = HASH_CTX_TABLE.lock();
+            let ctx = table.get(&handle)?;
+            ctx.clone()
+        };
+        let new_handle = insert_to_table(ctx_new);
+        Some(new_handle)
+    }
+
+    pub fn hash_ctx_init(base_hash_algo: SpdmBaseHashAlgo) -> Option<usize> {
+        let ctx = match base_hash_algo {
+            SpdmBaseHashAlgo::TPM_ALG_SHA_256 => HashCtxConcrete::Sha256(Sha256::new()),
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384 => HashCtxConcrete::Sha384(Sha384::new()),
+            SpdmBaseHashAlgo::TPM_ALG_SHA_512 => HashCtxConcrete::Sha512(Sha512::new()),
+            SpdmBaseHashAlgo::TPM_ALG_SM3_256 => HashCtxConcrete::Sm3(Sm3::new()),
+            _ => return None,
+        };
+        Some(insert_to_table(Box::new(ctx)))
+    }
+
+    fn insert_to_table(value: Box<HashCtxConcrete>) -> usize {
+        let handle_ptr: *const HashCtxConcrete = &*value;
+        let handle = handle_ptr as usize;
+        HASH_CTX_TABLE.lock().insert(handle, value);
+        handle
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_hash_all() {
+        let base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_512;
+        let data = &mut [0u8; 64];
+
+        let hash_all = hash_all(base_hash_algo, data).unwrap();
+        assert_eq!(hash_all.data_size, 64);
+    }
+    #[test]
+    fn test_case1_hash_all() {
+        let base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_256;
+        let data = &b"hello"[..];
+
+        let hash_all = hash_all(base_hash_algo, data).unwrap();
+        assert_eq!(
+            hash_all.as_ref(),
+            [
+                0x2c, 0xf2, 0x4d, 0xba, 0x5f, 0xb0, 0xa3, 0x0e, 0x26, 0xe8, 0x3b, 0x2a, 0xc5, 0xb9,
+                0xe2, 0x9e, 0x1b, 0x16, 0x1e, 0x5c, 0x1f, 0xa7, 0x42, 0x5e, 0x73, 0x04, 0x33, 0x62,
+                0x93, 0x8b, 0x98, 0x24
+            ]
+        );
+    }
+    #[test]
+    fn test_case3_hash_all() {
+        // GB/T 32905 example 1
+        let hash_all = hash_all(SpdmBaseHashAlgo::TPM_ALG_SM3_256, b"abc").unwrap();
+        assert_eq!(
+            hash_all.as_ref(),
+            [
+                0x66, 0xc7, 0xf0, 0xf4, 0x62, 0xee, 0xed, 0xd9, 0xd1, 0xf2, 0xd4, 0x6b, 0xdc, 0x10,
+                0xe4, 0xe2, 0x41, 0x67, 0xc4, 0x87, 0x5c, 0xf2, 0xf7, 0xa2, 0x29, 0x7d, 0xa0, 0x2b,
+                0x8f, 0x4b, 0xa8, 0xe0
+            ]
+        );
+    }
+    #[test]
+    fn test_case2_hash_all() {
+        let base_hash_algo = SpdmBaseHashAlgo::empty();
+        let data = &mut [0u8; 64];
+
+        let hash_all = hash_all(base_hash_algo, data);
+        assert!(hash_all.is_none());
+    }
+    #[cfg(feature = "hashed-transcript-data")]
+    #[test]
+    fn test_case0_hash_update() {
+        let helloworld = hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, b"hello, world").unwrap();
+        let hellobuddy = hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, b"hello, buddy").unwrap();
+        let ctx = hash_ext::hash_ctx_init(SpdmBaseHashAlgo::TPM_ALG_SHA_384).unwrap();
+        hash_ext::hash_ctx_update(ctx, b"hello").unwrap();
+        hash_ext::hash_ctx_update(ctx, b", ").unwrap();
+        let ctx_d = hash_ext::hash_ctx_dup(ctx).unwrap();
+        hash_ext::hash_ctx_update(ctx_d, b"buddy").unwrap();
+        hash_ext::hash_ctx_update(ctx, b"world").unwrap();
+        assert_eq!(
+            hash_ext::hash_ctx_finalize(ctx).unwrap().as_ref(),
+            helloworld.as_ref()
+        );
+        assert_eq!(
+            hash_ext::hash_ctx_finalize(ctx_d).unwrap().as_ref(),
+            hellobuddy.as_ref()
+        );
+        assert!(hash_ext::hash_ctx_finalize(ctx).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::SpdmHkdf;
+use crate::protocol::{
+    SpdmBaseHashAlgo, SpdmHkdfInputKeyingMaterial, SpdmHkdfOutputKeyingMaterial,
+    SpdmHkdfPseudoRandomKey, SPDM_MAX_HKDF_OKM_SIZE,
+};
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha384, Sha512};
+use sm3::Sm3;
+
+pub static DEFAULT: SpdmHkdf = SpdmHkdf {
+    hkdf_extract_cb: hkdf_extract,
+    hkdf_expand_cb: hkdf_expand,
+};
+
+fn hkdf_extract(
+    hash_algo: SpdmBaseHashAlgo,
+    salt: &[u8],
+    ikm: &SpdmHkdfInputKeyingMaterial,
+) -> Option<SpdmHkdfPseudoRandomKey> {
+    match hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => {
+            let (prk, _) = Hkdf::<Sha256>::extract(Some(salt), ikm.as_ref());
+            Some(SpdmHkdfPseudoRandomKey::from(prk.as_slice()))
+        }
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => {
+            let (prk, _) = Hkdf::<Sha384>::extract(Some(salt), ikm.as_ref());
+            Some(SpdmHkdfPseudoRandomKey::from(prk.as_slice()))
+        }
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => {
+            let (prk, _) = Hkdf::<Sha512>::extract(Some(salt), ikm.as_ref());
+            Some(SpdmHkdfPseudoRandomKey::from(prk.as_slice()))
+        }
+        SpdmBaseHashAlgo::TPM_ALG_SM3_256 => {
+            let (prk, _) = Hkdf::<Sm3>::extract(Some(salt), ikm.as_ref());
+            Some(SpdmHkdfPseudoRandomKey::from(prk.as_slice()))
+        }
+        _ => None,
+    }
+}
+
+fn hkdf_expand(
+    hash_algo: SpdmBaseHashAlgo,
+    prk: &SpdmHkdfPseudoRandomKey,
+    info: &[u8],
+    out_size: u16,
+) -> Option<SpdmHkdfOutputKeyingMaterial> {
+    if out_size as usize > SPDM_MAX_HKDF_OKM_SIZE {
+        return None;
+    }
+    // according to https://www.rfc-editor.org/rfc/rfc5869 prk.len is hashlen
+    match hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256
+        | SpdmBaseHashAlgo::TPM_ALG_SHA_384
+        | SpdmBaseHashAlgo::TPM_ALG_SHA_512
+        | SpdmBaseHashAlgo::TPM_ALG_SM3_256 => {
+            if prk.data_size != hash_algo.get_size() {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    let mut okm = SpdmHkdfOutputKeyingMaterial::default();
+    let okm_data = &mut okm.data[..out_size as usize];
+    let res = match hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => Hkdf::<Sha256>::from_prk(prk.as_ref())
+            .ok()?
+            .expand(info, okm_data),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => Hkdf::<Sha384>::from_prk(prk.as_ref())
+            .ok()?
+            .expand(info, okm_data),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => Hkdf::<Sha512>::from_prk(prk.as_ref())
+            .ok()?
+            .expand(info, okm_data),
+        SpdmBaseHashAlgo::TPM_ALG_SM3_256 => Hkdf::<Sm3>::from_prk(prk.as_ref())
+            .ok()?
+            .expand(info, okm_data),
+        _ => return None,
+    };
+    match res {
+        Ok(()) => {
+            okm.data_size = out_size;
+            Some(okm)
+        }
+        Err(_) => None,
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::protocol::SpdmDigestStruct;
+
+    #[test]
+    fn test_case0_hkdf_rfc5869_1() {
+        let ikm = SpdmDigestStruct::from(&[0x0bu8; 22][..]);
+        let salt = &[
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+        ];
+        let info = &[0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+        let okm: &[u8] = &[
+            0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f, 0x64, 0xd0, 0x36,
+            0x2f, 0x2a, 0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a, 0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56,
+            0xec, 0xc4, 0xc5, 0xbf, 0x34, 0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65,
+        ];
+
+        let prk = hkdf_extract(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+            salt,
+            &SpdmHkdfInputKeyingMaterial::SpdmDigest(&ikm),
+        )
+        .unwrap();
+        assert_eq!(prk.data_size, 32);
+        let res = hkdf_expand(SpdmBaseHashAlgo::TPM_ALG_SHA_256, &prk, info, 42).unwrap();
+        assert_eq!(res.as_ref(), okm);
+    }
+    #[test]
+    fn test_case1_hkdf_expand() {
+        let prk = SpdmHkdfPseudoRandomKey::from(&[100u8; 64][..]);
+        let info = &mut [100u8; 64];
+        assert!(hkdf_expand(SpdmBaseHashAlgo::empty(), &prk, info, 64).is_none());
+        // prk of the wrong size
+        assert!(hkdf_expand(SpdmBaseHashAlgo::TPM_ALG_SHA_256, &prk, info, 64).is_none());
+        assert!(hkdf_expand(SpdmBaseHashAlgo::TPM_ALG_SHA_512, &prk, info, 64).is_some());
+        let prk = SpdmHkdfPseudoRandomKey::from(&[100u8; 32][..]);
+        assert!(hkdf_expand(SpdmBaseHashAlgo::TPM_ALG_SM3_256, &prk, info, 32).is_some());
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::SpdmHmac;
+use crate::error::{SpdmResult, SPDM_STATUS_VERIF_FAIL};
+use crate::protocol::{SpdmBaseHashAlgo, SpdmDigestStruct};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha384, Sha512};
+use sm3::Sm3;
+
+pub static DEFAULT: SpdmHmac = SpdmHmac {
+    hmac_cb: hmac,
+    hmac_verify_cb: hmac_verify,
+};
+
+fn hmac(base_hash_algo: SpdmBaseHashAlgo, key: &[u8], data: &[u8]) -> Option<SpdmDigestStruct> {
+    match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => hmac_sign::<Hmac<Sha256>>(key, data),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => hmac_sign::<Hmac<Sha384>>(key, data),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => hmac_sign::<Hmac<Sha512>>(key, data),
+        SpdmBaseHashAlgo::TPM_ALG_SM3_256 => hmac_sign::<Hmac<Sm3>>(key, data),
+        _ => None,
+    }
+}
+
+fn hmac_verify(
+    base_hash_algo: SpdmBaseHashAlgo,
+    key: &[u8],
+    data: &[u8],
+    hmac: &SpdmDigestStruct,
+) -> SpdmResult {
+    match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => hmac_check::<Hmac<Sha256>>(key, data, hmac),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => hmac_check::<Hmac<Sha384>>(key, data, hmac),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => hmac_check::<Hmac<Sha512>>(key, data, hmac),
+        SpdmBaseHashAlgo::TPM_ALG_SM3_256 => hmac_check::<Hmac<Sm3>>(key, data, hmac),
+        _ => Err(SPDM_STATUS_VERIF_FAIL),
+    }
+}
+
+fn hmac_sign<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> Option<SpdmDigestStruct> {
+    let mut mac = <M as KeyInit>::new_from_slice(key).ok()?;
+    mac.update(data);
+    Some(SpdmDigestStruct::from(
+        mac.finalize().into_bytes().as_slice(),
+    ))
+}
+
+// the comparison is constant time
+fn hmac_check<M: Mac + KeyInit>(key: &[u8], data: &[u8], hmac: &SpdmDigestStruct) -> SpdmResult {
+    let mut mac = <M as KeyInit>::new_from_slice(key).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    mac.update(data);
+    mac.verify_slice(hmac.as_ref())
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_rfc4231_2() {
+        let key = &[0x4a, 0x65, 0x66, 0x65];
+        let data: &[u8] = &[
+            0x77, 0x68, 0x61, 0x74, 0x20, 0x64, 0x6f, 0x20, 0x79, 0x61, 0x20, 0x77, 0x61, 0x6e,
+            0x74, 0x20, 0x66, 0x6f, 0x72, 0x20, 0x6e, 0x6f, 0x74, 0x68, 0x69, 0x6e, 0x67, 0x3f,
+        ][..];
+        let hmac_256: &[u8] = &[
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+            0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+            0x64, 0xec, 0x38, 0x43,
+        ][..];
+
+        let base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_256;
+        let spdm_digest = hmac(base_hash_algo, key, data).unwrap();
+        assert_eq!(spdm_digest.as_ref(), hmac_256);
+        assert!(hmac_verify(base_hash_algo, key, data, &spdm_digest).is_ok());
+
+        let spdm_digest = hmac(SpdmBaseHashAlgo::TPM_ALG_SHA_512, key, data).unwrap();
+        assert_eq!(spdm_digest.data_size, 64);
+        assert!(hmac_verify(base_hash_algo, key, data, &spdm_digest).is_err());
+        assert!(hmac_verify(base_hash_algo, key, &data[1..], &spdm_digest).is_err());
+
+        let spdm_digest = hmac(SpdmBaseHashAlgo::TPM_ALG_SM3_256, key, data).unwrap();
+        assert_eq!(spdm_digest.data_size, 32);
+        assert!(hmac_verify(SpdmBaseHashAlgo::TPM_ALG_SM3_256, key, data, &spdm_digest).is_ok());
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::hash_impl::hash_all;
+use crate::crypto::{x509v3, SpdmPubKeyVerify};
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_VERIF_FAIL};
+use crate::protocol::{
+    SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct, ECDSA_ECC_NIST_P256_KEY_SIZE,
+    ECDSA_ECC_NIST_P384_KEY_SIZE, SM2_ECC_SM2_P256_KEY_SIZE,
+};
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::{Pkcs1v15Sign, Pss, RsaPublicKey};
+use sha2::{Sha256, Sha384, Sha512};
+use sm2::dsa::signature::Verifier;
+
+/// Default distinguishing ID of GB/T 32918.2, hashed into Z_A of SM2
+/// signatures.
+const SM2_DIST_ID: &str = "1234567812345678";
+
+pub static DEFAULT: SpdmPubKeyVerify = SpdmPubKeyVerify {
+    verify_cb: pub_key_verify,
+};
+
+fn pub_key_verify(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    public_key_der: &[u8],
+    data: &[u8],
+    signature: &SpdmSignatureStruct,
+) -> SpdmResult {
+    if signature.data_size != base_asym_algo.get_size() {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let digest = hash_all(base_hash_algo, data).ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+    let public_key = x509v3::get_public_key_from_spki(public_key_der)?;
+
+    match base_asym_algo {
+        SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256
+        | SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => {
+            // the point size must match the negotiated curve
+            if public_key.len() != 1 + base_asym_algo.get_size() as usize {
+                return Err(SPDM_STATUS_VERIF_FAIL);
+            }
+            ecdsa_verify_prehash(public_key, digest.as_ref(), signature.as_ref(), false)
+        }
+        SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096 => rsa_verify_prehash(
+            public_key,
+            base_hash_algo,
+            digest.as_ref(),
+            signature.as_ref(),
+            false,
+        ),
+        SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => rsa_verify_prehash(
+            public_key,
+            base_hash_algo,
+            digest.as_ref(),
+            signature.as_ref(),
+            true,
+        ),
+        SpdmBaseAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256 => {
+            sm2_verify(public_key, data, signature.as_ref(), false)
+        }
+        _ => Err(SPDM_STATUS_INVALID_PARAMETER),
+    }
+}
+
+/// Verify an ECDSA signature of digest, the curve is the one of the
+/// uncompressed public_key point. The signature is r || s, or the DER
+/// encoding of certificates if der is set.
+pub(super) fn ecdsa_verify_prehash(
+    public_key: &[u8],
+    digest: &[u8],
+    signature: &[u8],
+    der: bool,
+) -> SpdmResult {
+    let res = if public_key.len() == 1 + ECDSA_ECC_NIST_P256_KEY_SIZE {
+        let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+            .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+        let signature = if der {
+            p256::ecdsa::Signature::from_der(signature)
+        } else {
+            p256::ecdsa::Signature::from_slice(signature)
+        }
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+        key.verify_prehash(digest, &signature)
+    } else if public_key.len() == 1 + ECDSA_ECC_NIST_P384_KEY_SIZE {
+        let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+            .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+        let signature = if der {
+            p384::ecdsa::Signature::from_der(signature)
+        } else {
+            p384::ecdsa::Signature::from_slice(signature)
+        }
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+        key.verify_prehash(digest, &signature)
+    } else {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    };
+    res.map_err(|_| SPDM_STATUS_VERIF_FAIL)
+}
+
+/// Verify an SM2 signature of data, which is hashed with SM3 together with
+/// Z_A whatever the negotiated hash is. The signature is r || s, or the DER
+/// encoding of certificates if der is set.
+pub(super) fn sm2_verify(
+    public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+    der: bool,
+) -> SpdmResult {
+    if public_key.len() != 1 + SM2_ECC_SM2_P256_KEY_SIZE {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let key = sm2::dsa::VerifyingKey::from_sec1_bytes(SM2_DIST_ID, public_key)
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    let mut raw = [0u8; SM2_ECC_SM2_P256_KEY_SIZE];
+    if der {
+        sm2_signature_from_der(signature, &mut raw)?;
+    } else if signature.len() == SM2_ECC_SM2_P256_KEY_SIZE {
+        raw.copy_from_slice(signature);
+    } else {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let signature = sm2::dsa::Signature::from_slice(&raw).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    key.verify(data, &signature)
+        .map_err(|_| SPDM_STATUS_VERIF_FAIL)
+}
+
+/// Convert SEQUENCE { r INTEGER, s INTEGER } to r || s.
+fn sm2_signature_from_der(der: &[u8], raw: &mut [u8; SM2_ECC_SM2_P256_KEY_SIZE]) -> SpdmResult {
+    const SCALAR_SIZE: usize = SM2_ECC_SM2_P256_KEY_SIZE / 2;
+
+    // both integers are at most 33 bytes, the lengths fit in one byte
+    if der.len() < 2 || der[0] != 0x30 || der[1] as usize != der.len() - 2 {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let mut walker = 2;
+    for scalar in raw.chunks_mut(SCALAR_SIZE) {
+        if der.len() < walker + 2 || der[walker] != 0x02 {
+            return Err(SPDM_STATUS_VERIF_FAIL);
+        }
+        let len = der[walker + 1] as usize;
+        walker += 2;
+        if len == 0 || der.len() < walker + len {
+            return Err(SPDM_STATUS_VERIF_FAIL);
+        }
+        let mut integer = &der[walker..walker + len];
+        walker += len;
+        // strip the sign octet
+        if integer.len() == SCALAR_SIZE + 1 && integer[0] == 0 {
+            integer = &integer[1..];
+        }
+        if integer.len() > SCALAR_SIZE {
+            return Err(SPDM_STATUS_VERIF_FAIL);
+        }
+        scalar[SCALAR_SIZE - integer.len()..].copy_from_slice(integer);
+    }
+    if walker != der.len() {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    Ok(())
+}
+
+/// Verify an RSASSA-PKCS1-v1_5 signature of digest, or RSASSA-PSS with a
+/// salt of the digest size if pss is set. public_key is the RSAPublicKey
+/// DER.
+pub(super) fn rsa_verify_prehash(
+    public_key: &[u8],
+    base_hash_algo: SpdmBaseHashAlgo,
+    digest: &[u8],
+    signature: &[u8],
+    pss: bool,
+) -> SpdmResult {
+    let key = RsaPublicKey::from_pkcs1_der(public_key).map_err(|_| SPDM_STATUS_VERIF_FAIL)?;
+    let res = match (base_hash_algo, pss) {
+        (SpdmBaseHashAlgo::TPM_ALG_SHA_256, false) => {
+            key.verify(Pkcs1v15Sign::new::<Sha256>(), digest, signature)
+        }
+        (SpdmBaseHashAlgo::TPM_ALG_SHA_384, false) => {
+            key.verify(Pkcs1v15Sign::new::<Sha384>(), digest, signature)
+        }
+        (SpdmBaseHashAlgo::TPM_ALG_SHA_512, false) => {
+            key.verify(Pkcs1v15Sign::new::<Sha512>(), digest, signature)
+        }
+        (SpdmBaseHashAlgo::TPM_ALG_SHA_256, true) => {
+            key.verify(Pss::new::<Sha256>(), digest, signature)
+        }
+        (SpdmBaseHashAlgo::TPM_ALG_SHA_384, true) => {
+            key.verify(Pss::new::<Sha384>(), digest, signature)
+        }
+        (SpdmBaseHashAlgo::TPM_ALG_SHA_512, true) => {
+            key.verify(Pss::new::<Sha512>(), digest, signature)
+        }
+        _ => return Err(SPDM_STATUS_INVALID_PARAMETER),
+    };
+    res.map_err(|_| SPDM_STATUS_VERIF_FAIL)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    // ECDSA P-384 SHA-384 signature of "hello, world" by
+    // test_key/ecp384/end_responder.key
+    const SIGNATURE_P384: [u8; 96] = [
+        0x42, 0xfb, 0x14, 0x77, 0x76, 0x5d, 0x14, 0x81, 0x40, 0xd1, 0x65, 0xcc, 0x8c, 0x2c, 0x4e,
+        0x89, 0xfe, 0xff, 0x8c, 0xbc, 0x6d, 0x29, 0x2c, 0x85, 0x2d, 0x6a, 0xfe, 0x0e, 0x8e, 0xb1,
+        0xc0, 0x01, 0x1b, 0x0f, 0x77, 0x3d, 0x41, 0xc9, 0x1a, 0xe5, 0xc6, 0xdd, 0xd9, 0x53, 0xe1,
+        0x8c, 0x62, 0xbc, 0x2d, 0xa1, 0x05, 0xcc, 0x9f, 0x17, 0x01, 0x3c, 0xc4, 0x01, 0xca, 0x26,
+        0xd7, 0x66, 0xe2, 0xf5, 0x6f, 0x2c, 0xf6, 0x3c, 0x99, 0x55, 0xe9, 0xf4, 0xdd, 0xb3, 0x88,
+        0xdd, 0xba, 0xd2, 0xe0, 0x75, 0x1a, 0x27, 0x75, 0xbf, 0x2b, 0xe4, 0x51, 0xca, 0xe5, 0x48,
+        0x4f, 0x97, 0xd8, 0x80, 0x25, 0xa2,
+    ];
+
+    #[test]
+    fn test_case0_pub_key_verify() {
+        let spki = std::fs::read("../test_key/ecp384/end_responder.key.pub.der")
+            .expect("unable to read public key!");
+        let mut signature = SpdmSignatureStruct::default();
+        signature.data_size = SIGNATURE_P384.len() as u16;
+        signature.data[..SIGNATURE_P384.len()].copy_from_slice(&SIGNATURE_P384);
+
+        assert!(pub_key_verify(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            &spki,
+            b"hello, world",
+            &signature,
+        )
+        .is_ok());
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &spki,
+                b"hello, buddy",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        // not a SubjectPublicKeyInfo
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &[0x04u8; 97],
+                b"hello, world",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        // wrong signature size
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+                &spki,
+                b"hello, world",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+    }
+
+    #[test]
+    fn test_case0_sm2_verify() {
+        use sm2::dsa::signature::Signer;
+
+        let signing_key = sm2::dsa::SigningKey::from_bytes(SM2_DIST_ID, &[0x5au8; 32].into())
+            .expect("invalid SM2 key");
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        let signature: sm2::dsa::Signature = signing_key.sign(b"hello, world");
+        let signature = signature.to_bytes();
+
+        assert!(sm2_verify(public_key.as_bytes(), b"hello, world", &signature, false).is_ok());
+        assert_eq!(
+            sm2_verify(public_key.as_bytes(), b"hello, buddy", &signature, false),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+
+        // DER with a sign octet on r
+        let mut der = vec![0x30, 0x45, 0x02, 0x21, 0x00];
+        der.extend_from_slice(&signature[..32]);
+        der.extend_from_slice(&[0x02, 0x20]);
+        der.extend_from_slice(&signature[32..]);
+        let mut raw = [0u8; SM2_ECC_SM2_P256_KEY_SIZE];
+        assert!(sm2_signature_from_der(&der, &mut raw).is_ok());
+        assert_eq!(&raw[..], &signature[..]);
+        assert!(sm2_signature_from_der(&der[..der.len() - 1], &mut raw).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto::SpdmCryptoRandom;
+use crate::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR};
+use core::num::NonZeroU32;
+use rand_core::{CryptoRng, RngCore};
+
+pub static DEFAULT: SpdmCryptoRandom = SpdmCryptoRandom {
+    get_random_cb: get_random,
+};
+
+fn get_random(data: &mut [u8]) -> SpdmResult<usize> {
+    getrandom::getrandom(data).map_err(|_| SPDM_STATUS_CRYPTO_ERROR)?;
+    Ok(data.len())
+}
+
+/// Random generator of the RustCrypto key generation, drawing from the
+/// registered random callback.
+pub(super) struct SpdmCryptoRng;
+
+impl RngCore for SpdmCryptoRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("random callback should not fail")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match crate::crypto::rand::get_random(dest) {
+            Ok(len) if len == dest.len() => Ok(()),
+            _ => Err(NonZeroU32::new(rand_core::Error::CUSTOM_START)
+                .unwrap()
+                .into()),
+        }
+    }
+}
+
+impl CryptoRng for SpdmCryptoRng {}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_get_random() {
+        let data = &mut [0u8; 80];
+        assert_eq!(get_random(data), Ok(80));
+        assert_ne!(data, &[0u8; 80]);
+    }
+    #[test]
+    fn test_case0_crypto_rng() {
+        let data = &mut [0u8; 80];
+        assert!(SpdmCryptoRng.try_fill_bytes(data).is_ok());
+        assert_ne!(data, &[0u8; 80]);
+    }
+}
@@ -2,8 +2,12 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
-use crate::error::{SpdmResult, SPDM_STATUS_VERIF_FAIL};
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_CERT, SPDM_STATUS_VERIF_FAIL};
 use crate::protocol::SpdmBaseAsymAlgo;
+use codec::{u16be, Codec};
 
 // reference: https://www.itu.int/rec/T-REC-X.690/en
 // TAG
@@ -12,12 +16,22 @@ const ASN1_TAG_CLASS_CONTEXT_SPECIFIC_MASK: u8 = 0x80;
 
 const ASN1_FORM_CONSTRUCTED_MASK: u8 = 0x20;
 
+const ASN1_TAG_NUMBER_BOOLEAN: u8 = 0x1;
 const ASN1_TAG_NUMBER_INTEGER: u8 = 0x2;
+const ASN1_TAG_NUMBER_BIT_STRING: u8 = 0x3;
+const ASN1_TAG_NUMBER_OCTET_STRING: u8 = 0x4;
 const ASN1_TAG_NUMBER_OBJECT_IDENTIFIER: u8 = 0x6;
 const ASN1_TAG_NUMBER_SEQUENCE: u8 = 0x10;
+const ASN1_TAG_NUMBER_SET: u8 = 0x11;
+const ASN1_TAG_NUMBER_UTC_TIME: u8 = 0x17;
+const ASN1_TAG_NUMBER_GENERALIZED_TIME: u8 = 0x18;
 
 const ASN1_TAG_SEQUENCE: u8 =
     ASN1_TAG_CLASS_UNIVERSAL_MASK | ASN1_FORM_CONSTRUCTED_MASK | ASN1_TAG_NUMBER_SEQUENCE;
+const ASN1_TAG_SET: u8 =
+    ASN1_TAG_CLASS_UNIVERSAL_MASK | ASN1_FORM_CONSTRUCTED_MASK | ASN1_TAG_NUMBER_SET;
+const ASN1_TAG_EXTENSIONS: u8 =
+    ASN1_TAG_CLASS_CONTEXT_SPECIFIC_MASK | ASN1_FORM_CONSTRUCTED_MASK | 3;
 
 const ASN1_LENGTH_MULTI_OCTET_MASK: u8 = 0x80;
 
@@ -27,6 +41,26 @@ const OID_RSA_SHA384RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x0
 const OID_RSA_SHA512RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0du8];
 const OID_ECDSA_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02u8];
 const OID_ECDSA_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03u8];
+const OID_SM2_SM3: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x75u8];
+
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03u8];
+const OID_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0fu8];
+const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11u8];
+const OID_BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13u8];
+const OID_EXTENDED_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25u8];
+// 1.3.6.1.4.1.412.274.1, DMTF hardware identity otherName of the SAN
+pub const OID_DMTF_SPDM_HARDWARE_IDENTITY: &[u8] =
+    &[0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0x1c, 0x82, 0x12, 0x01u8];
+// 1.3.6.1.4.1.412.274.6, DMTF SPDM extension
+pub const OID_DMTF_SPDM_EXTENSION: &[u8] =
+    &[0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0x1c, 0x82, 0x12, 0x06u8];
+// reference: TCG DICE Attestation Architecture
+// 2.23.133.5.4.1, tcg-dice-TcbInfo
+pub const OID_DICE_TCB_INFO: &[u8] = &[0x67, 0x81, 0x05, 0x05, 0x04, 0x01u8];
+// 2.23.133.5.4.4, tcg-dice-Ueid
+pub const OID_DICE_UEID: &[u8] = &[0x67, 0x81, 0x05, 0x05, 0x04, 0x04u8];
+// 2.23.133.5.4.5, tcg-dice-MultiTcbInfo
+pub const OID_DICE_MULTI_TCB_INFO: &[u8] = &[0x67, 0x81, 0x05, 0x05, 0x04, 0x05u8];
 
 // reference: https://www.rfc-editor.org/rfc/rfc5280.txt
 // IN DER encoded certificate chain slice
@@ -86,6 +120,254 @@ fn check_cert_format(cert: &[u8], base_asym_algo: SpdmBaseAsymAlgo) -> SpdmResul
     }
 }
 
+// IN DER encoded certificate chain slice
+// IN index of the certificate, -1 for the last one
+// OUT Ok (begin, end) of the certificate in the chain
+// OUT Error certificate not found
+pub fn get_cert_from_cert_chain(cert_chain: &[u8], index: isize) -> SpdmResult<(usize, usize)> {
+    let mut offset = 0usize;
+    let mut this_index = 0isize;
+    let cert_chain_size = cert_chain.len();
+    loop {
+        if offset > cert_chain_size || cert_chain[offset..].len() < 4 {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        if cert_chain[offset] != 0x30 || cert_chain[offset + 1] != 0x82 {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        // DER length is big endian
+        let this_cert_len = u16be::read_bytes(&cert_chain[(offset + 2)..(offset + 4)])
+            .ok_or(SPDM_STATUS_INVALID_CERT)?
+            .get() as usize
+            + 4;
+        if this_cert_len > cert_chain_size - offset {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        if this_index == index {
+            // return the this one
+            return Ok((offset, offset + this_cert_len));
+        }
+        this_index += 1;
+        if (offset + this_cert_len == cert_chain_size) && (index == -1) {
+            // return the last one
+            return Ok((offset, offset + this_cert_len));
+        }
+        offset += this_cert_len;
+    }
+}
+
+// IN DER encoded certificate chain slice, the first certificate is the trust anchor
+// IN timestamp in seconds since unix epoch
+// IN verify_cert verifies a certificate against its issuer
+// OUT Ok the chain is valid
+// checked:
+// 1. the trust anchor is valid at timestamp.
+// 2. each certificate is verified against the one before it.
+// 3. the issuers are CAs allowed to sign certificates, see check_issuer.
+// 4. the extended key usage of the leaf certificate, if any, allows SPDM responder authentication.
+pub fn verify_cert_chain_with(
+    cert_chain: &[u8],
+    timestamp: u64,
+    verify_cert: fn(cert: &[u8], issuer_cert: &[u8], time: Option<u64>) -> SpdmResult,
+) -> SpdmResult {
+    static EKU_SPDM_RESPONDER_AUTH: &[u8] = &[40 + 3, 6, 1, 5, 5, 7, 3, 1];
+
+    let mut certs = Vec::new();
+    let mut offset = 0usize;
+    while offset < cert_chain.len() {
+        let (_, cert_len) = get_cert_from_cert_chain(&cert_chain[offset..], 0)?;
+        certs.push(&cert_chain[offset..offset + cert_len]);
+        offset += cert_len;
+    }
+    let (ca, ee) = match (certs.first(), certs.last()) {
+        (Some(ca), Some(ee)) => (*ca, *ee),
+        _ => return Err(SPDM_STATUS_INVALID_CERT),
+    };
+
+    let ca_info = get_cert_info(ca).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+    if timestamp < ca_info.not_before || timestamp > ca_info.not_after {
+        error!("Cert validity period check Fail\n");
+        return Err(SPDM_STATUS_INVALID_CERT);
+    }
+
+    let issuer_count = certs.len() - 1;
+    for (index, issuer) in certs[..issuer_count].iter().enumerate() {
+        let issuer_info = get_cert_info(issuer).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+        // the intermediate certificates below the issuer
+        let path_len = (issuer_count - 1 - index) as u32;
+        check_issuer(&issuer_info, path_len)?;
+        verify_cert(certs[index + 1], issuer, Some(timestamp))?;
+    }
+
+    let ee_info = get_cert_info(ee).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+    if !ee_info.extended_key_usage.is_empty()
+        && !ee_info
+            .extended_key_usage
+            .iter()
+            .any(|usage| *usage == EKU_SPDM_RESPONDER_AUTH)
+    {
+        error!("Cert extended key usage check Fail\n");
+        return Err(SPDM_STATUS_INVALID_CERT);
+    }
+
+    info!("Cert verification Pass\n");
+    Ok(())
+}
+
+// IN issuer certificate info
+// IN path_len the intermediate certificates below the issuer
+// OUT Ok the issuer may sign the certificate below it
+// checked:
+// 1. the issuer is a CA.
+// 2. the key usage of the issuer, if any, allows keyCertSign.
+// 3. path_len fits the pathLenConstraint of the issuer.
+pub fn check_issuer(issuer_info: &SpdmCertInfo, path_len: u32) -> SpdmResult {
+    if !issuer_info.is_ca
+        || issuer_info
+            .path_len_constraint
+            .map_or(false, |path_len_constraint| path_len > path_len_constraint)
+    {
+        error!("Cert basic constraints check Fail\n");
+        return Err(SPDM_STATUS_INVALID_CERT);
+    }
+    if issuer_info.key_usage.map_or(false, |key_usage| {
+        !key_usage.contains(SpdmX509KeyUsage::KEY_CERT_SIGN)
+    }) {
+        error!("Cert key usage check Fail\n");
+        return Err(SPDM_STATUS_INVALID_CERT);
+    }
+    Ok(())
+}
+
+// IN DER encoded certificate chain slice
+// OUT Ok (not_before, not_after) in seconds since unix epoch, the intersection
+//     of the validity periods of all certificates in the chain
+// OUT Error Mulformed certificate found
+pub fn get_cert_chain_validity(cert_chain: &[u8]) -> SpdmResult<(u64, u64)> {
+    let mut cc_walker = 0usize;
+    let mut not_before = 0u64;
+    let mut not_after = u64::MAX;
+
+    if cert_chain.is_empty() {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    while cc_walker < cert_chain.len() {
+        let (cert_not_before, cert_not_after, cert_size) =
+            get_cert_validity(&cert_chain[cc_walker..])?;
+        not_before = not_before.max(cert_not_before);
+        not_after = not_after.min(cert_not_after);
+        cc_walker += cert_size;
+    }
+
+    Ok((not_before, not_after))
+}
+
+// IN DER encoded certificate slice
+// OUT Ok (not_before, not_after, cert size)
+// OUT Error Mulformed certificate found
+fn get_cert_validity(cert: &[u8]) -> SpdmResult<(u64, u64, usize)> {
+    check_tag_is_sequence(cert)?;
+    let (body_size, bytes_consumed) = check_length(&cert[1..])?;
+    let cert_size = 1 + bytes_consumed + body_size;
+    if cert.len() < cert_size {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+
+    let data = &cert[1 + bytes_consumed..cert_size];
+    check_tag_is_sequence(data)?;
+    let (_, bytes_consumed) = check_length(&data[1..])?;
+    let mut t_walker = 1 + bytes_consumed;
+
+    // version         [0]  EXPLICIT Version DEFAULT v1,
+    if data.len() > t_walker
+        && data[t_walker] == (ASN1_TAG_CLASS_CONTEXT_SPECIFIC_MASK | ASN1_FORM_CONSTRUCTED_MASK)
+    {
+        t_walker += check_and_skip_common_tag(&data[t_walker..])?;
+    }
+    // serialNumber         CertificateSerialNumber,
+    t_walker += check_and_skip_common_tag(&data[t_walker..])?;
+    // signature            AlgorithmIdentifier,
+    t_walker += check_and_skip_common_sequence(&data[t_walker..])?;
+    // issuer               Name,
+    t_walker += check_name(&data[t_walker..])?;
+
+    // validity             Validity,
+    check_tag_is_sequence(&data[t_walker..])?;
+    t_walker += 1;
+    let (_, bytes_consumed) = check_length(&data[t_walker..])?;
+    t_walker += bytes_consumed;
+    let (not_before, bytes_consumed) = get_time(&data[t_walker..])?;
+    t_walker += bytes_consumed;
+    let (not_after, _) = get_time(&data[t_walker..])?;
+
+    Ok((not_before, not_after, cert_size))
+}
+
+// IN UTCTime (YYMMDDHHMMSSZ) or GeneralizedTime (YYYYMMDDHHMMSSZ)
+// OUT Ok (seconds since unix epoch, bytes consumed)
+fn get_time(data: &[u8]) -> SpdmResult<(u64, usize)> {
+    if data.len() < 2 {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let (year_digits, expected_length) = match data[0] {
+        ASN1_TAG_NUMBER_UTC_TIME => (2, 13),
+        ASN1_TAG_NUMBER_GENERALIZED_TIME => (4, 15),
+        _ => return Err(SPDM_STATUS_VERIF_FAIL),
+    };
+    let length = data[1] as usize;
+    if length != expected_length || data.len() < 2 + length || data[1 + length] != b'Z' {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let time = &data[2..1 + length];
+
+    let mut digits = [0u64; 14];
+    for (digit, c) in digits.iter_mut().zip(time.iter()) {
+        if !c.is_ascii_digit() {
+            return Err(SPDM_STATUS_VERIF_FAIL);
+        }
+        *digit = (c - b'0') as u64;
+    }
+    let number = |start: usize, count: usize| {
+        digits[start..start + count]
+            .iter()
+            .fold(0u64, |acc, d| acc * 10 + d)
+    };
+
+    let mut year = number(0, year_digits);
+    if year_digits == 2 {
+        // RFC 5280: YY >= 50 is 19YY, otherwise 20YY
+        year += if year >= 50 { 1900 } else { 2000 };
+    }
+    let month = number(year_digits, 2);
+    let day = number(year_digits + 2, 2);
+    let hour = number(year_digits + 4, 2);
+    let minute = number(year_digits + 6, 2);
+    let second = number(year_digits + 8, 2);
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+
+    // days since unix epoch of a proleptic gregorian date
+    let (y, m) = if month <= 2 {
+        (year as i64 - 1, month as i64 + 9)
+    } else {
+        (year as i64, month as i64 - 3)
+    };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * m + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let seconds = days * 86400 + (hour * 3600 + minute * 60 + second) as i64;
+    Ok((seconds.max(0) as u64, 2 + length))
+}
+
 fn check_tbs_certificate(
     data: &[u8],
     base_asym_algo: SpdmBaseAsymAlgo,
@@ -285,6 +567,486 @@ fn check_and_skip_common_tag(data: &[u8]) -> SpdmResult<usize> {
     }
 }
 
+// reference: https://www.rfc-editor.org/rfc/rfc5280#section-4.1.2.7
+// IN DER encoded SubjectPublicKeyInfo slice
+// OUT Ok subjectPublicKey, e.g. the EC point or the RSAPublicKey DER
+// OUT Error Mulformed SubjectPublicKeyInfo found
+pub fn get_public_key_from_spki(spki: &[u8]) -> SpdmResult<&[u8]> {
+    check_tag_is_sequence(spki)?;
+    let (spki_length, bytes_consumed) = check_length(&spki[1..])?;
+    let spki_end = 1 + bytes_consumed + spki_length;
+    if spki.len() != spki_end {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+
+    // skip AlgorithmIdentifier
+    let mut walker = 1 + bytes_consumed;
+    walker += check_and_skip_common_sequence(&spki[walker..])?;
+
+    if spki_end < walker + 1 || spki[walker] != ASN1_TAG_NUMBER_BIT_STRING {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let (bit_string_length, bytes_consumed) = check_length(&spki[walker + 1..])?;
+    walker += 1 + bytes_consumed;
+    // no unused bits are allowed in a public key
+    if bit_string_length < 2 || walker + bit_string_length != spki_end || spki[walker] != 0 {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    Ok(&spki[walker + 1..spki_end])
+}
+
+// reference: https://www.rfc-editor.org/rfc/rfc5280#section-4.1.1
+// IN DER encoded certificate slice, trailing bytes are ignored
+// OUT Ok (DER encoded tbsCertificate, signatureAlgorithm OID, signatureValue)
+// OUT Error Mulformed certificate found
+pub fn get_cert_signature(cert: &[u8]) -> SpdmResult<(&[u8], &[u8], &[u8])> {
+    let (_, cert_body, _) = get_tlv(cert, Some(ASN1_TAG_SEQUENCE))?;
+    // tbsCertificate       TBSCertificate,
+    let (_, _, size) = get_tlv(cert_body, Some(ASN1_TAG_SEQUENCE))?;
+    let tbs = &cert_body[..size];
+    let mut c_walker = size;
+    // signatureAlgorithm   AlgorithmIdentifier,
+    let (_, algorithm, size) = get_tlv(&cert_body[c_walker..], Some(ASN1_TAG_SEQUENCE))?;
+    let (_, signature_oid, _) = get_tlv(algorithm, Some(ASN1_TAG_NUMBER_OBJECT_IDENTIFIER))?;
+    c_walker += size;
+    // signatureValue       BIT STRING
+    let (_, signature, _) = get_tlv(&cert_body[c_walker..], Some(ASN1_TAG_NUMBER_BIT_STRING))?;
+    // no unused bits are allowed in a signature
+    if signature.len() < 2 || signature[0] != 0 {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    Ok((tbs, signature_oid, &signature[1..]))
+}
+
+bitflags! {
+    /// KeyUsage extension, bit n is the named bit n of RFC 5280.
+    #[derive(Default)]
+    pub struct SpdmX509KeyUsage: u16 {
+        const DIGITAL_SIGNATURE = 0b0000_0000_0000_0001;
+        const NON_REPUDIATION = 0b0000_0000_0000_0010;
+        const KEY_ENCIPHERMENT = 0b0000_0000_0000_0100;
+        const DATA_ENCIPHERMENT = 0b0000_0000_0000_1000;
+        const KEY_AGREEMENT = 0b0000_0000_0001_0000;
+        const KEY_CERT_SIGN = 0b0000_0000_0010_0000;
+        const CRL_SIGN = 0b0000_0000_0100_0000;
+        const ENCIPHER_ONLY = 0b0000_0000_1000_0000;
+        const DECIPHER_ONLY = 0b0000_0001_0000_0000;
+    }
+}
+
+/// GeneralName of the SubjectAltName extension, the values are the
+/// content octets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmGeneralName<'a> {
+    /// type_id is the OID, value the DER encoding of the value.
+    OtherName {
+        type_id: &'a [u8],
+        value: &'a [u8],
+    },
+    Rfc822Name(&'a [u8]),
+    DnsName(&'a [u8]),
+    /// DER encoded Name.
+    DirectoryName(&'a [u8]),
+    Uri(&'a [u8]),
+    IpAddress(&'a [u8]),
+    Other {
+        tag: u8,
+        value: &'a [u8],
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpdmCertExtension<'a> {
+    pub oid: &'a [u8],
+    pub critical: bool,
+    /// Content of the extnValue OCTET STRING.
+    pub value: &'a [u8],
+}
+
+/// Fields of a certificate, borrowed from its DER encoding. Names are DER
+/// encoded, OIDs are the content octets of the OBJECT IDENTIFIER.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpdmCertInfo<'a> {
+    /// Content octets of the INTEGER.
+    pub serial_number: &'a [u8],
+    pub issuer: &'a [u8],
+    pub subject: &'a [u8],
+    /// Content octets of the first commonName of the subject.
+    pub subject_common_name: Option<&'a [u8]>,
+    /// Seconds since unix epoch.
+    pub not_before: u64,
+    pub not_after: u64,
+    pub subject_public_key_info: &'a [u8],
+    pub key_usage: Option<SpdmX509KeyUsage>,
+    pub is_ca: bool,
+    /// pathLenConstraint of the BasicConstraints extension.
+    pub path_len_constraint: Option<u32>,
+    pub subject_alt_names: Vec<SpdmGeneralName<'a>>,
+    pub extended_key_usage: Vec<&'a [u8]>,
+    /// All the extensions, including the ones parsed above.
+    pub extensions: Vec<SpdmCertExtension<'a>>,
+}
+
+impl<'a> SpdmCertInfo<'a> {
+    pub fn get_extension(&self, oid: &[u8]) -> Option<&SpdmCertExtension<'a>> {
+        self.extensions
+            .iter()
+            .find(|extension| object_identifiers_are_same(extension.oid, oid))
+    }
+
+    /// Value of the DMTF hardware identity otherName of the SAN.
+    pub fn get_hardware_identity(&self) -> Option<&'a [u8]> {
+        self.subject_alt_names.iter().find_map(|name| match name {
+            SpdmGeneralName::OtherName { type_id, value }
+                if object_identifiers_are_same(type_id, OID_DMTF_SPDM_HARDWARE_IDENTITY) =>
+            {
+                Some(*value)
+            }
+            _ => None,
+        })
+    }
+
+    /// True if the certificate carries a DICE TcbInfo or MultiTcbInfo
+    /// extension, as DICE alias and device ID certificates do.
+    pub fn has_dice_tcb_info(&self) -> bool {
+        self.get_extension(OID_DICE_TCB_INFO).is_some()
+            || self.get_extension(OID_DICE_MULTI_TCB_INFO).is_some()
+    }
+
+    /// Parsed DICE TcbInfo extension and each entry of the MultiTcbInfo
+    /// extension, empty if the certificate has none.
+    pub fn get_dice_tcb_info(&self) -> SpdmResult<Vec<SpdmDiceTcbInfo<'a>>> {
+        let mut tcb_info_list = Vec::new();
+        if let Some(extension) = self.get_extension(OID_DICE_TCB_INFO) {
+            tcb_info_list.push(get_dice_tcb_info(extension.value)?);
+        }
+        if let Some(extension) = self.get_extension(OID_DICE_MULTI_TCB_INFO) {
+            // DiceTcbInfoSeq ::= SEQUENCE SIZE (1..MAX) OF DiceTcbInfo
+            let (_, tcb_info_seq, _) = get_tlv(extension.value, Some(ASN1_TAG_SEQUENCE))?;
+            let mut m_walker = 0usize;
+            while m_walker < tcb_info_seq.len() {
+                let (_, _, size) = get_tlv(&tcb_info_seq[m_walker..], Some(ASN1_TAG_SEQUENCE))?;
+                tcb_info_list.push(get_dice_tcb_info(&tcb_info_seq[m_walker..m_walker + size])?);
+                m_walker += size;
+            }
+        }
+        Ok(tcb_info_list)
+    }
+
+    /// FWIDs of all the DICE TcbInfo of the certificate.
+    pub fn get_dice_fwids(&self) -> SpdmResult<Vec<SpdmDiceFwid<'a>>> {
+        Ok(self
+            .get_dice_tcb_info()?
+            .into_iter()
+            .flat_map(|tcb_info| tcb_info.fwids)
+            .collect())
+    }
+}
+
+/// FWID of a DICE TcbInfo, the hash of a firmware layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpdmDiceFwid<'a> {
+    /// Content octets of the hash algorithm OID.
+    pub hash_alg: &'a [u8],
+    pub digest: &'a [u8],
+}
+
+/// DICE TcbInfo of the TCG DICE Attestation Architecture. Strings are
+/// the UTF8String content octets, integers at most 32 bits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpdmDiceTcbInfo<'a> {
+    pub vendor: Option<&'a [u8]>,
+    pub model: Option<&'a [u8]>,
+    pub version: Option<&'a [u8]>,
+    pub svn: Option<u32>,
+    pub layer: Option<u32>,
+    pub index: Option<u32>,
+    pub fwids: Vec<SpdmDiceFwid<'a>>,
+    /// Content octets of the OperationalFlags BIT STRING.
+    pub flags: Option<&'a [u8]>,
+    pub vendor_info: Option<&'a [u8]>,
+    pub tcb_type: Option<&'a [u8]>,
+}
+
+// reference: https://www.rfc-editor.org/rfc/rfc5280#section-4.1
+// IN DER encoded certificate slice, trailing bytes are ignored
+// OUT Ok parsed fields
+// OUT Error Mulformed certificate found
+pub fn get_cert_info(cert: &[u8]) -> SpdmResult<SpdmCertInfo<'_>> {
+    let (_, cert_body, _) = get_tlv(cert, Some(ASN1_TAG_SEQUENCE))?;
+    let (_, tbs, _) = get_tlv(cert_body, Some(ASN1_TAG_SEQUENCE))?;
+    let mut t_walker = 0usize;
+
+    // version         [0]  EXPLICIT Version DEFAULT v1,
+    if tbs.len() > t_walker
+        && tbs[t_walker] == (ASN1_TAG_CLASS_CONTEXT_SPECIFIC_MASK | ASN1_FORM_CONSTRUCTED_MASK)
+    {
+        t_walker += check_and_skip_common_tag(&tbs[t_walker..])?;
+    }
+    // serialNumber         CertificateSerialNumber,
+    let (_, serial_number, size) = get_tlv(&tbs[t_walker..], Some(ASN1_TAG_NUMBER_INTEGER))?;
+    t_walker += size;
+    // signature            AlgorithmIdentifier,
+    t_walker += check_and_skip_common_sequence(&tbs[t_walker..])?;
+    // issuer               Name,
+    let size = check_name(&tbs[t_walker..])?;
+    let issuer = &tbs[t_walker..t_walker + size];
+    t_walker += size;
+    // validity             Validity,
+    let (_, validity, size) = get_tlv(&tbs[t_walker..], Some(ASN1_TAG_SEQUENCE))?;
+    let (not_before, bytes_consumed) = get_time(validity)?;
+    let (not_after, _) = get_time(&validity[bytes_consumed..])?;
+    t_walker += size;
+    // subject              Name,
+    let size = check_name(&tbs[t_walker..])?;
+    let subject = &tbs[t_walker..t_walker + size];
+    t_walker += size;
+    // subjectPublicKeyInfo SubjectPublicKeyInfo,
+    let size = check_public_key_info(&tbs[t_walker..])?;
+    let subject_public_key_info = &tbs[t_walker..t_walker + size];
+    t_walker += size;
+
+    let mut cert_info = SpdmCertInfo {
+        serial_number,
+        issuer,
+        subject,
+        subject_common_name: get_common_name(subject)?,
+        not_before,
+        not_after,
+        subject_public_key_info,
+        key_usage: None,
+        is_ca: false,
+        path_len_constraint: None,
+        subject_alt_names: Vec::new(),
+        extended_key_usage: Vec::new(),
+        extensions: Vec::new(),
+    };
+
+    // issuerUniqueID  [1]  IMPLICIT UniqueIdentifier OPTIONAL,
+    // subjectUniqueID [2]  IMPLICIT UniqueIdentifier OPTIONAL,
+    // extensions      [3]  EXPLICIT Extensions OPTIONAL
+    while t_walker < tbs.len() {
+        let (tag, content, size) = get_tlv(&tbs[t_walker..], None)?;
+        if tag == ASN1_TAG_EXTENSIONS {
+            get_extensions(content, &mut cert_info)?;
+        }
+        t_walker += size;
+    }
+
+    Ok(cert_info)
+}
+
+// IN Extensions wrapped by the [3] tag
+fn get_extensions<'a>(data: &'a [u8], cert_info: &mut SpdmCertInfo<'a>) -> SpdmResult {
+    let (_, extensions, _) = get_tlv(data, Some(ASN1_TAG_SEQUENCE))?;
+    let mut e_walker = 0usize;
+    while e_walker < extensions.len() {
+        // Extension ::= SEQUENCE { extnID, critical BOOLEAN DEFAULT FALSE, extnValue }
+        let (_, extension, size) = get_tlv(&extensions[e_walker..], Some(ASN1_TAG_SEQUENCE))?;
+        e_walker += size;
+
+        let (_, oid, mut x_walker) = get_tlv(extension, Some(ASN1_TAG_NUMBER_OBJECT_IDENTIFIER))?;
+        let mut critical = false;
+        if extension.len() > x_walker && extension[x_walker] == ASN1_TAG_NUMBER_BOOLEAN {
+            let (_, value, size) = get_tlv(&extension[x_walker..], None)?;
+            critical = value.first().map_or(false, |value| *value != 0);
+            x_walker += size;
+        }
+        let (_, value, _) = get_tlv(&extension[x_walker..], Some(ASN1_TAG_NUMBER_OCTET_STRING))?;
+
+        if object_identifiers_are_same(oid, OID_KEY_USAGE) {
+            cert_info.key_usage = Some(get_key_usage(value)?);
+        } else if object_identifiers_are_same(oid, OID_BASIC_CONSTRAINTS) {
+            let (_, basic_constraints, _) = get_tlv(value, Some(ASN1_TAG_SEQUENCE))?;
+            let mut b_walker = 0usize;
+            if basic_constraints.first() == Some(&ASN1_TAG_NUMBER_BOOLEAN) {
+                let (_, ca, size) = get_tlv(basic_constraints, None)?;
+                cert_info.is_ca = ca.first().map_or(false, |ca| *ca != 0);
+                b_walker += size;
+            }
+            if b_walker < basic_constraints.len() {
+                let (_, path_len, _) = get_tlv(
+                    &basic_constraints[b_walker..],
+                    Some(ASN1_TAG_NUMBER_INTEGER),
+                )?;
+                if path_len.is_empty() || path_len.len() > 4 || path_len[0] & 0x80 != 0 {
+                    return Err(SPDM_STATUS_VERIF_FAIL);
+                }
+                cert_info.path_len_constraint = Some(
+                    path_len
+                        .iter()
+                        .fold(0u32, |acc, byte| (acc << 8) | *byte as u32),
+                );
+            }
+        } else if object_identifiers_are_same(oid, OID_SUBJECT_ALT_NAME) {
+            let (_, names, _) = get_tlv(value, Some(ASN1_TAG_SEQUENCE))?;
+            let mut n_walker = 0usize;
+            while n_walker < names.len() {
+                let (tag, name, size) = get_tlv(&names[n_walker..], None)?;
+                cert_info
+                    .subject_alt_names
+                    .push(get_general_name(tag, name)?);
+                n_walker += size;
+            }
+        } else if object_identifiers_are_same(oid, OID_EXTENDED_KEY_USAGE) {
+            let (_, key_purposes, _) = get_tlv(value, Some(ASN1_TAG_SEQUENCE))?;
+            let mut k_walker = 0usize;
+            while k_walker < key_purposes.len() {
+                let (_, key_purpose, size) = get_tlv(
+                    &key_purposes[k_walker..],
+                    Some(ASN1_TAG_NUMBER_OBJECT_IDENTIFIER),
+                )?;
+                cert_info.extended_key_usage.push(key_purpose);
+                k_walker += size;
+            }
+        }
+
+        cert_info.extensions.push(SpdmCertExtension {
+            oid,
+            critical,
+            value,
+        });
+    }
+    Ok(())
+}
+
+// reference: TCG DICE Attestation Architecture, 6.1.1
+// IN DER encoded DiceTcbInfo SEQUENCE
+fn get_dice_tcb_info(data: &[u8]) -> SpdmResult<SpdmDiceTcbInfo<'_>> {
+    let (_, fields, _) = get_tlv(data, Some(ASN1_TAG_SEQUENCE))?;
+    let mut tcb_info = SpdmDiceTcbInfo::default();
+    let mut f_walker = 0usize;
+    while f_walker < fields.len() {
+        // all the fields are IMPLICIT tagged and OPTIONAL
+        let (tag, value, size) = get_tlv(&fields[f_walker..], None)?;
+        f_walker += size;
+        match tag & !ASN1_FORM_CONSTRUCTED_MASK {
+            0x80 => tcb_info.vendor = Some(value),
+            0x81 => tcb_info.model = Some(value),
+            0x82 => tcb_info.version = Some(value),
+            0x83 => tcb_info.svn = Some(get_dice_integer(value)?),
+            0x84 => tcb_info.layer = Some(get_dice_integer(value)?),
+            0x85 => tcb_info.index = Some(get_dice_integer(value)?),
+            // fwids [6] IMPLICIT SEQUENCE OF FWID
+            0x86 => {
+                let mut w_walker = 0usize;
+                while w_walker < value.len() {
+                    // FWID ::= SEQUENCE { hashAlg OBJECT IDENTIFIER, digest OCTET STRING }
+                    let (_, fwid, size) = get_tlv(&value[w_walker..], Some(ASN1_TAG_SEQUENCE))?;
+                    w_walker += size;
+                    let (_, hash_alg, size) =
+                        get_tlv(fwid, Some(ASN1_TAG_NUMBER_OBJECT_IDENTIFIER))?;
+                    let (_, digest, _) =
+                        get_tlv(&fwid[size..], Some(ASN1_TAG_NUMBER_OCTET_STRING))?;
+                    tcb_info.fwids.push(SpdmDiceFwid { hash_alg, digest });
+                }
+            }
+            0x87 => tcb_info.flags = Some(value),
+            0x88 => tcb_info.vendor_info = Some(value),
+            0x89 => tcb_info.tcb_type = Some(value),
+            // flagsMask and later additions
+            _ => {}
+        }
+    }
+    Ok(tcb_info)
+}
+
+// IN content octets of a non negative INTEGER
+fn get_dice_integer(data: &[u8]) -> SpdmResult<u32> {
+    if data.is_empty() || data[0] & 0x80 != 0 {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    // a leading 0 keeps the sign bit of the next octet clear
+    let data = if data.len() > 1 && data[0] == 0 {
+        &data[1..]
+    } else {
+        data
+    };
+    if data.len() > 4 {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    Ok(data
+        .iter()
+        .fold(0u32, |acc, byte| (acc << 8) | *byte as u32))
+}
+
+// IN KeyUsage BIT STRING
+fn get_key_usage(data: &[u8]) -> SpdmResult<SpdmX509KeyUsage> {
+    let (_, bit_string, _) = get_tlv(data, Some(ASN1_TAG_NUMBER_BIT_STRING))?;
+    if bit_string.is_empty() {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let mut bits = 0u16;
+    for (i, byte) in bit_string[1..].iter().take(2).enumerate() {
+        for bit in 0..8 {
+            if byte & (0x80 >> bit) != 0 {
+                bits |= 1 << (i * 8 + bit);
+            }
+        }
+    }
+    Ok(SpdmX509KeyUsage::from_bits_truncate(bits))
+}
+
+fn get_general_name(tag: u8, name: &[u8]) -> SpdmResult<SpdmGeneralName<'_>> {
+    Ok(
+        match tag & !(ASN1_TAG_CLASS_CONTEXT_SPECIFIC_MASK | ASN1_FORM_CONSTRUCTED_MASK) {
+            // otherName [0] { type-id OBJECT IDENTIFIER, value [0] EXPLICIT ANY }
+            0 => {
+                let (_, type_id, size) = get_tlv(name, Some(ASN1_TAG_NUMBER_OBJECT_IDENTIFIER))?;
+                let (_, value, _) = get_tlv(
+                    &name[size..],
+                    Some(ASN1_TAG_CLASS_CONTEXT_SPECIFIC_MASK | ASN1_FORM_CONSTRUCTED_MASK),
+                )?;
+                SpdmGeneralName::OtherName { type_id, value }
+            }
+            1 => SpdmGeneralName::Rfc822Name(name),
+            2 => SpdmGeneralName::DnsName(name),
+            4 => SpdmGeneralName::DirectoryName(name),
+            6 => SpdmGeneralName::Uri(name),
+            7 => SpdmGeneralName::IpAddress(name),
+            _ => SpdmGeneralName::Other { tag, value: name },
+        },
+    )
+}
+
+// IN DER encoded Name
+// OUT Ok content of the first commonName attribute, if any
+fn get_common_name(name: &[u8]) -> SpdmResult<Option<&[u8]>> {
+    let (_, rdn_sequence, _) = get_tlv(name, Some(ASN1_TAG_SEQUENCE))?;
+    let mut r_walker = 0usize;
+    while r_walker < rdn_sequence.len() {
+        let (_, rdn, size) = get_tlv(&rdn_sequence[r_walker..], Some(ASN1_TAG_SET))?;
+        r_walker += size;
+        let mut a_walker = 0usize;
+        while a_walker < rdn.len() {
+            // AttributeTypeAndValue ::= SEQUENCE { type, value }
+            let (_, attribute, size) = get_tlv(&rdn[a_walker..], Some(ASN1_TAG_SEQUENCE))?;
+            a_walker += size;
+            let (_, oid, size) = get_tlv(attribute, Some(ASN1_TAG_NUMBER_OBJECT_IDENTIFIER))?;
+            if object_identifiers_are_same(oid, OID_COMMON_NAME) {
+                let (_, value, _) = get_tlv(&attribute[size..], None)?;
+                return Ok(Some(value));
+            }
+        }
+    }
+    Ok(None)
+}
+
+// IN bytes slice, expected tag if any
+// OUT Ok (tag, content, bytes consumed)
+// OUT Error Mulformed certificate found
+fn get_tlv(data: &[u8], tag: Option<u8>) -> SpdmResult<(u8, &[u8], usize)> {
+    if data.is_empty() || tag.map_or(false, |tag| tag != data[0]) {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    let (length, bytes_consumed) = check_length(&data[1..])?;
+    let start = 1 + bytes_consumed;
+    if data.len() - start < length {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+    Ok((data[0], &data[start..start + length], start + length))
+}
+
 fn get_oid_by_base_asym_algo(base_asym_algo: SpdmBaseAsymAlgo) -> Option<&'static [u8]> {
     match base_asym_algo {
         SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048 => Some(OID_RSA_SHA256RSA),
@@ -295,6 +1057,7 @@ fn get_oid_by_base_asym_algo(base_asym_algo: SpdmBaseAsymAlgo) -> Option<&'stati
         SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096 => Some(OID_RSA_SHA512RSA),
         SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => Some(OID_RSA_SHA512RSA),
         SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => Some(OID_ECDSA_SHA384),
+        SpdmBaseAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256 => Some(OID_SM2_SM3),
         _ => None,
     }
 }
@@ -317,6 +1080,167 @@ fn object_identifiers_are_same(a: &[u8], b: &[u8]) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_case0_get_public_key_from_spki() {
+        // SEQUENCE { SEQUENCE { OID ecPublicKey }, BIT STRING 04 01 02 }
+        let spki = &[
+            0x30, 0x11, 0x30, 0x09, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x03,
+            0x04, 0x00, 0x04, 0x01, 0x02,
+        ];
+        assert_eq!(
+            get_public_key_from_spki(spki),
+            Ok(&[0x04u8, 0x01, 0x02][..])
+        );
+        assert!(get_public_key_from_spki(&spki[..spki.len() - 1]).is_err());
+
+        let mut unused_bits = *spki;
+        unused_bits[15] = 0x01;
+        assert!(get_public_key_from_spki(&unused_bits).is_err());
+
+        let mut not_bit_string = *spki;
+        not_bit_string[13] = 0x04;
+        assert!(get_public_key_from_spki(&not_bit_string).is_err());
+    }
+
+    #[test]
+    fn test_case0_get_cert_info() {
+        let cert = std::fs::read("../test_key/ecp384/end_responder.cert.der")
+            .expect("unable to read leaf cert!");
+        let cert_info = get_cert_info(&cert).unwrap();
+        assert_eq!(cert_info.serial_number, &[0x03u8][..]);
+        assert_eq!(
+            cert_info.subject_common_name,
+            Some(&b"DMTF libspdm ECP256 responder cert"[..])
+        );
+        // Apr  3 05:55:54 2023 GMT - Mar 31 05:55:54 2033 GMT
+        assert_eq!(cert_info.not_before, 1680501354);
+        assert_eq!(cert_info.not_after, 1995861354);
+        assert!(!cert_info.is_ca);
+        assert_eq!(
+            cert_info.key_usage,
+            Some(
+                SpdmX509KeyUsage::DIGITAL_SIGNATURE
+                    | SpdmX509KeyUsage::NON_REPUDIATION
+                    | SpdmX509KeyUsage::KEY_ENCIPHERMENT
+            )
+        );
+        assert_eq!(
+            cert_info.get_hardware_identity(),
+            Some(&b"\x0c\x16ACME:WIDGET:1234567890"[..])
+        );
+        assert_eq!(cert_info.extended_key_usage.len(), 3);
+        assert!(
+            cert_info
+                .get_extension(OID_EXTENDED_KEY_USAGE)
+                .unwrap()
+                .critical
+        );
+        assert!(cert_info.get_extension(OID_DMTF_SPDM_EXTENSION).is_some());
+        assert!(!cert_info.has_dice_tcb_info());
+        assert!(get_public_key_from_spki(cert_info.subject_public_key_info).is_ok());
+
+        let ca = std::fs::read("../test_key/ecp384/ca.cert.der").expect("unable to read ca cert!");
+        let ca_info = get_cert_info(&ca).unwrap();
+        assert!(ca_info.is_ca);
+        assert_eq!(ca_info.path_len_constraint, None);
+        assert_eq!(ca_info.issuer, ca_info.subject);
+
+        assert!(get_cert_info(&cert[..cert.len() - 1]).is_err());
+        assert!(get_cert_info(&cert[..100]).is_err());
+    }
+
+    #[test]
+    fn test_case0_check_issuer() {
+        let cert = std::fs::read("../test_key/ecp384/end_responder.cert.der")
+            .expect("unable to read leaf cert!");
+        let cert_info = get_cert_info(&cert).unwrap();
+        assert!(check_issuer(&cert_info, 0).is_err());
+
+        let ca = std::fs::read("../test_key/ecp384/ca.cert.der").expect("unable to read ca cert!");
+        let mut ca_info = get_cert_info(&ca).unwrap();
+        assert!(check_issuer(&ca_info, 0).is_ok());
+        assert!(check_issuer(&ca_info, 2).is_ok());
+
+        ca_info.path_len_constraint = Some(0);
+        assert!(check_issuer(&ca_info, 0).is_ok());
+        assert!(check_issuer(&ca_info, 1).is_err());
+
+        ca_info.path_len_constraint = None;
+        ca_info.key_usage = Some(SpdmX509KeyUsage::KEY_CERT_SIGN);
+        assert!(check_issuer(&ca_info, 0).is_ok());
+        ca_info.key_usage = Some(SpdmX509KeyUsage::DIGITAL_SIGNATURE);
+        assert!(check_issuer(&ca_info, 0).is_err());
+    }
+
+    #[test]
+    fn test_case0_get_dice_tcb_info() {
+        #[rustfmt::skip]
+        const TCB_INFO: &[u8] = &[
+            0x30, 0x25,
+            // vendor
+            0x80, 0x04, b'A', b'C', b'M', b'E',
+            // svn, layer
+            0x83, 0x01, 0x02, 0x84, 0x01, 0x01,
+            // fwids, sha384
+            0xa6, 0x13, 0x30, 0x11,
+            0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02,
+            0x04, 0x04, 0x01, 0x02, 0x03, 0x04,
+            // flags
+            0x87, 0x02, 0x00, 0x80,
+        ];
+        let tcb_info = get_dice_tcb_info(TCB_INFO).unwrap();
+        assert_eq!(tcb_info.vendor, Some(&b"ACME"[..]));
+        assert_eq!(tcb_info.model, None);
+        assert_eq!(tcb_info.svn, Some(2));
+        assert_eq!(tcb_info.layer, Some(1));
+        assert_eq!(tcb_info.flags, Some(&[0x00u8, 0x80][..]));
+        assert_eq!(
+            tcb_info.fwids,
+            [SpdmDiceFwid {
+                hash_alg: &TCB_INFO[20..29],
+                digest: &[1, 2, 3, 4],
+            }]
+        );
+        assert!(get_dice_tcb_info(&TCB_INFO[..TCB_INFO.len() - 1]).is_err());
+
+        let mut multi_tcb_info = alloc::vec![0x30, 2 * TCB_INFO.len() as u8];
+        multi_tcb_info.extend_from_slice(TCB_INFO);
+        multi_tcb_info.extend_from_slice(TCB_INFO);
+        let ca = std::fs::read("../test_key/ecp384/ca.cert.der").expect("unable to read ca cert!");
+        let mut cert_info = get_cert_info(&ca).unwrap();
+        assert!(cert_info.get_dice_tcb_info().unwrap().is_empty());
+        cert_info.extensions.push(SpdmCertExtension {
+            oid: OID_DICE_MULTI_TCB_INFO,
+            critical: true,
+            value: &multi_tcb_info,
+        });
+        assert!(cert_info.has_dice_tcb_info());
+        assert_eq!(cert_info.get_dice_tcb_info().unwrap().len(), 2);
+        assert_eq!(cert_info.get_dice_fwids().unwrap().len(), 2);
+
+        assert_eq!(get_dice_integer(&[0x00, 0xff]), Ok(0xff));
+        assert!(get_dice_integer(&[0xff]).is_err());
+        assert!(get_dice_integer(&[0x01, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_case0_get_time() {
+        let utc_time = b"\x17\x0d230102030405Z";
+        assert_eq!(get_time(utc_time), Ok((1672628645, 15)));
+        let utc_time = b"\x17\x0d700101000000Z";
+        assert_eq!(get_time(utc_time), Ok((0, 15)));
+        let generalized_time = b"\x18\x0f20500101000000Z";
+        assert_eq!(get_time(generalized_time), Ok((2524608000, 17)));
+        let generalized_time = b"\x18\x0f20000229235959Z";
+        assert_eq!(get_time(generalized_time), Ok((951868799, 17)));
+
+        assert!(get_time(b"\x17\x0d230102030405").is_err());
+        assert!(get_time(b"\x17\x0d231302030405Z").is_err());
+        assert!(get_time(b"\x17\x0d2301020304a5Z").is_err());
+        assert!(get_time(b"\x18\x0d230102030405Z").is_err());
+        assert!(get_time(b"\x04\x0d230102030405Z").is_err());
+    }
+
     #[test]
     fn test_case0_object_identifiers_are_same() {
         let lt = [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0bu8];
@@ -340,6 +1264,10 @@ mod tests {
             get_oid_by_base_asym_algo(SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256),
             Some(OID_ECDSA_SHA256)
         );
+        assert_eq!(
+            get_oid_by_base_asym_algo(SpdmBaseAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256),
+            Some(OID_SM2_SM3)
+        );
     }
 
     #[test]
@@ -467,6 +1395,21 @@ mod tests {
         assert_eq!(check_length(&l3_wrong), Err(SPDM_STATUS_VERIF_FAIL));
     }
 
+    #[test]
+    fn test_case0_get_cert_signature() {
+        let cert =
+            std::fs::read("../test_key/ecp384/ca.cert.der").expect("unable to read ca cert!");
+        let (tbs, signature_oid, signature) = get_cert_signature(&cert).unwrap();
+        assert_eq!(tbs[0], ASN1_TAG_SEQUENCE);
+        assert_eq!(signature_oid, OID_ECDSA_SHA384);
+        assert_eq!(signature[0], ASN1_TAG_SEQUENCE);
+
+        assert_eq!(
+            get_cert_signature(&cert[..cert.len() - 1]),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+    }
+
     #[test]
     fn test_case0_check_tag_is_sequence() {
         let l1 = [0x30];
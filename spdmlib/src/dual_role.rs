@@ -0,0 +1,149 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Endpoint acting as SPDM responder toward the upstream requester (e.g. the
+//! host) and as SPDM requester toward downstream devices.
+//!
+//! Both roles keep their own SpdmContext: config_info, provision_info,
+//! negotiated state and sessions are never shared. The crypto and secret
+//! callbacks are registered once for the process and serve both roles, so
+//! a callback which needs role specific data, e.g. the responder signing
+//! key and the requester mutual authentication key, selects it with
+//! current_role(), unless each context sets its own common.secret_provider.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::error::SpdmResult;
+use crate::requester::RequesterContext;
+use crate::responder::ResponderContext;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmRole {
+    Requester,
+    Responder,
+}
+
+const ROLE_NONE: u8 = 0;
+const ROLE_REQUESTER: u8 = 1;
+const ROLE_RESPONDER: u8 = 2;
+
+static CURRENT_ROLE: AtomicU8 = AtomicU8::new(ROLE_NONE);
+
+impl SpdmRole {
+    fn get_u8(self) -> u8 {
+        match self {
+            SpdmRole::Requester => ROLE_REQUESTER,
+            SpdmRole::Responder => ROLE_RESPONDER,
+        }
+    }
+
+    fn from_u8(role: u8) -> Option<SpdmRole> {
+        match role {
+            ROLE_REQUESTER => Some(SpdmRole::Requester),
+            ROLE_RESPONDER => Some(SpdmRole::Responder),
+            _ => None,
+        }
+    }
+}
+
+/// Role the DualRoleContext is running, None outside of DualRoleContext.
+/// Intended to be called from the crypto and secret callbacks.
+pub fn current_role() -> Option<SpdmRole> {
+    SpdmRole::from_u8(CURRENT_ROLE.load(Ordering::SeqCst))
+}
+
+/// Set the current role until dropped, then restore the previous one,
+/// so the downstream requester may be driven from an upstream request.
+struct SpdmRoleGuard {
+    previous: u8,
+}
+
+impl SpdmRoleGuard {
+    fn enter(role: SpdmRole) -> Self {
+        SpdmRoleGuard {
+            previous: CURRENT_ROLE.swap(role.get_u8(), Ordering::SeqCst),
+        }
+    }
+}
+
+impl Drop for SpdmRoleGuard {
+    fn drop(&mut self) {
+        CURRENT_ROLE.store(self.previous, Ordering::SeqCst);
+    }
+}
+
+pub struct DualRoleContext<'a> {
+    // serves the upstream requester
+    pub responder: ResponderContext<'a>,
+    // talks to the downstream responder
+    pub requester: RequesterContext<'a>,
+}
+
+impl<'a> DualRoleContext<'a> {
+    pub fn new(responder: ResponderContext<'a>, requester: RequesterContext<'a>) -> Self {
+        DualRoleContext {
+            responder,
+            requester,
+        }
+    }
+
+    /// Receive one request from the upstream requester and respond to it,
+    /// see ResponderContext::process_message.
+    pub fn process_upstream_message(
+        &mut self,
+        timeout: usize,
+        auxiliary_app_data: &[u8],
+    ) -> Result<bool, (usize, Vec<u8>)> {
+        let _role = SpdmRoleGuard::enter(SpdmRole::Responder);
+        self.responder.process_message(timeout, auxiliary_app_data)
+    }
+
+    /// Hand the pending async message of the downstream responder, if any,
+    /// to the registered handlers, see RequesterContext::process_async_message.
+    pub fn process_downstream_async_message(&mut self) -> SpdmResult<bool> {
+        let _role = SpdmRoleGuard::enter(SpdmRole::Requester);
+        self.requester.process_async_message()
+    }
+
+    /// Run f with the upstream responder, e.g. to send an event.
+    pub fn with_upstream<T, F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut ResponderContext<'a>) -> T,
+    {
+        let _role = SpdmRoleGuard::enter(SpdmRole::Responder);
+        f(&mut self.responder)
+    }
+
+    /// Run f with the downstream requester, e.g. to attest a downstream device.
+    pub fn with_downstream<T, F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut RequesterContext<'a>) -> T,
+    {
+        let _role = SpdmRoleGuard::enter(SpdmRole::Requester);
+        f(&mut self.requester)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_role_guard() {
+        assert_eq!(current_role(), None);
+        {
+            let _responder = SpdmRoleGuard::enter(SpdmRole::Responder);
+            assert_eq!(current_role(), Some(SpdmRole::Responder));
+            {
+                let _requester = SpdmRoleGuard::enter(SpdmRole::Requester);
+                assert_eq!(current_role(), Some(SpdmRole::Requester));
+            }
+            assert_eq!(current_role(), Some(SpdmRole::Responder));
+        }
+        assert_eq!(current_role(), None);
+    }
+}
@@ -62,6 +62,11 @@ pub enum StatusCodeCore {
 
     // only in Rust-SPDM
     DECODE_AEAD_FAIL = 0xFE,
+    VENDOR_ERROR_PEER = 0xFD,
+    RESET_REQUIRED_PEER = 0xFC,
+    SEQUENCE_NUMBER_MISMATCH = 0xFB,
+    PEER_CAP_VIOLATION = 0xFA,
+    CANCELLED = 0xF9,
 }
 
 impl TryFrom<u16> for StatusCodeCore {
@@ -88,6 +93,11 @@ impl TryFrom<u16> for StatusCodeCore {
             16 => Ok(Self::ACQUIRE_FAIL),
             17 => Ok(Self::SESSION_TRY_DISCARD_KEY_UPDATE),
             0xFE => Ok(Self::DECODE_AEAD_FAIL),
+            0xFD => Ok(Self::VENDOR_ERROR_PEER),
+            0xFC => Ok(Self::RESET_REQUIRED_PEER),
+            0xFB => Ok(Self::SEQUENCE_NUMBER_MISMATCH),
+            0xFA => Ok(Self::PEER_CAP_VIOLATION),
+            0xF9 => Ok(Self::CANCELLED),
             _ => Err(()),
         }
     }
@@ -521,6 +531,36 @@ pub const SPDM_STATUS_DECODE_AEAD_FAIL: SpdmStatus = spdm_return_status!(
     StatusCode::CORE(StatusCodeCore::DECODE_AEAD_FAIL)
 );
 
+/*  Received a VendorDefined error message. */
+pub const SPDM_STATUS_VENDOR_ERROR_PEER: SpdmStatus = spdm_return_status!(
+    StatusSeverity::ERROR,
+    StatusCode::CORE(StatusCodeCore::VENDOR_ERROR_PEER)
+);
+
+/*  Received a ResetRequired error message. */
+pub const SPDM_STATUS_RESET_REQUIRED_PEER: SpdmStatus = spdm_return_status!(
+    StatusSeverity::ERROR,
+    StatusCode::CORE(StatusCodeCore::RESET_REQUIRED_PEER)
+);
+
+/*  Sequence number of a received secured message is not the expected one. */
+pub const SPDM_STATUS_SEQUENCE_NUMBER_MISMATCH: SpdmStatus = spdm_return_status!(
+    StatusSeverity::ERROR,
+    StatusCode::CORE(StatusCodeCore::SEQUENCE_NUMBER_MISMATCH)
+);
+
+/*  Received a response to a feature the peer did not advertise in CAPABILITIES. */
+pub const SPDM_STATUS_PEER_CAP_VIOLATION: SpdmStatus = spdm_return_status!(
+    StatusSeverity::ERROR,
+    StatusCode::CORE(StatusCodeCore::PEER_CAP_VIOLATION)
+);
+
+/*  The operation was cancelled through the SpdmCancelToken of the context. */
+pub const SPDM_STATUS_CANCELLED: SpdmStatus = spdm_return_status!(
+    StatusSeverity::ERROR,
+    StatusCode::CORE(StatusCodeCore::CANCELLED)
+);
+
 /* - Cryptography Errors - */
 
 /*  Generic failure originating from the cryptography module. */
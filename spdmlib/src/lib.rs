@@ -20,6 +20,7 @@ pub mod protocol;
 pub mod error;
 pub mod common;
 pub mod crypto;
+pub mod dual_role;
 pub mod message;
 pub mod requester;
 pub mod responder;
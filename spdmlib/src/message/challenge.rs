@@ -7,8 +7,8 @@ use crate::common::opaque::SpdmOpaqueStruct;
 use crate::common::spdm_codec::SpdmCodec;
 use crate::error::{SpdmStatus, SPDM_STATUS_BUFFER_FULL};
 use crate::protocol::{
-    SpdmDigestStruct, SpdmMeasurementSummaryHashType, SpdmNonceStruct, SpdmResponseCapabilityFlags,
-    SpdmSignatureStruct,
+    SpdmDigestStruct, SpdmMeasurementSummaryHashType, SpdmNonceStruct, SpdmReqContextStruct,
+    SpdmResponseCapabilityFlags, SpdmSignatureStruct, SpdmVersion,
 };
 use codec::{Codec, Reader, Writer};
 
@@ -17,12 +17,13 @@ pub struct SpdmChallengeRequestPayload {
     pub slot_id: u8,
     pub measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
     pub nonce: SpdmNonceStruct,
+    pub requester_context: SpdmReqContextStruct,
 }
 
 impl SpdmCodec for SpdmChallengeRequestPayload {
     fn spdm_encode(
         &self,
-        _context: &mut common::SpdmContext,
+        context: &mut common::SpdmContext,
         bytes: &mut Writer,
     ) -> Result<usize, SpdmStatus> {
         let mut cnt = 0usize;
@@ -38,6 +39,12 @@ impl SpdmCodec for SpdmChallengeRequestPayload {
             .nonce
             .encode(bytes)
             .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        if context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion13.get_u8() {
+            cnt += self
+                .requester_context
+                .encode(bytes)
+                .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        }
         Ok(cnt)
     }
 
@@ -66,11 +73,19 @@ impl SpdmCodec for SpdmChallengeRequestPayload {
             SpdmMeasurementSummaryHashType::Unknown(_) => return None,
         }
         let nonce = SpdmNonceStruct::read(r)?;
+        let requester_context = if context.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion13.get_u8()
+        {
+            SpdmReqContextStruct::read(r)?
+        } else {
+            SpdmReqContextStruct::default()
+        };
 
         Some(SpdmChallengeRequestPayload {
             slot_id,
             measurement_summary_hash_type,
             nonce,
+            requester_context,
         })
     }
 }
@@ -91,6 +106,7 @@ pub struct SpdmChallengeAuthResponsePayload {
     pub nonce: SpdmNonceStruct,
     pub measurement_summary_hash: SpdmDigestStruct,
     pub opaque: SpdmOpaqueStruct,
+    pub requester_context: SpdmReqContextStruct,
     pub signature: SpdmSignatureStruct,
 }
 
@@ -116,6 +132,12 @@ impl SpdmCodec for SpdmChallengeAuthResponsePayload {
             cnt += self.measurement_summary_hash.spdm_encode(context, bytes)?;
         }
         cnt += self.opaque.spdm_encode(context, bytes)?;
+        if context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion13.get_u8() {
+            cnt += self
+                .requester_context
+                .encode(bytes)
+                .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        }
         cnt += self.signature.spdm_encode(context, bytes)?;
         Ok(cnt)
     }
@@ -136,6 +158,13 @@ impl SpdmCodec for SpdmChallengeAuthResponsePayload {
             SpdmDigestStruct::default()
         };
         let opaque = SpdmOpaqueStruct::spdm_read(context, r)?;
+        let requester_context = if context.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion13.get_u8()
+        {
+            SpdmReqContextStruct::read(r)?
+        } else {
+            SpdmReqContextStruct::default()
+        };
         let signature = SpdmSignatureStruct::spdm_read(context, r)?;
         Some(SpdmChallengeAuthResponsePayload {
             slot_id,
@@ -145,6 +174,7 @@ impl SpdmCodec for SpdmChallengeAuthResponsePayload {
             nonce,
             measurement_summary_hash,
             opaque,
+            requester_context,
             signature,
         })
     }
@@ -174,6 +204,7 @@ mod tests {
             nonce: SpdmNonceStruct {
                 data: [100u8; SPDM_NONCE_SIZE],
             },
+            requester_context: SpdmReqContextStruct::default(),
         };
 
         create_spdm_context!(context);
@@ -226,6 +257,7 @@ mod tests {
                 data_size: SPDM_MAX_ASYM_KEY_SIZE as u16,
                 data: [0x55u8; SPDM_MAX_ASYM_KEY_SIZE],
             },
+            requester_context: SpdmReqContextStruct::default(),
         };
 
         create_spdm_context!(context);
@@ -316,6 +348,7 @@ mod tests {
                 data_size: SPDM_MAX_ASYM_KEY_SIZE as u16,
                 data: [0x55u8; SPDM_MAX_ASYM_KEY_SIZE],
             },
+            requester_context: SpdmReqContextStruct::default(),
         };
 
         create_spdm_context!(context);
@@ -23,6 +23,7 @@ fn test_challenge_struct() {
         measurement_summary_hash_type:
             SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll,
         nonce: SpdmNonceStruct::default(),
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(request.spdm_encode(context, writer).is_ok());
     assert_eq!(writer.used(), 34);
@@ -46,6 +47,37 @@ fn test_challenge_struct() {
     assert!(ret.is_none());
 }
 
+#[test]
+fn test_challenge_struct_requester_context() {
+    create_spdm_context!(context);
+    let context = &mut context;
+    context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+
+    // Validate request payload size is 44 - 2 = 42 with RequesterContext
+    let u8_slice = &mut [0u8; 44];
+    let writer = &mut Writer::init(u8_slice);
+    let request = SpdmChallengeRequestPayload {
+        slot_id: 0,
+        measurement_summary_hash_type:
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+        nonce: SpdmNonceStruct::default(),
+        requester_context: SpdmReqContextStruct { data: [0xa5; 8] },
+    };
+    assert!(request.spdm_encode(context, writer).is_ok());
+    assert_eq!(writer.used(), 42);
+
+    let reader = &mut Reader::init(&u8_slice[..42]);
+    let ret = SpdmChallengeRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(ret.requester_context.data, [0xa5; 8]);
+    assert_eq!(reader.left(), 0);
+
+    // RequesterContext is absent before 1.3
+    context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    let writer = &mut Writer::init(u8_slice);
+    assert!(request.spdm_encode(context, writer).is_ok());
+    assert_eq!(writer.used(), 34);
+}
+
 #[ignore = "Extend unit tests"]
 #[test]
 fn test_challenge_struct_opaque_data_length_negative() {
@@ -0,0 +1,241 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::spdm_codec::SpdmCodec;
+use crate::config;
+use crate::error::SPDM_STATUS_BUFFER_FULL;
+use crate::{common, error::SpdmStatus};
+use codec::{Codec, Reader, Writer};
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+pub const MAX_SPDM_CHUNK_SIZE: usize = config::MAX_SPDM_MSG_SIZE;
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmChunkGetRequestPayload {
+    pub handle: u8,
+    pub chunk_seq_no: u16,
+}
+
+impl SpdmCodec for SpdmChunkGetRequestPayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += self
+            .handle
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .chunk_seq_no
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmChunkGetRequestPayload> {
+        u8::read(r)?; // param1
+        let handle = u8::read(r)?; // param2
+        let chunk_seq_no = u16::read(r)?;
+
+        Some(SpdmChunkGetRequestPayload {
+            handle,
+            chunk_seq_no,
+        })
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct SpdmChunkSenderAttributes: u8 {
+        const LAST_CHUNK = 0b00000001;
+    }
+}
+
+impl Codec for SpdmChunkSenderAttributes {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        self.bits().encode(bytes)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmChunkSenderAttributes> {
+        let bits = u8::read(r)?;
+
+        SpdmChunkSenderAttributes::from_bits(bits)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmChunkResponsePayload {
+    pub chunk_sender_attributes: SpdmChunkSenderAttributes,
+    pub handle: u8,
+    pub chunk_seq_no: u16,
+    // only present in the first chunk
+    pub large_message_size: u32,
+    // ChunkSize is the length of chunk
+    pub chunk: Vec<u8>,
+}
+
+impl SpdmCodec for SpdmChunkResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        if self.chunk.len() > MAX_SPDM_CHUNK_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+
+        let mut cnt = 0usize;
+        cnt += self
+            .chunk_sender_attributes
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += self
+            .handle
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .chunk_seq_no
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += 0u16.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // reserved
+        cnt += (self.chunk.len() as u32)
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        if self.chunk_seq_no == 0 {
+            cnt += self
+                .large_message_size
+                .encode(bytes)
+                .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        }
+        cnt += bytes
+            .extend_from_slice(&self.chunk)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmChunkResponsePayload> {
+        let chunk_sender_attributes = SpdmChunkSenderAttributes::read(r)?; // param1
+        let handle = u8::read(r)?; // param2
+        let chunk_seq_no = u16::read(r)?;
+        u16::read(r)?; // reserved
+        let chunk_size = u32::read(r)?;
+        let large_message_size = if chunk_seq_no == 0 { u32::read(r)? } else { 0 };
+        if chunk_size as usize > MAX_SPDM_CHUNK_SIZE {
+            return None;
+        }
+
+        let chunk = r.take(chunk_size as usize)?.to_vec();
+
+        Some(SpdmChunkResponsePayload {
+            chunk_sender_attributes,
+            handle,
+            chunk_seq_no,
+            large_message_size,
+            chunk,
+        })
+    }
+}
+
+/// CHUNK_SEND shares the layout of CHUNK_RESPONSE.
+pub type SpdmChunkSendRequestPayload = SpdmChunkResponsePayload;
+
+bitflags! {
+    #[derive(Default)]
+    pub struct SpdmChunkReceiverAttributes: u8 {
+        const EARLY_ERROR_DETECTED = 0b00000001;
+    }
+}
+
+impl Codec for SpdmChunkReceiverAttributes {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        self.bits().encode(bytes)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmChunkReceiverAttributes> {
+        let bits = u8::read(r)?;
+
+        SpdmChunkReceiverAttributes::from_bits(bits)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmChunkSendAckResponsePayload {
+    pub chunk_receiver_attributes: SpdmChunkReceiverAttributes,
+    pub handle: u8,
+    pub chunk_seq_no: u16,
+    // the response to the large request, or an ERROR if
+    // EARLY_ERROR_DETECTED is set. Empty for other chunks.
+    pub response: Vec<u8>,
+}
+
+impl SpdmCodec for SpdmChunkSendAckResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        if self.response.len() > MAX_SPDM_CHUNK_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+
+        let mut cnt = 0usize;
+        cnt += self
+            .chunk_receiver_attributes
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += self
+            .handle
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .chunk_seq_no
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += bytes
+            .extend_from_slice(&self.response)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmChunkSendAckResponsePayload> {
+        let chunk_receiver_attributes = SpdmChunkReceiverAttributes::read(r)?; // param1
+        let handle = u8::read(r)?; // param2
+        let chunk_seq_no = u16::read(r)?;
+        let response_size = r.left();
+        if response_size > MAX_SPDM_CHUNK_SIZE {
+            return None;
+        }
+
+        let response = r.take(response_size)?.to_vec();
+
+        Some(SpdmChunkSendAckResponsePayload {
+            chunk_receiver_attributes,
+            handle,
+            chunk_seq_no,
+            response,
+        })
+    }
+}
+
+#[cfg(all(test,))]
+#[path = "mod_test.common.inc.rs"]
+mod testlib;
+
+#[cfg(all(test,))]
+#[path = "chunk_test.rs"]
+mod chunk_test;
@@ -0,0 +1,114 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::*;
+use crate::common::{SpdmCodec, SpdmConfigInfo, SpdmContext, SpdmProvisionInfo};
+use testlib::{create_spdm_context, DeviceIO, TransportEncap};
+
+#[test]
+fn test_chunk_get_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    // 1. Validate CHUNK_GET request length is 6.
+    let u8_slice = &mut [0u8; 8];
+    let mut writer = Writer::init(&mut u8_slice[2..]);
+    let value = SpdmChunkGetRequestPayload {
+        handle: 0x5A,
+        chunk_seq_no: 0x0102,
+    };
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(4));
+    assert_eq!(u8_slice[2..6], [0x00, 0x5A, 0x02, 0x01]);
+
+    let reader = &mut Reader::init(&u8_slice[2..6]);
+    let ret = SpdmChunkGetRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.handle, 0x5A);
+    assert_eq!(ret.chunk_seq_no, 0x0102);
+}
+
+#[test]
+fn test_chunk_response_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    // 1. The first chunk carries LargeMessageSize.
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    let mut value = SpdmChunkResponsePayload {
+        handle: 0x5A,
+        chunk_seq_no: 0,
+        large_message_size: 0x100,
+        chunk: [1, 2, 3, 4].to_vec(),
+        ..Default::default()
+    };
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(18));
+
+    let reader = &mut Reader::init(&u8_slice[..18]);
+    let ret = SpdmChunkResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.handle, 0x5A);
+    assert_eq!(ret.large_message_size, 0x100);
+    assert_eq!(ret.chunk, [1, 2, 3, 4]);
+    assert!(!ret
+        .chunk_sender_attributes
+        .contains(SpdmChunkSenderAttributes::LAST_CHUNK));
+
+    // 2. Later chunks do not carry LargeMessageSize.
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    value.chunk_seq_no = 1;
+    value.chunk_sender_attributes = SpdmChunkSenderAttributes::LAST_CHUNK;
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(14));
+
+    let reader = &mut Reader::init(&u8_slice[..14]);
+    let ret = SpdmChunkResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.chunk_seq_no, 1);
+    assert_eq!(ret.large_message_size, 0);
+    assert!(ret
+        .chunk_sender_attributes
+        .contains(SpdmChunkSenderAttributes::LAST_CHUNK));
+
+    // 3. Truncated chunk data is rejected.
+    let reader = &mut Reader::init(&u8_slice[..13]);
+    assert!(SpdmChunkResponsePayload::spdm_read(context, reader).is_none());
+}
+
+#[test]
+fn test_chunk_send_ack_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    // 1. Acks of intermediate chunks carry no response.
+    let u8_slice = &mut [0u8; 16];
+    let mut writer = Writer::init(u8_slice);
+    let mut value = SpdmChunkSendAckResponsePayload {
+        handle: 0x5A,
+        chunk_seq_no: 3,
+        ..Default::default()
+    };
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(4));
+    assert_eq!(u8_slice[..4], [0x00, 0x5A, 0x03, 0x00]);
+
+    let reader = &mut Reader::init(&u8_slice[..4]);
+    let ret = SpdmChunkSendAckResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(ret.chunk_seq_no, 3);
+    assert!(ret.response.is_empty());
+
+    // 2. The response takes the remaining bytes.
+    let u8_slice = &mut [0u8; 16];
+    let mut writer = Writer::init(u8_slice);
+    value.chunk_receiver_attributes = SpdmChunkReceiverAttributes::EARLY_ERROR_DETECTED;
+    value.response = [0x12, 0x7F, 0x01, 0x00].to_vec();
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(8));
+
+    let reader = &mut Reader::init(&u8_slice[..8]);
+    let ret = SpdmChunkSendAckResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert!(ret
+        .chunk_receiver_attributes
+        .contains(SpdmChunkReceiverAttributes::EARLY_ERROR_DETECTED));
+    assert_eq!(ret.response, [0x12, 0x7F, 0x01, 0x00]);
+}
@@ -0,0 +1,288 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Property based codec tests for every SPDM message payload.
+//!
+//! For any input that decodes, the decoded value must encode to exactly the
+//! bytes consumed, and encode -> decode -> encode must be stable.
+//! Length mutations: every prefix of a valid encoding either fails to decode
+//! or obeys the same rule, and trailing bytes must be left unread.
+
+use super::testlib::{create_spdm_context, DeviceIO, TransportEncap};
+use super::*;
+use crate::common::{SpdmConfigInfo, SpdmOpaqueSupport, SpdmProvisionInfo};
+use crate::config::MAX_SPDM_MSG_SIZE;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+
+const MAX_JUNK_SIZE: usize = 16;
+
+fn setup_context(context: &mut SpdmContext, spdm_version: SpdmVersion, signed: bool) {
+    context.negotiate_info.spdm_version_sel = spdm_version;
+    context.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    context.negotiate_info.req_asym_sel = SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    context.negotiate_info.dhe_sel = SpdmDheAlgo::SECP_384_R1;
+    context.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    context.negotiate_info.opaque_data_support = SpdmOpaqueSupport::OPAQUE_DATA_FMT1;
+    context.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP
+        | SpdmResponseCapabilityFlags::CHAL_CAP
+        | SpdmResponseCapabilityFlags::MEAS_CAP_SIG
+        | SpdmResponseCapabilityFlags::KEY_EX_CAP
+        | SpdmResponseCapabilityFlags::PSK_CAP_WITH_CONTEXT
+        | SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
+    context.negotiate_info.req_capabilities_sel = SpdmRequestCapabilityFlags::CERT_CAP
+        | SpdmRequestCapabilityFlags::CHAL_CAP
+        | SpdmRequestCapabilityFlags::KEY_EX_CAP
+        | SpdmRequestCapabilityFlags::PSK_CAP
+        | SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
+    context.runtime_info.need_measurement_summary_hash = signed;
+    context.runtime_info.need_measurement_signature = signed;
+}
+
+fn spdm_version_strategy() -> impl Strategy<Value = SpdmVersion> {
+    prop_oneof![
+        Just(SpdmVersion::SpdmVersion10),
+        Just(SpdmVersion::SpdmVersion11),
+        Just(SpdmVersion::SpdmVersion12),
+        Just(SpdmVersion::SpdmVersion13),
+    ]
+}
+
+/// Decode bytes and check the decoded value re-encodes to the consumed bytes.
+/// Return the canonical encoding size, or None when bytes do not decode.
+fn check_decoded<T: SpdmCodec>(
+    context: &mut SpdmContext,
+    bytes: &[u8],
+    encoded: &mut [u8],
+) -> Result<Option<usize>, TestCaseError> {
+    let mut reader = Reader::init(bytes);
+    let value = match T::spdm_read(context, &mut reader) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let used = reader.used();
+
+    let mut writer = Writer::init(encoded);
+    let size = value.spdm_encode(context, &mut writer);
+    prop_assert!(size.is_ok(), "decoded value fails to encode");
+    let size = size.unwrap();
+    prop_assert_eq!(size, used, "encoded size differs from decoded size");
+    Ok(Some(size))
+}
+
+fn check_codec<T: SpdmCodec>(
+    context: &mut SpdmContext,
+    bytes: &[u8],
+    junk: &[u8],
+    greedy: bool,
+) -> Result<(), TestCaseError> {
+    let first = &mut [0u8; MAX_SPDM_MSG_SIZE];
+    let size = match check_decoded::<T>(context, bytes, first)? {
+        Some(size) => size,
+        None => return Ok(()),
+    };
+
+    // Round trip: encode -> decode -> encode.
+    let second = &mut [0u8; MAX_SPDM_MSG_SIZE];
+    let second_size = check_decoded::<T>(context, &first[..size], second)?;
+    prop_assert_eq!(
+        second_size,
+        Some(size),
+        "canonical encoding fails to decode"
+    );
+    prop_assert_eq!(&first[..size], &second[..size]);
+
+    // Length mutation: truncation.
+    let truncated = &mut [0u8; MAX_SPDM_MSG_SIZE];
+    for len in 0..size {
+        check_decoded::<T>(context, &first[..len], truncated)?;
+    }
+
+    // Length mutation: trailing bytes must not be consumed.
+    if !greedy {
+        let extended = &mut [0u8; MAX_SPDM_MSG_SIZE + MAX_JUNK_SIZE];
+        extended[..size].copy_from_slice(&first[..size]);
+        extended[size..size + junk.len()].copy_from_slice(junk);
+        let mut reader = Reader::init(&extended[..size + junk.len()]);
+        prop_assert!(T::spdm_read(context, &mut reader).is_some());
+        prop_assert_eq!(reader.used(), size, "trailing bytes consumed");
+    }
+    Ok(())
+}
+
+macro_rules! codec_proptest {
+    ($name:ident, $payload:ty) => {
+        codec_proptest!($name, $payload, false);
+    };
+    // greedy payloads take all the remaining bytes, e.g. vendor defined error data.
+    ($name:ident, $payload:ty, greedy) => {
+        codec_proptest!($name, $payload, true);
+    };
+    ($name:ident, $payload:ty, $greedy:expr) => {
+        proptest! {
+            #[test]
+            fn $name(
+                spdm_version in spdm_version_strategy(),
+                signed in any::<bool>(),
+                bytes in vec(any::<u8>(), 0..MAX_SPDM_MSG_SIZE),
+                junk in vec(any::<u8>(), 1..MAX_JUNK_SIZE),
+            ) {
+                create_spdm_context!(context);
+                setup_context(&mut context, spdm_version, signed);
+                check_codec::<$payload>(&mut context, &bytes, &junk, $greedy)?;
+            }
+        }
+    };
+}
+
+codec_proptest!(proptest_get_version_request, SpdmGetVersionRequestPayload);
+codec_proptest!(proptest_version_response, SpdmVersionResponsePayload);
+codec_proptest!(
+    proptest_get_capabilities_request,
+    SpdmGetCapabilitiesRequestPayload
+);
+codec_proptest!(
+    proptest_capabilities_response,
+    SpdmCapabilitiesResponsePayload
+);
+codec_proptest!(
+    proptest_negotiate_algorithms_request,
+    SpdmNegotiateAlgorithmsRequestPayload
+);
+codec_proptest!(proptest_algorithms_response, SpdmAlgorithmsResponsePayload);
+codec_proptest!(proptest_get_digests_request, SpdmGetDigestsRequestPayload);
+codec_proptest!(proptest_digests_response, SpdmDigestsResponsePayload);
+codec_proptest!(
+    proptest_get_certificate_request,
+    SpdmGetCertificateRequestPayload
+);
+codec_proptest!(
+    proptest_certificate_response,
+    SpdmCertificateResponsePayload
+);
+codec_proptest!(proptest_challenge_request, SpdmChallengeRequestPayload);
+codec_proptest!(
+    proptest_challenge_auth_response,
+    SpdmChallengeAuthResponsePayload
+);
+codec_proptest!(
+    proptest_get_measurements_request,
+    SpdmGetMeasurementsRequestPayload
+);
+codec_proptest!(
+    proptest_measurements_response,
+    SpdmMeasurementsResponsePayload
+);
+codec_proptest!(proptest_key_exchange_request, SpdmKeyExchangeRequestPayload);
+codec_proptest!(
+    proptest_key_exchange_response,
+    SpdmKeyExchangeResponsePayload
+);
+codec_proptest!(proptest_finish_request, SpdmFinishRequestPayload);
+codec_proptest!(proptest_finish_response, SpdmFinishResponsePayload);
+codec_proptest!(proptest_psk_exchange_request, SpdmPskExchangeRequestPayload);
+codec_proptest!(
+    proptest_psk_exchange_response,
+    SpdmPskExchangeResponsePayload
+);
+codec_proptest!(proptest_psk_finish_request, SpdmPskFinishRequestPayload);
+codec_proptest!(proptest_psk_finish_response, SpdmPskFinishResponsePayload);
+codec_proptest!(proptest_heartbeat_request, SpdmHeartbeatRequestPayload);
+codec_proptest!(proptest_heartbeat_response, SpdmHeartbeatResponsePayload);
+codec_proptest!(proptest_key_update_request, SpdmKeyUpdateRequestPayload);
+codec_proptest!(proptest_key_update_response, SpdmKeyUpdateResponsePayload);
+codec_proptest!(proptest_end_session_request, SpdmEndSessionRequestPayload);
+codec_proptest!(proptest_end_session_response, SpdmEndSessionResponsePayload);
+codec_proptest!(
+    proptest_respond_if_ready_request,
+    SpdmRespondIfReadyRequestPayload
+);
+codec_proptest!(
+    proptest_respond_if_ready_response,
+    SpdmRespondIfReadyRespondPayload
+);
+#[cfg(feature = "mut-auth")]
+codec_proptest!(
+    proptest_get_encapsulated_request,
+    SpdmGetEncapsulatedRequestPayload
+);
+#[cfg(feature = "mut-auth")]
+codec_proptest!(
+    proptest_encapsulated_request,
+    SpdmEncapsulatedRequestPayload
+);
+#[cfg(feature = "mut-auth")]
+codec_proptest!(
+    proptest_deliver_encapsulated_response,
+    SpdmDeliverEncapsulatedResponsePayload
+);
+#[cfg(feature = "mut-auth")]
+codec_proptest!(
+    proptest_encapsulated_response_ack,
+    SpdmEncapsulatedResponseAckPayload
+);
+codec_proptest!(
+    proptest_vendor_defined_request,
+    SpdmVendorDefinedRequestPayload
+);
+codec_proptest!(
+    proptest_vendor_defined_response,
+    SpdmVendorDefinedResponsePayload
+);
+codec_proptest!(proptest_chunk_get_request, SpdmChunkGetRequestPayload);
+codec_proptest!(proptest_chunk_response, SpdmChunkResponsePayload);
+codec_proptest!(
+    proptest_chunk_send_ack_response,
+    SpdmChunkSendAckResponsePayload,
+    greedy
+);
+codec_proptest!(proptest_get_csr_request, SpdmGetCsrRequestPayload);
+codec_proptest!(proptest_csr_response, SpdmCsrResponsePayload);
+codec_proptest!(
+    proptest_set_certificate_request,
+    SpdmSetCertificateRequestPayload
+);
+codec_proptest!(
+    proptest_set_certificate_response,
+    SpdmSetCertificateResponsePayload
+);
+codec_proptest!(
+    proptest_get_supported_event_types_request,
+    SpdmGetSupportedEventTypesRequestPayload
+);
+codec_proptest!(
+    proptest_supported_event_types_response,
+    SpdmSupportedEventTypesResponsePayload
+);
+codec_proptest!(
+    proptest_subscribe_event_types_request,
+    SpdmSubscribeEventTypesRequestPayload
+);
+codec_proptest!(
+    proptest_subscribe_event_types_ack_response,
+    SpdmSubscribeEventTypesAckResponsePayload
+);
+codec_proptest!(proptest_send_event_request, SpdmSendEventRequestPayload);
+codec_proptest!(proptest_event_ack_response, SpdmEventAckResponsePayload);
+codec_proptest!(
+    proptest_get_key_pair_info_request,
+    SpdmGetKeyPairInfoRequestPayload
+);
+codec_proptest!(
+    proptest_key_pair_info_response,
+    SpdmKeyPairInfoResponsePayload
+);
+codec_proptest!(
+    proptest_set_key_pair_info_request,
+    SpdmSetKeyPairInfoRequestPayload
+);
+codec_proptest!(
+    proptest_set_key_pair_info_ack_response,
+    SpdmSetKeyPairInfoAckResponsePayload
+);
+codec_proptest!(proptest_error_response, SpdmErrorResponsePayload, greedy);
+codec_proptest!(proptest_message_general, SpdmMessageGeneralPayload);
+codec_proptest!(proptest_message, SpdmMessage, greedy);
@@ -0,0 +1,197 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::opaque::{SpdmOpaqueStruct, MAX_SPDM_OPAQUE_SIZE};
+use crate::common::spdm_codec::SpdmCodec;
+use crate::config;
+use crate::error::SPDM_STATUS_BUFFER_FULL;
+use crate::protocol::SpdmVersion;
+use crate::{common, error::SpdmStatus};
+use codec::{Codec, Reader, Writer};
+
+pub const MAX_SPDM_CSR_REQUESTER_INFO_SIZE: usize = 256;
+pub const MAX_SPDM_CSR_SIZE: usize = config::MAX_SPDM_MSG_SIZE;
+
+// RequestAttributes, SPDM 1.3
+pub const SPDM_CSR_TRACKING_TAG_MASK: u8 = 0b0011_1000;
+pub const SPDM_CSR_TRACKING_TAG_SHIFT: u8 = 3;
+pub const SPDM_CSR_OVERWRITE: u8 = 0b1000_0000;
+pub const SPDM_CSR_TRACKING_TAG_MAX: u8 = 7;
+
+#[derive(Debug, Clone)]
+pub struct SpdmGetCsrRequestPayload {
+    // SPDM 1.3, 0 for 1.2
+    pub key_pair_id: u8,
+    pub csr_tracking_tag: u8,
+    pub overwrite: bool,
+    pub requester_info_length: u16,
+    // DER CertificationRequestInfo
+    pub requester_info: [u8; MAX_SPDM_CSR_REQUESTER_INFO_SIZE],
+    pub opaque: SpdmOpaqueStruct,
+}
+
+impl Default for SpdmGetCsrRequestPayload {
+    fn default() -> SpdmGetCsrRequestPayload {
+        SpdmGetCsrRequestPayload {
+            key_pair_id: 0,
+            csr_tracking_tag: 0,
+            overwrite: false,
+            requester_info_length: 0,
+            requester_info: [0u8; MAX_SPDM_CSR_REQUESTER_INFO_SIZE],
+            opaque: SpdmOpaqueStruct::default(),
+        }
+    }
+}
+
+impl SpdmCodec for SpdmGetCsrRequestPayload {
+    fn spdm_encode(
+        &self,
+        context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        if self.requester_info_length as usize > MAX_SPDM_CSR_REQUESTER_INFO_SIZE
+            || self.opaque.data_size as usize > MAX_SPDM_OPAQUE_SIZE
+        {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+
+        let (key_pair_id, request_attributes) = if context.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion13.get_u8()
+        {
+            let mut request_attributes =
+                (self.csr_tracking_tag & SPDM_CSR_TRACKING_TAG_MAX) << SPDM_CSR_TRACKING_TAG_SHIFT;
+            if self.overwrite {
+                request_attributes |= SPDM_CSR_OVERWRITE;
+            }
+            (self.key_pair_id, request_attributes)
+        } else {
+            (0, 0)
+        };
+
+        let mut cnt = 0usize;
+        cnt += key_pair_id
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += request_attributes
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .requester_info_length
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += self
+            .opaque
+            .data_size
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += bytes
+            .extend_from_slice(&self.requester_info[..self.requester_info_length as usize])
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        cnt += bytes
+            .extend_from_slice(&self.opaque.data[..self.opaque.data_size as usize])
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmGetCsrRequestPayload> {
+        let key_pair_id = u8::read(r)?; // param1
+        let request_attributes = u8::read(r)?; // param2
+        let requester_info_length = u16::read(r)?;
+        let opaque_data_length = u16::read(r)?;
+        if requester_info_length as usize > MAX_SPDM_CSR_REQUESTER_INFO_SIZE
+            || opaque_data_length as usize > MAX_SPDM_OPAQUE_SIZE
+        {
+            return None;
+        }
+
+        let mut request = SpdmGetCsrRequestPayload {
+            requester_info_length,
+            ..Default::default()
+        };
+        if context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion13.get_u8() {
+            request.key_pair_id = key_pair_id;
+            request.csr_tracking_tag =
+                (request_attributes & SPDM_CSR_TRACKING_TAG_MASK) >> SPDM_CSR_TRACKING_TAG_SHIFT;
+            request.overwrite = request_attributes & SPDM_CSR_OVERWRITE != 0;
+        }
+        request.requester_info[..requester_info_length as usize]
+            .copy_from_slice(r.take(requester_info_length as usize)?);
+        request.opaque.data_size = opaque_data_length;
+        request.opaque.data[..opaque_data_length as usize]
+            .copy_from_slice(r.take(opaque_data_length as usize)?);
+        Some(request)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpdmCsrResponsePayload {
+    pub csr_length: u16,
+    // DER CertificationRequest
+    pub csr: [u8; MAX_SPDM_CSR_SIZE],
+}
+
+impl Default for SpdmCsrResponsePayload {
+    fn default() -> SpdmCsrResponsePayload {
+        SpdmCsrResponsePayload {
+            csr_length: 0,
+            csr: [0u8; MAX_SPDM_CSR_SIZE],
+        }
+    }
+}
+
+impl SpdmCodec for SpdmCsrResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        if self.csr_length as usize > MAX_SPDM_CSR_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .csr_length
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += 0u16.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // reserved
+        cnt += bytes
+            .extend_from_slice(&self.csr[..self.csr_length as usize])
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmCsrResponsePayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        let csr_length = u16::read(r)?;
+        u16::read(r)?; // reserved
+        if csr_length as usize > MAX_SPDM_CSR_SIZE {
+            return None;
+        }
+
+        let mut response = SpdmCsrResponsePayload {
+            csr_length,
+            ..Default::default()
+        };
+        response.csr[..csr_length as usize].copy_from_slice(r.take(csr_length as usize)?);
+        Some(response)
+    }
+}
+
+#[cfg(all(test,))]
+#[path = "mod_test.common.inc.rs"]
+mod testlib;
+
+#[cfg(all(test,))]
+#[path = "csr_test.rs"]
+mod csr_test;
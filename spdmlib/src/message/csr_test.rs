@@ -0,0 +1,77 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::*;
+use crate::common::{SpdmCodec, SpdmConfigInfo, SpdmContext, SpdmProvisionInfo};
+use testlib::{create_spdm_context, DeviceIO, TransportEncap};
+
+#[test]
+fn test_get_csr_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    // 1. RequestAttributes are reserved in SPDM 1.2.
+    context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    let mut value = SpdmGetCsrRequestPayload {
+        key_pair_id: 1,
+        csr_tracking_tag: 3,
+        overwrite: true,
+        requester_info_length: 4,
+        ..Default::default()
+    };
+    value.requester_info[..4].copy_from_slice(&[0x30, 0x02, 0x05, 0x00]);
+    value.opaque.data_size = 2;
+    value.opaque.data[..2].copy_from_slice(&[0xAA, 0xBB]);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(12));
+    assert_eq!(
+        u8_slice[..12],
+        [0x00, 0x00, 0x04, 0x00, 0x02, 0x00, 0x30, 0x02, 0x05, 0x00, 0xAA, 0xBB]
+    );
+
+    let reader = &mut Reader::init(&u8_slice[..12]);
+    let ret = SpdmGetCsrRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.requester_info_length, 4);
+    assert_eq!(ret.opaque.data[..2], [0xAA, 0xBB]);
+
+    // 2. SPDM 1.3 carries KeyPairID and RequestAttributes.
+    context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(12));
+    assert_eq!(u8_slice[..2], [0x01, 0x98]);
+
+    let reader = &mut Reader::init(&u8_slice[..12]);
+    let ret = SpdmGetCsrRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(ret.key_pair_id, 1);
+    assert_eq!(ret.csr_tracking_tag, 3);
+    assert!(ret.overwrite);
+}
+
+#[test]
+fn test_csr_response_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let u8_slice = &mut [0u8; 16];
+    let mut writer = Writer::init(u8_slice);
+    let mut value = SpdmCsrResponsePayload {
+        csr_length: 4,
+        ..Default::default()
+    };
+    value.csr[..4].copy_from_slice(&[0x30, 0x02, 0x05, 0x00]);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(10));
+
+    let reader = &mut Reader::init(&u8_slice[..10]);
+    let ret = SpdmCsrResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.csr_length, 4);
+    assert_eq!(ret.csr[..4], [0x30, 0x02, 0x05, 0x00]);
+
+    // Truncated CSR is rejected.
+    let reader = &mut Reader::init(&u8_slice[..9]);
+    assert!(SpdmCsrResponsePayload::spdm_read(context, reader).is_none());
+}
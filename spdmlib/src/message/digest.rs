@@ -5,7 +5,10 @@
 use crate::common;
 use crate::common::spdm_codec::SpdmCodec;
 use crate::error::{SpdmStatus, SPDM_STATUS_BUFFER_FULL};
-use crate::protocol::{gen_array_clone, SpdmDigestStruct, SPDM_MAX_SLOT_NUMBER};
+use crate::protocol::{
+    gen_array_clone, SpdmCertificateModelType, SpdmDigestStruct, SpdmKeyUsageMask,
+    SPDM_MAX_SLOT_NUMBER,
+};
 use codec::{Codec, Reader, Writer};
 
 #[derive(Debug, Clone, Default)]
@@ -35,9 +38,15 @@ impl SpdmCodec for SpdmGetDigestsRequestPayload {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmDigestsResponsePayload {
     pub slot_mask: u8,
     pub digests: [SpdmDigestStruct; SPDM_MAX_SLOT_NUMBER],
+    // below are only present in a SPDM 1.3 multi-key connection,
+    // each in the same order as digests
+    pub key_pair_id: [u8; SPDM_MAX_SLOT_NUMBER],
+    pub cert_model: [SpdmCertificateModelType; SPDM_MAX_SLOT_NUMBER],
+    pub key_usage_mask: [SpdmKeyUsageMask; SPDM_MAX_SLOT_NUMBER],
 }
 
 impl SpdmCodec for SpdmDigestsResponsePayload {
@@ -63,6 +72,25 @@ impl SpdmCodec for SpdmDigestsResponsePayload {
         for digest in self.digests.iter().take(count as usize) {
             cnt += digest.spdm_encode(context, bytes)?;
         }
+
+        if context.is_multi_key_conn() {
+            for key_pair_id in self.key_pair_id.iter().take(count as usize) {
+                cnt += key_pair_id
+                    .encode(bytes)
+                    .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+            }
+            // CertificateInfo, only the certificate model is defined
+            for cert_model in self.cert_model.iter().take(count as usize) {
+                cnt += cert_model
+                    .encode(bytes)
+                    .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+            }
+            for key_usage_mask in self.key_usage_mask.iter().take(count as usize) {
+                cnt += key_usage_mask
+                    .encode(bytes)
+                    .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+            }
+        }
         Ok(cnt)
     }
 
@@ -84,7 +112,33 @@ impl SpdmCodec for SpdmDigestsResponsePayload {
         for digest in digests.iter_mut().take(slot_count as usize) {
             *digest = SpdmDigestStruct::spdm_read(context, r)?;
         }
-        Some(SpdmDigestsResponsePayload { slot_mask, digests })
+
+        let mut key_pair_id = [0u8; SPDM_MAX_SLOT_NUMBER];
+        let mut cert_model = [SpdmCertificateModelType::default(); SPDM_MAX_SLOT_NUMBER];
+        let mut key_usage_mask = [SpdmKeyUsageMask::default(); SPDM_MAX_SLOT_NUMBER];
+        if context.is_multi_key_conn() {
+            for id in key_pair_id.iter_mut().take(slot_count as usize) {
+                *id = u8::read(r)?;
+            }
+            for model in cert_model.iter_mut().take(slot_count as usize) {
+                // CertificateInfo, bit [2:0] is the certificate model
+                *model = SpdmCertificateModelType::read_bytes(&[u8::read(r)? & 0x7])?;
+                if let SpdmCertificateModelType::Unknown(_) = model {
+                    return None;
+                }
+            }
+            for mask in key_usage_mask.iter_mut().take(slot_count as usize) {
+                *mask = SpdmKeyUsageMask::read(r)?;
+            }
+        }
+
+        Some(SpdmDigestsResponsePayload {
+            slot_mask,
+            digests,
+            key_pair_id,
+            cert_model,
+            key_usage_mask,
+        })
     }
 }
 
@@ -113,6 +167,7 @@ mod tests {
                 },
                 SPDM_MAX_SLOT_NUMBER,
             ),
+            ..Default::default()
         };
         for i in 0..SPDM_MAX_SLOT_NUMBER {
             for j in 0..SPDM_MAX_HASH_SIZE {
@@ -142,6 +197,57 @@ mod tests {
         assert_eq!(0, reader.left());
     }
     #[test]
+    fn test_case2_spdm_digests_response_payload() {
+        let u8_slice = &mut [0u8; 2 + 2 * SHA384_DIGEST_SIZE + 2 * 4];
+        let mut writer = Writer::init(u8_slice);
+
+        let mut value = SpdmDigestsResponsePayload {
+            slot_mask: 0b00000101,
+            digests: gen_array_clone(
+                SpdmDigestStruct {
+                    data_size: SHA384_DIGEST_SIZE as u16,
+                    data: Box::new([0x5au8; SPDM_MAX_HASH_SIZE]),
+                },
+                SPDM_MAX_SLOT_NUMBER,
+            ),
+            ..Default::default()
+        };
+        value.key_pair_id[0] = 1;
+        value.key_pair_id[1] = 2;
+        value.cert_model[0] = SpdmCertificateModelType::SpdmCertModelTypeDeviceCert;
+        value.cert_model[1] = SpdmCertificateModelType::SpdmCertModelTypeAliasCert;
+        value.key_usage_mask[0] = SpdmKeyUsageMask::KEY_EX_USE;
+        value.key_usage_mask[1] = SpdmKeyUsageMask::MEASUREMENT_USE;
+
+        create_spdm_context!(context);
+
+        context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+        context.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        context.negotiate_info.rsp_capabilities_sel =
+            SpdmResponseCapabilityFlags::MULTI_KEY_CAP_ONLY;
+
+        assert!(value.spdm_encode(&mut context, &mut writer).is_ok());
+        let mut reader = Reader::init(u8_slice);
+        let spdm_digests_response_payload =
+            SpdmDigestsResponsePayload::spdm_read(&mut context, &mut reader).unwrap();
+        assert_eq!(0, reader.left());
+        assert_eq!(spdm_digests_response_payload.key_pair_id[..2], [1, 2]);
+        assert_eq!(
+            spdm_digests_response_payload.cert_model[1],
+            SpdmCertificateModelType::SpdmCertModelTypeAliasCert
+        );
+        assert_eq!(
+            spdm_digests_response_payload.key_usage_mask[1],
+            SpdmKeyUsageMask::MEASUREMENT_USE
+        );
+
+        // without multi-key the per slot info is not present
+        context.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+        let mut reader = Reader::init(u8_slice);
+        assert!(SpdmDigestsResponsePayload::spdm_read(&mut context, &mut reader).is_some());
+        assert_eq!(2 * 4, reader.left());
+    }
+    #[test]
     #[should_panic]
     fn test_case1_spdm_digests_response_payload() {
         let u8_slice = &mut [0u8; 2];
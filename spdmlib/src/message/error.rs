@@ -61,6 +61,31 @@ impl SpdmCodec for SpdmErrorResponseNoneExtData {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpdmErrorResponseLargeResponseExtData {
+    pub handle: u8,
+}
+
+impl SpdmCodec for SpdmErrorResponseLargeResponseExtData {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        self.handle
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmErrorResponseLargeResponseExtData> {
+        let handle = u8::read(r)?;
+        Some(SpdmErrorResponseLargeResponseExtData { handle })
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SpdmErrorResponseNotReadyExtData {
     pub rdt_exponent: u8,
@@ -184,6 +209,7 @@ pub enum SpdmErrorResponseExtData {
     SpdmErrorExtDataNone(SpdmErrorResponseNoneExtData),
     SpdmErrorExtDataNotReady(SpdmErrorResponseNotReadyExtData),
     SpdmErrorExtDataVendorDefined(SpdmErrorResponseVendorExtData),
+    SpdmErrorExtDataLargeResponse(SpdmErrorResponseLargeResponseExtData),
 }
 impl Default for SpdmErrorResponseExtData {
     fn default() -> SpdmErrorResponseExtData {
@@ -221,6 +247,9 @@ impl SpdmCodec for SpdmErrorResponsePayload {
             SpdmErrorResponseExtData::SpdmErrorExtDataVendorDefined(extended_data) => {
                 cnt += extended_data.spdm_encode(context, bytes)?;
             }
+            SpdmErrorResponseExtData::SpdmErrorExtDataLargeResponse(extended_data) => {
+                cnt += extended_data.spdm_encode(context, bytes)?;
+            }
             SpdmErrorResponseExtData::SpdmErrorExtDataNone(extended_data) => {
                 cnt += extended_data.spdm_encode(context, bytes)?;
             }
@@ -247,6 +276,11 @@ impl SpdmCodec for SpdmErrorResponsePayload {
                     SpdmErrorResponseVendorExtData::spdm_read(context, r)?,
                 ))
             }
+            SpdmErrorCode::SpdmErrorLargeResponse => {
+                Some(SpdmErrorResponseExtData::SpdmErrorExtDataLargeResponse(
+                    SpdmErrorResponseLargeResponseExtData::spdm_read(context, r)?,
+                ))
+            }
             _ => Some(SpdmErrorResponseExtData::SpdmErrorExtDataNone(
                 SpdmErrorResponseNoneExtData::spdm_read(context, r)?,
             )),
@@ -262,6 +296,32 @@ impl SpdmCodec for SpdmErrorResponsePayload {
     }
 }
 
+/// Vendor defined error reported by the peer.
+/// error_data carries the registry or standards body ID (Param2),
+/// extended_data carries the vendor ID and opaque vendor error data.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpdmVendorDefinedError {
+    pub error_data: u8,
+    pub extended_data: SpdmErrorResponseVendorExtData,
+}
+
+impl SpdmVendorDefinedError {
+    pub fn from_error_response(payload: &SpdmErrorResponsePayload) -> Option<Self> {
+        if payload.error_code != SpdmErrorCode::SpdmErrorVendorDefined {
+            return None;
+        }
+        match &payload.extended_data {
+            SpdmErrorResponseExtData::SpdmErrorExtDataVendorDefined(extended_data) => {
+                Some(SpdmVendorDefinedError {
+                    error_data: payload.error_data,
+                    extended_data: extended_data.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(all(test,))]
 #[path = "mod_test.common.inc.rs"]
 mod testlib;
@@ -406,6 +466,33 @@ mod tests {
             SpdmErrorResponseExtData::SpdmErrorExtDataNone(SpdmErrorResponseNoneExtData {});
         new_spdm_response(value, &mut context);
     }
+    #[test]
+    fn test_case0_spdm_vendor_defined_error() {
+        let mut data = [0u8; SPDM_ERROR_VENDOR_EXT_DATA_SIZE];
+        data[..4].copy_from_slice(&[0x02, 0x86, 0x80, 0x5a]);
+        let value = SpdmErrorResponsePayload {
+            error_code: SpdmErrorCode::SpdmErrorVendorDefined,
+            error_data: 0x3,
+            extended_data: SpdmErrorResponseExtData::SpdmErrorExtDataVendorDefined(
+                SpdmErrorResponseVendorExtData { data_size: 4, data },
+            ),
+        };
+
+        let vendor_error = SpdmVendorDefinedError::from_error_response(&value).unwrap();
+        assert_eq!(vendor_error.error_data, 0x3);
+        assert_eq!(vendor_error.extended_data.data_size, 4);
+        assert_eq!(
+            vendor_error.extended_data.data[..4],
+            [0x02, 0x86, 0x80, 0x5a]
+        );
+
+        let value = SpdmErrorResponsePayload {
+            error_code: SpdmErrorCode::SpdmErrorUnspecified,
+            error_data: 0,
+            extended_data: SpdmErrorResponseExtData::default(),
+        };
+        assert!(SpdmVendorDefinedError::from_error_response(&value).is_none());
+    }
 
     fn new_spdm_response(
         value: SpdmErrorResponsePayload,
@@ -0,0 +1,466 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common;
+use crate::common::spdm_codec::SpdmCodec;
+use crate::error::{SpdmStatus, SPDM_STATUS_BUFFER_FULL};
+use crate::message::{
+    RegistryOrStandardsBodyID, VendorIDStruct, MAX_SPDM_VENDOR_DEFINED_VENDOR_ID_LEN,
+};
+use codec::{Codec, Reader, Writer};
+
+pub const MAX_SPDM_EVENT_GROUP_COUNT: usize = 4;
+pub const MAX_SPDM_EVENT_TYPE_COUNT: usize = 16;
+pub const MAX_SPDM_EVENT_COUNT: usize = 4;
+pub const MAX_SPDM_EVENT_DETAIL_SIZE: usize = 64;
+
+// DSP0274 1.3: DMTF event group
+pub const SPDM_DMTF_EVENT_GROUP_VERSION: u8 = 1;
+pub const SPDM_DMTF_EVENT_TYPE_EVENT_LOST: u16 = 1;
+pub const SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED: u16 = 2;
+pub const SPDM_DMTF_EVENT_TYPE_MEASUREMENT_PRE_UPDATE: u16 = 3;
+pub const SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED: u16 = 4;
+
+/// Standards body or vendor defined header identifying an event group.
+#[derive(Debug, Clone)]
+pub struct SpdmEventGroupId {
+    pub standard_id: RegistryOrStandardsBodyID,
+    pub vendor_id: VendorIDStruct,
+}
+
+impl Default for SpdmEventGroupId {
+    fn default() -> SpdmEventGroupId {
+        SpdmEventGroupId {
+            standard_id: RegistryOrStandardsBodyID::DMTF,
+            vendor_id: VendorIDStruct {
+                len: 0,
+                vendor_id: [0u8; MAX_SPDM_VENDOR_DEFINED_VENDOR_ID_LEN],
+            },
+        }
+    }
+}
+
+impl PartialEq for SpdmEventGroupId {
+    fn eq(&self, other: &SpdmEventGroupId) -> bool {
+        self.standard_id == other.standard_id
+            && self.vendor_id.len == other.vendor_id.len
+            && self.vendor_id.vendor_id[..self.vendor_id.len as usize]
+                == other.vendor_id.vendor_id[..other.vendor_id.len as usize]
+    }
+}
+
+impl Codec for SpdmEventGroupId {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.standard_id.encode(bytes)?;
+        cnt += self.vendor_id.encode(bytes)?;
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmEventGroupId> {
+        let standard_id = RegistryOrStandardsBodyID::read(r)?;
+        let vendor_id = VendorIDStruct::read(r)?;
+        Some(SpdmEventGroupId {
+            standard_id,
+            vendor_id,
+        })
+    }
+}
+
+/// An event group with the event types supported or subscribed.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmEventGroup {
+    pub group_id: SpdmEventGroupId,
+    pub group_version: u8,
+    pub event_type_count: u8,
+    pub event_types: [u16; MAX_SPDM_EVENT_TYPE_COUNT],
+}
+
+impl SpdmEventGroup {
+    /// The DMTF event group with all the DMTF event types.
+    pub fn dmtf() -> SpdmEventGroup {
+        let mut event_types = [0u16; MAX_SPDM_EVENT_TYPE_COUNT];
+        event_types[..4].copy_from_slice(&[
+            SPDM_DMTF_EVENT_TYPE_EVENT_LOST,
+            SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED,
+            SPDM_DMTF_EVENT_TYPE_MEASUREMENT_PRE_UPDATE,
+            SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED,
+        ]);
+        SpdmEventGroup {
+            group_id: SpdmEventGroupId::default(),
+            group_version: SPDM_DMTF_EVENT_GROUP_VERSION,
+            event_type_count: 4,
+            event_types,
+        }
+    }
+
+    pub fn event_types(&self) -> &[u16] {
+        &self.event_types[..self.event_type_count as usize]
+    }
+
+    pub fn contains(&self, event_type_id: u16) -> bool {
+        self.event_types().contains(&event_type_id)
+    }
+}
+
+impl Codec for SpdmEventGroup {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.event_type_count.encode(bytes)?;
+        cnt += self.group_version.encode(bytes)?;
+        cnt += 0u16.encode(bytes)?; // reserved
+        cnt += self.group_id.encode(bytes)?;
+        for event_type_id in self.event_types() {
+            cnt += event_type_id.encode(bytes)?;
+            cnt += 0u16.encode(bytes)?; // reserved
+        }
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmEventGroup> {
+        let event_type_count = u8::read(r)?;
+        if event_type_count as usize > MAX_SPDM_EVENT_TYPE_COUNT {
+            return None;
+        }
+        let group_version = u8::read(r)?;
+        u16::read(r)?; // reserved
+        let group_id = SpdmEventGroupId::read(r)?;
+        let mut event_types = [0u16; MAX_SPDM_EVENT_TYPE_COUNT];
+        for event_type_id in event_types.iter_mut().take(event_type_count as usize) {
+            *event_type_id = u16::read(r)?;
+            u16::read(r)?; // reserved
+        }
+        Some(SpdmEventGroup {
+            group_id,
+            group_version,
+            event_type_count,
+            event_types,
+        })
+    }
+}
+
+fn encode_event_group_list(
+    event_groups: &[SpdmEventGroup],
+    bytes: &mut Writer,
+) -> Result<usize, SpdmStatus> {
+    // the list length is patched once the groups are encoded
+    let list_len_offset = bytes.used();
+    let mut cnt = 0usize;
+    cnt += 0u32.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+    let mut list_len = 0usize;
+    for event_group in event_groups {
+        list_len += event_group
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+    }
+    bytes.mut_used_slice()[list_len_offset..list_len_offset + 4]
+        .copy_from_slice(&(list_len as u32).to_le_bytes());
+    Ok(cnt + list_len)
+}
+
+/// Read a list length and exactly event_group_count groups filling it.
+fn read_event_group_list(
+    r: &mut Reader,
+    event_group_count: u8,
+) -> Option<[SpdmEventGroup; MAX_SPDM_EVENT_GROUP_COUNT]> {
+    if event_group_count as usize > MAX_SPDM_EVENT_GROUP_COUNT {
+        return None;
+    }
+    let list_len = u32::read(r)? as usize;
+    let mut list_reader = Reader::init(r.take(list_len)?);
+    let mut event_groups: [SpdmEventGroup; MAX_SPDM_EVENT_GROUP_COUNT] = Default::default();
+    for event_group in event_groups.iter_mut().take(event_group_count as usize) {
+        *event_group = SpdmEventGroup::read(&mut list_reader)?;
+    }
+    if list_reader.left() != 0 {
+        return None;
+    }
+    Some(event_groups)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmGetSupportedEventTypesRequestPayload {}
+
+impl SpdmCodec for SpdmGetSupportedEventTypesRequestPayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmGetSupportedEventTypesRequestPayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        Some(SpdmGetSupportedEventTypesRequestPayload {})
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSupportedEventTypesResponsePayload {
+    pub event_group_count: u8,
+    pub event_groups: [SpdmEventGroup; MAX_SPDM_EVENT_GROUP_COUNT],
+}
+
+impl SpdmSupportedEventTypesResponsePayload {
+    pub fn event_groups(&self) -> &[SpdmEventGroup] {
+        &self.event_groups[..self.event_group_count as usize]
+    }
+}
+
+impl SpdmCodec for SpdmSupportedEventTypesResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += self
+            .event_group_count
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += encode_event_group_list(self.event_groups(), bytes)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSupportedEventTypesResponsePayload> {
+        let event_group_count = u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        if event_group_count == 0 {
+            return None;
+        }
+        let event_groups = read_event_group_list(r, event_group_count)?;
+        Some(SpdmSupportedEventTypesResponsePayload {
+            event_group_count,
+            event_groups,
+        })
+    }
+}
+
+/// An event_group_count of 0 unsubscribes from all the event types.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSubscribeEventTypesRequestPayload {
+    pub event_group_count: u8,
+    pub event_groups: [SpdmEventGroup; MAX_SPDM_EVENT_GROUP_COUNT],
+}
+
+impl SpdmSubscribeEventTypesRequestPayload {
+    pub fn event_groups(&self) -> &[SpdmEventGroup] {
+        &self.event_groups[..self.event_group_count as usize]
+    }
+}
+
+impl SpdmCodec for SpdmSubscribeEventTypesRequestPayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += self
+            .event_group_count
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += encode_event_group_list(self.event_groups(), bytes)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSubscribeEventTypesRequestPayload> {
+        let event_group_count = u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        let event_groups = read_event_group_list(r, event_group_count)?;
+        Some(SpdmSubscribeEventTypesRequestPayload {
+            event_group_count,
+            event_groups,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSubscribeEventTypesAckResponsePayload {}
+
+impl SpdmCodec for SpdmSubscribeEventTypesAckResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSubscribeEventTypesAckResponsePayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        Some(SpdmSubscribeEventTypesAckResponsePayload {})
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpdmEventData {
+    pub event_instance_id: u32,
+    pub group_id: SpdmEventGroupId,
+    pub event_type_id: u16,
+    pub event_detail_len: u16,
+    pub event_detail: [u8; MAX_SPDM_EVENT_DETAIL_SIZE],
+}
+
+impl Default for SpdmEventData {
+    fn default() -> SpdmEventData {
+        SpdmEventData {
+            event_instance_id: 0,
+            group_id: SpdmEventGroupId::default(),
+            event_type_id: 0,
+            event_detail_len: 0,
+            event_detail: [0u8; MAX_SPDM_EVENT_DETAIL_SIZE],
+        }
+    }
+}
+
+impl SpdmEventData {
+    pub fn event_detail(&self) -> &[u8] {
+        &self.event_detail[..self.event_detail_len as usize]
+    }
+}
+
+impl Codec for SpdmEventData {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.event_instance_id.encode(bytes)?;
+        cnt += 0u32.encode(bytes)?; // reserved
+        cnt += self.group_id.encode(bytes)?;
+        cnt += self.event_type_id.encode(bytes)?;
+        cnt += self.event_detail_len.encode(bytes)?;
+        for d in self.event_detail() {
+            cnt += d.encode(bytes)?;
+        }
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmEventData> {
+        let event_instance_id = u32::read(r)?;
+        u32::read(r)?; // reserved
+        let group_id = SpdmEventGroupId::read(r)?;
+        let event_type_id = u16::read(r)?;
+        let event_detail_len = u16::read(r)?;
+        if event_detail_len as usize > MAX_SPDM_EVENT_DETAIL_SIZE {
+            return None;
+        }
+        let mut event_detail = [0u8; MAX_SPDM_EVENT_DETAIL_SIZE];
+        event_detail[..event_detail_len as usize]
+            .copy_from_slice(r.take(event_detail_len as usize)?);
+        Some(SpdmEventData {
+            event_instance_id,
+            group_id,
+            event_type_id,
+            event_detail_len,
+            event_detail,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSendEventRequestPayload {
+    pub event_count: u32,
+    pub events: [SpdmEventData; MAX_SPDM_EVENT_COUNT],
+}
+
+impl SpdmSendEventRequestPayload {
+    pub fn events(&self) -> &[SpdmEventData] {
+        &self.events[..self.event_count as usize]
+    }
+}
+
+impl SpdmCodec for SpdmSendEventRequestPayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .event_count
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        for event in self.events() {
+            cnt += event.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        }
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSendEventRequestPayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        let event_count = u32::read(r)?;
+        if event_count == 0 || event_count as usize > MAX_SPDM_EVENT_COUNT {
+            return None;
+        }
+        let mut events: [SpdmEventData; MAX_SPDM_EVENT_COUNT] = Default::default();
+        for event in events.iter_mut().take(event_count as usize) {
+            *event = SpdmEventData::read(r)?;
+        }
+        Some(SpdmSendEventRequestPayload {
+            event_count,
+            events,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmEventAckResponsePayload {}
+
+impl SpdmCodec for SpdmEventAckResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmEventAckResponsePayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        Some(SpdmEventAckResponsePayload {})
+    }
+}
+
+#[cfg(all(test,))]
+#[path = "mod_test.common.inc.rs"]
+mod testlib;
+
+#[cfg(all(test,))]
+#[path = "event_test.rs"]
+mod event_test;
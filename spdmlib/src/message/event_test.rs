@@ -0,0 +1,160 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::*;
+use crate::common::{SpdmCodec, SpdmConfigInfo, SpdmContext, SpdmProvisionInfo};
+use testlib::{create_spdm_context, DeviceIO, TransportEncap};
+
+#[test]
+fn test_supported_event_types_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let mut value = SpdmSupportedEventTypesResponsePayload {
+        event_group_count: 1,
+        ..Default::default()
+    };
+    value.event_groups[0] = SpdmEventGroup::dmtf();
+
+    let u8_slice = &mut [0u8; 64];
+    let mut writer = Writer::init(u8_slice);
+    // param1, param2, list length, DMTF group header and 4 event types
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(2 + 4 + 7 + 16));
+    assert_eq!(u8_slice[..6], [0x01, 0x00, 23, 0x00, 0x00, 0x00]);
+    assert_eq!(
+        u8_slice[6..13],
+        [
+            0x04,
+            SPDM_DMTF_EVENT_GROUP_VERSION,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00
+        ]
+    );
+
+    let reader = &mut Reader::init(&u8_slice[..29]);
+    let ret = SpdmSupportedEventTypesResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.event_groups().len(), 1);
+    assert!(ret.event_groups()[0].group_id == SpdmEventGroupId::default());
+    assert_eq!(
+        ret.event_groups()[0].event_types(),
+        SpdmEventGroup::dmtf().event_types()
+    );
+
+    // The list length must match the groups.
+    u8_slice[2] = 24;
+    let reader = &mut Reader::init(&u8_slice[..30]);
+    assert!(SpdmSupportedEventTypesResponsePayload::spdm_read(context, reader).is_none());
+
+    // At least one group is supported.
+    let reader = &mut Reader::init(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert!(SpdmSupportedEventTypesResponsePayload::spdm_read(context, reader).is_none());
+}
+
+#[test]
+fn test_subscribe_event_types_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    // Unsubscribe from all.
+    let value = SpdmSubscribeEventTypesRequestPayload::default();
+    let u8_slice = &mut [0u8; 64];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(6));
+    assert_eq!(u8_slice[..6], [0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    let reader = &mut Reader::init(&u8_slice[..6]);
+    let ret = SpdmSubscribeEventTypesRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(ret.event_groups().len(), 0);
+
+    let mut value = SpdmSubscribeEventTypesRequestPayload {
+        event_group_count: 1,
+        ..Default::default()
+    };
+    value.event_groups[0] = SpdmEventGroup::dmtf();
+    value.event_groups[0].event_types[0] = SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED;
+    value.event_groups[0].event_type_count = 1;
+    let u8_slice = &mut [0u8; 64];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(2 + 4 + 7 + 4));
+    let reader = &mut Reader::init(&u8_slice[..17]);
+    let ret = SpdmSubscribeEventTypesRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(
+        ret.event_groups()[0].event_types(),
+        [SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED]
+    );
+
+    // More groups than supported.
+    let reader = &mut Reader::init(&[0x05, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert!(SpdmSubscribeEventTypesRequestPayload::spdm_read(context, reader).is_none());
+}
+
+#[test]
+fn test_send_event_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let mut value = SpdmSendEventRequestPayload {
+        event_count: 1,
+        ..Default::default()
+    };
+    value.events[0].event_instance_id = 0x10;
+    value.events[0].event_type_id = SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED;
+    value.events[0].event_detail_len = 1;
+    value.events[0].event_detail[0] = 2;
+
+    let u8_slice = &mut [0u8; 64];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(
+        value.spdm_encode(context, &mut writer),
+        Ok(2 + 4 + 8 + 3 + 4 + 1)
+    );
+    assert_eq!(
+        u8_slice[6..22],
+        [
+            0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x01,
+            0x00, 0x02
+        ]
+    );
+
+    let reader = &mut Reader::init(&u8_slice[..22]);
+    let ret = SpdmSendEventRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.events().len(), 1);
+    assert_eq!(ret.events()[0].event_instance_id, 0x10);
+    assert_eq!(ret.events()[0].event_detail(), [2]);
+
+    // Truncated event detail.
+    let reader = &mut Reader::init(&u8_slice[..21]);
+    assert!(SpdmSendEventRequestPayload::spdm_read(context, reader).is_none());
+
+    // No event.
+    let reader = &mut Reader::init(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert!(SpdmSendEventRequestPayload::spdm_read(context, reader).is_none());
+}
+
+#[test]
+fn test_event_ack_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let u8_slice = &mut [0u8; 4];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(
+        SpdmSubscribeEventTypesAckResponsePayload {}.spdm_encode(context, &mut writer),
+        Ok(2)
+    );
+    assert_eq!(
+        SpdmEventAckResponsePayload {}.spdm_encode(context, &mut writer),
+        Ok(2)
+    );
+    assert_eq!(u8_slice[..4], [0x00, 0x00, 0x00, 0x00]);
+
+    let reader = &mut Reader::init(&u8_slice[..2]);
+    assert!(SpdmEventAckResponsePayload::spdm_read(context, reader).is_some());
+    let reader = &mut Reader::init(&u8_slice[..1]);
+    assert!(SpdmEventAckResponsePayload::spdm_read(context, reader).is_none());
+}
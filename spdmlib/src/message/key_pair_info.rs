@@ -0,0 +1,377 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common;
+use crate::common::spdm_codec::SpdmCodec;
+use crate::error::{SpdmStatus, SPDM_STATUS_BUFFER_FULL};
+use crate::protocol::SpdmKeyUsageMask;
+use codec::enum_builder;
+use codec::{Codec, Reader, Writer};
+
+pub const SPDM_MAX_KEY_PAIR_NUMBER: usize = 8;
+// DER SubjectPublicKeyInfo, large enough for RSA 4096
+pub const MAX_SPDM_PUBLIC_KEY_INFO_SIZE: usize = 600;
+
+// Param1 of SET_KEY_PAIR_INFO
+pub const SPDM_SET_KEY_PAIR_INFO_OPERATION_MASK: u8 = 0b0000_1111;
+
+bitflags! {
+    #[derive(Default)]
+    pub struct SpdmKeyPairCapabilities: u16 {
+        const GEN_KEY_CAP = 0b0000_0001;
+        const ERASABLE_CAP = 0b0000_0010;
+        const CERT_ASSOC_CAP = 0b0000_0100;
+        const KEY_USAGE_CAP = 0b0000_1000;
+        const ASYM_ALGO_CAP = 0b0001_0000;
+        const SHAREABLE_CAP = 0b0010_0000;
+        const VALID_MASK = Self::GEN_KEY_CAP.bits
+            | Self::ERASABLE_CAP.bits
+            | Self::CERT_ASSOC_CAP.bits
+            | Self::KEY_USAGE_CAP.bits
+            | Self::ASYM_ALGO_CAP.bits
+            | Self::SHAREABLE_CAP.bits;
+    }
+}
+
+impl Codec for SpdmKeyPairCapabilities {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        self.bits().encode(bytes)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmKeyPairCapabilities> {
+        let bits = u16::read(r)?;
+
+        SpdmKeyPairCapabilities::from_bits(bits & SpdmKeyPairCapabilities::VALID_MASK.bits)
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct SpdmKeyPairAsymAlgo: u32 {
+        const RSA2048 = 0b0000_0001;
+        const RSA3072 = 0b0000_0010;
+        const RSA4096 = 0b0000_0100;
+        const ECC256 = 0b0000_1000;
+        const ECC384 = 0b0001_0000;
+        const ECC521 = 0b0010_0000;
+        const SM2 = 0b0100_0000;
+        const ED25519 = 0b1000_0000;
+        const ED448 = 0b0000_0001_0000_0000;
+        const VALID_MASK = Self::RSA2048.bits
+            | Self::RSA3072.bits
+            | Self::RSA4096.bits
+            | Self::ECC256.bits
+            | Self::ECC384.bits
+            | Self::ECC521.bits
+            | Self::SM2.bits
+            | Self::ED25519.bits
+            | Self::ED448.bits;
+    }
+}
+
+impl Codec for SpdmKeyPairAsymAlgo {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        self.bits().encode(bytes)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmKeyPairAsymAlgo> {
+        let bits = u32::read(r)?;
+
+        SpdmKeyPairAsymAlgo::from_bits(bits & SpdmKeyPairAsymAlgo::VALID_MASK.bits)
+    }
+}
+
+/// Description of one key pair of the responder, as reported in KEY_PAIR_INFO.
+#[derive(Debug, Clone)]
+pub struct SpdmKeyPairInfo {
+    pub capabilities: SpdmKeyPairCapabilities,
+    pub key_usage_capabilities: SpdmKeyUsageMask,
+    pub current_key_usage: SpdmKeyUsageMask,
+    pub asym_algo_capabilities: SpdmKeyPairAsymAlgo,
+    pub current_asym_algo: SpdmKeyPairAsymAlgo,
+    // bit N set if the cert chain in slot N belongs to this key pair
+    pub assoc_cert_slot_mask: u8,
+    pub public_key_info_len: u16,
+    pub public_key_info: [u8; MAX_SPDM_PUBLIC_KEY_INFO_SIZE],
+}
+
+impl Default for SpdmKeyPairInfo {
+    fn default() -> SpdmKeyPairInfo {
+        SpdmKeyPairInfo {
+            capabilities: SpdmKeyPairCapabilities::empty(),
+            key_usage_capabilities: SpdmKeyUsageMask::empty(),
+            current_key_usage: SpdmKeyUsageMask::empty(),
+            asym_algo_capabilities: SpdmKeyPairAsymAlgo::empty(),
+            current_asym_algo: SpdmKeyPairAsymAlgo::empty(),
+            assoc_cert_slot_mask: 0,
+            public_key_info_len: 0,
+            public_key_info: [0u8; MAX_SPDM_PUBLIC_KEY_INFO_SIZE],
+        }
+    }
+}
+
+impl SpdmKeyPairInfo {
+    pub fn public_key_info(&self) -> &[u8] {
+        &self.public_key_info[..self.public_key_info_len as usize]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmGetKeyPairInfoRequestPayload {
+    pub key_pair_id: u8,
+}
+
+impl SpdmCodec for SpdmGetKeyPairInfoRequestPayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .key_pair_id
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmGetKeyPairInfoRequestPayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        let key_pair_id = u8::read(r)?;
+        if key_pair_id == 0 {
+            return None;
+        }
+        Some(SpdmGetKeyPairInfoRequestPayload { key_pair_id })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmKeyPairInfoResponsePayload {
+    pub total_key_pairs: u8,
+    pub key_pair_id: u8,
+    pub key_pair_info: SpdmKeyPairInfo,
+}
+
+impl SpdmCodec for SpdmKeyPairInfoResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let info = &self.key_pair_info;
+        if info.public_key_info_len as usize > MAX_SPDM_PUBLIC_KEY_INFO_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += self
+            .total_key_pairs
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += self
+            .key_pair_id
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += info
+            .capabilities
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += info
+            .key_usage_capabilities
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += info
+            .current_key_usage
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += info
+            .asym_algo_capabilities
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += info
+            .current_asym_algo
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += info
+            .public_key_info_len
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += info
+            .assoc_cert_slot_mask
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += bytes
+            .extend_from_slice(info.public_key_info())
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmKeyPairInfoResponsePayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        let total_key_pairs = u8::read(r)?;
+        let key_pair_id = u8::read(r)?;
+        if key_pair_id == 0 || key_pair_id > total_key_pairs {
+            return None;
+        }
+        let mut key_pair_info = SpdmKeyPairInfo {
+            capabilities: SpdmKeyPairCapabilities::read(r)?,
+            key_usage_capabilities: SpdmKeyUsageMask::read(r)?,
+            current_key_usage: SpdmKeyUsageMask::read(r)?,
+            asym_algo_capabilities: SpdmKeyPairAsymAlgo::read(r)?,
+            current_asym_algo: SpdmKeyPairAsymAlgo::read(r)?,
+            ..Default::default()
+        };
+        let public_key_info_len = u16::read(r)?;
+        key_pair_info.assoc_cert_slot_mask = u8::read(r)?;
+        if public_key_info_len as usize > MAX_SPDM_PUBLIC_KEY_INFO_SIZE {
+            return None;
+        }
+        key_pair_info.public_key_info_len = public_key_info_len;
+        key_pair_info.public_key_info[..public_key_info_len as usize]
+            .copy_from_slice(r.take(public_key_info_len as usize)?);
+        Some(SpdmKeyPairInfoResponsePayload {
+            total_key_pairs,
+            key_pair_id,
+            key_pair_info,
+        })
+    }
+}
+
+enum_builder! {
+    @U8
+    EnumName: SpdmKeyPairInfoOperation;
+    EnumVal{
+        SpdmKeyPairInfoChange => 0x0,
+        SpdmKeyPairInfoErase => 0x1,
+        SpdmKeyPairInfoGenerate => 0x2
+    }
+}
+impl Default for SpdmKeyPairInfoOperation {
+    fn default() -> SpdmKeyPairInfoOperation {
+        SpdmKeyPairInfoOperation::SpdmKeyPairInfoChange
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSetKeyPairInfoRequestPayload {
+    pub operation: SpdmKeyPairInfoOperation,
+    pub key_pair_id: u8,
+    // Not sent for SpdmKeyPairInfoErase
+    pub desired_key_usage: SpdmKeyUsageMask,
+    pub desired_asym_algo: SpdmKeyPairAsymAlgo,
+    pub desired_assoc_cert_slot_mask: u8,
+}
+
+impl SpdmCodec for SpdmSetKeyPairInfoRequestPayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += (self.operation.get_u8() & SPDM_SET_KEY_PAIR_INFO_OPERATION_MASK)
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // reserved
+        cnt += self
+            .key_pair_id
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        if self.operation == SpdmKeyPairInfoOperation::SpdmKeyPairInfoErase {
+            return Ok(cnt);
+        }
+        cnt += self
+            .desired_key_usage
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += self
+            .desired_asym_algo
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        cnt += self
+            .desired_assoc_cert_slot_mask
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSetKeyPairInfoRequestPayload> {
+        let param1 = u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        u8::read(r)?; // reserved
+        let key_pair_id = u8::read(r)?;
+        if key_pair_id == 0 {
+            return None;
+        }
+        let operation =
+            SpdmKeyPairInfoOperation::read_bytes(
+                &[param1 & SPDM_SET_KEY_PAIR_INFO_OPERATION_MASK],
+            )?;
+        let mut request = SpdmSetKeyPairInfoRequestPayload {
+            operation,
+            key_pair_id,
+            ..Default::default()
+        };
+        match operation {
+            SpdmKeyPairInfoOperation::SpdmKeyPairInfoErase => {}
+            SpdmKeyPairInfoOperation::SpdmKeyPairInfoChange
+            | SpdmKeyPairInfoOperation::SpdmKeyPairInfoGenerate => {
+                request.desired_key_usage = SpdmKeyUsageMask::read(r)?;
+                request.desired_asym_algo = SpdmKeyPairAsymAlgo::read(r)?;
+                request.desired_assoc_cert_slot_mask = u8::read(r)?;
+            }
+            SpdmKeyPairInfoOperation::Unknown(_) => return None,
+        }
+        Some(request)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSetKeyPairInfoAckResponsePayload {}
+
+impl SpdmCodec for SpdmSetKeyPairInfoAckResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSetKeyPairInfoAckResponsePayload> {
+        u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        Some(SpdmSetKeyPairInfoAckResponsePayload {})
+    }
+}
+
+#[cfg(all(test,))]
+#[path = "mod_test.common.inc.rs"]
+mod testlib;
+
+#[cfg(all(test,))]
+#[path = "key_pair_info_test.rs"]
+mod key_pair_info_test;
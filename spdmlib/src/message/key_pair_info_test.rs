@@ -0,0 +1,157 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::*;
+use crate::common::{SpdmCodec, SpdmConfigInfo, SpdmContext, SpdmProvisionInfo};
+use testlib::{create_spdm_context, DeviceIO, TransportEncap};
+
+#[test]
+fn test_get_key_pair_info_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let u8_slice = &mut [0u8; 8];
+    let mut writer = Writer::init(u8_slice);
+    let value = SpdmGetKeyPairInfoRequestPayload { key_pair_id: 2 };
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(3));
+    assert_eq!(u8_slice[..3], [0x00, 0x00, 0x02]);
+
+    let reader = &mut Reader::init(&u8_slice[..3]);
+    let ret = SpdmGetKeyPairInfoRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.key_pair_id, 2);
+
+    // KeyPairID starts from 1.
+    let reader = &mut Reader::init(&[0x00, 0x00, 0x00]);
+    assert!(SpdmGetKeyPairInfoRequestPayload::spdm_read(context, reader).is_none());
+}
+
+#[test]
+fn test_key_pair_info_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let mut value = SpdmKeyPairInfoResponsePayload {
+        total_key_pairs: 2,
+        key_pair_id: 1,
+        key_pair_info: SpdmKeyPairInfo {
+            capabilities: SpdmKeyPairCapabilities::CERT_ASSOC_CAP
+                | SpdmKeyPairCapabilities::KEY_USAGE_CAP,
+            key_usage_capabilities: SpdmKeyUsageMask::KEY_EX_USE | SpdmKeyUsageMask::CHALLENGE_USE,
+            current_key_usage: SpdmKeyUsageMask::KEY_EX_USE,
+            asym_algo_capabilities: SpdmKeyPairAsymAlgo::ECC384,
+            current_asym_algo: SpdmKeyPairAsymAlgo::ECC384,
+            assoc_cert_slot_mask: 0b0000_0010,
+            public_key_info_len: 3,
+            ..Default::default()
+        },
+    };
+    value.key_pair_info.public_key_info[..3].copy_from_slice(&[0x30, 0x01, 0x00]);
+
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(2 + 19 + 3));
+    assert_eq!(
+        u8_slice[..24],
+        [
+            0x00, 0x00, 0x02, 0x01, 0x0C, 0x00, 0x03, 0x00, 0x01, 0x00, 0x10, 0x00, 0x00, 0x00,
+            0x10, 0x00, 0x00, 0x00, 0x03, 0x00, 0x02, 0x30, 0x01, 0x00
+        ]
+    );
+
+    let reader = &mut Reader::init(&u8_slice[..24]);
+    let ret = SpdmKeyPairInfoResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.total_key_pairs, 2);
+    assert_eq!(ret.key_pair_id, 1);
+    assert_eq!(
+        ret.key_pair_info.current_key_usage,
+        SpdmKeyUsageMask::KEY_EX_USE
+    );
+    assert_eq!(ret.key_pair_info.assoc_cert_slot_mask, 0b0000_0010);
+    assert_eq!(ret.key_pair_info.public_key_info(), [0x30, 0x01, 0x00]);
+
+    // Truncated public key info.
+    let reader = &mut Reader::init(&u8_slice[..23]);
+    assert!(SpdmKeyPairInfoResponsePayload::spdm_read(context, reader).is_none());
+
+    // KeyPairID beyond TotalKeyPairs.
+    u8_slice[3] = 3;
+    let reader = &mut Reader::init(&u8_slice[..24]);
+    assert!(SpdmKeyPairInfoResponsePayload::spdm_read(context, reader).is_none());
+}
+
+#[test]
+fn test_set_key_pair_info_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let value = SpdmSetKeyPairInfoRequestPayload {
+        operation: SpdmKeyPairInfoOperation::SpdmKeyPairInfoChange,
+        key_pair_id: 1,
+        desired_key_usage: SpdmKeyUsageMask::CHALLENGE_USE,
+        desired_asym_algo: SpdmKeyPairAsymAlgo::ECC256,
+        desired_assoc_cert_slot_mask: 0b0000_0001,
+    };
+    let u8_slice = &mut [0u8; 16];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(11));
+    assert_eq!(
+        u8_slice[..11],
+        [0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01]
+    );
+    let reader = &mut Reader::init(&u8_slice[..11]);
+    let ret = SpdmSetKeyPairInfoRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(
+        ret.operation,
+        SpdmKeyPairInfoOperation::SpdmKeyPairInfoChange
+    );
+    assert_eq!(ret.desired_key_usage, SpdmKeyUsageMask::CHALLENGE_USE);
+    assert_eq!(ret.desired_asym_algo, SpdmKeyPairAsymAlgo::ECC256);
+    assert_eq!(ret.desired_assoc_cert_slot_mask, 0b0000_0001);
+
+    // Erase carries the KeyPairID only.
+    let value = SpdmSetKeyPairInfoRequestPayload {
+        operation: SpdmKeyPairInfoOperation::SpdmKeyPairInfoErase,
+        key_pair_id: 2,
+        ..Default::default()
+    };
+    let u8_slice = &mut [0u8; 16];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(4));
+    assert_eq!(u8_slice[..4], [0x01, 0x00, 0x00, 0x02]);
+    let reader = &mut Reader::init(&u8_slice[..4]);
+    let ret = SpdmSetKeyPairInfoRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(
+        ret.operation,
+        SpdmKeyPairInfoOperation::SpdmKeyPairInfoErase
+    );
+    assert_eq!(ret.key_pair_id, 2);
+
+    // Unknown operation.
+    let reader = &mut Reader::init(&[0x03, 0x00, 0x00, 0x01]);
+    assert!(SpdmSetKeyPairInfoRequestPayload::spdm_read(context, reader).is_none());
+
+    // Change without the desired fields.
+    let reader = &mut Reader::init(&[0x00, 0x00, 0x00, 0x01]);
+    assert!(SpdmSetKeyPairInfoRequestPayload::spdm_read(context, reader).is_none());
+}
+
+#[test]
+fn test_set_key_pair_info_ack_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let u8_slice = &mut [0u8; 2];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(
+        SpdmSetKeyPairInfoAckResponsePayload {}.spdm_encode(context, &mut writer),
+        Ok(2)
+    );
+    let reader = &mut Reader::init(&u8_slice[..2]);
+    assert!(SpdmSetKeyPairInfoAckResponsePayload::spdm_read(context, reader).is_some());
+    let reader = &mut Reader::init(&u8_slice[..1]);
+    assert!(SpdmSetKeyPairInfoAckResponsePayload::spdm_read(context, reader).is_none());
+}
@@ -6,7 +6,10 @@ use crate::common;
 use crate::common::opaque::SpdmOpaqueStruct;
 use crate::common::spdm_codec::SpdmCodec;
 use crate::error::{SpdmStatus, SPDM_STATUS_BUFFER_FULL};
-use crate::protocol::{SpdmMeasurementRecordStructure, SpdmNonceStruct, SpdmSignatureStruct};
+use crate::protocol::{
+    SpdmMeasurementRecordStructure, SpdmNonceStruct, SpdmReqContextStruct, SpdmSignatureStruct,
+    SPDM_MAX_SLOT_NUMBER, SPDM_PUB_KEY_SLOT_ID,
+};
 use codec::enum_builder;
 use codec::{Codec, Reader, Writer};
 
@@ -16,6 +19,27 @@ use super::SpdmVersion;
 
 pub const MEASUREMENT_RESPONDER_PARAM2_SLOT_ID_MASK: u8 = 0b0000_1111;
 pub const MEASUREMENT_RESPONDER_PARAM2_CONTENT_CHANGED_MASK: u8 = 0b0011_0000;
+// SlotID [3:0] of a provisioned public key in SlotIDParam and MEASUREMENTS Param2
+pub const MEASUREMENT_PUB_KEY_SLOT_ID_PARAM: u8 = 0xF;
+
+fn encode_slot_id_param(slot_id: u8) -> u8 {
+    if slot_id == SPDM_PUB_KEY_SLOT_ID {
+        MEASUREMENT_PUB_KEY_SLOT_ID_PARAM
+    } else {
+        slot_id
+    }
+}
+
+fn decode_slot_id_param(slot_id_param: u8) -> Option<u8> {
+    let slot_id = slot_id_param & MEASUREMENT_RESPONDER_PARAM2_SLOT_ID_MASK;
+    if slot_id == MEASUREMENT_PUB_KEY_SLOT_ID_PARAM {
+        Some(SPDM_PUB_KEY_SLOT_ID)
+    } else if (slot_id as usize) < SPDM_MAX_SLOT_NUMBER {
+        Some(slot_id)
+    } else {
+        None
+    }
+}
 
 bitflags! {
     #[derive(Default)]
@@ -57,6 +81,7 @@ pub struct SpdmGetMeasurementsRequestPayload {
     pub measurement_operation: SpdmMeasurementOperation,
     pub nonce: SpdmNonceStruct,
     pub slot_id: u8,
+    pub requester_context: SpdmReqContextStruct,
 }
 
 impl SpdmCodec for SpdmGetMeasurementsRequestPayload {
@@ -85,12 +110,17 @@ impl SpdmCodec for SpdmGetMeasurementsRequestPayload {
             if context.negotiate_info.spdm_version_sel.get_u8()
                 >= SpdmVersion::SpdmVersion11.get_u8()
             {
-                cnt += self
-                    .slot_id
+                cnt += encode_slot_id_param(self.slot_id)
                     .encode(bytes)
                     .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
             }
         }
+        if context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion13.get_u8() {
+            cnt += self
+                .requester_context
+                .encode(bytes)
+                .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        }
         Ok(cnt)
     }
 
@@ -111,24 +141,33 @@ impl SpdmCodec for SpdmGetMeasurementsRequestPayload {
                 if context.negotiate_info.spdm_version_sel.get_u8()
                     >= SpdmVersion::SpdmVersion11.get_u8()
                 {
-                    u8::read(r)?
+                    decode_slot_id_param(u8::read(r)?)?
                 } else {
                     0
                 }
             } else {
                 0
             };
+        let requester_context = if context.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion13.get_u8()
+        {
+            SpdmReqContextStruct::read(r)?
+        } else {
+            SpdmReqContextStruct::default()
+        };
 
         Some(SpdmGetMeasurementsRequestPayload {
             measurement_attributes,
             measurement_operation,
             nonce,
             slot_id,
+            requester_context,
         })
     }
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmMeasurementsResponsePayload {
     pub number_of_measurement: u8,
     pub content_changed: SpdmMeasurementContentChanged,
@@ -136,6 +175,7 @@ pub struct SpdmMeasurementsResponsePayload {
     pub measurement_record: SpdmMeasurementRecordStructure,
     pub nonce: SpdmNonceStruct,
     pub opaque: SpdmOpaqueStruct,
+    pub requester_context: SpdmReqContextStruct,
     pub signature: SpdmSignatureStruct,
 }
 
@@ -160,15 +200,14 @@ impl SpdmCodec for SpdmMeasurementsResponsePayload {
         if context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion12.get_u8()
             && context.runtime_info.need_measurement_signature
         {
-            cnt += (self.slot_id | self.content_changed.bits())
+            cnt += (encode_slot_id_param(self.slot_id) | self.content_changed.bits())
                 .encode(bytes)
                 .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
         } else if context.negotiate_info.spdm_version_sel.get_u8()
             >= SpdmVersion::SpdmVersion11.get_u8()
             && context.runtime_info.need_measurement_signature
         {
-            cnt += self
-                .slot_id
+            cnt += encode_slot_id_param(self.slot_id)
                 .encode(bytes)
                 .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param 2
         } else {
@@ -180,6 +219,12 @@ impl SpdmCodec for SpdmMeasurementsResponsePayload {
             .encode(bytes)
             .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
         cnt += self.opaque.spdm_encode(context, bytes)?;
+        if context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion13.get_u8() {
+            cnt += self
+                .requester_context
+                .encode(bytes)
+                .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        }
         if context.runtime_info.need_measurement_signature {
             cnt += self.signature.spdm_encode(context, bytes)?;
         }
@@ -192,12 +237,19 @@ impl SpdmCodec for SpdmMeasurementsResponsePayload {
     ) -> Option<SpdmMeasurementsResponsePayload> {
         let number_of_measurement = u8::read(r)?; // param1
         let param2 = u8::read(r)?; // param2
-        let slot_id = param2 & MEASUREMENT_RESPONDER_PARAM2_SLOT_ID_MASK; // Bit [3:0]
+        let slot_id = decode_slot_id_param(param2)?; // Bit [3:0]
         let content_changed = param2 & MEASUREMENT_RESPONDER_PARAM2_CONTENT_CHANGED_MASK; // Bit [5:4]
         let content_changed = SpdmMeasurementContentChanged::from_bits(content_changed)?;
         let measurement_record = SpdmMeasurementRecordStructure::spdm_read(context, r)?;
         let nonce = SpdmNonceStruct::read(r)?;
         let opaque = SpdmOpaqueStruct::spdm_read(context, r)?;
+        let requester_context = if context.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion13.get_u8()
+        {
+            SpdmReqContextStruct::read(r)?
+        } else {
+            SpdmReqContextStruct::default()
+        };
         let signature = if context.runtime_info.need_measurement_signature {
             SpdmSignatureStruct::spdm_read(context, r)?
         } else {
@@ -210,6 +262,7 @@ impl SpdmCodec for SpdmMeasurementsResponsePayload {
             measurement_record,
             nonce,
             opaque,
+            requester_context,
             signature,
         })
     }
@@ -255,6 +308,7 @@ mod tests {
                 data: [100u8; SPDM_NONCE_SIZE],
             },
             slot_id: 0x7,
+            requester_context: SpdmReqContextStruct::default(),
         };
 
         create_spdm_context!(context);
@@ -290,6 +344,7 @@ mod tests {
                 data: [100u8; SPDM_NONCE_SIZE],
             },
             slot_id: 0x7,
+            requester_context: SpdmReqContextStruct::default(),
         };
 
         create_spdm_context!(context);
@@ -314,6 +369,38 @@ mod tests {
         assert_eq!(0, reader.left());
     }
     #[test]
+    fn test_case2_spdm_get_measurements_request_payload() {
+        let u8_slice = &mut [0u8; 2 + SPDM_NONCE_SIZE + 1];
+        let mut writer = Writer::init(u8_slice);
+        let mut value = SpdmGetMeasurementsRequestPayload {
+            measurement_attributes: SpdmMeasurementAttributes::SIGNATURE_REQUESTED,
+            measurement_operation: SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber,
+            nonce: SpdmNonceStruct {
+                data: [100u8; SPDM_NONCE_SIZE],
+            },
+            slot_id: SPDM_PUB_KEY_SLOT_ID,
+            requester_context: SpdmReqContextStruct::default(),
+        };
+
+        create_spdm_context!(context);
+        context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+
+        // The provisioned public key is SlotID 0xF on the wire.
+        assert!(value.spdm_encode(&mut context, &mut writer).is_ok());
+        assert_eq!(u8_slice[2 + SPDM_NONCE_SIZE], 0xF);
+        let mut reader = Reader::init(u8_slice);
+        let get_measurements =
+            SpdmGetMeasurementsRequestPayload::spdm_read(&mut context, &mut reader).unwrap();
+        assert_eq!(get_measurements.slot_id, SPDM_PUB_KEY_SLOT_ID);
+
+        // SlotIDs 8 to 14 do not exist.
+        value.slot_id = 8;
+        let mut writer = Writer::init(u8_slice);
+        assert!(value.spdm_encode(&mut context, &mut writer).is_ok());
+        let mut reader = Reader::init(u8_slice);
+        assert!(SpdmGetMeasurementsRequestPayload::spdm_read(&mut context, &mut reader).is_none());
+    }
+    #[test]
     fn test_case0_spdm_measurements_response_payload() {
         create_spdm_context!(context);
 
@@ -363,6 +450,7 @@ mod tests {
                 data_size: SPDM_MAX_ASYM_KEY_SIZE as u16,
                 data: [100u8; SPDM_MAX_ASYM_KEY_SIZE],
             },
+            requester_context: SpdmReqContextStruct::default(),
         };
 
         context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
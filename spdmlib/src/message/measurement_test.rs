@@ -25,6 +25,7 @@ fn test_measurement_struct() {
         measurement_operation: SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber,
         nonce: SpdmNonceStruct::default(),
         slot_id: 1,
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(request.spdm_encode(context, writer).is_ok());
     assert_eq!(writer.used(), 4 + 32 + 1 - 2);
@@ -36,11 +37,46 @@ fn test_measurement_struct() {
         measurement_operation: SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber,
         nonce: SpdmNonceStruct::default(),
         slot_id: 1,
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(request.spdm_encode(context, writer).is_ok());
     assert_eq!(writer.used(), 4 - 2);
 }
 
+#[test]
+fn test_measurement_struct_requester_context() {
+    create_spdm_context!(context);
+    let context = &mut context;
+    context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+
+    // RequesterContext follows SlotIDParam when signature is requested
+    let u8_slice = &mut [0u8; 4 + 32 + 1 + 8];
+    let writer = &mut Writer::init(u8_slice);
+    let request = SpdmGetMeasurementsRequestPayload {
+        measurement_attributes: SpdmMeasurementAttributes::SIGNATURE_REQUESTED,
+        measurement_operation: SpdmMeasurementOperation::SpdmMeasurementRequestAll,
+        nonce: SpdmNonceStruct::default(),
+        slot_id: 1,
+        requester_context: SpdmReqContextStruct { data: [0x5a; 8] },
+    };
+    assert!(request.spdm_encode(context, writer).is_ok());
+    assert_eq!(writer.used(), 4 + 32 + 1 + 8 - 2);
+    let reader = &mut Reader::init(&u8_slice[..4 + 32 + 1 + 8 - 2]);
+    let ret = SpdmGetMeasurementsRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(ret.slot_id, 1);
+    assert_eq!(ret.requester_context.data, [0x5a; 8]);
+    assert_eq!(reader.left(), 0);
+
+    // RequesterContext is present without signature too
+    let writer = &mut Writer::init(u8_slice);
+    let request = SpdmGetMeasurementsRequestPayload {
+        measurement_attributes: SpdmMeasurementAttributes::empty(),
+        ..request
+    };
+    assert!(request.spdm_encode(context, writer).is_ok());
+    assert_eq!(writer.used(), 4 + 8 - 2);
+}
+
 #[ignore = "Extend unit tests"]
 #[test]
 fn test_measurement_response() {
@@ -29,23 +29,35 @@ pub mod key_update;
 pub mod psk_exchange;
 pub mod psk_finish;
 pub mod respond_if_ready;
+// SPDM 1.2
+pub mod chunk;
+pub mod csr;
+pub mod set_certificate;
+// SPDM 1.3
+pub mod event;
+pub mod key_pair_info;
 
 pub use algorithm::*;
 pub use capability::*;
 pub use certificate::*;
 pub use challenge::*;
+pub use chunk::*;
+pub use csr::*;
 pub use digest::*;
 #[cfg(feature = "mut-auth")]
 pub use encapsulated::*;
 pub use end_session::*;
 pub use error::*;
+pub use event::*;
 pub use finish::*;
 pub use heartbeat::*;
 pub use key_exchange::*;
+pub use key_pair_info::*;
 pub use key_update::*;
 pub use measurement::*;
 pub use psk_exchange::*;
 pub use psk_finish::*;
+pub use set_certificate::*;
 pub use version::*;
 // Add new SPDM command here.
 pub use respond_if_ready::*;
@@ -75,6 +87,19 @@ enum_builder! {
         SpdmResponseEncapsulatedRequest => 0x6A,
         SpdmResponseEncapsulatedResponseAck => 0x6B,
         SpdmResponseEndSessionAck => 0x6C,
+        // 1.2 response
+        SpdmResponseChunkSendAck => 0x05,
+        SpdmResponseChunkResponse => 0x06,
+        SpdmResponseCsr => 0x6D,
+        SpdmResponseSetCertificateRsp => 0x6E,
+        // 1.3 response
+        SpdmResponseEndpointInfo => 0x07,
+        SpdmResponseSupportedEventTypes => 0x62,
+        SpdmResponseMeasurementExtensionLog => 0x6F,
+        SpdmResponseSubscribeEventTypesAck => 0x70,
+        SpdmResponseEventAck => 0x71,
+        SpdmResponseKeyPairInfo => 0x7C,
+        SpdmResponseSetKeyPairInfoAck => 0x7D,
 
         // 1.0 rerquest
         SpdmRequestGetDigests => 0x81,
@@ -95,7 +120,20 @@ enum_builder! {
         SpdmRequestKeyUpdate => 0xE9,
         SpdmRequestGetEncapsulatedRequest => 0xEA,
         SpdmRequestDeliverEncapsulatedResponse => 0xEB,
-        SpdmRequestEndSession => 0xEC
+        SpdmRequestEndSession => 0xEC,
+        // 1.2 request
+        SpdmRequestChunkSend => 0x85,
+        SpdmRequestChunkGet => 0x86,
+        SpdmRequestGetCsr => 0xED,
+        SpdmRequestSetCertificate => 0xEE,
+        // 1.3 request
+        SpdmRequestGetEndpointInfo => 0x87,
+        SpdmRequestGetSupportedEventTypes => 0xE2,
+        SpdmRequestGetMeasurementExtensionLog => 0xEF,
+        SpdmRequestSubscribeEventTypes => 0xF0,
+        SpdmRequestSendEvent => 0xF1,
+        SpdmRequestGetKeyPairInfo => 0xFC,
+        SpdmRequestSetKeyPairInfo => 0xFD
     }
 }
 impl Default for SpdmRequestResponseCode {
@@ -235,6 +273,29 @@ pub enum SpdmMessagePayload {
     SpdmEndSessionRequest(SpdmEndSessionRequestPayload),
     SpdmEndSessionResponse(SpdmEndSessionResponsePayload),
 
+    SpdmChunkGetRequest(SpdmChunkGetRequestPayload),
+    SpdmChunkResponse(SpdmChunkResponsePayload),
+    SpdmChunkSendRequest(SpdmChunkSendRequestPayload),
+    SpdmChunkSendAckResponse(SpdmChunkSendAckResponsePayload),
+
+    SpdmGetCsrRequest(SpdmGetCsrRequestPayload),
+    SpdmCsrResponse(SpdmCsrResponsePayload),
+
+    SpdmSetCertificateRequest(SpdmSetCertificateRequestPayload),
+    SpdmSetCertificateResponse(SpdmSetCertificateResponsePayload),
+
+    SpdmGetSupportedEventTypesRequest(SpdmGetSupportedEventTypesRequestPayload),
+    SpdmSupportedEventTypesResponse(SpdmSupportedEventTypesResponsePayload),
+    SpdmSubscribeEventTypesRequest(SpdmSubscribeEventTypesRequestPayload),
+    SpdmSubscribeEventTypesAckResponse(SpdmSubscribeEventTypesAckResponsePayload),
+    SpdmSendEventRequest(SpdmSendEventRequestPayload),
+    SpdmEventAckResponse(SpdmEventAckResponsePayload),
+
+    SpdmGetKeyPairInfoRequest(SpdmGetKeyPairInfoRequestPayload),
+    SpdmKeyPairInfoResponse(SpdmKeyPairInfoResponsePayload),
+    SpdmSetKeyPairInfoRequest(SpdmSetKeyPairInfoRequestPayload),
+    SpdmSetKeyPairInfoAckResponse(SpdmSetKeyPairInfoAckResponsePayload),
+
     // Add new SPDM command here.
     SpdmErrorResponse(SpdmErrorResponsePayload),
     SpdmVendorDefinedRequest(SpdmVendorDefinedRequestPayload),
@@ -403,6 +464,99 @@ impl SpdmMessage {
                 ))
             }
 
+            SpdmRequestResponseCode::SpdmResponseChunkResponse => {
+                Some(SpdmMessagePayload::SpdmChunkResponse(
+                    SpdmChunkResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmRequestChunkGet => {
+                Some(SpdmMessagePayload::SpdmChunkGetRequest(
+                    SpdmChunkGetRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmRequestChunkSend => {
+                Some(SpdmMessagePayload::SpdmChunkSendRequest(
+                    SpdmChunkSendRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseChunkSendAck => {
+                Some(SpdmMessagePayload::SpdmChunkSendAckResponse(
+                    SpdmChunkSendAckResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+
+            SpdmRequestResponseCode::SpdmRequestGetCsr => {
+                Some(SpdmMessagePayload::SpdmGetCsrRequest(
+                    SpdmGetCsrRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseCsr => Some(SpdmMessagePayload::SpdmCsrResponse(
+                SpdmCsrResponsePayload::spdm_read(context, r)?,
+            )),
+
+            SpdmRequestResponseCode::SpdmRequestSetCertificate => {
+                Some(SpdmMessagePayload::SpdmSetCertificateRequest(
+                    SpdmSetCertificateRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseSetCertificateRsp => {
+                Some(SpdmMessagePayload::SpdmSetCertificateResponse(
+                    SpdmSetCertificateResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+
+            SpdmRequestResponseCode::SpdmRequestGetSupportedEventTypes => {
+                Some(SpdmMessagePayload::SpdmGetSupportedEventTypesRequest(
+                    SpdmGetSupportedEventTypesRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseSupportedEventTypes => {
+                Some(SpdmMessagePayload::SpdmSupportedEventTypesResponse(
+                    SpdmSupportedEventTypesResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmRequestSubscribeEventTypes => {
+                Some(SpdmMessagePayload::SpdmSubscribeEventTypesRequest(
+                    SpdmSubscribeEventTypesRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseSubscribeEventTypesAck => {
+                Some(SpdmMessagePayload::SpdmSubscribeEventTypesAckResponse(
+                    SpdmSubscribeEventTypesAckResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmRequestSendEvent => {
+                Some(SpdmMessagePayload::SpdmSendEventRequest(
+                    SpdmSendEventRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseEventAck => {
+                Some(SpdmMessagePayload::SpdmEventAckResponse(
+                    SpdmEventAckResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+
+            SpdmRequestResponseCode::SpdmRequestGetKeyPairInfo => {
+                Some(SpdmMessagePayload::SpdmGetKeyPairInfoRequest(
+                    SpdmGetKeyPairInfoRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseKeyPairInfo => {
+                Some(SpdmMessagePayload::SpdmKeyPairInfoResponse(
+                    SpdmKeyPairInfoResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmRequestSetKeyPairInfo => {
+                Some(SpdmMessagePayload::SpdmSetKeyPairInfoRequest(
+                    SpdmSetKeyPairInfoRequestPayload::spdm_read(context, r)?,
+                ))
+            }
+            SpdmRequestResponseCode::SpdmResponseSetKeyPairInfoAck => {
+                Some(SpdmMessagePayload::SpdmSetKeyPairInfoAckResponse(
+                    SpdmSetKeyPairInfoAckResponsePayload::spdm_read(context, r)?,
+                ))
+            }
+
             // Add new SPDM command here.
             SpdmRequestResponseCode::SpdmResponseError => {
                 Some(SpdmMessagePayload::SpdmErrorResponse(
@@ -547,6 +701,65 @@ impl SpdmCodec for SpdmMessage {
                 cnt += payload.spdm_encode(context, bytes)?;
             }
 
+            SpdmMessagePayload::SpdmChunkGetRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmChunkResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmChunkSendRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmChunkSendAckResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+
+            SpdmMessagePayload::SpdmGetCsrRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmCsrResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+
+            SpdmMessagePayload::SpdmSetCertificateRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmSetCertificateResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+
+            SpdmMessagePayload::SpdmGetSupportedEventTypesRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmSupportedEventTypesResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmSubscribeEventTypesRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmSubscribeEventTypesAckResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmSendEventRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmEventAckResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+
+            SpdmMessagePayload::SpdmGetKeyPairInfoRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmKeyPairInfoResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmSetKeyPairInfoRequest(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+            SpdmMessagePayload::SpdmSetKeyPairInfoAckResponse(payload) => {
+                cnt += payload.spdm_encode(context, bytes)?;
+            }
+
             // Add new SPDM command here.
             SpdmMessagePayload::SpdmErrorResponse(payload) => {
                 cnt += payload.spdm_encode(context, bytes)?;
@@ -570,6 +783,10 @@ impl SpdmCodec for SpdmMessage {
 #[path = "mod_test.common.inc.rs"]
 mod testlib;
 
+#[cfg(all(test,))]
+#[path = "codec_proptest.rs"]
+mod codec_proptest;
+
 #[cfg(all(test,))]
 mod tests {
     use super::*;
@@ -920,6 +1137,7 @@ mod tests {
                 nonce: SpdmNonceStruct {
                     data: [100u8; SPDM_NONCE_SIZE],
                 },
+                requester_context: SpdmReqContextStruct::default(),
             }),
         };
 
@@ -972,6 +1190,7 @@ mod tests {
                         data_size: SPDM_MAX_ASYM_KEY_SIZE as u16,
                         data: [0x55u8; SPDM_MAX_ASYM_KEY_SIZE],
                     },
+                    requester_context: SpdmReqContextStruct::default(),
                 },
             ),
         };
@@ -1033,6 +1252,7 @@ mod tests {
                         data: [100u8; SPDM_NONCE_SIZE],
                     },
                     slot_id: 0x7,
+                    requester_context: SpdmReqContextStruct::default(),
                 },
             ),
         };
@@ -1106,6 +1326,7 @@ mod tests {
                         data_size: SPDM_MAX_ASYM_KEY_SIZE as u16,
                         data: [100u8; SPDM_MAX_ASYM_KEY_SIZE],
                     },
+                    requester_context: SpdmReqContextStruct::default(),
                 },
             ),
         };
@@ -1656,6 +1877,7 @@ mod tests {
                     },
                     SPDM_MAX_SLOT_NUMBER,
                 ),
+                ..Default::default()
             }),
         };
         create_spdm_context!(context);
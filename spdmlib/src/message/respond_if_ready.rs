@@ -9,7 +9,11 @@ use crate::error::{SpdmStatus, SPDM_STATUS_BUFFER_FULL};
 use codec::{Codec, Reader, Writer};
 
 #[derive(Debug, Clone, Default)]
-pub struct SpdmRespondIfReadyRequestPayload {}
+pub struct SpdmRespondIfReadyRequestPayload {
+    // RequestCode and Token of the ERROR(ResponseNotReady)
+    pub request_code: u8,
+    pub token: u8,
+}
 
 impl SpdmCodec for SpdmRespondIfReadyRequestPayload {
     fn spdm_encode(
@@ -17,8 +21,12 @@ impl SpdmCodec for SpdmRespondIfReadyRequestPayload {
         _context: &mut common::SpdmContext,
         bytes: &mut Writer,
     ) -> Result<usize, SpdmStatus> {
-        0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
-        0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        self.request_code
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        self.token
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
         Ok(2)
     }
 
@@ -26,10 +34,13 @@ impl SpdmCodec for SpdmRespondIfReadyRequestPayload {
         _context: &mut common::SpdmContext,
         r: &mut Reader,
     ) -> Option<SpdmRespondIfReadyRequestPayload> {
-        u8::read(r)?; // param1
-        u8::read(r)?; // param2
+        let request_code = u8::read(r)?; // param1
+        let token = u8::read(r)?; // param2
 
-        Some(SpdmRespondIfReadyRequestPayload {})
+        Some(SpdmRespondIfReadyRequestPayload {
+            request_code,
+            token,
+        })
     }
 }
 
@@ -0,0 +1,134 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::spdm_codec::SpdmCodec;
+use crate::error::SPDM_STATUS_BUFFER_FULL;
+use crate::protocol::{SpdmCertChainBuffer, SpdmVersion};
+use crate::{common, error::SpdmStatus};
+use codec::{Codec, Reader, Writer};
+
+pub const SPDM_SET_CERTIFICATE_SLOT_ID_MASK: u8 = 0b0000_1111;
+// Param1, SPDM 1.3
+pub const SPDM_SET_CERTIFICATE_CERT_MODEL_MASK: u8 = 0b0111_0000;
+pub const SPDM_SET_CERTIFICATE_CERT_MODEL_SHIFT: u8 = 4;
+pub const SPDM_SET_CERTIFICATE_ERASE: u8 = 0b1000_0000;
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSetCertificateRequestPayload {
+    pub slot_id: u8,
+    // SPDM 1.3, 0 for 1.2
+    pub cert_model: u8,
+    pub erase: bool,
+    pub key_pair_id: u8,
+    // Certificate chain format, including Length, Reserved and RootHash.
+    // Empty when erase is set.
+    pub cert_chain: SpdmCertChainBuffer,
+}
+
+impl SpdmCodec for SpdmSetCertificateRequestPayload {
+    fn spdm_encode(
+        &self,
+        context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let is_spdm13 =
+            context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion13.get_u8();
+        let erase = is_spdm13 && self.erase;
+
+        let mut param1 = self.slot_id & SPDM_SET_CERTIFICATE_SLOT_ID_MASK;
+        let mut key_pair_id = 0u8;
+        if is_spdm13 {
+            param1 |= (self.cert_model << SPDM_SET_CERTIFICATE_CERT_MODEL_SHIFT)
+                & SPDM_SET_CERTIFICATE_CERT_MODEL_MASK;
+            if erase {
+                param1 |= SPDM_SET_CERTIFICATE_ERASE;
+            }
+            key_pair_id = self.key_pair_id;
+        }
+
+        let mut cnt = 0usize;
+        cnt += param1.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += key_pair_id
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        if !erase {
+            cnt += bytes
+                .extend_from_slice(self.cert_chain.as_ref())
+                .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        }
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSetCertificateRequestPayload> {
+        let param1 = u8::read(r)?; // param1
+        let param2 = u8::read(r)?; // param2
+
+        let mut request = SpdmSetCertificateRequestPayload {
+            slot_id: param1 & SPDM_SET_CERTIFICATE_SLOT_ID_MASK,
+            ..Default::default()
+        };
+        if context.negotiate_info.spdm_version_sel.get_u8() >= SpdmVersion::SpdmVersion13.get_u8() {
+            request.cert_model = (param1 & SPDM_SET_CERTIFICATE_CERT_MODEL_MASK)
+                >> SPDM_SET_CERTIFICATE_CERT_MODEL_SHIFT;
+            request.erase = param1 & SPDM_SET_CERTIFICATE_ERASE != 0;
+            request.key_pair_id = param2;
+        }
+        if request.erase {
+            return Some(request);
+        }
+
+        let length = u16::read(r)?;
+        let reserved = u16::read(r)?;
+        if (length as usize) < 4 || length as usize > request.cert_chain.data.len() {
+            return None;
+        }
+        request.cert_chain.data_size = length;
+        request.cert_chain.data[..2].copy_from_slice(&length.to_le_bytes());
+        request.cert_chain.data[2..4].copy_from_slice(&reserved.to_le_bytes());
+        request.cert_chain.data[4..length as usize].copy_from_slice(r.take(length as usize - 4)?);
+        Some(request)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSetCertificateResponsePayload {
+    pub slot_id: u8,
+}
+
+impl SpdmCodec for SpdmSetCertificateResponsePayload {
+    fn spdm_encode(
+        &self,
+        _context: &mut common::SpdmContext,
+        bytes: &mut Writer,
+    ) -> Result<usize, SpdmStatus> {
+        let mut cnt = 0usize;
+        cnt += (self.slot_id & SPDM_SET_CERTIFICATE_SLOT_ID_MASK)
+            .encode(bytes)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param1
+        cnt += 0u8.encode(bytes).map_err(|_| SPDM_STATUS_BUFFER_FULL)?; // param2
+        Ok(cnt)
+    }
+
+    fn spdm_read(
+        _context: &mut common::SpdmContext,
+        r: &mut Reader,
+    ) -> Option<SpdmSetCertificateResponsePayload> {
+        let param1 = u8::read(r)?; // param1
+        u8::read(r)?; // param2
+        Some(SpdmSetCertificateResponsePayload {
+            slot_id: param1 & SPDM_SET_CERTIFICATE_SLOT_ID_MASK,
+        })
+    }
+}
+
+#[cfg(all(test,))]
+#[path = "mod_test.common.inc.rs"]
+mod testlib;
+
+#[cfg(all(test,))]
+#[path = "set_certificate_test.rs"]
+mod set_certificate_test;
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::*;
+use crate::common::{SpdmCodec, SpdmConfigInfo, SpdmContext, SpdmProvisionInfo};
+use testlib::{create_spdm_context, DeviceIO, TransportEncap};
+
+#[test]
+fn test_set_certificate_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    // 1. CertModel, Erase and KeyPairID are reserved in SPDM 1.2.
+    context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    let mut value = SpdmSetCertificateRequestPayload {
+        slot_id: 3,
+        cert_model: 1,
+        key_pair_id: 2,
+        ..Default::default()
+    };
+    value.cert_chain.data_size = 8;
+    value.cert_chain.data[..8].copy_from_slice(&[0x08, 0x00, 0x00, 0x00, 0x30, 0x02, 0x05, 0x00]);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(10));
+    assert_eq!(
+        u8_slice[..10],
+        [0x03, 0x00, 0x08, 0x00, 0x00, 0x00, 0x30, 0x02, 0x05, 0x00]
+    );
+
+    let reader = &mut Reader::init(&u8_slice[..10]);
+    let ret = SpdmSetCertificateRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(reader.left(), 0);
+    assert_eq!(ret.slot_id, 3);
+    assert_eq!(ret.cert_model, 0);
+    assert_eq!(ret.key_pair_id, 0);
+    assert_eq!(ret.cert_chain.as_ref(), value.cert_chain.as_ref());
+
+    // Truncated cert chain is rejected.
+    let reader = &mut Reader::init(&u8_slice[..9]);
+    assert!(SpdmSetCertificateRequestPayload::spdm_read(context, reader).is_none());
+
+    // 2. SPDM 1.3 carries CertModel and KeyPairID.
+    context.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(10));
+    assert_eq!(u8_slice[..2], [0x13, 0x02]);
+
+    let reader = &mut Reader::init(&u8_slice[..10]);
+    let ret = SpdmSetCertificateRequestPayload::spdm_read(context, reader).unwrap();
+    assert_eq!(ret.cert_model, 1);
+    assert_eq!(ret.key_pair_id, 2);
+    assert!(!ret.erase);
+
+    // 3. Erase carries no cert chain.
+    value.erase = true;
+    let u8_slice = &mut [0u8; 32];
+    let mut writer = Writer::init(u8_slice);
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(2));
+    assert_eq!(u8_slice[..2], [0x93, 0x02]);
+
+    let reader = &mut Reader::init(&u8_slice[..2]);
+    let ret = SpdmSetCertificateRequestPayload::spdm_read(context, reader).unwrap();
+    assert!(ret.erase);
+    assert_eq!(ret.cert_chain.data_size, 0);
+}
+
+#[test]
+fn test_set_certificate_response_struct() {
+    create_spdm_context!(context);
+    let context = &mut context;
+
+    let u8_slice = &mut [0u8; 4];
+    let mut writer = Writer::init(u8_slice);
+    let value = SpdmSetCertificateResponsePayload { slot_id: 5 };
+    assert_eq!(value.spdm_encode(context, &mut writer), Ok(2));
+    assert_eq!(u8_slice[..2], [0x05, 0x00]);
+
+    let reader = &mut Reader::init(&u8_slice[..2]);
+    let ret = SpdmSetCertificateResponsePayload::spdm_read(context, reader).unwrap();
+    assert_eq!(ret.slot_id, 5);
+}
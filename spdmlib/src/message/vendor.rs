@@ -3,15 +3,21 @@
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
 use crate::common;
+use crate::common::session::SpdmSessionState;
 use crate::common::spdm_codec::SpdmCodec;
 use crate::config;
 use crate::error::{
-    SpdmResult, SpdmStatus, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_STATE_LOCAL,
+    SpdmResult, SpdmStatus, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_PARAMETER,
+    SPDM_STATUS_INVALID_STATE_LOCAL, SPDM_STATUS_UNSUPPORTED_CAP,
 };
+use crate::message::SpdmVendorDefinedError;
 use codec::{enum_builder, Codec, Reader, Writer};
 
 use conquer_once::spin::OnceCell;
 
+pub mod cxl_ide_km;
+pub mod ide_km;
+
 // config::MAX_SPDM_MSG_SIZE - 7 - 2
 // SPDM0274 1.2.1: Table 56, table 57 VENDOR_DEFINED_RESPONSE message format
 pub const MAX_SPDM_VENDOR_DEFINED_PAYLOAD_SIZE: usize = config::MAX_SPDM_MSG_SIZE - 7 - 2;
@@ -277,3 +283,185 @@ pub fn vendor_defined_request_handler(
         Err(SPDM_STATUS_INVALID_STATE_LOCAL)
     }
 }
+
+/// Optional hook invoked when the vendor defined request handler fails.
+/// Returning Some makes the responder reply ERROR(VendorDefined) with the
+/// given vendor extended data instead of ERROR(Unspecified).
+#[derive(Clone, Copy)]
+pub struct VendorDefinedErrorStruct {
+    pub vendor_defined_error_handler:
+        fn(&VendorDefinedReqPayloadStruct, SpdmStatus) -> Option<SpdmVendorDefinedError>,
+}
+
+static VENDOR_DEFINED_ERROR: OnceCell<VendorDefinedErrorStruct> = OnceCell::uninit();
+
+pub fn register_vendor_defined_error_struct(context: VendorDefinedErrorStruct) -> bool {
+    VENDOR_DEFINED_ERROR.try_init_once(|| context).is_ok()
+}
+
+pub fn vendor_defined_error_handler(
+    vendor_defined_req_payload_struct: &VendorDefinedReqPayloadStruct,
+    status: SpdmStatus,
+) -> Option<SpdmVendorDefinedError> {
+    let vdes = VENDOR_DEFINED_ERROR.try_get().ok()?;
+    (vdes.vendor_defined_error_handler)(vendor_defined_req_payload_struct, status)
+}
+
+/// Session a vendor defined request arrived in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VendorDefinedRequestSession {
+    pub session_id: u32,
+    pub session_state: SpdmSessionState,
+}
+
+impl VendorDefinedRequestSession {
+    /// Return true if session is a secure session in the application phase.
+    pub fn is_established(session: Option<VendorDefinedRequestSession>) -> bool {
+        matches!(
+            session,
+            Some(VendorDefinedRequestSession {
+                session_state: SpdmSessionState::SpdmSessionEstablished,
+                ..
+            })
+        )
+    }
+}
+
+/// A registry handler gets the session of the request, None outside of a
+/// session, so that protocols carrying secrets can refuse cleartext requests.
+pub type VendorDefinedRequestHandler = fn(
+    Option<VendorDefinedRequestSession>,
+    &VendorDefinedReqPayloadStruct,
+) -> SpdmResult<VendorDefinedRspPayloadStruct>;
+
+/// Number of vendor defined protocols a registry can hold.
+pub const MAX_SPDM_VENDOR_DEFINED_HANDLER_COUNT: usize = 16;
+
+/// Longest vendor ID a registry entry can match, the IANA enterprise number.
+pub const MAX_SPDM_VENDOR_DEFINED_REGISTRY_VENDOR_ID_LEN: usize = 4;
+
+#[derive(Clone, Copy)]
+struct VendorDefinedHandlerEntry {
+    standard_id: RegistryOrStandardsBodyID,
+    vendor_id_len: u8,
+    vendor_id: [u8; MAX_SPDM_VENDOR_DEFINED_REGISTRY_VENDOR_ID_LEN],
+    handler: VendorDefinedRequestHandler,
+}
+
+impl VendorDefinedHandlerEntry {
+    fn matches(&self, standard_id: RegistryOrStandardsBodyID, vendor_id: &VendorIDStruct) -> bool {
+        self.standard_id == standard_id
+            && self.vendor_id_len == vendor_id.len
+            && self.vendor_id[..self.vendor_id_len as usize]
+                == vendor_id.vendor_id[..vendor_id.len as usize]
+    }
+}
+
+/// Vendor defined request handlers keyed by (standard ID, vendor ID), so
+/// that several vendor protocols, e.g. IDE_KM, TDISP and OEM ones, can be
+/// served by one responder.
+///
+/// Handlers sharing a key are tried in registration order, a handler
+/// returning SPDM_STATUS_UNSUPPORTED_CAP passes the request to the next one.
+/// This is how PCI-SIG protocols, which share the PCI-SIG vendor ID and are
+/// told apart by the protocol ID in the payload, coexist.
+#[derive(Clone, Copy, Default)]
+pub struct VendorDefinedRegistry {
+    entries: [Option<VendorDefinedHandlerEntry>; MAX_SPDM_VENDOR_DEFINED_HANDLER_COUNT],
+}
+
+impl VendorDefinedRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        standard_id: RegistryOrStandardsBodyID,
+        vendor_id: &VendorIDStruct,
+        handler: VendorDefinedRequestHandler,
+    ) -> SpdmResult {
+        if vendor_id.len as usize > MAX_SPDM_VENDOR_DEFINED_REGISTRY_VENDOR_ID_LEN {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        let slot = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.is_none())
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        let mut entry = VendorDefinedHandlerEntry {
+            standard_id,
+            vendor_id_len: vendor_id.len,
+            vendor_id: [0u8; MAX_SPDM_VENDOR_DEFINED_REGISTRY_VENDOR_ID_LEN],
+            handler,
+        };
+        entry.vendor_id[..vendor_id.len as usize]
+            .copy_from_slice(&vendor_id.vendor_id[..vendor_id.len as usize]);
+        *slot = Some(entry);
+        Ok(())
+    }
+
+    pub fn is_registered(
+        &self,
+        standard_id: RegistryOrStandardsBodyID,
+        vendor_id: &VendorIDStruct,
+    ) -> bool {
+        self.entries
+            .iter()
+            .flatten()
+            .any(|entry| entry.matches(standard_id, vendor_id))
+    }
+
+    /// Return SPDM_STATUS_UNSUPPORTED_CAP if no handler took the request.
+    pub fn dispatch(
+        &self,
+        standard_id: RegistryOrStandardsBodyID,
+        vendor_id: &VendorIDStruct,
+        session: Option<VendorDefinedRequestSession>,
+        vendor_defined_req_payload_struct: &VendorDefinedReqPayloadStruct,
+    ) -> SpdmResult<VendorDefinedRspPayloadStruct> {
+        for entry in self
+            .entries
+            .iter()
+            .flatten()
+            .filter(|entry| entry.matches(standard_id, vendor_id))
+        {
+            match (entry.handler)(session, vendor_defined_req_payload_struct) {
+                Err(status) if status == SPDM_STATUS_UNSUPPORTED_CAP => continue,
+                result => return result,
+            }
+        }
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    }
+}
+
+static VENDOR_DEFINED_REGISTRY: OnceCell<VendorDefinedRegistry> = OnceCell::uninit();
+
+pub fn register_vendor_defined_registry(registry: VendorDefinedRegistry) -> bool {
+    VENDOR_DEFINED_REGISTRY.try_init_once(|| registry).is_ok()
+}
+
+/// Route a vendor defined request to the registry handler of its
+/// (standard ID, vendor ID). Requests for other vendors go to the handler
+/// of register_vendor_defined_struct, if any.
+pub fn vendor_defined_request_dispatch(
+    standard_id: RegistryOrStandardsBodyID,
+    vendor_id: &VendorIDStruct,
+    session: Option<VendorDefinedRequestSession>,
+    vendor_defined_req_payload_struct: &VendorDefinedReqPayloadStruct,
+) -> SpdmResult<VendorDefinedRspPayloadStruct> {
+    if let Ok(registry) = VENDOR_DEFINED_REGISTRY.try_get() {
+        if registry.is_registered(standard_id, vendor_id) {
+            return registry.dispatch(
+                standard_id,
+                vendor_id,
+                session,
+                vendor_defined_req_payload_struct,
+            );
+        }
+        if VENDOR_DEFNIED.try_get().is_err() {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+    }
+    vendor_defined_request_handler(vendor_defined_req_payload_struct)
+}
@@ -34,6 +34,7 @@ impl SpdmCodec for SpdmGetVersionRequestPayload {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmVersionStruct {
     pub update: u8,
     pub version: SpdmVersion,
@@ -54,6 +55,7 @@ impl Codec for SpdmVersionStruct {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmVersionResponsePayload {
     pub version_number_entry_count: u8,
     pub versions: [SpdmVersionStruct; MAX_SPDM_VERSION_COUNT],
@@ -9,11 +9,13 @@ use codec::{enum_builder, u24, Codec, Reader, Writer};
 use core::convert::From;
 extern crate alloc;
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 pub const SHA256_DIGEST_SIZE: usize = 32;
 pub const SHA384_DIGEST_SIZE: usize = 48;
 pub const SHA512_DIGEST_SIZE: usize = 64;
+pub const SM3_256_DIGEST_SIZE: usize = 32;
 
 pub const RSASSA_2048_KEY_SIZE: usize = 256;
 pub const RSASSA_3072_KEY_SIZE: usize = 384;
@@ -24,6 +26,7 @@ pub const RSAPSS_4096_KEY_SIZE: usize = 512;
 
 pub const ECDSA_ECC_NIST_P256_KEY_SIZE: usize = 32 * 2;
 pub const ECDSA_ECC_NIST_P384_KEY_SIZE: usize = 48 * 2;
+pub const SM2_ECC_SM2_P256_KEY_SIZE: usize = 32 * 2;
 
 pub const SECP_256_R1_KEY_SIZE: usize = 32 * 2;
 pub const SECP_384_R1_KEY_SIZE: usize = 48 * 2;
@@ -31,21 +34,26 @@ pub const SECP_384_R1_KEY_SIZE: usize = 48 * 2;
 pub const AEAD_AES_128_GCM_KEY_SIZE: usize = 16;
 pub const AEAD_AES_256_GCM_KEY_SIZE: usize = 32;
 pub const AEAD_CHACHA20_POLY1305_KEY_SIZE: usize = 32;
+pub const AEAD_SM4_GCM_KEY_SIZE: usize = 16;
 
 pub const AEAD_AES_128_GCM_BLOCK_SIZE: usize = 16;
 pub const AEAD_AES_256_GCM_BLOCK_SIZE: usize = 16;
 pub const AEAD_CHACHA20_POLY1305_BLOCK_SIZE: usize = 16;
+pub const AEAD_SM4_GCM_BLOCK_SIZE: usize = 16;
 
 pub const AEAD_AES_128_GCM_IV_SIZE: usize = 12;
 pub const AEAD_AES_256_GCM_IV_SIZE: usize = 12;
 pub const AEAD_CHACHA20_POLY1305_IV_SIZE: usize = 12;
+pub const AEAD_SM4_GCM_IV_SIZE: usize = 12;
 
 pub const AEAD_AES_128_GCM_TAG_SIZE: usize = 16;
 pub const AEAD_AES_256_GCM_TAG_SIZE: usize = 16;
 pub const AEAD_CHACHA20_POLY1305_TAG_SIZE: usize = 16;
+pub const AEAD_SM4_GCM_TAG_SIZE: usize = 16;
 
 pub const SPDM_NONCE_SIZE: usize = 32;
 pub const SPDM_RANDOM_SIZE: usize = 32;
+pub const SPDM_REQ_CONTEXT_SIZE: usize = 8;
 pub const SPDM_MAX_HASH_SIZE: usize = 64;
 pub const SPDM_MAX_ASYM_KEY_SIZE: usize = 512;
 pub const SPDM_MAX_DHE_KEY_SIZE: usize = SECP_384_R1_KEY_SIZE;
@@ -178,6 +186,8 @@ bitflags! {
         const TPM_ALG_RSASSA_4096 = 0b0010_0000;
         const TPM_ALG_RSAPSS_4096 = 0b0100_0000;
         const TPM_ALG_ECDSA_ECC_NIST_P384 = 0b1000_0000;
+        // verify only, the signature is up to the device secret callbacks
+        const TPM_ALG_SM2_ECC_SM2_P256 = 0b1_0000_0000;
         const VALID_MASK = Self::TPM_ALG_RSASSA_2048.bits
             | Self::TPM_ALG_RSAPSS_2048.bits
             | Self::TPM_ALG_RSASSA_3072.bits
@@ -185,7 +195,8 @@ bitflags! {
             | Self::TPM_ALG_ECDSA_ECC_NIST_P256.bits
             | Self::TPM_ALG_RSASSA_4096.bits
             | Self::TPM_ALG_RSAPSS_4096.bits
-            | Self::TPM_ALG_ECDSA_ECC_NIST_P384.bits;
+            | Self::TPM_ALG_ECDSA_ECC_NIST_P384.bits
+            | Self::TPM_ALG_SM2_ECC_SM2_P256.bits;
     }
 }
 
@@ -200,6 +211,7 @@ impl SpdmBaseAsymAlgo {
             SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096,
             SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072,
             SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048,
+            SpdmBaseAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256,
         ];
 
         *self &= peer;
@@ -221,6 +233,7 @@ impl SpdmBaseAsymAlgo {
             SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => RSAPSS_4096_KEY_SIZE as u16,
             SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256 => ECDSA_ECC_NIST_P256_KEY_SIZE as u16,
             SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => ECDSA_ECC_NIST_P384_KEY_SIZE as u16,
+            SpdmBaseAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256 => SM2_ECC_SM2_P256_KEY_SIZE as u16,
             _ => {
                 panic!("invalid AsymAlgo");
             }
@@ -260,9 +273,11 @@ bitflags! {
         const TPM_ALG_SHA_256 = 0b0000_0001;
         const TPM_ALG_SHA_384 = 0b0000_0010;
         const TPM_ALG_SHA_512 = 0b0000_0100;
+        const TPM_ALG_SM3_256 = 0b0100_0000;
         const VALID_MASK = Self::TPM_ALG_SHA_256.bits
             | Self::TPM_ALG_SHA_384.bits
-            | Self::TPM_ALG_SHA_512.bits;
+            | Self::TPM_ALG_SHA_512.bits
+            | Self::TPM_ALG_SM3_256.bits;
     }
 }
 
@@ -272,6 +287,7 @@ impl SpdmBaseHashAlgo {
             SpdmBaseHashAlgo::TPM_ALG_SHA_512,
             SpdmBaseHashAlgo::TPM_ALG_SHA_384,
             SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+            SpdmBaseHashAlgo::TPM_ALG_SM3_256,
         ];
 
         *self &= peer;
@@ -288,6 +304,7 @@ impl SpdmBaseHashAlgo {
             SpdmBaseHashAlgo::TPM_ALG_SHA_256 => SHA256_DIGEST_SIZE as u16,
             SpdmBaseHashAlgo::TPM_ALG_SHA_384 => SHA384_DIGEST_SIZE as u16,
             SpdmBaseHashAlgo::TPM_ALG_SHA_512 => SHA512_DIGEST_SIZE as u16,
+            SpdmBaseHashAlgo::TPM_ALG_SM3_256 => SM3_256_DIGEST_SIZE as u16,
             _ => {
                 panic!("invalid HashAlgo");
             }
@@ -403,9 +420,11 @@ bitflags! {
         const AES_128_GCM = 0b0000_0001;
         const AES_256_GCM = 0b0000_0010;
         const CHACHA20_POLY1305 = 0b0000_0100;
+        const SM4_GCM = 0b0000_1000;
         const VALID_MASK = Self::AES_128_GCM.bits
             | Self::AES_256_GCM.bits
-            | Self::CHACHA20_POLY1305.bits;
+            | Self::CHACHA20_POLY1305.bits
+            | Self::SM4_GCM.bits;
     }
 }
 
@@ -415,6 +434,7 @@ impl SpdmAeadAlgo {
             SpdmAeadAlgo::AES_256_GCM,
             SpdmAeadAlgo::AES_128_GCM,
             SpdmAeadAlgo::CHACHA20_POLY1305,
+            SpdmAeadAlgo::SM4_GCM,
         ];
 
         *self &= peer;
@@ -431,6 +451,7 @@ impl SpdmAeadAlgo {
             SpdmAeadAlgo::AES_128_GCM => AEAD_AES_128_GCM_KEY_SIZE as u16,
             SpdmAeadAlgo::AES_256_GCM => AEAD_AES_256_GCM_KEY_SIZE as u16,
             SpdmAeadAlgo::CHACHA20_POLY1305 => AEAD_CHACHA20_POLY1305_KEY_SIZE as u16,
+            SpdmAeadAlgo::SM4_GCM => AEAD_SM4_GCM_KEY_SIZE as u16,
             _ => {
                 panic!("invalid AeadAlgo");
             }
@@ -441,6 +462,7 @@ impl SpdmAeadAlgo {
             SpdmAeadAlgo::AES_128_GCM => AEAD_AES_128_GCM_IV_SIZE as u16,
             SpdmAeadAlgo::AES_256_GCM => AEAD_AES_256_GCM_IV_SIZE as u16,
             SpdmAeadAlgo::CHACHA20_POLY1305 => AEAD_CHACHA20_POLY1305_IV_SIZE as u16,
+            SpdmAeadAlgo::SM4_GCM => AEAD_SM4_GCM_IV_SIZE as u16,
             _ => {
                 panic!("invalid AeadAlgo");
             }
@@ -451,6 +473,7 @@ impl SpdmAeadAlgo {
             SpdmAeadAlgo::AES_128_GCM => AEAD_AES_128_GCM_TAG_SIZE as u16,
             SpdmAeadAlgo::AES_256_GCM => AEAD_AES_256_GCM_TAG_SIZE as u16,
             SpdmAeadAlgo::CHACHA20_POLY1305 => AEAD_CHACHA20_POLY1305_TAG_SIZE as u16,
+            SpdmAeadAlgo::SM4_GCM => AEAD_SM4_GCM_TAG_SIZE as u16,
             _ => {
                 panic!("invalid AeadAlgo");
             }
@@ -495,6 +518,8 @@ bitflags! {
         const TPM_ALG_RSASSA_4096 = 0b0010_0000;
         const TPM_ALG_RSAPSS_4096 = 0b0100_0000;
         const TPM_ALG_ECDSA_ECC_NIST_P384 = 0b1000_0000;
+        // verify only, the signature is up to the device secret callbacks
+        const TPM_ALG_SM2_ECC_SM2_P256 = 0b1_0000_0000;
         const VALID_MASK = Self::TPM_ALG_RSASSA_2048.bits
             | Self::TPM_ALG_RSAPSS_2048.bits
             | Self::TPM_ALG_RSASSA_3072.bits
@@ -502,7 +527,8 @@ bitflags! {
             | Self::TPM_ALG_ECDSA_ECC_NIST_P256.bits
             | Self::TPM_ALG_RSASSA_4096.bits
             | Self::TPM_ALG_RSAPSS_4096.bits
-            | Self::TPM_ALG_ECDSA_ECC_NIST_P384.bits;
+            | Self::TPM_ALG_ECDSA_ECC_NIST_P384.bits
+            | Self::TPM_ALG_SM2_ECC_SM2_P256.bits;
     }
 }
 
@@ -517,6 +543,7 @@ impl SpdmReqAsymAlgo {
             SpdmReqAsymAlgo::TPM_ALG_RSASSA_4096,
             SpdmReqAsymAlgo::TPM_ALG_RSASSA_3072,
             SpdmReqAsymAlgo::TPM_ALG_RSASSA_2048,
+            SpdmReqAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256,
         ];
 
         *self &= peer;
@@ -538,6 +565,7 @@ impl SpdmReqAsymAlgo {
             SpdmReqAsymAlgo::TPM_ALG_RSAPSS_4096 => RSAPSS_4096_KEY_SIZE as u16,
             SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256 => ECDSA_ECC_NIST_P256_KEY_SIZE as u16,
             SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => ECDSA_ECC_NIST_P384_KEY_SIZE as u16,
+            SpdmReqAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256 => SM2_ECC_SM2_P256_KEY_SIZE as u16,
             _ => {
                 panic!("invalid ReqAsymAlgo");
             }
@@ -621,6 +649,7 @@ impl Codec for SpdmKeyScheduleAlgo {
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmUnknownAlgo {}
 impl Codec for SpdmUnknownAlgo {
     fn encode(&self, _bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
@@ -649,6 +678,7 @@ impl Default for SpdmAlgType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpdmAlg {
     SpdmAlgoDhe(SpdmDheAlgo),
     SpdmAlgoAead(SpdmAeadAlgo),
@@ -664,6 +694,7 @@ impl Default for SpdmAlg {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmAlgStruct {
     pub alg_type: SpdmAlgType,
     pub alg_supported: SpdmAlg,
@@ -733,6 +764,10 @@ impl Codec for SpdmAlgStruct {
 
 pub const SPDM_MAX_SLOT_NUMBER: usize = 8;
 
+/// SlotID selecting the provisioned public key (PUB_KEY_ID_CAP) instead of
+/// a certificate chain slot.
+pub const SPDM_PUB_KEY_SLOT_ID: u8 = 0xFF;
+
 enum_builder! {
     @U8
     EnumName: SpdmMeasurementSummaryHashType;
@@ -769,6 +804,29 @@ impl Codec for SpdmNonceStruct {
     }
 }
 
+/// RequesterContext of SPDM 1.3 CHALLENGE and GET_MEASUREMENTS,
+/// echoed back by the responder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmReqContextStruct {
+    pub data: [u8; SPDM_REQ_CONTEXT_SIZE],
+}
+
+impl Codec for SpdmReqContextStruct {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        for d in self.data.iter() {
+            d.encode(bytes)?;
+        }
+        Ok(SPDM_REQ_CONTEXT_SIZE)
+    }
+    fn read(r: &mut Reader) -> Option<SpdmReqContextStruct> {
+        let mut data = [0u8; SPDM_REQ_CONTEXT_SIZE];
+        for d in data.iter_mut() {
+            *d = u8::read(r)?;
+        }
+        Some(SpdmReqContextStruct { data })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SpdmRandomStruct {
     pub data: [u8; SPDM_RANDOM_SIZE],
@@ -996,6 +1054,9 @@ impl Codec for SpdmDmtfMeasurementStructure {
         // TBD: Check measurement_hash
 
         let value_size = u16::read(r)?;
+        if value_size as usize > config::MAX_SPDM_MEASUREMENT_VALUE_LEN {
+            return None;
+        }
         let mut value = [0u8; config::MAX_SPDM_MEASUREMENT_VALUE_LEN];
         for v in value.iter_mut().take(value_size as usize) {
             *v = u8::read(r)?;
@@ -1009,7 +1070,121 @@ impl Codec for SpdmDmtfMeasurementStructure {
     }
 }
 
+/// DMTFMeasurementValue, told apart by the representation bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmMeasurementValue<'a> {
+    Digest(&'a [u8]),
+    RawBitStream(&'a [u8]),
+}
+
+impl SpdmDmtfMeasurementStructure {
+    /// The digest or raw bit stream, None for an unknown representation.
+    pub fn get_measurement_value(&self) -> Option<SpdmMeasurementValue<'_>> {
+        let value = &self.value[..self.value_size as usize];
+        match self.representation {
+            SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementDigest => {
+                Some(SpdmMeasurementValue::Digest(value))
+            }
+            SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementRawBit => {
+                Some(SpdmMeasurementValue::RawBitStream(value))
+            }
+            SpdmDmtfMeasurementRepresentation::Unknown(_) => None,
+        }
+    }
+
+    /// DMTFMeasurementValue of the given type, None if value does not fit.
+    pub fn new(
+        r#type: SpdmDmtfMeasurementType,
+        representation: SpdmDmtfMeasurementRepresentation,
+        value: &[u8],
+    ) -> Option<Self> {
+        if value.len() > config::MAX_SPDM_MEASUREMENT_VALUE_LEN {
+            return None;
+        }
+        let mut measurement = SpdmDmtfMeasurementStructure {
+            r#type,
+            representation,
+            value_size: value.len() as u16,
+            ..Default::default()
+        };
+        measurement.value[..value.len()].copy_from_slice(value);
+        Some(measurement)
+    }
+
+    /// Immutable ROM, value is a digest or the raw bit stream.
+    pub fn immutable_rom(
+        representation: SpdmDmtfMeasurementRepresentation,
+        value: &[u8],
+    ) -> Option<Self> {
+        Self::new(
+            SpdmDmtfMeasurementType::SpdmDmtfMeasurementRom,
+            representation,
+            value,
+        )
+    }
+
+    /// Mutable firmware, value is a digest or the raw bit stream.
+    pub fn mutable_firmware(
+        representation: SpdmDmtfMeasurementRepresentation,
+        value: &[u8],
+    ) -> Option<Self> {
+        Self::new(
+            SpdmDmtfMeasurementType::SpdmDmtfMeasurementFirmware,
+            representation,
+            value,
+        )
+    }
+
+    /// Hardware configuration, such as straps, debug modes or fuses.
+    pub fn hardware_config(
+        representation: SpdmDmtfMeasurementRepresentation,
+        value: &[u8],
+    ) -> Option<Self> {
+        Self::new(
+            SpdmDmtfMeasurementType::SpdmDmtfMeasurementHardwareConfig,
+            representation,
+            value,
+        )
+    }
+
+    /// Freeform measurement manifest.
+    pub fn freeform_manifest(
+        representation: SpdmDmtfMeasurementRepresentation,
+        value: &[u8],
+    ) -> Option<Self> {
+        Self::new(
+            SpdmDmtfMeasurementType::SpdmDmtfMeasurementManifest,
+            representation,
+            value,
+        )
+    }
+
+    /// Mutable firmware version, always a raw bit stream.
+    pub fn firmware_version(version: &[u8]) -> Option<Self> {
+        Self::new(
+            SpdmDmtfMeasurementType::SpdmDmtfMeasurementMutableFirmwareVersionNumber,
+            SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementRawBit,
+            version,
+        )
+    }
+
+    /// Mutable firmware security version number, a raw 8-byte little-endian
+    /// unsigned integer.
+    pub fn secure_version_number(svn: u64) -> Self {
+        let mut measurement = SpdmDmtfMeasurementStructure {
+            r#type:
+                SpdmDmtfMeasurementType::SpdmDmtfMeasurementMutableFirmwareSecurityVersionNumber,
+            representation: SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementRawBit,
+            value_size: 8,
+            ..Default::default()
+        };
+        measurement.value[..8].copy_from_slice(&svn.to_le_bytes());
+        measurement
+    }
+}
+
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpdmMeasurementBlockStructure {
     pub index: u8,
     pub measurement_specification: SpdmMeasurementSpecification,
@@ -1030,6 +1205,9 @@ impl Codec for SpdmMeasurementBlockStructure {
         let measurement_specification = SpdmMeasurementSpecification::read(r)?;
         let measurement_size = u16::read(r)?;
         let measurement = SpdmDmtfMeasurementStructure::read(r)?;
+        if measurement_size as usize != 3 + measurement.value_size as usize {
+            return None;
+        }
         Some(SpdmMeasurementBlockStructure {
             index,
             measurement_specification,
@@ -1039,6 +1217,19 @@ impl Codec for SpdmMeasurementBlockStructure {
     }
 }
 
+impl SpdmMeasurementBlockStructure {
+    /// DMTF measurement block at index, with MeasurementSize set from the
+    /// measurement.
+    pub fn new(index: u8, measurement: SpdmDmtfMeasurementStructure) -> Self {
+        SpdmMeasurementBlockStructure {
+            index,
+            measurement_specification: SpdmMeasurementSpecification::DMTF,
+            measurement_size: 3 + measurement.value_size,
+            measurement,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SpdmMeasurementRecordStructure {
     pub number_of_blocks: u8,
@@ -1055,6 +1246,25 @@ impl Default for SpdmMeasurementRecordStructure {
     }
 }
 
+impl SpdmMeasurementRecordStructure {
+    /// The measurement blocks of the record, None if it is malformed.
+    pub fn get_measurement_blocks(&self) -> Option<Vec<SpdmMeasurementBlockStructure>> {
+        let measurement_record_length = self.measurement_record_length.get() as usize;
+        if measurement_record_length > config::MAX_SPDM_MEASUREMENT_RECORD_SIZE {
+            return None;
+        }
+        let mut reader = Reader::init(&self.measurement_record_data[..measurement_record_length]);
+        let mut blocks = Vec::with_capacity(self.number_of_blocks as usize);
+        for _ in 0..self.number_of_blocks {
+            blocks.push(SpdmMeasurementBlockStructure::read(&mut reader)?);
+        }
+        if reader.any_left() {
+            return None;
+        }
+        Some(blocks)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SpdmDheExchangeStruct {
     pub data_size: u16,
@@ -1208,6 +1418,7 @@ pub enum SpdmHkdfInputKeyingMaterial<'a> {
     SpdmDigest(&'a SpdmDigestStruct),
     SpdmMasterSecret(&'a SpdmMasterSecretStruct),
     SpdmDirectionDataSecret(&'a SpdmDirectionDataSecretStruct),
+    SpdmExportMasterSecret(&'a SpdmExportMasterSecretStruct),
 }
 
 impl AsRef<[u8]> for SpdmHkdfInputKeyingMaterial<'_> {
@@ -1220,6 +1431,7 @@ impl AsRef<[u8]> for SpdmHkdfInputKeyingMaterial<'_> {
             SpdmHkdfInputKeyingMaterial::SpdmDigest(inner) => inner.as_ref(),
             SpdmHkdfInputKeyingMaterial::SpdmMasterSecret(inner) => inner.as_ref(),
             SpdmHkdfInputKeyingMaterial::SpdmDirectionDataSecret(inner) => inner.as_ref(),
+            SpdmHkdfInputKeyingMaterial::SpdmExportMasterSecret(inner) => inner.as_ref(),
             SpdmHkdfInputKeyingMaterial::SpdmFinishedKey(inner) => inner.as_ref(),
         }
     }
@@ -1235,6 +1447,7 @@ impl SpdmHkdfInputKeyingMaterial<'_> {
             SpdmHkdfInputKeyingMaterial::SpdmDigest(inner) => inner.data_size,
             SpdmHkdfInputKeyingMaterial::SpdmMasterSecret(inner) => inner.data_size,
             SpdmHkdfInputKeyingMaterial::SpdmDirectionDataSecret(inner) => inner.data_size,
+            SpdmHkdfInputKeyingMaterial::SpdmExportMasterSecret(inner) => inner.data_size,
             SpdmHkdfInputKeyingMaterial::SpdmFinishedKey(inner) => inner.data_size,
         }
     }
@@ -1510,6 +1723,9 @@ impl SpdmHkdfPseudoRandomKey {
                 SpdmHkdfInputKeyingMaterial::SpdmDirectionDataSecret(inner) => prk.data
                     [..inner.data_size as usize]
                     .copy_from_slice(&inner.data[..inner.data_size as usize]),
+                SpdmHkdfInputKeyingMaterial::SpdmExportMasterSecret(inner) => prk.data
+                    [..inner.data_size as usize]
+                    .copy_from_slice(&inner.data[..inner.data_size as usize]),
             }
             Some(prk)
         }
@@ -1536,6 +1752,112 @@ mod tests {
         );
         assert_eq!(3, reader.left());
     }
+
+    #[test]
+    fn test_case0_spdm_dmtf_measurement_builders() {
+        let block = SpdmMeasurementBlockStructure::new(
+            1,
+            SpdmDmtfMeasurementStructure::immutable_rom(
+                SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementDigest,
+                &[0x5a; 48],
+            )
+            .unwrap(),
+        );
+        let u8_slice = &mut [0u8; 64];
+        let mut writer = Writer::init(u8_slice);
+        assert_eq!(block.encode(&mut writer), Ok(55));
+        assert_eq!(u8_slice[..7], [1, 1, 51, 0, 0x00, 48, 0]);
+        assert_eq!(u8_slice[7..55], [0x5a; 48]);
+
+        let block = SpdmMeasurementBlockStructure::new(
+            7,
+            SpdmDmtfMeasurementStructure::secure_version_number(0x0102),
+        );
+        let u8_slice = &mut [0u8; 16];
+        let mut writer = Writer::init(u8_slice);
+        assert_eq!(block.encode(&mut writer), Ok(15));
+        assert_eq!(
+            u8_slice[..15],
+            [7, 1, 11, 0, 0x87, 8, 0, 0x02, 0x01, 0, 0, 0, 0, 0, 0]
+        );
+
+        let mut reader = Reader::init(u8_slice);
+        let block = SpdmMeasurementBlockStructure::read(&mut reader).unwrap();
+        assert_eq!(
+            block.measurement.r#type,
+            SpdmDmtfMeasurementType::SpdmDmtfMeasurementMutableFirmwareSecurityVersionNumber
+        );
+        assert_eq!(block.measurement.value_size, 8);
+
+        let measurement = SpdmDmtfMeasurementStructure::firmware_version(b"1.2.3").unwrap();
+        assert_eq!(measurement.representation.get_u8(), 0x80);
+        assert_eq!(measurement.r#type.get_u8(), 0x06);
+        assert_eq!(&measurement.value[..5], b"1.2.3");
+
+        assert!(SpdmDmtfMeasurementStructure::freeform_manifest(
+            SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementRawBit,
+            &[0u8; config::MAX_SPDM_MEASUREMENT_VALUE_LEN + 1],
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_case0_spdm_measurement_record_blocks() {
+        let mut record = SpdmMeasurementRecordStructure::default();
+        let mut writer = Writer::init(&mut record.measurement_record_data);
+        let blocks = [
+            SpdmMeasurementBlockStructure::new(
+                1,
+                SpdmDmtfMeasurementStructure::mutable_firmware(
+                    SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementDigest,
+                    &[1; SHA384_DIGEST_SIZE],
+                )
+                .unwrap(),
+            ),
+            SpdmMeasurementBlockStructure::new(
+                2,
+                SpdmDmtfMeasurementStructure::freeform_manifest(
+                    SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementRawBit,
+                    &[2; config::MAX_SPDM_MEASUREMENT_VALUE_LEN],
+                )
+                .unwrap(),
+            ),
+        ];
+        for block in blocks.iter() {
+            assert!(block.encode(&mut writer).is_ok());
+        }
+        let used = writer.used();
+        record.number_of_blocks = 2;
+        record.measurement_record_length = u24::new(used as u32);
+
+        let blocks = record.get_measurement_blocks().unwrap();
+        assert_eq!(
+            blocks[0].measurement.get_measurement_value(),
+            Some(SpdmMeasurementValue::Digest(&[1; SHA384_DIGEST_SIZE]))
+        );
+        assert_eq!(
+            blocks[1].measurement.get_measurement_value(),
+            Some(SpdmMeasurementValue::RawBitStream(
+                &[2; config::MAX_SPDM_MEASUREMENT_VALUE_LEN]
+            ))
+        );
+
+        record.number_of_blocks = 3;
+        assert!(record.get_measurement_blocks().is_none());
+        record.number_of_blocks = 1;
+        assert!(record.get_measurement_blocks().is_none());
+
+        // a value larger than MAX_SPDM_MEASUREMENT_VALUE_LEN is refused
+        let value_size = config::MAX_SPDM_MEASUREMENT_VALUE_LEN as u16 + 1;
+        let mut buffer = [0u8; 7];
+        buffer[..4].copy_from_slice(&[3, 1, 0, 0]);
+        buffer[2..4].copy_from_slice(&(value_size + 3).to_le_bytes());
+        buffer[4] = 0x84;
+        buffer[5..7].copy_from_slice(&value_size.to_le_bytes());
+        let mut reader = Reader::init(&buffer);
+        assert!(SpdmMeasurementBlockStructure::read(&mut reader).is_none());
+    }
+
     #[test]
     fn test_case0_spdm_measurement_hash_algo() {
         let u8_slice = &mut [0u8; 4];
@@ -1783,6 +2105,9 @@ mod tests {
         value = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
         assert_eq!(value.get_size(), ECDSA_ECC_NIST_P384_KEY_SIZE as u16);
 
+        value = SpdmBaseAsymAlgo::TPM_ALG_SM2_ECC_SM2_P256;
+        assert_eq!(value.get_size(), SM2_ECC_SM2_P256_KEY_SIZE as u16);
+
         value = SpdmBaseAsymAlgo::empty();
         value.get_size();
     }
@@ -1810,6 +2135,9 @@ mod tests {
         value = SpdmAeadAlgo::CHACHA20_POLY1305;
         assert_eq!(value.get_key_size(), AEAD_CHACHA20_POLY1305_KEY_SIZE as u16);
 
+        value = SpdmAeadAlgo::SM4_GCM;
+        assert_eq!(value.get_key_size(), AEAD_SM4_GCM_KEY_SIZE as u16);
+
         value = SpdmAeadAlgo::empty();
         value.get_key_size();
     }
@@ -1825,6 +2153,9 @@ mod tests {
         value = SpdmAeadAlgo::CHACHA20_POLY1305;
         assert_eq!(value.get_key_size(), AEAD_CHACHA20_POLY1305_KEY_SIZE as u16);
 
+        value = SpdmAeadAlgo::SM4_GCM;
+        assert_eq!(value.get_key_size(), AEAD_SM4_GCM_KEY_SIZE as u16);
+
         value = SpdmAeadAlgo::empty();
         value.get_key_size();
     }
@@ -1840,6 +2171,9 @@ mod tests {
         value = SpdmAeadAlgo::CHACHA20_POLY1305;
         assert_eq!(value.get_iv_size(), AEAD_CHACHA20_POLY1305_IV_SIZE as u16);
 
+        value = SpdmAeadAlgo::SM4_GCM;
+        assert_eq!(value.get_iv_size(), AEAD_SM4_GCM_IV_SIZE as u16);
+
         value = SpdmAeadAlgo::empty();
         value.get_iv_size();
     }
@@ -1855,6 +2189,9 @@ mod tests {
         value = SpdmAeadAlgo::CHACHA20_POLY1305;
         assert_eq!(value.get_tag_size(), AEAD_CHACHA20_POLY1305_TAG_SIZE as u16);
 
+        value = SpdmAeadAlgo::SM4_GCM;
+        assert_eq!(value.get_tag_size(), AEAD_SM4_GCM_TAG_SIZE as u16);
+
         value = SpdmAeadAlgo::empty();
         value.get_tag_size();
     }
@@ -0,0 +1,51 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use codec::{enum_builder, Codec, Reader, Writer};
+
+enum_builder! {
+    @U8
+    EnumName: SpdmCertificateModelType;
+    EnumVal{
+        SpdmCertModelTypeNone => 0x0,
+        SpdmCertModelTypeDeviceCert => 0x1,
+        SpdmCertModelTypeAliasCert => 0x2,
+        SpdmCertModelTypeGenericCert => 0x3
+    }
+}
+impl Default for SpdmCertificateModelType {
+    fn default() -> SpdmCertificateModelType {
+        SpdmCertificateModelType::SpdmCertModelTypeNone
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct SpdmKeyUsageMask: u16 {
+        const KEY_EX_USE = 0b0000_0001;
+        const CHALLENGE_USE = 0b0000_0010;
+        const MEASUREMENT_USE = 0b0000_0100;
+        const ENDPOINT_INFO_USE = 0b0000_1000;
+        const STANDARDS_KEY_USE = 0b0100_0000_0000_0000;
+        const VENDOR_KEY_USE = 0b1000_0000_0000_0000;
+        const VALID_MASK = Self::KEY_EX_USE.bits
+            | Self::CHALLENGE_USE.bits
+            | Self::MEASUREMENT_USE.bits
+            | Self::ENDPOINT_INFO_USE.bits
+            | Self::STANDARDS_KEY_USE.bits
+            | Self::VENDOR_KEY_USE.bits;
+    }
+}
+
+impl Codec for SpdmKeyUsageMask {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        self.bits().encode(bytes)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmKeyUsageMask> {
+        let bits = u16::read(r)?;
+
+        SpdmKeyUsageMask::from_bits(bits & SpdmKeyUsageMask::VALID_MASK.bits)
+    }
+}
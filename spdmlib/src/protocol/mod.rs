@@ -9,9 +9,13 @@ use core::convert::TryInto;
 
 mod algo;
 mod capability;
+mod certificate;
+#[cfg(feature = "serde")]
+mod serialize;
 mod version;
 pub use algo::*;
 pub use capability::*;
+pub use certificate::*;
 pub use version::*;
 
 // util function
@@ -0,0 +1,325 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! serde support of the protocol types, with the serde feature, so that
+//! attestation results can be persisted or sent as JSON, CBOR...
+//!
+//! Bitflags are serialized as their bits and enums as their wire value. The
+//! fixed size buffers only serialize their data_size first bytes, as a byte
+//! string. The other structs derive the traits where they are declared.
+
+use super::*;
+use crate::common::{
+    SpdmMeasurementContentChanged, SpdmOpaqueStruct, SpdmOpaqueSupport, MAX_SPDM_OPAQUE_SIZE,
+};
+use crate::config;
+use alloc::vec::Vec;
+use codec::{u24, Codec, Reader};
+use core::fmt;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+struct BytesVisitor {
+    max_size: usize,
+}
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "at most {} bytes", self.max_size)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        if v.len() > self.max_size {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        Ok(v.to_vec())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element::<u8>()? {
+            if bytes.len() == self.max_size {
+                return Err(de::Error::invalid_length(bytes.len() + 1, &self));
+            }
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    max_size: usize,
+) -> Result<Vec<u8>, D::Error> {
+    deserializer.deserialize_bytes(BytesVisitor { max_size })
+}
+
+/// Byte string field of the serialized form of a struct.
+struct ByteBuf(Vec<u8>);
+
+impl Serialize for ByteBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_bytes(deserializer, usize::MAX).map(ByteBuf)
+    }
+}
+
+macro_rules! serde_bitflags {
+    ($name:ident: $bits:ty) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.bits().serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bits = <$bits>::deserialize(deserializer)?;
+                $name::from_bits(bits).ok_or_else(|| de::Error::custom("invalid flags"))
+            }
+        }
+    };
+}
+
+macro_rules! serde_enum {
+    ($name:ident) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.get_u8().serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = u8::deserialize(deserializer)?;
+                $name::read(&mut Reader::init(&[value]))
+                    .ok_or_else(|| de::Error::custom("invalid value"))
+            }
+        }
+    };
+}
+
+/// data_size and data, data may be boxed.
+macro_rules! serde_sized_bytes {
+    ($name:ident, $size:expr) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.data[..self.data_size as usize])
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = deserialize_bytes(deserializer, $size)?;
+                let mut value = $name::default();
+                value.data[..bytes.len()].copy_from_slice(&bytes);
+                value.data_size = bytes.len() as u16;
+                Ok(value)
+            }
+        }
+    };
+}
+
+macro_rules! serde_fixed_bytes {
+    ($name:ident, $size:expr) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.data)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = deserialize_bytes(deserializer, $size)?;
+                if bytes.len() != $size {
+                    return Err(de::Error::invalid_length(
+                        bytes.len(),
+                        &"exactly sized bytes",
+                    ));
+                }
+                let mut value = $name::default();
+                value.data.copy_from_slice(&bytes);
+                Ok(value)
+            }
+        }
+    };
+}
+
+serde_bitflags!(SpdmMeasurementSpecification: u8);
+serde_bitflags!(SpdmMeasurementHashAlgo: u32);
+serde_bitflags!(SpdmBaseAsymAlgo: u32);
+serde_bitflags!(SpdmBaseHashAlgo: u32);
+serde_bitflags!(SpdmDheAlgo: u16);
+serde_bitflags!(SpdmAeadAlgo: u16);
+serde_bitflags!(SpdmReqAsymAlgo: u16);
+serde_bitflags!(SpdmKeyScheduleAlgo: u16);
+serde_bitflags!(SpdmRequestCapabilityFlags: u32);
+serde_bitflags!(SpdmResponseCapabilityFlags: u32);
+serde_bitflags!(SpdmKeyUsageMask: u16);
+serde_bitflags!(SpdmOpaqueSupport: u8);
+serde_bitflags!(SpdmMeasurementContentChanged: u8);
+
+serde_enum!(SpdmVersion);
+serde_enum!(SpdmStandardId);
+serde_enum!(SpdmAlgType);
+serde_enum!(SpdmMeasurementSummaryHashType);
+serde_enum!(SpdmDmtfMeasurementType);
+serde_enum!(SpdmDmtfMeasurementRepresentation);
+serde_enum!(SpdmCertificateModelType);
+
+serde_fixed_bytes!(SpdmNonceStruct, SPDM_NONCE_SIZE);
+serde_fixed_bytes!(SpdmRandomStruct, SPDM_RANDOM_SIZE);
+serde_fixed_bytes!(SpdmReqContextStruct, SPDM_REQ_CONTEXT_SIZE);
+
+serde_sized_bytes!(SpdmDigestStruct, SPDM_MAX_HASH_SIZE);
+serde_sized_bytes!(SpdmSignatureStruct, SPDM_MAX_ASYM_KEY_SIZE);
+serde_sized_bytes!(SpdmCertChainData, config::MAX_SPDM_CERT_CHAIN_DATA_SIZE);
+serde_sized_bytes!(
+    SpdmCertChainBuffer,
+    4 + SPDM_MAX_HASH_SIZE + config::MAX_SPDM_CERT_CHAIN_DATA_SIZE
+);
+serde_sized_bytes!(SpdmDheExchangeStruct, SPDM_MAX_DHE_KEY_SIZE);
+serde_sized_bytes!(SpdmPskContextStruct, config::MAX_SPDM_PSK_CONTEXT_SIZE);
+serde_sized_bytes!(SpdmPskHintStruct, config::MAX_SPDM_PSK_HINT_SIZE);
+serde_sized_bytes!(SpdmOpaqueStruct, MAX_SPDM_OPAQUE_SIZE);
+
+#[derive(Serialize, Deserialize)]
+struct DmtfMeasurement {
+    r#type: SpdmDmtfMeasurementType,
+    representation: SpdmDmtfMeasurementRepresentation,
+    value: ByteBuf,
+}
+
+impl Serialize for SpdmDmtfMeasurementStructure {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DmtfMeasurement {
+            r#type: self.r#type,
+            representation: self.representation,
+            value: ByteBuf(self.value[..self.value_size as usize].to_vec()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpdmDmtfMeasurementStructure {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let measurement = DmtfMeasurement::deserialize(deserializer)?;
+        let value_size = measurement.value.0.len();
+        if value_size > config::MAX_SPDM_MEASUREMENT_VALUE_LEN {
+            return Err(de::Error::invalid_length(
+                value_size,
+                &"a measurement value",
+            ));
+        }
+        let mut value = SpdmDmtfMeasurementStructure {
+            r#type: measurement.r#type,
+            representation: measurement.representation,
+            value_size: value_size as u16,
+            ..Default::default()
+        };
+        value.value[..value_size].copy_from_slice(&measurement.value.0);
+        Ok(value)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct MeasurementRecord {
+    number_of_blocks: u8,
+    measurement_record_data: ByteBuf,
+}
+
+impl Serialize for SpdmMeasurementRecordStructure {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let length = self.measurement_record_length.get() as usize;
+        MeasurementRecord {
+            number_of_blocks: self.number_of_blocks,
+            measurement_record_data: ByteBuf(self.measurement_record_data[..length].to_vec()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpdmMeasurementRecordStructure {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = MeasurementRecord::deserialize(deserializer)?;
+        let length = record.measurement_record_data.0.len();
+        if length > config::MAX_SPDM_MEASUREMENT_RECORD_SIZE {
+            return Err(de::Error::invalid_length(length, &"a measurement record"));
+        }
+        let mut value = SpdmMeasurementRecordStructure {
+            number_of_blocks: record.number_of_blocks,
+            measurement_record_length: u24::new(length as u32),
+            ..Default::default()
+        };
+        value.measurement_record_data[..length].copy_from_slice(&record.measurement_record_data.0);
+        Ok(value)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        let json = serde_json::to_vec(value).unwrap();
+        serde_json::from_slice(&json).unwrap()
+    }
+
+    #[test]
+    fn test_case0_serde_algorithms() {
+        let hash = round_trip(&SpdmBaseHashAlgo::TPM_ALG_SHA_384);
+        assert_eq!(hash, SpdmBaseHashAlgo::TPM_ALG_SHA_384);
+        let version = round_trip(&SpdmVersion::SpdmVersion12);
+        assert_eq!(version, SpdmVersion::SpdmVersion12);
+        let alg = round_trip(&SpdmAlgStruct {
+            alg_type: SpdmAlgType::SpdmAlgTypeDHE,
+            alg_supported: SpdmAlg::SpdmAlgoDhe(SpdmDheAlgo::SECP_384_R1),
+        });
+        assert_eq!(alg.alg_type, SpdmAlgType::SpdmAlgTypeDHE);
+        assert_eq!(
+            alg.alg_supported,
+            SpdmAlg::SpdmAlgoDhe(SpdmDheAlgo::SECP_384_R1)
+        );
+    }
+
+    #[test]
+    fn test_case0_serde_buffers() {
+        let cert_chain = SpdmCertChainBuffer::new(&[0x30, 0x82, 0x01], &[0xAA; 48]).unwrap();
+        let value = round_trip(&cert_chain);
+        assert_eq!(value.as_ref(), cert_chain.as_ref());
+
+        let nonce = SpdmNonceStruct { data: [0x5A; 32] };
+        assert_eq!(round_trip(&nonce).data, nonce.data);
+
+        let mut measurement = SpdmDmtfMeasurementStructure {
+            r#type: SpdmDmtfMeasurementType::SpdmDmtfMeasurementFirmware,
+            value_size: 3,
+            ..Default::default()
+        };
+        measurement.value[..3].copy_from_slice(&[1, 2, 3]);
+        let value = round_trip(&measurement);
+        assert_eq!(value.r#type, measurement.r#type);
+        assert_eq!(value.value_size, 3);
+        assert_eq!(value.value[..3], [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_case1_serde_buffers() {
+        let json = serde_json::to_vec(&ByteBuf(alloc::vec![0u8; SPDM_MAX_HASH_SIZE + 1])).unwrap();
+        assert!(serde_json::from_slice::<SpdmDigestStruct>(&json).is_err());
+
+        let json = serde_json::to_vec(&ByteBuf(alloc::vec![0u8; SPDM_NONCE_SIZE - 1])).unwrap();
+        assert!(serde_json::from_slice::<SpdmNonceStruct>(&json).is_err());
+
+        assert!(serde_json::from_str::<SpdmBaseHashAlgo>("3").is_err());
+    }
+}
@@ -11,7 +11,8 @@ enum_builder! {
     EnumVal{
         SpdmVersion10 => 0x10,
         SpdmVersion11 => 0x11,
-        SpdmVersion12 => 0x12
+        SpdmVersion12 => 0x12,
+        SpdmVersion13 => 0x13
     }
 }
 impl Default for SpdmVersion {
@@ -20,7 +21,17 @@ impl Default for SpdmVersion {
     }
 }
 
-pub const MAX_SPDM_VERSION_COUNT: usize = 3;
+impl SpdmVersion {
+    /// Signing prefix context of SPDM 1.2 and later signatures.
+    pub fn get_signing_prefix_context(&self) -> &'static [u8] {
+        match self {
+            SpdmVersion::SpdmVersion13 => &SPDM_VERSION_1_3_SIGNING_PREFIX_CONTEXT,
+            _ => &SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT,
+        }
+    }
+}
+
+pub const MAX_SPDM_VERSION_COUNT: usize = 4;
 
 //SPDM V1.2 signing prefix context
 pub const SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT: [u8; 64] = [
@@ -30,6 +41,14 @@ pub const SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT: [u8; 64] = [
     0x64, 0x6d, 0x74, 0x66, 0x2d, 0x73, 0x70, 0x64, 0x6d, 0x2d, 0x76, 0x31, 0x2e, 0x32, 0x2e, 0x2a,
 ];
 //"dmtf-spdm-v1.2.*dmtf-spdm-v1.2.*dmtf-spdm-v1.2.*dmtf-spdm-v1.2.*"
+//SPDM V1.3 signing prefix context
+pub const SPDM_VERSION_1_3_SIGNING_PREFIX_CONTEXT: [u8; 64] = [
+    0x64, 0x6d, 0x74, 0x66, 0x2d, 0x73, 0x70, 0x64, 0x6d, 0x2d, 0x76, 0x31, 0x2e, 0x33, 0x2e, 0x2a,
+    0x64, 0x6d, 0x74, 0x66, 0x2d, 0x73, 0x70, 0x64, 0x6d, 0x2d, 0x76, 0x31, 0x2e, 0x33, 0x2e, 0x2a,
+    0x64, 0x6d, 0x74, 0x66, 0x2d, 0x73, 0x70, 0x64, 0x6d, 0x2d, 0x76, 0x31, 0x2e, 0x33, 0x2e, 0x2a,
+    0x64, 0x6d, 0x74, 0x66, 0x2d, 0x73, 0x70, 0x64, 0x6d, 0x2d, 0x76, 0x31, 0x2e, 0x33, 0x2e, 0x2a,
+];
+//"dmtf-spdm-v1.3.*dmtf-spdm-v1.3.*dmtf-spdm-v1.3.*dmtf-spdm-v1.3.*"
 pub const SPDM_CHALLENGE_AUTH_SIGN_CONTEXT: [u8; 32] = [
     0x72, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x64, 0x65, 0x72, 0x2d, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x65,
     0x6e, 0x67, 0x65, 0x5f, 0x61, 0x75, 0x74, 0x68, 0x20, 0x73, 0x69, 0x67, 0x6e, 0x69, 0x6e, 0x67,
@@ -66,6 +85,12 @@ pub const SPDM_FINISH_SIGN_CONTEXT: [u8; 24] = [
     0x20, 0x73, 0x69, 0x67, 0x6e, 0x69, 0x6e, 0x67,
 ];
 // "requester-finish signing"
+pub const SPDM_ATTESTATION_REPORT_SIGN_CONTEXT: [u8; 36] = [
+    0x72, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x64, 0x65, 0x72, 0x2d, 0x61, 0x74, 0x74, 0x65, 0x73, 0x74,
+    0x61, 0x74, 0x69, 0x6f, 0x6e, 0x5f, 0x72, 0x65, 0x70, 0x6f, 0x72, 0x74, 0x20, 0x73, 0x69, 0x67,
+    0x6e, 0x69, 0x6e, 0x67,
+];
+// "responder-attestation_report signing"
 pub const SPDM_VERSION_1_2_SIGNING_CONTEXT_SIZE: usize = 100;
 pub const SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_2: [u8; 2] = [0x0, 0x0];
 pub const SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_4: [u8; 4] = [0x0, 0x0, 0x0, 0x0];
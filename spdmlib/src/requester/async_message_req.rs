@@ -0,0 +1,124 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::{SpdmDeviceIoEvent, SpdmMessageDirection, ST1};
+use crate::error::{
+    SpdmResult, SPDM_STATUS_DECAP_FAIL, SPDM_STATUS_RECEIVE_FAIL, SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use crate::message::SpdmRequestResponseCode;
+use crate::requester::*;
+
+use conquer_once::spin::OnceCell;
+
+/// Handler of messages the responder delivers without a request,
+/// e.g. over the PCI DOE async message mechanism.
+#[derive(Clone, Copy)]
+pub struct SpdmAsyncMessageHandler {
+    pub async_message_cb: fn(session_id: Option<u32>, message: &[u8]) -> SpdmResult,
+}
+
+static ASYNC_MESSAGE_HANDLER: OnceCell<SpdmAsyncMessageHandler> = OnceCell::uninit();
+
+pub fn register_async_message_handler(context: SpdmAsyncMessageHandler) -> bool {
+    ASYNC_MESSAGE_HANDLER.try_init_once(|| context).is_ok()
+}
+
+pub fn async_message_handler(session_id: Option<u32>, message: &[u8]) -> SpdmResult {
+    match ASYNC_MESSAGE_HANDLER.try_get() {
+        Ok(handler) => (handler.async_message_cb)(session_id, message),
+        Err(_) => Err(SPDM_STATUS_UNSUPPORTED_CAP),
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Poll the transport event and return it. The connection is torn down
+    /// on PeerDisconnected before returning, so the event is handled even if
+    /// the caller only looks for AsyncMessagePending.
+    pub fn poll_device_io_event(&mut self) -> SpdmDeviceIoEvent {
+        let event = self.common.device_io.poll_event();
+        if event == SpdmDeviceIoEvent::PeerDisconnected {
+            self.common.handle_peer_disconnect();
+        }
+        event
+    }
+
+    /// Receive the pending async message into receive_buffer.
+    /// Return the session id if the message is secured and the message size,
+    /// or None if the transport reports no pending message.
+    /// Return SPDM_STATUS_RECEIVE_FAIL if the peer is disconnected.
+    pub fn receive_async_message(
+        &mut self,
+        receive_buffer: &mut [u8],
+    ) -> SpdmResult<Option<(Option<u32>, usize)>> {
+        match self.poll_device_io_event() {
+            SpdmDeviceIoEvent::AsyncMessagePending => {}
+            SpdmDeviceIoEvent::PeerDisconnected => return Err(SPDM_STATUS_RECEIVE_FAIL),
+            SpdmDeviceIoEvent::None => return Ok(None),
+        }
+        info!("receive_async_message!\n");
+
+        let mut transport_buffer = self.common.alloc_receiver_buffer();
+        let used = self
+            .common
+            .device_io
+            .receive(&mut transport_buffer, ST1)
+            .map_err(|_| SPDM_STATUS_RECEIVE_FAIL)?;
+
+        let mut encoded_buffer = self.common.alloc_receiver_buffer();
+        let (encoded_size, secured_message) = self
+            .common
+            .transport_encap
+            .decap(&transport_buffer[..used], &mut encoded_buffer)?;
+
+        if !secured_message {
+            if receive_buffer.len() < encoded_size {
+                return Err(SPDM_STATUS_DECAP_FAIL);
+            }
+            receive_buffer[..encoded_size].copy_from_slice(&encoded_buffer[..encoded_size]);
+            self.common.capture_message(
+                SpdmMessageDirection::Received,
+                None,
+                &receive_buffer[..encoded_size],
+            );
+            self.common.free_buffer(encoded_buffer);
+            self.common.free_buffer(transport_buffer);
+            return Ok(Some((None, encoded_size)));
+        }
+
+        let session_id =
+            u32::read_bytes(&encoded_buffer[..encoded_size]).ok_or(SPDM_STATUS_DECAP_FAIL)?;
+        self.common.free_buffer(encoded_buffer);
+        let used = self.common.decode_secured_message(
+            session_id,
+            &transport_buffer[..used],
+            receive_buffer,
+        )?;
+        self.common.free_buffer(transport_buffer);
+        Ok(Some((Some(session_id), used)))
+    }
+
+    /// Hand the pending async message, if any, to the registered handler.
+    /// SEND_EVENT in a session goes to the registered event handler.
+    /// Return true if a message is processed.
+    pub fn process_async_message(&mut self) -> SpdmResult<bool> {
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let result = match self.receive_async_message(&mut receive_buffer)? {
+            Some((Some(session_id), used))
+                if used >= 2
+                    && receive_buffer[1]
+                        == SpdmRequestResponseCode::SpdmRequestSendEvent.get_u8() =>
+            {
+                self.handle_spdm_send_event(session_id, &receive_buffer[..used])?;
+                Ok(true)
+            }
+            Some((session_id, used)) => {
+                async_message_handler(session_id, &receive_buffer[..used])?;
+                Ok(true)
+            }
+            None => Ok(false),
+        };
+        self.common.free_buffer(receive_buffer);
+        result
+    }
+}
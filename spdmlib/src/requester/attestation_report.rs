@@ -0,0 +1,61 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::attestation_report::SpdmAttestationReport;
+use crate::error::{
+    SpdmResult, SPDM_STATUS_INVALID_MSG_FIELD, SPDM_STATUS_INVALID_MSG_SIZE,
+    SPDM_STATUS_INVALID_PARAMETER,
+};
+use crate::message::SpdmRequestResponseCode;
+use crate::protocol::*;
+use crate::requester::*;
+
+impl<'a> RequesterContext<'a> {
+    /// Verify a report from ResponderContext::generate_attestation_report
+    /// against the nonce sent to the responder and the certificate chain
+    /// of the report slot, retrieved earlier.
+    pub fn verify_attestation_report<'r>(
+        &self,
+        report_buffer: &'r [u8],
+        nonce: &SpdmNonceStruct,
+    ) -> SpdmResult<SpdmAttestationReport<'r>> {
+        let (report, used) =
+            SpdmAttestationReport::read_body(report_buffer).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        if report.nonce.data != nonce.data {
+            error!("attestation report nonce mismatch!\n");
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+
+        let signature_size = self.common.negotiate_info.base_asym_sel.get_size() as usize;
+        if report_buffer.len() != used + signature_size || signature_size > SPDM_MAX_ASYM_KEY_SIZE {
+            return Err(SPDM_STATUS_INVALID_MSG_SIZE);
+        }
+        let mut signature = SpdmSignatureStruct {
+            data_size: signature_size as u16,
+            data: [0u8; SPDM_MAX_ASYM_KEY_SIZE],
+        };
+        signature.data[..signature_size].copy_from_slice(&report_buffer[used..]);
+
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(report.slot_id)
+            .ok_or_else(|| {
+                error!("peer_cert_chain is not populated!\n");
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
+        let (report_hash, message_sign) = self
+            .common
+            .attestation_report_sign_data(&report_buffer[..used])?;
+
+        self.verify_peer_signature(
+            SpdmRequestResponseCode::SpdmResponseVendorDefinedResponse,
+            report.slot_id,
+            &report_hash,
+            cert_chain_data,
+            message_sign.as_ref(),
+            &signature,
+        )?;
+        Ok(report)
+    }
+}
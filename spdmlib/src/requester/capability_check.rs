@@ -0,0 +1,108 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::SpdmConnectionState;
+use crate::error::{SpdmResult, SPDM_STATUS_PEER_CAP_VIOLATION};
+use crate::message::*;
+use crate::protocol::SpdmResponseCapabilityFlags;
+use crate::requester::*;
+
+/// Responder capabilities, any of which allows the responder to send
+/// the response code. None if the response needs no capability.
+pub fn required_response_capabilities(
+    response_code: SpdmRequestResponseCode,
+) -> Option<SpdmResponseCapabilityFlags> {
+    match response_code {
+        SpdmRequestResponseCode::SpdmResponseDigests
+        | SpdmRequestResponseCode::SpdmResponseCertificate => {
+            Some(SpdmResponseCapabilityFlags::CERT_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseChallengeAuth => {
+            Some(SpdmResponseCapabilityFlags::CHAL_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseMeasurements => Some(
+            SpdmResponseCapabilityFlags::MEAS_CAP_NO_SIG
+                | SpdmResponseCapabilityFlags::MEAS_CAP_SIG,
+        ),
+        SpdmRequestResponseCode::SpdmResponseKeyExchangeRsp
+        | SpdmRequestResponseCode::SpdmResponseFinishRsp => {
+            Some(SpdmResponseCapabilityFlags::KEY_EX_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponsePskExchangeRsp
+        | SpdmRequestResponseCode::SpdmResponsePskFinishRsp => Some(
+            SpdmResponseCapabilityFlags::PSK_CAP_WITHOUT_CONTEXT
+                | SpdmResponseCapabilityFlags::PSK_CAP_WITH_CONTEXT,
+        ),
+        SpdmRequestResponseCode::SpdmResponseEndSessionAck => Some(
+            SpdmResponseCapabilityFlags::KEY_EX_CAP
+                | SpdmResponseCapabilityFlags::PSK_CAP_WITHOUT_CONTEXT
+                | SpdmResponseCapabilityFlags::PSK_CAP_WITH_CONTEXT,
+        ),
+        SpdmRequestResponseCode::SpdmResponseHeartbeatAck => {
+            Some(SpdmResponseCapabilityFlags::HBEAT_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseKeyUpdateAck => {
+            Some(SpdmResponseCapabilityFlags::KEY_UPD_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseEncapsulatedRequest
+        | SpdmRequestResponseCode::SpdmResponseEncapsulatedResponseAck => {
+            Some(SpdmResponseCapabilityFlags::ENCAP_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseChunkSendAck
+        | SpdmRequestResponseCode::SpdmResponseChunkResponse => {
+            Some(SpdmResponseCapabilityFlags::CHUNK_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseCsr => Some(SpdmResponseCapabilityFlags::CSR_CAP),
+        SpdmRequestResponseCode::SpdmResponseSetCertificateRsp => {
+            Some(SpdmResponseCapabilityFlags::SET_CERT_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseSupportedEventTypes
+        | SpdmRequestResponseCode::SpdmResponseSubscribeEventTypesAck
+        | SpdmRequestResponseCode::SpdmResponseEventAck => {
+            Some(SpdmResponseCapabilityFlags::EVENT_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseKeyPairInfo => {
+            Some(SpdmResponseCapabilityFlags::GET_KEY_PAIR_INFO_CAP)
+        }
+        SpdmRequestResponseCode::SpdmResponseSetKeyPairInfoAck => {
+            Some(SpdmResponseCapabilityFlags::SET_KEY_PAIR_INFO_CAP)
+        }
+        _ => None,
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Reject a response the responder is not allowed to send per its
+    /// CAPABILITIES, when config_info.enforce_peer_capabilities is set.
+    pub(crate) fn check_response_capabilities(&self, receive_buffer: &[u8]) -> SpdmResult {
+        if !self.common.config_info.enforce_peer_capabilities
+            || self.common.runtime_info.get_connection_state().get_u8()
+                < SpdmConnectionState::SpdmConnectionAfterCapabilities.get_u8()
+        {
+            return Ok(());
+        }
+
+        let mut reader = Reader::init(receive_buffer);
+        let response_code = match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => message_header.request_response_code,
+            None => return Ok(()),
+        };
+        match required_response_capabilities(response_code) {
+            Some(caps)
+                if !self
+                    .common
+                    .negotiate_info
+                    .rsp_capabilities_sel
+                    .intersects(caps) =>
+            {
+                error!(
+                    "!!! responder sent {:?} without capability {:?} !!!\n",
+                    response_code, caps
+                );
+                Err(SPDM_STATUS_PEER_CAP_VIOLATION)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::protocol::{SpdmBaseHashAlgo, SPDM_MAX_HASH_SIZE};
+
+use conquer_once::spin::OnceCell;
+
+pub const MAX_SPDM_CERT_VERIFY_CACHE_ENTRIES: usize = 4;
+
+/// Wall clock used to re-check the validity period of cached cert chains.
+#[derive(Clone, Copy)]
+pub struct SpdmWallClock {
+    /// Seconds since unix epoch, None if the time is unknown.
+    pub get_time_cb: fn() -> Option<u64>,
+}
+
+static WALL_CLOCK: OnceCell<SpdmWallClock> = OnceCell::uninit();
+
+pub fn register_wall_clock(context: SpdmWallClock) -> bool {
+    WALL_CLOCK.try_init_once(|| context).is_ok()
+}
+
+/// The registered wall clock, or the system time with std.
+pub fn get_wall_clock_time() -> Option<u64> {
+    if let Ok(clock) = WALL_CLOCK.try_get() {
+        return (clock.get_time_cb)();
+    }
+    #[cfg(feature = "std")]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs())
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SpdmCertVerifyCacheEntry {
+    in_use: bool,
+    base_hash_algo: SpdmBaseHashAlgo,
+    chain_hash_size: u16,
+    chain_hash: [u8; SPDM_MAX_HASH_SIZE],
+    not_before: u64,
+    not_after: u64,
+    last_used: u64,
+}
+
+impl Default for SpdmCertVerifyCacheEntry {
+    fn default() -> Self {
+        SpdmCertVerifyCacheEntry {
+            in_use: false,
+            base_hash_algo: SpdmBaseHashAlgo::default(),
+            chain_hash_size: 0,
+            chain_hash: [0u8; SPDM_MAX_HASH_SIZE],
+            not_before: 0,
+            not_after: 0,
+            last_used: 0,
+        }
+    }
+}
+
+impl SpdmCertVerifyCacheEntry {
+    fn matches(&self, base_hash_algo: SpdmBaseHashAlgo, chain_hash: &[u8]) -> bool {
+        self.in_use
+            && self.base_hash_algo == base_hash_algo
+            && &self.chain_hash[..self.chain_hash_size as usize] == chain_hash
+    }
+}
+
+/// Bounded LRU cache of cert chains which passed X.509 path validation,
+/// keyed by the digest of the DER cert chain.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmCertVerifyCache {
+    entries: [SpdmCertVerifyCacheEntry; MAX_SPDM_CERT_VERIFY_CACHE_ENTRIES],
+    use_counter: u64,
+}
+
+impl SpdmCertVerifyCache {
+    /// Return true if the chain was validated before and, when now is known,
+    /// every certificate in it is still within its validity period.
+    pub fn lookup(
+        &mut self,
+        base_hash_algo: SpdmBaseHashAlgo,
+        chain_hash: &[u8],
+        now: Option<u64>,
+    ) -> bool {
+        self.use_counter += 1;
+        let use_counter = self.use_counter;
+        let entry = match self
+            .entries
+            .iter_mut()
+            .find(|e| e.matches(base_hash_algo, chain_hash))
+        {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        if let Some(now) = now {
+            if now < entry.not_before || now > entry.not_after {
+                *entry = SpdmCertVerifyCacheEntry::default();
+                return false;
+            }
+        }
+        entry.last_used = use_counter;
+        true
+    }
+
+    /// Remember a validated chain, evicting the least recently used entry if full.
+    pub fn insert(
+        &mut self,
+        base_hash_algo: SpdmBaseHashAlgo,
+        chain_hash: &[u8],
+        not_before: u64,
+        not_after: u64,
+    ) {
+        if chain_hash.len() > SPDM_MAX_HASH_SIZE {
+            return;
+        }
+        self.use_counter += 1;
+
+        let index = match self
+            .entries
+            .iter()
+            .position(|e| e.matches(base_hash_algo, chain_hash))
+        {
+            Some(index) => index,
+            None => self
+                .entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, e)| if e.in_use { e.last_used } else { 0 })
+                .map(|(index, _)| index)
+                .unwrap_or(0),
+        };
+
+        let mut entry = SpdmCertVerifyCacheEntry {
+            in_use: true,
+            base_hash_algo,
+            chain_hash_size: chain_hash.len() as u16,
+            not_before,
+            not_after,
+            last_used: self.use_counter,
+            ..Default::default()
+        };
+        entry.chain_hash[..chain_hash.len()].copy_from_slice(chain_hash);
+        self.entries[index] = entry;
+    }
+
+    pub fn clear(&mut self) {
+        *self = SpdmCertVerifyCache::default();
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    const SHA_384: SpdmBaseHashAlgo = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+
+    #[test]
+    fn test_case0_cert_verify_cache_lookup() {
+        let mut cache = SpdmCertVerifyCache::default();
+        assert!(!cache.lookup(SHA_384, &[1u8; 48], None));
+
+        cache.insert(SHA_384, &[1u8; 48], 100, 200);
+        assert!(cache.lookup(SHA_384, &[1u8; 48], None));
+        assert!(cache.lookup(SHA_384, &[1u8; 48], Some(150)));
+        assert!(!cache.lookup(SpdmBaseHashAlgo::TPM_ALG_SHA_256, &[1u8; 32], None));
+        assert!(!cache.lookup(SHA_384, &[2u8; 48], None));
+
+        // expired entries are dropped
+        assert!(!cache.lookup(SHA_384, &[1u8; 48], Some(201)));
+        assert!(!cache.lookup(SHA_384, &[1u8; 48], None));
+
+        cache.insert(SHA_384, &[1u8; 48], 100, 200);
+        cache.clear();
+        assert!(!cache.lookup(SHA_384, &[1u8; 48], None));
+    }
+
+    #[test]
+    fn test_case0_cert_verify_cache_evict_lru() {
+        let mut cache = SpdmCertVerifyCache::default();
+        for i in 0..MAX_SPDM_CERT_VERIFY_CACHE_ENTRIES as u8 {
+            cache.insert(SHA_384, &[i; 48], 0, u64::MAX);
+        }
+        // entry 0 is used, so entry 1 is the least recently used one
+        assert!(cache.lookup(SHA_384, &[0u8; 48], None));
+        cache.insert(SHA_384, &[0xFFu8; 48], 0, u64::MAX);
+
+        assert!(cache.lookup(SHA_384, &[0u8; 48], None));
+        assert!(!cache.lookup(SHA_384, &[1u8; 48], None));
+        for i in 2..MAX_SPDM_CERT_VERIFY_CACHE_ENTRIES as u8 {
+            assert!(cache.lookup(SHA_384, &[i; 48], None));
+        }
+        assert!(cache.lookup(SHA_384, &[0xFFu8; 48], None));
+    }
+}
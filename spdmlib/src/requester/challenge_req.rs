@@ -13,36 +13,55 @@ use crate::message::*;
 use crate::protocol::*;
 use crate::requester::*;
 
+/// Evidence proven by a successful CHALLENGE_AUTH, for verifiers to archive.
+#[derive(Debug, Clone)]
+pub struct SpdmChallengeAuthResult {
+    /// Slot of the certificate chain the signature was verified with.
+    pub slot_id: u8,
+    pub cert_chain_hash: SpdmDigestStruct,
+    pub requester_nonce: SpdmNonceStruct,
+    pub responder_nonce: SpdmNonceStruct,
+    /// Empty if SpdmMeasurementSummaryHashTypeNone is requested.
+    pub measurement_summary_hash: SpdmDigestStruct,
+    /// Hash of the M1/M2 transcript covered by the signature.
+    pub transcript_hash: SpdmDigestStruct,
+    pub opaque: SpdmOpaqueStruct,
+}
+
 impl<'a> RequesterContext<'a> {
     pub fn send_receive_spdm_challenge(
         &mut self,
         slot_id: u8,
         measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
-    ) -> SpdmResult {
+    ) -> SpdmResult<SpdmChallengeAuthResult> {
         info!("send spdm challenge\n");
 
-        if slot_id >= SPDM_MAX_SLOT_NUMBER as u8 {
+        if !self.common.is_rsp_slot_id_valid(slot_id) {
             return Err(SPDM_STATUS_INVALID_PARAMETER);
         }
 
         self.common
             .reset_buffer_via_request_code(SpdmRequestResponseCode::SpdmRequestChallenge, None);
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let send_used =
             self.encode_spdm_challenge(slot_id, measurement_summary_hash_type, &mut send_buffer)?;
         self.send_message(&send_buffer[..send_used])?;
 
         // Receive
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = self.receive_message(&mut receive_buffer, true)?;
-        self.handle_spdm_challenge_response(
+        let result = self.handle_spdm_challenge_response(
             0, // NULL
             slot_id,
             measurement_summary_hash_type,
             &send_buffer[..send_used],
             &receive_buffer[..used],
-        )
+        )?;
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        self.advance_requester_state(SpdmRequesterState::Authenticated);
+        Ok(result)
     }
 
     pub fn encode_spdm_challenge(
@@ -55,6 +74,12 @@ impl<'a> RequesterContext<'a> {
 
         let mut nonce = [0u8; SPDM_NONCE_SIZE];
         crypto::rand::get_random(&mut nonce)?;
+        let mut requester_context = [0u8; SPDM_REQ_CONTEXT_SIZE];
+        if self.common.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion13.get_u8()
+        {
+            crypto::rand::get_random(&mut requester_context)?;
+        }
 
         let request = SpdmMessage {
             header: SpdmMessageHeader {
@@ -65,6 +90,9 @@ impl<'a> RequesterContext<'a> {
                 slot_id,
                 measurement_summary_hash_type,
                 nonce: SpdmNonceStruct { data: nonce },
+                requester_context: SpdmReqContextStruct {
+                    data: requester_context,
+                },
             }),
         };
         request.spdm_encode(&mut self.common, &mut writer)
@@ -77,7 +105,7 @@ impl<'a> RequesterContext<'a> {
         measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
         send_buffer: &[u8],
         receive_buffer: &[u8],
-    ) -> SpdmResult {
+    ) -> SpdmResult<SpdmChallengeAuthResult> {
         if (measurement_summary_hash_type
             == SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeTcb)
             || (measurement_summary_hash_type
@@ -104,6 +132,17 @@ impl<'a> RequesterContext<'a> {
                         if let Some(challenge_auth) = challenge_auth {
                             debug!("!!! challenge_auth : {:02x?}\n", challenge_auth);
 
+                            // the request context is the last field of the request
+                            if self.common.negotiate_info.spdm_version_sel.get_u8()
+                                >= SpdmVersion::SpdmVersion13.get_u8()
+                                && (send_buffer.len() < SPDM_REQ_CONTEXT_SIZE
+                                    || send_buffer[send_buffer.len() - SPDM_REQ_CONTEXT_SIZE..]
+                                        != challenge_auth.requester_context.data)
+                            {
+                                error!("!!! challenge_auth : requester context mismatch !!!\n");
+                                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                            }
+
                             // verify signature
                             let base_asym_size =
                                 self.common.negotiate_info.base_asym_sel.get_size() as usize;
@@ -112,33 +151,65 @@ impl<'a> RequesterContext<'a> {
                             self.common.append_message_c(send_buffer)?;
                             self.common.append_message_c(&receive_buffer[..temp_used])?;
 
-                            if self
+                            let transcript_hash = match self
                                 .verify_challenge_auth_signature(slot_id, &challenge_auth.signature)
-                                .is_err()
                             {
-                                error!("verify_challenge_auth_signature fail");
-                                self.common.reset_message_b();
-                                self.common.reset_message_c();
-                                return Err(SPDM_STATUS_VERIF_FAIL);
-                            } else {
-                                self.common.reset_message_b();
-                                self.common.reset_message_c();
-                                info!("verify_challenge_auth_signature pass");
-                            }
+                                Ok(transcript_hash) => {
+                                    self.common.runtime_info.last_verified_m1m2 =
+                                        self.common.export_message_m1m2().ok();
+                                    self.common.reset_message_b();
+                                    self.common.reset_message_c();
+                                    info!("verify_challenge_auth_signature pass");
+                                    transcript_hash
+                                }
+                                Err(_) => {
+                                    error!("verify_challenge_auth_signature fail");
+                                    self.common.reset_message_b();
+                                    self.common.reset_message_c();
+                                    return Err(SPDM_STATUS_VERIF_FAIL);
+                                }
+                            };
+
+                            self.common.receive_opaque_elements(
+                                SpdmRequestResponseCode::SpdmResponseChallengeAuth,
+                                &challenge_auth.opaque,
+                            )?;
+
+                            let mut send_reader = Reader::init(send_buffer);
+                            SpdmMessageHeader::read(&mut send_reader)
+                                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+                            let challenge = SpdmChallengeRequestPayload::spdm_read(
+                                &mut self.common,
+                                &mut send_reader,
+                            )
+                            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
 
-                            Ok(())
+                            Ok(SpdmChallengeAuthResult {
+                                slot_id,
+                                cert_chain_hash: challenge_auth.cert_chain_hash,
+                                requester_nonce: challenge.nonce,
+                                responder_nonce: challenge_auth.nonce,
+                                measurement_summary_hash: challenge_auth.measurement_summary_hash,
+                                transcript_hash,
+                                opaque: challenge_auth.opaque,
+                            })
                         } else {
                             error!("!!! challenge_auth : fail !!!\n");
                             Err(SPDM_STATUS_INVALID_MSG_FIELD)
                         }
                     }
-                    SpdmRequestResponseCode::SpdmResponseError => self
-                        .spdm_handle_error_response_main(
+                    SpdmRequestResponseCode::SpdmResponseError => {
+                        let status = self.spdm_handle_error_response_main(
                             Some(session_id),
                             receive_buffer,
                             SpdmRequestResponseCode::SpdmRequestChallenge,
                             SpdmRequestResponseCode::SpdmResponseChallengeAuth,
-                        ),
+                        );
+                        match status {
+                            Err(status) => Err(status),
+                            Ok(()) => Err(SPDM_STATUS_ERROR_PEER),
+                        }
+                    }
                     _ => Err(SPDM_STATUS_ERROR_PEER),
                 }
             }
@@ -151,7 +222,7 @@ impl<'a> RequesterContext<'a> {
         &self,
         slot_id: u8,
         signature: &SpdmSignatureStruct,
-    ) -> SpdmResult {
+    ) -> SpdmResult<SpdmDigestStruct> {
         let message_m1m2_hash = crypto::hash::hash_ctx_finalize(
             self.common
                 .runtime_info
@@ -163,19 +234,13 @@ impl<'a> RequesterContext<'a> {
         .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
         debug!("message_m1m2_hash - {:02x?}", message_m1m2_hash.as_ref());
 
-        if self.common.peer_info.peer_cert_chain[slot_id as usize].is_none() {
-            error!("peer_cert_chain is not populated!\n");
-            return Err(SPDM_STATUS_INVALID_PARAMETER);
-        }
-
-        let cert_chain_data = &self.common.peer_info.peer_cert_chain[slot_id as usize]
-            .as_ref()
-            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-            .data[(4usize + self.common.negotiate_info.base_hash_sel.get_size() as usize)
-            ..(self.common.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(slot_id)
+            .ok_or_else(|| {
+                error!("peer_cert_chain is not populated!\n");
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
 
         let mut message_sign = ManagedBuffer12Sign::default();
 
@@ -184,7 +249,12 @@ impl<'a> RequesterContext<'a> {
         {
             message_sign.reset_message();
             message_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_4)
@@ -200,13 +270,15 @@ impl<'a> RequesterContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
-        crypto::asym_verify::verify(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
+        self.verify_peer_signature(
+            SpdmRequestResponseCode::SpdmResponseChallengeAuth,
+            slot_id,
+            &message_m1m2_hash,
             cert_chain_data,
             message_sign.as_ref(),
             signature,
-        )
+        )?;
+        Ok(message_m1m2_hash)
     }
 
     #[cfg(not(feature = "hashed-transcript-data"))]
@@ -214,7 +286,7 @@ impl<'a> RequesterContext<'a> {
         &self,
         slot_id: u8,
         signature: &SpdmSignatureStruct,
-    ) -> SpdmResult {
+    ) -> SpdmResult<SpdmDigestStruct> {
         let mut message_m1m2 = ManagedBufferM1M2::default();
         message_m1m2
             .append_message(self.common.runtime_info.message_a.as_ref())
@@ -235,26 +307,25 @@ impl<'a> RequesterContext<'a> {
         .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
         debug!("message_m1m2_hash - {:02x?}", message_m1m2_hash.as_ref());
 
-        if self.common.peer_info.peer_cert_chain[slot_id as usize].is_none() {
-            error!("peer_cert_chain is not populated!\n");
-            return Err(SPDM_STATUS_INVALID_PARAMETER);
-        }
-
-        let cert_chain_data = &self.common.peer_info.peer_cert_chain[slot_id as usize]
-            .as_ref()
-            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-            .data[(4usize + self.common.negotiate_info.base_hash_sel.get_size() as usize)
-            ..(self.common.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(slot_id)
+            .ok_or_else(|| {
+                error!("peer_cert_chain is not populated!\n");
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
 
         if self.common.negotiate_info.spdm_version_sel.get_u8()
             >= SpdmVersion::SpdmVersion12.get_u8()
         {
             message_m1m2.reset_message();
             message_m1m2
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_m1m2
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_4)
@@ -267,12 +338,14 @@ impl<'a> RequesterContext<'a> {
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         }
 
-        crypto::asym_verify::verify(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
+        self.verify_peer_signature(
+            SpdmRequestResponseCode::SpdmResponseChallengeAuth,
+            slot_id,
+            &message_m1m2_hash,
             cert_chain_data,
             message_m1m2.as_ref(),
             signature,
-        )
+        )?;
+        Ok(message_m1m2_hash)
     }
 }
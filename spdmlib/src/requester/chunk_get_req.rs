@@ -0,0 +1,201 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_TOO_SMALL, SPDM_STATUS_ERROR_PEER,
+    SPDM_STATUS_INVALID_MSG_FIELD, SPDM_STATUS_INVALID_MSG_SIZE,
+};
+use crate::message::*;
+use crate::protocol::*;
+use crate::requester::*;
+
+impl<'a> RequesterContext<'a> {
+    /// If receive_buffer[..used] is ERROR(LargeResponse), retrieve the large
+    /// response with CHUNK_GET and reassemble it into receive_buffer.
+    /// Return the size of the response in receive_buffer.
+    pub(crate) fn receive_large_response(
+        &mut self,
+        session_id: Option<u32>,
+        receive_buffer: &mut [u8],
+        used: usize,
+    ) -> SpdmResult<usize> {
+        let handle = match self.get_large_response_handle(&receive_buffer[..used]) {
+            Some(handle) => handle,
+            None => return Ok(used),
+        };
+        info!("receive large response via chunk get\n");
+
+        let mut chunk_seq_no = 0u16;
+        let mut large_message_size = 0usize;
+        let mut offset = 0usize;
+        loop {
+            let chunk = self.send_receive_spdm_chunk_get(session_id, handle, chunk_seq_no)?;
+
+            if chunk.chunk_seq_no == 0 {
+                large_message_size = chunk.large_message_size as usize;
+                if large_message_size
+                    <= self.common.negotiate_info.req_data_transfer_size_sel as usize
+                {
+                    return Err(SPDM_STATUS_INVALID_MSG_SIZE);
+                }
+                if large_message_size > receive_buffer.len()
+                    || large_message_size > self.common.config_info.max_spdm_msg_size as usize
+                {
+                    return Err(SPDM_STATUS_BUFFER_TOO_SMALL);
+                }
+            }
+
+            let chunk_size = chunk.chunk.len();
+            if chunk_size > large_message_size - offset {
+                return Err(SPDM_STATUS_INVALID_MSG_SIZE);
+            }
+            receive_buffer[offset..offset + chunk_size].copy_from_slice(&chunk.chunk);
+            offset += chunk_size;
+
+            if chunk
+                .chunk_sender_attributes
+                .contains(SpdmChunkSenderAttributes::LAST_CHUNK)
+            {
+                if offset != large_message_size {
+                    return Err(SPDM_STATUS_INVALID_MSG_SIZE);
+                }
+                return Ok(large_message_size);
+            }
+            if offset == large_message_size {
+                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+            }
+
+            chunk_seq_no = chunk_seq_no
+                .checked_add(1)
+                .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        }
+    }
+
+    /// Return the handle of an ERROR(LargeResponse) if chunking is negotiated.
+    fn get_large_response_handle(&mut self, receive_buffer: &[u8]) -> Option<u8> {
+        if self.common.negotiate_info.spdm_version_sel.get_u8()
+            < SpdmVersion::SpdmVersion12.get_u8()
+            || !self
+                .common
+                .negotiate_info
+                .req_capabilities_sel
+                .contains(SpdmRequestCapabilityFlags::CHUNK_CAP)
+            || !self
+                .common
+                .negotiate_info
+                .rsp_capabilities_sel
+                .contains(SpdmResponseCapabilityFlags::CHUNK_CAP)
+        {
+            return None;
+        }
+
+        let mut reader = Reader::init(receive_buffer);
+        let message_header = SpdmMessageHeader::read(&mut reader)?;
+        if message_header.version != self.common.negotiate_info.spdm_version_sel
+            || message_header.request_response_code != SpdmRequestResponseCode::SpdmResponseError
+        {
+            return None;
+        }
+        let error_response = SpdmErrorResponsePayload::spdm_read(&mut self.common, &mut reader)?;
+        match error_response.extended_data {
+            SpdmErrorResponseExtData::SpdmErrorExtDataLargeResponse(extended_data) => {
+                Some(extended_data.handle)
+            }
+            _ => None,
+        }
+    }
+
+    fn send_receive_spdm_chunk_get(
+        &mut self,
+        session_id: Option<u32>,
+        handle: u8,
+        chunk_seq_no: u16,
+    ) -> SpdmResult<SpdmChunkResponsePayload> {
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let used = self.encode_spdm_chunk_get(handle, chunk_seq_no, &mut send_buffer)?;
+        match session_id {
+            Some(session_id) => {
+                self.send_secured_message(session_id, &send_buffer[..used], false)?
+            }
+            None => self.send_message(&send_buffer[..used])?,
+        }
+
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let used = self.receive_single_message(session_id, &mut receive_buffer, false)?;
+        let result = self.handle_spdm_chunk_response(
+            session_id,
+            handle,
+            chunk_seq_no,
+            &receive_buffer[..used],
+        );
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    pub fn encode_spdm_chunk_get(
+        &mut self,
+        handle: u8,
+        chunk_seq_no: u16,
+        buf: &mut [u8],
+    ) -> SpdmResult<usize> {
+        let mut writer = Writer::init(buf);
+        let request = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmRequestChunkGet,
+            },
+            payload: SpdmMessagePayload::SpdmChunkGetRequest(SpdmChunkGetRequestPayload {
+                handle,
+                chunk_seq_no,
+            }),
+        };
+        request.spdm_encode(&mut self.common, &mut writer)
+    }
+
+    pub fn handle_spdm_chunk_response(
+        &mut self,
+        session_id: Option<u32>,
+        handle: u8,
+        chunk_seq_no: u16,
+        receive_buffer: &[u8],
+    ) -> SpdmResult<SpdmChunkResponsePayload> {
+        let mut reader = Reader::init(receive_buffer);
+        match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => {
+                if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                    return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                }
+                match message_header.request_response_code {
+                    SpdmRequestResponseCode::SpdmResponseChunkResponse => {
+                        let chunk_response =
+                            SpdmChunkResponsePayload::spdm_read(&mut self.common, &mut reader)
+                                .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+                        if chunk_response.handle != handle
+                            || chunk_response.chunk_seq_no != chunk_seq_no
+                        {
+                            error!("!!! chunk response : handle or seq no mismatch !!!\n");
+                            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                        }
+                        Ok(chunk_response)
+                    }
+                    SpdmRequestResponseCode::SpdmResponseError => {
+                        let status = self.spdm_handle_error_response_main(
+                            session_id,
+                            receive_buffer,
+                            SpdmRequestResponseCode::SpdmRequestChunkGet,
+                            SpdmRequestResponseCode::SpdmResponseChunkResponse,
+                        );
+                        match status {
+                            Err(status) => Err(status),
+                            Ok(()) => Err(SPDM_STATUS_ERROR_PEER),
+                        }
+                    }
+                    _ => Err(SPDM_STATUS_ERROR_PEER),
+                }
+            }
+            None => Err(SPDM_STATUS_INVALID_MSG_FIELD),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{SpdmResult, SPDM_STATUS_UNSUPPORTED_CAP};
+use crate::protocol::*;
+use crate::requester::*;
+
+/// What start_connection does on top of the negotiated capabilities.
+#[derive(Debug, Clone, Copy)]
+pub struct SpdmConnectionPolicy {
+    /// Responder certificate slot, ignored with a provisioned public key.
+    pub slot_id: u8,
+    /// Authenticate with CHALLENGE before the session, if the responder
+    /// supports it.
+    pub challenge: bool,
+    /// Use PSK_EXCHANGE if both KEY_EXCHANGE and PSK_EXCHANGE are supported.
+    pub prefer_psk: bool,
+    pub measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
+}
+
+impl Default for SpdmConnectionPolicy {
+    fn default() -> Self {
+        SpdmConnectionPolicy {
+            slot_id: 0,
+            challenge: true,
+            prefer_psk: false,
+            measurement_summary_hash_type:
+                SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+        }
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Run GET_VERSION, GET_CAPABILITIES and NEGOTIATE_ALGORITHMS, then
+    /// GET_DIGESTS, GET_CERTIFICATE and CHALLENGE as supported by the
+    /// responder, and establish a session. Return the session ID.
+    ///
+    /// Return SPDM_STATUS_UNSUPPORTED_CAP if neither KEY_EXCHANGE nor
+    /// PSK_EXCHANGE is supported by both sides.
+    pub fn start_connection(&mut self, policy: &SpdmConnectionPolicy) -> SpdmResult<u32> {
+        self.init_connection()?;
+
+        let req_capabilities = self.common.negotiate_info.req_capabilities_sel;
+        let rsp_capabilities = self.common.negotiate_info.rsp_capabilities_sel;
+        let key_ex = req_capabilities.contains(SpdmRequestCapabilityFlags::KEY_EX_CAP)
+            && rsp_capabilities.contains(SpdmResponseCapabilityFlags::KEY_EX_CAP);
+        let psk = req_capabilities.contains(SpdmRequestCapabilityFlags::PSK_CAP)
+            && rsp_capabilities.intersects(
+                SpdmResponseCapabilityFlags::PSK_CAP_WITHOUT_CONTEXT
+                    | SpdmResponseCapabilityFlags::PSK_CAP_WITH_CONTEXT,
+            );
+        let use_psk = psk && (policy.prefer_psk || !key_ex);
+        if !key_ex && !use_psk {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+
+        let slot_id = if rsp_capabilities.contains(SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP) {
+            SPDM_PUB_KEY_SLOT_ID
+        } else {
+            policy.slot_id
+        };
+        if slot_id != SPDM_PUB_KEY_SLOT_ID
+            && rsp_capabilities.contains(SpdmResponseCapabilityFlags::CERT_CAP)
+        {
+            self.send_receive_spdm_digest(None)?;
+            self.send_receive_spdm_certificate(None, slot_id)?;
+        }
+        if policy.challenge && rsp_capabilities.contains(SpdmResponseCapabilityFlags::CHAL_CAP) {
+            self.send_receive_spdm_challenge(slot_id, policy.measurement_summary_hash_type)?;
+        }
+
+        self.start_session(use_psk, slot_id, policy.measurement_summary_hash_type)
+    }
+}
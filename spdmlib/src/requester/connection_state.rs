@@ -0,0 +1,131 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::session::SpdmSessionState;
+use crate::error::SpdmResult;
+use crate::requester::*;
+
+use conquer_once::spin::OnceCell;
+
+/// Connection progress seen by the requester, the counterpart of the
+/// connection_state the responder tracks in runtime_info.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SpdmRequesterState {
+    // Before GET_VERSION/VERSION
+    NotStarted,
+    // After GET_VERSION/VERSION
+    AfterVersion,
+    // After GET_CAPABILITIES/CAPABILITIES
+    AfterCapabilities,
+    // After NEGOTIATE_ALGORITHMS/ALGORITHMS
+    Negotiated,
+    // After GET_DIGESTS/DIGESTS
+    AfterDigest,
+    // After GET_CERTIFICATE/CERTIFICATE, with the cert chain verified
+    AfterCertificate,
+    // After CHALLENGE/CHALLENGE_AUTH
+    Authenticated,
+    // At least one session is established
+    SessionEstablished,
+}
+
+impl Default for SpdmRequesterState {
+    fn default() -> SpdmRequesterState {
+        SpdmRequesterState::NotStarted
+    }
+}
+
+/// state_changed_cb is called each time the value returned by
+/// RequesterContext::get_requester_state changes.
+#[derive(Clone, Copy)]
+pub struct SpdmRequesterStateNotifier {
+    pub state_changed_cb: fn(old_state: SpdmRequesterState, new_state: SpdmRequesterState),
+}
+
+static REQUESTER_STATE_NOTIFIER: OnceCell<SpdmRequesterStateNotifier> = OnceCell::uninit();
+
+pub fn register_state_notifier(context: SpdmRequesterStateNotifier) -> bool {
+    REQUESTER_STATE_NOTIFIER.try_init_once(|| context).is_ok()
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Current progress of the connection, SessionEstablished while any
+    /// session is established.
+    pub fn get_requester_state(&self) -> SpdmRequesterState {
+        if self
+            .common
+            .session
+            .iter()
+            .any(|session| session.get_session_state() == SpdmSessionState::SpdmSessionEstablished)
+        {
+            SpdmRequesterState::SessionEstablished
+        } else {
+            self.requester_state
+        }
+    }
+
+    /// Run the negotiation steps not completed yet, e.g. after a
+    /// GET_CAPABILITIES failure, without restarting from GET_VERSION.
+    pub fn resume_connection(&mut self) -> SpdmResult {
+        match self.requester_state {
+            SpdmRequesterState::NotStarted => self.init_connection(),
+            SpdmRequesterState::AfterVersion => {
+                self.send_receive_spdm_capability()?;
+                self.send_receive_spdm_algorithm()
+            }
+            SpdmRequesterState::AfterCapabilities => self.send_receive_spdm_algorithm(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the connection progress. Only GET_VERSION and RequestResynch
+    /// may move it backward.
+    pub(crate) fn set_requester_state(&mut self, state: SpdmRequesterState) {
+        self.requester_state = state;
+        self.notify_requester_state();
+    }
+
+    /// Move the connection progress forward, never backward.
+    pub(crate) fn advance_requester_state(&mut self, state: SpdmRequesterState) {
+        if state > self.requester_state {
+            self.requester_state = state;
+        }
+        self.notify_requester_state();
+    }
+
+    /// Report a change of get_requester_state, e.g. after a session is
+    /// established or ended.
+    pub(crate) fn notify_requester_state(&mut self) {
+        let new_state = self.get_requester_state();
+        let old_state = self.notified_requester_state;
+        if new_state == old_state {
+            return;
+        }
+        self.notified_requester_state = new_state;
+        info!("requester state {:?} -> {:?}\n", old_state, new_state);
+        if let Ok(notifier) = REQUESTER_STATE_NOTIFIER.try_get() {
+            (notifier.state_changed_cb)(old_state, new_state);
+        }
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_requester_state_order() {
+        assert_eq!(
+            SpdmRequesterState::default(),
+            SpdmRequesterState::NotStarted
+        );
+        assert!(SpdmRequesterState::NotStarted < SpdmRequesterState::AfterVersion);
+        assert!(SpdmRequesterState::AfterVersion < SpdmRequesterState::AfterCapabilities);
+        assert!(SpdmRequesterState::AfterCapabilities < SpdmRequesterState::Negotiated);
+        assert!(SpdmRequesterState::Negotiated < SpdmRequesterState::AfterDigest);
+        assert!(SpdmRequesterState::AfterDigest < SpdmRequesterState::AfterCertificate);
+        assert!(SpdmRequesterState::AfterCertificate < SpdmRequesterState::Authenticated);
+        assert!(SpdmRequesterState::Authenticated < SpdmRequesterState::SessionEstablished);
+    }
+}
@@ -4,12 +4,24 @@
 
 use crate::common::ST1;
 use crate::common::{self, SpdmDeviceIo, SpdmTransportEncap};
-use crate::config;
 use crate::error::{SpdmResult, SPDM_STATUS_RECEIVE_FAIL, SPDM_STATUS_SEND_FAIL};
 use crate::protocol::*;
+use crate::time;
+
+use super::cert_verify_cache::SpdmCertVerifyCache;
+use super::connection_state::SpdmRequesterState;
+use super::error_stats::SpdmErrorResponseStats;
+use super::respond_if_ready_req::SPDM_MAX_RESPOND_IF_READY_RETRY;
 
 pub struct RequesterContext<'a> {
     pub common: common::SpdmContext<'a>,
+    pub cert_verify_cache: SpdmCertVerifyCache,
+    pub error_response_stats: SpdmErrorResponseStats,
+    /// RESPOND_IF_READY sent for one request, 0 gives ERROR(ResponseNotReady)
+    /// to the caller right away.
+    pub respond_if_ready_retry: usize,
+    pub(crate) requester_state: SpdmRequesterState,
+    pub(crate) notified_requester_state: SpdmRequesterState,
 }
 
 impl<'a> RequesterContext<'a> {
@@ -26,6 +38,11 @@ impl<'a> RequesterContext<'a> {
                 config_info,
                 provision_info,
             ),
+            cert_verify_cache: SpdmCertVerifyCache::default(),
+            error_response_stats: SpdmErrorResponseStats::default(),
+            respond_if_ready_retry: SPDM_MAX_RESPOND_IF_READY_RETRY,
+            requester_state: SpdmRequesterState::default(),
+            notified_requester_state: SpdmRequesterState::default(),
         }
     }
 
@@ -61,7 +78,9 @@ impl<'a> RequesterContext<'a> {
     }
 
     pub fn end_session(&mut self, session_id: u32) -> SpdmResult {
-        self.send_receive_spdm_end_session(session_id)
+        let result = self.send_receive_spdm_end_session(session_id);
+        self.notify_requester_state();
+        result
     }
 
     pub fn send_message(&mut self, send_buffer: &[u8]) -> SpdmResult {
@@ -70,9 +89,15 @@ impl<'a> RequesterContext<'a> {
         {
             return Err(SPDM_STATUS_SEND_FAIL);
         }
-        let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        if send_buffer.len() > self.common.buffer_config.max_spdm_msg_size {
+            return Err(SPDM_STATUS_SEND_FAIL);
+        }
+        self.common.check_cancelled()?;
+        let mut transport_buffer = self.common.alloc_sender_buffer();
         let used = self.common.encap(send_buffer, &mut transport_buffer)?;
-        self.common.device_io.send(&transport_buffer[..used])
+        let result = self.common.device_io_send(&transport_buffer[..used], false);
+        self.common.free_buffer(transport_buffer);
+        result
     }
 
     pub fn send_secured_message(
@@ -87,7 +112,8 @@ impl<'a> RequesterContext<'a> {
         {
             return Err(SPDM_STATUS_SEND_FAIL);
         }
-        let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        self.common.check_cancelled()?;
+        let mut transport_buffer = self.common.alloc_sender_buffer();
         let used = self.common.encode_secured_message(
             session_id,
             send_buffer,
@@ -95,7 +121,9 @@ impl<'a> RequesterContext<'a> {
             true,
             is_app_message,
         )?;
-        self.common.device_io.send(&transport_buffer[..used])
+        let result = self.common.device_io_send(&transport_buffer[..used], true);
+        self.common.free_buffer(transport_buffer);
+        result
     }
 
     pub fn receive_message(
@@ -105,20 +133,13 @@ impl<'a> RequesterContext<'a> {
     ) -> SpdmResult<usize> {
         info!("receive_message!\n");
 
-        let timeout: usize = if crypto_request {
-            2 << self.common.negotiate_info.rsp_ct_exponent_sel
-        } else {
-            ST1
-        };
-
-        let mut transport_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
-        let used = self
-            .common
-            .device_io
-            .receive(&mut transport_buffer, timeout)
-            .map_err(|_| SPDM_STATUS_RECEIVE_FAIL)?;
-
-        self.common.decap(&transport_buffer[..used], receive_buffer)
+        let used = self.receive_single_message(None, receive_buffer, crypto_request)?;
+        let used = self.receive_deferred_response(None, receive_buffer, used)?;
+        let used = self.receive_large_response(None, receive_buffer, used)?;
+        self.update_error_response_stats(&receive_buffer[..used]);
+        self.reset_peer_vendor_error(&receive_buffer[..used]);
+        self.check_response_capabilities(&receive_buffer[..used])?;
+        Ok(used)
     }
 
     pub fn receive_secured_message(
@@ -129,21 +150,63 @@ impl<'a> RequesterContext<'a> {
     ) -> SpdmResult<usize> {
         info!("receive_secured_message!\n");
 
+        let used = self.receive_single_message(Some(session_id), receive_buffer, crypto_request)?;
+        let used = self.receive_deferred_response(Some(session_id), receive_buffer, used)?;
+        let used = self.receive_large_response(Some(session_id), receive_buffer, used)?;
+        self.update_error_response_stats(&receive_buffer[..used]);
+        self.reset_peer_vendor_error(&receive_buffer[..used]);
+        self.check_response_capabilities(&receive_buffer[..used])?;
+        Ok(used)
+    }
+
+    /// Receive one message from device_io, without large response reassembly.
+    pub(crate) fn receive_single_message(
+        &mut self,
+        session_id: Option<u32>,
+        receive_buffer: &mut [u8],
+        crypto_request: bool,
+    ) -> SpdmResult<usize> {
         let timeout: usize = if crypto_request {
-            2 << self.common.negotiate_info.rsp_ct_exponent_sel
+            crate::common::timing::ct_timeout_us(self.common.negotiate_info.rsp_ct_exponent_sel)
         } else {
             ST1
         };
 
-        let mut transport_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
-
+        self.common.check_cancelled()?;
+        let start = if time::is_registered() {
+            Some(time::now_us())
+        } else {
+            None
+        };
+        let mut transport_buffer = self.common.alloc_receiver_buffer();
         let used = self
             .common
-            .device_io
-            .receive(&mut transport_buffer, timeout)
+            .device_io_receive(&mut transport_buffer, timeout)
             .map_err(|_| SPDM_STATUS_RECEIVE_FAIL)?;
+        // a response received after the cancellation is dropped
+        if let Err(status) = self.common.check_cancelled() {
+            self.common.free_buffer(transport_buffer);
+            return Err(status);
+        }
+        // ST1 is allowed at least, as the round trip time of the transport
+        let deadline = timeout.max(ST1) as u64;
+        if let Some(start) = start {
+            if time::elapsed_us(start) > deadline {
+                error!("!!! response after {} us timeout !!!\n", deadline);
+                self.common.free_buffer(transport_buffer);
+                return Err(SPDM_STATUS_RECEIVE_FAIL);
+            }
+        }
 
-        self.common
-            .decode_secured_message(session_id, &transport_buffer[..used], receive_buffer)
+        let result = match session_id {
+            Some(session_id) => self.common.decode_secured_message(
+                session_id,
+                &transport_buffer[..used],
+                receive_buffer,
+            ),
+            None => self.common.decap(&transport_buffer[..used], receive_buffer),
+        };
+        self.common.free_buffer(transport_buffer);
+        result
     }
 }
@@ -0,0 +1,104 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD,
+};
+use crate::message::cxl_ide_km::*;
+use crate::message::ide_km::IdeKmKpAckStatus;
+use crate::requester::*;
+
+impl<'a> RequesterContext<'a> {
+    fn send_receive_cxl_ide_km(
+        &mut self,
+        session_id: u32,
+        request: &CxlIdeKmMessage,
+    ) -> SpdmResult<CxlIdeKmMessage> {
+        let req_payload = request.to_req_payload().ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        let rsp_payload = self.send_spdm_vendor_defined_request(
+            Some(session_id),
+            CXL_IDE_KM_STANDARD_ID,
+            cxl_vendor_id(),
+            req_payload,
+        )?;
+        let mut reader = Reader::init(
+            &rsp_payload.vendor_defined_rsp_payload[..rsp_payload.rsp_length as usize],
+        );
+        let response = CxlIdeKmMessage::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        if reader.any_left() {
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        Ok(response)
+    }
+
+    /// Return the CXL IDE capabilities of port_index, 0 being the device itself.
+    pub fn send_receive_cxl_ide_km_query(
+        &mut self,
+        session_id: u32,
+        port_index: u8,
+    ) -> SpdmResult<CxlIdeKmQueryResp> {
+        info!("send cxl_ide_km query\n");
+        match self.send_receive_cxl_ide_km(session_id, &CxlIdeKmMessage::Query { port_index })? {
+            CxlIdeKmMessage::QueryResp(query_resp) if query_resp.port_index == port_index => {
+                Ok(query_resp)
+            }
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    /// Program one key, return the KP_ACK status of the device.
+    pub fn send_receive_cxl_ide_km_key_prog(
+        &mut self,
+        session_id: u32,
+        key_index: CxlIdeKmKeyIndex,
+        key: &CxlIdeKmAesKey,
+    ) -> SpdmResult<IdeKmKpAckStatus> {
+        info!("send cxl_ide_km key_prog\n");
+        match self.send_receive_cxl_ide_km(
+            session_id,
+            &CxlIdeKmMessage::KeyProg(key_index, key.clone()),
+        )? {
+            CxlIdeKmMessage::KpAck(ack_index, status) if ack_index == key_index => Ok(status),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    pub fn send_receive_cxl_ide_km_key_set_go(
+        &mut self,
+        session_id: u32,
+        key_index: CxlIdeKmKeyIndex,
+    ) -> SpdmResult {
+        info!("send cxl_ide_km k_set_go\n");
+        match self.send_receive_cxl_ide_km(session_id, &CxlIdeKmMessage::KSetGo(key_index))? {
+            CxlIdeKmMessage::KGoStopAck(ack_index) if ack_index == key_index => Ok(()),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    pub fn send_receive_cxl_ide_km_key_set_stop(
+        &mut self,
+        session_id: u32,
+        key_index: CxlIdeKmKeyIndex,
+    ) -> SpdmResult {
+        info!("send cxl_ide_km k_set_stop\n");
+        match self.send_receive_cxl_ide_km(session_id, &CxlIdeKmMessage::KSetStop(key_index))? {
+            CxlIdeKmMessage::KGoStopAck(ack_index) if ack_index == key_index => Ok(()),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    /// Return the key and IV generated by a device advertising
+    /// KEY_GENERATION_CAP or IV_GENERATION_CAP.
+    pub fn send_receive_cxl_ide_km_get_key(
+        &mut self,
+        session_id: u32,
+        key_index: CxlIdeKmKeyIndex,
+    ) -> SpdmResult<CxlIdeKmAesKey> {
+        info!("send cxl_ide_km get_key\n");
+        match self.send_receive_cxl_ide_km(session_id, &CxlIdeKmMessage::GetKey(key_index))? {
+            CxlIdeKmMessage::GetKeyAck(ack_index, key) if ack_index == key_index => Ok(key),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+}
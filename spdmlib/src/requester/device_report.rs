@@ -0,0 +1,282 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::{ManagedBufferL1L2, SpdmTranscript};
+use crate::crypto;
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_INVALID_CERT,
+    SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_INVALID_STATE_LOCAL, SPDM_STATUS_VERIF_FAIL,
+};
+use crate::protocol::*;
+use crate::requester::*;
+use codec::u24;
+
+/// Bumped whenever the encoding of SpdmDeviceReport changes.
+pub const SPDM_DEVICE_REPORT_FORMAT_VERSION: u8 = 2;
+
+/// Endpoint identity report built from evidence the requester already verified.
+///
+/// GET_ENDPOINT_INFO is not supported by this library yet, the negotiated
+/// responder capabilities are reported as endpoint information instead.
+///
+/// The report carries the requester nonce, the L1/L2 transcript and the
+/// signature of the last verified MEASUREMENTS response, so that a verifier
+/// can check the responder signature with the leaf certificate, and that the
+/// nonce and the measurement record are part of the signed transcript.
+/// SPDM 1.2 or later is required. The L1/L2 messages are not kept with
+/// hashed-transcript-data, no report can be composed then.
+///
+/// Encoding (little endian): format version (1), spdm version (1),
+/// rsp capabilities (4), base hash (4), base asym (4), measurement hash (4),
+/// slot id (1), leaf cert length (2), leaf cert, number of blocks (1),
+/// measurement record length (3), measurement record, requester nonce (32),
+/// L1/L2 length (4), L1/L2, signature (base asym size).
+#[derive(Debug, Clone)]
+pub struct SpdmDeviceReport {
+    pub spdm_version: SpdmVersion,
+    pub rsp_capabilities: SpdmResponseCapabilityFlags,
+    pub base_hash_algo: SpdmBaseHashAlgo,
+    pub base_asym_algo: SpdmBaseAsymAlgo,
+    pub measurement_hash_algo: SpdmMeasurementHashAlgo,
+    pub slot_id: u8,
+    pub leaf_cert_size: u16,
+    pub leaf_cert: [u8; config::MAX_SPDM_CERT_CHAIN_DATA_SIZE],
+    pub measurement_record: SpdmMeasurementRecordStructure,
+    pub requester_nonce: SpdmNonceStruct,
+    pub l1l2: ManagedBufferL1L2,
+    pub signature: SpdmSignatureStruct,
+}
+
+impl Default for SpdmDeviceReport {
+    fn default() -> SpdmDeviceReport {
+        SpdmDeviceReport {
+            spdm_version: SpdmVersion::default(),
+            rsp_capabilities: SpdmResponseCapabilityFlags::default(),
+            base_hash_algo: SpdmBaseHashAlgo::default(),
+            base_asym_algo: SpdmBaseAsymAlgo::default(),
+            measurement_hash_algo: SpdmMeasurementHashAlgo::default(),
+            slot_id: 0,
+            leaf_cert_size: 0,
+            leaf_cert: [0u8; config::MAX_SPDM_CERT_CHAIN_DATA_SIZE],
+            measurement_record: SpdmMeasurementRecordStructure::default(),
+            requester_nonce: SpdmNonceStruct::default(),
+            l1l2: ManagedBufferL1L2::default(),
+            signature: SpdmSignatureStruct::default(),
+        }
+    }
+}
+
+impl Codec for SpdmDeviceReport {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += SPDM_DEVICE_REPORT_FORMAT_VERSION.encode(bytes)?;
+        cnt += self.spdm_version.encode(bytes)?;
+        cnt += self.rsp_capabilities.encode(bytes)?;
+        cnt += self.base_hash_algo.encode(bytes)?;
+        cnt += self.base_asym_algo.encode(bytes)?;
+        cnt += self.measurement_hash_algo.encode(bytes)?;
+        cnt += self.slot_id.encode(bytes)?;
+        cnt += self.leaf_cert_size.encode(bytes)?;
+        for d in self.leaf_cert.iter().take(self.leaf_cert_size as usize) {
+            cnt += d.encode(bytes)?;
+        }
+        cnt += self.measurement_record.number_of_blocks.encode(bytes)?;
+        cnt += self
+            .measurement_record
+            .measurement_record_length
+            .encode(bytes)?;
+        for d in self
+            .measurement_record
+            .measurement_record_data
+            .iter()
+            .take(self.measurement_record.measurement_record_length.get() as usize)
+        {
+            cnt += d.encode(bytes)?;
+        }
+        cnt += self.requester_nonce.encode(bytes)?;
+        if self.signature.data_size != self.base_asym_algo.get_size() {
+            return Err(codec::EncodeErr);
+        }
+        cnt += (self.l1l2.as_ref().len() as u32).encode(bytes)?;
+        for d in self.l1l2.as_ref() {
+            cnt += d.encode(bytes)?;
+        }
+        for d in self.signature.as_ref() {
+            cnt += d.encode(bytes)?;
+        }
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmDeviceReport> {
+        let format_version = u8::read(r)?;
+        if format_version != SPDM_DEVICE_REPORT_FORMAT_VERSION {
+            return None;
+        }
+        let spdm_version = SpdmVersion::read(r)?;
+        let rsp_capabilities = SpdmResponseCapabilityFlags::read(r)?;
+        let base_hash_algo = SpdmBaseHashAlgo::read(r)?;
+        let base_asym_algo = SpdmBaseAsymAlgo::read(r)?;
+        let measurement_hash_algo = SpdmMeasurementHashAlgo::read(r)?;
+        let slot_id = u8::read(r)?;
+        let leaf_cert_size = u16::read(r)?;
+        if leaf_cert_size as usize > config::MAX_SPDM_CERT_CHAIN_DATA_SIZE {
+            return None;
+        }
+        let mut leaf_cert = [0u8; config::MAX_SPDM_CERT_CHAIN_DATA_SIZE];
+        for d in leaf_cert.iter_mut().take(leaf_cert_size as usize) {
+            *d = u8::read(r)?;
+        }
+        let number_of_blocks = u8::read(r)?;
+        let measurement_record_length = u24::read(r)?;
+        if measurement_record_length.get() as usize > config::MAX_SPDM_MEASUREMENT_RECORD_SIZE {
+            return None;
+        }
+        let mut measurement_record_data = [0u8; config::MAX_SPDM_MEASUREMENT_RECORD_SIZE];
+        for d in measurement_record_data
+            .iter_mut()
+            .take(measurement_record_length.get() as usize)
+        {
+            *d = u8::read(r)?;
+        }
+        let requester_nonce = SpdmNonceStruct::read(r)?;
+        let l1l2_length = u32::read(r)?;
+        let mut l1l2 = ManagedBufferL1L2::default();
+        l1l2.append_message(r.take(l1l2_length as usize)?)?;
+        let mut signature = SpdmSignatureStruct {
+            data_size: base_asym_algo.get_size(),
+            ..Default::default()
+        };
+        for d in signature.data.iter_mut().take(signature.data_size as usize) {
+            *d = u8::read(r)?;
+        }
+
+        Some(SpdmDeviceReport {
+            spdm_version,
+            rsp_capabilities,
+            base_hash_algo,
+            base_asym_algo,
+            measurement_hash_algo,
+            slot_id,
+            leaf_cert_size,
+            leaf_cert,
+            measurement_record: SpdmMeasurementRecordStructure {
+                number_of_blocks,
+                measurement_record_length,
+                measurement_record_data,
+            },
+            requester_nonce,
+            l1l2,
+            signature,
+        })
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Compose a device report from the certificate chain in slot_id and the
+    /// measurement record of the last signed MEASUREMENTS response verified by
+    /// send_receive_spdm_measurement. The report signature is verified before
+    /// it is returned.
+    pub fn compose_device_report(
+        &self,
+        slot_id: u8,
+        measurement_record: &SpdmMeasurementRecordStructure,
+    ) -> SpdmResult<SpdmDeviceReport> {
+        if slot_id >= SPDM_MAX_SLOT_NUMBER as u8 {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        // only the leaf is left of a chain verified in portions
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(slot_id)
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        if cert_chain_data.is_empty() {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        let (leaf_cert_begin, leaf_cert_end) =
+            crypto::cert_operation::get_cert_from_cert_chain(cert_chain_data, -1)?;
+        let leaf_cert_size = leaf_cert_end - leaf_cert_begin;
+
+        let runtime_info = &self.common.runtime_info;
+        let mut l1l2 = ManagedBufferL1L2::default();
+        match runtime_info.last_verified_l1l2.as_ref() {
+            Some(SpdmTranscript::Data(data)) => {
+                l1l2.append_message(data).ok_or(SPDM_STATUS_BUFFER_FULL)?;
+            }
+            _ => return Err(SPDM_STATUS_INVALID_STATE_LOCAL),
+        }
+        let requester_nonce = runtime_info
+            .last_verified_measurement_nonce
+            .clone()
+            .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+        let signature = runtime_info
+            .last_verified_measurement_signature
+            .clone()
+            .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+
+        let mut report = SpdmDeviceReport {
+            spdm_version: self.common.negotiate_info.spdm_version_sel,
+            rsp_capabilities: self.common.negotiate_info.rsp_capabilities_sel,
+            base_hash_algo: self.common.negotiate_info.base_hash_sel,
+            base_asym_algo: self.common.negotiate_info.base_asym_sel,
+            measurement_hash_algo: self.common.negotiate_info.measurement_hash_sel,
+            slot_id,
+            leaf_cert_size: leaf_cert_size as u16,
+            measurement_record: measurement_record.clone(),
+            requester_nonce,
+            l1l2,
+            signature,
+            ..Default::default()
+        };
+        report.leaf_cert[..leaf_cert_size]
+            .copy_from_slice(&cert_chain_data[leaf_cert_begin..leaf_cert_end]);
+        self.verify_device_report(&report)?;
+        Ok(report)
+    }
+
+    /// Verify the responder signature carried by report over its L1/L2
+    /// transcript, the same way as the signature of the MEASUREMENTS response,
+    /// and that the requester nonce and the measurement record are signed.
+    pub fn verify_device_report(&self, report: &SpdmDeviceReport) -> SpdmResult {
+        if report.spdm_version.get_u8() < SpdmVersion::SpdmVersion12.get_u8()
+            || report.leaf_cert_size as usize > config::MAX_SPDM_CERT_CHAIN_DATA_SIZE
+        {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        let l1l2 = report.l1l2.as_ref();
+        let measurement_record = &report.measurement_record.measurement_record_data
+            [..report.measurement_record.measurement_record_length.get() as usize];
+        if !contains(l1l2, &report.requester_nonce.data) || !contains(l1l2, measurement_record) {
+            return Err(SPDM_STATUS_VERIF_FAIL);
+        }
+        let l1l2_hash =
+            crypto::hash::hash_all(report.base_hash_algo, l1l2).ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+
+        let mut message_sign = ManagedBuffer12Sign::default();
+        message_sign
+            .append_message(report.spdm_version.get_signing_prefix_context())
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        message_sign
+            .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_6)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        message_sign
+            .append_message(&SPDM_MEASUREMENTS_SIGN_CONTEXT)
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        message_sign
+            .append_message(l1l2_hash.as_ref())
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+
+        crypto::asym_verify::verify(
+            report.base_hash_algo,
+            report.base_asym_algo,
+            &report.leaf_cert[..report.leaf_cert_size as usize],
+            message_sign.as_ref(),
+            &report.signature,
+        )
+    }
+}
+
+fn contains(data: &[u8], part: &[u8]) -> bool {
+    part.is_empty() || data.windows(part.len()).any(|window| window == part)
+}
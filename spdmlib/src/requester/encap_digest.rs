@@ -92,6 +92,7 @@ impl<'a> RequesterContext<'a> {
                     },
                     SPDM_MAX_SLOT_NUMBER,
                 ),
+                ..Default::default()
             }),
         };
 
@@ -94,12 +94,12 @@ impl<'a> RequesterContext<'a> {
         };
         let _ = get_encap_request.spdm_encode(&mut self.common, &mut writer)?;
 
-        self.send_secured_message(session_id, writer.mut_used_slice(), false)
+        self.send_encap_message(session_id, writer.mut_used_slice())
     }
 
     pub fn receive_encapsulated_request(&mut self, session_id: u32) -> SpdmResult {
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let _ = self.receive_secured_message(session_id, &mut receive_buffer, false)?;
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let _ = self.receive_encap_message(session_id, &mut receive_buffer)?;
         let mut reader = Reader::init(&receive_buffer);
 
         let header = SpdmMessageHeader::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_SIZE)?;
@@ -114,16 +114,18 @@ impl<'a> RequesterContext<'a> {
             SpdmEncapsulatedRequestPayload::spdm_read(&mut self.common, &mut reader)
                 .ok_or(SPDM_STATUS_INVALID_MSG_SIZE)?;
 
-        self.process_encapsulated_request(
+        let result = self.process_encapsulated_request(
             session_id,
             encapsulated_request.request_id,
             &receive_buffer[reader.used()..],
-        )
+        );
+        self.common.free_buffer(receive_buffer);
+        result
     }
 
     pub fn receive_encapsulated_response_ack(&mut self, session_id: u32) -> SpdmResult<bool> {
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let size = self.receive_secured_message(session_id, &mut receive_buffer, false)?;
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let size = self.receive_encap_message(session_id, &mut receive_buffer)?;
         let mut reader = Reader::init(&receive_buffer);
 
         let header = SpdmMessageHeader::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_SIZE)?;
@@ -169,6 +171,7 @@ impl<'a> RequesterContext<'a> {
             ack_header.request_id,
             &receive_buffer[reader.used()..],
         )?;
+        self.common.free_buffer(receive_buffer);
 
         Ok(true)
     }
@@ -180,7 +183,7 @@ impl<'a> RequesterContext<'a> {
         encap_request: &[u8],
     ) -> SpdmResult {
         let mut reader = Reader::init(encap_request);
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let mut writer = Writer::init(&mut send_buffer);
 
         let message = SpdmMessage {
@@ -212,6 +215,30 @@ impl<'a> RequesterContext<'a> {
             ),
         }
 
-        self.send_secured_message(session_id, writer.used_slice(), false)
+        let used = writer.used();
+        let result = self.send_encap_message(session_id, &send_buffer[..used]);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    // The encapsulated flow of a handshake in the clear is not secured either.
+    fn send_encap_message(&mut self, session_id: u32, send_buffer: &[u8]) -> SpdmResult {
+        if self.common.is_session_handshake_in_clear(session_id) {
+            self.send_message(send_buffer)
+        } else {
+            self.send_secured_message(session_id, send_buffer, false)
+        }
+    }
+
+    fn receive_encap_message(
+        &mut self,
+        session_id: u32,
+        receive_buffer: &mut [u8],
+    ) -> SpdmResult<usize> {
+        if self.common.is_session_handshake_in_clear(session_id) {
+            self.receive_message(receive_buffer, false)
+        } else {
+            self.receive_secured_message(session_id, receive_buffer, false)
+        }
     }
 }
@@ -18,13 +18,16 @@ impl<'a> RequesterContext<'a> {
             Some(session_id),
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let used = self.encode_spdm_end_session(&mut send_buffer)?;
         self.send_secured_message(session_id, &send_buffer[..used], false)?;
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = self.receive_secured_message(session_id, &mut receive_buffer, false)?;
-        self.handle_spdm_end_session_response(session_id, &receive_buffer[..used])
+        let result = self.handle_spdm_end_session_response(session_id, &receive_buffer[..used]);
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
     }
 
     pub fn encode_spdm_end_session(&mut self, buf: &mut [u8]) -> SpdmResult<usize> {
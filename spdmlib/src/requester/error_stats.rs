@@ -0,0 +1,150 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::message::*;
+use crate::requester::*;
+
+use conquer_once::spin::OnceCell;
+
+/// ERROR(Busy) and ERROR(ResponseNotReady) received on one connection.
+/// The consecutive counts are reset by any other response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmErrorResponseStats {
+    pub response_count: u32,
+    pub busy_count: u32,
+    pub not_ready_count: u32,
+    pub consecutive_busy_count: u32,
+    pub consecutive_not_ready_count: u32,
+}
+
+/// Lets the caller tell a slow but healthy responder from a wedged one.
+/// threshold_exceeded_cb is called once the consecutive count of
+/// error_code reaches its threshold, and again only after the streak is
+/// broken by another response. A threshold of 0 disables the callback.
+#[derive(Clone, Copy)]
+pub struct SpdmErrorResponsePolicy {
+    pub busy_threshold: u32,
+    pub not_ready_threshold: u32,
+    pub threshold_exceeded_cb: fn(error_code: SpdmErrorCode, stats: &SpdmErrorResponseStats),
+}
+
+static ERROR_RESPONSE_POLICY: OnceCell<SpdmErrorResponsePolicy> = OnceCell::uninit();
+
+pub fn register_error_response_policy(context: SpdmErrorResponsePolicy) -> bool {
+    ERROR_RESPONSE_POLICY.try_init_once(|| context).is_ok()
+}
+
+impl SpdmErrorResponseStats {
+    /// Account one response, return the error code whose threshold is reached.
+    fn update(
+        &mut self,
+        error_code: Option<SpdmErrorCode>,
+        busy_threshold: u32,
+        not_ready_threshold: u32,
+    ) -> Option<SpdmErrorCode> {
+        self.response_count = self.response_count.saturating_add(1);
+        match error_code {
+            Some(SpdmErrorCode::SpdmErrorBusy) => {
+                self.busy_count = self.busy_count.saturating_add(1);
+                self.consecutive_busy_count = self.consecutive_busy_count.saturating_add(1);
+                self.consecutive_not_ready_count = 0;
+                if busy_threshold != 0 && self.consecutive_busy_count == busy_threshold {
+                    return Some(SpdmErrorCode::SpdmErrorBusy);
+                }
+            }
+            Some(SpdmErrorCode::SpdmErrorResponseNotReady) => {
+                self.not_ready_count = self.not_ready_count.saturating_add(1);
+                self.consecutive_not_ready_count =
+                    self.consecutive_not_ready_count.saturating_add(1);
+                self.consecutive_busy_count = 0;
+                if not_ready_threshold != 0
+                    && self.consecutive_not_ready_count == not_ready_threshold
+                {
+                    return Some(SpdmErrorCode::SpdmErrorResponseNotReady);
+                }
+            }
+            _ => {
+                self.consecutive_busy_count = 0;
+                self.consecutive_not_ready_count = 0;
+            }
+        }
+        None
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    pub fn get_error_response_stats(&self) -> &SpdmErrorResponseStats {
+        &self.error_response_stats
+    }
+
+    pub fn reset_error_response_stats(&mut self) {
+        self.error_response_stats = SpdmErrorResponseStats::default();
+    }
+
+    /// Account a received response and notify the registered policy.
+    pub(crate) fn update_error_response_stats(&mut self, receive_buffer: &[u8]) {
+        let mut reader = Reader::init(receive_buffer);
+        let error_code = match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header)
+                if message_header.request_response_code
+                    == SpdmRequestResponseCode::SpdmResponseError =>
+            {
+                SpdmErrorCode::read(&mut reader)
+            }
+            _ => None,
+        };
+
+        let (busy_threshold, not_ready_threshold) = match ERROR_RESPONSE_POLICY.try_get() {
+            Ok(policy) => (policy.busy_threshold, policy.not_ready_threshold),
+            Err(_) => (0, 0),
+        };
+        let exceeded =
+            self.error_response_stats
+                .update(error_code, busy_threshold, not_ready_threshold);
+        if let (Some(error_code), Ok(policy)) = (exceeded, ERROR_RESPONSE_POLICY.try_get()) {
+            warn!(
+                "!!! error response threshold reached : {:?} !!!\n",
+                error_code
+            );
+            (policy.threshold_exceeded_cb)(error_code, &self.error_response_stats);
+        }
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_error_response_stats_update() {
+        let mut stats = SpdmErrorResponseStats::default();
+        let busy = Some(SpdmErrorCode::SpdmErrorBusy);
+        let not_ready = Some(SpdmErrorCode::SpdmErrorResponseNotReady);
+
+        assert_eq!(stats.update(busy, 2, 0), None);
+        assert_eq!(stats.update(busy, 2, 0), busy);
+        // reported once per streak
+        assert_eq!(stats.update(busy, 2, 0), None);
+        assert_eq!(stats.consecutive_busy_count, 3);
+
+        assert_eq!(stats.update(not_ready, 2, 0), None);
+        assert_eq!(stats.consecutive_busy_count, 0);
+        assert_eq!(stats.update(not_ready, 2, 0), None);
+
+        assert_eq!(stats.update(None, 2, 0), None);
+        assert_eq!(stats.update(busy, 2, 0), None);
+        assert_eq!(stats.update(busy, 2, 0), busy);
+
+        assert_eq!(
+            stats,
+            SpdmErrorResponseStats {
+                response_count: 8,
+                busy_count: 5,
+                not_ready_count: 2,
+                consecutive_busy_count: 2,
+                consecutive_not_ready_count: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{
+    SpdmResult, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD,
+    SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use crate::message::*;
+use crate::protocol::*;
+use crate::requester::*;
+
+use conquer_once::spin::OnceCell;
+
+/// Consumer of the events the responder delivers with SEND_EVENT
+/// after SUBSCRIBE_EVENT_TYPES.
+#[derive(Clone, Copy)]
+pub struct SpdmEventHandler {
+    pub event_cb: fn(session_id: u32, event: &SpdmEventData) -> SpdmResult,
+}
+
+static EVENT_HANDLER: OnceCell<SpdmEventHandler> = OnceCell::uninit();
+
+pub fn register_event_handler(context: SpdmEventHandler) -> bool {
+    EVENT_HANDLER.try_init_once(|| context).is_ok()
+}
+
+fn event_handler(session_id: u32, event: &SpdmEventData) -> SpdmResult {
+    match EVENT_HANDLER.try_get() {
+        Ok(handler) => (handler.event_cb)(session_id, event),
+        Err(_) => Err(SPDM_STATUS_UNSUPPORTED_CAP),
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    fn check_event_cap(&self) -> SpdmResult {
+        if self.common.negotiate_info.spdm_version_sel.get_u8()
+            < SpdmVersion::SpdmVersion13.get_u8()
+            || !self
+                .common
+                .negotiate_info
+                .rsp_capabilities_sel
+                .contains(SpdmResponseCapabilityFlags::EVENT_CAP)
+        {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+        Ok(())
+    }
+
+    pub fn send_receive_spdm_supported_event_types(
+        &mut self,
+        session_id: u32,
+    ) -> SpdmResult<SpdmSupportedEventTypesResponsePayload> {
+        info!("send spdm get_supported_event_types\n");
+        self.check_event_cap()?;
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestGetSupportedEventTypes,
+            Some(session_id),
+        );
+
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let mut writer = Writer::init(&mut send_buffer);
+        let request = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmRequestGetSupportedEventTypes,
+            },
+            payload: SpdmMessagePayload::SpdmGetSupportedEventTypesRequest(
+                SpdmGetSupportedEventTypesRequestPayload {},
+            ),
+        };
+        let used = request.spdm_encode(&mut self.common, &mut writer)?;
+        self.send_secured_message(session_id, &send_buffer[..used], false)?;
+
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let used = self.receive_secured_message(session_id, &mut receive_buffer, false)?;
+        let result =
+            self.handle_spdm_supported_event_types_response(session_id, &receive_buffer[..used]);
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    fn handle_spdm_supported_event_types_response(
+        &mut self,
+        session_id: u32,
+        receive_buffer: &[u8],
+    ) -> SpdmResult<SpdmSupportedEventTypesResponsePayload> {
+        let mut reader = Reader::init(receive_buffer);
+        match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => {
+                if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                    return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                }
+                match message_header.request_response_code {
+                    SpdmRequestResponseCode::SpdmResponseSupportedEventTypes => {
+                        let supported_event_types =
+                            SpdmSupportedEventTypesResponsePayload::spdm_read(
+                                &mut self.common,
+                                &mut reader,
+                            );
+                        if let Some(supported_event_types) = supported_event_types {
+                            debug!(
+                                "!!! supported_event_types : {:02x?}\n",
+                                supported_event_types.event_group_count
+                            );
+                            Ok(supported_event_types)
+                        } else {
+                            error!("!!! supported_event_types : fail !!!\n");
+                            Err(SPDM_STATUS_INVALID_MSG_FIELD)
+                        }
+                    }
+                    SpdmRequestResponseCode::SpdmResponseError => {
+                        let status = self.spdm_handle_error_response_main(
+                            Some(session_id),
+                            receive_buffer,
+                            SpdmRequestResponseCode::SpdmRequestGetSupportedEventTypes,
+                            SpdmRequestResponseCode::SpdmResponseSupportedEventTypes,
+                        );
+                        match status {
+                            Err(status) => Err(status),
+                            Ok(()) => Err(SPDM_STATUS_ERROR_PEER),
+                        }
+                    }
+                    _ => Err(SPDM_STATUS_ERROR_PEER),
+                }
+            }
+            None => Err(SPDM_STATUS_INVALID_MSG_FIELD),
+        }
+    }
+
+    /// Subscribe to event_groups, replacing the previous subscription.
+    /// An empty event_groups unsubscribes from all events.
+    pub fn send_receive_spdm_subscribe_event_types(
+        &mut self,
+        session_id: u32,
+        event_groups: &[SpdmEventGroup],
+    ) -> SpdmResult {
+        info!("send spdm subscribe_event_types\n");
+        self.check_event_cap()?;
+        if event_groups.len() > MAX_SPDM_EVENT_GROUP_COUNT {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestSubscribeEventTypes,
+            Some(session_id),
+        );
+
+        let mut payload = SpdmSubscribeEventTypesRequestPayload {
+            event_group_count: event_groups.len() as u8,
+            ..Default::default()
+        };
+        payload.event_groups[..event_groups.len()].clone_from_slice(event_groups);
+
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let mut writer = Writer::init(&mut send_buffer);
+        let request = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmRequestSubscribeEventTypes,
+            },
+            payload: SpdmMessagePayload::SpdmSubscribeEventTypesRequest(payload),
+        };
+        let used = request.spdm_encode(&mut self.common, &mut writer)?;
+        self.send_secured_message(session_id, &send_buffer[..used], false)?;
+
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let used = self.receive_secured_message(session_id, &mut receive_buffer, false)?;
+        let result = self
+            .handle_spdm_subscribe_event_types_ack_response(session_id, &receive_buffer[..used]);
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    fn handle_spdm_subscribe_event_types_ack_response(
+        &mut self,
+        session_id: u32,
+        receive_buffer: &[u8],
+    ) -> SpdmResult {
+        let mut reader = Reader::init(receive_buffer);
+        match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => {
+                if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                    return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                }
+                match message_header.request_response_code {
+                    SpdmRequestResponseCode::SpdmResponseSubscribeEventTypesAck => {
+                        if SpdmSubscribeEventTypesAckResponsePayload::spdm_read(
+                            &mut self.common,
+                            &mut reader,
+                        )
+                        .is_some()
+                        {
+                            Ok(())
+                        } else {
+                            error!("!!! subscribe_event_types_ack : fail !!!\n");
+                            Err(SPDM_STATUS_INVALID_MSG_FIELD)
+                        }
+                    }
+                    SpdmRequestResponseCode::SpdmResponseError => self
+                        .spdm_handle_error_response_main(
+                            Some(session_id),
+                            receive_buffer,
+                            SpdmRequestResponseCode::SpdmRequestSubscribeEventTypes,
+                            SpdmRequestResponseCode::SpdmResponseSubscribeEventTypesAck,
+                        ),
+                    _ => Err(SPDM_STATUS_ERROR_PEER),
+                }
+            }
+            None => Err(SPDM_STATUS_INVALID_MSG_FIELD),
+        }
+    }
+
+    /// Hand each event of a SEND_EVENT received in session_id to the
+    /// registered event handler, then reply EVENT_ACK.
+    pub fn handle_spdm_send_event(&mut self, session_id: u32, message: &[u8]) -> SpdmResult {
+        let mut reader = Reader::init(message);
+        let message_header =
+            SpdmMessageHeader::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        if message_header.version != self.common.negotiate_info.spdm_version_sel
+            || message_header.request_response_code != SpdmRequestResponseCode::SpdmRequestSendEvent
+        {
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        let send_event = SpdmSendEventRequestPayload::spdm_read(&mut self.common, &mut reader)
+            .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        for event in send_event.events() {
+            debug!(
+                "!!! event : {:08x?} type {:04x?}\n",
+                event.event_instance_id, event.event_type_id
+            );
+            event_handler(session_id, event)?;
+        }
+
+        info!("send spdm event_ack\n");
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let mut writer = Writer::init(&mut send_buffer);
+        let response = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseEventAck,
+            },
+            payload: SpdmMessagePayload::SpdmEventAckResponse(SpdmEventAckResponsePayload {}),
+        };
+        let used = response.spdm_encode(&mut self.common, &mut writer)?;
+        let result = self.send_secured_message(session_id, &send_buffer[..used], false);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+}
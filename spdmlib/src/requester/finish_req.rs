@@ -30,10 +30,10 @@ impl<'a> RequesterContext<'a> {
         info!("in_clear_text {:?}\n", in_clear_text);
 
         let req_slot_id = if let Some(req_slot_id) = req_slot_id {
-            if req_slot_id >= SPDM_MAX_SLOT_NUMBER as u8 {
+            if req_slot_id != SPDM_PUB_KEY_SLOT_ID && req_slot_id >= SPDM_MAX_SLOT_NUMBER as u8 {
                 return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
             }
-            if self.common.provision_info.my_cert_chain[req_slot_id as usize].is_none() {
+            if self.common.get_local_cert_chain_data(req_slot_id).is_none() {
                 return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
             }
             req_slot_id
@@ -50,7 +50,7 @@ impl<'a> RequesterContext<'a> {
             Some(session_id),
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let res = self.encode_spdm_finish(session_id, req_slot_id, &mut send_buffer);
         if res.is_err() {
             let _ = self
@@ -75,7 +75,7 @@ impl<'a> RequesterContext<'a> {
             return res;
         }
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let res = if in_clear_text {
             self.receive_message(&mut receive_buffer, false)
         } else {
@@ -95,11 +95,14 @@ impl<'a> RequesterContext<'a> {
             req_slot_id,
             &receive_buffer[..receive_used],
         );
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
         if res.is_err() {
             if let Some(session) = self.common.get_session_via_id(session_id) {
                 let _ = session.teardown(session_id);
             }
         }
+        self.notify_requester_state();
         res
     }
 
@@ -328,7 +331,12 @@ impl<'a> RequesterContext<'a> {
         {
             transcript_sign.reset_message();
             transcript_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             transcript_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_12)
@@ -341,12 +349,14 @@ impl<'a> RequesterContext<'a> {
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         }
 
-        crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            transcript_sign.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+        self.common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                transcript_sign.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
     }
 
     #[cfg(feature = "hashed-transcript-data")]
@@ -367,7 +377,12 @@ impl<'a> RequesterContext<'a> {
         {
             transcript_sign.reset_message();
             transcript_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             transcript_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_12)
@@ -383,31 +398,25 @@ impl<'a> RequesterContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
-        let signature = crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            transcript_sign.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
-
-        let peer_slot_id = self.common.runtime_info.get_local_used_cert_chain_slot_id();
-        let peer_cert = &self.common.provision_info.my_cert_chain[peer_slot_id as usize]
-            .as_ref()
-            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-            .data[(4usize + self.common.negotiate_info.base_hash_sel.get_size() as usize)
-            ..(self.common.peer_info.peer_cert_chain[peer_slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
-
-        crate::crypto::asym_verify::verify(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            peer_cert,
-            transcript_sign.as_ref(),
-            &signature,
-        )
-        .unwrap();
+        let signature = self
+            .common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                transcript_sign.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+
+        let my_slot_id = self.common.runtime_info.get_local_used_cert_chain_slot_id();
+        let my_cert = self
+            .common
+            .get_local_public_cert_der(my_slot_id)
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+
+        self.common
+            .verify_asym_signature(my_slot_id, my_cert, transcript_sign.as_ref(), &signature)
+            .unwrap();
 
         Ok(signature)
     }
@@ -14,13 +14,21 @@ impl<'a> RequesterContext<'a> {
             None,
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let send_used = self.encode_spdm_capability(&mut send_buffer)?;
         self.send_message(&send_buffer[..send_used])?;
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = self.receive_message(&mut receive_buffer, false)?;
-        self.handle_spdm_capability_response(0, &send_buffer[..send_used], &receive_buffer[..used])
+        self.handle_spdm_capability_response(
+            0,
+            &send_buffer[..send_used],
+            &receive_buffer[..used],
+        )?;
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        self.advance_requester_state(SpdmRequesterState::AfterCapabilities);
+        Ok(())
     }
 
     pub fn encode_spdm_capability(&mut self, buf: &mut [u8]) -> SpdmResult<usize> {
@@ -69,6 +77,7 @@ impl<'a> RequesterContext<'a> {
                                 self.common.config_info.req_capabilities;
                             self.common.negotiate_info.rsp_ct_exponent_sel =
                                 capabilities.ct_exponent;
+                            crate::common::timing::check_peer_ct_exponent(capabilities.ct_exponent);
                             self.common.negotiate_info.rsp_capabilities_sel = capabilities.flags;
 
                             if self.common.negotiate_info.spdm_version_sel.get_u8()
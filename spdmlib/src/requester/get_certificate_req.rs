@@ -6,11 +6,18 @@ use crate::crypto;
 use crate::error::{
     SpdmResult, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_CERT,
     SPDM_STATUS_INVALID_MSG_FIELD, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_INVALID_STATE_LOCAL,
+    SPDM_STATUS_UNSUPPORTED_CAP,
 };
 use crate::message::*;
 use crate::protocol::*;
 use crate::requester::*;
 
+/// Upper bound of GET_CERTIFICATE requests kept in flight when pipelining.
+pub const MAX_SPDM_CERT_PIPELINE_DEPTH: usize = 8;
+
+/// GET_CERTIFICATE request: header (4) + offset (2) + length (2)
+const SPDM_GET_CERTIFICATE_REQUEST_SIZE: usize = 8;
+
 impl<'a> RequesterContext<'a> {
     fn send_receive_spdm_certificate_partial(
         &mut self,
@@ -21,7 +28,7 @@ impl<'a> RequesterContext<'a> {
         length: u16,
     ) -> SpdmResult<(u16, u16)> {
         info!("send spdm certificate\n");
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let send_used =
             self.encode_spdm_certificate_partial(slot_id, offset, length, &mut send_buffer)?;
 
@@ -34,7 +41,7 @@ impl<'a> RequesterContext<'a> {
             }
         }
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = match session_id {
             Some(session_id) => {
                 self.receive_secured_message(session_id, &mut receive_buffer, false)?
@@ -42,7 +49,7 @@ impl<'a> RequesterContext<'a> {
             None => self.receive_message(&mut receive_buffer, false)?,
         };
 
-        self.handle_spdm_certificate_partial_response(
+        let result = self.handle_spdm_certificate_partial_response(
             session_id,
             slot_id,
             total_size,
@@ -50,7 +57,134 @@ impl<'a> RequesterContext<'a> {
             length,
             &send_buffer[..send_used],
             &receive_buffer[..used],
-        )
+        );
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    /// Issue up to max_outstanding_requests GET_CERTIFICATE requests back to back,
+    /// then handle the responses in request order so that message_b keeps
+    /// the same request/response pairing as the responder.
+    ///
+    /// Return the offset up to which the chain has been received without a gap.
+    /// On error the responses still in flight are drained before returning.
+    fn send_receive_spdm_certificate_pipelined(
+        &mut self,
+        session_id: Option<u32>,
+        slot_id: u8,
+        total_size: u16,
+        offset: u16,
+    ) -> SpdmResult<u16> {
+        let depth = core::cmp::min(
+            self.common.config_info.max_outstanding_requests as usize,
+            MAX_SPDM_CERT_PIPELINE_DEPTH,
+        );
+        let mut send_buffers =
+            [[0u8; SPDM_GET_CERTIFICATE_REQUEST_SIZE]; MAX_SPDM_CERT_PIPELINE_DEPTH];
+        let mut requests = [(0u16, 0u16, 0usize); MAX_SPDM_CERT_PIPELINE_DEPTH];
+        let mut count = 0usize;
+
+        let mut next_offset = offset;
+        while count < depth && next_offset < total_size {
+            let length = core::cmp::min(total_size - next_offset, MAX_SPDM_CERT_PORTION_LEN as u16);
+            info!("send spdm certificate\n");
+            let send_used = self.encode_spdm_certificate_partial(
+                slot_id,
+                next_offset,
+                length,
+                &mut send_buffers[count],
+            )?;
+            let result = match session_id {
+                Some(session_id) => {
+                    self.send_secured_message(session_id, &send_buffers[count][..send_used], false)
+                }
+                None => self.send_message(&send_buffers[count][..send_used]),
+            };
+            if let Err(e) = result {
+                self.drain_spdm_certificate_pipelined(session_id, count);
+                return Err(e);
+            }
+            requests[count] = (next_offset, length, send_used);
+            next_offset += length;
+            count += 1;
+        }
+
+        let mut received_offset = offset;
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        for (index, (send_buffer, (offset, length, send_used))) in send_buffers
+            .iter()
+            .zip(requests.iter())
+            .take(count)
+            .enumerate()
+        {
+            let used = match session_id {
+                Some(session_id) => {
+                    self.receive_secured_message(session_id, &mut receive_buffer, false)
+                }
+                None => self.receive_message(&mut receive_buffer, false),
+            };
+            let used = match used {
+                Ok(used) => used,
+                Err(e) => {
+                    // The responses left in flight can no longer be paired.
+                    let _ = self.common.device_io.flush_all();
+                    self.common.free_buffer(receive_buffer);
+                    return Err(e);
+                }
+            };
+
+            let result = self.handle_spdm_certificate_partial_response(
+                session_id,
+                slot_id,
+                total_size,
+                *offset,
+                *length,
+                &send_buffer[..*send_used],
+                &receive_buffer[..used],
+            );
+            let portion_length = match result {
+                Ok((portion_length, _)) => portion_length,
+                Err(e) => {
+                    self.common.free_buffer(receive_buffer);
+                    self.drain_spdm_certificate_pipelined(session_id, count - index - 1);
+                    return Err(e);
+                }
+            };
+            // A short portion leaves a gap, the rest of the window is refetched later.
+            if *offset == received_offset {
+                received_offset += portion_length;
+            }
+        }
+        self.common.free_buffer(receive_buffer);
+
+        if received_offset == offset {
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        Ok(received_offset)
+    }
+
+    /// Receive and drop the responses to outstanding pipelined requests, so
+    /// that the next request is not paired with a stale response.
+    /// The transport is flushed if one of them does not arrive.
+    fn drain_spdm_certificate_pipelined(&mut self, session_id: Option<u32>, outstanding: usize) {
+        if outstanding == 0 {
+            return;
+        }
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        for _ in 0..outstanding {
+            let result = match session_id {
+                Some(session_id) => {
+                    self.receive_secured_message(session_id, &mut receive_buffer, false)
+                }
+                None => self.receive_message(&mut receive_buffer, false),
+            };
+            if result.is_err() {
+                let _ = self.common.device_io.flush_all();
+                break;
+            }
+        }
+        self.common.free_buffer(receive_buffer);
     }
 
     pub fn encode_spdm_certificate_partial(
@@ -88,6 +222,50 @@ impl<'a> RequesterContext<'a> {
         send_buffer: &[u8],
         receive_buffer: &[u8],
     ) -> SpdmResult<(u16, u16)> {
+        if self.common.peer_info.peer_cert_chain_temp.is_none() {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        let certificate = self.read_spdm_certificate_portion(
+            session_id,
+            slot_id,
+            total_size,
+            offset,
+            length,
+            config::MAX_SPDM_CERT_CHAIN_DATA_SIZE,
+            send_buffer,
+            receive_buffer,
+        )?;
+
+        let peer_cert_chain_temp = self
+            .common
+            .peer_info
+            .peer_cert_chain_temp
+            .as_mut()
+            .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+
+        peer_cert_chain_temp.data
+            [(offset as usize)..(offset as usize + certificate.portion_length as usize)]
+            .copy_from_slice(&certificate.cert_chain[0..(certificate.portion_length as usize)]);
+
+        peer_cert_chain_temp.data_size = offset + certificate.portion_length;
+
+        Ok((certificate.portion_length, certificate.remainder_length))
+    }
+
+    /// Check a CERTIFICATE response against its request and record both in
+    /// message_b, max_size bounds the whole cert chain.
+    #[allow(clippy::too_many_arguments)]
+    fn read_spdm_certificate_portion(
+        &mut self,
+        session_id: Option<u32>,
+        slot_id: u8,
+        total_size: u16,
+        offset: u16,
+        length: u16,
+        max_size: usize,
+        send_buffer: &[u8],
+        receive_buffer: &[u8],
+    ) -> SpdmResult<SpdmCertificateResponsePayload> {
         let mut reader = Reader::init(receive_buffer);
         match SpdmMessageHeader::read(&mut reader) {
             Some(message_header) => {
@@ -104,24 +282,24 @@ impl<'a> RequesterContext<'a> {
                         if let Some(certificate) = certificate {
                             debug!("!!! certificate : {:02x?}\n", certificate);
 
-                            if certificate.portion_length > length
-                                || certificate.portion_length
-                                    > config::MAX_SPDM_CERT_CHAIN_DATA_SIZE as u16 - offset
+                            let offset = offset as usize;
+                            let portion_length = certificate.portion_length as usize;
+                            if portion_length > length as usize
+                                || offset > max_size
+                                || portion_length > max_size - offset
                             {
                                 return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                             }
-                            if certificate.remainder_length
-                                >= config::MAX_SPDM_CERT_CHAIN_DATA_SIZE as u16
-                                    - offset
-                                    - certificate.portion_length
+                            if certificate.remainder_length as usize
+                                >= max_size - offset - portion_length
                             {
                                 return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                             }
                             if total_size != 0
-                                && total_size
+                                && total_size as usize
                                     != offset
-                                        + certificate.portion_length
-                                        + certificate.remainder_length
+                                        + portion_length
+                                        + certificate.remainder_length as usize
                             {
                                 return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                             }
@@ -130,22 +308,6 @@ impl<'a> RequesterContext<'a> {
                                 return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                             }
 
-                            let peer_cert_chain_temp = self
-                                .common
-                                .peer_info
-                                .peer_cert_chain_temp
-                                .as_mut()
-                                .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
-
-                            peer_cert_chain_temp.data[(offset as usize)
-                                ..(offset as usize + certificate.portion_length as usize)]
-                                .copy_from_slice(
-                                    &certificate.cert_chain
-                                        [0..(certificate.portion_length as usize)],
-                                );
-
-                            peer_cert_chain_temp.data_size = offset + certificate.portion_length;
-
                             match session_id {
                                 None => {
                                     self.common.append_message_b(send_buffer)?;
@@ -154,7 +316,7 @@ impl<'a> RequesterContext<'a> {
                                 Some(_session_id) => {}
                             }
 
-                            Ok((certificate.portion_length, certificate.remainder_length))
+                            Ok(certificate)
                         } else {
                             error!("!!! certificate : fail !!!\n");
                             Err(SPDM_STATUS_INVALID_MSG_FIELD)
@@ -192,6 +354,16 @@ impl<'a> RequesterContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
+        // the responder public key was provisioned, there is no cert chain
+        if self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP)
+        {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+
         self.common.reset_buffer_via_request_code(
             SpdmRequestResponseCode::SpdmRequestGetCertificate,
             session_id,
@@ -199,6 +371,13 @@ impl<'a> RequesterContext<'a> {
 
         self.common.peer_info.peer_cert_chain_temp = Some(SpdmCertChainBuffer::default());
         while length != 0 {
+            if total_size != 0 && self.common.config_info.max_outstanding_requests > 1 {
+                offset = self.send_receive_spdm_certificate_pipelined(
+                    session_id, slot_id, total_size, offset,
+                )?;
+                length = total_size - offset;
+                continue;
+            }
             let (portion_length, remainder_length) = self.send_receive_spdm_certificate_partial(
                 session_id, slot_id, total_size, offset, length,
             )?;
@@ -220,11 +399,135 @@ impl<'a> RequesterContext<'a> {
         if result.is_ok() {
             self.common.peer_info.peer_cert_chain[slot_id as usize] =
                 self.common.peer_info.peer_cert_chain_temp.clone();
+            self.common.peer_info.peer_cert_chain_streamed[slot_id as usize] = None;
+            self.advance_requester_state(SpdmRequesterState::AfterCertificate);
         }
         self.common.peer_info.peer_cert_chain_temp = None;
         result
     }
 
+    /// Get the cert chain of slot_id verifying it as its portions arrive,
+    /// for chains larger than SpdmCertChainBuffer. Only the leaf certificate
+    /// and the cert chain hash are kept. The cert policy needs the whole
+    /// chain, it cannot be set.
+    #[cfg(feature = "hashed-transcript-data")]
+    pub fn send_receive_spdm_certificate_streaming(
+        &mut self,
+        session_id: Option<u32>,
+        slot_id: u8,
+    ) -> SpdmResult {
+        let mut offset = 0u16;
+        let mut length = MAX_SPDM_CERT_PORTION_LEN as u16;
+        let mut total_size = 0u16;
+
+        if slot_id >= SPDM_MAX_SLOT_NUMBER as u8 {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        if self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP)
+        {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+        if self.common.cert_policy.is_some() {
+            error!("!!! cert policy needs the whole cert chain !!!\n");
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestGetCertificate,
+            session_id,
+        );
+
+        let mut verifier = SpdmCertChainStreamVerifier::new(
+            self.common.negotiate_info.base_hash_sel,
+            self.common
+                .provision_info
+                .peer_root_cert_data
+                .as_ref()
+                .map(|root_cert| &root_cert.data[..root_cert.data_size as usize]),
+        )?;
+        while length != 0 {
+            let (portion_length, remainder_length) = self.send_receive_spdm_certificate_streamed(
+                session_id,
+                slot_id,
+                total_size,
+                offset,
+                length,
+                &mut verifier,
+            )?;
+            if portion_length == 0 && remainder_length != 0 {
+                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+            }
+            if total_size == 0 {
+                total_size = portion_length + remainder_length;
+            }
+            offset += portion_length;
+            length = core::cmp::min(remainder_length, MAX_SPDM_CERT_PORTION_LEN as u16);
+        }
+
+        let streamed = verifier.finish()?;
+        info!("cert_chain verification in portions - pass!\n");
+        self.common.peer_info.peer_cert_chain[slot_id as usize] = None;
+        self.common.peer_info.peer_cert_chain_streamed[slot_id as usize] = Some(streamed);
+        self.advance_requester_state(SpdmRequesterState::AfterCertificate);
+        Ok(())
+    }
+
+    #[cfg(feature = "hashed-transcript-data")]
+    fn send_receive_spdm_certificate_streamed(
+        &mut self,
+        session_id: Option<u32>,
+        slot_id: u8,
+        total_size: u16,
+        offset: u16,
+        length: u16,
+        verifier: &mut SpdmCertChainStreamVerifier,
+    ) -> SpdmResult<(u16, u16)> {
+        info!("send spdm certificate\n");
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let send_used =
+            self.encode_spdm_certificate_partial(slot_id, offset, length, &mut send_buffer)?;
+
+        match session_id {
+            Some(session_id) => {
+                self.send_secured_message(session_id, &send_buffer[..send_used], false)?;
+            }
+            None => {
+                self.send_message(&send_buffer[..send_used])?;
+            }
+        }
+
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let used = match session_id {
+            Some(session_id) => {
+                self.receive_secured_message(session_id, &mut receive_buffer, false)?
+            }
+            None => self.receive_message(&mut receive_buffer, false)?,
+        };
+
+        let result = self
+            .read_spdm_certificate_portion(
+                session_id,
+                slot_id,
+                total_size,
+                offset,
+                length,
+                u16::MAX as usize,
+                &send_buffer[..send_used],
+                &receive_buffer[..used],
+            )
+            .and_then(|certificate| {
+                verifier.update(&certificate.cert_chain[..certificate.portion_length as usize])?;
+                Ok((certificate.portion_length, certificate.remainder_length))
+            });
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
     pub fn verify_spdm_certificate_chain(&mut self) -> SpdmResult {
         //
         // 1. Verify the integrity of cert chain
@@ -245,7 +548,7 @@ impl<'a> RequesterContext<'a> {
         }
 
         let data_size_in_cert_chain =
-            peer_cert_chain.data[0] as u16 + ((peer_cert_chain.data[1] as u16) << 8);
+            u16::read_bytes(&peer_cert_chain.data[..2]).ok_or(SPDM_STATUS_INVALID_CERT)?;
         if data_size_in_cert_chain != peer_cert_chain.data_size {
             return Err(SPDM_STATUS_INVALID_CERT);
         }
@@ -262,17 +565,37 @@ impl<'a> RequesterContext<'a> {
         info!("1. get runtime_peer_cert_chain_data!\n");
 
         //
-        // 1.1 verify the integrity of the chain
+        // 1.1 verify the integrity of the chain, unless it passed before
         //
-        if crypto::cert_operation::verify_cert_chain(
-            &runtime_peer_cert_chain_data.data[..(runtime_peer_cert_chain_data.data_size as usize)],
-        )
-        .is_err()
+        let cert_chain_data =
+            &runtime_peer_cert_chain_data.data[..(runtime_peer_cert_chain_data.data_size as usize)];
+        let base_hash_algo = self.common.negotiate_info.base_hash_sel;
+        let cert_chain_hash = crypto::hash::hash_all(base_hash_algo, cert_chain_data)
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+        // a cert policy may change, only the plain verification is cached
+        if self.common.cert_policy.is_none()
+            && self.cert_verify_cache.lookup(
+                base_hash_algo,
+                cert_chain_hash.as_ref(),
+                cert_verify_cache::get_wall_clock_time(),
+            )
         {
-            error!("cert_chain verification - fail! - TBD later\n");
-            return Err(SPDM_STATUS_INVALID_CERT);
+            info!("1.1. integrity of cert_chain is verified before!\n");
+        } else {
+            if self.common.verify_peer_cert_chain(cert_chain_data).is_err() {
+                error!("cert_chain verification - fail! - TBD later\n");
+                return Err(SPDM_STATUS_INVALID_CERT);
+            }
+            if let Ok((not_before, not_after)) = crypto::get_cert_chain_validity(cert_chain_data) {
+                self.cert_verify_cache.insert(
+                    base_hash_algo,
+                    cert_chain_hash.as_ref(),
+                    not_before,
+                    not_after,
+                );
+            }
+            info!("1.1. integrity of cert_chain is verified!\n");
         }
-        info!("1.1. integrity of cert_chain is verified!\n");
 
         //
         // 1.2 verify the root cert hash
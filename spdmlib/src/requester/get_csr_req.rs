@@ -0,0 +1,162 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::opaque::MAX_SPDM_OPAQUE_SIZE;
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_TOO_SMALL, SPDM_STATUS_ERROR_PEER,
+    SPDM_STATUS_INVALID_MSG_FIELD, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_RESET_REQUIRED_PEER,
+    SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use crate::message::*;
+use crate::protocol::*;
+use crate::requester::*;
+
+impl<'a> RequesterContext<'a> {
+    /// Ask the responder for a CSR of its device key, copy the DER CSR into csr
+    /// and return its size.
+    ///
+    /// If the responder needs a reset to generate the CSR, the function fails with
+    /// SPDM_STATUS_RESET_REQUIRED_PEER and common.peer_info.peer_csr_tracking_tag
+    /// holds the CSRTrackingTag (SPDM 1.3) to send in GET_CSR after the reset.
+    pub fn send_spdm_get_csr(
+        &mut self,
+        session_id: Option<u32>,
+        csr_tracking_tag: u8,
+        requester_info: &[u8],
+        opaque_data: &[u8],
+        csr: &mut [u8],
+    ) -> SpdmResult<usize> {
+        info!("send spdm get csr\n");
+
+        if self.common.negotiate_info.spdm_version_sel.get_u8()
+            < SpdmVersion::SpdmVersion12.get_u8()
+            || !self
+                .common
+                .negotiate_info
+                .rsp_capabilities_sel
+                .contains(SpdmResponseCapabilityFlags::CSR_CAP)
+        {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+
+        self.common
+            .reset_buffer_via_request_code(SpdmRequestResponseCode::SpdmRequestGetCsr, session_id);
+
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let send_used = self.encode_spdm_get_csr(
+            csr_tracking_tag,
+            requester_info,
+            opaque_data,
+            &mut send_buffer,
+        )?;
+        match session_id {
+            Some(session_id) => {
+                self.send_secured_message(session_id, &send_buffer[..send_used], false)?;
+            }
+            None => {
+                self.send_message(&send_buffer[..send_used])?;
+            }
+        }
+
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let used = match session_id {
+            Some(session_id) => {
+                self.receive_secured_message(session_id, &mut receive_buffer, false)?
+            }
+            None => self.receive_message(&mut receive_buffer, false)?,
+        };
+
+        let result = self.handle_spdm_csr_response(session_id, &receive_buffer[..used], csr);
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    pub fn encode_spdm_get_csr(
+        &mut self,
+        csr_tracking_tag: u8,
+        requester_info: &[u8],
+        opaque_data: &[u8],
+        buf: &mut [u8],
+    ) -> SpdmResult<usize> {
+        if requester_info.len() > MAX_SPDM_CSR_REQUESTER_INFO_SIZE
+            || opaque_data.len() > MAX_SPDM_OPAQUE_SIZE
+        {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        let mut payload = SpdmGetCsrRequestPayload {
+            csr_tracking_tag,
+            requester_info_length: requester_info.len() as u16,
+            ..Default::default()
+        };
+        payload.requester_info[..requester_info.len()].copy_from_slice(requester_info);
+        payload.opaque.data_size = opaque_data.len() as u16;
+        payload.opaque.data[..opaque_data.len()].copy_from_slice(opaque_data);
+
+        let mut writer = Writer::init(buf);
+        let request = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmRequestGetCsr,
+            },
+            payload: SpdmMessagePayload::SpdmGetCsrRequest(payload),
+        };
+        request.spdm_encode(&mut self.common, &mut writer)
+    }
+
+    pub fn handle_spdm_csr_response(
+        &mut self,
+        session_id: Option<u32>,
+        receive_buffer: &[u8],
+        csr: &mut [u8],
+    ) -> SpdmResult<usize> {
+        let mut reader = Reader::init(receive_buffer);
+        match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => {
+                if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                    return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                }
+                match message_header.request_response_code {
+                    SpdmRequestResponseCode::SpdmResponseCsr => {
+                        let csr_response =
+                            SpdmCsrResponsePayload::spdm_read(&mut self.common, &mut reader)
+                                .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+                        let csr_length = csr_response.csr_length as usize;
+                        if csr_length > csr.len() {
+                            return Err(SPDM_STATUS_BUFFER_TOO_SMALL);
+                        }
+                        csr[..csr_length].copy_from_slice(&csr_response.csr[..csr_length]);
+                        Ok(csr_length)
+                    }
+                    SpdmRequestResponseCode::SpdmResponseError => {
+                        let error_response =
+                            SpdmErrorResponsePayload::spdm_read(&mut self.common, &mut reader);
+                        if let Some(error_response) = error_response {
+                            if error_response.error_code == SpdmErrorCode::SpdmErrorResetRequired {
+                                info!("!!! get csr : reset required !!!\n");
+                                self.common.peer_info.peer_csr_tracking_tag =
+                                    error_response.error_data;
+                                return Err(SPDM_STATUS_RESET_REQUIRED_PEER);
+                            }
+                        }
+
+                        let status = self.spdm_handle_error_response_main(
+                            session_id,
+                            receive_buffer,
+                            SpdmRequestResponseCode::SpdmRequestGetCsr,
+                            SpdmRequestResponseCode::SpdmResponseCsr,
+                        );
+                        match status {
+                            Err(status) => Err(status),
+                            Ok(()) => Err(SPDM_STATUS_ERROR_PEER),
+                        }
+                    }
+                    _ => Err(SPDM_STATUS_ERROR_PEER),
+                }
+            }
+            None => Err(SPDM_STATUS_INVALID_MSG_FIELD),
+        }
+    }
+}
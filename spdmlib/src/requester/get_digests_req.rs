@@ -2,20 +2,36 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
-use crate::error::{SpdmResult, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD};
+use crate::error::{
+    SpdmResult, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD, SPDM_STATUS_UNSUPPORTED_CAP,
+};
 use crate::message::*;
+use crate::protocol::{
+    SpdmCertificateModelType, SpdmDigestStruct, SpdmKeyUsageMask, SpdmResponseCapabilityFlags,
+    SPDM_MAX_SLOT_NUMBER,
+};
 use crate::requester::*;
 
 impl<'a> RequesterContext<'a> {
     pub fn send_receive_spdm_digest(&mut self, session_id: Option<u32>) -> SpdmResult {
         info!("send spdm digest\n");
 
+        // the responder public key was provisioned, there is no cert chain
+        if self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP)
+        {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+
         self.common.reset_buffer_via_request_code(
             SpdmRequestResponseCode::SpdmRequestGetDigests,
             session_id,
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let send_used = self.encode_spdm_digest(&mut send_buffer)?;
         match session_id {
             Some(session_id) => {
@@ -26,7 +42,7 @@ impl<'a> RequesterContext<'a> {
             }
         }
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = match session_id {
             Some(session_id) => {
                 self.receive_secured_message(session_id, &mut receive_buffer, false)?
@@ -38,7 +54,11 @@ impl<'a> RequesterContext<'a> {
             session_id,
             &send_buffer[..send_used],
             &receive_buffer[..used],
-        )
+        )?;
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        self.advance_requester_state(SpdmRequesterState::AfterDigest);
+        Ok(())
     }
 
     pub fn encode_spdm_digest(&mut self, buf: &mut [u8]) -> SpdmResult<usize> {
@@ -81,6 +101,30 @@ impl<'a> RequesterContext<'a> {
                                 Some(_session_id) => {}
                             }
 
+                            self.common.peer_info.peer_digest_slot_mask = digests.slot_mask;
+                            let mut index = 0;
+                            for slot_id in 0..SPDM_MAX_SLOT_NUMBER {
+                                if digests.slot_mask & (1 << slot_id) != 0 {
+                                    self.common.peer_info.peer_digests[slot_id] =
+                                        digests.digests[index].clone();
+                                    self.common.peer_info.peer_key_pair_id[slot_id] =
+                                        digests.key_pair_id[index];
+                                    self.common.peer_info.peer_cert_model[slot_id] =
+                                        digests.cert_model[index];
+                                    self.common.peer_info.peer_key_usage_mask[slot_id] =
+                                        digests.key_usage_mask[index];
+                                    index += 1;
+                                } else {
+                                    self.common.peer_info.peer_digests[slot_id] =
+                                        SpdmDigestStruct::default();
+                                    self.common.peer_info.peer_key_pair_id[slot_id] = 0;
+                                    self.common.peer_info.peer_cert_model[slot_id] =
+                                        SpdmCertificateModelType::default();
+                                    self.common.peer_info.peer_key_usage_mask[slot_id] =
+                                        SpdmKeyUsageMask::empty();
+                                }
+                            }
+
                             Ok(())
                         } else {
                             error!("!!! digests : fail !!!\n");
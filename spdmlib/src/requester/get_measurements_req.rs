@@ -13,6 +13,9 @@ use crate::message::*;
 use crate::protocol::*;
 use crate::requester::*;
 
+extern crate alloc;
+use alloc::vec::Vec;
+
 impl<'a> RequesterContext<'a> {
     fn send_receive_spdm_measurement_record(
         &mut self,
@@ -24,7 +27,7 @@ impl<'a> RequesterContext<'a> {
     ) -> SpdmResult<u8> {
         info!("send spdm measurement\n");
 
-        if slot_id >= SPDM_MAX_SLOT_NUMBER as u8 {
+        if !self.common.is_rsp_slot_id_valid(slot_id) {
             return Err(SPDM_STATUS_INVALID_PARAMETER);
         }
 
@@ -33,7 +36,7 @@ impl<'a> RequesterContext<'a> {
             session_id,
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let send_used = self.encode_spdm_measurement_record(
             measurement_attributes,
             measurement_operation,
@@ -50,7 +53,7 @@ impl<'a> RequesterContext<'a> {
         }
 
         // Receive
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = match session_id {
             Some(session_id) => {
                 self.receive_secured_message(session_id, &mut receive_buffer, true)?
@@ -58,7 +61,7 @@ impl<'a> RequesterContext<'a> {
             None => self.receive_message(&mut receive_buffer, true)?,
         };
 
-        self.handle_spdm_measurement_record_response(
+        let result = self.handle_spdm_measurement_record_response(
             session_id,
             slot_id,
             measurement_attributes,
@@ -66,7 +69,10 @@ impl<'a> RequesterContext<'a> {
             spdm_measurement_record_structure,
             &send_buffer[..send_used],
             &receive_buffer[..used],
-        )
+        );
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
     }
 
     pub fn encode_spdm_measurement_record(
@@ -79,6 +85,12 @@ impl<'a> RequesterContext<'a> {
         let mut writer = Writer::init(buf);
         let mut nonce = [0u8; SPDM_NONCE_SIZE];
         crypto::rand::get_random(&mut nonce)?;
+        let mut requester_context = [0u8; SPDM_REQ_CONTEXT_SIZE];
+        if self.common.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion13.get_u8()
+        {
+            crypto::rand::get_random(&mut requester_context)?;
+        }
 
         let request = SpdmMessage {
             header: SpdmMessageHeader {
@@ -91,6 +103,9 @@ impl<'a> RequesterContext<'a> {
                     measurement_operation,
                     nonce: SpdmNonceStruct { data: nonce },
                     slot_id,
+                    requester_context: SpdmReqContextStruct {
+                        data: requester_context,
+                    },
                 },
             ),
         };
@@ -137,6 +152,28 @@ impl<'a> RequesterContext<'a> {
                                     measurements.content_changed;
                             }
 
+                            // Param2 echoes the SlotID of a signed response
+                            if measurement_attributes
+                                .contains(SpdmMeasurementAttributes::SIGNATURE_REQUESTED)
+                                && self.common.negotiate_info.spdm_version_sel.get_u8()
+                                    >= SpdmVersion::SpdmVersion11.get_u8()
+                                && measurements.slot_id != slot_id
+                            {
+                                error!("!!! measurements : slot id mismatch !!!\n");
+                                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                            }
+
+                            // the request context is the last field of the request
+                            if self.common.negotiate_info.spdm_version_sel.get_u8()
+                                >= SpdmVersion::SpdmVersion13.get_u8()
+                                && (send_buffer.len() < SPDM_REQ_CONTEXT_SIZE
+                                    || send_buffer[send_buffer.len() - SPDM_REQ_CONTEXT_SIZE..]
+                                        != measurements.requester_context.data)
+                            {
+                                error!("!!! measurements : requester context mismatch !!!\n");
+                                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                            }
+
                             let base_asym_size =
                                 self.common.negotiate_info.base_asym_sel.get_size() as usize;
                             let temp_used = used
@@ -166,6 +203,13 @@ impl<'a> RequesterContext<'a> {
                                     self.common.reset_message_m(session_id);
                                     return Err(SPDM_STATUS_VERIF_FAIL);
                                 } else {
+                                    self.common.runtime_info.last_verified_l1l2 =
+                                        self.common.export_message_l1l2(session_id).ok();
+                                    // the nonce follows the header of GET_MEASUREMENTS
+                                    self.common.runtime_info.last_verified_measurement_nonce =
+                                        send_buffer.get(4..).and_then(SpdmNonceStruct::read_bytes);
+                                    self.common.runtime_info.last_verified_measurement_signature =
+                                        Some(measurements.signature.clone());
                                     self.common.reset_message_m(session_id);
                                     info!("verify_measurement_signature pass");
                                 }
@@ -228,6 +272,46 @@ impl<'a> RequesterContext<'a> {
         Ok(())
     }
 
+    /// Get the number of measurement blocks, then all of them in a response
+    /// signed by slot_id over L1/L2, and return the parsed blocks.
+    pub fn get_verified_measurements(
+        &mut self,
+        slot_id: u8,
+        session_id: Option<u32>,
+    ) -> SpdmResult<Vec<SpdmMeasurementBlockStructure>> {
+        let mut total_number = 0u8;
+        let mut measurement_record = SpdmMeasurementRecordStructure::default();
+        self.send_receive_spdm_measurement(
+            session_id,
+            slot_id,
+            SpdmMeasurementAttributes::empty(),
+            SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber,
+            &mut total_number,
+            &mut measurement_record,
+        )?;
+
+        let mut number_of_blocks = 0u8;
+        self.send_receive_spdm_measurement(
+            session_id,
+            slot_id,
+            SpdmMeasurementAttributes::SIGNATURE_REQUESTED,
+            SpdmMeasurementOperation::SpdmMeasurementRequestAll,
+            &mut number_of_blocks,
+            &mut measurement_record,
+        )?;
+        if number_of_blocks != total_number {
+            error!(
+                "!!! measurements : {} blocks of {} !!!\n",
+                number_of_blocks, total_number
+            );
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+
+        measurement_record
+            .get_measurement_blocks()
+            .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)
+    }
+
     #[cfg(feature = "hashed-transcript-data")]
     pub fn verify_measurement_signature(
         &self,
@@ -265,19 +349,13 @@ impl<'a> RequesterContext<'a> {
 
         debug!("message_l1l2_hash - {:02x?}", message_l1l2_hash.as_ref());
 
-        if self.common.peer_info.peer_cert_chain[slot_id as usize].is_none() {
-            error!("peer_cert_chain is not populated!\n");
-            return Err(SPDM_STATUS_INVALID_PARAMETER);
-        }
-
-        let cert_chain_data = &self.common.peer_info.peer_cert_chain[slot_id as usize]
-            .as_ref()
-            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-            .data[(4usize + self.common.negotiate_info.base_hash_sel.get_size() as usize)
-            ..(self.common.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(slot_id)
+            .ok_or_else(|| {
+                error!("peer_cert_chain is not populated!\n");
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
 
         let mut message_sign = ManagedBuffer12Sign::default();
         if self.common.negotiate_info.spdm_version_sel.get_u8()
@@ -285,7 +363,12 @@ impl<'a> RequesterContext<'a> {
         {
             message_sign.reset_message();
             message_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_6)
@@ -301,9 +384,10 @@ impl<'a> RequesterContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
-        crypto::asym_verify::verify(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
+        self.verify_peer_signature(
+            SpdmRequestResponseCode::SpdmResponseMeasurements,
+            slot_id,
+            &message_l1l2_hash,
             cert_chain_data,
             message_sign.as_ref(),
             signature,
@@ -357,26 +441,25 @@ impl<'a> RequesterContext<'a> {
         .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
         debug!("message_l1l2_hash - {:02x?}", message_l1l2_hash.as_ref());
 
-        if self.common.peer_info.peer_cert_chain[slot_id as usize].is_none() {
-            error!("peer_cert_chain is not populated!\n");
-            return Err(SPDM_STATUS_INVALID_PARAMETER);
-        }
-
-        let cert_chain_data = &self.common.peer_info.peer_cert_chain[slot_id as usize]
-            .as_ref()
-            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-            .data[(4usize + self.common.negotiate_info.base_hash_sel.get_size() as usize)
-            ..(self.common.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(slot_id)
+            .ok_or_else(|| {
+                error!("peer_cert_chain is not populated!\n");
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
 
         if self.common.negotiate_info.spdm_version_sel.get_u8()
             >= SpdmVersion::SpdmVersion12.get_u8()
         {
             message_l1l2.reset_message();
             message_l1l2
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_l1l2
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_6)
@@ -389,9 +472,10 @@ impl<'a> RequesterContext<'a> {
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         }
 
-        crypto::asym_verify::verify(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
+        self.verify_peer_signature(
+            SpdmRequestResponseCode::SpdmResponseMeasurements,
+            slot_id,
+            &message_l1l2_hash,
             cert_chain_data,
             message_l1l2.as_ref(),
             signature,
@@ -13,14 +13,19 @@ impl<'a> RequesterContext<'a> {
     pub fn send_receive_spdm_version(&mut self) -> SpdmResult {
         // reset context on get version request
         self.common.reset_context();
+        self.set_requester_state(SpdmRequesterState::NotStarted);
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let send_used = self.encode_spdm_version(&mut send_buffer)?;
         self.send_message(&send_buffer[..send_used])?;
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = self.receive_message(&mut receive_buffer, false)?;
-        self.handle_spdm_version_response(0, &send_buffer[..send_used], &receive_buffer[..used])
+        self.handle_spdm_version_response(0, &send_buffer[..send_used], &receive_buffer[..used])?;
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        self.set_requester_state(SpdmRequesterState::AfterVersion);
+        Ok(())
     }
 
     pub fn encode_spdm_version(&mut self, buf: &mut [u8]) -> SpdmResult<usize> {
@@ -56,7 +61,7 @@ impl<'a> RequesterContext<'a> {
                             mut versions,
                         } = version;
 
-                        versions
+                        versions[..version_number_entry_count as usize]
                             .sort_unstable_by(|a, b| b.version.get_u8().cmp(&a.version.get_u8()));
 
                         self.common.negotiate_info.spdm_version_sel = SpdmVersion::Unknown(0);
@@ -64,6 +69,9 @@ impl<'a> RequesterContext<'a> {
                         for spdm_version_struct in
                             versions.iter().take(version_number_entry_count as usize)
                         {
+                            if let SpdmVersion::Unknown(_) = spdm_version_struct.version {
+                                continue;
+                            }
                             if self
                                 .common
                                 .config_info
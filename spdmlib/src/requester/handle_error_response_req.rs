@@ -5,11 +5,14 @@
 use codec::{Codec, Reader};
 
 use crate::common::session::SpdmSessionState;
+use crate::common::SpdmCodec;
 use crate::error::{
     SpdmResult, SPDM_STATUS_BUSY_PEER, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD,
     SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_NOT_READY_PEER, SPDM_STATUS_SESSION_MSG_ERROR,
+    SPDM_STATUS_VENDOR_ERROR_PEER,
 };
 use crate::message::*;
+use crate::requester::connection_state::SpdmRequesterState;
 use crate::requester::RequesterContext;
 
 impl<'a> RequesterContext<'a> {
@@ -33,6 +36,8 @@ impl<'a> RequesterContext<'a> {
                 };
                 session.set_session_state(SpdmSessionState::SpdmSessionNotStarted);
             }
+            // The responder asks to restart from GET_VERSION.
+            self.set_requester_state(SpdmRequesterState::NotStarted);
             Err(SPDM_STATUS_INVALID_PARAMETER)
         } else {
             Err(SPDM_STATUS_ERROR_PEER)
@@ -81,8 +86,34 @@ impl<'a> RequesterContext<'a> {
                 let _ = session.teardown(sid);
             }
             Err(SPDM_STATUS_SESSION_MSG_ERROR)
+        } else if spdm_message_general_payload.param1
+            == SpdmErrorCode::SpdmErrorVendorDefined.get_u8()
+        {
+            self.spdm_handle_vendor_error_response(&response[header_size..])
         } else {
             self.spdm_handle_simple_error_response(session_id, spdm_message_general_payload.param1)
         }
     }
+
+    /// Record the vendor defined error so the application can inspect it via
+    /// common.peer_info.peer_vendor_error.
+    fn spdm_handle_vendor_error_response(&mut self, payload: &[u8]) -> SpdmResult {
+        let mut reader = Reader::init(payload);
+        let error_response = SpdmErrorResponsePayload::spdm_read(&mut self.common, &mut reader)
+            .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        self.common.peer_info.peer_vendor_error =
+            SpdmVendorDefinedError::from_error_response(&error_response);
+        Err(SPDM_STATUS_VENDOR_ERROR_PEER)
+    }
+
+    /// Forget the recorded vendor defined error once the peer answers a
+    /// request successfully, so that it only describes the last failure.
+    pub(crate) fn reset_peer_vendor_error(&mut self, response: &[u8]) {
+        let mut reader = Reader::init(response);
+        if let Some(message_header) = SpdmMessageHeader::read(&mut reader) {
+            if message_header.request_response_code != SpdmRequestResponseCode::SpdmResponseError {
+                self.common.peer_info.peer_vendor_error = None;
+            }
+        }
+    }
 }
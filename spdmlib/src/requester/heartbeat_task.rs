@@ -0,0 +1,184 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Periodic HEARTBEAT of a session. HeartbeatPeriod is in seconds and the
+//! requester has to send a message within it, HEARTBEAT is sent every half
+//! period so the round trip fits in the other half.
+
+#[cfg(feature = "async-io")]
+use core::pin::Pin;
+#[cfg(feature = "async-io")]
+use core::task::{Context, Poll};
+
+#[cfg(feature = "async-io")]
+use futures_core::Stream;
+
+use crate::common::session::{SpdmSessionState, SPDM_HEARTBEAT_PERIOD_UNIT_US};
+#[cfg(feature = "async-io")]
+use crate::error::SPDM_STATUS_UNSUPPORTED_CAP;
+use crate::error::{SpdmResult, SpdmStatus, SPDM_STATUS_INVALID_STATE_LOCAL};
+use crate::protocol::*;
+use crate::requester::*;
+#[cfg(feature = "async-io")]
+use crate::time;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmHeartbeatEvent {
+    /// HEARTBEAT_ACK received, the next HEARTBEAT is due at next_due_us.
+    Acked { next_due_us: u64 },
+    /// No HEARTBEAT_ACK, or the session is no longer established.
+    SessionLost(SpdmStatus),
+}
+
+/// Return the HEARTBEAT interval of the session, None if HBEAT_CAP is not
+/// negotiated or the responder selected a HeartbeatPeriod of 0.
+pub fn heartbeat_interval_us(requester: &RequesterContext, session_id: u32) -> Option<u64> {
+    let negotiate_info = &requester.common.negotiate_info;
+    if !negotiate_info
+        .req_capabilities_sel
+        .contains(SpdmRequestCapabilityFlags::HBEAT_CAP)
+        || !negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::HBEAT_CAP)
+    {
+        return None;
+    }
+    let session = requester.common.get_immutable_session_via_id(session_id)?;
+    if session.heartbeat_period == 0 {
+        return None;
+    }
+    Some(session.heartbeat_period as u64 * SPDM_HEARTBEAT_PERIOD_UNIT_US / 2)
+}
+
+/// Decides when HEARTBEAT is due, for an application which polls it from
+/// its own main loop with the current time.
+#[derive(Debug, Clone)]
+pub struct SpdmHeartbeatTimer {
+    session_id: u32,
+    interval_us: u64,
+    next_due_us: u64,
+}
+
+impl SpdmHeartbeatTimer {
+    pub fn new(requester: &RequesterContext, session_id: u32, now_us: u64) -> Option<Self> {
+        let interval_us = heartbeat_interval_us(requester, session_id)?;
+        Some(SpdmHeartbeatTimer {
+            session_id,
+            interval_us,
+            next_due_us: now_us.saturating_add(interval_us),
+        })
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.session_id
+    }
+
+    pub fn next_due_us(&self) -> u64 {
+        self.next_due_us
+    }
+
+    /// Any other message sent in the session counts as heartbeat too.
+    pub fn reset(&mut self, now_us: u64) {
+        self.next_due_us = now_us.saturating_add(self.interval_us);
+    }
+
+    /// Send HEARTBEAT if it is due at now_us, return None if it is not.
+    pub fn poll(
+        &mut self,
+        requester: &mut RequesterContext,
+        now_us: u64,
+    ) -> Option<SpdmHeartbeatEvent> {
+        if let Err(status) = self.check_established(requester) {
+            return Some(SpdmHeartbeatEvent::SessionLost(status));
+        }
+        if now_us < self.next_due_us {
+            return None;
+        }
+        Some(self.send_heartbeat(requester, now_us))
+    }
+
+    fn check_established(&self, requester: &RequesterContext) -> SpdmResult {
+        match requester
+            .common
+            .get_immutable_session_via_id(self.session_id)
+            .map(|session| session.get_session_state())
+        {
+            Some(SpdmSessionState::SpdmSessionEstablished) => Ok(()),
+            _ => Err(SPDM_STATUS_INVALID_STATE_LOCAL),
+        }
+    }
+
+    fn send_heartbeat(
+        &mut self,
+        requester: &mut RequesterContext,
+        now_us: u64,
+    ) -> SpdmHeartbeatEvent {
+        match requester.send_receive_spdm_heartbeat(self.session_id) {
+            Ok(()) => {
+                self.reset(now_us);
+                SpdmHeartbeatEvent::Acked {
+                    next_due_us: self.next_due_us,
+                }
+            }
+            Err(status) => {
+                error!("!!! heartbeat : session lost {:x?} !!!\n", self.session_id);
+                SpdmHeartbeatEvent::SessionLost(status)
+            }
+        }
+    }
+}
+
+/// Sends HEARTBEAT at the negotiated cadence, each item is one heartbeat and
+/// the stream ends after SessionLost. Until HEARTBEAT is due the stream is
+/// Pending and is woken with the wake_at callback of the registered SpdmTime.
+/// The HEARTBEAT round trip itself is done inline, as SpdmDeviceIo blocks.
+#[cfg(feature = "async-io")]
+pub struct SpdmHeartbeatStream<'r, 'a> {
+    requester: &'r mut RequesterContext<'a>,
+    timer: SpdmHeartbeatTimer,
+    lost: bool,
+}
+
+#[cfg(feature = "async-io")]
+impl<'r, 'a> SpdmHeartbeatStream<'r, 'a> {
+    pub fn new(requester: &'r mut RequesterContext<'a>, session_id: u32) -> SpdmResult<Self> {
+        if !time::is_registered() {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        let timer = SpdmHeartbeatTimer::new(requester, session_id, time::now_us())
+            .ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?;
+        Ok(SpdmHeartbeatStream {
+            requester,
+            timer,
+            lost: false,
+        })
+    }
+
+    /// Give the requester back, e.g. to send other requests in between.
+    pub fn into_inner(self) -> &'r mut RequesterContext<'a> {
+        self.requester
+    }
+}
+
+#[cfg(feature = "async-io")]
+impl<'r, 'a> Stream for SpdmHeartbeatStream<'r, 'a> {
+    type Item = SpdmHeartbeatEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.lost {
+            return Poll::Ready(None);
+        }
+        match this.timer.poll(this.requester, time::now_us()) {
+            Some(event) => {
+                this.lost = matches!(event, SpdmHeartbeatEvent::SessionLost(_));
+                Poll::Ready(Some(event))
+            }
+            None => {
+                time::wake_at(this.timer.next_due_us(), cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
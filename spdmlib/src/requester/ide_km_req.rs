@@ -0,0 +1,90 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD,
+};
+use crate::message::ide_km::*;
+use crate::requester::*;
+
+impl<'a> RequesterContext<'a> {
+    fn send_receive_ide_km(
+        &mut self,
+        session_id: u32,
+        request: &IdeKmMessage,
+    ) -> SpdmResult<IdeKmMessage> {
+        let req_payload = request.to_req_payload().ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        let rsp_payload = self.send_spdm_vendor_defined_request(
+            Some(session_id),
+            IDE_KM_STANDARD_ID,
+            ide_km_vendor_id(),
+            req_payload,
+        )?;
+        let mut reader = Reader::init(
+            &rsp_payload.vendor_defined_rsp_payload[..rsp_payload.rsp_length as usize],
+        );
+        let response = IdeKmMessage::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        if reader.any_left() {
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        Ok(response)
+    }
+
+    /// Return the IDE registers of port_index, 0 being the function itself.
+    pub fn send_receive_ide_km_query(
+        &mut self,
+        session_id: u32,
+        port_index: u8,
+    ) -> SpdmResult<IdeKmQueryResp> {
+        info!("send ide_km query\n");
+        match self.send_receive_ide_km(session_id, &IdeKmMessage::Query { port_index })? {
+            IdeKmMessage::QueryResp(query_resp) if query_resp.port_index == port_index => {
+                Ok(query_resp)
+            }
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    /// Program one key, return the KP_ACK status of the device.
+    pub fn send_receive_ide_km_key_prog(
+        &mut self,
+        session_id: u32,
+        key_index: IdeKmKeyIndex,
+        key: &IdeKmAesKey,
+    ) -> SpdmResult<IdeKmKpAckStatus> {
+        info!("send ide_km key_prog\n");
+        match self
+            .send_receive_ide_km(session_id, &IdeKmMessage::KeyProg(key_index, key.clone()))?
+        {
+            IdeKmMessage::KpAck(ack_index, status) if ack_index == key_index => Ok(status),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    /// Start using the key set of key_index.
+    pub fn send_receive_ide_km_key_set_go(
+        &mut self,
+        session_id: u32,
+        key_index: IdeKmKeyIndex,
+    ) -> SpdmResult {
+        info!("send ide_km k_set_go\n");
+        match self.send_receive_ide_km(session_id, &IdeKmMessage::KSetGo(key_index))? {
+            IdeKmMessage::KGoStopAck(ack_index) if ack_index == key_index => Ok(()),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    /// Stop using the key set of key_index, the device erases its keys.
+    pub fn send_receive_ide_km_key_set_stop(
+        &mut self,
+        session_id: u32,
+        key_index: IdeKmKeyIndex,
+    ) -> SpdmResult {
+        info!("send ide_km k_set_stop\n");
+        match self.send_receive_ide_km(session_id, &IdeKmMessage::KSetStop(key_index))? {
+            IdeKmMessage::KGoStopAck(ack_index) if ack_index == key_index => Ok(()),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+}
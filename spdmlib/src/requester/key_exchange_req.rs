@@ -33,7 +33,7 @@ impl<'a> RequesterContext<'a> {
     ) -> SpdmResult<u32> {
         info!("send spdm key exchange\n");
 
-        if slot_id >= SPDM_MAX_SLOT_NUMBER as u8 {
+        if !self.common.is_rsp_slot_id_valid(slot_id) {
             return Err(SPDM_STATUS_INVALID_PARAMETER);
         }
 
@@ -42,7 +42,7 @@ impl<'a> RequesterContext<'a> {
         self.common
             .reset_buffer_via_request_code(SpdmRequestResponseCode::SpdmRequestKeyExchange, None);
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let (key_exchange_context, send_used) = self.encode_spdm_key_exchange(
             req_session_id,
             &mut send_buffer,
@@ -52,16 +52,19 @@ impl<'a> RequesterContext<'a> {
         self.send_message(&send_buffer[..send_used])?;
 
         // Receive
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let receive_used = self.receive_message(&mut receive_buffer, false)?;
-        self.handle_spdm_key_exhcange_response(
+        let result = self.handle_spdm_key_exhcange_response(
             req_session_id,
             slot_id,
             &send_buffer[..send_used],
             &receive_buffer[..receive_used],
             measurement_summary_hash_type,
             key_exchange_context,
-        )
+        );
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
     }
 
     pub fn encode_spdm_key_exchange(
@@ -110,6 +113,10 @@ impl<'a> RequesterContext<'a> {
         } else {
             return Err(SPDM_STATUS_UNSUPPORTED_CAP);
         }
+        self.common.append_provided_opaque_elements(
+            SpdmRequestResponseCode::SpdmRequestKeyExchange,
+            &mut opaque,
+        )?;
 
         let request = SpdmMessage {
             header: SpdmMessageHeader {
@@ -243,8 +250,14 @@ impl<'a> RequesterContext<'a> {
                                 {
                                     return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                                 }
+                                // 0xF selects the provisioned requester public key
+                                let req_slot_id = key_exchange_rsp.req_slot_id & 0xf;
                                 self.common.runtime_info.set_local_used_cert_chain_slot_id(
-                                    key_exchange_rsp.req_slot_id & 0xf,
+                                    if req_slot_id == 0xf {
+                                        SPDM_PUB_KEY_SLOT_ID
+                                    } else {
+                                        req_slot_id
+                                    },
                                 );
                             }
 
@@ -374,6 +387,16 @@ impl<'a> RequesterContext<'a> {
                                 }
                             }
 
+                            // the opaque elements are authenticated by now
+                            if let Err(e) = self.common.receive_opaque_elements(
+                                SpdmRequestResponseCode::SpdmResponseKeyExchangeRsp,
+                                &key_exchange_rsp.opaque,
+                            ) {
+                                let session = self.common.get_session_via_id(session_id).unwrap();
+                                let _ = session.teardown(session_id);
+                                return Err(e);
+                            }
+
                             // append verify_data after TH1
                             let session = self.common.get_session_via_id(session_id).unwrap();
 
@@ -428,19 +451,13 @@ impl<'a> RequesterContext<'a> {
 
         debug!("message_hash - {:02x?}", transcript_hash.as_ref());
 
-        if self.common.peer_info.peer_cert_chain[slot_id as usize].is_none() {
-            error!("peer_cert_chain is not populated!\n");
-            return Err(SPDM_STATUS_INVALID_PARAMETER);
-        }
-
-        let cert_chain_data = &self.common.peer_info.peer_cert_chain[slot_id as usize]
-            .as_ref()
-            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-            .data[(4usize + self.common.negotiate_info.base_hash_sel.get_size() as usize)
-            ..(self.common.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(slot_id)
+            .ok_or_else(|| {
+                error!("peer_cert_chain is not populated!\n");
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
 
         let mut message_sign = ManagedBuffer12Sign::default();
         if self.common.negotiate_info.spdm_version_sel.get_u8()
@@ -448,7 +465,12 @@ impl<'a> RequesterContext<'a> {
         {
             message_sign.reset_message();
             message_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_2)
@@ -464,9 +486,10 @@ impl<'a> RequesterContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
-        crypto::asym_verify::verify(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
+        self.verify_peer_signature(
+            SpdmRequestResponseCode::SpdmResponseKeyExchangeRsp,
+            slot_id,
+            &transcript_hash,
             cert_chain_data,
             message_sign.as_ref(),
             signature,
@@ -487,19 +510,13 @@ impl<'a> RequesterContext<'a> {
         // we just print message hash for debug purpose
         debug!("message_hash - {:02x?}", message_hash.as_ref());
 
-        if self.common.peer_info.peer_cert_chain[slot_id as usize].is_none() {
-            error!("peer_cert_chain is not populated!\n");
-            return Err(SPDM_STATUS_INVALID_PARAMETER);
-        }
-
-        let cert_chain_data = &self.common.peer_info.peer_cert_chain[slot_id as usize]
-            .as_ref()
-            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-            .data[(4usize + self.common.negotiate_info.base_hash_sel.get_size() as usize)
-            ..(self.common.peer_info.peer_cert_chain[slot_id as usize]
-                .as_ref()
-                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?
-                .data_size as usize)];
+        let cert_chain_data = self
+            .common
+            .get_peer_public_cert_der(slot_id)
+            .ok_or_else(|| {
+                error!("peer_cert_chain is not populated!\n");
+                SPDM_STATUS_INVALID_PARAMETER
+            })?;
 
         let mut message = self.common.calc_req_transcript_data(
             false,
@@ -514,7 +531,12 @@ impl<'a> RequesterContext<'a> {
         {
             message.reset_message();
             message
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_2)
@@ -527,9 +549,10 @@ impl<'a> RequesterContext<'a> {
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         }
 
-        crypto::asym_verify::verify(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
+        self.verify_peer_signature(
+            SpdmRequestResponseCode::SpdmResponseKeyExchangeRsp,
+            slot_id,
+            &message_hash,
             cert_chain_data,
             message.as_ref(),
             signature,
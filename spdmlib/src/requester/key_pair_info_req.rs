@@ -0,0 +1,236 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{
+    SpdmResult, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD,
+    SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_RESET_REQUIRED_PEER, SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use crate::message::*;
+use crate::protocol::*;
+use crate::requester::*;
+
+impl<'a> RequesterContext<'a> {
+    fn check_key_pair_info_cap(&self, cap: SpdmResponseCapabilityFlags) -> SpdmResult {
+        if self.common.negotiate_info.spdm_version_sel.get_u8()
+            < SpdmVersion::SpdmVersion13.get_u8()
+            || !self
+                .common
+                .negotiate_info
+                .rsp_capabilities_sel
+                .contains(cap)
+        {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+        Ok(())
+    }
+
+    /// Get the description of key_pair_id, key pair IDs range from 1 to
+    /// the total_key_pairs of the response.
+    pub fn send_receive_spdm_key_pair_info(
+        &mut self,
+        session_id: Option<u32>,
+        key_pair_id: u8,
+    ) -> SpdmResult<SpdmKeyPairInfoResponsePayload> {
+        info!("send spdm get_key_pair_info\n");
+        self.check_key_pair_info_cap(SpdmResponseCapabilityFlags::GET_KEY_PAIR_INFO_CAP)?;
+        if key_pair_id == 0 {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestGetKeyPairInfo,
+            session_id,
+        );
+
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let mut writer = Writer::init(&mut send_buffer);
+        let request = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmRequestGetKeyPairInfo,
+            },
+            payload: SpdmMessagePayload::SpdmGetKeyPairInfoRequest(
+                SpdmGetKeyPairInfoRequestPayload { key_pair_id },
+            ),
+        };
+        let used = request.spdm_encode(&mut self.common, &mut writer)?;
+        match session_id {
+            Some(session_id) => {
+                self.send_secured_message(session_id, &send_buffer[..used], false)?;
+            }
+            None => {
+                self.send_message(&send_buffer[..used])?;
+            }
+        }
+
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let used = match session_id {
+            Some(session_id) => {
+                self.receive_secured_message(session_id, &mut receive_buffer, false)?
+            }
+            None => self.receive_message(&mut receive_buffer, false)?,
+        };
+        let result = self.handle_spdm_key_pair_info_response(
+            session_id,
+            key_pair_id,
+            &receive_buffer[..used],
+        );
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    fn handle_spdm_key_pair_info_response(
+        &mut self,
+        session_id: Option<u32>,
+        key_pair_id: u8,
+        receive_buffer: &[u8],
+    ) -> SpdmResult<SpdmKeyPairInfoResponsePayload> {
+        let mut reader = Reader::init(receive_buffer);
+        match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => {
+                if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                    return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                }
+                match message_header.request_response_code {
+                    SpdmRequestResponseCode::SpdmResponseKeyPairInfo => {
+                        let key_pair_info = SpdmKeyPairInfoResponsePayload::spdm_read(
+                            &mut self.common,
+                            &mut reader,
+                        );
+                        match key_pair_info {
+                            Some(key_pair_info) if key_pair_info.key_pair_id == key_pair_id => {
+                                debug!(
+                                    "!!! key_pair_info : {:02x?}\n",
+                                    key_pair_info.total_key_pairs
+                                );
+                                Ok(key_pair_info)
+                            }
+                            _ => {
+                                error!("!!! key_pair_info : fail !!!\n");
+                                Err(SPDM_STATUS_INVALID_MSG_FIELD)
+                            }
+                        }
+                    }
+                    SpdmRequestResponseCode::SpdmResponseError => {
+                        let status = self.spdm_handle_error_response_main(
+                            session_id,
+                            receive_buffer,
+                            SpdmRequestResponseCode::SpdmRequestGetKeyPairInfo,
+                            SpdmRequestResponseCode::SpdmResponseKeyPairInfo,
+                        );
+                        match status {
+                            Err(status) => Err(status),
+                            Ok(()) => Err(SPDM_STATUS_ERROR_PEER),
+                        }
+                    }
+                    _ => Err(SPDM_STATUS_ERROR_PEER),
+                }
+            }
+            None => Err(SPDM_STATUS_INVALID_MSG_FIELD),
+        }
+    }
+
+    /// Change, erase or generate a key pair of the responder.
+    ///
+    /// If the responder needs a reset to apply the change, the function fails
+    /// with SPDM_STATUS_RESET_REQUIRED_PEER.
+    pub fn send_receive_spdm_set_key_pair_info(
+        &mut self,
+        session_id: Option<u32>,
+        set_key_pair_info: &SpdmSetKeyPairInfoRequestPayload,
+    ) -> SpdmResult {
+        info!("send spdm set_key_pair_info\n");
+        self.check_key_pair_info_cap(SpdmResponseCapabilityFlags::SET_KEY_PAIR_INFO_CAP)?;
+        if set_key_pair_info.key_pair_id == 0 {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestSetKeyPairInfo,
+            session_id,
+        );
+
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let mut writer = Writer::init(&mut send_buffer);
+        let request = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmRequestSetKeyPairInfo,
+            },
+            payload: SpdmMessagePayload::SpdmSetKeyPairInfoRequest(set_key_pair_info.clone()),
+        };
+        let used = request.spdm_encode(&mut self.common, &mut writer)?;
+        match session_id {
+            Some(session_id) => {
+                self.send_secured_message(session_id, &send_buffer[..used], false)?;
+            }
+            None => {
+                self.send_message(&send_buffer[..used])?;
+            }
+        }
+
+        let mut receive_buffer = self.common.alloc_message_buffer();
+        let used = match session_id {
+            Some(session_id) => {
+                self.receive_secured_message(session_id, &mut receive_buffer, false)?
+            }
+            None => self.receive_message(&mut receive_buffer, false)?,
+        };
+        let result =
+            self.handle_spdm_set_key_pair_info_ack_response(session_id, &receive_buffer[..used]);
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
+    }
+
+    fn handle_spdm_set_key_pair_info_ack_response(
+        &mut self,
+        session_id: Option<u32>,
+        receive_buffer: &[u8],
+    ) -> SpdmResult {
+        let mut reader = Reader::init(receive_buffer);
+        match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => {
+                if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                    return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+                }
+                match message_header.request_response_code {
+                    SpdmRequestResponseCode::SpdmResponseSetKeyPairInfoAck => {
+                        if SpdmSetKeyPairInfoAckResponsePayload::spdm_read(
+                            &mut self.common,
+                            &mut reader,
+                        )
+                        .is_some()
+                        {
+                            Ok(())
+                        } else {
+                            error!("!!! set_key_pair_info_ack : fail !!!\n");
+                            Err(SPDM_STATUS_INVALID_MSG_FIELD)
+                        }
+                    }
+                    SpdmRequestResponseCode::SpdmResponseError => {
+                        let error_response =
+                            SpdmErrorResponsePayload::spdm_read(&mut self.common, &mut reader);
+                        if let Some(error_response) = error_response {
+                            if error_response.error_code == SpdmErrorCode::SpdmErrorResetRequired {
+                                info!("!!! set_key_pair_info : reset required !!!\n");
+                                return Err(SPDM_STATUS_RESET_REQUIRED_PEER);
+                            }
+                        }
+
+                        self.spdm_handle_error_response_main(
+                            session_id,
+                            receive_buffer,
+                            SpdmRequestResponseCode::SpdmRequestSetKeyPairInfo,
+                            SpdmRequestResponseCode::SpdmResponseSetKeyPairInfoAck,
+                        )
+                    }
+                    _ => Err(SPDM_STATUS_ERROR_PEER),
+                }
+            }
+            None => Err(SPDM_STATUS_INVALID_MSG_FIELD),
+        }
+    }
+}
@@ -23,9 +23,10 @@ impl<'a> RequesterContext<'a> {
             Some(session_id),
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let used = self.encode_spdm_key_update_op(key_update_operation, tag, &mut send_buffer)?;
         self.send_secured_message(session_id, &send_buffer[..used], false)?;
+        self.common.free_buffer(send_buffer);
 
         // update key
         let spdm_version_sel = self.common.negotiate_info.spdm_version_sel;
@@ -38,15 +39,17 @@ impl<'a> RequesterContext<'a> {
             || key_update_operation == SpdmKeyUpdateOperation::SpdmUpdateAllKeys;
         let update_responder = key_update_operation == SpdmKeyUpdateOperation::SpdmUpdateAllKeys;
         session.create_data_secret_update(spdm_version_sel, update_requester, update_responder)?;
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = self.receive_secured_message(session_id, &mut receive_buffer, false)?;
 
-        self.handle_spdm_key_update_op_response(
+        let result = self.handle_spdm_key_update_op_response(
             session_id,
             update_requester,
             update_responder,
             &receive_buffer[..used],
-        )
+        );
+        self.common.free_buffer(receive_buffer);
+        result
     }
 
     pub fn encode_spdm_key_update_op(
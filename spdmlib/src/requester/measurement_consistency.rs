@@ -0,0 +1,267 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{SpdmResult, SPDM_STATUS_ERROR_PEER};
+use crate::message::*;
+use crate::protocol::*;
+use crate::requester::*;
+
+const SPDM_MEASUREMENT_BLOCK_HEADER_SIZE: usize = 4;
+
+/// First difference found between the "all measurements" response and the
+/// individually queried measurement indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmMeasurementInconsistency {
+    /// The total number query and the "all measurements" response disagree.
+    TotalNumber {
+        total_number: u8,
+        number_of_blocks: u8,
+    },
+    /// A measurement record can not be parsed into blocks.
+    MalformedRecord,
+    /// An index present in the "all measurements" response is refused when queried alone.
+    MissingIndex(u8),
+    /// The response to an individual query carries other or more blocks than the queried one.
+    UnexpectedIndex(u8),
+    /// The block returned for an index differs from the one in the "all measurements" response.
+    Mismatch(u8),
+}
+
+/// Walk the raw measurement blocks of a record, calling f with the index and
+/// the block. Return the number of blocks, None if the record is malformed.
+fn walk_measurement_blocks<'r>(
+    record: &'r SpdmMeasurementRecordStructure,
+    mut f: impl FnMut(u8, &'r [u8]),
+) -> Option<usize> {
+    let record_length = record.measurement_record_length.get() as usize;
+    if record_length > record.measurement_record_data.len() {
+        return None;
+    }
+    let data = &record.measurement_record_data[..record_length];
+
+    let mut offset = 0usize;
+    let mut count = 0usize;
+    while offset < data.len() {
+        if count >= u8::MAX as usize || data.len() - offset < SPDM_MEASUREMENT_BLOCK_HEADER_SIZE {
+            return None;
+        }
+        let index = data[offset];
+        let measurement_size = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let block_size = SPDM_MEASUREMENT_BLOCK_HEADER_SIZE + measurement_size;
+        if data.len() - offset < block_size {
+            return None;
+        }
+        f(index, &data[offset..offset + block_size]);
+        offset += block_size;
+        count += 1;
+    }
+    if count != record.number_of_blocks as usize {
+        return None;
+    }
+    Some(count)
+}
+
+/// Check the response to a single index query against the "all measurements" record.
+pub fn check_measurement_block(
+    all: &SpdmMeasurementRecordStructure,
+    index: u8,
+    single: &SpdmMeasurementRecordStructure,
+) -> Result<(), SpdmMeasurementInconsistency> {
+    let mut expected = None;
+    walk_measurement_blocks(all, |block_index, block| {
+        if block_index == index && expected.is_none() {
+            expected = Some(block);
+        }
+    })
+    .ok_or(SpdmMeasurementInconsistency::MalformedRecord)?;
+    let mut first = None;
+    let single_count = walk_measurement_blocks(single, |block_index, block| {
+        if first.is_none() {
+            first = Some((block_index, block));
+        }
+    })
+    .ok_or(SpdmMeasurementInconsistency::MalformedRecord)?;
+
+    let expected = expected.ok_or(SpdmMeasurementInconsistency::UnexpectedIndex(index))?;
+    let (single_index, single_block) = match (single_count, first) {
+        (1, Some(first)) => first,
+        _ => return Err(SpdmMeasurementInconsistency::UnexpectedIndex(index)),
+    };
+    if single_index != index {
+        return Err(SpdmMeasurementInconsistency::UnexpectedIndex(single_index));
+    }
+    if single_block != expected {
+        return Err(SpdmMeasurementInconsistency::Mismatch(index));
+    }
+    Ok(())
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Cross check the "all measurements" response with the total number query
+    /// and with every index queried alone, to detect a responder presenting
+    /// different measurements depending on the query form.
+    ///
+    /// Return Ok(None) if consistent, or the first inconsistency found.
+    pub fn verify_measurement_consistency(
+        &mut self,
+        session_id: Option<u32>,
+        slot_id: u8,
+        measurement_attributes: SpdmMeasurementAttributes,
+    ) -> SpdmResult<Option<SpdmMeasurementInconsistency>> {
+        let mut total_number = 0u8;
+        let mut record = SpdmMeasurementRecordStructure::default();
+        self.send_receive_spdm_measurement(
+            session_id,
+            slot_id,
+            measurement_attributes,
+            SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber,
+            &mut total_number,
+            &mut record,
+        )?;
+
+        let mut number_of_blocks = 0u8;
+        let mut all = SpdmMeasurementRecordStructure::default();
+        self.send_receive_spdm_measurement(
+            session_id,
+            slot_id,
+            measurement_attributes,
+            SpdmMeasurementOperation::SpdmMeasurementRequestAll,
+            &mut number_of_blocks,
+            &mut all,
+        )?;
+        if total_number != all.number_of_blocks {
+            return Ok(Some(SpdmMeasurementInconsistency::TotalNumber {
+                total_number,
+                number_of_blocks: all.number_of_blocks,
+            }));
+        }
+
+        let mut indices = self.common.alloc_buffer(u8::MAX as usize);
+        let mut next = 0usize;
+        let result = match walk_measurement_blocks(&all, |index, _| {
+            indices[next] = index;
+            next += 1;
+        }) {
+            Some(count) => self.verify_measurement_indices(
+                session_id,
+                slot_id,
+                measurement_attributes,
+                &all,
+                &indices[..count],
+            ),
+            None => Ok(Some(SpdmMeasurementInconsistency::MalformedRecord)),
+        };
+        self.common.free_buffer(indices);
+        result
+    }
+
+    fn verify_measurement_indices(
+        &mut self,
+        session_id: Option<u32>,
+        slot_id: u8,
+        measurement_attributes: SpdmMeasurementAttributes,
+        all: &SpdmMeasurementRecordStructure,
+        indices: &[u8],
+    ) -> SpdmResult<Option<SpdmMeasurementInconsistency>> {
+        let mut number_of_blocks = 0u8;
+        for index in indices {
+            if *index == SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber.get_u8()
+                || *index == SpdmMeasurementOperation::SpdmMeasurementRequestAll.get_u8()
+            {
+                return Ok(Some(SpdmMeasurementInconsistency::UnexpectedIndex(*index)));
+            }
+
+            let mut single = SpdmMeasurementRecordStructure::default();
+            match self.send_receive_spdm_measurement(
+                session_id,
+                slot_id,
+                measurement_attributes,
+                SpdmMeasurementOperation::Unknown(*index),
+                &mut number_of_blocks,
+                &mut single,
+            ) {
+                Ok(()) => {}
+                Err(e) if e == SPDM_STATUS_ERROR_PEER => {
+                    return Ok(Some(SpdmMeasurementInconsistency::MissingIndex(*index)));
+                }
+                Err(e) => return Err(e),
+            }
+
+            if let Err(inconsistency) = check_measurement_block(all, *index, &single) {
+                error!("!!! measurement consistency : {:?} !!!\n", inconsistency);
+                return Ok(Some(inconsistency));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use codec::u24;
+
+    fn create_record(blocks: &[(u8, &[u8])]) -> SpdmMeasurementRecordStructure {
+        let mut record = SpdmMeasurementRecordStructure::default();
+        let mut writer = Writer::init(&mut record.measurement_record_data);
+        for (index, value) in blocks {
+            let mut measurement_value = [0u8; config::MAX_SPDM_MEASUREMENT_VALUE_LEN];
+            measurement_value[..value.len()].copy_from_slice(value);
+            let block = SpdmMeasurementBlockStructure {
+                index: *index,
+                measurement_specification: SpdmMeasurementSpecification::DMTF,
+                measurement_size: value.len() as u16 + 3,
+                measurement: SpdmDmtfMeasurementStructure {
+                    r#type: SpdmDmtfMeasurementType::SpdmDmtfMeasurementFirmware,
+                    representation: SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementDigest,
+                    value_size: value.len() as u16,
+                    value: measurement_value,
+                },
+            };
+            assert!(block.encode(&mut writer).is_ok());
+        }
+        let used = writer.used();
+        record.number_of_blocks = blocks.len() as u8;
+        record.measurement_record_length = u24::new(used as u32);
+        record
+    }
+
+    #[test]
+    fn test_case0_check_measurement_block() {
+        let all = create_record(&[(1, &[0x11; 48]), (2, &[0x22; 48])]);
+
+        assert_eq!(
+            check_measurement_block(&all, 1, &create_record(&[(1, &[0x11; 48])])),
+            Ok(())
+        );
+        assert_eq!(
+            check_measurement_block(&all, 2, &create_record(&[(2, &[0x11; 48])])),
+            Err(SpdmMeasurementInconsistency::Mismatch(2))
+        );
+        assert_eq!(
+            check_measurement_block(&all, 2, &create_record(&[(1, &[0x11; 48])])),
+            Err(SpdmMeasurementInconsistency::UnexpectedIndex(1))
+        );
+        assert_eq!(
+            check_measurement_block(
+                &all,
+                1,
+                &create_record(&[(1, &[0x11; 48]), (2, &[0x22; 48])])
+            ),
+            Err(SpdmMeasurementInconsistency::UnexpectedIndex(1))
+        );
+        assert_eq!(
+            check_measurement_block(&all, 3, &create_record(&[(3, &[0x33; 48])])),
+            Err(SpdmMeasurementInconsistency::UnexpectedIndex(3))
+        );
+
+        let mut malformed = create_record(&[(1, &[0x11; 48])]);
+        malformed.number_of_blocks = 2;
+        assert_eq!(
+            check_measurement_block(&all, 1, &malformed),
+            Err(SpdmMeasurementInconsistency::MalformedRecord)
+        );
+    }
+}
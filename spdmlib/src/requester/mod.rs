@@ -4,7 +4,16 @@
 
 mod context;
 
-mod challenge_req;
+pub mod async_message_req;
+mod attestation_report;
+pub mod capability_check;
+pub mod cert_verify_cache;
+pub mod challenge_req;
+mod chunk_get_req;
+pub mod connection_driver;
+pub mod connection_state;
+mod cxl_ide_km_req;
+pub mod device_report;
 #[cfg(feature = "mut-auth")]
 mod encap_certificate;
 #[cfg(feature = "mut-auth")]
@@ -14,25 +23,41 @@ mod encap_error;
 #[cfg(feature = "mut-auth")]
 mod encap_req;
 mod end_session_req;
+pub mod error_stats;
+pub mod event_req;
 mod finish_req;
 mod get_capabilities_req;
 mod get_certificate_req;
+mod get_csr_req;
 mod get_digests_req;
 pub mod get_measurements_req;
 mod get_version_req;
 mod handle_error_response_req;
 mod heartbeat_req;
+pub mod heartbeat_task;
+mod ide_km_req;
 mod key_exchange_req;
+mod key_pair_info_req;
 pub mod key_update_req;
+pub mod measurement_consistency;
 #[cfg(feature = "mut-auth")]
 mod mutual_authenticate;
 mod negotiate_algorithms_req;
+pub mod negotiation_report;
 mod psk_exchange_req;
 mod psk_finish_req;
+pub mod reattestation;
+pub mod respond_if_ready_req;
+#[cfg(feature = "async-io")]
+pub mod session_stream;
+pub mod signature_offload;
+pub mod slot_cert_info;
 mod vendor_req;
 
 pub use context::RequesterContext;
 
+use connection_state::SpdmRequesterState;
+
 use crate::common::*;
 use crate::config;
 use codec::{Codec, Reader, Writer};
@@ -11,7 +11,7 @@ use super::RequesterContext;
 
 impl<'a> RequesterContext<'a> {
     pub fn session_based_mutual_authenticate(&mut self, session_id: u32) -> SpdmResult<()> {
-        self.common.construct_my_cert_chain()?;
+        self.common.construct_my_cert_chain_as(true)?;
 
         let spdm_session = self
             .common
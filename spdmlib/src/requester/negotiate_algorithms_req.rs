@@ -17,13 +17,17 @@ impl<'a> RequesterContext<'a> {
             None,
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let send_used = self.encode_spdm_algorithm(&mut send_buffer)?;
         self.send_message(&send_buffer[..send_used])?;
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let used = self.receive_message(&mut receive_buffer, false)?;
-        self.handle_spdm_algorithm_response(0, &send_buffer[..send_used], &receive_buffer[..used])
+        self.handle_spdm_algorithm_response(0, &send_buffer[..send_used], &receive_buffer[..used])?;
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        self.advance_requester_state(SpdmRequesterState::Negotiated);
+        Ok(())
     }
 
     pub fn encode_spdm_algorithm(&mut self, buf: &mut [u8]) -> SpdmResult<usize> {
@@ -0,0 +1,240 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_STATE_LOCAL};
+use crate::protocol::*;
+use crate::requester::*;
+
+/// Bumped whenever the encoding of SpdmNegotiationReport changes.
+pub const SPDM_NEGOTIATION_REPORT_FORMAT_VERSION: u8 = 1;
+
+/// Connection parameters the responder advertised or selected during
+/// GET_VERSION, GET_CAPABILITIES and NEGOTIATE_ALGORITHMS.
+///
+/// The encoding is canonical, so a report stored as the baseline of a
+/// device can be compared byte for byte or with diff after a reconnect.
+///
+/// Encoding (little endian): format version (1), spdm version (1),
+/// rsp capabilities (4), rsp ct exponent (1), measurement specification (1),
+/// measurement hash (4), base asym (4), base hash (4), dhe (2), aead (2),
+/// req asym (2), key schedule (2), opaque data support (1),
+/// rsp data transfer size (4), rsp max spdm msg size (4).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmNegotiationReport {
+    pub spdm_version: SpdmVersion,
+    pub rsp_capabilities: SpdmResponseCapabilityFlags,
+    pub rsp_ct_exponent: u8,
+    pub measurement_specification: SpdmMeasurementSpecification,
+    pub measurement_hash_algo: SpdmMeasurementHashAlgo,
+    pub base_asym_algo: SpdmBaseAsymAlgo,
+    pub base_hash_algo: SpdmBaseHashAlgo,
+    pub dhe_algo: SpdmDheAlgo,
+    pub aead_algo: SpdmAeadAlgo,
+    pub req_asym_algo: SpdmReqAsymAlgo,
+    pub key_schedule_algo: SpdmKeyScheduleAlgo,
+    pub opaque_data_support: SpdmOpaqueSupport,
+    pub rsp_data_transfer_size: u32,
+    pub rsp_max_spdm_msg_size: u32,
+}
+
+impl Codec for SpdmNegotiationReport {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += SPDM_NEGOTIATION_REPORT_FORMAT_VERSION.encode(bytes)?;
+        cnt += self.spdm_version.encode(bytes)?;
+        cnt += self.rsp_capabilities.encode(bytes)?;
+        cnt += self.rsp_ct_exponent.encode(bytes)?;
+        cnt += self.measurement_specification.encode(bytes)?;
+        cnt += self.measurement_hash_algo.encode(bytes)?;
+        cnt += self.base_asym_algo.encode(bytes)?;
+        cnt += self.base_hash_algo.encode(bytes)?;
+        cnt += self.dhe_algo.encode(bytes)?;
+        cnt += self.aead_algo.encode(bytes)?;
+        cnt += self.req_asym_algo.encode(bytes)?;
+        cnt += self.key_schedule_algo.encode(bytes)?;
+        cnt += self.opaque_data_support.encode(bytes)?;
+        cnt += self.rsp_data_transfer_size.encode(bytes)?;
+        cnt += self.rsp_max_spdm_msg_size.encode(bytes)?;
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<SpdmNegotiationReport> {
+        let format_version = u8::read(r)?;
+        if format_version != SPDM_NEGOTIATION_REPORT_FORMAT_VERSION {
+            return None;
+        }
+        Some(SpdmNegotiationReport {
+            spdm_version: SpdmVersion::read(r)?,
+            rsp_capabilities: SpdmResponseCapabilityFlags::read(r)?,
+            rsp_ct_exponent: u8::read(r)?,
+            measurement_specification: SpdmMeasurementSpecification::read(r)?,
+            measurement_hash_algo: SpdmMeasurementHashAlgo::read(r)?,
+            base_asym_algo: SpdmBaseAsymAlgo::read(r)?,
+            base_hash_algo: SpdmBaseHashAlgo::read(r)?,
+            dhe_algo: SpdmDheAlgo::read(r)?,
+            aead_algo: SpdmAeadAlgo::read(r)?,
+            req_asym_algo: SpdmReqAsymAlgo::read(r)?,
+            key_schedule_algo: SpdmKeyScheduleAlgo::read(r)?,
+            opaque_data_support: SpdmOpaqueSupport::read(r)?,
+            rsp_data_transfer_size: u32::read(r)?,
+            rsp_max_spdm_msg_size: u32::read(r)?,
+        })
+    }
+}
+
+bitflags! {
+    /// Fields of SpdmNegotiationReport that differ from the baseline.
+    #[derive(Default)]
+    pub struct SpdmNegotiationDriftFields: u16 {
+        const SPDM_VERSION = 0b0000_0000_0000_0001;
+        const RSP_CAPABILITIES = 0b0000_0000_0000_0010;
+        const RSP_CT_EXPONENT = 0b0000_0000_0000_0100;
+        const MEASUREMENT_SPECIFICATION = 0b0000_0000_0000_1000;
+        const MEASUREMENT_HASH_ALGO = 0b0000_0000_0001_0000;
+        const BASE_ASYM_ALGO = 0b0000_0000_0010_0000;
+        const BASE_HASH_ALGO = 0b0000_0000_0100_0000;
+        const DHE_ALGO = 0b0000_0000_1000_0000;
+        const AEAD_ALGO = 0b0000_0001_0000_0000;
+        const REQ_ASYM_ALGO = 0b0000_0010_0000_0000;
+        const KEY_SCHEDULE_ALGO = 0b0000_0100_0000_0000;
+        const OPAQUE_DATA_SUPPORT = 0b0000_1000_0000_0000;
+        const RSP_DATA_TRANSFER_SIZE = 0b0001_0000_0000_0000;
+        const RSP_MAX_SPDM_MSG_SIZE = 0b0010_0000_0000_0000;
+    }
+}
+
+/// Difference between a negotiation report and the stored baseline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmNegotiationDrift {
+    pub changed: SpdmNegotiationDriftFields,
+    /// Capabilities in the baseline the responder no longer advertises.
+    pub rsp_capabilities_removed: SpdmResponseCapabilityFlags,
+    /// Capabilities the responder advertises which are not in the baseline.
+    pub rsp_capabilities_added: SpdmResponseCapabilityFlags,
+}
+
+impl SpdmNegotiationDrift {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
+}
+
+impl SpdmNegotiationReport {
+    /// Compare this report with the baseline previously stored for the device.
+    pub fn diff(&self, baseline: &SpdmNegotiationReport) -> SpdmNegotiationDrift {
+        let mut changed = SpdmNegotiationDriftFields::empty();
+        changed.set(
+            SpdmNegotiationDriftFields::SPDM_VERSION,
+            self.spdm_version != baseline.spdm_version,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::RSP_CAPABILITIES,
+            self.rsp_capabilities != baseline.rsp_capabilities,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::RSP_CT_EXPONENT,
+            self.rsp_ct_exponent != baseline.rsp_ct_exponent,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::MEASUREMENT_SPECIFICATION,
+            self.measurement_specification != baseline.measurement_specification,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::MEASUREMENT_HASH_ALGO,
+            self.measurement_hash_algo != baseline.measurement_hash_algo,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::BASE_ASYM_ALGO,
+            self.base_asym_algo != baseline.base_asym_algo,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::BASE_HASH_ALGO,
+            self.base_hash_algo != baseline.base_hash_algo,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::DHE_ALGO,
+            self.dhe_algo != baseline.dhe_algo,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::AEAD_ALGO,
+            self.aead_algo != baseline.aead_algo,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::REQ_ASYM_ALGO,
+            self.req_asym_algo != baseline.req_asym_algo,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::KEY_SCHEDULE_ALGO,
+            self.key_schedule_algo != baseline.key_schedule_algo,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::OPAQUE_DATA_SUPPORT,
+            self.opaque_data_support != baseline.opaque_data_support,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::RSP_DATA_TRANSFER_SIZE,
+            self.rsp_data_transfer_size != baseline.rsp_data_transfer_size,
+        );
+        changed.set(
+            SpdmNegotiationDriftFields::RSP_MAX_SPDM_MSG_SIZE,
+            self.rsp_max_spdm_msg_size != baseline.rsp_max_spdm_msg_size,
+        );
+
+        SpdmNegotiationDrift {
+            changed,
+            rsp_capabilities_removed: baseline.rsp_capabilities - self.rsp_capabilities,
+            rsp_capabilities_added: self.rsp_capabilities - baseline.rsp_capabilities,
+        }
+    }
+
+    /// Decode a stored baseline and compare this report with it.
+    /// Returns None if the baseline is not a valid encoded report.
+    pub fn diff_encoded(&self, baseline: &[u8]) -> Option<SpdmNegotiationDrift> {
+        let mut reader = Reader::init(baseline);
+        let baseline = SpdmNegotiationReport::read(&mut reader)?;
+        Some(self.diff(&baseline))
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Compose a negotiation report after init_connection.
+    pub fn compose_negotiation_report(&self) -> SpdmResult<SpdmNegotiationReport> {
+        if self.common.runtime_info.get_connection_state().get_u8()
+            < SpdmConnectionState::SpdmConnectionNegotiated.get_u8()
+        {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+
+        let negotiate_info = &self.common.negotiate_info;
+        Ok(SpdmNegotiationReport {
+            spdm_version: negotiate_info.spdm_version_sel,
+            rsp_capabilities: negotiate_info.rsp_capabilities_sel,
+            rsp_ct_exponent: negotiate_info.rsp_ct_exponent_sel,
+            measurement_specification: negotiate_info.measurement_specification_sel,
+            measurement_hash_algo: negotiate_info.measurement_hash_sel,
+            base_asym_algo: negotiate_info.base_asym_sel,
+            base_hash_algo: negotiate_info.base_hash_sel,
+            dhe_algo: negotiate_info.dhe_sel,
+            aead_algo: negotiate_info.aead_sel,
+            req_asym_algo: negotiate_info.req_asym_sel,
+            key_schedule_algo: negotiate_info.key_schedule_sel,
+            opaque_data_support: negotiate_info.opaque_data_support,
+            rsp_data_transfer_size: negotiate_info.rsp_data_transfer_size_sel,
+            rsp_max_spdm_msg_size: negotiate_info.rsp_max_spdm_msg_size_sel,
+        })
+    }
+
+    /// Exact bytes of GET_VERSION, VERSION, GET_CAPABILITIES, CAPABILITIES,
+    /// NEGOTIATE_ALGORITHMS and ALGORITHMS as sent and received, i.e. the VCA
+    /// part of the transcript, for protocols binding to it after init_connection.
+    pub fn get_vca_bytes(&self) -> SpdmResult<&[u8]> {
+        if self.common.runtime_info.get_connection_state().get_u8()
+            < SpdmConnectionState::SpdmConnectionNegotiated.get_u8()
+        {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+
+        Ok(self.common.runtime_info.message_a.as_ref())
+    }
+}
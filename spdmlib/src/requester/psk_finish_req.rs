@@ -25,7 +25,7 @@ impl<'a> RequesterContext<'a> {
             Some(session_id),
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let res = self.encode_spdm_psk_finish(session_id, &mut send_buffer);
         if res.is_err() {
             let _ = self
@@ -46,7 +46,7 @@ impl<'a> RequesterContext<'a> {
             return res;
         }
 
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let res = self.receive_secured_message(session_id, &mut receive_buffer, false);
         if res.is_err() {
             let _ = self
@@ -58,11 +58,14 @@ impl<'a> RequesterContext<'a> {
         }
         let receive_used = res.unwrap();
         let res = self.handle_spdm_psk_finish_response(session_id, &receive_buffer[..receive_used]);
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
         if res.is_err() {
             if let Some(session) = self.common.get_session_via_id(session_id) {
                 let _ = session.teardown(session_id);
             }
         }
+        self.notify_requester_state();
         res
     }
 
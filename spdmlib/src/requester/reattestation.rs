@@ -0,0 +1,214 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto;
+use crate::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_INVALID_PARAMETER};
+use crate::message::*;
+use crate::protocol::*;
+use crate::requester::*;
+
+pub const MAX_SPDM_REATTESTATION_CONNECTIONS: usize = 4;
+
+/// When a connection is attested again.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpdmReattestationPolicy {
+    /// Seconds between two attestations, 0 to disable.
+    pub interval: u64,
+    /// Attest again once notify_event is called, e.g. on device reset.
+    pub on_event: bool,
+    /// Query unsigned measurements on every poll and attest again once they change.
+    pub on_measurement_change: bool,
+    pub slot_id: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmReattestationTrigger {
+    Initial,
+    Interval,
+    Event,
+    MeasurementChange,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpdmReattestationReport {
+    pub connection: usize,
+    pub trigger: SpdmReattestationTrigger,
+    /// Hash of the signed measurement record.
+    pub measurement_hash: SpdmDigestStruct,
+    /// True if the measurements differ from the previous attestation.
+    pub drift: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SpdmReattestationState {
+    last_run: Option<u64>,
+    event_pending: bool,
+    measurement_hash: Option<SpdmDigestStruct>,
+}
+
+/// Runs CHALLENGE and signed GET_MEASUREMENTS again on established
+/// connections according to a policy, and reports measurement drift.
+///
+/// The scheduler owns no connection. The caller polls it periodically
+/// for each connection, identified by its index, with the current time.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmReattestationScheduler {
+    policy: SpdmReattestationPolicy,
+    states: [SpdmReattestationState; MAX_SPDM_REATTESTATION_CONNECTIONS],
+}
+
+impl SpdmReattestationScheduler {
+    pub fn new(policy: SpdmReattestationPolicy) -> Self {
+        SpdmReattestationScheduler {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    pub fn notify_event(&mut self, connection: usize) {
+        if let Some(state) = self.states.get_mut(connection) {
+            state.event_pending = true;
+        }
+    }
+
+    /// Forget a connection, it is attested again on the next poll.
+    pub fn reset(&mut self, connection: usize) {
+        if let Some(state) = self.states.get_mut(connection) {
+            *state = SpdmReattestationState::default();
+        }
+    }
+
+    /// Return the trigger if the connection is due by time or event.
+    pub fn due_trigger(&self, connection: usize, now: u64) -> Option<SpdmReattestationTrigger> {
+        let state = self.states.get(connection)?;
+        match state.last_run {
+            None => Some(SpdmReattestationTrigger::Initial),
+            Some(_) if self.policy.on_event && state.event_pending => {
+                Some(SpdmReattestationTrigger::Event)
+            }
+            Some(last_run)
+                if self.policy.interval != 0
+                    && now.saturating_sub(last_run) >= self.policy.interval =>
+            {
+                Some(SpdmReattestationTrigger::Interval)
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// Attest the connection again if due, return the report if attested.
+    pub fn poll(
+        &mut self,
+        connection: usize,
+        requester: &mut RequesterContext,
+        now: u64,
+    ) -> SpdmResult<Option<SpdmReattestationReport>> {
+        if connection >= MAX_SPDM_REATTESTATION_CONNECTIONS {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        let mut trigger = self.due_trigger(connection, now);
+        if trigger.is_none() && self.policy.on_measurement_change {
+            let measurement_hash =
+                self.collect_measurement_hash(requester, SpdmMeasurementAttributes::empty())?;
+            if let Some(last_hash) = &self.states[connection].measurement_hash {
+                if last_hash.as_ref() != measurement_hash.as_ref() {
+                    trigger = Some(SpdmReattestationTrigger::MeasurementChange);
+                }
+            }
+        }
+        let trigger = match trigger {
+            Some(trigger) => trigger,
+            None => return Ok(None),
+        };
+        info!("reattestation {} - {:?}\n", connection, trigger);
+
+        requester.send_receive_spdm_challenge(
+            self.policy.slot_id,
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+        )?;
+        let measurement_hash = self
+            .collect_measurement_hash(requester, SpdmMeasurementAttributes::SIGNATURE_REQUESTED)?;
+
+        let state = &mut self.states[connection];
+        let drift = match &state.measurement_hash {
+            Some(last_hash) => last_hash.as_ref() != measurement_hash.as_ref(),
+            None => false,
+        };
+        state.last_run = Some(now);
+        state.event_pending = false;
+        state.measurement_hash = Some(measurement_hash.clone());
+
+        Ok(Some(SpdmReattestationReport {
+            connection,
+            trigger,
+            measurement_hash,
+            drift,
+        }))
+    }
+
+    fn collect_measurement_hash(
+        &self,
+        requester: &mut RequesterContext,
+        measurement_attributes: SpdmMeasurementAttributes,
+    ) -> SpdmResult<SpdmDigestStruct> {
+        let mut total_number = 0u8;
+        let mut measurement_record = SpdmMeasurementRecordStructure::default();
+        requester.send_receive_spdm_measurement(
+            None,
+            self.policy.slot_id,
+            measurement_attributes,
+            SpdmMeasurementOperation::SpdmMeasurementRequestAll,
+            &mut total_number,
+            &mut measurement_record,
+        )?;
+        crypto::hash::hash_all(
+            requester.common.negotiate_info.base_hash_sel,
+            &measurement_record.measurement_record_data
+                [..measurement_record.measurement_record_length.get() as usize],
+        )
+        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_due_trigger() {
+        let mut scheduler = SpdmReattestationScheduler::new(SpdmReattestationPolicy {
+            interval: 60,
+            on_event: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            scheduler.due_trigger(0, 0),
+            Some(SpdmReattestationTrigger::Initial)
+        );
+        assert_eq!(
+            scheduler.due_trigger(MAX_SPDM_REATTESTATION_CONNECTIONS, 0),
+            None
+        );
+
+        scheduler.states[0].last_run = Some(100);
+        assert_eq!(scheduler.due_trigger(0, 159), None);
+        assert_eq!(
+            scheduler.due_trigger(0, 160),
+            Some(SpdmReattestationTrigger::Interval)
+        );
+
+        scheduler.notify_event(0);
+        assert_eq!(
+            scheduler.due_trigger(0, 100),
+            Some(SpdmReattestationTrigger::Event)
+        );
+
+        scheduler.reset(0);
+        assert_eq!(
+            scheduler.due_trigger(0, 100),
+            Some(SpdmReattestationTrigger::Initial)
+        );
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::timing;
+use crate::error::{SpdmResult, SPDM_STATUS_BUFFER_FULL};
+use crate::message::*;
+use crate::requester::*;
+use crate::time;
+
+/// Default RequesterContext::respond_if_ready_retry.
+pub const SPDM_MAX_RESPOND_IF_READY_RETRY: usize = 3;
+
+impl<'a> RequesterContext<'a> {
+    /// Replace an ERROR(ResponseNotReady) in receive_buffer by the response
+    /// retrieved with RESPOND_IF_READY, after waiting RDT as the responder asked.
+    /// Nothing is done without a registered time source.
+    pub(crate) fn receive_deferred_response(
+        &mut self,
+        session_id: Option<u32>,
+        receive_buffer: &mut [u8],
+        used: usize,
+    ) -> SpdmResult<usize> {
+        if !time::is_registered() {
+            return Ok(used);
+        }
+
+        let mut used = used;
+        for _ in 0..self.respond_if_ready_retry {
+            let not_ready = match self.get_response_not_ready(&receive_buffer[..used]) {
+                Some(not_ready) => not_ready,
+                None => return Ok(used),
+            };
+            let rdt = timing::exponent_to_us(not_ready.rdt_exponent)
+                .saturating_mul(not_ready.rdtm as u64);
+            info!(
+                "response not ready, respond if ready in {} us : token {:02x?}\n",
+                rdt, not_ready.token
+            );
+            time::sleep(rdt.min(usize::MAX as u64) as usize);
+            self.common.check_cancelled()?;
+
+            let mut send_buffer = self.common.alloc_message_buffer();
+            let send_used = self.encode_spdm_respond_if_ready(
+                not_ready.request_code,
+                not_ready.token,
+                &mut send_buffer,
+            )?;
+            match session_id {
+                Some(session_id) => {
+                    self.send_secured_message(session_id, &send_buffer[..send_used], false)?
+                }
+                None => self.send_message(&send_buffer[..send_used])?,
+            }
+            self.common.free_buffer(send_buffer);
+
+            used = self.receive_single_message(session_id, receive_buffer, true)?;
+        }
+        Ok(used)
+    }
+
+    fn get_response_not_ready(
+        &mut self,
+        receive_buffer: &[u8],
+    ) -> Option<SpdmErrorResponseNotReadyExtData> {
+        let mut reader = Reader::init(receive_buffer);
+        let message_header = SpdmMessageHeader::read(&mut reader)?;
+        if message_header.version != self.common.negotiate_info.spdm_version_sel
+            || message_header.request_response_code != SpdmRequestResponseCode::SpdmResponseError
+        {
+            return None;
+        }
+        let error_response = SpdmErrorResponsePayload::spdm_read(&mut self.common, &mut reader)?;
+        match error_response.extended_data {
+            SpdmErrorResponseExtData::SpdmErrorExtDataNotReady(extended_data) => {
+                Some(extended_data)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn encode_spdm_respond_if_ready(
+        &mut self,
+        request_code: u8,
+        token: u8,
+        buf: &mut [u8],
+    ) -> SpdmResult<usize> {
+        let mut writer = Writer::init(buf);
+        let header = SpdmMessageHeader {
+            version: self.common.negotiate_info.spdm_version_sel,
+            request_response_code: SpdmRequestResponseCode::SpdmRequestResponseIfReady,
+        };
+        header
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_BUFFER_FULL)?;
+        SpdmRespondIfReadyRequestPayload {
+            request_code,
+            token,
+        }
+        .spdm_encode(&mut self.common, &mut writer)?;
+        Ok(writer.used())
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! futures Stream/Sink of application messages over an established session.
+//!
+//! poll_next is Pending until SpdmDeviceIo::poll_receive_ready reports a
+//! message, the device wakes the task once one arrives. spdmlib is
+//! synchronous, so the receive and send themselves are done inline in
+//! poll_next and poll_flush.
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_sink::Sink;
+
+use crate::common::session::SpdmSessionState;
+use crate::error::{SpdmResult, SpdmStatus, SPDM_STATUS_INVALID_STATE_LOCAL};
+use crate::requester::*;
+
+pub struct SpdmSessionStream<'r, 'a> {
+    requester: &'r mut RequesterContext<'a>,
+    session_id: u32,
+    pending: Option<Bytes>,
+}
+
+impl<'r, 'a> SpdmSessionStream<'r, 'a> {
+    pub fn new(requester: &'r mut RequesterContext<'a>, session_id: u32) -> SpdmResult<Self> {
+        let stream = SpdmSessionStream {
+            requester,
+            session_id,
+            pending: None,
+        };
+        if !stream.is_established() {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        Ok(stream)
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.session_id
+    }
+
+    /// Give the requester back, e.g. to end the session.
+    pub fn into_inner(self) -> &'r mut RequesterContext<'a> {
+        self.requester
+    }
+
+    fn is_established(&self) -> bool {
+        matches!(
+            self.requester
+                .common
+                .get_immutable_session_via_id(self.session_id)
+                .map(|session| session.get_session_state()),
+            Some(SpdmSessionState::SpdmSessionEstablished)
+        )
+    }
+
+    fn flush_pending(&mut self) -> SpdmResult {
+        if let Some(message) = self.pending.take() {
+            self.requester
+                .send_secured_message(self.session_id, &message, true)?;
+        }
+        Ok(())
+    }
+}
+
+/// Each item is one application message, the stream ends with the session.
+impl<'r, 'a> Stream for SpdmSessionStream<'r, 'a> {
+    type Item = SpdmResult<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if !this.is_established() {
+            return Poll::Ready(None);
+        }
+        if !this
+            .requester
+            .common
+            .device_io
+            .poll_receive_ready(cx.waker())
+        {
+            return Poll::Pending;
+        }
+        let mut receive_buffer = this.requester.common.alloc_message_buffer();
+        let result = this
+            .requester
+            .receive_single_message(Some(this.session_id), &mut receive_buffer, false)
+            .map(|used| Bytes::copy_from_slice(&receive_buffer[..used]));
+        this.requester.common.free_buffer(receive_buffer);
+        Poll::Ready(Some(result))
+    }
+}
+
+/// Each item is sent as one application message, when flushed.
+impl<'r, 'a> Sink<Bytes> for SpdmSessionStream<'r, 'a> {
+    type Error = SpdmStatus;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<SpdmResult> {
+        let this = self.get_mut();
+        if !this.is_established() {
+            return Poll::Ready(Err(SPDM_STATUS_INVALID_STATE_LOCAL));
+        }
+        Poll::Ready(this.flush_pending())
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> SpdmResult {
+        let this = self.get_mut();
+        this.flush_pending()?;
+        this.pending = Some(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<SpdmResult> {
+        Poll::Ready(self.get_mut().flush_pending())
+    }
+
+    /// The session is kept, end it with into_inner().end_session().
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<SpdmResult> {
+        Poll::Ready(self.get_mut().flush_pending())
+    }
+}
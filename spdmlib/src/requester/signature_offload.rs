@@ -0,0 +1,71 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::SpdmResult;
+use crate::message::SpdmRequestResponseCode;
+use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmDigestStruct, SpdmSignatureStruct};
+use crate::requester::*;
+
+use conquer_once::spin::OnceCell;
+
+/// External verifier of responder signatures, e.g. a remote verification
+/// service or a secure element, used instead of crypto::asym_verify.
+///
+/// request_response_code is the signed response: CHALLENGE_AUTH, MEASUREMENTS
+/// or KEY_EXCHANGE_RSP. transcript_hash is the hash of the signed transcript,
+/// message is the exact data covered by the signature (the SPDM 1.2 signing
+/// prefix and transcript hash, or the whole transcript before 1.2).
+/// For SPDM_PUB_KEY_SLOT_ID public_cert_der is the provisioned public key.
+#[derive(Clone, Copy)]
+pub struct SpdmSignatureOffload {
+    pub verify_cb: fn(
+        request_response_code: SpdmRequestResponseCode,
+        slot_id: u8,
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        public_cert_der: &[u8],
+        transcript_hash: &SpdmDigestStruct,
+        message: &[u8],
+        signature: &SpdmSignatureStruct,
+    ) -> SpdmResult,
+}
+
+static SIGNATURE_OFFLOAD: OnceCell<SpdmSignatureOffload> = OnceCell::uninit();
+
+pub fn register_signature_offload(context: SpdmSignatureOffload) -> bool {
+    SIGNATURE_OFFLOAD.try_init_once(|| context).is_ok()
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Verify a responder signature with the registered offload verifier,
+    /// or locally if none is registered.
+    pub(crate) fn verify_peer_signature(
+        &self,
+        request_response_code: SpdmRequestResponseCode,
+        slot_id: u8,
+        transcript_hash: &SpdmDigestStruct,
+        public_cert_der: &[u8],
+        message: &[u8],
+        signature: &SpdmSignatureStruct,
+    ) -> SpdmResult {
+        let base_hash_algo = self.common.negotiate_info.base_hash_sel;
+        let base_asym_algo = self.common.negotiate_info.base_asym_sel;
+        match SIGNATURE_OFFLOAD.try_get() {
+            Ok(offload) => (offload.verify_cb)(
+                request_response_code,
+                slot_id,
+                base_hash_algo,
+                base_asym_algo,
+                public_cert_der,
+                transcript_hash,
+                message,
+                signature,
+            ),
+            Err(_) => {
+                self.common
+                    .verify_asym_signature(slot_id, public_cert_der, message, signature)
+            }
+        }
+    }
+}
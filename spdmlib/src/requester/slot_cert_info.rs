@@ -0,0 +1,226 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_VERIF_FAIL};
+use crate::message::SpdmKeyPairInfoResponsePayload;
+use crate::protocol::*;
+use crate::requester::*;
+use alloc::vec::Vec;
+
+/// Certificate information of one responder slot.
+///
+/// Outside a SPDM 1.3 multi-key connection DIGESTS carries no per-slot
+/// certificate info nor key usage mask. The certificate model then follows
+/// the negotiated ALIAS_CERT_CAP for every slot and key_usage is empty,
+/// meaning the key is not restricted to any use.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmSlotCertInfo {
+    pub slot_id: u8,
+    /// Key pair id of the slot, 0 outside a multi-key connection.
+    pub key_pair_id: u8,
+    pub cert_model: SpdmCertificateModelType,
+    pub key_usage: SpdmKeyUsageMask,
+    /// True if the cert chain of the slot is retrieved and matches the digest.
+    pub cert_chain_verified: bool,
+}
+
+impl SpdmSlotCertInfo {
+    /// Return true if the key of this slot may be used for key_usage.
+    pub fn is_usage_allowed(&self, key_usage: SpdmKeyUsageMask) -> bool {
+        self.key_usage.is_empty() || self.key_usage.contains(key_usage)
+    }
+
+    /// Check the slot against the KEY_PAIR_INFO of its key pair: the key
+    /// pair must be the one of the slot, be associated with the slot and
+    /// currently allow every key usage reported for the slot.
+    pub fn check_key_pair_info(
+        &self,
+        key_pair_info: &SpdmKeyPairInfoResponsePayload,
+    ) -> SpdmResult {
+        let info = &key_pair_info.key_pair_info;
+        if self.key_pair_id == 0
+            || key_pair_info.key_pair_id != self.key_pair_id
+            || key_pair_info.key_pair_id > key_pair_info.total_key_pairs
+            || info.assoc_cert_slot_mask & (1 << self.slot_id) == 0
+            || !info.current_key_usage.contains(self.key_usage)
+        {
+            error!(
+                "!!! slot {} mismatches key pair {} info !!!\n",
+                self.slot_id, key_pair_info.key_pair_id
+            );
+            return Err(SPDM_STATUS_VERIF_FAIL);
+        }
+        Ok(())
+    }
+}
+
+/// Populated slots of the responder, as reported by DIGESTS.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmResponderSlots {
+    pub slot_mask: u8,
+    /// Digest of the cert chain of each populated slot, indexed by slot id.
+    pub digests: [Option<SpdmDigestStruct>; SPDM_MAX_SLOT_NUMBER],
+    /// Cert chain of each populated slot as presented by the responder,
+    /// with the SPDM cert chain header and root hash, if retrieved.
+    pub cert_chains: [Option<Vec<u8>>; SPDM_MAX_SLOT_NUMBER],
+}
+
+impl SpdmResponderSlots {
+    pub fn is_populated(&self, slot_id: u8) -> bool {
+        slot_id < SPDM_MAX_SLOT_NUMBER as u8 && self.slot_mask & (1 << slot_id) != 0
+    }
+
+    /// Ids of the populated slots, in increasing order.
+    pub fn slot_ids(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..SPDM_MAX_SLOT_NUMBER as u8).filter(move |slot_id| self.is_populated(*slot_id))
+    }
+
+    pub fn get_digest(&self, slot_id: u8) -> Option<&SpdmDigestStruct> {
+        self.digests.get(slot_id as usize)?.as_ref()
+    }
+
+    pub fn get_cert_chain(&self, slot_id: u8) -> Option<&[u8]> {
+        self.cert_chains
+            .get(slot_id as usize)?
+            .as_ref()
+            .map(|cert_chain| cert_chain.as_slice())
+    }
+}
+
+impl<'a> RequesterContext<'a> {
+    /// Issue GET_DIGESTS and report the populated slots of the responder.
+    ///
+    /// With fetch_cert_chains, GET_CERTIFICATE is then issued for every
+    /// populated slot and the retrieved chains are returned as well.
+    pub fn enumerate_slots(
+        &mut self,
+        session_id: Option<u32>,
+        fetch_cert_chains: bool,
+    ) -> SpdmResult<SpdmResponderSlots> {
+        self.send_receive_spdm_digest(session_id)?;
+
+        let mut slots = SpdmResponderSlots {
+            slot_mask: self.common.peer_info.peer_digest_slot_mask,
+            ..Default::default()
+        };
+        for slot_id in 0..SPDM_MAX_SLOT_NUMBER {
+            if slots.is_populated(slot_id as u8) {
+                slots.digests[slot_id] = Some(self.common.peer_info.peer_digests[slot_id].clone());
+            }
+        }
+
+        if fetch_cert_chains {
+            for slot_id in 0..SPDM_MAX_SLOT_NUMBER as u8 {
+                if !slots.is_populated(slot_id) {
+                    continue;
+                }
+                self.send_receive_spdm_certificate(session_id, slot_id)?;
+                slots.cert_chains[slot_id as usize] = self
+                    .common
+                    .get_peer_cert_chain_data(slot_id)
+                    .map(|cert_chain| cert_chain.to_vec());
+            }
+        }
+
+        Ok(slots)
+    }
+
+    /// Report the certificate information of slot_id from the last DIGESTS
+    /// and, if retrieved, the cert chain of the slot.
+    ///
+    /// Fail if the slot is not populated, or if the retrieved cert chain
+    /// does not match the digest reported by the responder.
+    pub fn get_slot_cert_info(&self, slot_id: u8) -> SpdmResult<SpdmSlotCertInfo> {
+        if slot_id >= SPDM_MAX_SLOT_NUMBER as u8
+            || self.common.peer_info.peer_digest_slot_mask & (1 << slot_id) == 0
+        {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        let (key_pair_id, cert_model, key_usage) = if self.common.is_multi_key_conn() {
+            let cert_model = self.common.peer_info.peer_cert_model[slot_id as usize];
+            let key_usage = self.common.peer_info.peer_key_usage_mask[slot_id as usize];
+            // a populated slot must carry a certificate and at least one key usage
+            if cert_model == SpdmCertificateModelType::SpdmCertModelTypeNone || key_usage.is_empty()
+            {
+                error!("!!! slot {} certificate info is invalid !!!\n", slot_id);
+                return Err(SPDM_STATUS_VERIF_FAIL);
+            }
+            (
+                self.common.peer_info.peer_key_pair_id[slot_id as usize],
+                cert_model,
+                key_usage,
+            )
+        } else if self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::ALIAS_CERT_CAP)
+        {
+            (
+                0,
+                SpdmCertificateModelType::SpdmCertModelTypeAliasCert,
+                SpdmKeyUsageMask::empty(),
+            )
+        } else {
+            (
+                0,
+                SpdmCertificateModelType::SpdmCertModelTypeDeviceCert,
+                SpdmKeyUsageMask::empty(),
+            )
+        };
+
+        let cert_chain_verified = match self.common.get_peer_cert_chain_hash(slot_id) {
+            Some(digest) => {
+                if digest.as_ref() != self.common.peer_info.peer_digests[slot_id as usize].as_ref()
+                {
+                    error!("!!! slot {} cert chain mismatches digest !!!\n", slot_id);
+                    return Err(SPDM_STATUS_VERIF_FAIL);
+                }
+                true
+            }
+            None => false,
+        };
+
+        Ok(SpdmSlotCertInfo {
+            slot_id,
+            key_pair_id,
+            cert_model,
+            key_usage,
+            cert_chain_verified,
+        })
+    }
+
+    /// Issue GET_KEY_PAIR_INFO for the key pair of slot_id, as reported by
+    /// the last DIGESTS, and check that it matches the slot.
+    ///
+    /// Only meaningful on a multi-key connection, elsewhere the slot has no
+    /// key pair id and the function fails with SPDM_STATUS_INVALID_PARAMETER.
+    pub fn get_slot_key_pair_info(
+        &mut self,
+        session_id: Option<u32>,
+        slot_id: u8,
+    ) -> SpdmResult<SpdmKeyPairInfoResponsePayload> {
+        let slot = self.get_slot_cert_info(slot_id)?;
+        if slot.key_pair_id == 0 {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        let key_pair_info = self.send_receive_spdm_key_pair_info(session_id, slot.key_pair_id)?;
+        slot.check_key_pair_info(&key_pair_info)?;
+        Ok(key_pair_info)
+    }
+
+    /// Report every populated slot, indexed by slot id.
+    pub fn get_all_slot_cert_info(
+        &self,
+    ) -> SpdmResult<[Option<SpdmSlotCertInfo>; SPDM_MAX_SLOT_NUMBER]> {
+        let mut slots: [Option<SpdmSlotCertInfo>; SPDM_MAX_SLOT_NUMBER] = Default::default();
+        for (slot_id, slot) in slots.iter_mut().enumerate() {
+            if self.common.peer_info.peer_digest_slot_mask & (1 << slot_id) != 0 {
+                *slot = Some(self.get_slot_cert_info(slot_id as u8)?);
+            }
+        }
+        Ok(slots)
+    }
+}
@@ -21,7 +21,7 @@ impl<'a> RequesterContext<'a> {
             session_id,
         );
 
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let mut writer = Writer::init(&mut send_buffer);
         let request = SpdmMessage {
             header: SpdmMessageHeader {
@@ -48,7 +48,7 @@ impl<'a> RequesterContext<'a> {
         }
 
         //receive
-        let mut receive_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut receive_buffer = self.common.alloc_message_buffer();
         let receive_used = match session_id {
             Some(session_id) => {
                 self.receive_secured_message(session_id, &mut receive_buffer, false)?
@@ -56,7 +56,11 @@ impl<'a> RequesterContext<'a> {
             None => self.receive_message(&mut receive_buffer, false)?,
         };
 
-        self.handle_spdm_vendor_defined_respond(session_id, &receive_buffer[..receive_used])
+        let result =
+            self.handle_spdm_vendor_defined_respond(session_id, &receive_buffer[..receive_used]);
+        self.common.free_buffer(receive_buffer);
+        self.common.free_buffer(send_buffer);
+        result
     }
 
     pub fn handle_spdm_vendor_defined_respond(
@@ -11,10 +11,10 @@ use crate::responder::*;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_algorithm(&mut self, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_algorithm(bytes, &mut writer);
-        self.send_message(writer.used_slice())
+        self.send_response(None, |responder, writer| {
+            responder.write_spdm_algorithm(bytes, writer);
+            Ok(())
+        })
     }
 
     pub fn write_spdm_algorithm(&mut self, bytes: &[u8], writer: &mut Writer) {
@@ -0,0 +1,61 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::SpdmConnectionState;
+use crate::crypto;
+use crate::error::{
+    SpdmResult, SPDM_STATUS_INVALID_CERT, SPDM_STATUS_INVALID_PARAMETER,
+    SPDM_STATUS_INVALID_STATE_LOCAL, SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use crate::protocol::{SpdmResponseCapabilityFlags, SPDM_MAX_SLOT_NUMBER};
+use crate::responder::*;
+use crate::secret;
+
+impl<'a> ResponderContext<'a> {
+    /// Replace the alias cert chain of slot_id by the one regenerated with
+    /// secret::alias_cert, to be called when the measurements change. The
+    /// new chain is reported by the following DIGESTS and CERTIFICATE.
+    pub fn regenerate_alias_cert_chain(&mut self, slot_id: u8) -> SpdmResult {
+        if !self
+            .common
+            .config_info
+            .rsp_capabilities
+            .contains(SpdmResponseCapabilityFlags::ALIAS_CERT_CAP)
+        {
+            return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+        }
+        if slot_id as usize >= SPDM_MAX_SLOT_NUMBER {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        let cert_chain_data = secret::alias_cert::generate_alias_cert_chain(
+            self.common.negotiate_info.spdm_version_sel,
+            self.common.negotiate_info.base_hash_sel,
+            self.common.negotiate_info.base_asym_sel,
+            slot_id,
+        )
+        .ok_or(SPDM_STATUS_INVALID_STATE_LOCAL)?;
+
+        // the alias certificate carries the TCB of the measured layers
+        let certs = cert_chain_data.as_ref();
+        let (leaf_begin, leaf_end) = crypto::cert_operation::get_cert_from_cert_chain(certs, -1)?;
+        let leaf_info = crypto::get_cert_info(&certs[leaf_begin..leaf_end])
+            .map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+        if !leaf_info.has_dice_tcb_info() || leaf_info.get_dice_tcb_info().is_err() {
+            error!("!!! alias cert without DICE TcbInfo !!!\n");
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+
+        let slot_id = slot_id as usize;
+        self.common.provision_info.my_cert_chain_data[slot_id] = Some(cert_chain_data);
+        self.common.provision_info.my_cert_chain[slot_id] = None;
+        // otherwise the chain is built once the algorithms are negotiated
+        if self.common.runtime_info.get_connection_state().get_u8()
+            >= SpdmConnectionState::SpdmConnectionNegotiated.get_u8()
+        {
+            self.common.construct_my_cert_chain()?;
+        }
+        Ok(())
+    }
+}
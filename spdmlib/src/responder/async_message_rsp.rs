@@ -0,0 +1,31 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::{SpdmResult, SPDM_STATUS_SEND_FAIL};
+use crate::responder::*;
+
+impl<'a> ResponderContext<'a> {
+    /// Deliver a message to the requester without a request, e.g. an event.
+    /// The device IO signals the pending message through the transport,
+    /// for example with the PCI DOE async message mechanism.
+    pub fn send_async_message(&mut self, session_id: Option<u32>, message: &[u8]) -> SpdmResult {
+        if message.len() < 2 {
+            return Err(SPDM_STATUS_SEND_FAIL);
+        }
+        let mut transport_buffer = self.common.alloc_sender_buffer();
+        let used = match session_id {
+            Some(session_id) => self.common.encode_secured_message(
+                session_id,
+                message,
+                &mut transport_buffer,
+                false,
+                false,
+            )?,
+            None => self.common.encap(message, &mut transport_buffer)?,
+        };
+        let result = self.common.device_io.send_async(&transport_buffer[..used]);
+        self.common.free_buffer(transport_buffer);
+        result
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::attestation_report::SpdmAttestationReport;
+use crate::error::{SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CRYPTO_ERROR};
+use crate::responder::*;
+
+impl<'a> ResponderContext<'a> {
+    /// Encode and sign report into buffer with one signature operation,
+    /// e.g. from a vendor defined request handler. Return the size.
+    /// The requester nonce must be in report, the negotiated version >= 1.2.
+    pub fn generate_attestation_report(
+        &self,
+        report: &SpdmAttestationReport,
+        buffer: &mut [u8],
+    ) -> SpdmResult<usize> {
+        let used = report.encode_body(&mut Writer::init(buffer))?;
+        let (_, message_sign) = self.common.attestation_report_sign_data(&buffer[..used])?;
+
+        let signature = self
+            .common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                message_sign.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+        let signature = signature.as_ref();
+        if buffer.len() < used + signature.len() {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+        buffer[used..used + signature.len()].copy_from_slice(signature);
+        Ok(used + signature.len())
+    }
+}
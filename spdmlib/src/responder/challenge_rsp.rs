@@ -18,14 +18,13 @@ extern crate alloc;
 #[cfg(feature = "hashed-transcript-data")]
 use crate::error::SPDM_STATUS_INVALID_STATE_LOCAL;
 use crate::error::{SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CRYPTO_ERROR};
-use crate::secret;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_challenge(&mut self, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_challenge_response(bytes, &mut writer);
-        self.send_message(writer.used_slice())
+        self.send_response(None, |responder, writer| {
+            responder.write_spdm_challenge_response(bytes, writer);
+            Ok(())
+        })
     }
 
     pub fn write_spdm_challenge_response(&mut self, bytes: &[u8], writer: &mut Writer) {
@@ -62,13 +61,7 @@ impl<'a> ResponderContext<'a> {
             {
                 self.common.runtime_info.need_measurement_summary_hash = true;
                 let measurement_summary_hash_res =
-                    secret::measurement::generate_measurement_summary_hash(
-                        self.common.negotiate_info.spdm_version_sel,
-                        self.common.negotiate_info.base_hash_sel,
-                        self.common.negotiate_info.measurement_specification_sel,
-                        self.common.negotiate_info.measurement_hash_sel,
-                        challenge.measurement_summary_hash_type,
-                    );
+                    self.generate_measurement_summary_hash(challenge.measurement_summary_hash_type);
                 if measurement_summary_hash_res.is_none() {
                     self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
                     return;
@@ -90,14 +83,22 @@ impl<'a> ResponderContext<'a> {
 
         let challenge = challenge.unwrap();
         let slot_id = challenge.slot_id as usize;
-        if slot_id >= SPDM_MAX_SLOT_NUMBER {
+        if !self.common.is_rsp_slot_id_valid(challenge.slot_id) {
             self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
             return;
         }
-        if self.common.provision_info.my_cert_chain[slot_id].is_none() {
+        if self
+            .common
+            .get_local_cert_chain_data(challenge.slot_id)
+            .is_none()
+        {
             self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
             return;
         }
+        if self.is_slot_quarantined(slot_id) {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+            return;
+        }
 
         if self
             .common
@@ -108,14 +109,10 @@ impl<'a> ResponderContext<'a> {
             return;
         }
 
-        let my_cert_chain = self.common.provision_info.my_cert_chain[slot_id]
-            .as_ref()
+        let cert_chain_hash = self
+            .common
+            .get_certchain_hash_local(false, slot_id)
             .unwrap();
-        let cert_chain_hash = crypto::hash::hash_all(
-            self.common.negotiate_info.base_hash_sel,
-            my_cert_chain.as_ref(),
-        )
-        .unwrap();
 
         let mut nonce = [0u8; SPDM_NONCE_SIZE];
         let res = crypto::rand::get_random(&mut nonce);
@@ -124,6 +121,22 @@ impl<'a> ResponderContext<'a> {
             return;
         }
 
+        let mut opaque = SpdmOpaqueStruct {
+            data_size: 0,
+            data: [0u8; MAX_SPDM_OPAQUE_SIZE],
+        };
+        if self
+            .common
+            .append_provided_opaque_elements(
+                SpdmRequestResponseCode::SpdmResponseChallengeAuth,
+                &mut opaque,
+            )
+            .is_err()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+            return;
+        }
+
         info!("send spdm challenge_auth\n");
 
         let response = SpdmMessage {
@@ -133,20 +146,23 @@ impl<'a> ResponderContext<'a> {
             },
             payload: SpdmMessagePayload::SpdmChallengeAuthResponse(
                 SpdmChallengeAuthResponsePayload {
-                    slot_id: slot_id as u8,
-                    slot_mask: 0x1,
+                    // the public key is reported as slot 0xF with no slot mask
+                    slot_id: slot_id as u8 & 0xF,
+                    slot_mask: if challenge.slot_id == SPDM_PUB_KEY_SLOT_ID {
+                        0x0
+                    } else {
+                        0x1
+                    },
                     challenge_auth_attribute: SpdmChallengeAuthAttribute::empty(),
                     cert_chain_hash,
                     nonce: SpdmNonceStruct { data: nonce },
                     measurement_summary_hash,
-                    opaque: SpdmOpaqueStruct {
-                        data_size: 0,
-                        data: [0u8; MAX_SPDM_OPAQUE_SIZE],
-                    },
+                    opaque,
                     signature: SpdmSignatureStruct {
                         data_size: self.common.negotiate_info.base_asym_sel.get_size(),
                         data: [0xbb; SPDM_MAX_ASYM_KEY_SIZE],
                     },
+                    requester_context: challenge.requester_context,
                 },
             ),
         };
@@ -171,11 +187,18 @@ impl<'a> ResponderContext<'a> {
         }
 
         let signature = self.generate_challenge_auth_signature();
-        if signature.is_err() {
-            self.send_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0);
-            return;
-        }
-        let signature = signature.unwrap();
+        let signature = match self.check_signature_result(
+            SpdmRequestResponseCode::SpdmRequestChallenge,
+            slot_id,
+            signature,
+            writer,
+        ) {
+            Some(signature) => signature,
+            None => {
+                self.common.reset_message_c();
+                return;
+            }
+        };
         // patch the message before send
         writer.mut_used_slice()[(used - base_asym_size)..used].copy_from_slice(signature.as_ref());
 
@@ -203,7 +226,12 @@ impl<'a> ResponderContext<'a> {
         {
             message_sign.reset_message();
             message_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_4)
@@ -219,12 +247,14 @@ impl<'a> ResponderContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
-        crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            message_sign.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+        self.common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                message_sign.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
     }
 
     #[cfg(not(feature = "hashed-transcript-data"))]
@@ -253,7 +283,12 @@ impl<'a> ResponderContext<'a> {
         {
             message_m1m2.reset_message();
             message_m1m2
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_m1m2
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_4)
@@ -266,11 +301,13 @@ impl<'a> ResponderContext<'a> {
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         }
 
-        crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            message_m1m2.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+        self.common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                message_m1m2.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
     }
 }
@@ -0,0 +1,284 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::SpdmCodec;
+use crate::error::SpdmResult;
+use crate::message::*;
+use crate::protocol::*;
+use crate::responder::*;
+extern crate alloc;
+use alloc::vec::Vec;
+use zeroize::Zeroize;
+
+// SPDM header, param1, param2 and ChunkSeqNo.
+const SPDM_CHUNK_SEND_ACK_HEADER_SIZE: usize = 6;
+
+/// Reassembly state of a large request delivered with CHUNK_SEND.
+pub(crate) struct SpdmChunkSendContext {
+    in_progress: bool,
+    handle: u8,
+    // next expected ChunkSeqNo
+    chunk_seq_no: u16,
+    large_message_size: usize,
+    received_size: usize,
+    // sized to LargeMessageSize by the first chunk
+    large_message: Vec<u8>,
+    // (handle, ChunkSeqNo) of the CHUNK_SEND_ACK the response to the
+    // reassembled request has to be wrapped in.
+    pub(crate) ack_pending: Option<(u8, u16)>,
+}
+
+impl Default for SpdmChunkSendContext {
+    fn default() -> SpdmChunkSendContext {
+        SpdmChunkSendContext {
+            in_progress: false,
+            handle: 0,
+            chunk_seq_no: 0,
+            large_message_size: 0,
+            received_size: 0,
+            large_message: Vec::new(),
+            ack_pending: None,
+        }
+    }
+}
+
+impl SpdmChunkSendContext {
+    fn reset(&mut self) {
+        self.in_progress = false;
+        self.handle = 0;
+        self.chunk_seq_no = 0;
+        self.large_message_size = 0;
+        self.received_size = 0;
+        self.large_message.zeroize();
+    }
+
+    /// Append chunk to the large message.
+    /// Return true once the last chunk completes the large message.
+    fn receive_chunk(
+        &mut self,
+        chunk: &SpdmChunkSendRequestPayload,
+        data_transfer_size: usize,
+        max_spdm_msg_size: usize,
+    ) -> Result<bool, SpdmErrorCode> {
+        if chunk.chunk_seq_no == 0 {
+            // a new large request replaces any unfinished one
+            let large_message_size = chunk.large_message_size as usize;
+            if large_message_size <= data_transfer_size {
+                return Err(SpdmErrorCode::SpdmErrorInvalidRequest);
+            }
+            if large_message_size > max_spdm_msg_size {
+                return Err(SpdmErrorCode::SpdmErrorRequestTooLarge);
+            }
+            self.large_message.zeroize();
+            self.large_message.resize(large_message_size, 0);
+            self.in_progress = true;
+            self.handle = chunk.handle;
+            self.large_message_size = large_message_size;
+            self.received_size = 0;
+        } else if !self.in_progress
+            || chunk.handle != self.handle
+            || chunk.chunk_seq_no != self.chunk_seq_no
+        {
+            return Err(SpdmErrorCode::SpdmErrorInvalidRequest);
+        }
+
+        let chunk_size = chunk.chunk.len();
+        if chunk_size == 0 || chunk_size > self.large_message_size - self.received_size {
+            return Err(SpdmErrorCode::SpdmErrorInvalidRequest);
+        }
+        self.large_message[self.received_size..self.received_size + chunk_size]
+            .copy_from_slice(&chunk.chunk);
+        self.received_size += chunk_size;
+
+        if chunk
+            .chunk_sender_attributes
+            .contains(SpdmChunkSenderAttributes::LAST_CHUNK)
+        {
+            if self.received_size != self.large_message_size {
+                return Err(SpdmErrorCode::SpdmErrorInvalidRequest);
+            }
+            self.in_progress = false;
+            return Ok(true);
+        }
+        if self.received_size == self.large_message_size {
+            return Err(SpdmErrorCode::SpdmErrorInvalidRequest);
+        }
+        self.chunk_seq_no = chunk
+            .chunk_seq_no
+            .checked_add(1)
+            .ok_or(SpdmErrorCode::SpdmErrorInvalidRequest)?;
+        Ok(false)
+    }
+}
+
+impl<'a> ResponderContext<'a> {
+    pub fn handle_spdm_chunk_send(&mut self, session_id: Option<u32>, bytes: &[u8]) -> SpdmResult {
+        if !self
+            .common
+            .negotiate_info
+            .req_capabilities_sel
+            .contains(SpdmRequestCapabilityFlags::CHUNK_CAP)
+            || !self
+                .common
+                .negotiate_info
+                .rsp_capabilities_sel
+                .contains(SpdmResponseCapabilityFlags::CHUNK_CAP)
+        {
+            return self.handle_error_request(
+                SpdmErrorCode::SpdmErrorUnsupportedRequest,
+                session_id,
+                bytes,
+            );
+        }
+
+        let mut reader = Reader::init(bytes);
+        let chunk = match SpdmMessageHeader::read(&mut reader) {
+            Some(_) => SpdmChunkSendRequestPayload::spdm_read(&mut self.common, &mut reader),
+            None => None,
+        };
+        let chunk = match chunk {
+            Some(chunk) => chunk,
+            None => {
+                self.chunk_send_context.reset();
+                return self.handle_error_request(
+                    SpdmErrorCode::SpdmErrorInvalidRequest,
+                    session_id,
+                    bytes,
+                );
+            }
+        };
+
+        let data_transfer_size = self.common.negotiate_info.rsp_data_transfer_size_sel as usize;
+        let max_spdm_msg_size = self.common.config_info.max_spdm_msg_size as usize;
+        match self
+            .chunk_send_context
+            .receive_chunk(&chunk, data_transfer_size, max_spdm_msg_size)
+        {
+            Err(error_code) => {
+                error!("!!! chunk send : fail {:?} !!!\n", error_code);
+                self.chunk_send_context.reset();
+                let mut err_buffer = self.common.alloc_message_buffer();
+                let mut writer = Writer::init(&mut err_buffer);
+                self.write_spdm_error(error_code, 0, &mut writer);
+                let used = writer.used();
+                let result = self.send_spdm_chunk_send_ack(
+                    session_id,
+                    SpdmChunkReceiverAttributes::EARLY_ERROR_DETECTED,
+                    chunk.handle,
+                    chunk.chunk_seq_no,
+                    &err_buffer[..used],
+                );
+                self.common.free_buffer(err_buffer);
+                result
+            }
+            Ok(false) => self.send_spdm_chunk_send_ack(
+                session_id,
+                SpdmChunkReceiverAttributes::empty(),
+                chunk.handle,
+                chunk.chunk_seq_no,
+                &[],
+            ),
+            Ok(true) => {
+                let size = self.chunk_send_context.large_message_size;
+                let mut large_request = self.common.alloc_buffer(size);
+                large_request.copy_from_slice(&self.chunk_send_context.large_message[..size]);
+                self.chunk_send_context.reset();
+                let result = self.dispatch_large_request(
+                    session_id,
+                    chunk.handle,
+                    chunk.chunk_seq_no,
+                    &large_request,
+                );
+                self.common.free_buffer(large_request);
+                result
+            }
+        }
+    }
+
+    /// Dispatch the reassembled request, its response is sent in the
+    /// CHUNK_SEND_ACK of the last chunk.
+    fn dispatch_large_request(
+        &mut self,
+        session_id: Option<u32>,
+        handle: u8,
+        chunk_seq_no: u16,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        self.chunk_send_context.ack_pending = Some((handle, chunk_seq_no));
+
+        let is_chunk_send =
+            bytes.get(1).copied() == Some(SpdmRequestResponseCode::SpdmRequestChunkSend.get_u8());
+        let result = if is_chunk_send {
+            self.handle_error_request(SpdmErrorCode::SpdmErrorUnexpectedRequest, session_id, bytes)
+        } else {
+            match session_id {
+                Some(session_id) => self.dispatch_secured_message(session_id, bytes),
+                None => self.dispatch_message(bytes),
+            }
+        };
+
+        // The request was dropped without a response, the ack must still be sent.
+        if self.chunk_send_context.ack_pending.is_some() {
+            return self.handle_error_request(
+                SpdmErrorCode::SpdmErrorUnsupportedRequest,
+                session_id,
+                bytes,
+            );
+        }
+        result
+    }
+
+    /// Send CHUNK_SEND_ACK, with response wrapped in it for the last chunk.
+    pub(crate) fn send_spdm_chunk_send_ack(
+        &mut self,
+        session_id: Option<u32>,
+        chunk_receiver_attributes: SpdmChunkReceiverAttributes,
+        handle: u8,
+        chunk_seq_no: u16,
+        response: &[u8],
+    ) -> SpdmResult {
+        self.chunk_send_context.ack_pending = None;
+
+        let mut err_buffer = self.common.alloc_message_buffer();
+        let mut response = response;
+        let data_transfer_size = self.common.negotiate_info.req_data_transfer_size_sel as usize;
+        if data_transfer_size != 0
+            && SPDM_CHUNK_SEND_ACK_HEADER_SIZE + response.len() > data_transfer_size
+        {
+            let mut writer = Writer::init(&mut err_buffer);
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorResponseTooLarge, 0, &mut writer);
+            let used = writer.used();
+            response = &err_buffer[..used];
+        }
+
+        let payload = SpdmChunkSendAckResponsePayload {
+            chunk_receiver_attributes,
+            handle,
+            chunk_seq_no,
+            response: response.to_vec(),
+        };
+
+        let result = self.send_response(session_id, |responder, writer| {
+            let ack = SpdmMessage {
+                header: SpdmMessageHeader {
+                    version: responder.common.negotiate_info.spdm_version_sel,
+                    request_response_code: SpdmRequestResponseCode::SpdmResponseChunkSendAck,
+                },
+                payload: SpdmMessagePayload::SpdmChunkSendAckResponse(payload),
+            };
+            ack.spdm_encode(&mut responder.common, writer)?;
+            Ok(())
+        });
+
+        // state changes follow the wrapped response
+        if result.is_ok() && response.len() >= 2 {
+            match session_id {
+                Some(session_id) => self.update_session_state(session_id, response[1]),
+                None => self.update_connection_state(response[1]),
+            }
+        }
+        self.common.free_buffer(err_buffer);
+        result
+    }
+}
@@ -3,16 +3,51 @@
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
 use super::app_message_handler::dispatch_secured_app_message_cb;
+use super::chunk_send_rsp::SpdmChunkSendContext;
+use super::deferred_rsp::{
+    SpdmDeferredContext, SpdmResponseReadyHandler, SPDM_DEFERRABLE_REQUESTS,
+};
+use super::dispatch::{SpdmRequestDispatchEntry, MAX_SPDM_REQUEST_HANDLER_COUNT};
+use super::event_rsp::SpdmEventContext;
+use super::measurement_provider::MeasurementProvider;
+use super::request_policy::{
+    get_reject_error_code, get_request_state_error_code, is_request_supported_in_version,
+    SpdmRequestStates,
+};
+use super::secure_version::SpdmSecureVersionSelector;
+use super::sign_failure::SpdmSignFailureStats;
+use crate::common::timing::SpdmLatencyStats;
 use crate::common::SpdmConnectionState;
-use crate::common::{session::SpdmSessionState, SpdmDeviceIo, SpdmTransportEncap};
-use crate::config;
-use crate::error::{SpdmResult, SPDM_STATUS_UNSUPPORTED_CAP};
+use crate::common::{
+    session::SpdmSessionState, SpdmDeviceIo, SpdmDeviceIoEvent, SpdmMessageDirection,
+    SpdmTransportEncap,
+};
+use crate::error::{SpdmResult, SPDM_STATUS_SEND_FAIL, SPDM_STATUS_UNSUPPORTED_CAP};
 use crate::message::*;
-use crate::protocol::{SpdmRequestCapabilityFlags, SpdmResponseCapabilityFlags};
+use crate::protocol::SPDM_MAX_SLOT_NUMBER;
+use crate::time;
 use codec::{Codec, Reader, Writer};
 
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
 pub struct ResponderContext<'a> {
     pub common: crate::common::SpdmContext<'a>,
+    pub(crate) chunk_send_context: SpdmChunkSendContext,
+    pub(crate) event_context: SpdmEventContext,
+    pub(crate) deferred_context: SpdmDeferredContext,
+    // None answers every request right away
+    pub response_ready_handler: Option<SpdmResponseReadyHandler>,
+    pub(crate) sign_failure_stats: [SpdmSignFailureStats; SPDM_MAX_SLOT_NUMBER],
+    // latency of the deferrable request handlers, recorded when a time source is
+    // registered, e.g. to size rsp_ct_exponent with suggest_ct_exponent
+    pub crypto_latency: SpdmLatencyStats,
+    pub(crate) request_handlers: [Option<SpdmRequestDispatchEntry>; MAX_SPDM_REQUEST_HANDLER_COUNT],
+    // None takes the measurements from common.secret_provider
+    pub measurement_provider: Option<Box<dyn MeasurementProvider>>,
+    // None falls back to the registered secure version selector
+    pub secure_version_selector: Option<SpdmSecureVersionSelector>,
 }
 
 impl<'a> ResponderContext<'a> {
@@ -29,67 +64,161 @@ impl<'a> ResponderContext<'a> {
                 config_info,
                 provision_info,
             ),
+            chunk_send_context: SpdmChunkSendContext::default(),
+            event_context: SpdmEventContext::default(),
+            deferred_context: SpdmDeferredContext::default(),
+            response_ready_handler: None,
+            sign_failure_stats: [SpdmSignFailureStats::default(); SPDM_MAX_SLOT_NUMBER],
+            crypto_latency: SpdmLatencyStats::default(),
+            request_handlers: [None; MAX_SPDM_REQUEST_HANDLER_COUNT],
+            measurement_provider: None,
+            secure_version_selector: None,
         }
     }
 
     pub fn send_message(&mut self, send_buffer: &[u8]) -> SpdmResult {
+        if let Some((handle, chunk_seq_no)) = self.chunk_send_context.ack_pending {
+            return self.send_spdm_chunk_send_ack(
+                None,
+                SpdmChunkReceiverAttributes::empty(),
+                handle,
+                chunk_seq_no,
+                send_buffer,
+            );
+        }
         if self.common.negotiate_info.req_data_transfer_size_sel != 0
             && (send_buffer.len() > self.common.negotiate_info.req_data_transfer_size_sel as usize)
         {
-            let mut err_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+            let mut err_buffer = self.common.alloc_message_buffer();
             let mut writer = Writer::init(&mut err_buffer);
             self.write_spdm_error(SpdmErrorCode::SpdmErrorResponseTooLarge, 0, &mut writer);
-            return self.send_message(writer.used_slice());
+            let used = writer.used();
+            let result = self.send_message(&err_buffer[..used]);
+            self.common.free_buffer(err_buffer);
+            return result;
         }
-        let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let mut transport_buffer = self.common.alloc_sender_buffer();
         let used = self.common.encap(send_buffer, &mut transport_buffer)?;
-        let result = self.common.device_io.send(&transport_buffer[..used]);
+        let result = self.common.device_io_send(&transport_buffer[..used], false);
+        self.common.free_buffer(transport_buffer);
         if result.is_ok() {
-            let opcode = send_buffer[1];
-            if opcode == SpdmRequestResponseCode::SpdmResponseVersion.get_u8() {
-                self.common
-                    .runtime_info
-                    .set_connection_state(SpdmConnectionState::SpdmConnectionAfterVersion);
-            } else if opcode == SpdmRequestResponseCode::SpdmResponseCapabilities.get_u8() {
-                self.common
-                    .runtime_info
-                    .set_connection_state(SpdmConnectionState::SpdmConnectionAfterCapabilities);
-            } else if opcode == SpdmRequestResponseCode::SpdmResponseAlgorithms.get_u8() {
+            self.update_connection_state(send_buffer[1]);
+        }
+        result
+    }
+
+    /// Send the spdm_size bytes response which is already encoded right after
+    /// the reserved transport header of transport_buffer.
+    pub fn send_message_in_place(
+        &mut self,
+        transport_buffer: &mut [u8],
+        spdm_size: usize,
+    ) -> SpdmResult {
+        let header_size = self.common.transport_encap.get_header_size(false);
+        if self.chunk_send_context.ack_pending.is_some() {
+            if transport_buffer.len() < header_size + spdm_size {
+                return Err(SPDM_STATUS_SEND_FAIL);
+            }
+            let mut send_buffer = self.common.alloc_buffer(spdm_size);
+            send_buffer.copy_from_slice(&transport_buffer[header_size..(header_size + spdm_size)]);
+            let result = self.send_message(&send_buffer);
+            self.common.free_buffer(send_buffer);
+            return result;
+        }
+        if self.common.negotiate_info.req_data_transfer_size_sel != 0
+            && (spdm_size > self.common.negotiate_info.req_data_transfer_size_sel as usize)
+        {
+            let mut writer = Writer::init(&mut transport_buffer[header_size..]);
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorResponseTooLarge, 0, &mut writer);
+            let used = writer.used();
+            return self.send_message_in_place(transport_buffer, used);
+        }
+        if spdm_size < 2 || transport_buffer.len() < header_size + spdm_size {
+            return Err(SPDM_STATUS_SEND_FAIL);
+        }
+        let opcode = transport_buffer[header_size + 1];
+        let used = self.common.encap_in_place(transport_buffer, spdm_size)?;
+        let result = self.common.device_io_send(&transport_buffer[..used], false);
+        if result.is_ok() {
+            self.update_connection_state(opcode);
+        }
+        result
+    }
+
+    /// Encode the response with write_response right after the reserved transport
+    /// header, so plain text responses are sent without an intermediate SPDM buffer
+    /// and secured responses reuse the same buffer while being encrypted.
+    pub fn send_response<F>(&mut self, session_id: Option<u32>, write_response: F) -> SpdmResult
+    where
+        F: FnOnce(&mut Self, &mut Writer) -> SpdmResult,
+    {
+        let mut transport_buffer = self.common.alloc_sender_buffer();
+        let header_size = self.common.transport_encap.get_header_size(false);
+        let max_spdm_msg_size = self.common.buffer_config.max_spdm_msg_size;
+        if transport_buffer.len() < header_size + max_spdm_msg_size {
+            return Err(SPDM_STATUS_SEND_FAIL);
+        }
+        let used = {
+            let mut writer =
+                Writer::init(&mut transport_buffer[header_size..(header_size + max_spdm_msg_size)]);
+            write_response(self, &mut writer)?;
+            writer.used()
+        };
+        let result = match session_id {
+            Some(session_id) => self.send_secured_message_in_place(
+                session_id,
+                &mut transport_buffer[header_size..],
+                used,
+            ),
+            None => self.send_message_in_place(&mut transport_buffer, used),
+        };
+        self.common.free_buffer(transport_buffer);
+        result
+    }
+
+    pub(crate) fn update_connection_state(&mut self, opcode: u8) {
+        if opcode == SpdmRequestResponseCode::SpdmResponseVersion.get_u8() {
+            self.common
+                .runtime_info
+                .set_connection_state(SpdmConnectionState::SpdmConnectionAfterVersion);
+        } else if opcode == SpdmRequestResponseCode::SpdmResponseCapabilities.get_u8() {
+            self.common
+                .runtime_info
+                .set_connection_state(SpdmConnectionState::SpdmConnectionAfterCapabilities);
+        } else if opcode == SpdmRequestResponseCode::SpdmResponseAlgorithms.get_u8() {
+            self.common
+                .runtime_info
+                .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+        } else if opcode == SpdmRequestResponseCode::SpdmResponseDigests.get_u8() {
+            if self.common.runtime_info.get_connection_state().get_u8()
+                < SpdmConnectionState::SpdmConnectionAfterDigest.get_u8()
+            {
                 self.common
                     .runtime_info
-                    .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
-            } else if opcode == SpdmRequestResponseCode::SpdmResponseDigests.get_u8() {
-                if self.common.runtime_info.get_connection_state().get_u8()
-                    < SpdmConnectionState::SpdmConnectionAfterDigest.get_u8()
-                {
-                    self.common
-                        .runtime_info
-                        .set_connection_state(SpdmConnectionState::SpdmConnectionAfterDigest);
-                }
-            } else if opcode == SpdmRequestResponseCode::SpdmResponseCertificate.get_u8() {
-                if self.common.runtime_info.get_connection_state().get_u8()
-                    < SpdmConnectionState::SpdmConnectionAfterCertificate.get_u8()
-                {
-                    self.common
-                        .runtime_info
-                        .set_connection_state(SpdmConnectionState::SpdmConnectionAfterCertificate);
-                }
-            } else if opcode == SpdmRequestResponseCode::SpdmResponseChallengeAuth.get_u8() {
+                    .set_connection_state(SpdmConnectionState::SpdmConnectionAfterDigest);
+            }
+        } else if opcode == SpdmRequestResponseCode::SpdmResponseCertificate.get_u8() {
+            if self.common.runtime_info.get_connection_state().get_u8()
+                < SpdmConnectionState::SpdmConnectionAfterCertificate.get_u8()
+            {
                 self.common
                     .runtime_info
-                    .set_connection_state(SpdmConnectionState::SpdmConnectionAuthenticated);
-            } else if opcode == SpdmRequestResponseCode::SpdmResponseFinishRsp.get_u8() {
-                let session = self
-                    .common
-                    .get_session_via_id(self.common.runtime_info.get_last_session_id().unwrap())
-                    .unwrap();
-                session.set_session_state(
-                    crate::common::session::SpdmSessionState::SpdmSessionEstablished,
-                );
-                self.common.runtime_info.set_last_session_id(None);
+                    .set_connection_state(SpdmConnectionState::SpdmConnectionAfterCertificate);
             }
+        } else if opcode == SpdmRequestResponseCode::SpdmResponseChallengeAuth.get_u8() {
+            self.common
+                .runtime_info
+                .set_connection_state(SpdmConnectionState::SpdmConnectionAuthenticated);
+        } else if opcode == SpdmRequestResponseCode::SpdmResponseFinishRsp.get_u8() {
+            let session = self
+                .common
+                .get_session_via_id(self.common.runtime_info.get_last_session_id().unwrap())
+                .unwrap();
+            session.set_session_state(
+                crate::common::session::SpdmSessionState::SpdmSessionEstablished,
+            );
+            self.common.runtime_info.set_last_session_id(None);
         }
-        result
     }
 
     pub fn send_secured_message(
@@ -98,17 +227,31 @@ impl<'a> ResponderContext<'a> {
         send_buffer: &[u8],
         is_app_message: bool,
     ) -> SpdmResult {
+        if !is_app_message {
+            if let Some((handle, chunk_seq_no)) = self.chunk_send_context.ack_pending {
+                return self.send_spdm_chunk_send_ack(
+                    Some(session_id),
+                    SpdmChunkReceiverAttributes::empty(),
+                    handle,
+                    chunk_seq_no,
+                    send_buffer,
+                );
+            }
+        }
         if !is_app_message
             && self.common.negotiate_info.req_data_transfer_size_sel != 0
             && send_buffer.len() > self.common.negotiate_info.req_data_transfer_size_sel as usize
         {
-            let mut err_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+            let mut err_buffer = self.common.alloc_message_buffer();
             let mut writer = Writer::init(&mut err_buffer);
             self.write_spdm_error(SpdmErrorCode::SpdmErrorResponseTooLarge, 0, &mut writer);
-            return self.send_secured_message(session_id, writer.used_slice(), is_app_message);
+            let used = writer.used();
+            let result = self.send_secured_message(session_id, &err_buffer[..used], is_app_message);
+            self.common.free_buffer(err_buffer);
+            return result;
         }
 
-        let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let mut transport_buffer = self.common.alloc_sender_buffer();
         let used = self.common.encode_secured_message(
             session_id,
             send_buffer,
@@ -116,44 +259,107 @@ impl<'a> ResponderContext<'a> {
             false,
             is_app_message,
         )?;
-        let result = self.common.device_io.send(&transport_buffer[..used]);
+        let result = self.common.device_io_send(&transport_buffer[..used], true);
+        self.common.free_buffer(transport_buffer);
         if result.is_ok() {
-            let opcode = send_buffer[1];
-            // change state after message is sent.
-            if opcode == SpdmRequestResponseCode::SpdmResponseEndSessionAck.get_u8() {
-                let session = self.common.get_session_via_id(session_id).unwrap();
-                let _ = session.teardown(session_id);
-            }
-            if opcode == SpdmRequestResponseCode::SpdmResponseFinishRsp.get_u8()
-                || opcode == SpdmRequestResponseCode::SpdmResponsePskFinishRsp.get_u8()
-            {
-                let session = self.common.get_session_via_id(session_id).unwrap();
-                session.set_session_state(
-                    crate::common::session::SpdmSessionState::SpdmSessionEstablished,
-                );
-            }
+            self.update_session_state(session_id, send_buffer[1]);
+        }
+        result
+    }
+
+    /// Send the spdm_size bytes response at the start of message_buffer in
+    /// session_id. message_buffer is reused while encoding the secured message.
+    fn send_secured_message_in_place(
+        &mut self,
+        session_id: u32,
+        message_buffer: &mut [u8],
+        spdm_size: usize,
+    ) -> SpdmResult {
+        if spdm_size < 2 || message_buffer.len() < spdm_size {
+            return Err(SPDM_STATUS_SEND_FAIL);
+        }
+        if self.chunk_send_context.ack_pending.is_some()
+            || (self.common.negotiate_info.req_data_transfer_size_sel != 0
+                && spdm_size > self.common.negotiate_info.req_data_transfer_size_sel as usize)
+        {
+            return self.send_secured_message(session_id, &message_buffer[..spdm_size], false);
+        }
+
+        let opcode = message_buffer[1];
+        let mut transport_buffer = self.common.alloc_sender_buffer();
+        let used = self.common.encode_secured_message_in_place(
+            session_id,
+            message_buffer,
+            spdm_size,
+            &mut transport_buffer,
+            false,
+            false,
+        )?;
+        let result = self.common.device_io_send(&transport_buffer[..used], true);
+        self.common.free_buffer(transport_buffer);
+        if result.is_ok() {
+            self.update_session_state(session_id, opcode);
         }
         result
     }
 
+    /// Change session state after the response opcode is sent in session_id.
+    pub(crate) fn update_session_state(&mut self, session_id: u32, opcode: u8) {
+        if opcode == SpdmRequestResponseCode::SpdmResponseEndSessionAck.get_u8() {
+            let session = self.common.get_session_via_id(session_id).unwrap();
+            let _ = session.teardown(session_id);
+            self.event_context.end_session(session_id);
+        }
+        if opcode == SpdmRequestResponseCode::SpdmResponseFinishRsp.get_u8()
+            || opcode == SpdmRequestResponseCode::SpdmResponsePskFinishRsp.get_u8()
+        {
+            let session = self.common.get_session_via_id(session_id).unwrap();
+            session.set_session_state(
+                crate::common::session::SpdmSessionState::SpdmSessionEstablished,
+            );
+        }
+    }
+
+    /// Receive and handle one request. On Err the raw received packet is
+    /// returned for the caller to handle.
     pub fn process_message(
         &mut self,
         timeout: usize,
         auxiliary_app_data: &[u8],
-    ) -> Result<bool, (usize, [u8; config::RECEIVER_BUFFER_SIZE])> {
-        let mut receive_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
-        match self.receive_message(&mut receive_buffer[..], timeout) {
+    ) -> Result<bool, (usize, Vec<u8>)> {
+        let mut receive_buffer = self.common.alloc_receiver_buffer();
+        match self.process_message_in(&mut receive_buffer, timeout, auxiliary_app_data) {
+            Ok(result) => {
+                self.common.free_buffer(receive_buffer);
+                Ok(result)
+            }
+            Err(used) => Err((used, core::mem::take(&mut *receive_buffer))),
+        }
+    }
+
+    fn process_message_in(
+        &mut self,
+        receive_buffer: &mut [u8],
+        timeout: usize,
+        auxiliary_app_data: &[u8],
+    ) -> Result<bool, usize> {
+        if self.handle_device_io_event() || self.common.check_cancelled().is_err() {
+            return Err(0);
+        }
+        match self.receive_message(receive_buffer, timeout) {
             Ok((used, secured_message)) => {
+                // a request received after the cancellation is dropped
+                if self.common.check_cancelled().is_err() {
+                    return Err(0);
+                }
                 if secured_message {
                     let mut read = Reader::init(&receive_buffer[0..used]);
-                    let session_id = u32::read(&mut read).ok_or((used, receive_buffer))?;
-
-                    let spdm_session = self
-                        .common
-                        .get_session_via_id(session_id)
-                        .ok_or((used, receive_buffer))?;
+                    let session_id = u32::read(&mut read).ok_or(used)?;
 
-                    let mut app_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+                    let max_mac_failure_count =
+                        self.common.config_info.max_session_mac_failure_count;
+                    let mut app_buffer = self.common.alloc_receiver_buffer();
+                    let spdm_session = self.common.get_session_via_id(session_id).ok_or(used)?;
 
                     let decode_size = spdm_session.decode_spdm_secured_message(
                         &receive_buffer[..used],
@@ -161,19 +367,42 @@ impl<'a> ResponderContext<'a> {
                         true,
                     );
                     if decode_size.is_err() {
-                        return Err((used, receive_buffer));
+                        if max_mac_failure_count != 0
+                            && spdm_session
+                                .get_decode_stats()
+                                .consecutive_mac_failure_count
+                                >= max_mac_failure_count
+                        {
+                            error!("!!! too many MAC failures, terminate session !!!\n");
+                            let _ = spdm_session.teardown(session_id);
+                            self.event_context.end_session(session_id);
+                        }
+                        return Err(used);
                     }
                     let decode_size = decode_size.unwrap();
+                    if time::is_registered() {
+                        spdm_session.touch(time::now_us());
+                    }
+                    self.common.mark_session_used(session_id);
 
-                    let mut spdm_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+                    let mut spdm_buffer = self.common.alloc_message_buffer();
                     let decap_result = self
                         .common
                         .transport_encap
                         .decap_app(&app_buffer[0..decode_size], &mut spdm_buffer);
-                    match decap_result {
-                        Err(_) => Err((used, receive_buffer)),
+                    let result = match decap_result {
+                        Err(_) => Err(used),
                         Ok((decode_size, is_app_message)) => {
                             if !is_app_message {
+                                self.common.capture_message(
+                                    SpdmMessageDirection::Received,
+                                    Some(session_id),
+                                    &spdm_buffer[..decode_size],
+                                );
+                            }
+                            if !is_app_message && self.is_request_too_large(decode_size) {
+                                Ok(self.handle_request_too_large(Some(session_id)).is_ok())
+                            } else if !is_app_message {
                                 Ok(self
                                     .dispatch_secured_message(
                                         session_id,
@@ -190,12 +419,43 @@ impl<'a> ResponderContext<'a> {
                                     .is_ok())
                             }
                         }
-                    }
+                    };
+                    self.common.free_buffer(spdm_buffer);
+                    self.common.free_buffer(app_buffer);
+                    result
+                } else if self.is_request_too_large(used) {
+                    Ok(self.handle_request_too_large(None).is_ok())
                 } else {
                     Ok(self.dispatch_message(&receive_buffer[0..used]).is_ok())
                 }
             }
-            Err(used) => Err((used, receive_buffer)),
+            Err(size) if size > receive_buffer.len() => {
+                error!("!!! request size {:x?} exceeds receive buffer !!!\n", size);
+                Ok(self.handle_request_too_large(None).is_ok())
+            }
+            Err(used) => {
+                self.handle_device_io_event();
+                Err(used)
+            }
+        }
+    }
+
+    /// A request larger than our DataTransferSize must be sent with CHUNK_SEND.
+    fn is_request_too_large(&self, size: usize) -> bool {
+        let data_transfer_size = self.common.negotiate_info.rsp_data_transfer_size_sel as usize;
+        (data_transfer_size != 0 && size > data_transfer_size)
+            || size > self.common.buffer_config.max_spdm_msg_size
+    }
+
+    /// Return true if the peer is disconnected, in which case all sessions
+    /// and connection state are dropped.
+    fn handle_device_io_event(&mut self) -> bool {
+        match self.common.device_io.poll_event() {
+            SpdmDeviceIoEvent::PeerDisconnected => {
+                self.common.handle_peer_disconnect();
+                true
+            }
+            SpdmDeviceIoEvent::None | SpdmDeviceIoEvent::AsyncMessagePending => false,
         }
     }
 
@@ -210,9 +470,9 @@ impl<'a> ResponderContext<'a> {
     ) -> Result<(usize, bool), usize> {
         info!("receive_message!\n");
 
-        let mut transport_buffer = [0u8; config::RECEIVER_BUFFER_SIZE];
+        let mut transport_buffer = self.common.alloc_receiver_buffer();
 
-        let used = self.common.device_io.receive(receive_buffer, timeout)?;
+        let used = self.common.device_io_receive(receive_buffer, timeout)?;
 
         let (used, secured_message) = self
             .common
@@ -221,143 +481,128 @@ impl<'a> ResponderContext<'a> {
             .map_err(|_| used)?;
 
         receive_buffer[..used].copy_from_slice(&transport_buffer[..used]);
+        self.common.free_buffer(transport_buffer);
+        if !secured_message {
+            self.common.capture_message(
+                SpdmMessageDirection::Received,
+                None,
+                &receive_buffer[..used],
+            );
+        }
         Ok((used, secured_message))
     }
 
-    fn dispatch_secured_message(&mut self, session_id: u32, bytes: &[u8]) -> SpdmResult {
-        let mut reader = Reader::init(bytes);
-
+    pub(crate) fn dispatch_secured_message(&mut self, session_id: u32, bytes: &[u8]) -> SpdmResult {
         let session = self.common.get_immutable_session_via_id(session_id);
         if session.is_none() {
             return Err(SPDM_STATUS_UNSUPPORTED_CAP);
         }
-        let session = session.unwrap();
+        let session_state = session.unwrap().get_session_state();
 
-        match session.get_session_state() {
+        match session_state {
             SpdmSessionState::SpdmSessionHandshaking => {
-                let in_clear_text = self
-                    .common
-                    .negotiate_info
-                    .req_capabilities_sel
-                    .contains(SpdmRequestCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP)
-                    && self
-                        .common
-                        .negotiate_info
-                        .rsp_capabilities_sel
-                        .contains(SpdmResponseCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP);
-                if in_clear_text {
+                if self.common.is_session_handshake_in_clear(session_id) {
                     return Err(SPDM_STATUS_UNSUPPORTED_CAP);
                 }
+            }
+            SpdmSessionState::SpdmSessionEstablished => {}
+            SpdmSessionState::SpdmSessionNotStarted => return Err(SPDM_STATUS_UNSUPPORTED_CAP),
+            SpdmSessionState::Unknown(_) => return Err(SPDM_STATUS_UNSUPPORTED_CAP),
+        }
 
-                match SpdmMessageHeader::read(&mut reader) {
-                    Some(message_header) => match message_header.request_response_code {
-                        #[cfg(feature = "mut-auth")]
-                        SpdmRequestResponseCode::SpdmRequestGetEncapsulatedRequest => {
-                            self.handle_get_encapsulated_request(session_id, bytes)
-                        }
-                        #[cfg(feature = "mut-auth")]
-                        SpdmRequestResponseCode::SpdmRequestDeliverEncapsulatedResponse => {
-                            self.handle_deliver_encapsulated_reponse(session_id, bytes)
-                        }
-                        SpdmRequestResponseCode::SpdmRequestFinish => {
-                            self.handle_spdm_finish(session_id, bytes)
-                        }
+        self.dispatch_request(Some(session_id), session_state, bytes)
+    }
 
-                        SpdmRequestResponseCode::SpdmRequestPskFinish => {
-                            self.handle_spdm_psk_finish(session_id, bytes)
-                        }
+    /// Gate the request with the state and version tables, then call its
+    /// handler from the registered handlers or SPDM_REQUEST_DISPATCH_TABLE.
+    fn dispatch_request(
+        &mut self,
+        session_id: Option<u32>,
+        session_state: SpdmSessionState,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        let mut reader = Reader::init(bytes);
+        let code = match SpdmMessageHeader::read(&mut reader) {
+            Some(message_header) => message_header.request_response_code,
+            None => return Err(SPDM_STATUS_UNSUPPORTED_CAP),
+        };
 
-                        SpdmRequestResponseCode::SpdmRequestVendorDefinedRequest => {
-                            self.handle_spdm_vendor_defined_request(Some(session_id), bytes)
-                        }
+        if self.is_request_rejected(session_state, code) {
+            return self.handle_rejected_request(session_state, session_id, code, bytes);
+        }
+        if !self.is_request_supported_in_version(code) {
+            return self.handle_error_request(
+                SpdmErrorCode::SpdmErrorUnsupportedRequest,
+                session_id,
+                bytes,
+            );
+        }
+        if session_state != SpdmSessionState::SpdmSessionHandshaking
+            && self.is_response_deferred(session_id, code, bytes)
+        {
+            return self.handle_response_not_ready(session_id);
+        }
 
-                        SpdmRequestResponseCode::SpdmRequestGetVersion
-                        | SpdmRequestResponseCode::SpdmRequestGetCapabilities
-                        | SpdmRequestResponseCode::SpdmRequestNegotiateAlgorithms
-                        | SpdmRequestResponseCode::SpdmRequestGetDigests
-                        | SpdmRequestResponseCode::SpdmRequestGetCertificate
-                        | SpdmRequestResponseCode::SpdmRequestChallenge
-                        | SpdmRequestResponseCode::SpdmRequestGetMeasurements
-                        | SpdmRequestResponseCode::SpdmRequestKeyExchange
-                        | SpdmRequestResponseCode::SpdmRequestPskExchange
-                        | SpdmRequestResponseCode::SpdmRequestHeartbeat
-                        | SpdmRequestResponseCode::SpdmRequestKeyUpdate
-                        | SpdmRequestResponseCode::SpdmRequestEndSession => self
-                            .handle_error_request(
-                                SpdmErrorCode::SpdmErrorUnexpectedRequest,
-                                Some(session_id),
-                                bytes,
-                            ),
-
-                        SpdmRequestResponseCode::SpdmRequestResponseIfReady => self
-                            .handle_error_request(
-                                SpdmErrorCode::SpdmErrorUnsupportedRequest,
-                                Some(session_id),
-                                bytes,
-                            ),
-
-                        _ => Err(SPDM_STATUS_UNSUPPORTED_CAP),
-                    },
-                    None => Err(SPDM_STATUS_UNSUPPORTED_CAP),
-                }
+        match self.get_request_handler(code, SpdmRequestStates::from_session_state(session_state)) {
+            Some(entry)
+                if self.common.runtime_info.get_connection_state().get_u8()
+                    < entry.connection_state.get_u8() =>
+            {
+                self.handle_error_request(
+                    SpdmErrorCode::SpdmErrorUnexpectedRequest,
+                    session_id,
+                    bytes,
+                )
             }
-            SpdmSessionState::SpdmSessionEstablished => {
-                match SpdmMessageHeader::read(&mut reader) {
-                    Some(message_header) => match message_header.request_response_code {
-                        SpdmRequestResponseCode::SpdmRequestGetDigests => {
-                            self.handle_spdm_digest(bytes, Some(session_id))
-                        }
-                        SpdmRequestResponseCode::SpdmRequestGetCertificate => {
-                            self.handle_spdm_certificate(bytes, Some(session_id))
-                        }
-                        SpdmRequestResponseCode::SpdmRequestGetMeasurements => {
-                            self.handle_spdm_measurement(Some(session_id), bytes)
-                        }
-
-                        SpdmRequestResponseCode::SpdmRequestHeartbeat => {
-                            self.handle_spdm_heartbeat(session_id, bytes)
-                        }
+            Some(entry) if time::is_registered() && SPDM_DEFERRABLE_REQUESTS.contains(&code) => {
+                let start = time::now_us();
+                let result = (entry.handler)(self, session_id, bytes);
+                self.crypto_latency.record(time::elapsed_us(start));
+                result
+            }
+            Some(entry) => (entry.handler)(self, session_id, bytes),
+            None => Err(SPDM_STATUS_UNSUPPORTED_CAP),
+        }
+    }
 
-                        SpdmRequestResponseCode::SpdmRequestKeyUpdate => {
-                            self.handle_spdm_key_update(session_id, bytes)
-                        }
+    /// Check the request against SPDM_REQUEST_STATE_TABLE.
+    fn is_request_rejected(
+        &self,
+        session_state: SpdmSessionState,
+        request_response_code: SpdmRequestResponseCode,
+    ) -> bool {
+        get_request_state_error_code(
+            self.common.negotiate_info.spdm_version_sel,
+            session_state,
+            request_response_code,
+        )
+        .is_some()
+    }
 
-                        SpdmRequestResponseCode::SpdmRequestEndSession => {
-                            self.handle_spdm_end_session(session_id, bytes)
-                        }
-                        SpdmRequestResponseCode::SpdmRequestVendorDefinedRequest => {
-                            self.handle_spdm_vendor_defined_request(Some(session_id), bytes)
-                        }
+    /// Check the request against SPDM_REQUEST_VERSION_TABLE.
+    fn is_request_supported_in_version(
+        &self,
+        request_response_code: SpdmRequestResponseCode,
+    ) -> bool {
+        is_request_supported_in_version(
+            self.common.negotiate_info.spdm_version_sel,
+            request_response_code,
+        )
+    }
 
-                        SpdmRequestResponseCode::SpdmRequestGetVersion
-                        | SpdmRequestResponseCode::SpdmRequestGetCapabilities
-                        | SpdmRequestResponseCode::SpdmRequestNegotiateAlgorithms
-                        | SpdmRequestResponseCode::SpdmRequestChallenge
-                        | SpdmRequestResponseCode::SpdmRequestKeyExchange
-                        | SpdmRequestResponseCode::SpdmRequestPskExchange
-                        | SpdmRequestResponseCode::SpdmRequestFinish
-                        | SpdmRequestResponseCode::SpdmRequestPskFinish => self
-                            .handle_error_request(
-                                SpdmErrorCode::SpdmErrorUnexpectedRequest,
-                                Some(session_id),
-                                bytes,
-                            ),
-
-                        SpdmRequestResponseCode::SpdmRequestResponseIfReady => self
-                            .handle_error_request(
-                                SpdmErrorCode::SpdmErrorUnsupportedRequest,
-                                Some(session_id),
-                                bytes,
-                            ),
-
-                        _ => Err(SPDM_STATUS_UNSUPPORTED_CAP),
-                    },
-                    None => Err(SPDM_STATUS_UNSUPPORTED_CAP),
-                }
-            }
-            SpdmSessionState::SpdmSessionNotStarted => Err(SPDM_STATUS_UNSUPPORTED_CAP),
-            SpdmSessionState::Unknown(_) => Err(SPDM_STATUS_UNSUPPORTED_CAP),
-        }
+    fn handle_rejected_request(
+        &mut self,
+        session_state: SpdmSessionState,
+        session_id: Option<u32>,
+        request_response_code: SpdmRequestResponseCode,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        let error_code = get_reject_error_code(
+            self.common.negotiate_info.spdm_version_sel,
+            session_state,
+            request_response_code,
+        );
+        self.handle_error_request(error_code, session_id, bytes)
     }
 
     fn dispatch_secured_app_message(
@@ -372,88 +617,39 @@ impl<'a> ResponderContext<'a> {
             dispatch_secured_app_message_cb(self, session_id, bytes, auxiliary_app_data).unwrap();
         self.send_secured_message(session_id, &rsp_app_buffer[..size], true)
     }
+
+    /// The handshaking session whose handshake messages are sent in the clear, if any.
+    fn get_in_clear_handshake_session_id(&self) -> Option<u32> {
+        let session_id = self.common.runtime_info.get_last_session_id()?;
+        if self.common.is_session_handshake_in_clear(session_id) {
+            Some(session_id)
+        } else {
+            None
+        }
+    }
+
     pub fn dispatch_message(&mut self, bytes: &[u8]) -> SpdmResult {
         let mut reader = Reader::init(bytes);
         match SpdmMessageHeader::read(&mut reader) {
             Some(message_header) => match message_header.request_response_code {
-                SpdmRequestResponseCode::SpdmRequestGetVersion => self.handle_spdm_version(bytes),
-                SpdmRequestResponseCode::SpdmRequestGetCapabilities => {
-                    self.handle_spdm_capability(bytes)
-                }
-                SpdmRequestResponseCode::SpdmRequestNegotiateAlgorithms => {
-                    self.handle_spdm_algorithm(bytes)
-                }
-                SpdmRequestResponseCode::SpdmRequestGetDigests => {
-                    self.handle_spdm_digest(bytes, None)
-                }
-                SpdmRequestResponseCode::SpdmRequestGetCertificate => {
-                    self.handle_spdm_certificate(bytes, None)
-                }
-                SpdmRequestResponseCode::SpdmRequestChallenge => self.handle_spdm_challenge(bytes),
-                SpdmRequestResponseCode::SpdmRequestGetMeasurements => {
-                    self.handle_spdm_measurement(None, bytes)
-                }
-
-                SpdmRequestResponseCode::SpdmRequestKeyExchange => {
-                    self.handle_spdm_key_exchange(bytes)
-                }
-
-                SpdmRequestResponseCode::SpdmRequestPskExchange => {
-                    self.handle_spdm_psk_exchange(bytes)
-                }
-
-                SpdmRequestResponseCode::SpdmRequestVendorDefinedRequest => {
-                    self.handle_spdm_vendor_defined_request(None, bytes)
-                }
-
-                SpdmRequestResponseCode::SpdmRequestFinish => {
-                    let in_clear_text = self
-                        .common
-                        .negotiate_info
-                        .req_capabilities_sel
-                        .contains(SpdmRequestCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP)
-                        && self
-                            .common
-                            .negotiate_info
-                            .rsp_capabilities_sel
-                            .contains(SpdmResponseCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP);
-                    if in_clear_text {
-                        if let Some(session_id) = self.common.runtime_info.get_last_session_id() {
-                            if let Some(session) =
-                                self.common.get_immutable_session_via_id(session_id)
-                            {
-                                if session.get_session_state()
-                                    == SpdmSessionState::SpdmSessionHandshaking
-                                {
-                                    return self.handle_spdm_finish(session_id, bytes);
-                                }
-                            }
-                        }
+                // FINISH and the encapsulated flow of a handshake in the clear are not secured.
+                SpdmRequestResponseCode::SpdmRequestFinish
+                | SpdmRequestResponseCode::SpdmRequestGetEncapsulatedRequest
+                | SpdmRequestResponseCode::SpdmRequestDeliverEncapsulatedResponse => {
+                    match self.get_in_clear_handshake_session_id() {
+                        Some(session_id) => self.dispatch_request(
+                            Some(session_id),
+                            SpdmSessionState::SpdmSessionHandshaking,
+                            bytes,
+                        ),
+                        None => self.dispatch_request(
+                            None,
+                            SpdmSessionState::SpdmSessionNotStarted,
+                            bytes,
+                        ),
                     }
-
-                    self.handle_error_request(
-                        SpdmErrorCode::SpdmErrorUnexpectedRequest,
-                        None,
-                        bytes,
-                    )
                 }
-
-                SpdmRequestResponseCode::SpdmRequestPskFinish
-                | SpdmRequestResponseCode::SpdmRequestHeartbeat
-                | SpdmRequestResponseCode::SpdmRequestKeyUpdate
-                | SpdmRequestResponseCode::SpdmRequestEndSession => self.handle_error_request(
-                    SpdmErrorCode::SpdmErrorUnexpectedRequest,
-                    None,
-                    bytes,
-                ),
-
-                SpdmRequestResponseCode::SpdmRequestResponseIfReady => self.handle_error_request(
-                    SpdmErrorCode::SpdmErrorUnsupportedRequest,
-                    None,
-                    bytes,
-                ),
-
-                _ => Err(SPDM_STATUS_UNSUPPORTED_CAP),
+                _ => self.dispatch_request(None, SpdmSessionState::SpdmSessionNotStarted, bytes),
             },
             None => Err(SPDM_STATUS_UNSUPPORTED_CAP),
         }
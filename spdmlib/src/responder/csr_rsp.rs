@@ -0,0 +1,116 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::SpdmCodec;
+use crate::common::SpdmConnectionState;
+use crate::error::SpdmResult;
+use crate::message::*;
+use crate::protocol::*;
+use crate::responder::*;
+use crate::secret::SpdmCsrResult;
+
+impl<'a> ResponderContext<'a> {
+    pub fn handle_spdm_get_csr(&mut self, session_id: Option<u32>, bytes: &[u8]) -> SpdmResult {
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_csr_response(session_id, bytes, writer);
+            Ok(())
+        })
+    }
+
+    fn write_spdm_csr_response(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+        writer: &mut Writer,
+    ) {
+        if self.common.runtime_info.get_connection_state().get_u8()
+            < SpdmConnectionState::SpdmConnectionNegotiated.get_u8()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnexpectedRequest, 0, writer);
+            return;
+        }
+        if !self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::CSR_CAP)
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnsupportedRequest, 0, writer);
+            return;
+        }
+
+        let mut reader = Reader::init(bytes);
+        let message_header = SpdmMessageHeader::read(&mut reader);
+        if let Some(message_header) = message_header {
+            if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorVersionMismatch, 0, writer);
+                return;
+            }
+        } else {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        self.common
+            .reset_buffer_via_request_code(SpdmRequestResponseCode::SpdmRequestGetCsr, session_id);
+
+        let get_csr = SpdmGetCsrRequestPayload::spdm_read(&mut self.common, &mut reader);
+        let get_csr = if let Some(get_csr) = get_csr {
+            debug!("!!! get_csr : {:02x?}\n", get_csr.csr_tracking_tag);
+            get_csr
+        } else {
+            error!("!!! get_csr : fail !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        };
+
+        let mut csr_response = SpdmCsrResponsePayload::default();
+        let csr_length = match self.common.secret_provider.generate_csr(
+            self.common.negotiate_info.spdm_version_sel,
+            self.common.negotiate_info.base_hash_sel,
+            self.common.negotiate_info.base_asym_sel,
+            get_csr.csr_tracking_tag,
+            &get_csr.requester_info[..get_csr.requester_info_length as usize],
+            &get_csr.opaque.data[..get_csr.opaque.data_size as usize],
+            &mut csr_response.csr,
+        ) {
+            SpdmCsrResult::Generated(csr_length) => csr_length,
+            SpdmCsrResult::ResetRequired(csr_tracking_tag) => {
+                info!("!!! get_csr : reset required !!!\n");
+                let error_data = if self.common.negotiate_info.spdm_version_sel.get_u8()
+                    >= SpdmVersion::SpdmVersion13.get_u8()
+                {
+                    csr_tracking_tag & SPDM_CSR_TRACKING_TAG_MAX
+                } else {
+                    0
+                };
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorResetRequired, error_data, writer);
+                return;
+            }
+            SpdmCsrResult::Failed => {
+                error!("!!! get_csr : generate fail !!!\n");
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                return;
+            }
+        };
+        if csr_length > csr_response.csr.len() || csr_length > u16::MAX as usize {
+            error!("!!! get_csr : csr too large !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+            return;
+        }
+        csr_response.csr_length = csr_length as u16;
+
+        info!("send spdm csr\n");
+        let response = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseCsr,
+            },
+            payload: SpdmMessagePayload::SpdmCsrResponse(csr_response),
+        };
+        if response.spdm_encode(&mut self.common, writer).is_err() {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! CXL IDE_KM responder, registered like the IDE_KM one but for the CXL
+//! vendor ID, as CXL_IDE_KM shares protocol ID 0 with PCIe IDE_KM:
+//!
+//! registry.register(CXL_IDE_KM_STANDARD_ID, &cxl_vendor_id(), cxl_ide_km_request_handler)?;
+//!
+//! As with IDE_KM, requests are only served in an established secure session.
+
+use conquer_once::spin::OnceCell;
+
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_MSG_FIELD,
+    SPDM_STATUS_INVALID_STATE_PEER, SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use crate::message::cxl_ide_km::*;
+use crate::message::ide_km::IdeKmKpAckStatus;
+use crate::message::{
+    VendorDefinedReqPayloadStruct, VendorDefinedRequestSession, VendorDefinedRspPayloadStruct,
+};
+use codec::{Codec, Reader};
+
+#[derive(Clone, Copy)]
+pub struct SpdmCxlIdeKmHandler {
+    /// Return None for an unsupported port_index.
+    pub query_cb: fn(port_index: u8) -> Option<CxlIdeKmQueryResp>,
+    pub key_prog_cb: fn(key_index: &CxlIdeKmKeyIndex, key: &CxlIdeKmAesKey) -> IdeKmKpAckStatus,
+    pub key_set_go_cb: fn(key_index: &CxlIdeKmKeyIndex) -> SpdmResult,
+    pub key_set_stop_cb: fn(key_index: &CxlIdeKmKeyIndex) -> SpdmResult,
+    /// Return None if the device does not generate keys.
+    pub get_key_cb: fn(key_index: &CxlIdeKmKeyIndex) -> Option<CxlIdeKmAesKey>,
+}
+
+static SPDM_CXL_IDE_KM_HANDLER: OnceCell<SpdmCxlIdeKmHandler> = OnceCell::uninit();
+
+pub fn register(context: SpdmCxlIdeKmHandler) -> bool {
+    SPDM_CXL_IDE_KM_HANDLER.try_init_once(|| context).is_ok()
+}
+
+/// Handle a CXL_IDE_KM request. Return SPDM_STATUS_UNSUPPORTED_CAP if no
+/// handler is registered, SPDM_STATUS_INVALID_STATE_PEER if the request is
+/// not in an established session.
+pub fn cxl_ide_km_request_handler(
+    session: Option<VendorDefinedRequestSession>,
+    vendor_defined_req_payload_struct: &VendorDefinedReqPayloadStruct,
+) -> SpdmResult<VendorDefinedRspPayloadStruct> {
+    let handler = SPDM_CXL_IDE_KM_HANDLER
+        .try_get()
+        .map_err(|_| SPDM_STATUS_UNSUPPORTED_CAP)?;
+    if !VendorDefinedRequestSession::is_established(session) {
+        return Err(SPDM_STATUS_INVALID_STATE_PEER);
+    }
+
+    let mut reader = Reader::init(
+        &vendor_defined_req_payload_struct.vendor_defined_req_payload
+            [..vendor_defined_req_payload_struct.req_length as usize],
+    );
+    let request = CxlIdeKmMessage::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+    let response = match request {
+        CxlIdeKmMessage::Query { port_index } => CxlIdeKmMessage::QueryResp(
+            (handler.query_cb)(port_index).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?,
+        ),
+        CxlIdeKmMessage::KeyProg(key_index, key) => {
+            let status = if reader.any_left() {
+                IdeKmKpAckStatus::IdeKmKpAckIncorrectLength
+            } else {
+                (handler.key_prog_cb)(&key_index, &key)
+            };
+            CxlIdeKmMessage::KpAck(key_index, status)
+        }
+        CxlIdeKmMessage::KSetGo(key_index) => {
+            (handler.key_set_go_cb)(&key_index)?;
+            CxlIdeKmMessage::KGoStopAck(key_index)
+        }
+        CxlIdeKmMessage::KSetStop(key_index) => {
+            (handler.key_set_stop_cb)(&key_index)?;
+            CxlIdeKmMessage::KGoStopAck(key_index)
+        }
+        CxlIdeKmMessage::GetKey(key_index) => CxlIdeKmMessage::GetKeyAck(
+            key_index,
+            (handler.get_key_cb)(&key_index).ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?,
+        ),
+        _ => return Err(SPDM_STATUS_INVALID_MSG_FIELD),
+    };
+    response.to_rsp_payload().ok_or(SPDM_STATUS_BUFFER_FULL)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::common::session::SpdmSessionState;
+
+    const SESSION: Option<VendorDefinedRequestSession> = Some(VendorDefinedRequestSession {
+        session_id: 0xFFFE_FFFE,
+        session_state: SpdmSessionState::SpdmSessionEstablished,
+    });
+
+    fn handle(request: &CxlIdeKmMessage) -> SpdmResult<CxlIdeKmMessage> {
+        let rsp_payload = cxl_ide_km_request_handler(SESSION, &request.to_req_payload().unwrap())?;
+        Ok(CxlIdeKmMessage::read_bytes(
+            &rsp_payload.vendor_defined_rsp_payload[..rsp_payload.rsp_length as usize],
+        )
+        .unwrap())
+    }
+
+    #[test]
+    fn test_case0_cxl_ide_km_request_handler() {
+        assert!(register(SpdmCxlIdeKmHandler {
+            query_cb: |port_index| {
+                Some(CxlIdeKmQueryResp {
+                    port_index,
+                    capabilities: CxlIdeKmCapabilities::KEY_GENERATION_CAP,
+                    ..Default::default()
+                })
+            },
+            key_prog_cb: |_, _| IdeKmKpAckStatus::IdeKmKpAckSuccess,
+            key_set_go_cb: |_| Ok(()),
+            key_set_stop_cb: |_| Ok(()),
+            get_key_cb: |key_index| {
+                if !key_index.tx {
+                    return None;
+                }
+                Some(CxlIdeKmAesKey {
+                    key: [0x33u8; 32],
+                    ..Default::default()
+                })
+            },
+        }));
+
+        match handle(&CxlIdeKmMessage::Query { port_index: 2 }).unwrap() {
+            CxlIdeKmMessage::QueryResp(query_resp) => {
+                assert_eq!(query_resp.port_index, 2);
+                assert!(query_resp
+                    .capabilities
+                    .contains(CxlIdeKmCapabilities::KEY_GENERATION_CAP));
+            }
+            _ => panic!(),
+        }
+
+        let key_index = CxlIdeKmKeyIndex {
+            tx: true,
+            ..Default::default()
+        };
+        match handle(&CxlIdeKmMessage::GetKey(key_index)).unwrap() {
+            CxlIdeKmMessage::GetKeyAck(ack_index, key) => {
+                assert_eq!(ack_index, key_index);
+                assert_eq!(key.key, [0x33u8; 32]);
+            }
+            _ => panic!(),
+        }
+        let rx_index = CxlIdeKmKeyIndex {
+            tx: false,
+            ..key_index
+        };
+        assert_eq!(
+            handle(&CxlIdeKmMessage::GetKey(rx_index)).err(),
+            Some(SPDM_STATUS_UNSUPPORTED_CAP)
+        );
+        assert!(matches!(
+            handle(&CxlIdeKmMessage::KeyProg(
+                rx_index,
+                CxlIdeKmAesKey::default()
+            ))
+            .unwrap(),
+            CxlIdeKmMessage::KpAck(_, IdeKmKpAckStatus::IdeKmKpAckSuccess)
+        ));
+        assert_eq!(
+            handle(&CxlIdeKmMessage::KGoStopAck(key_index)).err(),
+            Some(SPDM_STATUS_INVALID_MSG_FIELD)
+        );
+
+        // keys are not programmed nor handed out in the clear
+        for request in [
+            CxlIdeKmMessage::KeyProg(rx_index, CxlIdeKmAesKey::default()),
+            CxlIdeKmMessage::KSetGo(key_index),
+            CxlIdeKmMessage::GetKey(key_index),
+        ] {
+            assert_eq!(
+                cxl_ide_km_request_handler(None, &request.to_req_payload().unwrap()).err(),
+                Some(SPDM_STATUS_INVALID_STATE_PEER)
+            );
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::timing::{exponent_to_us, rdt_from_latency};
+use crate::common::SpdmCodec;
+use crate::error::SpdmResult;
+use crate::message::*;
+use crate::protocol::SpdmVersion;
+use crate::responder::*;
+extern crate alloc;
+use alloc::vec::Vec;
+use zeroize::Zeroize;
+
+/// Requests whose handler calls the measurement or signing callbacks,
+/// only those may be answered with ERROR(ResponseNotReady).
+pub const SPDM_DEFERRABLE_REQUESTS: &[SpdmRequestResponseCode] = &[
+    SpdmRequestResponseCode::SpdmRequestChallenge,
+    SpdmRequestResponseCode::SpdmRequestGetMeasurements,
+    SpdmRequestResponseCode::SpdmRequestKeyExchange,
+    SpdmRequestResponseCode::SpdmRequestGetCsr,
+    SpdmRequestResponseCode::SpdmRequestSetCertificate,
+    SpdmRequestResponseCode::SpdmRequestSetKeyPairInfo,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmResponseReadiness {
+    Ready,
+    /// The response is expected in rdtm * 2^rdt_exponent us.
+    NotReady {
+        rdt_exponent: u8,
+        rdtm: u8,
+    },
+    /// Not ready, the response is expected in the worst latency recorded in
+    /// ResponderContext::crypto_latency, or in CT before any is recorded.
+    NotReadyAsRecorded,
+}
+
+impl SpdmResponseReadiness {
+    /// Not ready, the response is expected in latency_us.
+    pub fn not_ready_in(latency_us: u64) -> SpdmResponseReadiness {
+        let (rdt_exponent, rdtm) = rdt_from_latency(latency_us);
+        SpdmResponseReadiness::NotReady { rdt_exponent, rdtm }
+    }
+}
+
+/// Tells whether the response to a deferrable request can be generated now,
+/// e.g. whether the measurement or signing engine behind the secret
+/// callbacks has completed. A request not ready is answered with
+/// ERROR(ResponseNotReady) and handled on the matching RESPOND_IF_READY.
+#[derive(Clone)]
+pub struct SpdmResponseReadyHandler {
+    pub check_response_ready_cb: fn(
+        spdm_version: SpdmVersion,
+        session_id: Option<u32>,
+        request: &[u8],
+    ) -> SpdmResponseReadiness,
+}
+
+/// The request waiting for RESPOND_IF_READY.
+pub(crate) struct SpdmDeferredContext {
+    pending: bool,
+    session_id: Option<u32>,
+    request_code: u8,
+    token: u8,
+    rdt_exponent: u8,
+    rdtm: u8,
+    request: Vec<u8>,
+    next_token: u8,
+    // set while the pending request is handled again
+    resuming: bool,
+}
+
+impl Default for SpdmDeferredContext {
+    fn default() -> SpdmDeferredContext {
+        SpdmDeferredContext {
+            pending: false,
+            session_id: None,
+            request_code: 0,
+            token: 0,
+            rdt_exponent: 0,
+            rdtm: 0,
+            request: Vec::new(),
+            next_token: 0,
+            resuming: false,
+        }
+    }
+}
+
+impl<'a> ResponderContext<'a> {
+    // Return the (rdt_exponent, rdtm) of a request not ready.
+    fn check_response_ready(&self, session_id: Option<u32>, bytes: &[u8]) -> Option<(u8, u8)> {
+        let readiness = match &self.response_ready_handler {
+            Some(handler) => (handler.check_response_ready_cb)(
+                self.common.negotiate_info.spdm_version_sel,
+                session_id,
+                bytes,
+            ),
+            None => SpdmResponseReadiness::Ready,
+        };
+        match readiness {
+            SpdmResponseReadiness::Ready => None,
+            SpdmResponseReadiness::NotReady { rdt_exponent, rdtm } => Some((rdt_exponent, rdtm)),
+            SpdmResponseReadiness::NotReadyAsRecorded if self.crypto_latency.count != 0 => {
+                Some(rdt_from_latency(self.crypto_latency.max_us))
+            }
+            SpdmResponseReadiness::NotReadyAsRecorded => Some(rdt_from_latency(exponent_to_us(
+                self.common.config_info.rsp_ct_exponent,
+            ))),
+        }
+    }
+
+    /// Return true if the deferrable request is not ready, the request is
+    /// then kept until RESPOND_IF_READY and replaced by a later one.
+    pub(crate) fn is_response_deferred(
+        &mut self,
+        session_id: Option<u32>,
+        request_response_code: SpdmRequestResponseCode,
+        bytes: &[u8],
+    ) -> bool {
+        if self.deferred_context.resuming
+            || !SPDM_DEFERRABLE_REQUESTS.contains(&request_response_code)
+            || bytes.len() > self.common.buffer_config.max_spdm_msg_size
+        {
+            return false;
+        }
+        let (rdt_exponent, rdtm) = match self.check_response_ready(session_id, bytes) {
+            Some(rdt) => rdt,
+            None => return false,
+        };
+
+        let deferred_context = &mut self.deferred_context;
+        deferred_context.pending = true;
+        deferred_context.session_id = session_id;
+        deferred_context.request_code = request_response_code.get_u8();
+        deferred_context.token = deferred_context.next_token;
+        deferred_context.next_token = deferred_context.next_token.wrapping_add(1);
+        deferred_context.rdt_exponent = rdt_exponent;
+        deferred_context.rdtm = rdtm;
+        deferred_context.request.zeroize();
+        deferred_context.request.extend_from_slice(bytes);
+        true
+    }
+
+    pub(crate) fn handle_response_not_ready(&mut self, session_id: Option<u32>) -> SpdmResult {
+        info!(
+            "!!! response not ready : token {:02x?} !!!\n",
+            self.deferred_context.token
+        );
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_response_not_ready(writer);
+            Ok(())
+        })
+    }
+
+    fn write_spdm_response_not_ready(&mut self, writer: &mut Writer) {
+        let error = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseError,
+            },
+            payload: SpdmMessagePayload::SpdmErrorResponse(SpdmErrorResponsePayload {
+                error_code: SpdmErrorCode::SpdmErrorResponseNotReady,
+                error_data: 0,
+                extended_data: SpdmErrorResponseExtData::SpdmErrorExtDataNotReady(
+                    SpdmErrorResponseNotReadyExtData {
+                        rdt_exponent: self.deferred_context.rdt_exponent,
+                        request_code: self.deferred_context.request_code,
+                        token: self.deferred_context.token,
+                        rdtm: self.deferred_context.rdtm,
+                    },
+                ),
+            }),
+        };
+        let _ = error.spdm_encode(&mut self.common, writer);
+    }
+
+    pub fn handle_spdm_respond_if_ready(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        let mut reader = Reader::init(bytes);
+        let message_header = SpdmMessageHeader::read(&mut reader);
+        if let Some(message_header) = message_header {
+            if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                return self.handle_error_request(
+                    SpdmErrorCode::SpdmErrorVersionMismatch,
+                    session_id,
+                    bytes,
+                );
+            }
+        } else {
+            return self.handle_error_request(
+                SpdmErrorCode::SpdmErrorInvalidRequest,
+                session_id,
+                bytes,
+            );
+        }
+
+        let respond_if_ready =
+            SpdmRespondIfReadyRequestPayload::spdm_read(&mut self.common, &mut reader);
+        let deferred_context = &self.deferred_context;
+        let matched = match &respond_if_ready {
+            Some(respond_if_ready) => {
+                deferred_context.pending
+                    && deferred_context.session_id == session_id
+                    && deferred_context.request_code == respond_if_ready.request_code
+                    && deferred_context.token == respond_if_ready.token
+            }
+            None => false,
+        };
+        if !matched {
+            error!("!!! respond_if_ready : no matching request !!!\n");
+            return self.handle_error_request(
+                SpdmErrorCode::SpdmErrorInvalidRequest,
+                session_id,
+                bytes,
+            );
+        }
+
+        let size = self.deferred_context.request.len();
+        let mut request = self.common.alloc_buffer(size);
+        request.copy_from_slice(&self.deferred_context.request[..size]);
+        let result = match self.check_response_ready(session_id, &request) {
+            Some((rdt_exponent, rdtm)) => {
+                self.deferred_context.rdt_exponent = rdt_exponent;
+                self.deferred_context.rdtm = rdtm;
+                self.handle_response_not_ready(session_id)
+            }
+            None => {
+                debug!("!!! respond_if_ready : {:02x?}\n", request[1]);
+                self.deferred_context.pending = false;
+                self.deferred_context.resuming = true;
+                let result = match session_id {
+                    Some(session_id) => self.dispatch_secured_message(session_id, &request),
+                    None => self.dispatch_message(&request),
+                };
+                self.deferred_context.resuming = false;
+                result
+            }
+        };
+        self.common.free_buffer(request);
+        result
+    }
+}
@@ -15,15 +15,10 @@ use alloc::boxed::Box;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_digest(&mut self, bytes: &[u8], session_id: Option<u32>) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_digest_response(session_id, bytes, &mut writer);
-
-        if let Some(session_id) = session_id {
-            self.send_secured_message(session_id, writer.used_slice(), false)
-        } else {
-            self.send_message(writer.used_slice())
-        }
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_digest_response(session_id, bytes, writer);
+            Ok(())
+        })
     }
 
     fn write_spdm_digest_response(
@@ -80,10 +75,52 @@ impl<'a> ResponderContext<'a> {
 
         let digest_size = self.common.negotiate_info.base_hash_sel.get_size();
 
+        // the digests are sent in slot order, only for the slots usable
+        // with the negotiated algorithms
         let mut slot_mask = 0u8;
+        let mut digests = gen_array_clone(
+            SpdmDigestStruct {
+                data_size: digest_size,
+                data: Box::new([0xffu8; SPDM_MAX_HASH_SIZE]),
+            },
+            SPDM_MAX_SLOT_NUMBER,
+        );
+        let mut key_pair_id = [0u8; SPDM_MAX_SLOT_NUMBER];
+        let mut cert_model = [SpdmCertificateModelType::default(); SPDM_MAX_SLOT_NUMBER];
+        let mut key_usage_mask = [SpdmKeyUsageMask::empty(); SPDM_MAX_SLOT_NUMBER];
+        let my_cert_model = if self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::ALIAS_CERT_CAP)
+        {
+            SpdmCertificateModelType::SpdmCertModelTypeAliasCert
+        } else {
+            SpdmCertificateModelType::SpdmCertModelTypeDeviceCert
+        };
+        let mut count = 0usize;
         for slot_id in 0..SPDM_MAX_SLOT_NUMBER {
-            if self.common.provision_info.my_cert_chain[slot_id].is_some() {
-                slot_mask |= (1 << slot_id) as u8;
+            if let Some(my_cert_chain) = self.common.provision_info.my_cert_chain[slot_id].as_ref()
+            {
+                if let Some(cert_chain_hash) = crypto::hash::hash_all(
+                    self.common.negotiate_info.base_hash_sel,
+                    my_cert_chain.as_ref(),
+                ) {
+                    slot_mask |= (1 << slot_id) as u8;
+                    digests[count] = cert_chain_hash;
+                    cert_model[count] = my_cert_model;
+                    let provision_info = &self.common.provision_info;
+                    if let Some(id) = provision_info.get_slot_key_pair_id(slot_id as u8) {
+                        key_pair_id[count] = id;
+                        if let Some(key_pair_info) = provision_info.get_key_pair_info(id) {
+                            key_usage_mask[count] = key_pair_info.current_key_usage;
+                        }
+                    }
+                    count += 1;
+                } else {
+                    self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                    return;
+                }
             }
         }
 
@@ -95,13 +132,10 @@ impl<'a> ResponderContext<'a> {
             },
             payload: SpdmMessagePayload::SpdmDigestsResponse(SpdmDigestsResponsePayload {
                 slot_mask,
-                digests: gen_array_clone(
-                    SpdmDigestStruct {
-                        data_size: digest_size,
-                        data: Box::new([0xffu8; SPDM_MAX_HASH_SIZE]),
-                    },
-                    SPDM_MAX_SLOT_NUMBER,
-                ),
+                digests,
+                key_pair_id,
+                cert_model,
+                key_usage_mask,
             }),
         };
         let res = response.spdm_encode(&mut self.common, writer);
@@ -110,24 +144,6 @@ impl<'a> ResponderContext<'a> {
             return;
         }
 
-        for slot_id in 0..SPDM_MAX_SLOT_NUMBER {
-            if self.common.provision_info.my_cert_chain[slot_id].is_some() {
-                let my_cert_chain = self.common.provision_info.my_cert_chain[slot_id]
-                    .as_ref()
-                    .unwrap();
-                let cert_chain_hash = crypto::hash::hash_all(
-                    self.common.negotiate_info.base_hash_sel,
-                    my_cert_chain.as_ref(),
-                )
-                .unwrap();
-
-                // patch the message before send
-                let used = writer.used();
-                writer.mut_used_slice()[(used - cert_chain_hash.data_size as usize)..used]
-                    .copy_from_slice(cert_chain_hash.as_ref());
-            }
-        }
-
         match session_id {
             None => {
                 if self.common.append_message_b(writer.used_slice()).is_err() {
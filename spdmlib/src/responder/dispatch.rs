@@ -0,0 +1,257 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::request_policy::SpdmRequestStates;
+use super::ResponderContext;
+use crate::common::SpdmConnectionState;
+use crate::error::{SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_UNSUPPORTED_CAP};
+use crate::message::SpdmRequestResponseCode;
+
+/// Number of request handlers an application can register on a responder.
+pub const MAX_SPDM_REQUEST_HANDLER_COUNT: usize = 8;
+
+/// Handle one request and send the response.
+/// session_id is None for a request received outside of a session.
+pub type SpdmRequestHandler =
+    fn(context: &mut ResponderContext<'_>, session_id: Option<u32>, bytes: &[u8]) -> SpdmResult;
+
+/// One row of the dispatch table.
+///
+/// The handler is called for request_response_code in the session states
+/// listed in states, once the connection reached connection_state.
+/// A request with a lower connection state is answered with UnexpectedRequest.
+#[derive(Clone, Copy)]
+pub struct SpdmRequestDispatchEntry {
+    pub request_response_code: SpdmRequestResponseCode,
+    pub states: SpdmRequestStates,
+    pub connection_state: SpdmConnectionState,
+    pub handler: SpdmRequestHandler,
+}
+
+impl SpdmRequestDispatchEntry {
+    pub(crate) fn matches(
+        &self,
+        request_response_code: SpdmRequestResponseCode,
+        states: SpdmRequestStates,
+    ) -> bool {
+        self.request_response_code == request_response_code && self.states.intersects(states)
+    }
+}
+
+macro_rules! dispatch_entry {
+    ($code:ident, $states:expr, $handler:expr) => {
+        SpdmRequestDispatchEntry {
+            request_response_code: SpdmRequestResponseCode::$code,
+            states: $states,
+            // the built-in handlers check the connection state themselves
+            connection_state: SpdmConnectionState::SpdmConnectionNotStarted,
+            handler: $handler,
+        }
+    };
+}
+
+const OUT_OF_SESSION: SpdmRequestStates = SpdmRequestStates::OUT_OF_SESSION;
+const HANDSHAKING: SpdmRequestStates = SpdmRequestStates::HANDSHAKING;
+const ESTABLISHED: SpdmRequestStates = SpdmRequestStates::ESTABLISHED;
+const OUT_OF_SESSION_OR_ESTABLISHED: SpdmRequestStates =
+    SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits());
+
+/// Built-in handling of each request.
+///
+/// SPDM_REQUEST_STATE_TABLE and SPDM_REQUEST_VERSION_TABLE are applied before
+/// the lookup. A request without a matching row is dropped.
+pub const SPDM_REQUEST_DISPATCH_TABLE: &[SpdmRequestDispatchEntry] = &[
+    dispatch_entry!(
+        SpdmRequestResponseIfReady,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_respond_if_ready(session_id, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestGetVersion,
+        OUT_OF_SESSION,
+        |context, _, bytes| context.handle_spdm_version(bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestGetCapabilities,
+        OUT_OF_SESSION,
+        |context, _, bytes| context.handle_spdm_capability(bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestNegotiateAlgorithms,
+        OUT_OF_SESSION,
+        |context, _, bytes| context.handle_spdm_algorithm(bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestGetDigests,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_digest(bytes, session_id)
+    ),
+    dispatch_entry!(
+        SpdmRequestGetCertificate,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_certificate(bytes, session_id)
+    ),
+    dispatch_entry!(SpdmRequestChallenge, OUT_OF_SESSION, |context, _, bytes| {
+        context.handle_spdm_challenge(bytes)
+    }),
+    dispatch_entry!(
+        SpdmRequestGetMeasurements,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_measurement(session_id, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestGetCsr,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_get_csr(session_id, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestSetCertificate,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_set_certificate(session_id, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestGetKeyPairInfo,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_get_key_pair_info(session_id, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestSetKeyPairInfo,
+        OUT_OF_SESSION_OR_ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_set_key_pair_info(session_id, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestKeyExchange,
+        OUT_OF_SESSION,
+        |context, _, bytes| context.handle_spdm_key_exchange(bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestPskExchange,
+        OUT_OF_SESSION,
+        |context, _, bytes| context.handle_spdm_psk_exchange(bytes)
+    ),
+    #[cfg(feature = "mut-auth")]
+    dispatch_entry!(
+        SpdmRequestGetEncapsulatedRequest,
+        HANDSHAKING,
+        |context, session_id, bytes| context
+            .handle_get_encapsulated_request(session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?, bytes)
+    ),
+    #[cfg(feature = "mut-auth")]
+    dispatch_entry!(
+        SpdmRequestDeliverEncapsulatedResponse,
+        HANDSHAKING,
+        |context, session_id, bytes| context.handle_deliver_encapsulated_reponse(
+            session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?,
+            bytes
+        )
+    ),
+    dispatch_entry!(
+        SpdmRequestFinish,
+        HANDSHAKING,
+        |context, session_id, bytes| context
+            .handle_spdm_finish(session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestPskFinish,
+        HANDSHAKING,
+        |context, session_id, bytes| context
+            .handle_spdm_psk_finish(session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestHeartbeat,
+        ESTABLISHED,
+        |context, session_id, bytes| context
+            .handle_spdm_heartbeat(session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestKeyUpdate,
+        ESTABLISHED,
+        |context, session_id, bytes| context
+            .handle_spdm_key_update(session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestEndSession,
+        ESTABLISHED,
+        |context, session_id, bytes| context
+            .handle_spdm_end_session(session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestVendorDefinedRequest,
+        SpdmRequestStates::all(),
+        |context, session_id, bytes| context.handle_spdm_vendor_defined_request(session_id, bytes)
+    ),
+    // the reassembled request is checked against the session state when dispatched
+    dispatch_entry!(
+        SpdmRequestChunkSend,
+        SpdmRequestStates::all(),
+        |context, session_id, bytes| context.handle_spdm_chunk_send(session_id, bytes)
+    ),
+    dispatch_entry!(
+        SpdmRequestGetSupportedEventTypes,
+        ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_get_supported_event_types(
+            session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?,
+            bytes
+        )
+    ),
+    dispatch_entry!(
+        SpdmRequestSubscribeEventTypes,
+        ESTABLISHED,
+        |context, session_id, bytes| context.handle_spdm_subscribe_event_types(
+            session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?,
+            bytes
+        )
+    ),
+    dispatch_entry!(
+        SpdmResponseEventAck,
+        ESTABLISHED,
+        |context, session_id, bytes| context
+            .handle_spdm_event_ack(session_id.ok_or(SPDM_STATUS_UNSUPPORTED_CAP)?, bytes)
+    ),
+];
+
+impl<'a> ResponderContext<'a> {
+    /// Register a handler taking precedence over SPDM_REQUEST_DISPATCH_TABLE.
+    /// A handler registered before for the same request code and states is replaced.
+    pub fn register_request_handler(&mut self, entry: SpdmRequestDispatchEntry) -> SpdmResult {
+        let slot = self
+            .request_handlers
+            .iter()
+            .position(|registered| {
+                registered.map_or(false, |registered| {
+                    registered.request_response_code == entry.request_response_code
+                        && registered.states == entry.states
+                })
+            })
+            .or_else(|| self.request_handlers.iter().position(|r| r.is_none()))
+            .ok_or(SPDM_STATUS_BUFFER_FULL)?;
+        self.request_handlers[slot] = Some(entry);
+        Ok(())
+    }
+
+    /// Remove all handlers registered for request_response_code.
+    pub fn unregister_request_handler(&mut self, request_response_code: SpdmRequestResponseCode) {
+        for registered in self.request_handlers.iter_mut() {
+            if registered.map_or(false, |registered| {
+                registered.request_response_code == request_response_code
+            }) {
+                *registered = None;
+            }
+        }
+    }
+
+    /// Find the handler of request_response_code in states, registered handlers first.
+    pub(crate) fn get_request_handler(
+        &self,
+        request_response_code: SpdmRequestResponseCode,
+        states: SpdmRequestStates,
+    ) -> Option<SpdmRequestDispatchEntry> {
+        self.request_handlers
+            .iter()
+            .flatten()
+            .chain(SPDM_REQUEST_DISPATCH_TABLE.iter())
+            .find(|entry| entry.matches(request_response_code, states))
+            .copied()
+    }
+}
@@ -182,7 +182,7 @@ impl<'a> ResponderContext<'a> {
         }
 
         let data_size_in_cert_chain =
-            peer_cert_chain.data[0] as u16 + ((peer_cert_chain.data[1] as u16) << 8);
+            u16::read_bytes(&peer_cert_chain.data[..2]).ok_or(SPDM_STATUS_INVALID_CERT)?;
         if data_size_in_cert_chain != peer_cert_chain.data_size {
             return Err(SPDM_STATUS_INVALID_CERT);
         }
@@ -201,10 +201,13 @@ impl<'a> ResponderContext<'a> {
         //
         // 1.1 verify the integrity of the chain
         //
-        if crypto::cert_operation::verify_cert_chain(
-            &runtime_peer_cert_chain_data.data[..(runtime_peer_cert_chain_data.data_size as usize)],
-        )
-        .is_err()
+        if self
+            .common
+            .verify_peer_cert_chain(
+                &runtime_peer_cert_chain_data.data
+                    [..(runtime_peer_cert_chain_data.data_size as usize)],
+            )
+            .is_err()
         {
             error!("cert_chain verification - fail! - TBD later\n");
             return Err(SPDM_STATUS_INVALID_CERT);
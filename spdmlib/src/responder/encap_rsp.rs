@@ -23,7 +23,7 @@ use super::ResponderContext;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_get_encapsulated_request(&mut self, session_id: u32, bytes: &[u8]) -> SpdmResult {
-        let mut encapsulated_request = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut encapsulated_request = self.common.alloc_message_buffer();
         let mut writer = Writer::init(&mut encapsulated_request);
 
         self.encap_check_version_cap_state(
@@ -32,7 +32,10 @@ impl<'a> ResponderContext<'a> {
         );
         self.write_encap_request_response(bytes, &mut writer);
 
-        self.send_secured_message(session_id, writer.used_slice(), false)
+        let used = writer.used();
+        let result = self.send_encap_message(session_id, &encapsulated_request[..used]);
+        self.common.free_buffer(encapsulated_request);
+        result
     }
 
     fn write_encap_request_response(&mut self, bytes: &[u8], writer: &mut Writer) {
@@ -78,7 +81,7 @@ impl<'a> ResponderContext<'a> {
         session_id: u32,
         bytes: &[u8],
     ) -> SpdmResult {
-        let mut encap_response_ack = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut encap_response_ack = self.common.alloc_message_buffer();
         let mut writer = Writer::init(&mut encap_response_ack);
 
         self.encap_check_version_cap_state(
@@ -87,7 +90,10 @@ impl<'a> ResponderContext<'a> {
         );
         self.write_encap_response_ack_response(bytes, &mut writer);
 
-        self.send_secured_message(session_id, writer.used_slice(), false)
+        let used = writer.used();
+        let result = self.send_encap_message(session_id, &encap_response_ack[..used]);
+        self.common.free_buffer(encap_response_ack);
+        result
     }
 
     fn write_encap_response_ack_response(&mut self, bytes: &[u8], writer: &mut Writer) {
@@ -119,6 +125,14 @@ impl<'a> ResponderContext<'a> {
         }
     }
 
+    fn send_encap_message(&mut self, session_id: u32, send_buffer: &[u8]) -> SpdmResult {
+        if self.common.is_session_handshake_in_clear(session_id) {
+            self.send_message(send_buffer)
+        } else {
+            self.send_secured_message(session_id, send_buffer, false)
+        }
+    }
+
     fn encap_check_version_cap_state(&mut self, request_response_code: u8, writer: &mut Writer) {
         if self.common.negotiate_info.spdm_version_sel.get_u8()
             < SpdmVersion::SpdmVersion11.get_u8()
@@ -5,6 +5,7 @@
 use crate::common::SpdmCodec;
 use crate::error::SpdmResult;
 use crate::message::*;
+use crate::protocol::SpdmVersion;
 use crate::responder::*;
 
 impl<'a> ResponderContext<'a> {
@@ -30,12 +31,49 @@ impl<'a> ResponderContext<'a> {
         let _ = error.spdm_encode(&mut self.common, writer);
     }
 
+    pub fn write_spdm_vendor_error(
+        &mut self,
+        vendor_error: &SpdmVendorDefinedError,
+        writer: &mut Writer,
+    ) {
+        let error = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseError,
+            },
+            payload: SpdmMessagePayload::SpdmErrorResponse(SpdmErrorResponsePayload {
+                error_code: SpdmErrorCode::SpdmErrorVendorDefined,
+                error_data: vendor_error.error_data,
+                extended_data: SpdmErrorResponseExtData::SpdmErrorExtDataVendorDefined(
+                    vendor_error.extended_data.clone(),
+                ),
+            }),
+        };
+        let _ = error.spdm_encode(&mut self.common, writer);
+    }
+
+    /// Reply to a request larger than the receive buffer or than the
+    /// negotiated DataTransferSize. RequestTooLarge is defined since SPDM 1.2.
+    pub fn handle_request_too_large(&mut self, session_id: Option<u32>) -> SpdmResult {
+        let error_code = if self.common.negotiate_info.spdm_version_sel.get_u8()
+            >= SpdmVersion::SpdmVersion12.get_u8()
+        {
+            SpdmErrorCode::SpdmErrorRequestTooLarge
+        } else {
+            SpdmErrorCode::SpdmErrorInvalidRequest
+        };
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_error(error_code, 0, writer);
+            Ok(())
+        })
+    }
+
     pub fn send_spdm_error(&mut self, error_code: SpdmErrorCode, error_data: u8) {
         info!("send spdm version\n");
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_error(error_code, error_data, &mut writer);
-        let _ = self.send_message(writer.used_slice());
+        let _ = self.send_response(None, |responder, writer| {
+            responder.write_spdm_error(error_code, error_data, writer);
+            Ok(())
+        });
     }
 }
 
@@ -46,14 +84,10 @@ impl<'a> ResponderContext<'a> {
         session_id: Option<u32>,
         bytes: &[u8],
     ) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_error_response(error_code, bytes, &mut writer);
-        if let Some(session_id) = session_id {
-            self.send_secured_message(session_id, writer.used_slice(), false)
-        } else {
-            self.send_message(writer.used_slice())
-        }
+        self.send_response(session_id, |responder, writer| {
+            responder.write_error_response(error_code, bytes, writer);
+            Ok(())
+        })
     }
 
     pub fn write_error_response(
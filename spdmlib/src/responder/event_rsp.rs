@@ -0,0 +1,333 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::session::SpdmSessionState;
+use crate::common::SpdmCodec;
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_INVALID_STATE_LOCAL};
+use crate::message::*;
+use crate::protocol::*;
+use crate::responder::*;
+
+/// Event types the responder supports and the subscription of the requester.
+pub(crate) struct SpdmEventContext {
+    supported_group_count: usize,
+    supported_groups: [SpdmEventGroup; MAX_SPDM_EVENT_GROUP_COUNT],
+    // session the events are delivered in, None until SUBSCRIBE_EVENT_TYPES
+    subscribed_session_id: Option<u32>,
+    subscribed_group_count: usize,
+    subscribed_groups: [SpdmEventGroup; MAX_SPDM_EVENT_GROUP_COUNT],
+    next_event_instance_id: u32,
+    // EventInstanceID of the last EVENT_ACK
+    pub(crate) last_acked_event_instance_id: Option<u32>,
+}
+
+impl Default for SpdmEventContext {
+    fn default() -> SpdmEventContext {
+        let mut supported_groups = <[SpdmEventGroup; MAX_SPDM_EVENT_GROUP_COUNT]>::default();
+        supported_groups[0] = SpdmEventGroup::dmtf();
+        SpdmEventContext {
+            supported_group_count: 1,
+            supported_groups,
+            subscribed_session_id: None,
+            subscribed_group_count: 0,
+            subscribed_groups: Default::default(),
+            next_event_instance_id: 0,
+            last_acked_event_instance_id: None,
+        }
+    }
+}
+
+impl SpdmEventContext {
+    fn supported_groups(&self) -> &[SpdmEventGroup] {
+        &self.supported_groups[..self.supported_group_count]
+    }
+
+    fn subscribed_groups(&self) -> &[SpdmEventGroup] {
+        &self.subscribed_groups[..self.subscribed_group_count]
+    }
+
+    fn is_supported(&self, group: &SpdmEventGroup) -> bool {
+        self.supported_groups().iter().any(|supported| {
+            supported.group_id == group.group_id
+                && group
+                    .event_types()
+                    .iter()
+                    .all(|event_type_id| supported.contains(*event_type_id))
+        })
+    }
+
+    fn is_subscribed(
+        &self,
+        session_id: u32,
+        group_id: &SpdmEventGroupId,
+        event_type_id: u16,
+    ) -> bool {
+        self.subscribed_session_id == Some(session_id)
+            && self
+                .subscribed_groups()
+                .iter()
+                .any(|group| group.group_id == *group_id && group.contains(event_type_id))
+    }
+
+    fn unsubscribe(&mut self) {
+        self.subscribed_session_id = None;
+        self.subscribed_group_count = 0;
+    }
+
+    /// The subscription does not outlive its session.
+    pub(crate) fn end_session(&mut self, session_id: u32) {
+        if self.subscribed_session_id == Some(session_id) {
+            self.unsubscribe();
+        }
+    }
+}
+
+impl<'a> ResponderContext<'a> {
+    /// Replace the event types reported in SUPPORTED_EVENT_TYPES.
+    /// The DMTF event group is supported by default.
+    pub fn set_supported_event_types(&mut self, event_groups: &[SpdmEventGroup]) -> SpdmResult {
+        if event_groups.is_empty() || event_groups.len() > MAX_SPDM_EVENT_GROUP_COUNT {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        self.event_context.supported_groups[..event_groups.len()].clone_from_slice(event_groups);
+        self.event_context.supported_group_count = event_groups.len();
+        self.event_context.unsubscribe();
+        Ok(())
+    }
+
+    /// Deliver an event to the requester in the session it subscribed in,
+    /// e.g. SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED after a firmware update.
+    /// Events the requester did not subscribe to are not sent.
+    pub fn send_event(
+        &mut self,
+        session_id: u32,
+        group_id: &SpdmEventGroupId,
+        event_type_id: u16,
+        event_detail: &[u8],
+    ) -> SpdmResult {
+        if event_detail.len() > MAX_SPDM_EVENT_DETAIL_SIZE {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        match self.common.get_immutable_session_via_id(session_id) {
+            Some(session)
+                if session.get_session_state() == SpdmSessionState::SpdmSessionEstablished => {}
+            _ => return Err(SPDM_STATUS_INVALID_STATE_LOCAL),
+        }
+        if !self
+            .event_context
+            .is_subscribed(session_id, group_id, event_type_id)
+        {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+
+        let mut request = SpdmSendEventRequestPayload {
+            event_count: 1,
+            ..Default::default()
+        };
+        request.events[0].event_instance_id = self.event_context.next_event_instance_id;
+        request.events[0].group_id = group_id.clone();
+        request.events[0].event_type_id = event_type_id;
+        request.events[0].event_detail_len = event_detail.len() as u16;
+        request.events[0].event_detail[..event_detail.len()].copy_from_slice(event_detail);
+
+        info!("send spdm event\n");
+        let message = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmRequestSendEvent,
+            },
+            payload: SpdmMessagePayload::SpdmSendEventRequest(request),
+        };
+        let mut send_buffer = self.common.alloc_message_buffer();
+        let mut writer = Writer::init(&mut send_buffer);
+        let used = message.spdm_encode(&mut self.common, &mut writer)?;
+        self.send_async_message(Some(session_id), &send_buffer[..used])?;
+        self.common.free_buffer(send_buffer);
+        self.event_context.next_event_instance_id =
+            self.event_context.next_event_instance_id.wrapping_add(1);
+        Ok(())
+    }
+
+    pub fn handle_spdm_get_supported_event_types(
+        &mut self,
+        session_id: u32,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        self.send_response(Some(session_id), |responder, writer| {
+            responder.write_spdm_supported_event_types_response(session_id, bytes, writer);
+            Ok(())
+        })
+    }
+
+    fn write_spdm_supported_event_types_response(
+        &mut self,
+        session_id: u32,
+        bytes: &[u8],
+        writer: &mut Writer,
+    ) {
+        if !self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::EVENT_CAP)
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnsupportedRequest, 0, writer);
+            return;
+        }
+
+        let mut reader = Reader::init(bytes);
+        let message_header = SpdmMessageHeader::read(&mut reader);
+        if let Some(message_header) = message_header {
+            if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorVersionMismatch, 0, writer);
+                return;
+            }
+        } else {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestGetSupportedEventTypes,
+            Some(session_id),
+        );
+
+        if SpdmGetSupportedEventTypesRequestPayload::spdm_read(&mut self.common, &mut reader)
+            .is_none()
+        {
+            error!("!!! get_supported_event_types : fail !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        info!("send spdm supported_event_types\n");
+        let mut payload = SpdmSupportedEventTypesResponsePayload {
+            event_group_count: self.event_context.supported_group_count as u8,
+            ..Default::default()
+        };
+        payload.event_groups[..self.event_context.supported_group_count]
+            .clone_from_slice(self.event_context.supported_groups());
+        let response = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseSupportedEventTypes,
+            },
+            payload: SpdmMessagePayload::SpdmSupportedEventTypesResponse(payload),
+        };
+        if response.spdm_encode(&mut self.common, writer).is_err() {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+        }
+    }
+
+    pub fn handle_spdm_subscribe_event_types(
+        &mut self,
+        session_id: u32,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        self.send_response(Some(session_id), |responder, writer| {
+            responder.write_spdm_subscribe_event_types_ack_response(session_id, bytes, writer);
+            Ok(())
+        })
+    }
+
+    fn write_spdm_subscribe_event_types_ack_response(
+        &mut self,
+        session_id: u32,
+        bytes: &[u8],
+        writer: &mut Writer,
+    ) {
+        if !self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::EVENT_CAP)
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnsupportedRequest, 0, writer);
+            return;
+        }
+
+        let mut reader = Reader::init(bytes);
+        let message_header = SpdmMessageHeader::read(&mut reader);
+        if let Some(message_header) = message_header {
+            if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorVersionMismatch, 0, writer);
+                return;
+            }
+        } else {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestSubscribeEventTypes,
+            Some(session_id),
+        );
+
+        let subscribe =
+            SpdmSubscribeEventTypesRequestPayload::spdm_read(&mut self.common, &mut reader);
+        let subscribe = if let Some(subscribe) = subscribe {
+            debug!(
+                "!!! subscribe_event_types : {:02x?}\n",
+                subscribe.event_group_count
+            );
+            subscribe
+        } else {
+            error!("!!! subscribe_event_types : fail !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        };
+        if !subscribe
+            .event_groups()
+            .iter()
+            .all(|group| self.event_context.is_supported(group))
+        {
+            error!("!!! subscribe_event_types : unsupported event type !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        if subscribe.event_group_count == 0 {
+            self.event_context.unsubscribe();
+        } else {
+            let count = subscribe.event_group_count as usize;
+            self.event_context.subscribed_groups[..count]
+                .clone_from_slice(subscribe.event_groups());
+            self.event_context.subscribed_group_count = count;
+            self.event_context.subscribed_session_id = Some(session_id);
+        }
+
+        info!("send spdm subscribe_event_types_ack\n");
+        let response = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseSubscribeEventTypesAck,
+            },
+            payload: SpdmMessagePayload::SpdmSubscribeEventTypesAckResponse(
+                SpdmSubscribeEventTypesAckResponsePayload {},
+            ),
+        };
+        if response.spdm_encode(&mut self.common, writer).is_err() {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+        }
+    }
+
+    /// EVENT_ACK for the last event sent. No response is sent back.
+    pub fn handle_spdm_event_ack(&mut self, session_id: u32, bytes: &[u8]) -> SpdmResult {
+        let mut reader = Reader::init(bytes);
+        let message_header =
+            SpdmMessageHeader::read(&mut reader).ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        if message_header.version != self.common.negotiate_info.spdm_version_sel
+            || self.event_context.subscribed_session_id != Some(session_id)
+            || self.event_context.next_event_instance_id == 0
+        {
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+        SpdmEventAckResponsePayload::spdm_read(&mut self.common, &mut reader)
+            .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        debug!("!!! event_ack : {:08x?}\n", session_id);
+        self.event_context.last_acked_event_instance_id =
+            Some(self.event_context.next_event_instance_id.wrapping_sub(1));
+        Ok(())
+    }
+}
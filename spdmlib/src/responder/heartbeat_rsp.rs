@@ -2,17 +2,61 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
+use crate::common::session::SpdmSessionState;
 use crate::common::SpdmCodec;
 use crate::error::SpdmResult;
 use crate::message::*;
+use crate::protocol::{SpdmRequestCapabilityFlags, SpdmResponseCapabilityFlags};
 use crate::responder::*;
 
 impl<'a> ResponderContext<'a> {
+    /// Tear down the established sessions which received no message within
+    /// twice their HeartbeatPeriod, return how many. Received messages are
+    /// timed with the registered SpdmTime, or by the application with
+    /// SpdmSession::touch. A session without any is timed from this call.
+    pub fn expire_idle_sessions(&mut self, now_us: u64) -> usize {
+        if !self
+            .common
+            .negotiate_info
+            .req_capabilities_sel
+            .contains(SpdmRequestCapabilityFlags::HBEAT_CAP)
+            || !self
+                .common
+                .negotiate_info
+                .rsp_capabilities_sel
+                .contains(SpdmResponseCapabilityFlags::HBEAT_CAP)
+        {
+            return 0;
+        }
+
+        let mut expired = 0;
+        for session in self.common.session.iter_mut() {
+            if session.get_session_state() != SpdmSessionState::SpdmSessionEstablished
+                || session.heartbeat_period == 0
+            {
+                continue;
+            }
+            if session.get_last_message_us().is_none() {
+                session.touch(now_us);
+            } else if session.is_heartbeat_expired(now_us) {
+                let session_id = session.get_session_id();
+                error!("!!! heartbeat : session {:x?} expired !!!\n", session_id);
+                let _ = session.teardown(session_id);
+                self.event_context.end_session(session_id);
+                expired += 1;
+            }
+        }
+        expired
+    }
+
     pub fn handle_spdm_heartbeat(&mut self, session_id: u32, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let mut writer = Writer::init(&mut send_buffer);
         self.write_spdm_heartbeat_response(session_id, bytes, &mut writer);
-        self.send_secured_message(session_id, writer.used_slice(), false)
+        let used = writer.used();
+        let result = self.send_secured_message(session_id, &send_buffer[..used], false);
+        self.common.free_buffer(send_buffer);
+        result
     }
 
     pub fn write_spdm_heartbeat_response(
@@ -0,0 +1,213 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! IDE_KM responder. The device registers its IDE key handlers, and
+//! ide_km_request_handler for the PCI-SIG vendor ID:
+//!
+//! registry.register(IDE_KM_STANDARD_ID, &ide_km_vendor_id(), ide_km_request_handler)?;
+//! register_vendor_defined_registry(registry);
+//!
+//! IDE_KM requests are only served in an established secure session, as
+//! they carry the IDE keys.
+
+use conquer_once::spin::OnceCell;
+
+use crate::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_MSG_FIELD,
+    SPDM_STATUS_INVALID_STATE_PEER, SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use crate::message::ide_km::*;
+use crate::message::{
+    VendorDefinedReqPayloadStruct, VendorDefinedRequestSession, VendorDefinedRspPayloadStruct,
+};
+use codec::{Codec, Reader};
+
+#[derive(Clone, Copy)]
+pub struct SpdmIdeKmHandler {
+    /// Return None for an unsupported port_index.
+    pub query_cb: fn(port_index: u8) -> Option<IdeKmQueryResp>,
+    pub key_prog_cb: fn(key_index: &IdeKmKeyIndex, key: &IdeKmAesKey) -> IdeKmKpAckStatus,
+    pub key_set_go_cb: fn(key_index: &IdeKmKeyIndex) -> SpdmResult,
+    pub key_set_stop_cb: fn(key_index: &IdeKmKeyIndex) -> SpdmResult,
+}
+
+static SPDM_IDE_KM_HANDLER: OnceCell<SpdmIdeKmHandler> = OnceCell::uninit();
+
+pub fn register(context: SpdmIdeKmHandler) -> bool {
+    SPDM_IDE_KM_HANDLER.try_init_once(|| context).is_ok()
+}
+
+/// Handle an IDE_KM request. Return SPDM_STATUS_UNSUPPORTED_CAP if no
+/// handler is registered or the payload is another PCI-SIG protocol, so a
+/// device handler can try its other protocols.
+///
+/// Return SPDM_STATUS_INVALID_STATE_PEER if the request is not in an
+/// established session.
+pub fn ide_km_request_handler(
+    session: Option<VendorDefinedRequestSession>,
+    vendor_defined_req_payload_struct: &VendorDefinedReqPayloadStruct,
+) -> SpdmResult<VendorDefinedRspPayloadStruct> {
+    let payload = &vendor_defined_req_payload_struct.vendor_defined_req_payload
+        [..vendor_defined_req_payload_struct.req_length as usize];
+    if !IdeKmMessage::is_ide_km(payload) {
+        return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+    }
+    let handler = SPDM_IDE_KM_HANDLER
+        .try_get()
+        .map_err(|_| SPDM_STATUS_UNSUPPORTED_CAP)?;
+    if !VendorDefinedRequestSession::is_established(session) {
+        return Err(SPDM_STATUS_INVALID_STATE_PEER);
+    }
+
+    let mut reader = Reader::init(payload);
+    let request = IdeKmMessage::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+    let response = match request {
+        IdeKmMessage::Query { port_index } => IdeKmMessage::QueryResp(
+            (handler.query_cb)(port_index).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?,
+        ),
+        IdeKmMessage::KeyProg(key_index, key) => {
+            let status = if reader.any_left() {
+                IdeKmKpAckStatus::IdeKmKpAckIncorrectLength
+            } else {
+                (handler.key_prog_cb)(&key_index, &key)
+            };
+            IdeKmMessage::KpAck(key_index, status)
+        }
+        IdeKmMessage::KSetGo(key_index) => {
+            (handler.key_set_go_cb)(&key_index)?;
+            IdeKmMessage::KGoStopAck(key_index)
+        }
+        IdeKmMessage::KSetStop(key_index) => {
+            (handler.key_set_stop_cb)(&key_index)?;
+            IdeKmMessage::KGoStopAck(key_index)
+        }
+        _ => return Err(SPDM_STATUS_INVALID_MSG_FIELD),
+    };
+    response.to_rsp_payload().ok_or(SPDM_STATUS_BUFFER_FULL)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use crate::common::session::SpdmSessionState;
+    use crate::error::SPDM_STATUS_INVALID_STATE_LOCAL;
+
+    const SESSION: Option<VendorDefinedRequestSession> = Some(VendorDefinedRequestSession {
+        session_id: 0xFFFE_FFFE,
+        session_state: SpdmSessionState::SpdmSessionEstablished,
+    });
+
+    fn handle(request: &IdeKmMessage) -> SpdmResult<IdeKmMessage> {
+        let rsp_payload = ide_km_request_handler(SESSION, &request.to_req_payload().unwrap())?;
+        Ok(IdeKmMessage::read_bytes(
+            &rsp_payload.vendor_defined_rsp_payload[..rsp_payload.rsp_length as usize],
+        )
+        .unwrap())
+    }
+
+    #[test]
+    fn test_case0_ide_km_request_handler() {
+        assert!(register(SpdmIdeKmHandler {
+            query_cb: |port_index| {
+                if port_index != 0 {
+                    return None;
+                }
+                let mut query_resp = IdeKmQueryResp {
+                    register_count: 2,
+                    ..Default::default()
+                };
+                query_resp.registers[0] = 0x8000_0002;
+                Some(query_resp)
+            },
+            key_prog_cb: |key_index, key| {
+                if key_index.stream_id != 1 {
+                    IdeKmKpAckStatus::IdeKmKpAckUnsupportedValue
+                } else if key.key[0] != 0x11 {
+                    IdeKmKpAckStatus::IdeKmKpAckUnspecifiedFailure
+                } else {
+                    IdeKmKpAckStatus::IdeKmKpAckSuccess
+                }
+            },
+            key_set_go_cb: |_| Ok(()),
+            key_set_stop_cb: |_| Err(SPDM_STATUS_INVALID_STATE_LOCAL),
+        }));
+
+        match handle(&IdeKmMessage::Query { port_index: 0 }).unwrap() {
+            IdeKmMessage::QueryResp(query_resp) => {
+                assert_eq!(query_resp.registers(), &[0x8000_0002, 0])
+            }
+            _ => panic!(),
+        }
+        assert_eq!(
+            handle(&IdeKmMessage::Query { port_index: 1 }).err(),
+            Some(SPDM_STATUS_INVALID_MSG_FIELD)
+        );
+
+        let key_index = IdeKmKeyIndex {
+            stream_id: 1,
+            ..Default::default()
+        };
+        let key = IdeKmAesKey {
+            key: [0x11u8; IDE_KM_KEY_SIZE],
+            ..Default::default()
+        };
+        assert!(matches!(
+            handle(&IdeKmMessage::KeyProg(key_index, key.clone())).unwrap(),
+            IdeKmMessage::KpAck(ack_index, IdeKmKpAckStatus::IdeKmKpAckSuccess) if ack_index == key_index
+        ));
+        let other_index = IdeKmKeyIndex {
+            stream_id: 2,
+            ..key_index
+        };
+        assert!(matches!(
+            handle(&IdeKmMessage::KeyProg(other_index, key.clone())).unwrap(),
+            IdeKmMessage::KpAck(_, IdeKmKpAckStatus::IdeKmKpAckUnsupportedValue)
+        ));
+        assert!(matches!(
+            handle(&IdeKmMessage::KSetGo(key_index)).unwrap(),
+            IdeKmMessage::KGoStopAck(ack_index) if ack_index == key_index
+        ));
+        assert_eq!(
+            handle(&IdeKmMessage::KSetStop(key_index)).err(),
+            Some(SPDM_STATUS_INVALID_STATE_LOCAL)
+        );
+        // responses are not requests
+        assert_eq!(
+            handle(&IdeKmMessage::KGoStopAck(key_index)).err(),
+            Some(SPDM_STATUS_INVALID_MSG_FIELD)
+        );
+
+        // TDISP request
+        let mut req_payload = IdeKmMessage::Query { port_index: 0 }
+            .to_req_payload()
+            .unwrap();
+        req_payload.vendor_defined_req_payload[0] = 0x01;
+        assert_eq!(
+            ide_km_request_handler(SESSION, &req_payload).err(),
+            Some(SPDM_STATUS_UNSUPPORTED_CAP)
+        );
+
+        // keys are not programmed in the clear nor during the handshake
+        let req_payload = IdeKmMessage::KeyProg(key_index, key)
+            .to_req_payload()
+            .unwrap();
+        assert_eq!(
+            ide_km_request_handler(None, &req_payload).err(),
+            Some(SPDM_STATUS_INVALID_STATE_PEER)
+        );
+        let handshaking = Some(VendorDefinedRequestSession {
+            session_id: 0xFFFE_FFFE,
+            session_state: SpdmSessionState::SpdmSessionHandshaking,
+        });
+        assert_eq!(
+            ide_km_request_handler(handshaking, &req_payload).err(),
+            Some(SPDM_STATUS_INVALID_STATE_PEER)
+        );
+        let req_payload = IdeKmMessage::KSetGo(key_index).to_req_payload().unwrap();
+        assert_eq!(
+            ide_km_request_handler(None, &req_payload).err(),
+            Some(SPDM_STATUS_INVALID_STATE_PEER)
+        );
+    }
+}
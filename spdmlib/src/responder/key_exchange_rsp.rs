@@ -7,7 +7,6 @@ use crate::common::session::SpdmSession;
 use crate::common::ManagedBuffer12Sign;
 use crate::common::SpdmCodec;
 use crate::common::SpdmConnectionState;
-use crate::common::SpdmOpaqueSupport;
 use crate::crypto;
 use crate::error::{
     SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_INVALID_MSG_FIELD,
@@ -18,15 +17,20 @@ use crate::responder::*;
 extern crate alloc;
 use crate::common::opaque::SpdmOpaqueStruct;
 use crate::message::*;
-use crate::secret;
 use alloc::boxed::Box;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_key_exchange(&mut self, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_key_exchange_response(bytes, &mut writer)?;
-        self.send_message(writer.used_slice())
+        let evicted = self.common.evict_session();
+        let res = self.send_response(None, |responder, writer| {
+            responder.write_spdm_key_exchange_response(bytes, writer)
+        });
+        if let Some(session_id) =
+            evicted.and_then(|evicted| self.common.complete_session_eviction(evicted))
+        {
+            self.event_context.end_session(session_id);
+        }
+        res
     }
 
     pub fn write_spdm_key_exchange_response(
@@ -74,14 +78,9 @@ impl<'a> ResponderContext<'a> {
                     == SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll)
             {
                 self.common.runtime_info.need_measurement_summary_hash = true;
-                let measurement_summary_hash_res =
-                    secret::measurement::generate_measurement_summary_hash(
-                        self.common.negotiate_info.spdm_version_sel,
-                        self.common.negotiate_info.base_hash_sel,
-                        self.common.negotiate_info.measurement_specification_sel,
-                        self.common.negotiate_info.measurement_hash_sel,
-                        key_exchange_req.measurement_summary_hash_type,
-                    );
+                let measurement_summary_hash_res = self.generate_measurement_summary_hash(
+                    key_exchange_req.measurement_summary_hash_type,
+                );
                 if measurement_summary_hash_res.is_none() {
                     self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
                     return Err(SPDM_STATUS_INVALID_MSG_FIELD);
@@ -115,46 +114,18 @@ impl<'a> ResponderContext<'a> {
                     self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
                     return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                 }
-                for index in 0..secured_message_version_list.version_count as usize {
-                    for local_version in self.common.config_info.secure_spdm_version {
-                        if secured_message_version_list.versions_list[index]
-                            .get_secure_spdm_version()
-                            == local_version
-                        {
-                            if self.common.negotiate_info.spdm_version_sel.get_u8()
-                                < SpdmVersion::SpdmVersion12.get_u8()
-                            {
-                                return_opaque.data_size =
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0277
-                                        .len() as u16;
-                                return_opaque.data[..(return_opaque.data_size as usize)]
-                                    .copy_from_slice(
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0277
-                                        .as_ref(),
-                                );
-                                return_opaque.data[return_opaque.data_size as usize - 1] =
-                                    local_version;
-                            } else if self.common.negotiate_info.opaque_data_support
-                                == SpdmOpaqueSupport::OPAQUE_DATA_FMT1
-                            {
-                                return_opaque.data_size =
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0274_FMT1
-                                        .len() as u16;
-                                return_opaque.data[..(return_opaque.data_size as usize)]
-                                    .copy_from_slice(
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0274_FMT1
-                                        .as_ref(),
-                                );
-                                return_opaque.data[return_opaque.data_size as usize - 1] =
-                                    local_version;
-                            } else {
-                                self.write_spdm_error(
-                                    SpdmErrorCode::SpdmErrorUnsupportedRequest,
-                                    0,
-                                    writer,
-                                );
-                                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
-                            }
+                if let Some(secure_spdm_version) =
+                    self.select_secure_spdm_version(&secured_message_version_list)
+                {
+                    match self.secure_spdm_version_selection_opaque(secure_spdm_version) {
+                        Some(opaque) => return_opaque = opaque,
+                        None => {
+                            self.write_spdm_error(
+                                SpdmErrorCode::SpdmErrorUnsupportedRequest,
+                                0,
+                                writer,
+                            );
+                            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                         }
                     }
                 }
@@ -167,11 +138,31 @@ impl<'a> ResponderContext<'a> {
 
         let key_exchange_req = key_exchange_req.unwrap();
         let slot_id = key_exchange_req.slot_id as usize;
-        if slot_id >= SPDM_MAX_SLOT_NUMBER {
+        if !self.common.is_rsp_slot_id_valid(key_exchange_req.slot_id) {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        if self
+            .common
+            .get_local_cert_chain_data(key_exchange_req.slot_id)
+            .is_none()
+        {
             self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
             return Err(SPDM_STATUS_INVALID_MSG_FIELD);
         }
-        if self.common.provision_info.my_cert_chain[slot_id].is_none() {
+        if self.is_slot_quarantined(slot_id) {
+            // The error is sent to the requester.
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+            return Ok(());
+        }
+        if self
+            .common
+            .receive_opaque_elements(
+                SpdmRequestResponseCode::SpdmRequestKeyExchange,
+                &key_exchange_req.opaque,
+            )
+            .is_err()
+        {
             self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
             return Err(SPDM_STATUS_INVALID_MSG_FIELD);
         }
@@ -229,8 +220,22 @@ impl<'a> ResponderContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
+        // A requester with a provisioned public key has no cert chain to
+        // retrieve, it signs FINISH with ReqSlotID 0xF right away.
+        let req_pub_key_id = self
+            .common
+            .negotiate_info
+            .req_capabilities_sel
+            .contains(SpdmRequestCapabilityFlags::PUB_KEY_ID_CAP);
         #[cfg(feature = "mut-auth")]
-        let mut_auth_req = SpdmKeyExchangeMutAuthAttributes::MUT_AUTH_REQ_WITH_GET_DIGESTS;
+        let mut_auth_req = if req_pub_key_id {
+            self.common
+                .runtime_info
+                .set_peer_used_cert_chain_slot_id(SPDM_PUB_KEY_SLOT_ID);
+            SpdmKeyExchangeMutAuthAttributes::MUT_AUTH_REQ
+        } else {
+            SpdmKeyExchangeMutAuthAttributes::MUT_AUTH_REQ_WITH_GET_DIGESTS
+        };
         #[cfg(not(feature = "mut-auth"))]
         let mut_auth_req = SpdmKeyExchangeMutAuthAttributes::empty();
 
@@ -270,6 +275,19 @@ impl<'a> ResponderContext<'a> {
                 .contains(SpdmResponseCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP);
         info!("in_clear_text {:?}\n", in_clear_text);
 
+        let mut response_opaque = return_opaque.clone();
+        if self
+            .common
+            .append_provided_opaque_elements(
+                SpdmRequestResponseCode::SpdmResponseKeyExchangeRsp,
+                &mut response_opaque,
+            )
+            .is_err()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+            return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
+        }
+
         info!("send spdm key_exchange rsp\n");
 
         // prepare response
@@ -282,11 +300,15 @@ impl<'a> ResponderContext<'a> {
                 heartbeat_period: self.common.config_info.heartbeat_period,
                 rsp_session_id,
                 mut_auth_req,
-                req_slot_id: 0x0,
+                req_slot_id: if req_pub_key_id && !mut_auth_req.is_empty() {
+                    0xF
+                } else {
+                    0x0
+                },
                 random: SpdmRandomStruct { data: random },
                 exchange,
                 measurement_summary_hash,
-                opaque: return_opaque.clone(),
+                opaque: response_opaque,
                 signature: SpdmSignatureStruct {
                     data_size: self.common.negotiate_info.base_asym_sel.get_size(),
                     data: [0xbb; SPDM_MAX_ASYM_KEY_SIZE],
@@ -337,11 +359,21 @@ impl<'a> ResponderContext<'a> {
             .unwrap();
 
         let signature = self.generate_key_exchange_rsp_signature(slot_id as u8, session);
-        if signature.is_err() {
-            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
-            return Err(SPDM_STATUS_CRYPTO_ERROR);
-        }
-        let signature = signature.unwrap();
+        let signature = match self.check_signature_result(
+            SpdmRequestResponseCode::SpdmRequestKeyExchange,
+            slot_id,
+            signature,
+            writer,
+        ) {
+            Some(signature) => signature,
+            None => {
+                if let Some(session) = self.common.get_session_via_id(session_id) {
+                    let _ = session.teardown(session_id);
+                }
+                // The error of the sign failure policy is sent to the requester.
+                return Ok(());
+            }
+        };
 
         if self
             .common
@@ -454,7 +486,12 @@ impl<'a> ResponderContext<'a> {
         {
             message_sign.reset_message();
             message_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_2)
@@ -470,12 +507,14 @@ impl<'a> ResponderContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
-        crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            message_sign.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+        self.common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                message_sign.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
     }
 
     #[cfg(not(feature = "hashed-transcript-data"))]
@@ -503,7 +542,12 @@ impl<'a> ResponderContext<'a> {
         {
             message.reset_message();
             message
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_2)
@@ -516,11 +560,13 @@ impl<'a> ResponderContext<'a> {
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         }
 
-        crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            message.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+        self.common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                message.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
     }
 }
@@ -0,0 +1,292 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::SpdmCodec;
+use crate::common::SpdmConnectionState;
+use crate::error::SpdmResult;
+use crate::message::*;
+use crate::protocol::*;
+use crate::responder::*;
+use crate::secret::SpdmSetKeyPairInfoResult;
+
+impl<'a> ResponderContext<'a> {
+    pub fn handle_spdm_get_key_pair_info(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_key_pair_info_response(session_id, bytes, writer);
+            Ok(())
+        })
+    }
+
+    fn write_spdm_key_pair_info_response(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+        writer: &mut Writer,
+    ) {
+        if self.common.runtime_info.get_connection_state().get_u8()
+            < SpdmConnectionState::SpdmConnectionNegotiated.get_u8()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnexpectedRequest, 0, writer);
+            return;
+        }
+        if !self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::GET_KEY_PAIR_INFO_CAP)
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnsupportedRequest, 0, writer);
+            return;
+        }
+
+        let mut reader = Reader::init(bytes);
+        let message_header = SpdmMessageHeader::read(&mut reader);
+        if let Some(message_header) = message_header {
+            if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorVersionMismatch, 0, writer);
+                return;
+            }
+        } else {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestGetKeyPairInfo,
+            session_id,
+        );
+
+        let get_key_pair_info =
+            SpdmGetKeyPairInfoRequestPayload::spdm_read(&mut self.common, &mut reader);
+        let key_pair_id = if let Some(get_key_pair_info) = get_key_pair_info {
+            debug!(
+                "!!! get_key_pair_info : {:02x?}\n",
+                get_key_pair_info.key_pair_id
+            );
+            get_key_pair_info.key_pair_id
+        } else {
+            error!("!!! get_key_pair_info : fail !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        };
+
+        let key_pair_info = match self.common.provision_info.get_key_pair_info(key_pair_id) {
+            Some(key_pair_info) => key_pair_info.clone(),
+            None => {
+                error!("!!! get_key_pair_info : unknown key pair !!!\n");
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+                return;
+            }
+        };
+
+        info!("send spdm key_pair_info\n");
+        let response = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseKeyPairInfo,
+            },
+            payload: SpdmMessagePayload::SpdmKeyPairInfoResponse(SpdmKeyPairInfoResponsePayload {
+                total_key_pairs: self.common.provision_info.get_total_key_pairs(),
+                key_pair_id,
+                key_pair_info,
+            }),
+        };
+        if response.spdm_encode(&mut self.common, writer).is_err() {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+        }
+    }
+
+    pub fn handle_spdm_set_key_pair_info(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_set_key_pair_info_response(session_id, bytes, writer);
+            Ok(())
+        })
+    }
+
+    fn write_spdm_set_key_pair_info_response(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+        writer: &mut Writer,
+    ) {
+        if self.common.runtime_info.get_connection_state().get_u8()
+            < SpdmConnectionState::SpdmConnectionNegotiated.get_u8()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnexpectedRequest, 0, writer);
+            return;
+        }
+        if !self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::SET_KEY_PAIR_INFO_CAP)
+            || self.common.secret_provider.key_pair.is_none()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnsupportedRequest, 0, writer);
+            return;
+        }
+
+        let mut reader = Reader::init(bytes);
+        let message_header = SpdmMessageHeader::read(&mut reader);
+        if let Some(message_header) = message_header {
+            if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorVersionMismatch, 0, writer);
+                return;
+            }
+        } else {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestSetKeyPairInfo,
+            session_id,
+        );
+
+        let set_key_pair_info =
+            SpdmSetKeyPairInfoRequestPayload::spdm_read(&mut self.common, &mut reader);
+        let set_key_pair_info = if let Some(set_key_pair_info) = set_key_pair_info {
+            debug!(
+                "!!! set_key_pair_info : {:02x?} {:02x?}\n",
+                set_key_pair_info.operation, set_key_pair_info.key_pair_id
+            );
+            set_key_pair_info
+        } else {
+            error!("!!! set_key_pair_info : fail !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        };
+
+        let mut key_pair_info = match self.get_desired_key_pair_info(&set_key_pair_info) {
+            Some(key_pair_info) => key_pair_info,
+            None => {
+                error!("!!! set_key_pair_info : invalid request !!!\n");
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+                return;
+            }
+        };
+
+        match self.common.secret_provider.set_key_pair_info(
+            self.common.negotiate_info.spdm_version_sel,
+            set_key_pair_info.operation,
+            set_key_pair_info.key_pair_id,
+            &mut key_pair_info,
+        ) {
+            SpdmSetKeyPairInfoResult::Applied => {
+                self.common.provision_info.key_pair_info
+                    [set_key_pair_info.key_pair_id as usize - 1] = Some(key_pair_info);
+            }
+            SpdmSetKeyPairInfoResult::ResetRequired => {
+                info!("!!! set_key_pair_info : reset required !!!\n");
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorResetRequired, 0, writer);
+                return;
+            }
+            SpdmSetKeyPairInfoResult::Failed => {
+                error!("!!! set_key_pair_info : apply fail !!!\n");
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                return;
+            }
+        }
+
+        info!("send spdm set_key_pair_info_ack\n");
+        let response = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseSetKeyPairInfoAck,
+            },
+            payload: SpdmMessagePayload::SpdmSetKeyPairInfoAckResponse(
+                SpdmSetKeyPairInfoAckResponsePayload {},
+            ),
+        };
+        if response.spdm_encode(&mut self.common, writer).is_err() {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+        }
+    }
+
+    /// Check the request against the capabilities of the key pair and
+    /// return the key pair info to apply, None if the request is invalid.
+    /// An empty desired key usage or asym algo keeps the current one.
+    fn get_desired_key_pair_info(
+        &self,
+        set_key_pair_info: &SpdmSetKeyPairInfoRequestPayload,
+    ) -> Option<SpdmKeyPairInfo> {
+        let key_pair_id = set_key_pair_info.key_pair_id;
+        let mut key_pair_info = self
+            .common
+            .provision_info
+            .get_key_pair_info(key_pair_id)?
+            .clone();
+        let capabilities = key_pair_info.capabilities;
+
+        match set_key_pair_info.operation {
+            SpdmKeyPairInfoOperation::SpdmKeyPairInfoErase => {
+                if !capabilities.contains(SpdmKeyPairCapabilities::ERASABLE_CAP) {
+                    return None;
+                }
+                key_pair_info.assoc_cert_slot_mask = 0;
+                return Some(key_pair_info);
+            }
+            SpdmKeyPairInfoOperation::SpdmKeyPairInfoGenerate => {
+                if !capabilities.contains(SpdmKeyPairCapabilities::GEN_KEY_CAP) {
+                    return None;
+                }
+            }
+            SpdmKeyPairInfoOperation::SpdmKeyPairInfoChange => {}
+            SpdmKeyPairInfoOperation::Unknown(_) => return None,
+        }
+
+        let desired_key_usage = set_key_pair_info.desired_key_usage;
+        if !desired_key_usage.is_empty() && desired_key_usage != key_pair_info.current_key_usage {
+            if !capabilities.contains(SpdmKeyPairCapabilities::KEY_USAGE_CAP)
+                || !key_pair_info
+                    .key_usage_capabilities
+                    .contains(desired_key_usage)
+            {
+                return None;
+            }
+            key_pair_info.current_key_usage = desired_key_usage;
+        }
+
+        let desired_asym_algo = set_key_pair_info.desired_asym_algo;
+        if !desired_asym_algo.is_empty() && desired_asym_algo != key_pair_info.current_asym_algo {
+            if !capabilities.contains(SpdmKeyPairCapabilities::ASYM_ALGO_CAP)
+                || desired_asym_algo.bits().count_ones() != 1
+                || !key_pair_info
+                    .asym_algo_capabilities
+                    .contains(desired_asym_algo)
+            {
+                return None;
+            }
+            key_pair_info.current_asym_algo = desired_asym_algo;
+        }
+
+        let desired_assoc_cert_slot_mask = set_key_pair_info.desired_assoc_cert_slot_mask;
+        if desired_assoc_cert_slot_mask != key_pair_info.assoc_cert_slot_mask {
+            if !capabilities.contains(SpdmKeyPairCapabilities::CERT_ASSOC_CAP) {
+                return None;
+            }
+            // A slot belongs to one key pair at most.
+            for slot_id in 0..SPDM_MAX_SLOT_NUMBER as u8 {
+                if desired_assoc_cert_slot_mask & (1 << slot_id) == 0 {
+                    continue;
+                }
+                match self.common.provision_info.get_slot_key_pair_id(slot_id) {
+                    Some(assoc_key_pair_id) if assoc_key_pair_id != key_pair_id => return None,
+                    _ => {}
+                }
+            }
+            key_pair_info.assoc_cert_slot_mask = desired_assoc_cert_slot_mask;
+        }
+
+        Some(key_pair_info)
+    }
+}
@@ -9,10 +9,13 @@ use crate::responder::*;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_key_update(&mut self, session_id: u32, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+        let mut send_buffer = self.common.alloc_message_buffer();
         let mut writer = Writer::init(&mut send_buffer);
         self.write_spdm_key_update_response(session_id, bytes, &mut writer);
-        self.send_secured_message(session_id, writer.used_slice(), false)
+        let used = writer.used();
+        let result = self.send_secured_message(session_id, &send_buffer[..used], false);
+        self.common.free_buffer(send_buffer);
+        result
     }
 
     pub fn write_spdm_key_update_response(
@@ -0,0 +1,193 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::crypto;
+use crate::message::SpdmMeasurementOperation;
+use crate::protocol::*;
+use crate::responder::*;
+use codec::u24;
+
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// Measurements of one responder context, set with
+/// ResponderContext::set_measurement_provider. Without a provider they
+/// come from common.secret_provider or the secret::measurement callbacks.
+pub trait MeasurementProvider {
+    /// Indices of the measurement blocks, in increasing order.
+    fn get_measurement_indices(&mut self) -> Vec<u8>;
+
+    /// Measurement block at index, None if there is none. raw_bit_stream
+    /// asks for the raw value rather than its digest, a provider which
+    /// cannot reveal it returns the digest. fresh asks to measure again,
+    /// otherwise the value measured at the last reset is returned.
+    fn get_measurement_block(
+        &mut self,
+        index: u8,
+        measurement_specification: SpdmMeasurementSpecification,
+        measurement_hash_algo: SpdmMeasurementHashAlgo,
+        raw_bit_stream: bool,
+        fresh: bool,
+    ) -> Option<SpdmMeasurementBlockStructure>;
+
+    /// Summary hash of CHALLENGE_AUTH, KEY_EXCHANGE_RSP and PSK_EXCHANGE_RSP.
+    /// By default the hash of all the blocks for both TCB and All, a
+    /// provider telling the TCB blocks apart overrides it.
+    fn generate_measurement_summary_hash(
+        &mut self,
+        base_hash_algo: SpdmBaseHashAlgo,
+        measurement_specification: SpdmMeasurementSpecification,
+        measurement_hash_algo: SpdmMeasurementHashAlgo,
+        measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
+    ) -> Option<SpdmDigestStruct> {
+        if measurement_summary_hash_type
+            == SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone
+        {
+            return None;
+        }
+        let mut blocks = Vec::new();
+        for index in self.get_measurement_indices() {
+            let block = self.get_measurement_block(
+                index,
+                measurement_specification,
+                measurement_hash_algo,
+                false,
+                false,
+            )?;
+            let mut buffer = [0u8; 4 + config::MAX_SPDM_MEASUREMENT_VALUE_LEN + 3];
+            let mut writer = Writer::init(&mut buffer);
+            block.encode(&mut writer).ok()?;
+            blocks.extend_from_slice(writer.used_slice());
+        }
+        crypto::hash::hash_all(base_hash_algo, &blocks)
+    }
+}
+
+impl<'a> ResponderContext<'a> {
+    pub fn set_measurement_provider(
+        &mut self,
+        measurement_provider: Option<Box<dyn MeasurementProvider>>,
+    ) {
+        self.measurement_provider = measurement_provider;
+    }
+
+    /// Measurement record for measurement_index, a SpdmMeasurementOperation
+    /// or a block index, from the measurement provider if any.
+    pub(crate) fn measurement_collection(
+        &mut self,
+        measurement_index: usize,
+        raw_bit_stream: bool,
+    ) -> Option<SpdmMeasurementRecordStructure> {
+        let spdm_version = self.common.negotiate_info.spdm_version_sel;
+        let measurement_specification = self.common.negotiate_info.measurement_specification_sel;
+        let measurement_hash_algo = self.common.negotiate_info.measurement_hash_sel;
+        let fresh = self
+            .common
+            .config_info
+            .rsp_capabilities
+            .contains(SpdmResponseCapabilityFlags::MEAS_FRESH_CAP);
+
+        let measurement_provider = match self.measurement_provider.as_mut() {
+            Some(measurement_provider) => measurement_provider,
+            None => {
+                return self.common.secret_provider.measurement_collection(
+                    spdm_version,
+                    measurement_specification,
+                    measurement_hash_algo,
+                    measurement_index,
+                )
+            }
+        };
+
+        let indices = measurement_provider.get_measurement_indices();
+        let mut measurement_record = SpdmMeasurementRecordStructure::default();
+        if measurement_index
+            == SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber.get_u8() as usize
+        {
+            measurement_record.number_of_blocks = indices.len() as u8;
+            return Some(measurement_record);
+        }
+        let indices = if measurement_index
+            == SpdmMeasurementOperation::SpdmMeasurementRequestAll.get_u8() as usize
+        {
+            indices
+        } else if indices.contains(&(measurement_index as u8)) {
+            alloc::vec![measurement_index as u8]
+        } else {
+            return None;
+        };
+
+        // Only the configured indices are returned as raw bit stream, unless
+        // no measurement hash algorithm was negotiated.
+        let raw_bit_stream_only = measurement_hash_algo == SpdmMeasurementHashAlgo::RAW_BIT_STREAM;
+        let mut writer = Writer::init(&mut measurement_record.measurement_record_data);
+        for index in indices.iter() {
+            let raw_bit_stream = raw_bit_stream_only
+                || (raw_bit_stream
+                    && self
+                        .common
+                        .config_info
+                        .is_raw_bit_stream_measurement_index(*index));
+            let mut block = measurement_provider.get_measurement_block(
+                *index,
+                measurement_specification,
+                measurement_hash_algo,
+                raw_bit_stream,
+                fresh,
+            )?;
+            // A raw manifest too large for the record is returned as digest.
+            if raw_bit_stream
+                && !raw_bit_stream_only
+                && 4 + block.measurement_size as usize > writer.left()
+            {
+                block = measurement_provider.get_measurement_block(
+                    *index,
+                    measurement_specification,
+                    measurement_hash_algo,
+                    false,
+                    fresh,
+                )?;
+            }
+            if block.index != *index || block.measurement_size != 3 + block.measurement.value_size {
+                error!("!!! measurement block {} is malformed !!!\n", index);
+                return None;
+            }
+            block.encode(&mut writer).ok()?;
+        }
+        let used = writer.used();
+        measurement_record.number_of_blocks = indices.len() as u8;
+        measurement_record.measurement_record_length = u24::new(used as u32);
+        Some(measurement_record)
+    }
+
+    /// Measurement summary hash, from the measurement provider if any.
+    pub(crate) fn generate_measurement_summary_hash(
+        &mut self,
+        measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
+    ) -> Option<SpdmDigestStruct> {
+        let spdm_version = self.common.negotiate_info.spdm_version_sel;
+        let base_hash_algo = self.common.negotiate_info.base_hash_sel;
+        let measurement_specification = self.common.negotiate_info.measurement_specification_sel;
+        let measurement_hash_algo = self.common.negotiate_info.measurement_hash_sel;
+        match self.measurement_provider.as_mut() {
+            Some(measurement_provider) => measurement_provider.generate_measurement_summary_hash(
+                base_hash_algo,
+                measurement_specification,
+                measurement_hash_algo,
+                measurement_summary_hash_type,
+            ),
+            None => self
+                .common
+                .secret_provider
+                .generate_measurement_summary_hash(
+                    spdm_version,
+                    base_hash_algo,
+                    measurement_specification,
+                    measurement_hash_algo,
+                    measurement_summary_hash_type,
+                ),
+        }
+    }
+}
@@ -21,17 +21,13 @@ use crate::error::SPDM_STATUS_INVALID_STATE_LOCAL;
 use crate::message::*;
 use crate::protocol::*;
 use crate::responder::*;
-use crate::secret;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_measurement(&mut self, session_id: Option<u32>, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_measurement_response(session_id, bytes, &mut writer);
-        match session_id {
-            None => self.send_message(writer.used_slice()),
-            Some(session_id) => self.send_secured_message(session_id, writer.used_slice(), false),
-        }
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_measurement_response(session_id, bytes, writer);
+            Ok(())
+        })
     }
 
     pub fn write_spdm_measurement_response(
@@ -83,14 +79,22 @@ impl<'a> ResponderContext<'a> {
         {
             self.common.runtime_info.need_measurement_signature = true;
 
-            if slot_id >= SPDM_MAX_SLOT_NUMBER {
+            if !self.common.is_rsp_slot_id_valid(get_measurements.slot_id) {
                 self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
                 return;
             }
-            if self.common.provision_info.my_cert_chain[slot_id].is_none() {
+            if self
+                .common
+                .get_local_cert_chain_data(get_measurements.slot_id)
+                .is_none()
+            {
                 self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
                 return;
             }
+            if self.is_slot_quarantined(slot_id) {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                return;
+            }
         } else {
             self.common.runtime_info.need_measurement_signature = false;
 
@@ -117,14 +121,19 @@ impl<'a> ResponderContext<'a> {
             return;
         }
 
-        let real_measurement_block_count = secret::measurement::measurement_collection(
-            spdm_version_sel,
-            measurement_specification_sel,
-            measurement_hash_sel,
+        let raw_bit_stream = get_measurements
+            .measurement_attributes
+            .contains(SpdmMeasurementAttributes::RAW_BIT_STREAM_REQUESTED);
+        let real_measurement_block_count = match self.measurement_collection(
             SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber.get_u8() as usize,
-        )
-        .unwrap()
-        .number_of_blocks;
+            raw_bit_stream,
+        ) {
+            Some(measurement_record) => measurement_record.number_of_blocks,
+            None => {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                return;
+            }
+        };
 
         let number_of_measurement: u8 = if get_measurements.measurement_operation
             == SpdmMeasurementOperation::SpdmMeasurementRequestAll
@@ -138,27 +147,31 @@ impl<'a> ResponderContext<'a> {
         let measurement_record = if get_measurements.measurement_operation
             == SpdmMeasurementOperation::SpdmMeasurementRequestAll
         {
-            secret::measurement::measurement_collection(
-                spdm_version_sel,
-                measurement_specification_sel,
-                measurement_hash_sel,
+            match self.measurement_collection(
                 SpdmMeasurementOperation::SpdmMeasurementRequestAll.get_u8() as usize,
-            )
-            .unwrap()
+                raw_bit_stream,
+            ) {
+                Some(measurement_record) => measurement_record,
+                None => {
+                    self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                    return;
+                }
+            }
         } else if let SpdmMeasurementOperation::Unknown(index) =
             get_measurements.measurement_operation
         {
-            if index > real_measurement_block_count {
+            // the indices of a measurement provider need not be contiguous
+            if self.measurement_provider.is_none() && index > real_measurement_block_count {
                 self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
                 return;
             }
-            secret::measurement::measurement_collection(
-                spdm_version_sel,
-                measurement_specification_sel,
-                measurement_hash_sel,
-                index as usize,
-            )
-            .unwrap()
+            match self.measurement_collection(index as usize, raw_bit_stream) {
+                Some(measurement_record) => measurement_record,
+                None => {
+                    self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+                    return;
+                }
+            }
         } else {
             SpdmMeasurementRecordStructure::default()
         };
@@ -178,6 +191,22 @@ impl<'a> ResponderContext<'a> {
             return;
         }
 
+        let mut opaque = SpdmOpaqueStruct {
+            data_size: 0,
+            data: [0u8; MAX_SPDM_OPAQUE_SIZE],
+        };
+        if self
+            .common
+            .append_provided_opaque_elements(
+                SpdmRequestResponseCode::SpdmResponseMeasurements,
+                &mut opaque,
+            )
+            .is_err()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+            return;
+        }
+
         info!("send spdm measurement\n");
 
         let response = SpdmMessage {
@@ -192,14 +221,12 @@ impl<'a> ResponderContext<'a> {
                     content_changed,
                     measurement_record,
                     nonce: SpdmNonceStruct { data: nonce },
-                    opaque: SpdmOpaqueStruct {
-                        data_size: 0,
-                        data: [0u8; MAX_SPDM_OPAQUE_SIZE],
-                    },
+                    opaque,
                     signature: SpdmSignatureStruct {
                         data_size: signature_size,
                         data: [0x60u8; SPDM_MAX_ASYM_KEY_SIZE],
                     },
+                    requester_context: get_measurements.requester_context,
                 },
             ),
         };
@@ -229,11 +256,18 @@ impl<'a> ResponderContext<'a> {
             }
 
             let signature = self.generate_measurement_signature(session_id);
-            if signature.is_err() {
-                self.send_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0);
-                return;
-            }
-            let signature = signature.unwrap();
+            let signature = match self.check_signature_result(
+                SpdmRequestResponseCode::SpdmRequestGetMeasurements,
+                slot_id,
+                signature,
+                writer,
+            ) {
+                Some(signature) => signature,
+                None => {
+                    self.common.reset_message_m(session_id);
+                    return;
+                }
+            };
             // patch the message before send
             writer.mut_used_slice()[(used - base_asym_size)..used]
                 .copy_from_slice(signature.as_ref());
@@ -284,7 +318,12 @@ impl<'a> ResponderContext<'a> {
         {
             message_sign.reset_message();
             message_sign
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_sign
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_6)
@@ -300,12 +339,14 @@ impl<'a> ResponderContext<'a> {
             return Err(SPDM_STATUS_INVALID_STATE_LOCAL);
         }
 
-        crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            message_sign.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+        self.common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                message_sign.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
     }
 
     #[cfg(not(feature = "hashed-transcript-data"))]
@@ -356,7 +397,12 @@ impl<'a> ResponderContext<'a> {
         {
             message_l1l2.reset_message();
             message_l1l2
-                .append_message(&SPDM_VERSION_1_2_SIGNING_PREFIX_CONTEXT)
+                .append_message(
+                    self.common
+                        .negotiate_info
+                        .spdm_version_sel
+                        .get_signing_prefix_context(),
+                )
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
             message_l1l2
                 .append_message(&SPDM_VERSION_1_2_SIGNING_CONTEXT_ZEROPAD_6)
@@ -369,11 +415,13 @@ impl<'a> ResponderContext<'a> {
                 .ok_or(SPDM_STATUS_BUFFER_FULL)?;
         }
 
-        crate::secret::asym_sign::sign(
-            self.common.negotiate_info.base_hash_sel,
-            self.common.negotiate_info.base_asym_sel,
-            message_l1l2.as_ref(),
-        )
-        .ok_or(SPDM_STATUS_CRYPTO_ERROR)
+        self.common
+            .secret_provider
+            .sign(
+                self.common.negotiate_info.base_hash_sel,
+                self.common.negotiate_info.base_asym_sel,
+                message_l1l2.as_ref(),
+            )
+            .ok_or(SPDM_STATUS_CRYPTO_ERROR)
     }
 }
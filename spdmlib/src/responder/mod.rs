@@ -5,10 +5,15 @@
 mod context;
 
 mod algorithm_rsp;
+mod async_message_rsp;
 mod capability_rsp;
 mod certificate_rsp;
 mod challenge_rsp;
+mod chunk_send_rsp;
+mod csr_rsp;
+mod deferred_rsp;
 mod digest_rsp;
+pub mod dispatch;
 #[cfg(feature = "mut-auth")]
 mod encap_get_certificate;
 #[cfg(feature = "mut-auth")]
@@ -16,21 +21,34 @@ mod encap_get_digest;
 #[cfg(feature = "mut-auth")]
 mod encap_rsp;
 mod end_session_rsp;
+mod event_rsp;
 mod finish_rsp;
 mod heartbeat_rsp;
 mod key_exchange_rsp;
+mod key_pair_info_rsp;
 mod key_update_rsp;
 mod measurement_rsp;
 mod psk_exchange_rsp;
 mod psk_finish_rsp;
+mod set_certificate_rsp;
 mod version_rsp;
 
 mod error_rsp;
 mod vendor_rsp;
 
+mod alias_cert;
 pub mod app_message_handler;
+mod attestation_report;
+pub mod cxl_ide_km_rsp;
+pub mod ide_km_rsp;
+pub mod measurement_provider;
+pub mod request_policy;
+pub mod secure_version;
+pub mod sign_failure;
 
 pub use context::ResponderContext;
+pub use deferred_rsp::{SpdmResponseReadiness, SpdmResponseReadyHandler, SPDM_DEFERRABLE_REQUESTS};
+pub use measurement_provider::MeasurementProvider;
 
 use crate::config;
 use codec::{Codec, Reader, Writer};
@@ -5,7 +5,6 @@
 use crate::common::opaque::SpdmOpaqueStruct;
 use crate::common::SpdmCodec;
 use crate::common::SpdmConnectionState;
-use crate::common::SpdmOpaqueSupport;
 use crate::common::INVALID_SLOT;
 use crate::crypto;
 use crate::error::SpdmResult;
@@ -18,15 +17,20 @@ use crate::protocol::*;
 use crate::responder::*;
 use config::MAX_SPDM_PSK_CONTEXT_SIZE;
 extern crate alloc;
-use crate::secret;
 use alloc::boxed::Box;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_psk_exchange(&mut self, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_psk_exchange_response(bytes, &mut writer)?;
-        self.send_message(writer.used_slice())
+        let evicted = self.common.evict_session();
+        let res = self.send_response(None, |responder, writer| {
+            responder.write_spdm_psk_exchange_response(bytes, writer)
+        });
+        if let Some(session_id) =
+            evicted.and_then(|evicted| self.common.complete_session_eviction(evicted))
+        {
+            self.event_context.end_session(session_id);
+        }
+        res
     }
 
     pub fn write_spdm_psk_exchange_response(
@@ -75,14 +79,9 @@ impl<'a> ResponderContext<'a> {
                     == SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll)
             {
                 self.common.runtime_info.need_measurement_summary_hash = true;
-                let measurement_summary_hash_res =
-                    secret::measurement::generate_measurement_summary_hash(
-                        self.common.negotiate_info.spdm_version_sel,
-                        self.common.negotiate_info.base_hash_sel,
-                        self.common.negotiate_info.measurement_specification_sel,
-                        self.common.negotiate_info.measurement_hash_sel,
-                        psk_exchange_req.measurement_summary_hash_type,
-                    );
+                let measurement_summary_hash_res = self.generate_measurement_summary_hash(
+                    psk_exchange_req.measurement_summary_hash_type,
+                );
                 if measurement_summary_hash_res.is_none() {
                     self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
                     return Err(SPDM_STATUS_CRYPTO_ERROR);
@@ -109,46 +108,18 @@ impl<'a> ResponderContext<'a> {
                     self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
                     return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                 }
-                for index in 0..secured_message_version_list.version_count as usize {
-                    for local_version in self.common.config_info.secure_spdm_version {
-                        if secured_message_version_list.versions_list[index]
-                            .get_secure_spdm_version()
-                            == local_version
-                        {
-                            if self.common.negotiate_info.spdm_version_sel.get_u8()
-                                < SpdmVersion::SpdmVersion12.get_u8()
-                            {
-                                return_opaque.data_size =
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0277
-                                        .len() as u16;
-                                return_opaque.data[..(return_opaque.data_size as usize)]
-                                    .copy_from_slice(
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0277
-                                        .as_ref(),
-                                );
-                                return_opaque.data[return_opaque.data_size as usize - 1] =
-                                    local_version;
-                            } else if self.common.negotiate_info.opaque_data_support
-                                == SpdmOpaqueSupport::OPAQUE_DATA_FMT1
-                            {
-                                return_opaque.data_size =
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0274_FMT1
-                                        .len() as u16;
-                                return_opaque.data[..(return_opaque.data_size as usize)]
-                                    .copy_from_slice(
-                                    crate::common::opaque::RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0274_FMT1
-                                        .as_ref(),
-                                );
-                                return_opaque.data[return_opaque.data_size as usize - 1] =
-                                    local_version;
-                            } else {
-                                self.write_spdm_error(
-                                    SpdmErrorCode::SpdmErrorUnsupportedRequest,
-                                    0,
-                                    writer,
-                                );
-                                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
-                            }
+                if let Some(secure_spdm_version) =
+                    self.select_secure_spdm_version(&secured_message_version_list)
+                {
+                    match self.secure_spdm_version_selection_opaque(secure_spdm_version) {
+                        Some(opaque) => return_opaque = opaque,
+                        None => {
+                            self.write_spdm_error(
+                                SpdmErrorCode::SpdmErrorUnsupportedRequest,
+                                0,
+                                writer,
+                            );
+                            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
                         }
                     }
                 }
@@ -195,6 +166,7 @@ impl<'a> ResponderContext<'a> {
 
         let spdm_version_sel = self.common.negotiate_info.spdm_version_sel;
         let message_a = self.common.runtime_info.message_a.clone();
+        let secret_psk = self.common.secret_provider.psk.clone();
 
         let session = self.common.get_next_avaiable_session();
         if session.is_none() {
@@ -208,6 +180,7 @@ impl<'a> ResponderContext<'a> {
             ((rsp_session_id as u32) << 16) + psk_exchange_req.unwrap().req_session_id as u32;
         session.setup(session_id).unwrap();
         session.set_use_psk(true);
+        session.set_secret_psk(secret_psk);
 
         session.set_crypto_param(hash_algo, dhe_algo, aead_algo, key_schedule_algo);
         session.set_transport_param(sequence_number_count, max_random_count);
@@ -0,0 +1,590 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::session::SpdmSessionState;
+use crate::message::{SpdmErrorCode, SpdmRequestResponseCode};
+use crate::protocol::SpdmVersion;
+
+bitflags! {
+    /// Session states in which a request is processed.
+    #[derive(Default)]
+    pub struct SpdmRequestStates: u8 {
+        const OUT_OF_SESSION = 0b0000_0001;
+        const HANDSHAKING = 0b0000_0010;
+        const ESTABLISHED = 0b0000_0100;
+    }
+}
+
+impl SpdmRequestStates {
+    pub fn from_session_state(session_state: SpdmSessionState) -> Self {
+        match session_state {
+            SpdmSessionState::SpdmSessionNotStarted => SpdmRequestStates::OUT_OF_SESSION,
+            SpdmSessionState::SpdmSessionHandshaking => SpdmRequestStates::HANDSHAKING,
+            SpdmSessionState::SpdmSessionEstablished => SpdmRequestStates::ESTABLISHED,
+            SpdmSessionState::Unknown(_) => SpdmRequestStates::empty(),
+        }
+    }
+}
+
+/// One row of the request state table.
+pub struct SpdmRequestStateRule {
+    pub request_response_code: SpdmRequestResponseCode,
+    pub allowed_states: SpdmRequestStates,
+}
+
+macro_rules! state_rule {
+    ($code:ident, $states:expr) => {
+        SpdmRequestStateRule {
+            request_response_code: SpdmRequestResponseCode::$code,
+            allowed_states: $states,
+        }
+    };
+}
+
+const OUT_OF_SESSION: SpdmRequestStates = SpdmRequestStates::OUT_OF_SESSION;
+const HANDSHAKING: SpdmRequestStates = SpdmRequestStates::HANDSHAKING;
+const ESTABLISHED: SpdmRequestStates = SpdmRequestStates::ESTABLISHED;
+
+/// Session states each request is accepted in.
+/// Reference: DSP0274 1.2 Table 4 SPDM request codes, DSP0277 session phases.
+///
+/// The dispatcher rejects a listed request outside its states with the error
+/// code from SPDM_REQUEST_REJECT_TABLE. Unlisted requests are not gated here.
+pub const SPDM_REQUEST_STATE_TABLE: &[SpdmRequestStateRule] = &[
+    state_rule!(SpdmRequestGetVersion, OUT_OF_SESSION),
+    state_rule!(SpdmRequestGetCapabilities, OUT_OF_SESSION),
+    state_rule!(SpdmRequestNegotiateAlgorithms, OUT_OF_SESSION),
+    state_rule!(SpdmRequestChallenge, OUT_OF_SESSION),
+    state_rule!(SpdmRequestKeyExchange, OUT_OF_SESSION),
+    state_rule!(SpdmRequestPskExchange, OUT_OF_SESSION),
+    state_rule!(
+        SpdmRequestGetDigests,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+    state_rule!(
+        SpdmRequestGetCertificate,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+    state_rule!(
+        SpdmRequestGetMeasurements,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+    state_rule!(
+        SpdmRequestGetCsr,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+    state_rule!(
+        SpdmRequestSetCertificate,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+    state_rule!(
+        SpdmRequestGetKeyPairInfo,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+    state_rule!(
+        SpdmRequestSetKeyPairInfo,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+    state_rule!(SpdmRequestFinish, HANDSHAKING),
+    state_rule!(SpdmRequestPskFinish, HANDSHAKING),
+    state_rule!(SpdmRequestHeartbeat, ESTABLISHED),
+    state_rule!(SpdmRequestKeyUpdate, ESTABLISHED),
+    state_rule!(SpdmRequestEndSession, ESTABLISHED),
+    state_rule!(SpdmRequestGetSupportedEventTypes, ESTABLISHED),
+    state_rule!(SpdmRequestSubscribeEventTypes, ESTABLISHED),
+    state_rule!(SpdmRequestVendorDefinedRequest, SpdmRequestStates::all()),
+    state_rule!(
+        SpdmRequestResponseIfReady,
+        SpdmRequestStates::from_bits_truncate(OUT_OF_SESSION.bits() | ESTABLISHED.bits())
+    ),
+];
+
+/// Return the error code to reject request_response_code with in session_state,
+/// or None when the request is allowed or not listed in SPDM_REQUEST_STATE_TABLE.
+pub fn get_request_state_error_code(
+    version: SpdmVersion,
+    session_state: SpdmSessionState,
+    request_response_code: SpdmRequestResponseCode,
+) -> Option<SpdmErrorCode> {
+    let rule = SPDM_REQUEST_STATE_TABLE
+        .iter()
+        .find(|rule| rule.request_response_code == request_response_code)?;
+    if rule
+        .allowed_states
+        .intersects(SpdmRequestStates::from_session_state(session_state))
+    {
+        None
+    } else {
+        Some(get_reject_error_code(
+            version,
+            session_state,
+            request_response_code,
+        ))
+    }
+}
+
+/// One row of the request rejection table.
+/// session_state SpdmSessionNotStarted means the request arrived outside of a session.
+/// The rule applies when the negotiated version is no lower than min_version.
+pub struct SpdmRequestRejectRule {
+    pub request_response_code: SpdmRequestResponseCode,
+    pub session_state: SpdmSessionState,
+    pub min_version: SpdmVersion,
+    pub error_code: SpdmErrorCode,
+}
+
+macro_rules! reject_rule {
+    ($code:ident, $state:ident, $version:ident, $error:ident) => {
+        SpdmRequestRejectRule {
+            request_response_code: SpdmRequestResponseCode::$code,
+            session_state: SpdmSessionState::$state,
+            min_version: SpdmVersion::$version,
+            error_code: SpdmErrorCode::$error,
+        }
+    };
+}
+
+/// Error codes for requests that are not allowed in the current session state.
+/// Reference: DSP0274 1.2 Table 4 SPDM request codes and 10.4 ERROR response.
+///
+/// Requests which are never allowed in a session are UnsupportedRequest from 1.2.
+/// Session-only requests outside a session are SessionRequired from 1.2.
+/// Everything else rejected by the dispatcher falls back to UnexpectedRequest.
+pub const SPDM_REQUEST_REJECT_TABLE: &[SpdmRequestRejectRule] = &[
+    // Outside of a session
+    reject_rule!(
+        SpdmRequestHeartbeat,
+        SpdmSessionNotStarted,
+        SpdmVersion12,
+        SpdmErrorSessionRequired
+    ),
+    reject_rule!(
+        SpdmRequestKeyUpdate,
+        SpdmSessionNotStarted,
+        SpdmVersion12,
+        SpdmErrorSessionRequired
+    ),
+    reject_rule!(
+        SpdmRequestEndSession,
+        SpdmSessionNotStarted,
+        SpdmVersion12,
+        SpdmErrorSessionRequired
+    ),
+    reject_rule!(
+        SpdmRequestGetSupportedEventTypes,
+        SpdmSessionNotStarted,
+        SpdmVersion12,
+        SpdmErrorSessionRequired
+    ),
+    reject_rule!(
+        SpdmRequestSubscribeEventTypes,
+        SpdmSessionNotStarted,
+        SpdmVersion12,
+        SpdmErrorSessionRequired
+    ),
+    // Handshaking phase
+    reject_rule!(
+        SpdmRequestGetVersion,
+        SpdmSessionHandshaking,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestGetCapabilities,
+        SpdmSessionHandshaking,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestNegotiateAlgorithms,
+        SpdmSessionHandshaking,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestChallenge,
+        SpdmSessionHandshaking,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestKeyExchange,
+        SpdmSessionHandshaking,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestPskExchange,
+        SpdmSessionHandshaking,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestResponseIfReady,
+        SpdmSessionHandshaking,
+        SpdmVersion10,
+        SpdmErrorUnsupportedRequest
+    ),
+    // Application phase
+    reject_rule!(
+        SpdmRequestGetVersion,
+        SpdmSessionEstablished,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestGetCapabilities,
+        SpdmSessionEstablished,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestNegotiateAlgorithms,
+        SpdmSessionEstablished,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestChallenge,
+        SpdmSessionEstablished,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestKeyExchange,
+        SpdmSessionEstablished,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+    reject_rule!(
+        SpdmRequestPskExchange,
+        SpdmSessionEstablished,
+        SpdmVersion12,
+        SpdmErrorUnsupportedRequest
+    ),
+];
+
+/// One row of the request version table.
+pub struct SpdmRequestVersionRule {
+    pub request_response_code: SpdmRequestResponseCode,
+    pub min_version: SpdmVersion,
+}
+
+macro_rules! version_rule {
+    ($code:ident, $version:ident) => {
+        SpdmRequestVersionRule {
+            request_response_code: SpdmRequestResponseCode::$code,
+            min_version: SpdmVersion::$version,
+        }
+    };
+}
+
+/// Lowest SPDM version each request is defined in.
+/// Reference: DSP0274 1.3 Table 4 SPDM request codes.
+///
+/// The dispatcher replies UnsupportedRequest to a listed request if the
+/// negotiated version is lower. Unlisted requests are not gated here.
+pub const SPDM_REQUEST_VERSION_TABLE: &[SpdmRequestVersionRule] = &[
+    version_rule!(SpdmRequestChunkSend, SpdmVersion12),
+    version_rule!(SpdmRequestChunkGet, SpdmVersion12),
+    version_rule!(SpdmRequestGetCsr, SpdmVersion12),
+    version_rule!(SpdmRequestSetCertificate, SpdmVersion12),
+    version_rule!(SpdmRequestGetEndpointInfo, SpdmVersion13),
+    version_rule!(SpdmRequestGetSupportedEventTypes, SpdmVersion13),
+    version_rule!(SpdmRequestGetMeasurementExtensionLog, SpdmVersion13),
+    version_rule!(SpdmRequestSubscribeEventTypes, SpdmVersion13),
+    version_rule!(SpdmRequestSendEvent, SpdmVersion13),
+    version_rule!(SpdmRequestGetKeyPairInfo, SpdmVersion13),
+    version_rule!(SpdmRequestSetKeyPairInfo, SpdmVersion13),
+];
+
+/// Return false if request_response_code is not defined in the negotiated version.
+pub fn is_request_supported_in_version(
+    version: SpdmVersion,
+    request_response_code: SpdmRequestResponseCode,
+) -> bool {
+    match SPDM_REQUEST_VERSION_TABLE
+        .iter()
+        .find(|rule| rule.request_response_code == request_response_code)
+    {
+        Some(rule) => version.get_u8() >= rule.min_version.get_u8(),
+        None => true,
+    }
+}
+
+/// Look up the error code to reply with when request_response_code is rejected
+/// in session_state under the negotiated version.
+pub fn get_reject_error_code(
+    version: SpdmVersion,
+    session_state: SpdmSessionState,
+    request_response_code: SpdmRequestResponseCode,
+) -> SpdmErrorCode {
+    SPDM_REQUEST_REJECT_TABLE
+        .iter()
+        .find(|rule| {
+            rule.request_response_code == request_response_code
+                && rule.session_state == session_state
+                && version.get_u8() >= rule.min_version.get_u8()
+        })
+        .map(|rule| rule.error_code)
+        .unwrap_or(SpdmErrorCode::SpdmErrorUnexpectedRequest)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use codec::Codec;
+
+    const NOT_STARTED: SpdmSessionState = SpdmSessionState::SpdmSessionNotStarted;
+    const HANDSHAKING_STATE: SpdmSessionState = SpdmSessionState::SpdmSessionHandshaking;
+    const ESTABLISHED_STATE: SpdmSessionState = SpdmSessionState::SpdmSessionEstablished;
+
+    const UNEXPECTED: Option<SpdmErrorCode> = Some(SpdmErrorCode::SpdmErrorUnexpectedRequest);
+    const UNSUPPORTED: Option<SpdmErrorCode> = Some(SpdmErrorCode::SpdmErrorUnsupportedRequest);
+    const SESSION_REQUIRED: Option<SpdmErrorCode> = Some(SpdmErrorCode::SpdmErrorSessionRequired);
+
+    /// (version, session state, request code, expected error code)
+    /// Written out from DSP0274 1.2 Table 4 and 10.4 ERROR response.
+    const STATE_CASES: [(u8, SpdmSessionState, u8, Option<SpdmErrorCode>); 30] = [
+        // GET_VERSION, GET_CAPABILITIES, NEGOTIATE_ALGORITHMS, CHALLENGE
+        (0x12, NOT_STARTED, 0x84, None),
+        (0x12, HANDSHAKING_STATE, 0x84, UNSUPPORTED),
+        (0x11, HANDSHAKING_STATE, 0x84, UNEXPECTED),
+        (0x12, ESTABLISHED_STATE, 0xE1, UNSUPPORTED),
+        (0x13, ESTABLISHED_STATE, 0xE3, UNSUPPORTED),
+        (0x10, ESTABLISHED_STATE, 0xE3, UNEXPECTED),
+        (0x12, HANDSHAKING_STATE, 0x83, UNSUPPORTED),
+        // KEY_EXCHANGE, PSK_EXCHANGE
+        (0x12, ESTABLISHED_STATE, 0xE4, UNSUPPORTED),
+        (0x11, ESTABLISHED_STATE, 0xE4, UNEXPECTED),
+        (0x12, HANDSHAKING_STATE, 0xE6, UNSUPPORTED),
+        // FINISH, PSK_FINISH
+        (0x12, HANDSHAKING_STATE, 0xE5, None),
+        (0x12, NOT_STARTED, 0xE5, UNEXPECTED),
+        (0x12, ESTABLISHED_STATE, 0xE7, UNEXPECTED),
+        // HEARTBEAT, KEY_UPDATE, END_SESSION
+        (0x12, ESTABLISHED_STATE, 0xE8, None),
+        (0x12, NOT_STARTED, 0xE8, SESSION_REQUIRED),
+        (0x11, NOT_STARTED, 0xE8, UNEXPECTED),
+        (0x12, HANDSHAKING_STATE, 0xE9, UNEXPECTED),
+        (0x12, NOT_STARTED, 0xEC, SESSION_REQUIRED),
+        // GET_SUPPORTED_EVENT_TYPES, SUBSCRIBE_EVENT_TYPES
+        (0x13, NOT_STARTED, 0xE2, SESSION_REQUIRED),
+        (0x13, NOT_STARTED, 0xF0, SESSION_REQUIRED),
+        // GET_DIGESTS, GET_CERTIFICATE, GET_MEASUREMENTS
+        (0x12, HANDSHAKING_STATE, 0x81, UNEXPECTED),
+        (0x12, ESTABLISHED_STATE, 0x82, None),
+        (0x11, HANDSHAKING_STATE, 0xE0, UNEXPECTED),
+        // GET_CSR, SET_CERTIFICATE, GET_KEY_PAIR_INFO
+        (0x12, HANDSHAKING_STATE, 0xED, UNEXPECTED),
+        (0x12, ESTABLISHED_STATE, 0xEE, None),
+        (0x13, HANDSHAKING_STATE, 0xFC, UNEXPECTED),
+        // VENDOR_DEFINED_REQUEST, RESPOND_IF_READY
+        (0x12, HANDSHAKING_STATE, 0xFE, None),
+        (0x10, HANDSHAKING_STATE, 0xFF, UNSUPPORTED),
+        (0x12, ESTABLISHED_STATE, 0xFF, None),
+        // CHUNK_SEND is not gated by session state
+        (0x12, HANDSHAKING_STATE, 0x85, None),
+    ];
+
+    /// (request code, first SPDM version defining it)
+    /// Written out from DSP0274 1.3 Table 4.
+    const VERSION_CASES: [(u8, u8); 11] = [
+        (0x85, 0x12), // CHUNK_SEND
+        (0x86, 0x12), // CHUNK_GET
+        (0xED, 0x12), // GET_CSR
+        (0xEE, 0x12), // SET_CERTIFICATE
+        (0x87, 0x13), // GET_ENDPOINT_INFO
+        (0xE2, 0x13), // GET_SUPPORTED_EVENT_TYPES
+        (0xEF, 0x13), // GET_MEASUREMENT_EXTENSION_LOG
+        (0xF0, 0x13), // SUBSCRIBE_EVENT_TYPES
+        (0xF1, 0x13), // SEND_EVENT
+        (0xFC, 0x13), // GET_KEY_PAIR_INFO
+        (0xFD, 0x13), // SET_KEY_PAIR_INFO
+    ];
+
+    const VERSIONS: [u8; 4] = [0x10, 0x11, 0x12, 0x13];
+    const STATES: [SpdmSessionState; 3] = [NOT_STARTED, HANDSHAKING_STATE, ESTABLISHED_STATE];
+
+    const SESSION_REQUIRED_REQUESTS: [u8; 5] = [0xE8, 0xE9, 0xEC, 0xE2, 0xF0];
+    const NOT_IN_SESSION_REQUESTS: [u8; 6] = [0x84, 0xE1, 0xE3, 0x83, 0xE4, 0xE6];
+
+    /// (request code, accepted [out of session, handshaking, established])
+    /// Written out from DSP0274 1.3 Table 4 for every request gated by session
+    /// state, independently of SPDM_REQUEST_STATE_TABLE.
+    const STATE_TABLE_CASES: [(u8, [bool; 3]); 22] = [
+        (0x84, [true, false, false]), // GET_VERSION
+        (0xE1, [true, false, false]), // GET_CAPABILITIES
+        (0xE3, [true, false, false]), // NEGOTIATE_ALGORITHMS
+        (0x83, [true, false, false]), // CHALLENGE
+        (0xE4, [true, false, false]), // KEY_EXCHANGE
+        (0xE6, [true, false, false]), // PSK_EXCHANGE
+        (0x81, [true, false, true]),  // GET_DIGESTS
+        (0x82, [true, false, true]),  // GET_CERTIFICATE
+        (0xE0, [true, false, true]),  // GET_MEASUREMENTS
+        (0xED, [true, false, true]),  // GET_CSR
+        (0xEE, [true, false, true]),  // SET_CERTIFICATE
+        (0xFC, [true, false, true]),  // GET_KEY_PAIR_INFO
+        (0xFD, [true, false, true]),  // SET_KEY_PAIR_INFO
+        (0xFF, [true, false, true]),  // RESPOND_IF_READY
+        (0xE5, [false, true, false]), // FINISH
+        (0xE7, [false, true, false]), // PSK_FINISH
+        (0xE8, [false, false, true]), // HEARTBEAT
+        (0xE9, [false, false, true]), // KEY_UPDATE
+        (0xEC, [false, false, true]), // END_SESSION
+        (0xE2, [false, false, true]), // GET_SUPPORTED_EVENT_TYPES
+        (0xF0, [false, false, true]), // SUBSCRIBE_EVENT_TYPES
+        (0xFE, [true, true, true]),   // VENDOR_DEFINED_REQUEST
+    ];
+
+    /// Error code a rejected request gets.
+    /// Written out from DSP0274 1.2 10.4 ERROR response independently of
+    /// SPDM_REQUEST_REJECT_TABLE.
+    fn expected_error_code(
+        spdm_version: u8,
+        session_state: SpdmSessionState,
+        opcode: u8,
+    ) -> Option<SpdmErrorCode> {
+        // RESPOND_IF_READY is never valid during the handshake
+        if opcode == 0xFF {
+            return if session_state == HANDSHAKING_STATE {
+                UNSUPPORTED
+            } else {
+                UNEXPECTED
+            };
+        }
+        if spdm_version < 0x12 {
+            UNEXPECTED
+        } else if session_state == NOT_STARTED && SESSION_REQUIRED_REQUESTS.contains(&opcode) {
+            SESSION_REQUIRED
+        } else if session_state != NOT_STARTED && NOT_IN_SESSION_REQUESTS.contains(&opcode) {
+            UNSUPPORTED
+        } else {
+            UNEXPECTED
+        }
+    }
+
+    fn version(version: u8) -> SpdmVersion {
+        SpdmVersion::read_bytes(&[version]).unwrap()
+    }
+
+    fn code(opcode: u8) -> SpdmRequestResponseCode {
+        let code = SpdmRequestResponseCode::read_bytes(&[opcode]).unwrap();
+        assert_ne!(code, SpdmRequestResponseCode::Unknown(opcode));
+        code
+    }
+
+    #[test]
+    fn test_case0_get_request_state_error_code() {
+        for (spdm_version, session_state, opcode, error_code) in STATE_CASES {
+            assert_eq!(
+                get_request_state_error_code(version(spdm_version), session_state, code(opcode)),
+                error_code,
+                "version {:x} state {:?} code {:x}",
+                spdm_version,
+                session_state,
+                opcode
+            );
+        }
+    }
+
+    #[test]
+    fn test_case1_get_request_state_error_code() {
+        // every gated request is in the written out table
+        for rule in SPDM_REQUEST_STATE_TABLE {
+            assert!(STATE_TABLE_CASES
+                .iter()
+                .any(|(opcode, _)| code(*opcode) == rule.request_response_code));
+        }
+        for spdm_version in VERSIONS {
+            for (state_index, session_state) in STATES.iter().copied().enumerate() {
+                for opcode in 0x80u8..=0xFF {
+                    let request_code = SpdmRequestResponseCode::read_bytes(&[opcode]).unwrap();
+                    let expected = match STATE_TABLE_CASES
+                        .iter()
+                        .find(|(table_opcode, _)| *table_opcode == opcode)
+                    {
+                        Some((_, accepted)) if !accepted[state_index] => {
+                            expected_error_code(spdm_version, session_state, opcode)
+                        }
+                        _ => None,
+                    };
+                    assert_eq!(
+                        get_request_state_error_code(
+                            version(spdm_version),
+                            session_state,
+                            request_code
+                        ),
+                        expected,
+                        "version {:x} state {:?} code {:x}",
+                        spdm_version,
+                        session_state,
+                        opcode
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_case1_reject_table_matches_state_table() {
+        for rule in SPDM_REQUEST_REJECT_TABLE {
+            let state_rule = SPDM_REQUEST_STATE_TABLE
+                .iter()
+                .find(|state_rule| state_rule.request_response_code == rule.request_response_code)
+                .unwrap();
+            assert!(!state_rule
+                .allowed_states
+                .intersects(SpdmRequestStates::from_session_state(rule.session_state)));
+        }
+    }
+
+    #[test]
+    fn test_case0_is_request_supported_in_version() {
+        for (opcode, min_version) in VERSION_CASES {
+            for spdm_version in VERSIONS {
+                assert_eq!(
+                    is_request_supported_in_version(version(spdm_version), code(opcode)),
+                    spdm_version >= min_version,
+                    "version {:x} code {:x}",
+                    spdm_version,
+                    opcode
+                );
+            }
+        }
+        // GET_VERSION, GET_MEASUREMENTS, KEY_EXCHANGE and VENDOR_DEFINED_REQUEST
+        // are not gated by the version table.
+        for opcode in [0x84, 0xE0, 0xE4, 0xFE] {
+            for spdm_version in VERSIONS {
+                assert!(is_request_supported_in_version(
+                    version(spdm_version),
+                    code(opcode)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_case0_get_reject_error_code() {
+        for spdm_version in VERSIONS {
+            for session_state in STATES {
+                for opcode in 0x80u8..=0xFF {
+                    let request_code = SpdmRequestResponseCode::read_bytes(&[opcode]).unwrap();
+                    assert_eq!(
+                        Some(get_reject_error_code(
+                            version(spdm_version),
+                            session_state,
+                            request_code
+                        )),
+                        expected_error_code(spdm_version, session_state, opcode),
+                        "version {:x} state {:?} code {:x}",
+                        spdm_version,
+                        session_state,
+                        opcode
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::opaque::{
+    SecuredMessageVersionList, SpdmOpaqueStruct, SpdmOpaqueSupport,
+    RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0274_FMT1,
+    RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0277,
+};
+use crate::protocol::SpdmVersion;
+use crate::responder::*;
+
+use conquer_once::spin::OnceCell;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// Application hook choosing the secured message version (DSP0277) of a
+/// session among the ones the requester advertises in KEY_EXCHANGE or
+/// PSK_EXCHANGE. local_versions are the ones of
+/// config_info.secure_spdm_version. The result must be in both lists,
+/// None selects no version.
+///
+/// The selector of ResponderContext::secure_version_selector is used first,
+/// then the registered one. Without any the highest common version is
+/// selected.
+#[derive(Clone, Copy)]
+pub struct SpdmSecureVersionSelector {
+    pub select_secure_spdm_version_cb:
+        fn(spdm_version: SpdmVersion, peer_versions: &[u8], local_versions: &[u8]) -> Option<u8>,
+}
+
+static SECURE_VERSION_SELECTOR: OnceCell<SpdmSecureVersionSelector> = OnceCell::uninit();
+
+pub fn register_secure_version_selector(context: SpdmSecureVersionSelector) -> bool {
+    SECURE_VERSION_SELECTOR.try_init_once(|| context).is_ok()
+}
+
+fn highest_common_version(peer_versions: &[u8], local_versions: &[u8]) -> Option<u8> {
+    peer_versions
+        .iter()
+        .filter(|version| local_versions.contains(version))
+        .max()
+        .copied()
+}
+
+impl<'a> ResponderContext<'a> {
+    /// Secured message version for the versions advertised by the requester,
+    /// None if there is no common one.
+    pub(crate) fn select_secure_spdm_version(
+        &self,
+        secured_message_version_list: &SecuredMessageVersionList,
+    ) -> Option<u8> {
+        let peer_versions: Vec<u8> = secured_message_version_list
+            .versions_list
+            .iter()
+            .take(secured_message_version_list.version_count as usize)
+            .map(|version| version.get_secure_spdm_version())
+            .collect();
+        let local_versions: Vec<u8> = self
+            .common
+            .config_info
+            .secure_spdm_version
+            .iter()
+            .filter(|version| **version != 0)
+            .copied()
+            .collect();
+
+        let selector = self
+            .secure_version_selector
+            .or_else(|| SECURE_VERSION_SELECTOR.try_get().ok().copied());
+        let selected = match selector {
+            Some(selector) => (selector.select_secure_spdm_version_cb)(
+                self.common.negotiate_info.spdm_version_sel,
+                &peer_versions,
+                &local_versions,
+            ),
+            None => highest_common_version(&peer_versions, &local_versions),
+        }?;
+        if !peer_versions.contains(&selected) || !local_versions.contains(&selected) {
+            error!(
+                "!!! secure spdm version {:02x} not supported !!!\n",
+                selected
+            );
+            return None;
+        }
+        Some(selected)
+    }
+
+    /// Opaque data of the response selecting secure_spdm_version, None if no
+    /// opaque data format is negotiated.
+    pub(crate) fn secure_spdm_version_selection_opaque(
+        &self,
+        secure_spdm_version: u8,
+    ) -> Option<SpdmOpaqueStruct> {
+        let selection: &[u8] = if self.common.negotiate_info.spdm_version_sel.get_u8()
+            < SpdmVersion::SpdmVersion12.get_u8()
+        {
+            &RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0277
+        } else if self.common.negotiate_info.opaque_data_support
+            == SpdmOpaqueSupport::OPAQUE_DATA_FMT1
+        {
+            &RSP_DMTF_OPAQUE_DATA_VERSION_SELECTION_DSP0274_FMT1
+        } else {
+            return None;
+        };
+
+        let mut opaque = SpdmOpaqueStruct {
+            data_size: selection.len() as u16,
+            ..Default::default()
+        };
+        opaque.data[..selection.len()].copy_from_slice(selection);
+        opaque.data[selection.len() - 1] = secure_spdm_version;
+        Some(opaque)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_highest_common_version() {
+        assert_eq!(
+            highest_common_version(&[0x10, 0x11], &[0x10, 0x11]),
+            Some(0x11)
+        );
+        assert_eq!(highest_common_version(&[0x11, 0x10], &[0x10]), Some(0x10));
+        assert_eq!(highest_common_version(&[0x12], &[0x10, 0x11]), None);
+        assert_eq!(highest_common_version(&[], &[0x10]), None);
+    }
+}
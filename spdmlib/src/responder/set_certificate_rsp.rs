@@ -0,0 +1,205 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::SpdmCodec;
+use crate::common::SpdmConnectionState;
+use crate::crypto;
+use crate::error::{SpdmResult, SPDM_STATUS_INVALID_CERT};
+use crate::message::*;
+use crate::protocol::*;
+use crate::responder::*;
+use crate::secret::SpdmSetCertificateResult;
+
+impl<'a> ResponderContext<'a> {
+    pub fn handle_spdm_set_certificate(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+    ) -> SpdmResult {
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_set_certificate_response(session_id, bytes, writer);
+            Ok(())
+        })
+    }
+
+    fn write_spdm_set_certificate_response(
+        &mut self,
+        session_id: Option<u32>,
+        bytes: &[u8],
+        writer: &mut Writer,
+    ) {
+        if self.common.runtime_info.get_connection_state().get_u8()
+            < SpdmConnectionState::SpdmConnectionNegotiated.get_u8()
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnexpectedRequest, 0, writer);
+            return;
+        }
+        if !self
+            .common
+            .negotiate_info
+            .rsp_capabilities_sel
+            .contains(SpdmResponseCapabilityFlags::SET_CERT_CAP)
+        {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnsupportedRequest, 0, writer);
+            return;
+        }
+        // the requester is not authenticated outside a session
+        if session_id.is_none() && !self.common.config_info.allow_set_certificate_out_of_session {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnexpectedRequest, 0, writer);
+            return;
+        }
+
+        let mut reader = Reader::init(bytes);
+        let message_header = SpdmMessageHeader::read(&mut reader);
+        if let Some(message_header) = message_header {
+            if message_header.version != self.common.negotiate_info.spdm_version_sel {
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorVersionMismatch, 0, writer);
+                return;
+            }
+        } else {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        self.common.reset_buffer_via_request_code(
+            SpdmRequestResponseCode::SpdmRequestSetCertificate,
+            session_id,
+        );
+
+        let set_certificate =
+            SpdmSetCertificateRequestPayload::spdm_read(&mut self.common, &mut reader);
+        let set_certificate = if let Some(set_certificate) = set_certificate {
+            debug!("!!! set_certificate : {:02x?}\n", set_certificate.slot_id);
+            set_certificate
+        } else {
+            error!("!!! set_certificate : fail !!!\n");
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        };
+        let slot_id = set_certificate.slot_id as usize;
+        if slot_id >= SPDM_MAX_SLOT_NUMBER {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+            return;
+        }
+
+        let cert_chain_data = if set_certificate.erase {
+            None
+        } else {
+            match self.verify_set_certificate_chain(&set_certificate.cert_chain) {
+                Ok(cert_chain_data) => Some(cert_chain_data),
+                Err(_) => {
+                    error!("!!! set_certificate : invalid cert chain !!!\n");
+                    self.write_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0, writer);
+                    return;
+                }
+            }
+        };
+
+        match self.common.secret_provider.set_certificate(
+            self.common.negotiate_info.spdm_version_sel,
+            set_certificate.slot_id,
+            set_certificate.key_pair_id,
+            cert_chain_data
+                .as_ref()
+                .map(|cert_chain_data| cert_chain_data.as_ref()),
+        ) {
+            SpdmSetCertificateResult::Stored => {
+                if self
+                    .common
+                    .store_cert_chain(set_certificate.slot_id, cert_chain_data.as_ref())
+                    .is_err()
+                {
+                    error!("!!! set_certificate : provision store fail !!!\n");
+                    self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                    return;
+                }
+                self.common.provision_info.my_cert_chain_data[slot_id] = cert_chain_data;
+                self.common.provision_info.my_cert_chain[slot_id] = if set_certificate.erase {
+                    None
+                } else {
+                    Some(set_certificate.cert_chain.clone())
+                };
+                // SPDM 1.3 multi-key: the slot now belongs to KeyPairID.
+                if set_certificate.erase {
+                    self.common
+                        .provision_info
+                        .set_slot_key_pair_id(set_certificate.slot_id, 0);
+                } else if self
+                    .common
+                    .provision_info
+                    .get_key_pair_info(set_certificate.key_pair_id)
+                    .is_some()
+                {
+                    self.common
+                        .provision_info
+                        .set_slot_key_pair_id(set_certificate.slot_id, set_certificate.key_pair_id);
+                }
+            }
+            SpdmSetCertificateResult::ResetRequired => {
+                info!("!!! set_certificate : reset required !!!\n");
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorResetRequired, 0, writer);
+                return;
+            }
+            SpdmSetCertificateResult::Failed => {
+                error!("!!! set_certificate : store fail !!!\n");
+                self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                return;
+            }
+        }
+
+        info!("send spdm set_certificate_rsp\n");
+        let response = SpdmMessage {
+            header: SpdmMessageHeader {
+                version: self.common.negotiate_info.spdm_version_sel,
+                request_response_code: SpdmRequestResponseCode::SpdmResponseSetCertificateRsp,
+            },
+            payload: SpdmMessagePayload::SpdmSetCertificateResponse(
+                SpdmSetCertificateResponsePayload {
+                    slot_id: set_certificate.slot_id,
+                },
+            ),
+        };
+        if response.spdm_encode(&mut self.common, writer).is_err() {
+            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+        }
+    }
+
+    /// Check the received cert chain against the negotiated algorithms:
+    /// the root hash uses base_hash_sel, the leaf cert key matches base_asym_sel
+    /// and the chain verifies. Return the DER certificates.
+    fn verify_set_certificate_chain(
+        &self,
+        cert_chain: &SpdmCertChainBuffer,
+    ) -> SpdmResult<SpdmCertChainData> {
+        let base_hash_algo = self.common.negotiate_info.base_hash_sel;
+        let hash_size = base_hash_algo.get_size() as usize;
+        let cert_chain = cert_chain.as_ref();
+        if cert_chain.len() <= 4 + hash_size
+            || cert_chain.len() - 4 - hash_size > config::MAX_SPDM_CERT_CHAIN_DATA_SIZE
+        {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+        let certs = &cert_chain[(4 + hash_size)..];
+
+        crypto::check_cert_chain_format(certs, self.common.negotiate_info.base_asym_sel)?;
+        crypto::cert_operation::verify_cert_chain(certs)?;
+
+        let (root_cert_begin, root_cert_end) =
+            crypto::cert_operation::get_cert_from_cert_chain(certs, 0)?;
+        let root_hash =
+            crypto::hash::hash_all(base_hash_algo, &certs[root_cert_begin..root_cert_end])
+                .ok_or(SPDM_STATUS_INVALID_CERT)?;
+        if root_hash.as_ref() != &cert_chain[4..(4 + hash_size)] {
+            error!("root_hash - fail!\n");
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+
+        let mut cert_chain_data = SpdmCertChainData {
+            data_size: certs.len() as u16,
+            ..Default::default()
+        };
+        cert_chain_data.data[..certs.len()].copy_from_slice(certs);
+        Ok(cert_chain_data)
+    }
+}
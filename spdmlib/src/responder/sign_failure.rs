@@ -0,0 +1,168 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::error::SpdmResult;
+use crate::message::*;
+use crate::protocol::{SpdmSignatureStruct, SPDM_MAX_SLOT_NUMBER};
+use crate::responder::*;
+
+use conquer_once::spin::OnceCell;
+
+/// Reply to a request whose signature cannot be generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmSignFailureAction {
+    /// ERROR(Unspecified), the requester is not expected to retry.
+    Unspecified,
+    /// ERROR(Busy), the requester may retry, e.g. after a transient HSM failure.
+    Busy,
+}
+
+/// Signature failures of one certificate slot.
+/// The consecutive count is reset by a successful signature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpdmSignFailureStats {
+    pub failure_count: u32,
+    pub consecutive_failure_count: u32,
+    pub quarantined: bool,
+}
+
+/// sign_failure_cb is called on every signature failure, e.g. to feed
+/// metrics. After quarantine_threshold consecutive failures the slot is
+/// quarantined: CHALLENGE, signed GET_MEASUREMENTS and KEY_EXCHANGE for it
+/// are rejected with ERROR(Unspecified) without calling the signer, until
+/// release_quarantined_slot. A threshold of 0 never quarantines.
+///
+/// Without a registered policy a failure is answered with ERROR(Unspecified).
+#[derive(Clone, Copy)]
+pub struct SpdmSignFailurePolicy {
+    pub action: SpdmSignFailureAction,
+    pub quarantine_threshold: u32,
+    pub sign_failure_cb: fn(
+        request_response_code: SpdmRequestResponseCode,
+        slot_id: u8,
+        stats: &SpdmSignFailureStats,
+    ),
+}
+
+static SIGN_FAILURE_POLICY: OnceCell<SpdmSignFailurePolicy> = OnceCell::uninit();
+
+pub fn register_sign_failure_policy(context: SpdmSignFailurePolicy) -> bool {
+    SIGN_FAILURE_POLICY.try_init_once(|| context).is_ok()
+}
+
+impl SpdmSignFailureStats {
+    /// Account one signature, return true if the slot gets quarantined.
+    fn update(&mut self, success: bool, quarantine_threshold: u32) -> bool {
+        if success {
+            self.consecutive_failure_count = 0;
+            return false;
+        }
+        self.failure_count = self.failure_count.saturating_add(1);
+        self.consecutive_failure_count = self.consecutive_failure_count.saturating_add(1);
+        if !self.quarantined
+            && quarantine_threshold != 0
+            && self.consecutive_failure_count >= quarantine_threshold
+        {
+            self.quarantined = true;
+            return true;
+        }
+        false
+    }
+}
+
+impl<'a> ResponderContext<'a> {
+    pub fn get_sign_failure_stats(&self, slot_id: u8) -> Option<&SpdmSignFailureStats> {
+        self.sign_failure_stats.get(slot_id as usize)
+    }
+
+    /// Put a quarantined slot back in service, e.g. once the HSM recovered.
+    pub fn release_quarantined_slot(&mut self, slot_id: u8) {
+        if let Some(stats) = self.sign_failure_stats.get_mut(slot_id as usize) {
+            stats.quarantined = false;
+            stats.consecutive_failure_count = 0;
+        }
+    }
+
+    pub(crate) fn is_slot_quarantined(&self, slot_id: usize) -> bool {
+        slot_id < SPDM_MAX_SLOT_NUMBER && self.sign_failure_stats[slot_id].quarantined
+    }
+
+    /// Account the signature generated with the key of slot_id. On failure
+    /// the response in writer is replaced by the error of the policy.
+    pub(crate) fn check_signature_result(
+        &mut self,
+        request_response_code: SpdmRequestResponseCode,
+        slot_id: usize,
+        signature: SpdmResult<SpdmSignatureStruct>,
+        writer: &mut Writer,
+    ) -> Option<SpdmSignatureStruct> {
+        let policy = SIGN_FAILURE_POLICY.try_get().ok();
+        if slot_id < SPDM_MAX_SLOT_NUMBER {
+            let quarantine_threshold = policy.map_or(0, |policy| policy.quarantine_threshold);
+            if self.sign_failure_stats[slot_id].update(signature.is_ok(), quarantine_threshold) {
+                warn!("!!! slot {:?} quarantined !!!\n", slot_id);
+            }
+        }
+
+        let signature = match signature {
+            Ok(signature) => return Some(signature),
+            Err(e) => e,
+        };
+        error!(
+            "!!! {:?} signature : fail {:?} !!!\n",
+            request_response_code, signature
+        );
+        if let (Some(policy), Some(stats)) = (policy, self.sign_failure_stats.get(slot_id)) {
+            (policy.sign_failure_cb)(request_response_code, slot_id as u8, stats);
+        }
+
+        let error_code = match policy {
+            Some(policy)
+                if policy.action == SpdmSignFailureAction::Busy
+                    && !self.is_slot_quarantined(slot_id) =>
+            {
+                SpdmErrorCode::SpdmErrorBusy
+            }
+            _ => SpdmErrorCode::SpdmErrorUnspecified,
+        };
+        writer.clear();
+        self.write_spdm_error(error_code, 0, writer);
+        None
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_sign_failure_stats_update() {
+        let mut stats = SpdmSignFailureStats::default();
+
+        assert!(!stats.update(false, 3));
+        assert!(!stats.update(false, 3));
+        assert!(!stats.update(true, 3));
+        assert_eq!(stats.consecutive_failure_count, 0);
+
+        assert!(!stats.update(false, 3));
+        assert!(!stats.update(false, 3));
+        assert!(stats.update(false, 3));
+        // reported once
+        assert!(!stats.update(false, 3));
+        assert_eq!(
+            stats,
+            SpdmSignFailureStats {
+                failure_count: 6,
+                consecutive_failure_count: 4,
+                quarantined: true,
+            }
+        );
+
+        let mut stats = SpdmSignFailureStats::default();
+        for _ in 0..10 {
+            assert!(!stats.update(false, 0));
+        }
+        assert!(!stats.quarantined);
+    }
+}
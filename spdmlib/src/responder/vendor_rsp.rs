@@ -13,13 +13,10 @@ impl<'a> ResponderContext<'a> {
         session_id: Option<u32>,
         bytes: &[u8],
     ) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_vendor_defined_response(session_id, bytes, &mut writer);
-        match session_id {
-            Some(session_id) => self.send_secured_message(session_id, writer.used_slice(), false),
-            None => self.send_message(writer.used_slice()),
-        }
+        self.send_response(session_id, |responder, writer| {
+            responder.write_spdm_vendor_defined_response(session_id, bytes, writer);
+            Ok(())
+        })
     }
 
     pub fn write_spdm_vendor_defined_response(
@@ -56,14 +53,28 @@ impl<'a> ResponderContext<'a> {
         let standard_id = vendor_defined_request_payload.standard_id;
         let vendor_id = vendor_defined_request_payload.vendor_id;
         let req_payload = vendor_defined_request_payload.req_payload;
-        let rsp_payload =
-            self.respond_to_vendor_defined_request(&req_payload, vendor_defined_request_handler);
-        if rsp_payload.is_err() {
-            self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
-            return;
-        }
+        let session = session_id.and_then(|session_id| {
+            self.common
+                .get_immutable_session_via_id(session_id)
+                .map(|session| VendorDefinedRequestSession {
+                    session_id,
+                    session_state: session.get_session_state(),
+                })
+        });
+        let rsp_payload = match self.respond_to_vendor_defined_request(&req_payload, |req| {
+            vendor_defined_request_dispatch(standard_id, &vendor_id, session, req)
+        }) {
+            Ok(rsp_payload) => rsp_payload,
+            Err(status) => {
+                if let Some(vendor_error) = vendor_defined_error_handler(&req_payload, status) {
+                    self.write_spdm_vendor_error(&vendor_error, writer);
+                } else {
+                    self.write_spdm_error(SpdmErrorCode::SpdmErrorUnspecified, 0, writer);
+                }
+                return;
+            }
+        };
 
-        let rsp_payload = rsp_payload.unwrap();
         let response = SpdmMessage {
             header: SpdmMessageHeader {
                 version: self.common.negotiate_info.spdm_version_sel,
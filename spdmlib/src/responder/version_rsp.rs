@@ -10,10 +10,10 @@ use crate::responder::*;
 
 impl<'a> ResponderContext<'a> {
     pub fn handle_spdm_version(&mut self, bytes: &[u8]) -> SpdmResult {
-        let mut send_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
-        let mut writer = Writer::init(&mut send_buffer);
-        self.write_spdm_version_response(bytes, &mut writer);
-        self.send_message(writer.used_slice())
+        self.send_response(None, |responder, writer| {
+            responder.write_spdm_version_response(bytes, writer);
+            Ok(())
+        })
     }
 
     pub fn write_spdm_version_response(&mut self, bytes: &[u8], writer: &mut Writer) {
@@ -53,6 +53,16 @@ impl<'a> ResponderContext<'a> {
             return;
         }
 
+        let mut version_number_entry_count = 0;
+        let mut versions = gen_array_clone(SpdmVersionStruct::default(), MAX_SPDM_VERSION_COUNT);
+        for spdm_version in self.common.config_info.spdm_version.iter() {
+            if let SpdmVersion::Unknown(_) = spdm_version {
+                continue;
+            }
+            versions[version_number_entry_count].version = *spdm_version;
+            version_number_entry_count += 1;
+        }
+
         info!("send spdm version\n");
         let response = SpdmMessage {
             header: SpdmMessageHeader {
@@ -60,21 +70,8 @@ impl<'a> ResponderContext<'a> {
                 request_response_code: SpdmRequestResponseCode::SpdmResponseVersion,
             },
             payload: SpdmMessagePayload::SpdmVersionResponse(SpdmVersionResponsePayload {
-                version_number_entry_count: 3,
-                versions: [
-                    SpdmVersionStruct {
-                        update: 0,
-                        version: self.common.config_info.spdm_version[0],
-                    },
-                    SpdmVersionStruct {
-                        update: 0,
-                        version: self.common.config_info.spdm_version[1],
-                    },
-                    SpdmVersionStruct {
-                        update: 0,
-                        version: self.common.config_info.spdm_version[2],
-                    },
-                ],
+                version_number_entry_count: version_number_entry_count as u8,
+                versions,
             }),
         };
 
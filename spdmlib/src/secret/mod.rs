@@ -1,14 +1,27 @@
 // Copyright (c) 2021 Intel Corporation
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
+mod provider;
 mod secret_callback;
+#[cfg(feature = "tpm")]
+pub mod tpm;
 
 use conquer_once::spin::OnceCell;
-pub use secret_callback::{SpdmSecretAsymSign, SpdmSecretMeasurement, SpdmSecretPsk};
+pub use provider::SpdmSecretProvider;
+pub use secret_callback::{
+    SpdmCsrResult, SpdmSecretAliasCert, SpdmSecretAsymSign, SpdmSecretCertificate, SpdmSecretCsr,
+    SpdmSecretKeyPair, SpdmSecretMeasurement, SpdmSecretPsk, SpdmSetCertificateResult,
+    SpdmSetKeyPairInfoResult,
+};
 
 static SECRET_MEASUREMENT_INSTANCE: OnceCell<SpdmSecretMeasurement> = OnceCell::uninit();
 static SECRET_PSK_INSTANCE: OnceCell<SpdmSecretPsk> = OnceCell::uninit();
 static SECRET_ASYM_INSTANCE: OnceCell<SpdmSecretAsymSign> = OnceCell::uninit();
+static SECRET_CSR_INSTANCE: OnceCell<SpdmSecretCsr> = OnceCell::uninit();
+static SECRET_CERTIFICATE_INSTANCE: OnceCell<SpdmSecretCertificate> = OnceCell::uninit();
+static SECRET_ALIAS_CERT_INSTANCE: OnceCell<SpdmSecretAliasCert> = OnceCell::uninit();
 
 pub mod measurement {
     use super::{SpdmSecretMeasurement, SECRET_MEASUREMENT_INSTANCE};
@@ -135,6 +148,44 @@ pub mod psk {
             .ok()?
             .master_secret_hkdf_expand_cb)(spdm_version, base_hash_algo, psk_hint, info)
     }
+
+    /// Use secret_psk of the session if any, the registered callback otherwise.
+    pub fn handshake_secret_hkdf_expand_with(
+        secret_psk: Option<&SpdmSecretPsk>,
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        psk_hint: &SpdmPskHintStruct,
+        info: &[u8],
+    ) -> Option<SpdmHkdfOutputKeyingMaterial> {
+        match secret_psk {
+            Some(instance) => (instance.handshake_secret_hkdf_expand_cb)(
+                spdm_version,
+                base_hash_algo,
+                psk_hint,
+                info,
+            ),
+            None => handshake_secret_hkdf_expand(spdm_version, base_hash_algo, psk_hint, info),
+        }
+    }
+
+    /// Use secret_psk of the session if any, the registered callback otherwise.
+    pub fn master_secret_hkdf_expand_with(
+        secret_psk: Option<&SpdmSecretPsk>,
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        psk_hint: &SpdmPskHintStruct,
+        info: &[u8],
+    ) -> Option<SpdmHkdfOutputKeyingMaterial> {
+        match secret_psk {
+            Some(instance) => (instance.master_secret_hkdf_expand_cb)(
+                spdm_version,
+                base_hash_algo,
+                psk_hint,
+                info,
+            ),
+            None => master_secret_hkdf_expand(spdm_version, base_hash_algo, psk_hint, info),
+        }
+    }
 }
 
 pub mod asym_sign {
@@ -164,3 +215,118 @@ pub mod asym_sign {
             .sign_cb)(base_hash_algo, base_asym_algo, data)
     }
 }
+
+pub mod csr {
+    use super::SECRET_CSR_INSTANCE;
+    use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmVersion};
+    use crate::secret::{SpdmCsrResult, SpdmSecretCsr};
+
+    pub fn register(context: SpdmSecretCsr) -> bool {
+        SECRET_CSR_INSTANCE.try_init_once(|| context).is_ok()
+    }
+
+    static DEFAULT: SpdmSecretCsr = SpdmSecretCsr {
+        generate_csr_cb: |_spdm_version: SpdmVersion,
+                          _base_hash_algo: SpdmBaseHashAlgo,
+                          _base_asym_algo: SpdmBaseAsymAlgo,
+                          _csr_tracking_tag: u8,
+                          _requester_info: &[u8],
+                          _opaque_data: &[u8],
+                          _csr: &mut [u8]|
+         -> SpdmCsrResult { unimplemented!() },
+    };
+
+    /// Generate a CSR for the device key selected by base_asym_algo.
+    /// requester_info and opaque_data come from the GET_CSR request.
+    pub fn generate_csr(
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        csr_tracking_tag: u8,
+        requester_info: &[u8],
+        opaque_data: &[u8],
+        csr: &mut [u8],
+    ) -> SpdmCsrResult {
+        match SECRET_CSR_INSTANCE.try_get_or_init(|| DEFAULT.clone()) {
+            Ok(instance) => (instance.generate_csr_cb)(
+                spdm_version,
+                base_hash_algo,
+                base_asym_algo,
+                csr_tracking_tag,
+                requester_info,
+                opaque_data,
+                csr,
+            ),
+            Err(_) => SpdmCsrResult::Failed,
+        }
+    }
+}
+
+pub mod certificate {
+    use super::SECRET_CERTIFICATE_INSTANCE;
+    use crate::protocol::SpdmVersion;
+    use crate::secret::{SpdmSecretCertificate, SpdmSetCertificateResult};
+
+    pub fn register(context: SpdmSecretCertificate) -> bool {
+        SECRET_CERTIFICATE_INSTANCE
+            .try_init_once(|| context)
+            .is_ok()
+    }
+
+    static DEFAULT: SpdmSecretCertificate = SpdmSecretCertificate {
+        set_certificate_cb: |_spdm_version: SpdmVersion,
+                             _slot_id: u8,
+                             _key_pair_id: u8,
+                             _cert_chain: Option<&[u8]>|
+         -> SpdmSetCertificateResult { unimplemented!() },
+    };
+
+    /// Persist the cert chain of slot_id received in SET_CERTIFICATE,
+    /// None erases the slot.
+    pub fn set_certificate(
+        spdm_version: SpdmVersion,
+        slot_id: u8,
+        key_pair_id: u8,
+        cert_chain: Option<&[u8]>,
+    ) -> SpdmSetCertificateResult {
+        match SECRET_CERTIFICATE_INSTANCE.try_get_or_init(|| DEFAULT.clone()) {
+            Ok(instance) => {
+                (instance.set_certificate_cb)(spdm_version, slot_id, key_pair_id, cert_chain)
+            }
+            Err(_) => SpdmSetCertificateResult::Failed,
+        }
+    }
+}
+
+pub mod alias_cert {
+    use super::SECRET_ALIAS_CERT_INSTANCE;
+    use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmCertChainData, SpdmVersion};
+    use crate::secret::SpdmSecretAliasCert;
+
+    pub fn register(context: SpdmSecretAliasCert) -> bool {
+        SECRET_ALIAS_CERT_INSTANCE.try_init_once(|| context).is_ok()
+    }
+
+    static DEFAULT: SpdmSecretAliasCert = SpdmSecretAliasCert {
+        generate_alias_cert_chain_cb: |_spdm_version: SpdmVersion,
+                                       _base_hash_algo: SpdmBaseHashAlgo,
+                                       _base_asym_algo: SpdmBaseAsymAlgo,
+                                       _slot_id: u8|
+         -> Option<SpdmCertChainData> { unimplemented!() },
+    };
+
+    /// Regenerate the alias cert chain of slot_id from the current measurements.
+    pub fn generate_alias_cert_chain(
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        slot_id: u8,
+    ) -> Option<SpdmCertChainData> {
+        (SECRET_ALIAS_CERT_INSTANCE
+            .try_get_or_init(|| DEFAULT.clone())
+            .ok()?
+            .generate_alias_cert_chain_cb)(
+            spdm_version, base_hash_algo, base_asym_algo, slot_id
+        )
+    }
+}
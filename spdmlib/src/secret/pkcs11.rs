@@ -0,0 +1,204 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! asym_sign callback signing with a private key of a PKCS#11 token, so
+//! that the key never leaves the token.
+
+use super::{asym_sign, SpdmSecretAsymSign};
+use crate::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct};
+
+use conquer_once::spin::OnceCell;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::rsa::{PkcsMgfType, PkcsPssParams};
+use cryptoki::mechanism::{Mechanism, MechanismType};
+use cryptoki::object::{Attribute, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use std::sync::Mutex;
+
+/// Token and key used for signing.
+#[derive(Clone)]
+pub struct SpdmPkcs11Config {
+    /// Path of the PKCS#11 module, e.g. /usr/lib/softhsm/libsofthsm2.so.
+    pub module_path: String,
+    /// Label of the token, the first token present is used if None.
+    pub token_label: Option<String>,
+    /// User PIN of the token.
+    pub pin: String,
+    /// CKA_LABEL of the private key.
+    pub key_label: String,
+}
+
+struct SpdmPkcs11Signer {
+    session: Session,
+    key: ObjectHandle,
+}
+
+static PKCS11_SIGNER: OnceCell<Mutex<SpdmPkcs11Signer>> = OnceCell::uninit();
+
+static PKCS11_ASYM_SIGN: SpdmSecretAsymSign = SpdmSecretAsymSign { sign_cb: sign };
+
+/// Log in to the token and register the key as asym_sign callback.
+/// Returns false if the key cannot be found or a signer is already
+/// registered.
+pub fn register(config: &SpdmPkcs11Config) -> bool {
+    let signer = match open_signer(config) {
+        Ok(signer) => signer,
+        Err(e) => {
+            error!("!!! pkcs11 signer init fail: {} !!!\n", e);
+            return false;
+        }
+    };
+    PKCS11_SIGNER.try_init_once(|| Mutex::new(signer)).is_ok()
+        && asym_sign::register(PKCS11_ASYM_SIGN.clone())
+}
+
+fn open_signer(config: &SpdmPkcs11Config) -> Result<SpdmPkcs11Signer, cryptoki::error::Error> {
+    let pkcs11 = Pkcs11::new(&config.module_path)?;
+    pkcs11.initialize(CInitializeArgs::OsThreads)?;
+
+    let mut slot = None;
+    for candidate in pkcs11.get_slots_with_token()? {
+        let matched = match &config.token_label {
+            Some(token_label) => pkcs11.get_token_info(candidate)?.label() == token_label.as_str(),
+            None => true,
+        };
+        if matched {
+            slot = Some(candidate);
+            break;
+        }
+    }
+    let slot = slot.ok_or(cryptoki::error::Error::InvalidValue)?;
+
+    let session = pkcs11.open_ro_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(config.pin.clone())))?;
+    let key = session
+        .find_objects(&[
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::Label(config.key_label.as_bytes().to_vec()),
+        ])?
+        .first()
+        .copied()
+        .ok_or(cryptoki::error::Error::InvalidValue)?;
+
+    Ok(SpdmPkcs11Signer { session, key })
+}
+
+/// Mechanism signing data itself, or its digest for ECDSA. The CKM_ECDSA
+/// signature is r || s like the SPDM one.
+fn mechanism(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+) -> Option<Mechanism<'static>> {
+    let (hash_alg, mgf) = match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => (MechanismType::SHA256, PkcsMgfType::MGF1_SHA256),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => (MechanismType::SHA384, PkcsMgfType::MGF1_SHA384),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => (MechanismType::SHA512, PkcsMgfType::MGF1_SHA512),
+        _ => return None,
+    };
+    let pss = PkcsPssParams {
+        hash_alg,
+        mgf,
+        s_len: (base_hash_algo.get_size() as u64).into(),
+    };
+
+    match base_asym_algo {
+        SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256
+        | SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => Some(Mechanism::Ecdsa),
+        SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096 => match base_hash_algo {
+            SpdmBaseHashAlgo::TPM_ALG_SHA_256 => Some(Mechanism::Sha256RsaPkcs),
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384 => Some(Mechanism::Sha384RsaPkcs),
+            _ => Some(Mechanism::Sha512RsaPkcs),
+        },
+        SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => match base_hash_algo {
+            SpdmBaseHashAlgo::TPM_ALG_SHA_256 => Some(Mechanism::Sha256RsaPkcsPss(pss)),
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384 => Some(Mechanism::Sha384RsaPkcsPss(pss)),
+            _ => Some(Mechanism::Sha512RsaPkcsPss(pss)),
+        },
+        _ => None,
+    }
+}
+
+fn sign(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    data: &[u8],
+) -> Option<SpdmSignatureStruct> {
+    let mechanism = mechanism(base_hash_algo, base_asym_algo)?;
+    let signer = PKCS11_SIGNER.try_get().ok()?.lock().ok()?;
+
+    let signature = if matches!(mechanism, Mechanism::Ecdsa) {
+        let digest = crate::crypto::hash::hash_all(base_hash_algo, data)?;
+        signer.session.sign(&mechanism, signer.key, digest.as_ref())
+    } else {
+        signer.session.sign(&mechanism, signer.key, data)
+    };
+    let signature = match signature {
+        Ok(signature) => signature,
+        Err(e) => {
+            error!("!!! pkcs11 sign fail: {} !!!\n", e);
+            return None;
+        }
+    };
+    if signature.len() != base_asym_algo.get_size() as usize {
+        return None;
+    }
+
+    let mut spdm_signature = SpdmSignatureStruct {
+        data_size: signature.len() as u16,
+        ..Default::default()
+    };
+    spdm_signature.data[..signature.len()].copy_from_slice(&signature);
+    Some(spdm_signature)
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_mechanism() {
+        assert!(matches!(
+            mechanism(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
+            ),
+            Some(Mechanism::Ecdsa)
+        ));
+        assert!(matches!(
+            mechanism(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+                SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048
+            ),
+            Some(Mechanism::Sha256RsaPkcs)
+        ));
+        assert!(matches!(
+            mechanism(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072
+            ),
+            Some(Mechanism::Sha384RsaPkcsPss(_))
+        ));
+        assert!(mechanism(
+            SpdmBaseHashAlgo::empty(),
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256
+        )
+        .is_none());
+        assert!(mechanism(SpdmBaseHashAlgo::TPM_ALG_SHA_256, SpdmBaseAsymAlgo::empty()).is_none());
+    }
+
+    #[test]
+    fn test_case0_sign_unregistered() {
+        assert!(sign(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            b"hello, world"
+        )
+        .is_none());
+    }
+}
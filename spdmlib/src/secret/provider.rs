@@ -0,0 +1,187 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use super::{
+    asym_sign, certificate, csr, measurement, SpdmCsrResult, SpdmSecretAsymSign,
+    SpdmSecretCertificate, SpdmSecretCsr, SpdmSecretKeyPair, SpdmSecretMeasurement, SpdmSecretPsk,
+    SpdmSetCertificateResult, SpdmSetKeyPairInfoResult,
+};
+use crate::message::{SpdmKeyPairInfo, SpdmKeyPairInfoOperation};
+use crate::protocol::*;
+
+/// Secret callbacks of one SpdmContext, so that a process can run several
+/// contexts with different keys, e.g. when emulating many devices.
+/// A callback left None falls back to the one registered globally.
+#[derive(Clone, Default)]
+pub struct SpdmSecretProvider {
+    pub measurement: Option<SpdmSecretMeasurement>,
+    pub psk: Option<SpdmSecretPsk>,
+    pub asym_sign: Option<SpdmSecretAsymSign>,
+    pub csr: Option<SpdmSecretCsr>,
+    pub certificate: Option<SpdmSecretCertificate>,
+    pub key_pair: Option<SpdmSecretKeyPair>,
+}
+
+impl SpdmSecretProvider {
+    pub fn measurement_collection(
+        &self,
+        spdm_version: SpdmVersion,
+        measurement_specification: SpdmMeasurementSpecification,
+        measurement_hash_algo: SpdmMeasurementHashAlgo,
+        measurement_index: usize,
+    ) -> Option<SpdmMeasurementRecordStructure> {
+        match &self.measurement {
+            Some(instance) => (instance.measurement_collection_cb)(
+                spdm_version,
+                measurement_specification,
+                measurement_hash_algo,
+                measurement_index,
+            ),
+            None => measurement::measurement_collection(
+                spdm_version,
+                measurement_specification,
+                measurement_hash_algo,
+                measurement_index,
+            ),
+        }
+    }
+
+    pub fn generate_measurement_summary_hash(
+        &self,
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        measurement_specification: SpdmMeasurementSpecification,
+        measurement_hash_algo: SpdmMeasurementHashAlgo,
+        measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
+    ) -> Option<SpdmDigestStruct> {
+        match &self.measurement {
+            Some(instance) => (instance.generate_measurement_summary_hash_cb)(
+                spdm_version,
+                base_hash_algo,
+                measurement_specification,
+                measurement_hash_algo,
+                measurement_summary_hash_type,
+            ),
+            None => measurement::generate_measurement_summary_hash(
+                spdm_version,
+                base_hash_algo,
+                measurement_specification,
+                measurement_hash_algo,
+                measurement_summary_hash_type,
+            ),
+        }
+    }
+
+    pub fn sign(
+        &self,
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        data: &[u8],
+    ) -> Option<SpdmSignatureStruct> {
+        match &self.asym_sign {
+            Some(instance) => (instance.sign_cb)(base_hash_algo, base_asym_algo, data),
+            None => asym_sign::sign(base_hash_algo, base_asym_algo, data),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_csr(
+        &self,
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        csr_tracking_tag: u8,
+        requester_info: &[u8],
+        opaque_data: &[u8],
+        csr: &mut [u8],
+    ) -> SpdmCsrResult {
+        match &self.csr {
+            Some(instance) => (instance.generate_csr_cb)(
+                spdm_version,
+                base_hash_algo,
+                base_asym_algo,
+                csr_tracking_tag,
+                requester_info,
+                opaque_data,
+                csr,
+            ),
+            None => csr::generate_csr(
+                spdm_version,
+                base_hash_algo,
+                base_asym_algo,
+                csr_tracking_tag,
+                requester_info,
+                opaque_data,
+                csr,
+            ),
+        }
+    }
+
+    pub fn set_certificate(
+        &self,
+        spdm_version: SpdmVersion,
+        slot_id: u8,
+        key_pair_id: u8,
+        cert_chain: Option<&[u8]>,
+    ) -> SpdmSetCertificateResult {
+        match &self.certificate {
+            Some(instance) => {
+                (instance.set_certificate_cb)(spdm_version, slot_id, key_pair_id, cert_chain)
+            }
+            None => certificate::set_certificate(spdm_version, slot_id, key_pair_id, cert_chain),
+        }
+    }
+
+    /// Key pairs belong to the device behind this context, there is no
+    /// global fallback: without key_pair the request fails.
+    pub fn set_key_pair_info(
+        &self,
+        spdm_version: SpdmVersion,
+        operation: SpdmKeyPairInfoOperation,
+        key_pair_id: u8,
+        key_pair_info: &mut SpdmKeyPairInfo,
+    ) -> SpdmSetKeyPairInfoResult {
+        match &self.key_pair {
+            Some(instance) => {
+                (instance.set_key_pair_info_cb)(spdm_version, operation, key_pair_id, key_pair_info)
+            }
+            None => SpdmSetKeyPairInfoResult::Failed,
+        }
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    fn fake_sign(
+        _base_hash_algo: SpdmBaseHashAlgo,
+        _base_asym_algo: SpdmBaseAsymAlgo,
+        data: &[u8],
+    ) -> Option<SpdmSignatureStruct> {
+        let mut signature = SpdmSignatureStruct {
+            data_size: data.len() as u16,
+            ..Default::default()
+        };
+        signature.data[..data.len()].copy_from_slice(data);
+        Some(signature)
+    }
+
+    #[test]
+    fn test_case0_provider_overrides_global() {
+        let provider = SpdmSecretProvider {
+            asym_sign: Some(SpdmSecretAsymSign { sign_cb: fake_sign }),
+            ..Default::default()
+        };
+        let signature = provider
+            .sign(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &[1, 2, 3],
+            )
+            .unwrap();
+        assert_eq!(signature.data_size, 3);
+        assert_eq!(signature.data[..3], [1, 2, 3]);
+    }
+}
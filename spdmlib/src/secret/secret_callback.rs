@@ -2,10 +2,12 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
+use crate::message::{SpdmKeyPairInfo, SpdmKeyPairInfoOperation};
 use crate::protocol::{
-    SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmDigestStruct, SpdmHkdfOutputKeyingMaterial,
-    SpdmMeasurementHashAlgo, SpdmMeasurementRecordStructure, SpdmMeasurementSpecification,
-    SpdmMeasurementSummaryHashType, SpdmPskHintStruct, SpdmSignatureStruct, SpdmVersion,
+    SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmCertChainData, SpdmDigestStruct,
+    SpdmHkdfOutputKeyingMaterial, SpdmMeasurementHashAlgo, SpdmMeasurementRecordStructure,
+    SpdmMeasurementSpecification, SpdmMeasurementSummaryHashType, SpdmPskHintStruct,
+    SpdmSignatureStruct, SpdmVersion,
 };
 
 type SpdmMeasurementCollectionCbType = fn(
@@ -58,3 +60,84 @@ pub struct SpdmSecretAsymSign {
         data: &[u8],
     ) -> Option<SpdmSignatureStruct>,
 }
+
+/// Outcome of a CSR generation request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmCsrResult {
+    /// A DER CertificationRequest of this size was written to csr.
+    Generated(usize),
+    /// The device must be reset before the CSR is available.
+    /// Holds the CSRTrackingTag reported in ERROR(ResetRequired), 0 before SPDM 1.3.
+    ResetRequired(u8),
+    Failed,
+}
+
+#[derive(Clone)]
+pub struct SpdmSecretCsr {
+    pub generate_csr_cb: fn(
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        csr_tracking_tag: u8,
+        requester_info: &[u8],
+        opaque_data: &[u8],
+        csr: &mut [u8],
+    ) -> SpdmCsrResult,
+}
+
+/// Outcome of persisting a certificate chain received in SET_CERTIFICATE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmSetCertificateResult {
+    Stored,
+    /// The device must be reset before the new certificate chain is used.
+    ResetRequired,
+    Failed,
+}
+
+#[derive(Clone)]
+pub struct SpdmSecretCertificate {
+    /// cert_chain holds the DER certificates, without the SPDM cert chain header.
+    /// None erases the slot.
+    pub set_certificate_cb: fn(
+        spdm_version: SpdmVersion,
+        slot_id: u8,
+        key_pair_id: u8,
+        cert_chain: Option<&[u8]>,
+    ) -> SpdmSetCertificateResult,
+}
+
+/// Outcome of applying SET_KEY_PAIR_INFO to a key pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmSetKeyPairInfoResult {
+    Applied,
+    /// The device must be reset before the key pair change takes effect.
+    ResetRequired,
+    Failed,
+}
+
+#[derive(Clone)]
+pub struct SpdmSecretKeyPair {
+    /// key_pair_info holds the desired key usage, asym algo and cert slot association.
+    /// For SpdmKeyPairInfoGenerate the callback fills in the new public_key_info,
+    /// for SpdmKeyPairInfoErase it clears it.
+    pub set_key_pair_info_cb: fn(
+        spdm_version: SpdmVersion,
+        operation: SpdmKeyPairInfoOperation,
+        key_pair_id: u8,
+        key_pair_info: &mut SpdmKeyPairInfo,
+    ) -> SpdmSetKeyPairInfoResult,
+}
+
+#[derive(Clone)]
+pub struct SpdmSecretAliasCert {
+    /// Issue a new alias certificate of slot_id reflecting the current
+    /// measurements, e.g. after a firmware update of a DICE layer. The
+    /// returned chain holds the DER certificates, from the root to the new
+    /// alias certificate, without the SPDM cert chain header.
+    pub generate_alias_cert_chain_cb: fn(
+        spdm_version: SpdmVersion,
+        base_hash_algo: SpdmBaseHashAlgo,
+        base_asym_algo: SpdmBaseAsymAlgo,
+        slot_id: u8,
+    ) -> Option<SpdmCertChainData>,
+}
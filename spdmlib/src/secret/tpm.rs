@@ -0,0 +1,527 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Reference asym_sign and measurement callbacks backed by a TPM 2.0: the
+//! signing key is resident in the TPM and the measurement blocks are PCR
+//! values. The platform submits the marshalled commands to its TPM, e.g.
+//! through a TIS or CRB driver.
+
+use super::{asym_sign, measurement, SpdmSecretAsymSign, SpdmSecretMeasurement};
+use crate::crypto;
+use crate::protocol::*;
+
+use codec::{u16be, u24, u32be, Codec, Reader, Writer};
+use conquer_once::spin::OnceCell;
+
+#[derive(Clone)]
+pub struct SpdmTpm {
+    /// Send a TPM 2.0 command, return the size of the response written.
+    pub submit_command_cb: fn(command: &[u8], response: &mut [u8]) -> Option<usize>,
+    /// Handle of the signing key, e.g. the persistent handle 0x81000001.
+    /// The key must not be restricted.
+    pub key_handle: u32,
+    /// authValue of the key, sent in a password session.
+    pub key_auth: &'static [u8],
+    /// Measurement block N is the value of PCR pcrs[N - 1] in the bank of
+    /// the negotiated measurement hash algorithm.
+    pub pcrs: &'static [u8],
+}
+
+static TPM_INSTANCE: OnceCell<SpdmTpm> = OnceCell::uninit();
+
+static TPM_ASYM_SIGN: SpdmSecretAsymSign = SpdmSecretAsymSign { sign_cb: sign };
+
+static TPM_MEASUREMENT: SpdmSecretMeasurement = SpdmSecretMeasurement {
+    measurement_collection_cb: measurement_collection,
+    generate_measurement_summary_hash_cb: generate_measurement_summary_hash,
+};
+
+/// Register the TPM as asym_sign and measurement callbacks.
+/// Returns false if any of them was already registered.
+pub fn register(tpm: SpdmTpm) -> bool {
+    TPM_INSTANCE.try_init_once(|| tpm).is_ok()
+        && asym_sign::register(TPM_ASYM_SIGN.clone())
+        && measurement::register(TPM_MEASUREMENT.clone())
+}
+
+const TPM_ST_NO_SESSIONS: u16 = 0x8001;
+const TPM_ST_SESSIONS: u16 = 0x8002;
+const TPM_ST_HASHCHECK: u16 = 0x8024;
+const TPM_CC_SIGN: u32 = 0x0000_015D;
+const TPM_CC_PCR_READ: u32 = 0x0000_017E;
+const TPM_RS_PW: u32 = 0x4000_0009;
+const TPM_RH_NULL: u32 = 0x4000_0007;
+const TPM_RC_SUCCESS: u32 = 0;
+
+const TPM_ALG_SHA256: u16 = 0x000B;
+const TPM_ALG_SHA384: u16 = 0x000C;
+const TPM_ALG_SHA512: u16 = 0x000D;
+const TPM_ALG_RSASSA: u16 = 0x0014;
+const TPM_ALG_RSAPSS: u16 = 0x0016;
+const TPM_ALG_ECDSA: u16 = 0x0018;
+
+const TPM_HEADER_SIZE: usize = 10;
+const TPM_MAX_COMMAND_SIZE: usize = 1024;
+const TPM_MAX_RESPONSE_SIZE: usize = 1024;
+// PCR_SELECT_MAX of a PC client TPM, 24 PCRs
+const TPM_PCR_SELECT_SIZE: usize = 3;
+
+fn tpm_hash_alg(base_hash_algo: SpdmBaseHashAlgo) -> Option<u16> {
+    match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => Some(TPM_ALG_SHA256),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => Some(TPM_ALG_SHA384),
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => Some(TPM_ALG_SHA512),
+        _ => None,
+    }
+}
+
+fn put_u16(writer: &mut Writer, value: u16) -> Option<()> {
+    u16be::new(value).encode(writer).ok().map(|_| ())
+}
+
+fn put_u32(writer: &mut Writer, value: u32) -> Option<()> {
+    u32be::new(value).encode(writer).ok().map(|_| ())
+}
+
+fn put_tpm2b(writer: &mut Writer, value: &[u8]) -> Option<()> {
+    put_u16(writer, value.len() as u16)?;
+    writer.extend_from_slice(value).map(|_| ())
+}
+
+fn get_tpm2b<'a>(reader: &mut Reader<'a>) -> Option<&'a [u8]> {
+    let size = u16be::read(reader)?.get() as usize;
+    reader.take(size)
+}
+
+/// Command header, the commandSize is set by transact.
+fn put_header(writer: &mut Writer, tag: u16, command_code: u32) -> Option<()> {
+    put_u16(writer, tag)?;
+    put_u32(writer, 0)?;
+    put_u32(writer, command_code)
+}
+
+/// Submit command and return a reader of the response after its header if
+/// it succeeded.
+fn transact<'a>(tpm: &SpdmTpm, command: &mut [u8], response: &'a mut [u8]) -> Option<Reader<'a>> {
+    let command_size = command.len() as u32;
+    command[2..6].copy_from_slice(&command_size.to_be_bytes());
+
+    let response_size = (tpm.submit_command_cb)(command, response)?;
+    if response_size < TPM_HEADER_SIZE || response_size > response.len() {
+        return None;
+    }
+    let mut reader = Reader::init(&response[..response_size]);
+    u16be::read(&mut reader)?;
+    if u32be::read(&mut reader)?.get() as usize != response_size {
+        return None;
+    }
+    let response_code = u32be::read(&mut reader)?.get();
+    if response_code != TPM_RC_SUCCESS {
+        error!("!!! tpm command fail: {:x} !!!\n", response_code);
+        return None;
+    }
+    Some(reader)
+}
+
+/// TPM2_PCR_Read of a single PCR.
+fn pcr_read(tpm: &SpdmTpm, hash_alg: u16, pcr: u8) -> Option<SpdmDigestStruct> {
+    if pcr as usize >= TPM_PCR_SELECT_SIZE * 8 {
+        return None;
+    }
+    let mut pcr_select = [0u8; TPM_PCR_SELECT_SIZE];
+    pcr_select[pcr as usize / 8] = 1 << (pcr % 8);
+
+    let mut command = [0u8; TPM_MAX_COMMAND_SIZE];
+    let mut writer = Writer::init(&mut command);
+    put_header(&mut writer, TPM_ST_NO_SESSIONS, TPM_CC_PCR_READ)?;
+    // pcrSelectionIn
+    put_u32(&mut writer, 1)?;
+    put_u16(&mut writer, hash_alg)?;
+    writer.push(TPM_PCR_SELECT_SIZE as u8)?;
+    writer.extend_from_slice(&pcr_select)?;
+    let command_size = writer.used();
+
+    let mut response = [0u8; TPM_MAX_RESPONSE_SIZE];
+    let mut reader = transact(tpm, &mut command[..command_size], &mut response)?;
+    // pcrUpdateCounter
+    u32be::read(&mut reader)?;
+    // pcrSelectionOut, empty if the bank is not allocated
+    let selection_count = u32be::read(&mut reader)?.get();
+    for _ in 0..selection_count {
+        u16be::read(&mut reader)?;
+        let size_of_select = u8::read(&mut reader)?;
+        reader.take(size_of_select as usize)?;
+    }
+    // pcrValues
+    if u32be::read(&mut reader)?.get() != 1 {
+        return None;
+    }
+    let digest = get_tpm2b(&mut reader)?;
+    if digest.len() > SPDM_MAX_HASH_SIZE {
+        return None;
+    }
+    Some(SpdmDigestStruct::from(digest))
+}
+
+/// TPM2_Sign of the digest of data. ECDSA signatures are returned as
+/// r || s, each left padded to the size of the curve.
+fn tpm_sign(
+    tpm: &SpdmTpm,
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    data: &[u8],
+) -> Option<SpdmSignatureStruct> {
+    let hash_alg = tpm_hash_alg(base_hash_algo)?;
+    let scheme = match base_asym_algo {
+        SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256
+        | SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => TPM_ALG_ECDSA,
+        SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096 => TPM_ALG_RSASSA,
+        SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => TPM_ALG_RSAPSS,
+        _ => return None,
+    };
+    let digest = crypto::hash::hash_all(base_hash_algo, data)?;
+
+    let mut command = [0u8; TPM_MAX_COMMAND_SIZE];
+    let mut writer = Writer::init(&mut command);
+    put_header(&mut writer, TPM_ST_SESSIONS, TPM_CC_SIGN)?;
+    put_u32(&mut writer, tpm.key_handle)?;
+    // authorizationSize and the password session
+    put_u32(&mut writer, (4 + 2 + 1 + 2 + tpm.key_auth.len()) as u32)?;
+    put_u32(&mut writer, TPM_RS_PW)?;
+    put_tpm2b(&mut writer, &[])?;
+    writer.push(0)?;
+    put_tpm2b(&mut writer, tpm.key_auth)?;
+    // digest, inScheme and a NULL validation ticket
+    put_tpm2b(&mut writer, digest.as_ref())?;
+    put_u16(&mut writer, scheme)?;
+    put_u16(&mut writer, hash_alg)?;
+    put_u16(&mut writer, TPM_ST_HASHCHECK)?;
+    put_u32(&mut writer, TPM_RH_NULL)?;
+    put_tpm2b(&mut writer, &[])?;
+    let command_size = writer.used();
+
+    let mut response = [0u8; TPM_MAX_RESPONSE_SIZE];
+    let mut reader = transact(tpm, &mut command[..command_size], &mut response)?;
+    // parameterSize
+    u32be::read(&mut reader)?;
+    if u16be::read(&mut reader)?.get() != scheme || u16be::read(&mut reader)?.get() != hash_alg {
+        return None;
+    }
+
+    let signature_size = base_asym_algo.get_size() as usize;
+    let mut signature = SpdmSignatureStruct {
+        data_size: signature_size as u16,
+        ..Default::default()
+    };
+    if scheme == TPM_ALG_ECDSA {
+        let half_size = signature_size / 2;
+        let r = get_tpm2b(&mut reader)?;
+        let s = get_tpm2b(&mut reader)?;
+        if r.len() > half_size || s.len() > half_size {
+            return None;
+        }
+        signature.data[half_size - r.len()..half_size].copy_from_slice(r);
+        signature.data[signature_size - s.len()..signature_size].copy_from_slice(s);
+    } else {
+        let sig = get_tpm2b(&mut reader)?;
+        if sig.len() != signature_size {
+            return None;
+        }
+        signature.data[..signature_size].copy_from_slice(sig);
+    }
+    Some(signature)
+}
+
+/// Measurement block of PCR pcrs[index - 1], a mutable firmware digest.
+fn pcr_measurement_block(
+    tpm: &SpdmTpm,
+    hash_alg: u16,
+    index: usize,
+) -> Option<SpdmMeasurementBlockStructure> {
+    let pcr = *tpm.pcrs.get(index.checked_sub(1)?)?;
+    let value = pcr_read(tpm, hash_alg, pcr)?;
+    Some(SpdmMeasurementBlockStructure::new(
+        index as u8,
+        SpdmDmtfMeasurementStructure::mutable_firmware(
+            SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementDigest,
+            value.as_ref(),
+        )?,
+    ))
+}
+
+fn measurement_record(
+    tpm: &SpdmTpm,
+    hash_alg: u16,
+    indices: core::ops::RangeInclusive<usize>,
+) -> Option<SpdmMeasurementRecordStructure> {
+    let mut record = SpdmMeasurementRecordStructure::default();
+    let mut writer = Writer::init(&mut record.measurement_record_data);
+    let mut number_of_blocks = 0u8;
+    for index in indices {
+        pcr_measurement_block(tpm, hash_alg, index)?
+            .encode(&mut writer)
+            .ok()?;
+        number_of_blocks += 1;
+    }
+    let measurement_record_length = writer.used() as u32;
+    record.number_of_blocks = number_of_blocks;
+    record.measurement_record_length = u24::new(measurement_record_length);
+    Some(record)
+}
+
+fn tpm_measurement_collection(
+    tpm: &SpdmTpm,
+    measurement_specification: SpdmMeasurementSpecification,
+    measurement_hash_algo: SpdmMeasurementHashAlgo,
+    measurement_index: usize,
+) -> Option<SpdmMeasurementRecordStructure> {
+    if measurement_specification != SpdmMeasurementSpecification::DMTF {
+        return None;
+    }
+    let hash_alg = match measurement_hash_algo {
+        SpdmMeasurementHashAlgo::TPM_ALG_SHA_256 => TPM_ALG_SHA256,
+        SpdmMeasurementHashAlgo::TPM_ALG_SHA_384 => TPM_ALG_SHA384,
+        SpdmMeasurementHashAlgo::TPM_ALG_SHA_512 => TPM_ALG_SHA512,
+        _ => return None,
+    };
+
+    let number_of_blocks = tpm.pcrs.len();
+    if measurement_index
+        == SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber.get_u8() as usize
+    {
+        Some(SpdmMeasurementRecordStructure {
+            number_of_blocks: number_of_blocks as u8,
+            ..Default::default()
+        })
+    } else if measurement_index
+        == SpdmMeasurementOperation::SpdmMeasurementRequestAll.get_u8() as usize
+    {
+        measurement_record(tpm, hash_alg, 1..=number_of_blocks)
+    } else if (1..=number_of_blocks).contains(&measurement_index) {
+        measurement_record(tpm, hash_alg, measurement_index..=measurement_index)
+    } else {
+        None
+    }
+}
+
+/// Hash of all the measurement blocks, the PCRs all measure the TCB.
+fn tpm_measurement_summary_hash(
+    tpm: &SpdmTpm,
+    base_hash_algo: SpdmBaseHashAlgo,
+    measurement_specification: SpdmMeasurementSpecification,
+    measurement_hash_algo: SpdmMeasurementHashAlgo,
+    measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
+) -> Option<SpdmDigestStruct> {
+    match measurement_summary_hash_type {
+        SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll
+        | SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeTcb => {
+            let record = tpm_measurement_collection(
+                tpm,
+                measurement_specification,
+                measurement_hash_algo,
+                SpdmMeasurementOperation::SpdmMeasurementRequestAll.get_u8() as usize,
+            )?;
+            crypto::hash::hash_all(
+                base_hash_algo,
+                &record.measurement_record_data[..record.measurement_record_length.get() as usize],
+            )
+        }
+        _ => None,
+    }
+}
+
+fn sign(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    data: &[u8],
+) -> Option<SpdmSignatureStruct> {
+    tpm_sign(
+        TPM_INSTANCE.try_get().ok()?,
+        base_hash_algo,
+        base_asym_algo,
+        data,
+    )
+}
+
+fn measurement_collection(
+    _spdm_version: SpdmVersion,
+    measurement_specification: SpdmMeasurementSpecification,
+    measurement_hash_algo: SpdmMeasurementHashAlgo,
+    measurement_index: usize,
+) -> Option<SpdmMeasurementRecordStructure> {
+    tpm_measurement_collection(
+        TPM_INSTANCE.try_get().ok()?,
+        measurement_specification,
+        measurement_hash_algo,
+        measurement_index,
+    )
+}
+
+fn generate_measurement_summary_hash(
+    _spdm_version: SpdmVersion,
+    base_hash_algo: SpdmBaseHashAlgo,
+    measurement_specification: SpdmMeasurementSpecification,
+    measurement_hash_algo: SpdmMeasurementHashAlgo,
+    measurement_summary_hash_type: SpdmMeasurementSummaryHashType,
+) -> Option<SpdmDigestStruct> {
+    tpm_measurement_summary_hash(
+        TPM_INSTANCE.try_get().ok()?,
+        base_hash_algo,
+        measurement_specification,
+        measurement_hash_algo,
+        measurement_summary_hash_type,
+    )
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+    use core::convert::TryInto;
+
+    // PCR n holds n in every byte, signatures hold 0x01 in r and 0x02 in s,
+    // with r one byte short.
+    fn fake_submit_command(command: &[u8], response: &mut [u8]) -> Option<usize> {
+        let command_code = u32::from_be_bytes(command[6..10].try_into().unwrap());
+        let mut writer = Writer::init(response);
+        put_header(&mut writer, TPM_ST_NO_SESSIONS, TPM_RC_SUCCESS)?;
+        match command_code {
+            TPM_CC_PCR_READ => {
+                let hash_alg = u16::from_be_bytes(command[14..16].try_into().unwrap());
+                let pcr_select = &command[17..20];
+                let pcr = (0..24u8)
+                    .find(|pcr| pcr_select[*pcr as usize / 8] & (1 << (pcr % 8)) != 0)
+                    .unwrap();
+                let size = match hash_alg {
+                    TPM_ALG_SHA256 => 32,
+                    TPM_ALG_SHA384 => 48,
+                    _ => 64,
+                };
+                put_u32(&mut writer, 7)?;
+                writer.extend_from_slice(&command[10..20])?;
+                put_u32(&mut writer, 1)?;
+                put_tpm2b(&mut writer, &[pcr; 64][..size])?;
+            }
+            TPM_CC_SIGN => {
+                assert_eq!(command[10..14], 0x8100_0001u32.to_be_bytes());
+                // after the password session with the 4 bytes auth
+                let digest_offset = 14 + 4 + 4 + 2 + 1 + 2 + 4;
+                let digest_size = u16::from_be_bytes(
+                    command[digest_offset..digest_offset + 2]
+                        .try_into()
+                        .unwrap(),
+                ) as usize;
+                let scheme_offset = digest_offset + 2 + digest_size;
+                let half_size = if digest_size == 32 { 32 } else { 48 };
+                put_u32(&mut writer, 0)?;
+                writer.extend_from_slice(&command[scheme_offset..scheme_offset + 4])?;
+                put_tpm2b(&mut writer, &[0x01; 48][..half_size - 1])?;
+                put_tpm2b(&mut writer, &[0x02; 48][..half_size])?;
+            }
+            _ => return None,
+        }
+        let size = writer.used();
+        response[2..6].copy_from_slice(&(size as u32).to_be_bytes());
+        Some(size)
+    }
+
+    fn fake_tpm() -> SpdmTpm {
+        SpdmTpm {
+            submit_command_cb: fake_submit_command,
+            key_handle: 0x8100_0001,
+            key_auth: b"auth",
+            pcrs: &[0, 2, 7],
+        }
+    }
+
+    #[test]
+    fn test_case0_pcr_read() {
+        let tpm = fake_tpm();
+        let digest = pcr_read(&tpm, TPM_ALG_SHA384, 7).unwrap();
+        assert_eq!(digest.as_ref(), &[7u8; 48]);
+        assert!(pcr_read(&tpm, TPM_ALG_SHA384, 24).is_none());
+    }
+
+    #[test]
+    fn test_case0_measurement_collection() {
+        let tpm = fake_tpm();
+        let record = tpm_measurement_collection(
+            &tpm,
+            SpdmMeasurementSpecification::DMTF,
+            SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+            0,
+        )
+        .unwrap();
+        assert_eq!(record.number_of_blocks, 3);
+
+        let record = tpm_measurement_collection(
+            &tpm,
+            SpdmMeasurementSpecification::DMTF,
+            SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+            0xFF,
+        )
+        .unwrap();
+        let blocks = record.get_measurement_blocks().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[2].index, 3);
+        assert_eq!(
+            blocks[2].measurement.value[..blocks[2].measurement.value_size as usize],
+            [7u8; 48]
+        );
+
+        let record = tpm_measurement_collection(
+            &tpm,
+            SpdmMeasurementSpecification::DMTF,
+            SpdmMeasurementHashAlgo::TPM_ALG_SHA_256,
+            2,
+        )
+        .unwrap();
+        let blocks = record.get_measurement_blocks().unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].index, 2);
+        assert_eq!(blocks[0].measurement.value_size, 32);
+
+        assert!(tpm_measurement_collection(
+            &tpm,
+            SpdmMeasurementSpecification::DMTF,
+            SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+            4,
+        )
+        .is_none());
+        assert!(tpm_measurement_collection(
+            &tpm,
+            SpdmMeasurementSpecification::DMTF,
+            SpdmMeasurementHashAlgo::RAW_BIT_STREAM,
+            1,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_case0_sign() {
+        let tpm = fake_tpm();
+        let signature = tpm_sign(
+            &tpm,
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            b"hello, world",
+        )
+        .unwrap();
+        assert_eq!(signature.data_size, 96);
+        assert_eq!(signature.data[0], 0);
+        assert_eq!(signature.data[1..48], [0x01u8; 47]);
+        assert_eq!(signature.data[48..96], [0x02u8; 48]);
+
+        assert!(tpm_sign(
+            &tpm,
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmBaseAsymAlgo::empty(),
+            b"hello, world",
+        )
+        .is_none());
+    }
+}
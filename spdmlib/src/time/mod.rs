@@ -2,22 +2,33 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
+//! Time source of the requester. Without a registered SpdmTime the requester
+//! neither checks the response timeouts nor waits for RESPOND_IF_READY, and
+//! the device_io receive timeout is the only one applied.
+
 mod time_callbacks;
 
 pub use time_callbacks::SpdmTime;
 
 use conquer_once::spin::OnceCell;
+use core::task::Waker;
 
 static TIME_INSTANCE: OnceCell<SpdmTime> = OnceCell::uninit();
 
 static DEFAULT: SpdmTime = SpdmTime {
     sleep_cb: |_: usize| unimplemented!(),
+    now_us_cb: || unimplemented!(),
+    wake_at_cb: None,
 };
 
 pub fn register(context: SpdmTime) -> bool {
     TIME_INSTANCE.try_init_once(|| context).is_ok()
 }
 
+pub fn is_registered() -> bool {
+    TIME_INSTANCE.is_initialized()
+}
+
 pub fn sleep(us: usize) {
     (TIME_INSTANCE
         .try_get_or_init(|| DEFAULT.clone())
@@ -25,3 +36,29 @@ pub fn sleep(us: usize) {
         .unwrap()
         .sleep_cb)(us)
 }
+
+pub fn now_us() -> u64 {
+    (TIME_INSTANCE
+        .try_get_or_init(|| DEFAULT.clone())
+        .ok()
+        .unwrap()
+        .now_us_cb)()
+}
+
+/// Microseconds since start, a value returned by now_us().
+pub fn elapsed_us(start: u64) -> u64 {
+    now_us().saturating_sub(start)
+}
+
+/// Wake waker once now_us() reaches deadline_us. Without a wake_at callback
+/// the waker is woken right away, so that the task is polled again.
+pub fn wake_at(deadline_us: u64, waker: &Waker) {
+    match TIME_INSTANCE
+        .try_get()
+        .ok()
+        .and_then(|time| time.wake_at_cb)
+    {
+        Some(wake_at_cb) => wake_at_cb(deadline_us, waker.clone()),
+        None => waker.wake_by_ref(),
+    }
+}
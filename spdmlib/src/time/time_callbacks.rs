@@ -2,7 +2,33 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
+use core::task::Waker;
+
 #[derive(Clone)]
 pub struct SpdmTime {
     pub sleep_cb: fn(us: usize),
+    pub(crate) now_us_cb: fn() -> u64,
+    pub(crate) wake_at_cb: Option<fn(deadline_us: u64, waker: Waker)>,
+}
+
+impl SpdmTime {
+    /// now_us_cb returns a monotonic time in microseconds, the origin is up
+    /// to the implementation. It must not follow wall clock adjustments.
+    pub const fn new(sleep_cb: fn(us: usize), now_us_cb: fn() -> u64) -> Self {
+        SpdmTime {
+            sleep_cb,
+            now_us_cb,
+            wake_at_cb: None,
+        }
+    }
+
+    /// wake_at_cb wakes waker once now_us_cb reaches deadline_us, e.g. with
+    /// a timer of the executor. The async-io streams wait with it instead of
+    /// being polled again right away.
+    pub const fn with_wake_at(self, wake_at_cb: fn(deadline_us: u64, waker: Waker)) -> Self {
+        SpdmTime {
+            wake_at_cb: Some(wake_at_cb),
+            ..self
+        }
+    }
 }
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! The hash engine is registered for the whole process, this test runs in a
+//! binary of its own so that the other tests keep the software contexts.
+
+#![cfg(all(
+    feature = "hashed-transcript-data",
+    any(feature = "spdm-ring", feature = "spdm-rustcrypto")
+))]
+
+use spdmlib::crypto::{hash, SpdmHwHash};
+use spdmlib::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR};
+use spdmlib::protocol::{SpdmBaseHashAlgo, SpdmDigestStruct};
+use std::sync::Mutex;
+
+const ENGINE_CONTEXT_COUNT: usize = 2;
+
+// An engine supporting SHA-512 only, with two contexts buffering the data.
+static ENGINE: Mutex<[Option<Vec<u8>>; ENGINE_CONTEXT_COUNT]> = Mutex::new([None, None]);
+
+fn engine_alloc(data: Vec<u8>) -> Option<usize> {
+    let mut engine = ENGINE.lock().unwrap();
+    let index = engine.iter().position(|ctx| ctx.is_none())?;
+    engine[index] = Some(data);
+    Some(index + 1)
+}
+
+fn engine_init(base_hash_algo: SpdmBaseHashAlgo) -> Option<usize> {
+    match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_512 => engine_alloc(Vec::new()),
+        _ => None,
+    }
+}
+
+fn engine_update(ctx: usize, data: &[u8]) -> SpdmResult {
+    let mut engine = ENGINE.lock().unwrap();
+    engine[ctx - 1]
+        .as_mut()
+        .ok_or(SPDM_STATUS_CRYPTO_ERROR)?
+        .extend_from_slice(data);
+    Ok(())
+}
+
+fn engine_finalize(ctx: usize) -> Option<SpdmDigestStruct> {
+    let data = ENGINE.lock().unwrap()[ctx - 1].take()?;
+    hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_512, &data)
+}
+
+fn engine_dup(ctx: usize) -> Option<usize> {
+    let data = ENGINE.lock().unwrap()[ctx - 1].clone()?;
+    engine_alloc(data)
+}
+
+#[test]
+fn test_case0_hw_hash() {
+    hash::register_hw(SpdmHwHash {
+        hash_ctx_init_cb: engine_init,
+        hash_ctx_update_cb: engine_update,
+        hash_ctx_finalize_cb: engine_finalize,
+        hash_ctx_dup_cb: engine_dup,
+    });
+
+    let ctx = hash::hash_ctx_init(SpdmBaseHashAlgo::TPM_ALG_SHA_256).unwrap();
+    assert!(!ctx.is_hw());
+
+    let mut ctx = hash::hash_ctx_init(SpdmBaseHashAlgo::TPM_ALG_SHA_512).unwrap();
+    assert!(ctx.is_hw());
+    hash::hash_ctx_update(&mut ctx, b"hello, ").unwrap();
+    let mut ctx2 = hash::hash_ctx_dup(&ctx).unwrap();
+    assert!(ctx2.is_hw());
+
+    // Both engine contexts are in use.
+    assert!(hash::hash_ctx_dup(&ctx).is_none());
+    let mut ctx3 = ctx.clone();
+    assert!(hash::hash_ctx_update(&mut ctx3, b"world").is_err());
+    assert!(hash::hash_ctx_finalize(ctx3).is_none());
+
+    hash::hash_ctx_update(&mut ctx2, b"world").unwrap();
+    let res = hash::hash_ctx_finalize(ctx2).unwrap();
+    let md = hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_512, b"hello, world").unwrap();
+    assert_eq!(res.as_ref(), md.as_ref());
+}
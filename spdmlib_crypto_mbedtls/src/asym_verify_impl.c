@@ -5,6 +5,7 @@
 
 #include <mbedtls/x509.h>
 #include <mbedtls/x509_crt.h>
+#include <mbedtls/pk.h>
 
 /**
  * Verifies RSASSA and Ecdsa signature.
@@ -102,3 +103,68 @@ int spdm_rsa_pss_verify(
 
     return ret;
 }
+
+/**
+ * Verifies RSASSA, RSA-PSS and Ecdsa signature with a public key.
+ *
+ * @param[in]  md_type         Hash algorithm used.
+ * @param[in]  pss             Non zero for RSA-PSS verification.
+ * @param[in]  public_key      DER SubjectPublicKeyInfo.
+ * @param[in]  public_key_size Public key size in bytes.
+ * @param[in]  data            Pointer to octet data to be checked (hash).
+ * @param[in]  data_size       Size of the data in bytes.
+ * @param[in]  signature       Pointer to signature to be verified.
+ * @param[in]  signature_size  Size of signature in bytes.
+ * @param[in]  key_size        Expected key size in bytes, the RSA modulus
+ *                             size or the size of an ECC coordinate.
+ *
+ * @retval  0       Valid signature.
+ * @retval  not 0   Invalid signature.
+ *
+ **/
+int spdm_pub_key_verify(
+    const int md_type, const int pss,
+    const uint8_t *public_key, size_t public_key_size,
+    const uint8_t *data, size_t data_size,
+    const uint8_t *signature, size_t signature_size,
+    size_t key_size)
+{
+    mbedtls_pk_context pk;
+    int ret;
+
+    mbedtls_pk_init(&pk);
+
+    ret = mbedtls_pk_parse_public_key(&pk, public_key, public_key_size);
+
+    // the key must match the negotiated algorithm
+    if (ret == 0 && mbedtls_pk_get_len(&pk) != key_size)
+    {
+        ret = MBEDTLS_ERR_PK_INVALID_PUBKEY;
+    }
+
+    if (ret == 0)
+    {
+        if (pss)
+        {
+            if (mbedtls_pk_get_type(&pk) != MBEDTLS_PK_RSA)
+            {
+                ret = MBEDTLS_ERR_PK_TYPE_MISMATCH;
+            }
+            else
+            {
+                ret = mbedtls_rsa_rsassa_pss_verify(
+                    mbedtls_pk_rsa(pk), NULL, NULL,
+                    MBEDTLS_RSA_PUBLIC,
+                    md_type, data_size, data, signature);
+            }
+        }
+        else
+        {
+            ret = mbedtls_pk_verify(&pk, md_type, data, data_size, signature, signature_size);
+        }
+    }
+
+    mbedtls_pk_free(&pk);
+
+    return ret;
+}
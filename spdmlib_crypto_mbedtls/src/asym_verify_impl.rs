@@ -12,8 +12,8 @@ pub static DEFAULT: SpdmAsymVerify = SpdmAsymVerify {
 
 use core::ffi::c_int;
 
-const MBEDTLS_MD_SHA256: c_int = 6;
-const MBEDTLS_MD_SHA384: c_int = 7;
+pub(crate) const MBEDTLS_MD_SHA256: c_int = 6;
+pub(crate) const MBEDTLS_MD_SHA384: c_int = 7;
 use super::ffi::{spdm_pk_verify, spdm_rsa_pss_verify};
 
 fn asym_verify(
@@ -101,7 +101,10 @@ fn asym_verify(
 }
 
 // add ASN.1 for the ECDSA binary signature
-fn ecc_signature_bin_to_der(signature: &[u8], der_signature: &mut [u8]) -> SpdmResult<usize> {
+pub(crate) fn ecc_signature_bin_to_der(
+    signature: &[u8],
+    der_signature: &mut [u8],
+) -> SpdmResult<usize> {
     let sign_size = signature.len();
     let half_size = sign_size / 2;
 
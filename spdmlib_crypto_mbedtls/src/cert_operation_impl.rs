@@ -3,13 +3,15 @@
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
 extern crate alloc;
+use alloc::vec::Vec;
 
-use spdmlib::crypto::SpdmCertOperation;
+use spdmlib::crypto::{get_cert_chain_validity, SpdmCertOperation};
 use spdmlib::error::{SpdmResult, SPDM_STATUS_INVALID_CERT};
 
 pub static DEFAULT: SpdmCertOperation = SpdmCertOperation {
     get_cert_from_cert_chain_cb: get_cert_from_cert_chain,
     verify_cert_chain_cb: verify_cert_chain,
+    verify_cert_cb: verify_cert,
 };
 
 use crate::ffi::spdm_verify_cert_chain;
@@ -43,7 +45,10 @@ fn get_cert_from_cert_chain(cert_chain: &[u8], index: isize) -> SpdmResult<(usiz
     }
 }
 
-fn verify_cert_chain(cert_chain: &[u8]) -> SpdmResult {
+// MBEDTLS_HAVE_TIME_DATE is not enabled, the validity periods of all the
+// certificates are checked against time here.
+fn verify_cert_chain(cert_chain: &[u8], time: Option<u64>) -> SpdmResult {
+    check_validity(cert_chain, time)?;
     let ret = unsafe { spdm_verify_cert_chain(cert_chain.as_ptr(), cert_chain.len()) };
     if ret == 0 {
         Ok(())
@@ -52,6 +57,29 @@ fn verify_cert_chain(cert_chain: &[u8]) -> SpdmResult {
     }
 }
 
+// The issuer is the trust anchor of a two certificates chain, the validity
+// period of the certificate is checked against time.
+fn verify_cert(cert: &[u8], issuer_cert: &[u8], time: Option<u64>) -> SpdmResult {
+    check_validity(cert, time)?;
+    let mut cert_chain = Vec::with_capacity(issuer_cert.len() + cert.len());
+    cert_chain.extend_from_slice(issuer_cert);
+    cert_chain.extend_from_slice(cert);
+    verify_cert_chain(&cert_chain, None)
+}
+
+// mbedtls has no clock, the validity periods are checked here if time is
+// given.
+fn check_validity(cert_chain: &[u8], time: Option<u64>) -> SpdmResult {
+    if let Some(time) = time {
+        let (not_before, not_after) =
+            get_cert_chain_validity(cert_chain).map_err(|_| SPDM_STATUS_INVALID_CERT)?;
+        if time < not_before || time > not_after {
+            return Err(SPDM_STATUS_INVALID_CERT);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(all(test,))]
 mod tests {
     use super::*;
@@ -95,7 +123,29 @@ mod tests {
         let status = get_cert_from_cert_chain(cert_chain, -1).is_ok();
         assert!(status);
 
-        let status = verify_cert_chain(cert_chain).is_ok();
+        let status = verify_cert_chain(cert_chain, None).is_ok();
         assert!(status);
     }
+
+    #[test]
+    fn test_case0_verify_cert() {
+        let cert_chain = &include_bytes!("public_cert.der")[..];
+        let (ca_begin, ca_end) = get_cert_from_cert_chain(cert_chain, 0).unwrap();
+        let (cert_begin, cert_end) = get_cert_from_cert_chain(cert_chain, 1).unwrap();
+        let ca = &cert_chain[ca_begin..ca_end];
+        let cert = &cert_chain[cert_begin..cert_end];
+        let (not_before, not_after) = get_cert_chain_validity(cert).unwrap();
+
+        assert!(verify_cert(cert, ca, Some(not_before.saturating_sub(1))).is_err());
+        assert!(verify_cert(cert, ca, Some(not_after + 1)).is_err());
+    }
+
+    #[test]
+    fn test_case0_verify_cert_chain() {
+        let cert_chain = &include_bytes!("public_cert.der")[..];
+        let (not_before, not_after) = get_cert_chain_validity(cert_chain).unwrap();
+        assert!(verify_cert_chain(cert_chain, Some(not_before)).is_ok());
+        assert!(verify_cert_chain(cert_chain, Some(not_before.saturating_sub(1))).is_err());
+        assert!(verify_cert_chain(cert_chain, Some(not_after + 1)).is_err());
+    }
 }
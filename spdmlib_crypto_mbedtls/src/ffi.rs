@@ -85,6 +85,18 @@ extern "C" {
         signature_size: usize,
     ) -> c_int;
 
+    pub fn spdm_pub_key_verify(
+        md_type: c_int,
+        pss: c_int,
+        public_key: *const c_uchar,
+        public_key_size: usize,
+        data: *const c_uchar,
+        data_size: usize,
+        signature: *const c_uchar,
+        signature_size: usize,
+        key_size: usize,
+    ) -> c_int;
+
     pub fn spdm_verify_cert_chain(certchain: *const c_uchar, certchain_size: usize) -> c_int;
 
     pub fn spdm_ecdh_compute_shared_p256(
@@ -17,11 +17,27 @@ pub mod dhe_impl;
 pub mod hash_impl;
 pub mod hkdf_impl;
 pub mod hmac_impl;
+pub mod pub_key_verify_impl;
 pub mod rand_impl;
 
 #[cfg(any(target_os = "uefi", target_os = "none"))]
 mod platform_support;
 
+/// Register the mbedtls implementations of all spdmlib crypto callbacks.
+/// Returns false if any callback was already registered.
+pub fn register_handles() -> bool {
+    let mut registered = spdmlib::crypto::aead::register(aead_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::asym_verify::register(asym_verify_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::cert_operation::register(cert_operation_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::dhe::register(dhe_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::hash::register(hash_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::hkdf::register(hkdf_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::hmac::register(hmac_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::pub_key_verify::register(pub_key_verify_impl::DEFAULT.clone());
+    registered &= spdmlib::crypto::rand::register(rand_impl::DEFAULT.clone());
+    registered
+}
+
 #[no_mangle]
 pub extern "C" fn mbedtls_param_failed() {
     panic!("mbedtls_param_failed fail called")
@@ -0,0 +1,151 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use spdmlib::crypto::SpdmPubKeyVerify;
+use spdmlib::error::{SpdmResult, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_VERIF_FAIL};
+use spdmlib::protocol::{SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmSignatureStruct};
+
+use super::asym_verify_impl::{ecc_signature_bin_to_der, MBEDTLS_MD_SHA256, MBEDTLS_MD_SHA384};
+use super::ffi::spdm_pub_key_verify;
+
+pub static DEFAULT: SpdmPubKeyVerify = SpdmPubKeyVerify {
+    verify_cb: pub_key_verify,
+};
+
+fn pub_key_verify(
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    public_key_der: &[u8],
+    data: &[u8],
+    signature: &SpdmSignatureStruct,
+) -> SpdmResult {
+    if signature.data_size != base_asym_algo.get_size() {
+        return Err(SPDM_STATUS_VERIF_FAIL);
+    }
+
+    let mbedtls_hash_algo = match base_hash_algo {
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384 => MBEDTLS_MD_SHA384,
+        SpdmBaseHashAlgo::TPM_ALG_SHA_256 => MBEDTLS_MD_SHA256,
+        _ => {
+            return Err(SPDM_STATUS_CRYPTO_ERROR);
+        }
+    };
+
+    // DER has this format: 0x30 size 0x02 r_size 0x00 [r_size] 0x02 s_size 0x00 [s_size]
+    let mut der_signature = [0u8; spdmlib::protocol::ECDSA_ECC_NIST_P384_KEY_SIZE + 8];
+
+    // the key size is the size of a coordinate for ECDSA, the modulus size for RSA
+    let (signature, key_size, pss) = match base_asym_algo {
+        SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256
+        | SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384 => {
+            let der_sign_size = ecc_signature_bin_to_der(signature.as_ref(), &mut der_signature)?;
+            (
+                &der_signature[0..der_sign_size],
+                signature.data_size as usize / 2,
+                0,
+            )
+        }
+        SpdmBaseAsymAlgo::TPM_ALG_RSASSA_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSASSA_4096 => {
+            (signature.as_ref(), signature.data_size as usize, 0)
+        }
+        SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_2048
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_3072
+        | SpdmBaseAsymAlgo::TPM_ALG_RSAPSS_4096 => {
+            (signature.as_ref(), signature.data_size as usize, 1)
+        }
+        _ => {
+            return Err(SPDM_STATUS_CRYPTO_ERROR);
+        }
+    };
+
+    let data_hash = (super::hash_impl::DEFAULT.hash_all_cb)(base_hash_algo, data)
+        .ok_or(SPDM_STATUS_CRYPTO_ERROR)?;
+
+    let ret = unsafe {
+        spdm_pub_key_verify(
+            mbedtls_hash_algo,
+            pss,
+            public_key_der.as_ptr(),
+            public_key_der.len(),
+            data_hash.data.as_ptr(),
+            data_hash.data_size as usize,
+            signature.as_ptr(),
+            signature.len(),
+            key_size,
+        )
+    };
+    match ret {
+        0 => Ok(()),
+        _ => Err(SPDM_STATUS_VERIF_FAIL),
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    // ECDSA P-384 SHA-384 signature of "hello, world" by
+    // test_key/ecp384/end_responder.key
+    const SIGNATURE_P384: [u8; 96] = [
+        0x42, 0xfb, 0x14, 0x77, 0x76, 0x5d, 0x14, 0x81, 0x40, 0xd1, 0x65, 0xcc, 0x8c, 0x2c, 0x4e,
+        0x89, 0xfe, 0xff, 0x8c, 0xbc, 0x6d, 0x29, 0x2c, 0x85, 0x2d, 0x6a, 0xfe, 0x0e, 0x8e, 0xb1,
+        0xc0, 0x01, 0x1b, 0x0f, 0x77, 0x3d, 0x41, 0xc9, 0x1a, 0xe5, 0xc6, 0xdd, 0xd9, 0x53, 0xe1,
+        0x8c, 0x62, 0xbc, 0x2d, 0xa1, 0x05, 0xcc, 0x9f, 0x17, 0x01, 0x3c, 0xc4, 0x01, 0xca, 0x26,
+        0xd7, 0x66, 0xe2, 0xf5, 0x6f, 0x2c, 0xf6, 0x3c, 0x99, 0x55, 0xe9, 0xf4, 0xdd, 0xb3, 0x88,
+        0xdd, 0xba, 0xd2, 0xe0, 0x75, 0x1a, 0x27, 0x75, 0xbf, 0x2b, 0xe4, 0x51, 0xca, 0xe5, 0x48,
+        0x4f, 0x97, 0xd8, 0x80, 0x25, 0xa2,
+    ];
+
+    #[test]
+    fn test_case0_pub_key_verify() {
+        let spki = std::fs::read("../test_key/ecp384/end_responder.key.pub.der")
+            .expect("unable to read public key!");
+        let mut signature = SpdmSignatureStruct::default();
+        signature.data_size = SIGNATURE_P384.len() as u16;
+        signature.data[..SIGNATURE_P384.len()].copy_from_slice(&SIGNATURE_P384);
+
+        assert!(pub_key_verify(
+            SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+            &spki,
+            b"hello, world",
+            &signature,
+        )
+        .is_ok());
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &spki,
+                b"hello, buddy",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        // not a SubjectPublicKeyInfo
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+                &[0x04u8; 97],
+                b"hello, world",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+        // wrong signature size
+        assert_eq!(
+            pub_key_verify(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+                SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+                &spki,
+                b"hello, world",
+                &signature,
+            ),
+            Err(SPDM_STATUS_VERIF_FAIL)
+        );
+    }
+}
@@ -25,6 +25,7 @@ pub fn req_create_info() -> (common::SpdmConfigInfo, common::SpdmProvisionInfo)
             SpdmVersion::SpdmVersion10,
             SpdmVersion::SpdmVersion11,
             SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
         ],
         req_capabilities: SpdmRequestCapabilityFlags::CERT_CAP
         | SpdmRequestCapabilityFlags::CHAL_CAP
@@ -82,6 +83,10 @@ pub fn req_create_info() -> (common::SpdmConfigInfo, common::SpdmProvisionInfo)
         my_cert_chain_data: [None, None, None, None, None, None, None, None],
         my_cert_chain: [None, None, None, None, None, None, None, None],
         peer_root_cert_data: Some(peer_root_cert_data),
+        key_pair_info: Default::default(),
+        my_pub_key: None,
+        peer_pub_key: None,
+        my_cert_chain_algo: Default::default(),
     };
 
     (config_info, provision_info)
@@ -93,6 +98,7 @@ pub fn rsp_create_info() -> (common::SpdmConfigInfo, common::SpdmProvisionInfo)
             SpdmVersion::SpdmVersion10,
             SpdmVersion::SpdmVersion11,
             SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
         ],
         rsp_capabilities: SpdmResponseCapabilityFlags::CERT_CAP
         | SpdmResponseCapabilityFlags::CHAL_CAP
@@ -166,6 +172,10 @@ pub fn rsp_create_info() -> (common::SpdmConfigInfo, common::SpdmProvisionInfo)
         ],
         my_cert_chain: [None, None, None, None, None, None, None, None],
         peer_root_cert_data: None,
+        key_pair_info: Default::default(),
+        my_pub_key: None,
+        peer_pub_key: None,
+        my_cert_chain_algo: Default::default(),
     };
 
     (config_info, provision_info)
@@ -0,0 +1,304 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use codec::enum_builder;
+use codec::{Codec, Reader, Writer};
+use spdmlib::common::SpdmTransportEncap;
+use spdmlib::error::{SpdmResult, SPDM_STATUS_DECAP_FAIL, SPDM_STATUS_ENCAP_FAIL};
+
+/// DSP0286 Security Protocol field: the value of SECURITY PROTOCOL IN/OUT
+/// assigned to DMTF SPDM.
+pub const STORAGE_SECURITY_PROTOCOL_DMTF_SPDM: u8 = 0xE8;
+
+/// DSP0286 Security Protocol Specific field: the connection ID is carried in
+/// bits 1:0 of its first byte.
+pub const STORAGE_MAX_CONNECTION_ID: u8 = 3;
+
+/// DSP0286 secured messages carry the 8 bytes sequence number and up to this
+/// many bytes of DSP0277 random data after the application data.
+pub const STORAGE_SEQUENCE_NUMBER_COUNT: u8 = 8;
+pub const STORAGE_MAX_RANDOM_COUNT: u16 = 32;
+
+// DSP0286 SPDM Storage Operation, the second byte of the Security Protocol
+// Specific field.
+
+enum_builder! {
+    @U8
+    EnumName: StorageSpdmOperation;
+    EnumVal{
+        StorageSpdmOperationDiscovery => 0x01,
+        StorageSpdmOperationPendingInfo => 0x02,
+        StorageSpdmOperationSpdmMessage => 0x05,
+        StorageSpdmOperationSecuredSpdmMessage => 0x06
+    }
+}
+impl Default for StorageSpdmOperation {
+    fn default() -> StorageSpdmOperation {
+        StorageSpdmOperation::Unknown(0)
+    }
+}
+
+pub const STORAGE_MESSAGE_HEADER_SIZE: usize = 8;
+
+/// Parameters of the SECURITY PROTOCOL IN/OUT command (NVMe Security Send/Receive,
+/// ATA TRUSTED SEND/RECEIVE) transferring one SPDM message.
+///
+/// The storage command carries these in its command fields, not in the data
+/// buffer. The device IO builds the command from this header in front of the
+/// SPDM message, and places a header describing the received command in front
+/// of the data of SECURITY PROTOCOL IN.
+///
+/// Encoding, DSP0286 field names:
+/// - byte 0: Security Protocol, STORAGE_SECURITY_PROTOCOL_DMTF_SPDM
+/// - byte 1: Security Protocol Specific bits 7:0, connection ID
+/// - byte 2: Security Protocol Specific bits 15:8, SPDM Storage Operation
+/// - byte 3: reserved
+/// - bytes 4-7: Transfer Length (OUT) or Allocation Length (IN), in bytes,
+///   little endian
+#[derive(Debug, Copy, Clone, Default)]
+pub struct StorageMessageHeader {
+    pub security_protocol: u8,
+    pub connection_id: u8,
+    pub operation: StorageSpdmOperation,
+    pub transfer_length: u32, // in bytes
+}
+
+impl Codec for StorageMessageHeader {
+    fn encode(&self, bytes: &mut Writer) -> Result<usize, codec::EncodeErr> {
+        let mut cnt = 0usize;
+        cnt += self.security_protocol.encode(bytes)?;
+        cnt += (self.connection_id & STORAGE_MAX_CONNECTION_ID).encode(bytes)?;
+        cnt += self.operation.encode(bytes)?;
+        cnt += 0u8.encode(bytes)?;
+        cnt += self.transfer_length.encode(bytes)?;
+        Ok(cnt)
+    }
+
+    fn read(r: &mut Reader) -> Option<StorageMessageHeader> {
+        let security_protocol = u8::read(r)?;
+        let connection_id = u8::read(r)?;
+        if connection_id > STORAGE_MAX_CONNECTION_ID {
+            return None;
+        }
+        let operation = StorageSpdmOperation::read(r)?;
+        u8::read(r)?;
+        let transfer_length = u32::read(r)?;
+        Some(StorageMessageHeader {
+            security_protocol,
+            connection_id,
+            operation,
+            transfer_length,
+        })
+    }
+}
+
+/// SPDM storage binding (DSP0286).
+///
+/// Messages are sent with SECURITY PROTOCOL OUT and received with SECURITY
+/// PROTOCOL IN on connection_id, one SPDM message per command.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct StorageTransportEncap {
+    pub connection_id: u8,
+}
+
+impl StorageTransportEncap {
+    pub fn new(connection_id: u8) -> Option<Self> {
+        if connection_id > STORAGE_MAX_CONNECTION_ID {
+            return None;
+        }
+        Some(StorageTransportEncap { connection_id })
+    }
+
+    fn header(&self, payload_len: usize, secured_message: bool) -> StorageMessageHeader {
+        StorageMessageHeader {
+            security_protocol: STORAGE_SECURITY_PROTOCOL_DMTF_SPDM,
+            connection_id: self.connection_id,
+            operation: if secured_message {
+                StorageSpdmOperation::StorageSpdmOperationSecuredSpdmMessage
+            } else {
+                StorageSpdmOperation::StorageSpdmOperationSpdmMessage
+            },
+            transfer_length: payload_len as u32,
+        }
+    }
+}
+
+impl SpdmTransportEncap for StorageTransportEncap {
+    fn encap(
+        &mut self,
+        spdm_buffer: &[u8],
+        transport_buffer: &mut [u8],
+        secured_message: bool,
+    ) -> SpdmResult<usize> {
+        let payload_len = spdm_buffer.len();
+        let mut writer = Writer::init(&mut *transport_buffer);
+        self.header(payload_len, secured_message)
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_ENCAP_FAIL)?;
+        let header_size = writer.used();
+        if transport_buffer.len() < header_size + payload_len {
+            return Err(SPDM_STATUS_ENCAP_FAIL);
+        }
+        transport_buffer[header_size..(header_size + payload_len)].copy_from_slice(spdm_buffer);
+        Ok(header_size + payload_len)
+    }
+
+    fn decap(
+        &mut self,
+        transport_buffer: &[u8],
+        spdm_buffer: &mut [u8],
+    ) -> SpdmResult<(usize, bool)> {
+        let mut reader = Reader::init(transport_buffer);
+        let storage_header =
+            StorageMessageHeader::read(&mut reader).ok_or(SPDM_STATUS_DECAP_FAIL)?;
+        if storage_header.security_protocol != STORAGE_SECURITY_PROTOCOL_DMTF_SPDM
+            || storage_header.connection_id != self.connection_id
+        {
+            return Err(SPDM_STATUS_DECAP_FAIL);
+        }
+        let secured_message = match storage_header.operation {
+            StorageSpdmOperation::StorageSpdmOperationSpdmMessage => false,
+            StorageSpdmOperation::StorageSpdmOperationSecuredSpdmMessage => true,
+            _ => return Err(SPDM_STATUS_DECAP_FAIL),
+        };
+        let header_size = reader.used();
+        let payload_size = storage_header.transfer_length as usize;
+        if transport_buffer.len() < header_size + payload_size {
+            return Err(SPDM_STATUS_DECAP_FAIL);
+        }
+        if spdm_buffer.len() < payload_size {
+            return Err(SPDM_STATUS_DECAP_FAIL);
+        }
+        let payload = &transport_buffer[header_size..(header_size + payload_size)];
+        spdm_buffer[..payload_size].copy_from_slice(payload);
+        Ok((payload_size, secured_message))
+    }
+
+    fn encap_app(
+        &mut self,
+        spdm_buffer: &[u8],
+        app_buffer: &mut [u8],
+        _is_app_message: bool,
+    ) -> SpdmResult<usize> {
+        app_buffer[0..spdm_buffer.len()].copy_from_slice(spdm_buffer);
+        Ok(spdm_buffer.len())
+    }
+
+    fn decap_app(
+        &mut self,
+        app_buffer: &[u8],
+        spdm_buffer: &mut [u8],
+    ) -> SpdmResult<(usize, bool)> {
+        spdm_buffer[0..app_buffer.len()].copy_from_slice(app_buffer);
+        Ok((app_buffer.len(), false))
+    }
+
+    fn get_header_size(&mut self, _secured_message: bool) -> usize {
+        STORAGE_MESSAGE_HEADER_SIZE
+    }
+
+    fn encap_in_place(
+        &mut self,
+        transport_buffer: &mut [u8],
+        spdm_size: usize,
+        secured_message: bool,
+    ) -> SpdmResult<usize> {
+        if transport_buffer.len() < STORAGE_MESSAGE_HEADER_SIZE + spdm_size {
+            return Err(SPDM_STATUS_ENCAP_FAIL);
+        }
+        let mut writer = Writer::init(&mut transport_buffer[..STORAGE_MESSAGE_HEADER_SIZE]);
+        self.header(spdm_size, secured_message)
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_ENCAP_FAIL)?;
+        Ok(STORAGE_MESSAGE_HEADER_SIZE + spdm_size)
+    }
+
+    fn get_sequence_number_count(&mut self) -> u8 {
+        STORAGE_SEQUENCE_NUMBER_COUNT
+    }
+    fn get_max_random_count(&mut self) -> u16 {
+        STORAGE_MAX_RANDOM_COUNT
+    }
+}
+
+#[cfg(all(test,))]
+mod tests_header {
+    use super::*;
+
+    #[test]
+    fn test_case0_storagemessageheader() {
+        let u8_slice = &mut [0u8; 8];
+        let mut writer = Writer::init(u8_slice);
+        let value = StorageMessageHeader {
+            security_protocol: STORAGE_SECURITY_PROTOCOL_DMTF_SPDM,
+            connection_id: 2,
+            operation: StorageSpdmOperation::StorageSpdmOperationSecuredSpdmMessage,
+            transfer_length: 100,
+        };
+        assert!(value.encode(&mut writer).is_ok());
+        assert_eq!(u8_slice, &[0xE8, 0x02, 0x06, 0x00, 100, 0, 0, 0]);
+        let mut reader = Reader::init(u8_slice);
+        let storagemessageheader = StorageMessageHeader::read(&mut reader).unwrap();
+        assert_eq!(0, reader.left());
+        assert_eq!(storagemessageheader.connection_id, 2);
+        assert_eq!(
+            storagemessageheader.operation,
+            StorageSpdmOperation::StorageSpdmOperationSecuredSpdmMessage
+        );
+        assert_eq!(storagemessageheader.transfer_length, 100);
+
+        let u8_slice = &[0xE8u8, 0x04, 0x05, 0x00, 0, 0, 0, 0];
+        let mut reader = Reader::init(u8_slice);
+        assert!(StorageMessageHeader::read(&mut reader).is_none());
+    }
+    #[test]
+    fn test_case0_encap_decap() {
+        let mut storage_transport_encap = StorageTransportEncap::new(1).unwrap();
+        let spdm_buffer = [0x5au8; 101];
+        let mut transport_buffer = [0u8; 256];
+        let size = storage_transport_encap
+            .encap(&spdm_buffer, &mut transport_buffer, true)
+            .unwrap();
+        assert_eq!(size, STORAGE_MESSAGE_HEADER_SIZE + spdm_buffer.len());
+
+        let mut decap_buffer = [0u8; 256];
+        let (decap_size, secured_message) = storage_transport_encap
+            .decap(&transport_buffer[..size], &mut decap_buffer)
+            .unwrap();
+        assert!(secured_message);
+        assert_eq!(decap_buffer[..decap_size], spdm_buffer);
+
+        // a message of another connection
+        let mut other_transport_encap = StorageTransportEncap::new(0).unwrap();
+        assert!(other_transport_encap
+            .decap(&transport_buffer[..size], &mut decap_buffer)
+            .is_err());
+
+        assert!(StorageTransportEncap::new(STORAGE_MAX_CONNECTION_ID + 1).is_none());
+    }
+    #[test]
+    fn test_case0_encap_in_place() {
+        let mut storage_transport_encap = StorageTransportEncap::new(3).unwrap();
+        let spdm_buffer = [0x5au8; 101];
+        let mut expected_buffer = [0u8; 256];
+        let expected_size = storage_transport_encap
+            .encap(&spdm_buffer, &mut expected_buffer, false)
+            .unwrap();
+
+        let mut transport_buffer = [0xffu8; 256];
+        let header_size = storage_transport_encap.get_header_size(false);
+        transport_buffer[header_size..(header_size + spdm_buffer.len())]
+            .copy_from_slice(&spdm_buffer);
+        let size = storage_transport_encap
+            .encap_in_place(&mut transport_buffer, spdm_buffer.len(), false)
+            .unwrap();
+        assert_eq!(size, expected_size);
+        assert_eq!(transport_buffer[..size], expected_buffer[..expected_size]);
+
+        let mut transport_buffer = [0u8; 64];
+        assert!(storage_transport_encap
+            .encap_in_place(&mut transport_buffer, spdm_buffer.len(), false)
+            .is_err());
+    }
+}
@@ -0,0 +1,11 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+#![forbid(unsafe_code)]
+#![no_std]
+
+mod header;
+pub use header::*;
+
+extern crate codec;
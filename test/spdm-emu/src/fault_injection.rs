@@ -0,0 +1,216 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Transport fault and latency injection for the emulators.
+//!
+//! Faults are applied to the responses: the requester emu injects them on
+//! received packets, the responder emu on sent packets. Packets are counted
+//! from 1 and offsets are relative to the transport packet (e.g. the PCI DOE
+//! header comes first). A response dropped by the requester emu is reported as
+//! a receive failure, a response dropped by the responder emu is never sent.
+
+use spdmlib::common::{SpdmDeviceIo, SpdmDeviceIoEvent};
+use spdmlib::error::SpdmResult;
+use std::time::Duration;
+
+pub const FAULT_INJECTION_USAGE: &str = "fault injection options:
+    --latency-ms <ms>         delay every packet
+    --drop-nth <n>            drop the n-th packet
+    --corrupt-nth <n>         corrupt the n-th packet at the offsets below
+    --corrupt-offset <off>    flip the byte at offset, may be repeated
+    --truncate-nth <n>        truncate the n-th packet
+    --truncate-size <size>    size of the truncated packet";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultInjectionConfig {
+    pub latency_ms: u64,
+    pub drop_nth: Option<usize>,
+    pub corrupt_nth: Option<usize>,
+    pub corrupt_offsets: Vec<usize>,
+    pub truncate_nth: Option<usize>,
+    pub truncate_size: usize,
+}
+
+impl FaultInjectionConfig {
+    /// Parse the fault injection options, args excludes the program name.
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut config = FaultInjectionConfig::default();
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value of {}", arg))?;
+            let value = value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid value of {} - {}", arg, value))?;
+            match arg.as_str() {
+                "--latency-ms" => config.latency_ms = value as u64,
+                "--drop-nth" => config.drop_nth = Some(value),
+                "--corrupt-nth" => config.corrupt_nth = Some(value),
+                "--corrupt-offset" => config.corrupt_offsets.push(value),
+                "--truncate-nth" => config.truncate_nth = Some(value),
+                "--truncate-size" => config.truncate_size = value,
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+        if config.corrupt_nth.is_some() && config.corrupt_offsets.is_empty() {
+            return Err(String::from("--corrupt-nth requires --corrupt-offset"));
+        }
+        Ok(config)
+    }
+}
+
+/// Fault injection state, kept across connections of the emulator.
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    config: FaultInjectionConfig,
+    packet_count: usize,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultInjectionConfig) -> Self {
+        FaultInjector {
+            config,
+            packet_count: 0,
+        }
+    }
+
+    /// Apply the faults to one packet of size used in buffer.
+    /// Return the new packet size, or None if the packet is dropped.
+    pub fn inject(&mut self, buffer: &mut [u8], used: usize) -> Option<usize> {
+        self.packet_count += 1;
+        let nth = Some(self.packet_count);
+
+        if self.config.latency_ms != 0 {
+            std::thread::sleep(Duration::from_millis(self.config.latency_ms));
+        }
+        if self.config.drop_nth == nth {
+            log::info!("fault injection: drop packet {}\n", self.packet_count);
+            return None;
+        }
+        if self.config.corrupt_nth == nth {
+            for offset in self.config.corrupt_offsets.iter() {
+                if *offset < used {
+                    log::info!("fault injection: corrupt byte {}\n", offset);
+                    buffer[*offset] ^= 0xFF;
+                }
+            }
+        }
+        if self.config.truncate_nth == nth && self.config.truncate_size < used {
+            log::info!(
+                "fault injection: truncate to {}\n",
+                self.config.truncate_size
+            );
+            return Some(self.config.truncate_size);
+        }
+        Some(used)
+    }
+}
+
+pub struct FaultInjectionDeviceIo<'a> {
+    inner: &'a mut dyn SpdmDeviceIo,
+    injector: &'a mut FaultInjector,
+    inject_on_send: bool,
+}
+
+impl<'a> FaultInjectionDeviceIo<'a> {
+    pub fn new(
+        inner: &'a mut dyn SpdmDeviceIo,
+        injector: &'a mut FaultInjector,
+        inject_on_send: bool,
+    ) -> Self {
+        FaultInjectionDeviceIo {
+            inner,
+            injector,
+            inject_on_send,
+        }
+    }
+}
+
+impl SpdmDeviceIo for FaultInjectionDeviceIo<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], timeout: usize) -> Result<usize, usize> {
+        let used = self.inner.receive(read_buffer, timeout)?;
+        if self.inject_on_send {
+            return Ok(used);
+        }
+        self.injector.inject(read_buffer, used).ok_or(0)
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        if !self.inject_on_send {
+            return self.inner.send(buffer);
+        }
+        let mut packet = buffer.to_vec();
+        match self.injector.inject(&mut packet, buffer.len()) {
+            Some(used) => self.inner.send(&packet[..used]),
+            None => Ok(()),
+        }
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        self.inner.flush_all()
+    }
+
+    fn poll_event(&mut self) -> SpdmDeviceIoEvent {
+        self.inner.poll_event()
+    }
+
+    fn poll_receive_ready(&mut self, waker: &core::task::Waker) -> bool {
+        self.inner.poll_receive_ready(waker)
+    }
+
+    fn send_vectored(&mut self, buffers: &[&[u8]]) -> SpdmResult {
+        if !self.inject_on_send {
+            return self.inner.send_vectored(buffers);
+        }
+        self.send(&buffers.concat())
+    }
+
+    fn is_vectored(&self) -> bool {
+        self.inner.is_vectored()
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn test_case0_from_args() {
+        let config = FaultInjectionConfig::from_args(args(
+            "--latency-ms 10 --drop-nth 3 --corrupt-nth 2 --corrupt-offset 8 --corrupt-offset 9",
+        ))
+        .unwrap();
+        assert_eq!(config.latency_ms, 10);
+        assert_eq!(config.drop_nth, Some(3));
+        assert_eq!(config.corrupt_nth, Some(2));
+        assert_eq!(config.corrupt_offsets, vec![8, 9]);
+        assert_eq!(config.truncate_nth, None);
+
+        assert!(FaultInjectionConfig::from_args(args("--drop-nth")).is_err());
+        assert!(FaultInjectionConfig::from_args(args("--drop-nth x")).is_err());
+        assert!(FaultInjectionConfig::from_args(args("--unknown 1")).is_err());
+        assert!(FaultInjectionConfig::from_args(args("--corrupt-nth 1")).is_err());
+    }
+
+    #[test]
+    fn test_case0_inject() {
+        let config = FaultInjectionConfig::from_args(args(
+            "--drop-nth 2 --corrupt-nth 3 --corrupt-offset 1 --corrupt-offset 10 --truncate-nth 4 --truncate-size 2",
+        ))
+        .unwrap();
+        let mut injector = FaultInjector::new(config);
+        let mut packet = [0u8; 4];
+
+        assert_eq!(injector.inject(&mut packet, 4), Some(4));
+        assert_eq!(injector.inject(&mut packet, 4), None);
+        assert_eq!(injector.inject(&mut packet, 4), Some(4));
+        assert_eq!(packet, [0, 0xFF, 0, 0]);
+        assert_eq!(injector.inject(&mut packet, 4), Some(2));
+        assert_eq!(injector.inject(&mut packet, 4), Some(4));
+    }
+}
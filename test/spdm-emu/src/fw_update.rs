@@ -0,0 +1,491 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Sample firmware update channel over secured application messages.
+//!
+//! The requester offers an image with its SHA-384 hash, transfers it in
+//! chunks each carrying the hash of the chunk data, then asks the responder
+//! to apply and commit it. The committed image can be read back in chunks,
+//! so large application messages flow in both directions.
+//!
+//! Every message starts with the message type (1) and a reserved byte (1),
+//! all fields are little endian:
+//!
+//! | Request       | Fields                                                  |
+//! | ------------- | ------------------------------------------------------- |
+//! | OFFER   0x01  | image size (4), chunk size (2), image hash (48)         |
+//! | CHUNK   0x02  | offset (4), length (2), chunk hash (48), data           |
+//! | APPLY   0x03  |                                                         |
+//! | COMMIT  0x04  |                                                         |
+//! | READ    0x05  | offset (4), length (2)                                  |
+//!
+//! Each response sets bit 7 of the request type and starts with a status (1).
+//! OFFER is answered with the accepted chunk size (2), CHUNK with the
+//! offset of the next expected chunk (4), and READ with the same fields
+//! as CHUNK.
+
+use codec::{Codec, Reader, Writer};
+use spdmlib::config::MAX_SPDM_MSG_SIZE;
+use spdmlib::crypto;
+use spdmlib::error::{
+    SpdmResult, SPDM_STATUS_CRYPTO_ERROR, SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD,
+    SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_VERIF_FAIL,
+};
+use spdmlib::protocol::{SpdmBaseHashAlgo, SHA384_DIGEST_SIZE};
+use spdmlib::requester::RequesterContext;
+use spdmlib::responder::ResponderContext;
+use std::sync::Mutex;
+
+pub const FW_UPDATE_OFFER: u8 = 0x01;
+pub const FW_UPDATE_CHUNK: u8 = 0x02;
+pub const FW_UPDATE_APPLY: u8 = 0x03;
+pub const FW_UPDATE_COMMIT: u8 = 0x04;
+pub const FW_UPDATE_READ: u8 = 0x05;
+pub const FW_UPDATE_RESPONSE_FLAG: u8 = 0x80;
+
+pub const FW_UPDATE_STATUS_SUCCESS: u8 = 0;
+pub const FW_UPDATE_STATUS_INVALID_STATE: u8 = 1;
+pub const FW_UPDATE_STATUS_INVALID_PARAMETER: u8 = 2;
+pub const FW_UPDATE_STATUS_HASH_MISMATCH: u8 = 3;
+
+pub const FW_UPDATE_DEFAULT_CHUNK_SIZE: u16 = 1024;
+pub const FW_UPDATE_MAX_CHUNK_SIZE: u16 = 2048;
+pub const FW_UPDATE_MAX_IMAGE_SIZE: u32 = 0x10_0000;
+
+/// Size of the CHUNK and READ response fields before the data.
+const FW_UPDATE_CHUNK_HEADER_SIZE: usize = 2 + 4 + 2 + SHA384_DIGEST_SIZE;
+
+fn hash(data: &[u8]) -> Option<[u8; SHA384_DIGEST_SIZE]> {
+    let digest = crypto::hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, data)?;
+    let mut hash = [0u8; SHA384_DIGEST_SIZE];
+    hash.copy_from_slice(&digest.data[..SHA384_DIGEST_SIZE]);
+    Some(hash)
+}
+
+fn read_hash(r: &mut Reader) -> Option<[u8; SHA384_DIGEST_SIZE]> {
+    let mut hash = [0u8; SHA384_DIGEST_SIZE];
+    for d in hash.iter_mut() {
+        *d = u8::read(r)?;
+    }
+    Some(hash)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FwUpdateState {
+    Idle,
+    Receiving,
+    Received,
+    Applied,
+}
+
+/// Responder side of the firmware update channel.
+#[derive(Debug)]
+pub struct FwUpdateResponder {
+    state: FwUpdateState,
+    chunk_size: u16,
+    image_size: u32,
+    image_hash: [u8; SHA384_DIGEST_SIZE],
+    image: Vec<u8>,
+    committed_image: Vec<u8>,
+}
+
+impl Default for FwUpdateResponder {
+    fn default() -> Self {
+        FwUpdateResponder::new()
+    }
+}
+
+impl FwUpdateResponder {
+    pub const fn new() -> Self {
+        FwUpdateResponder {
+            state: FwUpdateState::Idle,
+            chunk_size: 0,
+            image_size: 0,
+            image_hash: [0u8; SHA384_DIGEST_SIZE],
+            image: Vec::new(),
+            committed_image: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> FwUpdateState {
+        self.state
+    }
+
+    pub fn committed_image(&self) -> &[u8] {
+        &self.committed_image
+    }
+
+    /// Process one request and write the response.
+    /// Return the response size, or None if the request is malformed.
+    pub fn process(&mut self, request: &[u8], response: &mut [u8]) -> Option<usize> {
+        let mut reader = Reader::init(request);
+        let request_type = u8::read(&mut reader)?;
+        u8::read(&mut reader)?; // reserved
+
+        let mut writer = Writer::init(response);
+        (request_type | FW_UPDATE_RESPONSE_FLAG)
+            .encode(&mut writer)
+            .ok()?;
+        0u8.encode(&mut writer).ok()?; // reserved
+        match request_type {
+            FW_UPDATE_OFFER => {
+                let image_size = u32::read(&mut reader)?;
+                let chunk_size = u16::read(&mut reader)?;
+                let image_hash = read_hash(&mut reader)?;
+                let chunk_size = chunk_size.min(FW_UPDATE_MAX_CHUNK_SIZE);
+                if image_size == 0 || image_size > FW_UPDATE_MAX_IMAGE_SIZE || chunk_size == 0 {
+                    FW_UPDATE_STATUS_INVALID_PARAMETER
+                        .encode(&mut writer)
+                        .ok()?;
+                    0u16.encode(&mut writer).ok()?;
+                } else {
+                    self.state = FwUpdateState::Receiving;
+                    self.chunk_size = chunk_size;
+                    self.image_size = image_size;
+                    self.image_hash = image_hash;
+                    self.image.clear();
+                    FW_UPDATE_STATUS_SUCCESS.encode(&mut writer).ok()?;
+                    chunk_size.encode(&mut writer).ok()?;
+                }
+            }
+            FW_UPDATE_CHUNK => {
+                let offset = u32::read(&mut reader)?;
+                let length = u16::read(&mut reader)?;
+                let chunk_hash = read_hash(&mut reader)?;
+                let data = reader.take(length as usize)?;
+                let status = self.receive_chunk(offset, data, &chunk_hash);
+                status.encode(&mut writer).ok()?;
+                (self.image.len() as u32).encode(&mut writer).ok()?;
+            }
+            FW_UPDATE_APPLY => {
+                let status = if self.state != FwUpdateState::Received {
+                    FW_UPDATE_STATUS_INVALID_STATE
+                } else if hash(&self.image)? != self.image_hash {
+                    self.state = FwUpdateState::Idle;
+                    FW_UPDATE_STATUS_HASH_MISMATCH
+                } else {
+                    self.state = FwUpdateState::Applied;
+                    FW_UPDATE_STATUS_SUCCESS
+                };
+                status.encode(&mut writer).ok()?;
+            }
+            FW_UPDATE_COMMIT => {
+                let status = if self.state != FwUpdateState::Applied {
+                    FW_UPDATE_STATUS_INVALID_STATE
+                } else {
+                    self.committed_image = core::mem::take(&mut self.image);
+                    self.state = FwUpdateState::Idle;
+                    FW_UPDATE_STATUS_SUCCESS
+                };
+                status.encode(&mut writer).ok()?;
+            }
+            FW_UPDATE_READ => {
+                let offset = u32::read(&mut reader)? as usize;
+                let length = u16::read(&mut reader)?.min(FW_UPDATE_MAX_CHUNK_SIZE) as usize;
+                let end = offset.checked_add(length)?;
+                if end > self.committed_image.len() {
+                    FW_UPDATE_STATUS_INVALID_PARAMETER
+                        .encode(&mut writer)
+                        .ok()?;
+                    (offset as u32).encode(&mut writer).ok()?;
+                    0u16.encode(&mut writer).ok()?;
+                    writer.extend_from_slice(&[0u8; SHA384_DIGEST_SIZE])?;
+                } else {
+                    let data = &self.committed_image[offset..end];
+                    FW_UPDATE_STATUS_SUCCESS.encode(&mut writer).ok()?;
+                    (offset as u32).encode(&mut writer).ok()?;
+                    (length as u16).encode(&mut writer).ok()?;
+                    writer.extend_from_slice(&hash(data)?)?;
+                    writer.extend_from_slice(data)?;
+                }
+            }
+            _ => return None,
+        }
+        Some(writer.used())
+    }
+
+    fn receive_chunk(&mut self, offset: u32, data: &[u8], chunk_hash: &[u8]) -> u8 {
+        if self.state != FwUpdateState::Receiving {
+            return FW_UPDATE_STATUS_INVALID_STATE;
+        }
+        if offset as usize != self.image.len()
+            || data.is_empty()
+            || data.len() > self.chunk_size as usize
+            || self.image.len() + data.len() > self.image_size as usize
+        {
+            return FW_UPDATE_STATUS_INVALID_PARAMETER;
+        }
+        match hash(data) {
+            Some(hash) if hash == chunk_hash => {}
+            _ => return FW_UPDATE_STATUS_HASH_MISMATCH,
+        }
+        self.image.extend_from_slice(data);
+        if self.image.len() == self.image_size as usize {
+            self.state = FwUpdateState::Received;
+        }
+        FW_UPDATE_STATUS_SUCCESS
+    }
+}
+
+/// Firmware update state of the responder emulator, one update at a time.
+pub static FW_UPDATE_RESPONDER: Mutex<FwUpdateResponder> = Mutex::new(FwUpdateResponder::new());
+
+/// Application message handler serving the firmware update channel,
+/// register it with spdmlib::responder::app_message_handler::register.
+pub fn fw_update_dispatch_secured_app_message_cb(
+    _ctx: &mut ResponderContext,
+    _session_id: u32,
+    app_buffer: &[u8],
+    _auxiliary_app_data: &[u8],
+) -> SpdmResult<([u8; MAX_SPDM_MSG_SIZE], usize)> {
+    let mut response = [0u8; MAX_SPDM_MSG_SIZE];
+    let mut responder = FW_UPDATE_RESPONDER
+        .lock()
+        .map_err(|_| SPDM_STATUS_INVALID_PARAMETER)?;
+    let used = responder
+        .process(app_buffer, &mut response)
+        .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+    Ok((response, used))
+}
+
+/// Requester side of the firmware update channel over an established session.
+pub struct FwUpdateRequester<'a, 'b> {
+    context: &'a mut RequesterContext<'b>,
+    session_id: u32,
+    chunk_size: u16,
+}
+
+impl<'a, 'b> FwUpdateRequester<'a, 'b> {
+    pub fn new(context: &'a mut RequesterContext<'b>, session_id: u32) -> Self {
+        FwUpdateRequester {
+            context,
+            session_id,
+            chunk_size: FW_UPDATE_DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    fn send_receive(&mut self, request: &[u8], response: &mut [u8]) -> SpdmResult<usize> {
+        self.context
+            .send_secured_message(self.session_id, request, true)?;
+        self.context
+            .receive_secured_message(self.session_id, response, false)
+    }
+
+    /// Send request and check the response type and status.
+    /// Return the response fields after the status.
+    fn transact<'r>(&mut self, request: &[u8], response: &'r mut [u8]) -> SpdmResult<Reader<'r>> {
+        let used = self.send_receive(request, response)?;
+        let mut reader = Reader::init(&response[..used]);
+        let response_type = u8::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        u8::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?; // reserved
+        let status = u8::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        if response_type != request[0] | FW_UPDATE_RESPONSE_FLAG {
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        match status {
+            FW_UPDATE_STATUS_SUCCESS => Ok(reader),
+            FW_UPDATE_STATUS_HASH_MISMATCH => Err(SPDM_STATUS_VERIF_FAIL),
+            _ => Err(SPDM_STATUS_ERROR_PEER),
+        }
+    }
+
+    /// Offer, transfer, apply and commit image.
+    pub fn update(&mut self, image: &[u8]) -> SpdmResult {
+        if image.is_empty() || image.len() > FW_UPDATE_MAX_IMAGE_SIZE as usize {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        let mut request = [0u8; MAX_SPDM_MSG_SIZE];
+        let mut response = [0u8; MAX_SPDM_MSG_SIZE];
+
+        let mut writer = Writer::init(&mut request);
+        let _ = FW_UPDATE_OFFER.encode(&mut writer);
+        let _ = 0u8.encode(&mut writer);
+        let _ = (image.len() as u32).encode(&mut writer);
+        let _ = FW_UPDATE_DEFAULT_CHUNK_SIZE.encode(&mut writer);
+        let _ = writer.extend_from_slice(&hash(image).ok_or(SPDM_STATUS_CRYPTO_ERROR)?);
+        let used = writer.used();
+        let mut reader = self.transact(&request[..used], &mut response)?;
+        let chunk_size = u16::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+        if chunk_size == 0 || chunk_size > FW_UPDATE_DEFAULT_CHUNK_SIZE {
+            return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+        }
+        self.chunk_size = chunk_size;
+
+        for (index, data) in image.chunks(self.chunk_size as usize).enumerate() {
+            let offset = index * self.chunk_size as usize;
+            let mut writer = Writer::init(&mut request);
+            let _ = FW_UPDATE_CHUNK.encode(&mut writer);
+            let _ = 0u8.encode(&mut writer);
+            let _ = (offset as u32).encode(&mut writer);
+            let _ = (data.len() as u16).encode(&mut writer);
+            let _ = writer.extend_from_slice(&hash(data).ok_or(SPDM_STATUS_CRYPTO_ERROR)?);
+            writer
+                .extend_from_slice(data)
+                .ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+            let used = writer.used();
+            let mut reader = self.transact(&request[..used], &mut response)?;
+            let next_offset = u32::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+            if next_offset as usize != offset + data.len() {
+                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+            }
+        }
+
+        self.transact(&[FW_UPDATE_APPLY, 0], &mut response)?;
+        self.transact(&[FW_UPDATE_COMMIT, 0], &mut response)?;
+        Ok(())
+    }
+
+    /// Read back size bytes of the committed image, verifying each chunk hash.
+    pub fn read_back(&mut self, size: usize) -> SpdmResult<Vec<u8>> {
+        let mut image = Vec::with_capacity(size);
+        let mut response = [0u8; MAX_SPDM_MSG_SIZE];
+        while image.len() < size {
+            let length = (size - image.len()).min(self.chunk_size as usize);
+            let mut request = [0u8; 8];
+            let mut writer = Writer::init(&mut request);
+            let _ = FW_UPDATE_READ.encode(&mut writer);
+            let _ = 0u8.encode(&mut writer);
+            let _ = (image.len() as u32).encode(&mut writer);
+            let _ = (length as u16).encode(&mut writer);
+
+            let mut reader = self.transact(&request, &mut response)?;
+            let offset = u32::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+            let read_length = u16::read(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+            let chunk_hash = read_hash(&mut reader).ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+            let data = reader
+                .take(read_length as usize)
+                .ok_or(SPDM_STATUS_INVALID_MSG_FIELD)?;
+            if offset as usize != image.len() || data.len() != length {
+                return Err(SPDM_STATUS_INVALID_MSG_FIELD);
+            }
+            if hash(data).ok_or(SPDM_STATUS_CRYPTO_ERROR)? != chunk_hash {
+                return Err(SPDM_STATUS_VERIF_FAIL);
+            }
+            image.extend_from_slice(data);
+        }
+        Ok(image)
+    }
+}
+
+#[cfg(all(test,))]
+mod tests {
+    use super::*;
+
+    fn chunk_request(offset: usize, data: &[u8], corrupt_hash: bool) -> Vec<u8> {
+        let mut request = vec![FW_UPDATE_CHUNK, 0];
+        request.extend_from_slice(&(offset as u32).to_le_bytes());
+        request.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        let mut chunk_hash = hash(data).unwrap();
+        if corrupt_hash {
+            chunk_hash[0] ^= 0xFF;
+        }
+        request.extend_from_slice(&chunk_hash);
+        request.extend_from_slice(data);
+        request
+    }
+
+    fn offer_request(image: &[u8], chunk_size: u16) -> Vec<u8> {
+        let mut request = vec![FW_UPDATE_OFFER, 0];
+        request.extend_from_slice(&(image.len() as u32).to_le_bytes());
+        request.extend_from_slice(&chunk_size.to_le_bytes());
+        request.extend_from_slice(&hash(image).unwrap());
+        request
+    }
+
+    fn status(responder: &mut FwUpdateResponder, request: &[u8]) -> u8 {
+        let mut response = [0u8; MAX_SPDM_MSG_SIZE];
+        let used = responder.process(request, &mut response).unwrap();
+        assert!(used >= 3);
+        assert_eq!(response[0], request[0] | FW_UPDATE_RESPONSE_FLAG);
+        response[2]
+    }
+
+    #[test]
+    fn test_case0_fw_update_responder() {
+        let image: Vec<u8> = (0..3000u32).map(|i| i as u8).collect();
+        let mut responder = FwUpdateResponder::new();
+
+        assert_eq!(
+            status(&mut responder, &[FW_UPDATE_APPLY, 0]),
+            FW_UPDATE_STATUS_INVALID_STATE
+        );
+        assert_eq!(
+            status(&mut responder, &offer_request(&image, 1024)),
+            FW_UPDATE_STATUS_SUCCESS
+        );
+        assert_eq!(responder.state(), FwUpdateState::Receiving);
+
+        // out of order and corrupted chunks are rejected
+        assert_eq!(
+            status(
+                &mut responder,
+                &chunk_request(1024, &image[1024..2048], false)
+            ),
+            FW_UPDATE_STATUS_INVALID_PARAMETER
+        );
+        assert_eq!(
+            status(&mut responder, &chunk_request(0, &image[..1024], true)),
+            FW_UPDATE_STATUS_HASH_MISMATCH
+        );
+
+        for (index, data) in image.chunks(1024).enumerate() {
+            assert_eq!(
+                status(&mut responder, &chunk_request(index * 1024, data, false)),
+                FW_UPDATE_STATUS_SUCCESS
+            );
+        }
+        assert_eq!(responder.state(), FwUpdateState::Received);
+        assert_eq!(
+            status(&mut responder, &[FW_UPDATE_COMMIT, 0]),
+            FW_UPDATE_STATUS_INVALID_STATE
+        );
+        assert_eq!(
+            status(&mut responder, &[FW_UPDATE_APPLY, 0]),
+            FW_UPDATE_STATUS_SUCCESS
+        );
+        assert_eq!(
+            status(&mut responder, &[FW_UPDATE_COMMIT, 0]),
+            FW_UPDATE_STATUS_SUCCESS
+        );
+        assert_eq!(responder.committed_image(), &image[..]);
+
+        let mut response = [0u8; MAX_SPDM_MSG_SIZE];
+        let used = responder
+            .process(
+                &[FW_UPDATE_READ, 0, 0xB7, 0x0B, 0, 0, 0x01, 0x00],
+                &mut response,
+            )
+            .unwrap();
+        assert_eq!(response[2], FW_UPDATE_STATUS_SUCCESS);
+        assert_eq!(used, FW_UPDATE_CHUNK_HEADER_SIZE + 1);
+        assert_eq!(responder.process(&[0x7F, 0], &mut response), None);
+    }
+
+    #[test]
+    fn test_case1_fw_update_responder_image_hash_mismatch() {
+        let image = [0x5Au8; 100];
+        let mut responder = FwUpdateResponder::new();
+        let mut offer = offer_request(&image, 4096);
+        offer[8] ^= 0xFF;
+
+        let mut response = [0u8; MAX_SPDM_MSG_SIZE];
+        responder.process(&offer, &mut response).unwrap();
+        assert_eq!(response[2], FW_UPDATE_STATUS_SUCCESS);
+        // the chunk size is capped
+        assert_eq!(
+            u16::from_le_bytes([response[3], response[4]]),
+            FW_UPDATE_MAX_CHUNK_SIZE
+        );
+        assert_eq!(
+            status(&mut responder, &chunk_request(0, &image, false)),
+            FW_UPDATE_STATUS_SUCCESS
+        );
+        assert_eq!(
+            status(&mut responder, &[FW_UPDATE_APPLY, 0]),
+            FW_UPDATE_STATUS_HASH_MISMATCH
+        );
+        assert_eq!(responder.state(), FwUpdateState::Idle);
+        assert!(responder.committed_image().is_empty());
+    }
+}
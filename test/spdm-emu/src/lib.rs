@@ -6,7 +6,13 @@
 
 pub mod crypto;
 pub mod crypto_callback;
+pub mod fault_injection;
+pub mod fw_update;
+pub mod pcie_doe_io;
+pub mod provision_store;
 pub mod secret_impl_sample;
 pub mod socket_io_transport;
 pub mod spdm_emu;
 pub mod tcp_transport;
+pub mod time_impl;
+pub mod transport_registry;
@@ -0,0 +1,296 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use pcidoe_transport::{
+    PCI_DOE_STATUS_BUSY, PCI_DOE_STATUS_DATA_OBJECT_READY, PCI_DOE_STATUS_ERROR,
+};
+use spdmlib::common::SpdmDeviceIo;
+use spdmlib::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_SEND_FAIL};
+
+pub const PCI_EXT_CAP_START: u32 = 0x100;
+pub const PCI_EXT_CAP_ID_DOE: u16 = 0x2E;
+
+// DOE extended capability registers, PCIe 6.0 7.9.24
+pub const PCI_DOE_CAP: u32 = 0x04;
+pub const PCI_DOE_CTRL: u32 = 0x08;
+pub const PCI_DOE_STATUS: u32 = 0x0C;
+pub const PCI_DOE_WRITE: u32 = 0x10;
+pub const PCI_DOE_READ: u32 = 0x14;
+
+pub const PCI_DOE_CTRL_ABORT: u32 = 0x1;
+pub const PCI_DOE_CTRL_GO: u32 = 0x8000_0000;
+
+const PCI_DOE_LENGTH_MASK: u32 = 0x3FFFF;
+const PCI_DOE_MAX_LENGTH: usize = 0x40000;
+const PCI_DOE_POLL_INTERVAL: Duration = Duration::from_micros(100);
+
+/// Dword access to the PCI configuration space of one function.
+pub trait PciConfigSpace {
+    fn read_u32(&mut self, offset: u32) -> io::Result<u32>;
+    fn write_u32(&mut self, offset: u32, value: u32) -> io::Result<()>;
+}
+
+/// The sysfs config file of a device, e.g.
+/// /sys/bus/pci/devices/0000:01:00.0/config. Access to the extended
+/// configuration space requires root.
+pub struct SysfsPciConfigSpace {
+    file: File,
+}
+
+impl SysfsPciConfigSpace {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(SysfsPciConfigSpace { file })
+    }
+}
+
+impl PciConfigSpace for SysfsPciConfigSpace {
+    fn read_u32(&mut self, offset: u32) -> io::Result<u32> {
+        let mut data = [0u8; 4];
+        self.file.read_exact_at(&mut data, offset as u64)?;
+        Ok(u32::from_le_bytes(data))
+    }
+
+    fn write_u32(&mut self, offset: u32, value: u32) -> io::Result<()> {
+        self.file.write_all_at(&value.to_le_bytes(), offset as u64)
+    }
+}
+
+/// Find the first DOE extended capability, return its offset.
+pub fn find_doe_capability<C: PciConfigSpace>(config_space: &mut C) -> io::Result<Option<u32>> {
+    let mut offset = PCI_EXT_CAP_START;
+    // each capability takes at least one dword of the 4K config space
+    for _ in 0..(0x1000 - PCI_EXT_CAP_START) / 4 {
+        let header = config_space.read_u32(offset)?;
+        if header == 0 || header == 0xFFFF_FFFF {
+            return Ok(None);
+        }
+        if header as u16 == PCI_EXT_CAP_ID_DOE {
+            return Ok(Some(offset));
+        }
+        offset = header >> 20;
+        if offset < PCI_EXT_CAP_START {
+            return Ok(None);
+        }
+    }
+    Ok(None)
+}
+
+/// SpdmDeviceIo over a PCIe DOE mailbox, to be used with PciDoeTransportEncap
+/// which builds and parses the DOE data objects.
+pub struct PcieDoeDeviceIo<C: PciConfigSpace> {
+    config_space: C,
+    doe_offset: u32,
+}
+
+impl<C: PciConfigSpace> PcieDoeDeviceIo<C> {
+    /// Use the DOE capability at doe_offset, or the first one if None.
+    pub fn new(mut config_space: C, doe_offset: Option<u32>) -> io::Result<Self> {
+        let doe_offset = match doe_offset {
+            Some(doe_offset) => doe_offset,
+            None => find_doe_capability(&mut config_space)?
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no DOE capability"))?,
+        };
+        Ok(PcieDoeDeviceIo {
+            config_space,
+            doe_offset,
+        })
+    }
+
+    pub fn doe_offset(&self) -> u32 {
+        self.doe_offset
+    }
+
+    fn read_reg(&mut self, reg: u32) -> io::Result<u32> {
+        self.config_space.read_u32(self.doe_offset + reg)
+    }
+
+    fn write_reg(&mut self, reg: u32, value: u32) -> io::Result<()> {
+        self.config_space.write_u32(self.doe_offset + reg, value)
+    }
+
+    /// Poll the status register until condition or timeout, in microseconds.
+    fn wait_status(&mut self, timeout: usize, condition: fn(u32) -> bool) -> io::Result<u32> {
+        let deadline = Instant::now() + Duration::from_micros(timeout as u64);
+        loop {
+            let status = self.read_reg(PCI_DOE_STATUS)?;
+            if status & PCI_DOE_STATUS_ERROR != 0 || condition(status) {
+                return Ok(status);
+            }
+            if Instant::now() >= deadline {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "DOE timeout"));
+            }
+            thread::sleep(PCI_DOE_POLL_INTERVAL);
+        }
+    }
+
+    /// Abort the current data object exchange, e.g. after an error.
+    pub fn abort(&mut self) -> io::Result<()> {
+        self.write_reg(PCI_DOE_CTRL, PCI_DOE_CTRL_ABORT)?;
+        self.wait_status(spdmlib::common::ST1, |status| {
+            status & PCI_DOE_STATUS_BUSY == 0
+        })
+        .map(|_| ())
+    }
+
+    fn write_object(&mut self, buffer: &[u8]) -> io::Result<()> {
+        let status = self.wait_status(spdmlib::common::ST1, |status| {
+            status & PCI_DOE_STATUS_BUSY == 0
+        })?;
+        if status & PCI_DOE_STATUS_ERROR != 0 {
+            self.abort()?;
+        }
+        for dword in buffer.chunks(4) {
+            let mut data = [0u8; 4];
+            data[..dword.len()].copy_from_slice(dword);
+            self.write_reg(PCI_DOE_WRITE, u32::from_le_bytes(data))?;
+        }
+        self.write_reg(PCI_DOE_CTRL, PCI_DOE_CTRL_GO)
+    }
+
+    /// Read one data object, return its size or Err(size) if it does not fit.
+    fn read_object(
+        &mut self,
+        buffer: &mut [u8],
+        timeout: usize,
+    ) -> io::Result<Result<usize, usize>> {
+        let status = self.wait_status(timeout, |status| {
+            status & PCI_DOE_STATUS_DATA_OBJECT_READY != 0
+        })?;
+        if status & PCI_DOE_STATUS_ERROR != 0 {
+            self.abort()?;
+            return Ok(Err(0));
+        }
+
+        let mut size = 8usize;
+        let mut index = 0usize;
+        while index < size {
+            let dword = self.read_reg(PCI_DOE_READ)?;
+            // any write to the read mailbox pops the dword
+            self.write_reg(PCI_DOE_READ, 0)?;
+            if index == 4 {
+                size = match (dword & PCI_DOE_LENGTH_MASK) as usize {
+                    0 => PCI_DOE_MAX_LENGTH,
+                    length if length < 2 => 2,
+                    length => length,
+                } * 4;
+            }
+            if index + 4 <= buffer.len() {
+                buffer[index..index + 4].copy_from_slice(&dword.to_le_bytes());
+            }
+            index += 4;
+        }
+        Ok(if size > buffer.len() {
+            Err(size)
+        } else {
+            Ok(size)
+        })
+    }
+}
+
+impl<C: PciConfigSpace> SpdmDeviceIo for PcieDoeDeviceIo<C> {
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        if buffer.is_empty() || buffer.len() % 4 != 0 {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        self.write_object(buffer).map_err(|e| {
+            log::error!("DOE send failed: {}\n", e);
+            SPDM_STATUS_SEND_FAIL
+        })
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: usize) -> Result<usize, usize> {
+        match self.read_object(buffer, timeout) {
+            Ok(result) => result,
+            Err(e) => {
+                log::error!("DOE receive failed: {}\n", e);
+                Err(0)
+            }
+        }
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    const DOE_OFFSET: u32 = 0x150;
+
+    /// A DOE mailbox echoing each data object back.
+    struct FakeDoeConfigSpace {
+        written: Vec<u32>,
+        readable: VecDeque<u32>,
+    }
+
+    impl PciConfigSpace for FakeDoeConfigSpace {
+        fn read_u32(&mut self, offset: u32) -> io::Result<u32> {
+            Ok(match offset {
+                // an AER capability pointing to the DOE capability
+                0x100 => (DOE_OFFSET << 20) | 0x0001_0001,
+                DOE_OFFSET => 0x0001_002E,
+                o if o == DOE_OFFSET + PCI_DOE_STATUS => {
+                    if self.readable.is_empty() {
+                        0
+                    } else {
+                        PCI_DOE_STATUS_DATA_OBJECT_READY
+                    }
+                }
+                o if o == DOE_OFFSET + PCI_DOE_READ => *self.readable.front().unwrap_or(&0),
+                _ => 0,
+            })
+        }
+
+        fn write_u32(&mut self, offset: u32, value: u32) -> io::Result<()> {
+            match offset - DOE_OFFSET {
+                PCI_DOE_WRITE => self.written.push(value),
+                PCI_DOE_READ => {
+                    self.readable.pop_front();
+                }
+                PCI_DOE_CTRL if value & PCI_DOE_CTRL_GO != 0 => {
+                    self.readable.extend(self.written.drain(..));
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_case0_pcie_doe_device_io() {
+        let config_space = FakeDoeConfigSpace {
+            written: Vec::new(),
+            readable: VecDeque::new(),
+        };
+        let mut device_io = PcieDoeDeviceIo::new(config_space, None).unwrap();
+        assert_eq!(device_io.doe_offset(), DOE_OFFSET);
+
+        // PCI-SIG SPDM data object of 4 dwords
+        let object = [
+            0x01, 0x00, 0x01, 0x00, 0x04, 0x00, 0x00, 0x00, 0x12, 0x84, 0x00, 0x00, 0xAA, 0xBB,
+            0xCC, 0xDD,
+        ];
+        assert!(device_io.send(&object).is_ok());
+        let buffer = &mut [0u8; 64];
+        assert_eq!(device_io.receive(buffer, 0), Ok(16));
+        assert_eq!(&buffer[..16], &object);
+
+        assert!(device_io.send(&object).is_ok());
+        assert_eq!(device_io.receive(&mut buffer[..8], 0), Err(16));
+
+        assert_eq!(device_io.receive(buffer, 0), Err(0));
+        assert!(device_io.send(&object[..6]).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use spdmlib::common::provision_store::SpdmProvisionStore;
+use spdmlib::config;
+use spdmlib::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_BUFFER_TOO_SMALL,
+    SPDM_STATUS_INVALID_PARAMETER,
+};
+use spdmlib::protocol::{SpdmCertChainData, SpdmPskHintStruct, SPDM_MAX_SLOT_NUMBER};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+const IMMUTABLE_CONFIG_FILE: &str = "immutable_config.bin";
+const MEASUREMENT_INDEX_MAP_FILE: &str = "measurement_index_map.bin";
+const PEER_ROOT_CERT_FILE: &str = "peer_root_cert.der";
+
+/// SpdmProvisionStore keeping every provisioned item in its own file under dir:
+/// slot<N>.der for cert chains, peer_root_cert.der, psk_<hex hint>.bin,
+/// immutable_config.bin and measurement_index_map.bin.
+pub struct FileProvisionStore {
+    dir: PathBuf,
+}
+
+impl FileProvisionStore {
+    pub fn new<P: AsRef<Path>>(dir: P) -> SpdmResult<Self> {
+        std::fs::create_dir_all(dir.as_ref()).map_err(|_| SPDM_STATUS_INVALID_PARAMETER)?;
+        Ok(FileProvisionStore {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn cert_chain_path(&self, slot_id: u8) -> SpdmResult<PathBuf> {
+        if slot_id as usize >= SPDM_MAX_SLOT_NUMBER {
+            return Err(SPDM_STATUS_INVALID_PARAMETER);
+        }
+        Ok(self.dir.join(format!("slot{}.der", slot_id)))
+    }
+
+    fn psk_path(&self, psk_hint: &SpdmPskHintStruct) -> PathBuf {
+        let hint: String = psk_hint
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(format!("psk_{}.bin", hint))
+    }
+
+    fn read(path: &Path) -> SpdmResult<Option<Vec<u8>>> {
+        match std::fs::read(path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(_) => Err(SPDM_STATUS_INVALID_PARAMETER),
+        }
+    }
+
+    fn write(path: &Path, data: Option<&[u8]>) -> SpdmResult {
+        let result = match data {
+            Some(data) => std::fs::write(path, data),
+            None => match std::fs::remove_file(path) {
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+                result => result,
+            },
+        };
+        result.map_err(|_| SPDM_STATUS_INVALID_PARAMETER)
+    }
+
+    fn read_cert_chain(path: &Path) -> SpdmResult<Option<SpdmCertChainData>> {
+        let data = match Self::read(path)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        if data.len() > config::MAX_SPDM_CERT_CHAIN_DATA_SIZE {
+            return Err(SPDM_STATUS_BUFFER_FULL);
+        }
+        let mut cert_chain_data = SpdmCertChainData {
+            data_size: data.len() as u16,
+            ..Default::default()
+        };
+        cert_chain_data.data[..data.len()].copy_from_slice(&data);
+        Ok(Some(cert_chain_data))
+    }
+
+    fn read_to(path: &Path, buffer: &mut [u8]) -> SpdmResult<usize> {
+        let data = Self::read(path)?.ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+        if buffer.len() < data.len() {
+            return Err(SPDM_STATUS_BUFFER_TOO_SMALL);
+        }
+        buffer[..data.len()].copy_from_slice(&data);
+        Ok(data.len())
+    }
+}
+
+impl SpdmProvisionStore for FileProvisionStore {
+    fn load_cert_chain(&mut self, slot_id: u8) -> SpdmResult<Option<SpdmCertChainData>> {
+        Self::read_cert_chain(&self.cert_chain_path(slot_id)?)
+    }
+
+    fn store_cert_chain(
+        &mut self,
+        slot_id: u8,
+        cert_chain: Option<&SpdmCertChainData>,
+    ) -> SpdmResult {
+        Self::write(
+            &self.cert_chain_path(slot_id)?,
+            cert_chain.map(|c| c.as_ref()),
+        )
+    }
+
+    fn load_peer_root_cert(&mut self) -> SpdmResult<Option<SpdmCertChainData>> {
+        Self::read_cert_chain(&self.dir.join(PEER_ROOT_CERT_FILE))
+    }
+
+    fn store_peer_root_cert(&mut self, root_cert: Option<&SpdmCertChainData>) -> SpdmResult {
+        Self::write(
+            &self.dir.join(PEER_ROOT_CERT_FILE),
+            root_cert.map(|c| c.as_ref()),
+        )
+    }
+
+    fn load_psk(&mut self, psk_hint: &SpdmPskHintStruct, psk: &mut [u8]) -> SpdmResult<usize> {
+        Self::read_to(&self.psk_path(psk_hint), psk)
+    }
+
+    fn store_psk(&mut self, psk_hint: &SpdmPskHintStruct, psk: &[u8]) -> SpdmResult {
+        Self::write(&self.psk_path(psk_hint), Some(psk))
+    }
+
+    fn load_immutable_config(&mut self, data: &mut [u8]) -> SpdmResult<usize> {
+        // no immutable configuration is provisioned
+        let path = self.dir.join(IMMUTABLE_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(0);
+        }
+        Self::read_to(&path, data)
+    }
+
+    fn load_measurement_index_map(&mut self, data: &mut [u8]) -> SpdmResult<usize> {
+        let path = self.dir.join(MEASUREMENT_INDEX_MAP_FILE);
+        if !path.exists() {
+            return Ok(0);
+        }
+        Self::read_to(&path, data)
+    }
+
+    fn store_measurement_index_map(&mut self, data: &[u8]) -> SpdmResult {
+        Self::write(&self.dir.join(MEASUREMENT_INDEX_MAP_FILE), Some(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spdmlib::common::SpdmProvisionInfo;
+
+    #[test]
+    fn test_case0_file_provision_store() {
+        let dir = std::env::temp_dir().join(format!("spdm-provision-{}", std::process::id()));
+        let mut store = FileProvisionStore::new(&dir).unwrap();
+
+        let mut provision_info = SpdmProvisionInfo::load_from(&mut store).unwrap();
+        assert!(provision_info.my_cert_chain_data[0].is_none());
+
+        let mut cert_chain_data = SpdmCertChainData {
+            data_size: 16,
+            ..Default::default()
+        };
+        cert_chain_data.data[..16].copy_from_slice(&[0xAA; 16]);
+        provision_info.my_cert_chain_data[1] = Some(cert_chain_data);
+        assert!(provision_info.store_to(&mut store).is_ok());
+
+        let mut store = FileProvisionStore::new(&dir).unwrap();
+        let provision_info = SpdmProvisionInfo::load_from(&mut store).unwrap();
+        assert!(provision_info.my_cert_chain_data[0].is_none());
+        assert_eq!(
+            provision_info.my_cert_chain_data[1]
+                .as_ref()
+                .unwrap()
+                .as_ref(),
+            &[0xAA; 16]
+        );
+
+        let psk_hint = SpdmPskHintStruct {
+            data_size: 2,
+            ..Default::default()
+        };
+        let psk = &mut [0u8; 32];
+        assert!(store.load_psk(&psk_hint, psk).is_err());
+        assert!(store.store_psk(&psk_hint, &[0x5A; 32]).is_ok());
+        assert_eq!(store.load_psk(&psk_hint, psk), Ok(32));
+        assert_eq!(psk, &[0x5A; 32]);
+
+        assert_eq!(store.load_immutable_config(&mut [0u8; 16]), Ok(0));
+
+        assert_eq!(store.load_measurement_index_map(&mut [0u8; 16]), Ok(0));
+        assert!(store.store_measurement_index_map(&[1, 2, 3]).is_ok());
+        let data = &mut [0u8; 16];
+        assert_eq!(store.load_measurement_index_map(data), Ok(3));
+        assert_eq!(data[..3], [1, 2, 3]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -7,11 +7,76 @@ use std::net::TcpStream;
 
 use codec::{Codec, Reader, Writer};
 use spdmlib::config;
+use spdmlib::protocol::{
+    SpdmAeadAlgo, SpdmBaseAsymAlgo, SpdmBaseHashAlgo, SpdmDheAlgo, SpdmMeasurementHashAlgo,
+    SpdmReqAsymAlgo,
+};
 
 pub const SOCKET_HEADER_LEN: usize = 12;
 pub const USE_PCIDOE: bool = true; // align with DMTF spdm_emu
 pub const USE_ECDSA: bool = true;
 
+/// Algorithms and test_key directory the emulators are configured with.
+pub struct EmuCryptoSuite {
+    pub base_asym_algo: SpdmBaseAsymAlgo,
+    pub req_asym_algo: SpdmReqAsymAlgo,
+    pub base_hash_algo: SpdmBaseHashAlgo,
+    pub measurement_hash_algo: SpdmMeasurementHashAlgo,
+    pub dhe_algo: SpdmDheAlgo,
+    pub aead_algo: SpdmAeadAlgo,
+    pub key_dir: &'static str,
+}
+
+pub const EMU_SUITE_ECP384: EmuCryptoSuite = EmuCryptoSuite {
+    base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+    req_asym_algo: SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+    base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+    measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+    dhe_algo: SpdmDheAlgo::SECP_384_R1,
+    aead_algo: SpdmAeadAlgo::AES_256_GCM,
+    key_dir: "test_key/ecp384",
+};
+
+pub const EMU_SUITE_ECP256: EmuCryptoSuite = EmuCryptoSuite {
+    base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+    req_asym_algo: SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+    base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+    measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_256,
+    dhe_algo: SpdmDheAlgo::SECP_256_R1,
+    aead_algo: SpdmAeadAlgo::AES_128_GCM,
+    key_dir: "test_key/ecp256",
+};
+
+pub const EMU_SUITE_ECP256_CHACHA20: EmuCryptoSuite = EmuCryptoSuite {
+    aead_algo: SpdmAeadAlgo::CHACHA20_POLY1305,
+    ..EMU_SUITE_ECP256
+};
+
+pub const EMU_SUITE_RSA3072: EmuCryptoSuite = EmuCryptoSuite {
+    base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072,
+    req_asym_algo: SpdmReqAsymAlgo::TPM_ALG_RSASSA_3072,
+    base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+    measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+    dhe_algo: SpdmDheAlgo::SECP_384_R1,
+    aead_algo: SpdmAeadAlgo::AES_256_GCM,
+    key_dir: "test_key/rsa3072",
+};
+
+/// The "ecp256" feature selects the P-256/SHA-256/AES-128-GCM suite of MCU
+/// class devices, "chacha20" the same with ChaCha20-Poly1305 for devices
+/// without AES acceleration. P-384/SHA-384/AES-256-GCM is used otherwise.
+pub fn emu_crypto_suite() -> &'static EmuCryptoSuite {
+    if !USE_ECDSA {
+        &EMU_SUITE_RSA3072
+    } else if cfg!(feature = "chacha20") {
+        &EMU_SUITE_ECP256_CHACHA20
+    } else if cfg!(feature = "ecp256") {
+        &EMU_SUITE_ECP256
+    } else {
+        &EMU_SUITE_ECP384
+    }
+}
+
 pub const SOCKET_TRANSPORT_TYPE_MCTP: u32 = 0x01;
 pub const SOCKET_TRANSPORT_TYPE_PCI_DOE: u32 = 0x02;
 
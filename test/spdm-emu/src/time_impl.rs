@@ -0,0 +1,19 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use spdmlib::time::SpdmTime;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Microseconds since the first call.
+fn now_us() -> u64 {
+    static START: Mutex<Option<Instant>> = Mutex::new(None);
+    let mut start = START.lock().unwrap();
+    start.get_or_insert_with(Instant::now).elapsed().as_micros() as u64
+}
+
+pub static SPDM_TIME_IMPL: SpdmTime = SpdmTime::new(
+    |us: usize| std::thread::sleep(Duration::from_micros(us as u64)),
+    now_us,
+);
@@ -0,0 +1,71 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use mctp_transport::MctpTransportEncap;
+use pcidoe_transport::{CxlDoeTransportEncap, PciDoeTransportEncap};
+use spdmlib::common::transport_registry::{
+    SpdmTransportFactory, SpdmTransportRegistry, SpdmTransportType,
+};
+use spdmlib::common::SpdmTransportEncap;
+use spdmlib::error::{SpdmResult, SPDM_STATUS_INVALID_PARAMETER};
+use storage_transport::StorageTransportEncap;
+
+fn mctp_factory(_connection_id: u8) -> SpdmResult<Box<dyn SpdmTransportEncap + Send>> {
+    Ok(Box::new(MctpTransportEncap {}))
+}
+
+fn pcidoe_factory(_connection_id: u8) -> SpdmResult<Box<dyn SpdmTransportEncap + Send>> {
+    Ok(Box::new(PciDoeTransportEncap {}))
+}
+
+fn cxl_doe_factory(_connection_id: u8) -> SpdmResult<Box<dyn SpdmTransportEncap + Send>> {
+    Ok(Box::new(CxlDoeTransportEncap {}))
+}
+
+fn storage_factory(connection_id: u8) -> SpdmResult<Box<dyn SpdmTransportEncap + Send>> {
+    let encap = StorageTransportEncap::new(connection_id).ok_or(SPDM_STATUS_INVALID_PARAMETER)?;
+    Ok(Box::new(encap))
+}
+
+/// The transport bindings available to the emulators.
+pub fn new_emu_transport_registry() -> SpdmTransportRegistry {
+    let mut registry = SpdmTransportRegistry::new();
+    let factories: [(SpdmTransportType, SpdmTransportFactory); 4] = [
+        (SpdmTransportType::SpdmTransportMctp, mctp_factory),
+        (SpdmTransportType::SpdmTransportPciDoe, pcidoe_factory),
+        (SpdmTransportType::SpdmTransportCxlDoe, cxl_doe_factory),
+        (SpdmTransportType::SpdmTransportStorage, storage_factory),
+    ];
+    for (transport_type, factory) in factories.iter() {
+        registry
+            .register(*transport_type, *factory)
+            .expect("transport registry is too small");
+    }
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case0_emu_transport_registry() {
+        let registry = new_emu_transport_registry();
+        assert!(registry
+            .create(SpdmTransportType::SpdmTransportPciDoe, 0)
+            .is_ok());
+        assert!(registry
+            .create(SpdmTransportType::SpdmTransportCxlDoe, 0)
+            .is_ok());
+        assert!(registry
+            .create(SpdmTransportType::SpdmTransportStorage, 1)
+            .is_ok());
+        assert!(registry
+            .create(SpdmTransportType::SpdmTransportStorage, 0xFF)
+            .is_err());
+        assert!(registry
+            .create(SpdmTransportType::SpdmTransportTcp, 0)
+            .is_err());
+    }
+}
@@ -0,0 +1,367 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Attest a PCIe device over its DOE mailbox, establish a secure session and
+//! program the keys of a selective IDE stream with IDE_KM.
+//!
+//! usage: ide_attest <sysfs config> <root cert der> <policy>
+//! e.g. ide_attest /sys/bus/pci/devices/0000:01:00.0/config ca.cert.der policy.txt
+//!
+//! The policy file holds one "<index> <hex digest>" line per measurement
+//! block to check, '#' starts a comment. Every listed index must be returned
+//! by the device with that digest, other blocks are ignored.
+
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeMap;
+
+use log::*;
+use simple_logger::SimpleLogger;
+
+use spdm_emu::pcie_doe_io::{PcieDoeDeviceIo, SysfsPciConfigSpace};
+use spdm_emu::secret_impl_sample::SECRET_PSK_IMPL_INSTANCE;
+use spdm_emu::spdm_emu::emu_crypto_suite;
+use spdm_emu::transport_registry::new_emu_transport_registry;
+use spdmlib::common::transport_registry::SpdmTransportType;
+use spdmlib::common::{self, SpdmDeviceIo, SpdmOpaqueSupport, ST1};
+use spdmlib::config;
+use spdmlib::message::ide_km::{IdeKmAesKey, IdeKmKeyIndex, IdeKmKpAckStatus, IdeKmSubStream};
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+
+// DOE discovery data object, PCIe 6.0 6.30.1.1
+const PCI_DOE_VENDOR_ID_PCISIG: u16 = 0x0001;
+const PCI_DOE_DATA_OBJECT_TYPE_DISCOVERY: u8 = 0x00;
+const PCI_DOE_DATA_OBJECT_TYPE_SPDM: u8 = 0x01;
+const PCI_DOE_DATA_OBJECT_TYPE_SECURED_SPDM: u8 = 0x02;
+
+const IDE_STREAM_ID: u8 = 0;
+
+/// Return the (vendor id, data object type) pairs supported by the mailbox.
+fn doe_discovery(device_io: &mut dyn SpdmDeviceIo) -> Result<Vec<(u16, u8)>, String> {
+    let mut protocols = Vec::new();
+    let mut index = 0u8;
+    loop {
+        let mut request = [0u8; 12];
+        request[..2].copy_from_slice(&PCI_DOE_VENDOR_ID_PCISIG.to_le_bytes());
+        request[2] = PCI_DOE_DATA_OBJECT_TYPE_DISCOVERY;
+        request[4..8].copy_from_slice(&3u32.to_le_bytes());
+        request[8] = index;
+        device_io
+            .send(&request)
+            .map_err(|e| format!("DOE discovery send failed: {:?}", e))?;
+
+        let mut response = [0u8; 12];
+        let used = device_io
+            .receive(&mut response, ST1)
+            .map_err(|_| "DOE discovery receive failed".to_string())?;
+        if used != response.len() || response[..3] != request[..3] {
+            return Err("malformed DOE discovery response".to_string());
+        }
+        protocols.push((u16::from_le_bytes([response[8], response[9]]), response[10]));
+
+        index = response[11];
+        if index == 0 || protocols.len() > u8::MAX as usize {
+            return Ok(protocols);
+        }
+    }
+}
+
+/// Read the policy file into index -> expected digest.
+fn read_policy(path: &str) -> Result<BTreeMap<u8, Vec<u8>>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut policy = BTreeMap::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let malformed = || format!("{}:{}: malformed policy line", path, number + 1);
+        let mut fields = line.split_whitespace();
+        let index = fields
+            .next()
+            .and_then(|index| index.parse::<u8>().ok())
+            .ok_or_else(malformed)?;
+        let digest = fields.next().ok_or_else(malformed)?;
+        if fields.next().is_some() || digest.len() % 2 != 0 {
+            return Err(malformed());
+        }
+        let digest = (0..digest.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digest[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| malformed())?;
+        policy.insert(index, digest);
+    }
+    Ok(policy)
+}
+
+/// Return index -> value of the DMTF measurement blocks of a record.
+fn measurement_values(
+    record: &SpdmMeasurementRecordStructure,
+) -> Result<BTreeMap<u8, &[u8]>, String> {
+    let length = record.measurement_record_length.get() as usize;
+    let mut data = record
+        .measurement_record_data
+        .get(..length)
+        .ok_or("measurement record too large")?;
+    let mut values = BTreeMap::new();
+    for _ in 0..record.number_of_blocks {
+        // index(1) measurement_specification(1) measurement_size(2)
+        if data.len() < 4 {
+            return Err("malformed measurement block".to_string());
+        }
+        let index = data[0];
+        let size = u16::from_le_bytes([data[2], data[3]]) as usize;
+        let measurement = data.get(4..4 + size).ok_or("malformed measurement block")?;
+        // DMTF value_type(1) value_size(2) value
+        if data[1] == SpdmMeasurementSpecification::DMTF.bits() && measurement.len() >= 3 {
+            let value_size = u16::from_le_bytes([measurement[1], measurement[2]]) as usize;
+            let value = measurement
+                .get(3..3 + value_size)
+                .ok_or("malformed DMTF measurement")?;
+            values.insert(index, value);
+        }
+        data = &data[4 + size..];
+    }
+    Ok(values)
+}
+
+fn check_measurements(
+    record: &SpdmMeasurementRecordStructure,
+    policy: &BTreeMap<u8, Vec<u8>>,
+) -> Result<(), String> {
+    let values = measurement_values(record)?;
+    for (index, expected) in policy {
+        match values.get(index) {
+            Some(value) if *value == expected.as_slice() => {
+                info!("measurement {} matches the policy\n", index)
+            }
+            Some(_) => return Err(format!("measurement {} differs from the policy", index)),
+            None => return Err(format!("measurement {} is missing", index)),
+        }
+    }
+    Ok(())
+}
+
+/// Program fresh keys for all sub-streams and directions of stream_id, key
+/// set 0, then start using them. The host side of the stream, the root port,
+/// must be programmed with the same keys by platform specific means, which
+/// is out of the scope of this example.
+fn program_ide_keys(
+    context: &mut RequesterContext,
+    session_id: u32,
+    stream_id: u8,
+) -> Result<(), String> {
+    let query_resp = context
+        .send_receive_ide_km_query(session_id, 0)
+        .map_err(|e| format!("IDE_KM QUERY failed: {:?}", e))?;
+    info!(
+        "IDE port {:02x}:{:02x}.{} registers {:x?}\n",
+        query_resp.bus_num,
+        query_resp.dev_func_num >> 3,
+        query_resp.dev_func_num & 0x7,
+        query_resp.registers()
+    );
+
+    let mut key_indexes = Vec::new();
+    for sub_stream in [
+        IdeKmSubStream::IdeKmSubStreamPr,
+        IdeKmSubStream::IdeKmSubStreamNpr,
+        IdeKmSubStream::IdeKmSubStreamCpl,
+    ] {
+        for tx in [false, true] {
+            key_indexes.push(IdeKmKeyIndex {
+                stream_id,
+                key_set: 0,
+                tx,
+                sub_stream,
+                port_index: 0,
+            });
+        }
+    }
+
+    for key_index in key_indexes.iter() {
+        let mut key = IdeKmAesKey::default();
+        spdmlib::crypto::rand::get_random(&mut key.key)
+            .map_err(|e| format!("no random key: {:?}", e))?;
+        match context.send_receive_ide_km_key_prog(session_id, *key_index, &key) {
+            Ok(IdeKmKpAckStatus::IdeKmKpAckSuccess) => {}
+            Ok(status) => return Err(format!("IDE_KM KEY_PROG refused: {:?}", status)),
+            Err(e) => return Err(format!("IDE_KM KEY_PROG failed: {:?}", e)),
+        }
+    }
+    for key_index in key_indexes.iter() {
+        context
+            .send_receive_ide_km_key_set_go(session_id, *key_index)
+            .map_err(|e| format!("IDE_KM K_SET_GO failed: {:?}", e))?;
+    }
+    info!("IDE stream {} keys programmed\n", stream_id);
+    Ok(())
+}
+
+fn new_requester_info(root_cert: &[u8]) -> (common::SpdmConfigInfo, common::SpdmProvisionInfo) {
+    let suite = emu_crypto_suite();
+    let config_info = common::SpdmConfigInfo {
+        spdm_version: [
+            SpdmVersion::SpdmVersion10,
+            SpdmVersion::SpdmVersion11,
+            SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
+        ],
+        req_capabilities: SpdmRequestCapabilityFlags::CERT_CAP
+            | SpdmRequestCapabilityFlags::CHAL_CAP
+            | SpdmRequestCapabilityFlags::ENCRYPT_CAP
+            | SpdmRequestCapabilityFlags::MAC_CAP
+            | SpdmRequestCapabilityFlags::KEY_EX_CAP
+            | SpdmRequestCapabilityFlags::HBEAT_CAP
+            | SpdmRequestCapabilityFlags::KEY_UPD_CAP,
+        req_ct_exponent: 0,
+        measurement_specification: SpdmMeasurementSpecification::DMTF,
+        base_asym_algo: suite.base_asym_algo,
+        base_hash_algo: suite.base_hash_algo,
+        dhe_algo: suite.dhe_algo,
+        aead_algo: suite.aead_algo,
+        req_asym_algo: suite.req_asym_algo,
+        key_schedule_algo: SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+        opaque_support: SpdmOpaqueSupport::OPAQUE_DATA_FMT1,
+        data_transfer_size: config::MAX_SPDM_MSG_SIZE as u32,
+        max_spdm_msg_size: config::MAX_SPDM_MSG_SIZE as u32,
+        enforce_peer_capabilities: true,
+        ..Default::default()
+    };
+
+    let mut peer_root_cert_data = SpdmCertChainData {
+        ..Default::default()
+    };
+    peer_root_cert_data.data_size = root_cert.len() as u16;
+    peer_root_cert_data.data[..root_cert.len()].copy_from_slice(root_cert);
+
+    let provision_info = common::SpdmProvisionInfo {
+        my_cert_chain_data: [None, None, None, None, None, None, None, None],
+        my_cert_chain: [None, None, None, None, None, None, None, None],
+        peer_root_cert_data: Some(peer_root_cert_data),
+        key_pair_info: Default::default(),
+        my_pub_key: None,
+        peer_pub_key: None,
+        my_cert_chain_algo: Default::default(),
+    };
+    (config_info, provision_info)
+}
+
+fn attest(config_path: &str, root_cert_path: &str, policy_path: &str) -> Result<(), String> {
+    let policy = read_policy(policy_path)?;
+    let root_cert =
+        std::fs::read(root_cert_path).map_err(|e| format!("{}: {}", root_cert_path, e))?;
+    if root_cert.len() > config::MAX_SPDM_CERT_CHAIN_DATA_SIZE {
+        return Err(format!("{}: root cert too large", root_cert_path));
+    }
+
+    let config_space =
+        SysfsPciConfigSpace::open(config_path).map_err(|e| format!("{}: {}", config_path, e))?;
+    let device_io = &mut PcieDoeDeviceIo::new(config_space, None)
+        .map_err(|e| format!("{}: {}", config_path, e))?;
+    info!("DOE mailbox at {:#x}\n", device_io.doe_offset());
+
+    // 1. discovery, the mailbox must carry SPDM and secured SPDM
+    let protocols = doe_discovery(device_io)?;
+    for data_object_type in [
+        PCI_DOE_DATA_OBJECT_TYPE_SPDM,
+        PCI_DOE_DATA_OBJECT_TYPE_SECURED_SPDM,
+    ] {
+        if !protocols.contains(&(PCI_DOE_VENDOR_ID_PCISIG, data_object_type)) {
+            return Err(format!(
+                "DOE data object type {} not supported",
+                data_object_type
+            ));
+        }
+    }
+
+    let mut transport_encap = new_emu_transport_registry()
+        .create(SpdmTransportType::SpdmTransportPciDoe, 0)
+        .map_err(|e| format!("no PCI DOE transport: {:?}", e))?;
+    let (config_info, provision_info) = new_requester_info(&root_cert);
+    let mut context = RequesterContext::new(
+        device_io,
+        &mut *transport_encap,
+        config_info,
+        provision_info,
+    );
+
+    // 2. authentication
+    context
+        .init_connection()
+        .map_err(|e| format!("init_connection failed: {:?}", e))?;
+    context
+        .send_receive_spdm_digest(None)
+        .map_err(|e| format!("GET_DIGESTS failed: {:?}", e))?;
+    context
+        .send_receive_spdm_certificate(None, 0)
+        .map_err(|e| format!("GET_CERTIFICATE failed: {:?}", e))?;
+    context
+        .send_receive_spdm_challenge(
+            0,
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+        )
+        .map_err(|e| format!("CHALLENGE failed: {:?}", e))?;
+
+    // 3. signed measurements against the policy
+    let mut total_number = 0u8;
+    let mut record = SpdmMeasurementRecordStructure::default();
+    context
+        .send_receive_spdm_measurement(
+            None,
+            0,
+            SpdmMeasurementAttributes::SIGNATURE_REQUESTED,
+            SpdmMeasurementOperation::SpdmMeasurementRequestAll,
+            &mut total_number,
+            &mut record,
+        )
+        .map_err(|e| format!("GET_MEASUREMENTS failed: {:?}", e))?;
+    check_measurements(&record, &policy)?;
+
+    // 4. secure session, carrying the IDE key programming
+    let session_id = context
+        .start_session(
+            false,
+            0,
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+        )
+        .map_err(|e| format!("session establishment failed: {:?}", e))?;
+    info!("session {:#x} established\n", session_id);
+
+    // 5. IDE_KM key programming of the selective IDE stream
+    program_ide_keys(&mut context, session_id, IDE_STREAM_ID)?;
+
+    context
+        .end_session(session_id)
+        .map_err(|e| format!("END_SESSION failed: {:?}", e))
+}
+
+fn main() {
+    SimpleLogger::new()
+        .with_level(LevelFilter::Info)
+        .init()
+        .unwrap();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() != 3 {
+        println!("usage: ide_attest <sysfs config> <root cert der> <policy>");
+        std::process::exit(2);
+    }
+
+    spdmlib::secret::psk::register(SECRET_PSK_IMPL_INSTANCE.clone());
+
+    #[cfg(feature = "spdm-mbedtls")]
+    spdm_emu::crypto::crypto_mbedtls_register_handles();
+
+    #[cfg(feature = "spdm-openssl")]
+    spdm_emu::crypto::crypto_openssl_register_handles();
+
+    if let Err(e) = attest(&args[0], &args[1], &args[2]) {
+        error!("{}\n", e);
+        std::process::exit(1);
+    }
+    println!("device attested, IDE keys programmed");
+}
@@ -22,8 +22,13 @@ use spdmlib::requester;
 
 use mctp_transport::MctpTransportEncap;
 use pcidoe_transport::PciDoeTransportEncap;
+use spdm_emu::fault_injection::{
+    FaultInjectionConfig, FaultInjectionDeviceIo, FaultInjector, FAULT_INJECTION_USAGE,
+};
+use spdm_emu::fw_update::FwUpdateRequester;
 use spdm_emu::socket_io_transport::SocketIoTransport;
 use spdm_emu::spdm_emu::*;
+use spdm_emu::time_impl::SPDM_TIME_IMPL;
 use std::net::TcpStream;
 
 fn send_receive_hello(
@@ -72,7 +77,7 @@ fn send_receive_stop(
 }
 
 fn test_spdm(
-    socket_io_transport: &mut SocketIoTransport,
+    socket_io_transport: &mut dyn common::SpdmDeviceIo,
     transport_encap: &mut dyn SpdmTransportEncap,
 ) {
     let req_capabilities = SpdmRequestCapabilityFlags::CERT_CAP
@@ -92,28 +97,22 @@ fn test_spdm(
         req_capabilities
     };
 
+    let suite = emu_crypto_suite();
     let config_info = common::SpdmConfigInfo {
         spdm_version: [
             SpdmVersion::SpdmVersion10,
             SpdmVersion::SpdmVersion11,
             SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
         ],
         req_capabilities,
         req_ct_exponent: 0,
         measurement_specification: SpdmMeasurementSpecification::DMTF,
-        base_asym_algo: if USE_ECDSA {
-            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
-        },
-        base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
-        dhe_algo: SpdmDheAlgo::SECP_384_R1,
-        aead_algo: SpdmAeadAlgo::AES_256_GCM,
-        req_asym_algo: if USE_ECDSA {
-            SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmReqAsymAlgo::TPM_ALG_RSASSA_3072
-        },
+        base_asym_algo: suite.base_asym_algo,
+        base_hash_algo: suite.base_hash_algo,
+        dhe_algo: suite.dhe_algo,
+        aead_algo: suite.aead_algo,
+        req_asym_algo: suite.req_asym_algo,
         key_schedule_algo: SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
         opaque_support: SpdmOpaqueSupport::OPAQUE_DATA_FMT1,
         data_transfer_size: config::MAX_SPDM_MSG_SIZE as u32,
@@ -125,23 +124,11 @@ fn test_spdm(
         ..Default::default()
     };
 
-    let ca_file_path = if USE_ECDSA {
-        "test_key/ecp384/ca.cert.der"
-    } else {
-        "test_key/rsa3072/ca.cert.der"
-    };
+    let ca_file_path = format!("{}/ca.cert.der", suite.key_dir);
     let ca_cert = std::fs::read(ca_file_path).expect("unable to read ca cert!");
-    let inter_file_path = if USE_ECDSA {
-        "test_key/ecp384/inter.cert.der"
-    } else {
-        "test_key/rsa3072/inter.cert.der"
-    };
+    let inter_file_path = format!("{}/inter.cert.der", suite.key_dir);
     let inter_cert = std::fs::read(inter_file_path).expect("unable to read inter cert!");
-    let leaf_file_path = if USE_ECDSA {
-        "test_key/ecp384/end_responder.cert.der"
-    } else {
-        "test_key/rsa3072/end_responder.cert.der"
-    };
+    let leaf_file_path = format!("{}/end_responder.cert.der", suite.key_dir);
     let leaf_cert = std::fs::read(leaf_file_path).expect("unable to read leaf cert!");
 
     let ca_len = ca_cert.len();
@@ -182,12 +169,20 @@ fn test_spdm(
             ],
             my_cert_chain: [None, None, None, None, None, None, None, None],
             peer_root_cert_data: Some(peer_root_cert_data),
+            key_pair_info: Default::default(),
+            my_pub_key: None,
+            peer_pub_key: None,
+            my_cert_chain_algo: Default::default(),
         }
     } else {
         common::SpdmProvisionInfo {
             my_cert_chain_data: [None, None, None, None, None, None, None, None],
             my_cert_chain: [None, None, None, None, None, None, None, None],
             peer_root_cert_data: Some(peer_root_cert_data),
+            key_pair_info: Default::default(),
+            my_pub_key: None,
+            peer_pub_key: None,
+            my_cert_chain_algo: Default::default(),
         }
     };
 
@@ -300,6 +295,23 @@ fn test_spdm(
             panic!("send_receive_spdm_certificate failed");
         }
 
+        // The firmware update channel is only served by the rust responder emu,
+        // set `SPDM_FW_UPDATE_SIZE` to the image size to exercise it.
+        if let Some(image_size) = std::env::var("SPDM_FW_UPDATE_SIZE")
+            .ok()
+            .and_then(|size| size.parse::<usize>().ok())
+        {
+            let image: Vec<u8> = (0..image_size).map(|i| i as u8).collect();
+            let mut fw_update = FwUpdateRequester::new(&mut context, session_id);
+            if fw_update.update(&image).is_err() {
+                panic!("firmware update failed");
+            }
+            match fw_update.read_back(image.len()) {
+                Ok(read_back) if read_back == image => {}
+                _ => panic!("firmware read back failed"),
+            }
+        }
+
         if context.end_session(session_id).is_err() {
             panic!("end_session failed");
         }
@@ -341,11 +353,24 @@ fn new_logger_from_env() -> SimpleLogger {
 fn main() {
     new_logger_from_env().init().unwrap();
 
+    let fault_injection_config = match FaultInjectionConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            println!("{}\n{}", err, FAULT_INJECTION_USAGE);
+            return;
+        }
+    };
+    let mut fault_injector = FaultInjector::new(fault_injection_config);
+
     spdmlib::secret::psk::register(SECRET_PSK_IMPL_INSTANCE.clone());
+    spdmlib::time::register(SPDM_TIME_IMPL.clone());
 
     #[cfg(feature = "spdm-mbedtls")]
     spdm_emu::crypto::crypto_mbedtls_register_handles();
 
+    #[cfg(feature = "spdm-openssl")]
+    spdm_emu::crypto::crypto_openssl_register_handles();
+
     let since_the_epoch = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .expect("Time went backwards");
@@ -372,7 +397,9 @@ fn main() {
     send_receive_hello(&mut socket, transport_encap, transport_type);
 
     let socket_io_transport = &mut SocketIoTransport::new(&mut socket);
-    test_spdm(socket_io_transport, transport_encap);
+    let fault_injection_io =
+        &mut FaultInjectionDeviceIo::new(socket_io_transport, &mut fault_injector, false);
+    test_spdm(fault_injection_io, transport_encap);
 
     send_receive_stop(&mut socket, transport_encap, transport_type);
 }
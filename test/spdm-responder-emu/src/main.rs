@@ -20,9 +20,16 @@ use pcidoe_transport::{
     PciDoeDataObjectType, PciDoeMessageHeader, PciDoeTransportEncap, PciDoeVendorId,
 };
 use spdm_emu::crypto_callback::SECRET_ASYM_IMPL_INSTANCE;
+use spdm_emu::fault_injection::{
+    FaultInjectionConfig, FaultInjectionDeviceIo, FaultInjector, FAULT_INJECTION_USAGE,
+};
+use spdm_emu::fw_update::fw_update_dispatch_secured_app_message_cb;
+use spdm_emu::provision_store::FileProvisionStore;
 use spdm_emu::secret_impl_sample::*;
 use spdm_emu::socket_io_transport::SocketIoTransport;
 use spdm_emu::spdm_emu::*;
+use spdmlib::common::provision_store::SpdmProvisionStore;
+use spdmlib::responder::app_message_handler::SpdmAppMessageHandler;
 use spdmlib::{common, config, protocol::*, responder};
 
 fn process_socket_message(
@@ -83,9 +90,21 @@ fn new_logger_from_env() -> SimpleLogger {
 fn main() {
     new_logger_from_env().init().unwrap();
 
+    let fault_injection_config = match FaultInjectionConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            println!("{}\n{}", err, FAULT_INJECTION_USAGE);
+            return;
+        }
+    };
+    let mut fault_injector = FaultInjector::new(fault_injection_config);
+
     #[cfg(feature = "spdm-mbedtls")]
     spdm_emu::crypto::crypto_mbedtls_register_handles();
 
+    #[cfg(feature = "spdm-openssl")]
+    spdm_emu::crypto::crypto_openssl_register_handles();
+
     spdmlib::secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
     spdmlib::secret::psk::register(SECRET_PSK_IMPL_INSTANCE.clone());
 
@@ -102,6 +121,7 @@ fn main() {
         loop {
             let res = handle_message(
                 &mut stream,
+                &mut fault_injector,
                 if USE_PCIDOE {
                     pcidoe_transport_encap
                 } else {
@@ -135,10 +155,13 @@ fn main() {
 
 fn handle_message(
     stream: &mut TcpStream,
+    fault_injector: &mut FaultInjector,
     transport_encap: &mut dyn SpdmTransportEncap,
-) -> Result<bool, (usize, [u8; config::RECEIVER_BUFFER_SIZE])> {
+) -> Result<bool, (usize, Vec<u8>)> {
     println!("handle_message!");
     let mut socket_io_transport = SocketIoTransport::new(stream);
+    let mut fault_injection_io =
+        FaultInjectionDeviceIo::new(&mut socket_io_transport, fault_injector, true);
     let rsp_capabilities = SpdmResponseCapabilityFlags::CERT_CAP
         | SpdmResponseCapabilityFlags::CHAL_CAP
         | SpdmResponseCapabilityFlags::MEAS_CAP_SIG
@@ -158,29 +181,23 @@ fn handle_message(
         rsp_capabilities
     };
 
+    let suite = emu_crypto_suite();
     let config_info = common::SpdmConfigInfo {
         spdm_version: [
             SpdmVersion::SpdmVersion10,
             SpdmVersion::SpdmVersion11,
             SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
         ],
         rsp_capabilities,
         rsp_ct_exponent: 0,
         measurement_specification: SpdmMeasurementSpecification::DMTF,
-        measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
-        base_asym_algo: if USE_ECDSA {
-            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
-        },
-        base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
-        dhe_algo: SpdmDheAlgo::SECP_384_R1,
-        aead_algo: SpdmAeadAlgo::AES_256_GCM,
-        req_asym_algo: if USE_ECDSA {
-            SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmReqAsymAlgo::TPM_ALG_RSASSA_3072
-        },
+        measurement_hash_algo: suite.measurement_hash_algo,
+        base_asym_algo: suite.base_asym_algo,
+        base_hash_algo: suite.base_hash_algo,
+        dhe_algo: suite.dhe_algo,
+        aead_algo: suite.aead_algo,
+        req_asym_algo: suite.req_asym_algo,
         key_schedule_algo: SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
         opaque_support: SpdmOpaqueSupport::OPAQUE_DATA_FMT1,
         data_transfer_size: config::MAX_SPDM_MSG_SIZE as u32,
@@ -194,23 +211,11 @@ fn handle_message(
         ..Default::default()
     };
 
-    let ca_file_path = if USE_ECDSA {
-        "test_key/ecp384/ca.cert.der"
-    } else {
-        "test_key/rsa3072/ca.cert.der"
-    };
+    let ca_file_path = format!("{}/ca.cert.der", suite.key_dir);
     let ca_cert = std::fs::read(ca_file_path).expect("unable to read ca cert!");
-    let inter_file_path = if USE_ECDSA {
-        "test_key/ecp384/inter.cert.der"
-    } else {
-        "test_key/rsa3072/inter.cert.der"
-    };
+    let inter_file_path = format!("{}/inter.cert.der", suite.key_dir);
     let inter_cert = std::fs::read(inter_file_path).expect("unable to read inter cert!");
-    let leaf_file_path = if USE_ECDSA {
-        "test_key/ecp384/end_responder.cert.der"
-    } else {
-        "test_key/rsa3072/end_responder.cert.der"
-    };
+    let leaf_file_path = format!("{}/end_responder.cert.der", suite.key_dir);
     let leaf_cert = std::fs::read(leaf_file_path).expect("unable to read leaf cert!");
 
     let ca_len = ca_cert.len();
@@ -229,28 +234,46 @@ fn handle_message(
     my_cert_chain_data.data[(ca_len + inter_len)..(ca_len + inter_len + leaf_len)]
         .copy_from_slice(leaf_cert.as_ref());
 
-    let provision_info = common::SpdmProvisionInfo {
-        my_cert_chain_data: [
-            Some(my_cert_chain_data),
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-        ],
-        my_cert_chain: [None, None, None, None, None, None, None, None],
-        peer_root_cert_data: None,
+    let mut provision_store = std::env::var("SPDM_PROVISION_DIR")
+        .ok()
+        .map(|provision_dir| open_provision_store(&provision_dir, &my_cert_chain_data));
+    let provision_info = match provision_store.as_mut() {
+        Some(store) => {
+            common::SpdmProvisionInfo::load_from(store).expect("unable to load provision info!")
+        }
+        None => common::SpdmProvisionInfo {
+            my_cert_chain_data: [
+                Some(my_cert_chain_data),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            my_cert_chain: [None, None, None, None, None, None, None, None],
+            peer_root_cert_data: None,
+            key_pair_info: Default::default(),
+            my_pub_key: None,
+            peer_pub_key: None,
+            my_cert_chain_algo: Default::default(),
+        },
     };
 
     spdmlib::secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    responder::app_message_handler::register(SpdmAppMessageHandler {
+        dispatch_secured_app_message_cb: fw_update_dispatch_secured_app_message_cb,
+    });
     let mut context = responder::ResponderContext::new(
-        &mut socket_io_transport,
+        &mut fault_injection_io,
         transport_encap,
         config_info,
         provision_info,
     );
+    if let Some(store) = provision_store {
+        context.common.set_provision_store(Some(Box::new(store)));
+    }
     loop {
         // if failed, receieved message can't be processed. then the message will need caller to deal.
         // now caller need to deal with message in context.
@@ -271,6 +294,26 @@ fn handle_message(
     }
 }
 
+/// Open the store of the provisioned data in provision_dir.
+/// An empty store is seeded with default_cert_chain_data in slot 0.
+fn open_provision_store(
+    provision_dir: &str,
+    default_cert_chain_data: &SpdmCertChainData,
+) -> FileProvisionStore {
+    let mut store =
+        FileProvisionStore::new(provision_dir).expect("unable to open provision store!");
+    if store
+        .load_cert_chain(0)
+        .expect("unable to load cert chain!")
+        .is_none()
+    {
+        store
+            .store_cert_chain(0, Some(default_cert_chain_data))
+            .expect("unable to store cert chain!");
+    }
+    store
+}
+
 pub fn send_hello(
     stream: &mut TcpStream,
     transport_encap: &mut dyn SpdmTransportEncap,
@@ -1,163 +0,0 @@
-// Copyright (c) 2023 Intel Corporation
-//
-// SPDX-License-Identifier: BSD-2-Clause-Patent
-
-#![allow(unused)]
-
-use spdmlib::common::{SpdmDeviceIo, ST1};
-use spdmlib::error::{SpdmResult, SPDM_STATUS_ERROR_PEER};
-use spdmlib::responder;
-use std::cell::RefCell;
-use std::collections::VecDeque;
-
-pub struct MySpdmDeviceIo;
-
-impl SpdmDeviceIo for MySpdmDeviceIo {
-    fn send(&mut self, _buffer: &[u8]) -> SpdmResult {
-        todo!()
-    }
-
-    fn receive(&mut self, _buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
-        todo!()
-    }
-
-    fn flush_all(&mut self) -> SpdmResult {
-        todo!()
-    }
-}
-
-pub struct FakeSpdmDeviceIo<'a> {
-    pub data: &'a SharedBuffer,
-    pub responder: &'a mut responder::ResponderContext<'a>,
-}
-
-impl<'a> FakeSpdmDeviceIo<'a> {
-    pub fn new(data: &'a SharedBuffer, responder: &'a mut responder::ResponderContext<'a>) -> Self {
-        FakeSpdmDeviceIo { data, responder }
-    }
-}
-
-impl SpdmDeviceIo for FakeSpdmDeviceIo<'_> {
-    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
-        let len = self.data.get_buffer(read_buffer);
-        log::info!("requester receive RAW - {:02x?}\n", &read_buffer[0..len]);
-        Ok(len)
-    }
-
-    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
-        self.data.set_buffer(buffer);
-        log::info!("requester send    RAW - {:02x?}\n", buffer);
-
-        if self.responder.process_message(ST1, &[0]).is_err() {
-            return Err(SPDM_STATUS_ERROR_PEER);
-        }
-        Ok(())
-    }
-
-    fn flush_all(&mut self) -> SpdmResult {
-        Ok(())
-    }
-}
-
-pub struct SpdmDeviceIoReceve<'a> {
-    data: &'a SharedBuffer,
-    fuzzdata: &'a [u8],
-}
-
-impl<'a> SpdmDeviceIoReceve<'a> {
-    pub fn new(data: &'a SharedBuffer, fuzzdata: &'a [u8]) -> Self {
-        SpdmDeviceIoReceve { data, fuzzdata }
-    }
-}
-
-impl SpdmDeviceIo for SpdmDeviceIoReceve<'_> {
-    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
-        let len = self.data.get_buffer(read_buffer);
-        log::info!("responder receive RAW - {:02x?}\n", &read_buffer[0..len]);
-        Ok(len)
-    }
-
-    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
-        self.data.set_buffer(self.fuzzdata);
-        log::info!("responder send    RAW - {:02x?}\n", buffer);
-        Ok(())
-    }
-
-    fn flush_all(&mut self) -> SpdmResult {
-        Ok(())
-    }
-}
-
-pub struct FakeSpdmDeviceIoReceve<'a> {
-    data: &'a SharedBuffer,
-}
-
-impl<'a> FakeSpdmDeviceIoReceve<'a> {
-    pub fn new(data: &'a SharedBuffer) -> Self {
-        FakeSpdmDeviceIoReceve { data }
-    }
-}
-
-impl SpdmDeviceIo for FakeSpdmDeviceIoReceve<'_> {
-    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
-        let len = self.data.get_buffer(read_buffer);
-        log::info!("responder receive RAW - {:02x?}\n", &read_buffer[0..len]);
-        Ok(len)
-    }
-
-    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
-        self.data.set_buffer(buffer);
-        log::info!("responder send    RAW - {:02x?}\n", buffer);
-        Ok(())
-    }
-
-    fn flush_all(&mut self) -> SpdmResult {
-        Ok(())
-    }
-}
-
-pub struct SharedBuffer {
-    queue: RefCell<VecDeque<u8>>,
-}
-
-impl SharedBuffer {
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
-        SharedBuffer {
-            queue: RefCell::new(VecDeque::<u8>::new()),
-        }
-    }
-    pub fn set_buffer(&self, b: &[u8]) {
-        log::info!("send    {:02x?}\n", b);
-        let mut queue = self.queue.borrow_mut();
-        for i in b {
-            queue.push_back(*i);
-        }
-    }
-
-    pub fn get_buffer(&self, b: &mut [u8]) -> usize {
-        let mut queue = self.queue.borrow_mut();
-        let mut len = 0usize;
-        for i in b.iter_mut() {
-            if queue.is_empty() {
-                break;
-            }
-            *i = queue.pop_front().unwrap();
-            len += 1;
-        }
-        log::info!("recieve {:02x?}\n", &b[..len]);
-        len
-    }
-}
-
-#[test]
-fn test_fake_device_io() {
-    let buffer = SharedBuffer::new();
-    let mut server = FakeSpdmDeviceIoReceve::new(&buffer);
-    let mut client = FakeSpdmDeviceIoReceve::new(&buffer);
-    const SEND_DATA: &[u8] = &[1, 2];
-    client.send(SEND_DATA).unwrap();
-    let mut rev = [0u8, 64];
-    server.receive(&mut rev, ST1).unwrap();
-    assert_eq!(&rev[..=1], SEND_DATA)
-}
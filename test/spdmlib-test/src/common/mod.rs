@@ -4,13 +4,13 @@
 
 #![forbid(unsafe_code)]
 
-// TBD: need test different algorithm combinations
-pub const USE_ECDSA: bool = true;
+pub use spdmlib_testutils::USE_ECDSA;
 
-pub mod util;
+pub use spdmlib_testutils::util;
 
-pub mod device_io;
-pub mod transport;
+pub use spdmlib_testutils::device_io;
+pub use spdmlib_testutils::executor;
+pub use spdmlib_testutils::transport;
 
-pub mod crypto_callback;
-pub mod secret_callback;
+pub use spdmlib_testutils::crypto_callback;
+pub use spdmlib_testutils::secret_callback;
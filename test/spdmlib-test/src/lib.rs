@@ -5,11 +5,18 @@
 #![forbid(unsafe_code)]
 
 pub mod common;
+pub mod trace_replay;
 
 #[cfg(test)]
 mod test_client_server;
 #[cfg(test)]
+mod test_dual_role;
+#[cfg(test)]
+mod test_executor;
+#[cfg(test)]
 mod test_library;
+#[cfg(test)]
+mod test_trace_replay;
 
 #[cfg(test)]
 mod requester_tests;
@@ -0,0 +1,107 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoAsync, FakeSpdmDeviceIoDisconnect, SharedBuffer};
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::create_info;
+use spdmlib::common::SpdmDeviceIoEvent;
+use spdmlib::config;
+use spdmlib::error::SPDM_STATUS_RECEIVE_FAIL;
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use spdmlib::responder;
+use std::cell::Cell;
+
+#[test]
+fn test_case0_receive_async_message() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let pending = Cell::new(false);
+
+    let mut device_io_responder = FakeSpdmDeviceIoAsync::new(&shared_buffer, &pending);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let mut device_io_requester = FakeSpdmDeviceIoAsync::new(&shared_buffer, &pending);
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    assert_eq!(requester.receive_async_message(receive_buffer), Ok(None));
+    assert_eq!(requester.process_async_message(), Ok(false));
+
+    let message = [
+        SpdmVersion::SpdmVersion12.get_u8(),
+        0x7E,
+        0x00,
+        0x00,
+        0x01,
+        0x02,
+        0x03,
+        0x04,
+    ];
+    assert!(responder.send_async_message(None, &message).is_ok());
+    assert!(pending.get());
+
+    let (session_id, used) = requester
+        .receive_async_message(receive_buffer)
+        .unwrap()
+        .unwrap();
+    assert_eq!(session_id, None);
+    assert_eq!(&receive_buffer[..used], &message);
+    assert!(!pending.get());
+    assert_eq!(requester.receive_async_message(receive_buffer), Ok(None));
+}
+
+#[test]
+fn test_case1_receive_async_message_disconnected() {
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let disconnected = Cell::new(false);
+
+    let mut device_io_requester = FakeSpdmDeviceIoDisconnect::new(&shared_buffer, &disconnected);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    assert_eq!(requester.receive_async_message(receive_buffer), Ok(None));
+    assert_eq!(
+        requester.common.negotiate_info.spdm_version_sel,
+        SpdmVersion::SpdmVersion12
+    );
+
+    // the disconnect is not swallowed while looking for async messages
+    disconnected.set(true);
+    assert_eq!(
+        requester.receive_async_message(receive_buffer),
+        Err(SPDM_STATUS_RECEIVE_FAIL)
+    );
+    assert_eq!(
+        requester.common.negotiate_info.spdm_version_sel,
+        SpdmVersion::default()
+    );
+    assert_eq!(
+        requester.poll_device_io_event(),
+        SpdmDeviceIoEvent::PeerDisconnected
+    );
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::SECRET_ASYM_IMPL_INSTANCE;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use spdmlib::common::attestation_report::SpdmAttestationReport;
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use spdmlib::{responder, secret};
+
+#[test]
+fn test_case0_attestation_report_round_trip() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+
+    let nonce = SpdmNonceStruct { data: [0xA5u8; 32] };
+    let endpoint_info = [0x01u8, 0x02, 0x03];
+    let measurement_record = [0x01u8, 0x01, 0x04, 0x00, 0xDE, 0xAD, 0xBE, 0xEF];
+    let report = SpdmAttestationReport {
+        slot_id: 0,
+        nonce: nonce.clone(),
+        endpoint_info: &endpoint_info,
+        measurement_record: &measurement_record,
+    };
+    let buffer = &mut [0u8; 512];
+    let used = responder
+        .generate_attestation_report(&report, buffer)
+        .unwrap();
+    assert_eq!(
+        used,
+        4 + 32
+            + 2
+            + endpoint_info.len()
+            + 3
+            + measurement_record.len()
+            + ECDSA_ECC_NIST_P384_KEY_SIZE
+    );
+
+    // no report before SPDM 1.2
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
+    assert!(responder
+        .generate_attestation_report(&report, &mut [0u8; 512])
+        .is_err());
+    // no room for the signature
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    assert!(responder
+        .generate_attestation_report(&report, &mut [0u8; 64])
+        .is_err());
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+
+    // no certificate chain
+    assert!(requester
+        .verify_attestation_report(&buffer[..used], &nonce)
+        .is_err());
+
+    requester.common.peer_info.peer_cert_chain[0] = Some(get_rsp_cert_chain_buff());
+    let decoded = requester
+        .verify_attestation_report(&buffer[..used], &nonce)
+        .unwrap();
+    assert_eq!(decoded.slot_id, 0);
+    assert_eq!(decoded.nonce.data, nonce.data);
+    assert_eq!(decoded.endpoint_info, &endpoint_info);
+    assert_eq!(decoded.measurement_record, &measurement_record);
+
+    // stale nonce
+    let stale_nonce = SpdmNonceStruct { data: [0x5Au8; 32] };
+    assert!(requester
+        .verify_attestation_report(&buffer[..used], &stale_nonce)
+        .is_err());
+    // truncated signature
+    assert!(requester
+        .verify_attestation_report(&buffer[..used - 1], &nonce)
+        .is_err());
+    // the signature covers the measurements
+    let mut tampered = buffer[..used].to_vec();
+    tampered[4 + 32 + 2 + endpoint_info.len() + 3] ^= 0xFF;
+    assert!(requester
+        .verify_attestation_report(&tampered, &nonce)
+        .is_err());
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::FakeSpdmDeviceIoScripted;
+use crate::common::transport::{encap, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use spdmlib::common::SpdmConnectionState;
+use spdmlib::config;
+use spdmlib::error::SPDM_STATUS_PEER_CAP_VIOLATION;
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+#[test]
+fn test_case0_response_capability_violation() {
+    let (mut req_config_info, req_provision_info) = create_info();
+    req_config_info.enforce_peer_capabilities = true;
+    let version = SpdmVersion::SpdmVersion12.get_u8();
+    let measurements = encap(&[version, 0x60, 0x00, 0x00]);
+    let heartbeat_ack = encap(&[version, 0x68, 0x00, 0x00]);
+    let error = encap(&[version, 0x7F, 0x03, 0x00]);
+
+    let responses = RefCell::new(VecDeque::from(vec![
+        measurements.clone(),
+        heartbeat_ack.clone(),
+        error,
+        measurements.clone(),
+        heartbeat_ack,
+        measurements,
+    ]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+    requester
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    // MEAS_CAP and HBEAT_CAP are not advertised
+    assert_eq!(
+        requester.receive_message(receive_buffer, false),
+        Err(SPDM_STATUS_PEER_CAP_VIOLATION)
+    );
+    assert_eq!(
+        requester.receive_message(receive_buffer, false),
+        Err(SPDM_STATUS_PEER_CAP_VIOLATION)
+    );
+    // ERROR needs no capability
+    assert!(requester.receive_message(receive_buffer, false).is_ok());
+
+    requester.common.negotiate_info.rsp_capabilities_sel |=
+        SpdmResponseCapabilityFlags::MEAS_CAP_SIG | SpdmResponseCapabilityFlags::HBEAT_CAP;
+    assert!(requester.receive_message(receive_buffer, false).is_ok());
+    assert!(requester.receive_message(receive_buffer, false).is_ok());
+
+    // enforcement is off by default
+    requester.common.config_info.enforce_peer_capabilities = false;
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::empty();
+    assert!(requester.receive_message(receive_buffer, false).is_ok());
+}
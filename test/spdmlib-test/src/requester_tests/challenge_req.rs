@@ -82,11 +82,37 @@ fn test_case0_send_receive_spdm_challenge() {
     requester.common.peer_info.peer_cert_chain[0] = Some(get_rsp_cert_chain_buff());
     requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
 
-    let status = requester
-        .send_receive_spdm_challenge(
-            0,
-            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
-        )
-        .is_ok();
-    assert!(status);
+    let result = requester.send_receive_spdm_challenge(
+        0,
+        SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+    );
+    assert!(result.is_ok());
+    let result = result.unwrap();
+    assert_eq!(result.slot_id, 0);
+    assert_eq!(
+        result.cert_chain_hash.data_size,
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384.get_size()
+    );
+    assert_eq!(
+        result.transcript_hash.data_size,
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384.get_size()
+    );
+    assert_eq!(result.measurement_summary_hash.data_size, 0);
+
+    // the signed transcript outlives the reset after verification
+    #[cfg(feature = "hashed-transcript-data")]
+    assert!(requester.common.export_message_m1m2().is_err());
+    let transcript = requester
+        .common
+        .runtime_info
+        .last_verified_m1m2
+        .as_ref()
+        .unwrap();
+    assert_eq!(
+        transcript
+            .hash(SpdmBaseHashAlgo::TPM_ALG_SHA_384)
+            .unwrap()
+            .as_ref(),
+        result.transcript_hash.as_ref()
+    );
 }
@@ -0,0 +1,188 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::FakeSpdmDeviceIoScripted;
+use crate::common::transport::{encap, PciDoeTransportEncap, PCI_DOE_MESSAGE_HEADER_SIZE};
+use crate::common::util::create_info;
+use spdmlib::config;
+use spdmlib::error::{SPDM_STATUS_ERROR_PEER, SPDM_STATUS_INVALID_MSG_FIELD};
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+const LARGE_RESPONSE_HANDLE: u8 = 0x5A;
+
+fn large_response_error() -> Vec<u8> {
+    encap(&[
+        SpdmVersion::SpdmVersion12.get_u8(),
+        0x7F,
+        0x0F,
+        0x00,
+        LARGE_RESPONSE_HANDLE,
+    ])
+}
+
+fn chunk_response(chunk_seq_no: u16, last: bool, large_message_size: u32, chunk: &[u8]) -> Vec<u8> {
+    let mut message = vec![
+        SpdmVersion::SpdmVersion12.get_u8(),
+        0x06,
+        last as u8,
+        LARGE_RESPONSE_HANDLE,
+    ];
+    message.extend_from_slice(&chunk_seq_no.to_le_bytes());
+    message.extend_from_slice(&[0, 0]);
+    message.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+    if chunk_seq_no == 0 {
+        message.extend_from_slice(&large_message_size.to_le_bytes());
+    }
+    message.extend_from_slice(chunk);
+    encap(&message)
+}
+
+fn large_message() -> Vec<u8> {
+    let mut message = vec![SpdmVersion::SpdmVersion12.get_u8(), 0x02, 0x00, 0x00];
+    message.extend((0..36).map(|i| i as u8));
+    message
+}
+
+fn setup_chunk_negotiated(requester: &mut RequesterContext) {
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    requester.common.negotiate_info.req_capabilities_sel = SpdmRequestCapabilityFlags::CHUNK_CAP;
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CHUNK_CAP;
+    requester.common.negotiate_info.req_data_transfer_size_sel = 32;
+}
+
+#[test]
+fn test_case0_receive_large_response() {
+    let (req_config_info, req_provision_info) = create_info();
+    let large_message = large_message();
+
+    let responses = RefCell::new(VecDeque::from(vec![
+        large_response_error(),
+        chunk_response(0, false, large_message.len() as u32, &large_message[..16]),
+        chunk_response(1, false, 0, &large_message[16..32]),
+        chunk_response(2, true, 0, &large_message[32..]),
+    ]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_chunk_negotiated(&mut requester);
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    let used = requester.receive_message(receive_buffer, false).unwrap();
+    assert_eq!(&receive_buffer[..used], &large_message[..]);
+    assert!(responses.borrow().is_empty());
+
+    let requests = requests.borrow();
+    assert_eq!(requests.len(), 3);
+    for (chunk_seq_no, request) in requests.iter().enumerate() {
+        let chunk_get = &request[PCI_DOE_MESSAGE_HEADER_SIZE..PCI_DOE_MESSAGE_HEADER_SIZE + 6];
+        assert_eq!(chunk_get[1], 0x86);
+        assert_eq!(chunk_get[3], LARGE_RESPONSE_HANDLE);
+        assert_eq!(
+            u16::from_le_bytes([chunk_get[4], chunk_get[5]]),
+            chunk_seq_no as u16
+        );
+    }
+}
+
+#[test]
+fn test_case1_receive_large_response() {
+    let (req_config_info, req_provision_info) = create_info();
+    let large_message = large_message();
+
+    // ChunkSeqNo is not the requested one.
+    let responses = RefCell::new(VecDeque::from(vec![
+        large_response_error(),
+        chunk_response(0, false, large_message.len() as u32, &large_message[..16]),
+        chunk_response(2, true, 0, &large_message[16..]),
+    ]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_chunk_negotiated(&mut requester);
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    assert_eq!(
+        requester.receive_message(receive_buffer, false),
+        Err(SPDM_STATUS_INVALID_MSG_FIELD)
+    );
+}
+
+#[test]
+fn test_case2_receive_large_response() {
+    let (req_config_info, req_provision_info) = create_info();
+    let large_message = large_message();
+
+    // Chunks exceed LargeMessageSize.
+    let responses = RefCell::new(VecDeque::from(vec![
+        large_response_error(),
+        chunk_response(0, false, 34, &large_message[..16]),
+        chunk_response(1, true, 0, &large_message[16..]),
+    ]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_chunk_negotiated(&mut requester);
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    assert!(requester.receive_message(receive_buffer, false).is_err());
+}
+
+#[test]
+fn test_case3_receive_large_response() {
+    let (req_config_info, req_provision_info) = create_info();
+
+    // Without CHUNK_CAP the error is returned to the caller as is.
+    let responses = RefCell::new(VecDeque::from(vec![large_response_error()]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_chunk_negotiated(&mut requester);
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    let used = requester.receive_message(receive_buffer, false).unwrap();
+    assert_eq!(receive_buffer[1], 0x7F);
+    assert_eq!(receive_buffer[2], 0x0F);
+    assert_eq!(
+        requester.spdm_handle_error_response_main(
+            None,
+            &receive_buffer[..used],
+            spdmlib::message::SpdmRequestResponseCode::SpdmRequestGetCertificate,
+            spdmlib::message::SpdmRequestResponseCode::SpdmResponseCertificate,
+        ),
+        Err(SPDM_STATUS_ERROR_PEER)
+    );
+    assert!(requests.borrow().is_empty());
+}
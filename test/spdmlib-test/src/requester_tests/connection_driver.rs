@@ -0,0 +1,93 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::*;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::create_info;
+use spdmlib::common::session::SpdmSessionState;
+use spdmlib::common::{SpdmConfigInfo, SpdmProvisionInfo};
+use spdmlib::error::{SpdmResult, SPDM_STATUS_UNSUPPORTED_CAP};
+use spdmlib::protocol::*;
+use spdmlib::requester::connection_driver::SpdmConnectionPolicy;
+use spdmlib::requester::RequesterContext;
+use spdmlib::{responder, secret};
+
+fn start_connection(
+    rsp_info: (SpdmConfigInfo, SpdmProvisionInfo),
+    policy: &SpdmConnectionPolicy,
+) -> SpdmResult<SpdmSessionState> {
+    let (mut rsp_config_info, rsp_provision_info) = rsp_info;
+    let (mut req_config_info, req_provision_info) = create_info();
+    // the requester certificate of the mut-auth session
+    rsp_config_info.req_asym_algo = SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    req_config_info.req_asym_algo = SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
+    secret::psk::register(SECRET_PSK_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    let session_id = requester.start_connection(policy)?;
+    Ok(requester
+        .common
+        .get_immutable_session_via_id(session_id)
+        .unwrap()
+        .get_session_state())
+}
+
+#[test]
+fn test_case0_start_connection() {
+    assert_eq!(
+        start_connection(create_info(), &SpdmConnectionPolicy::default()),
+        Ok(SpdmSessionState::SpdmSessionEstablished)
+    );
+
+    let policy = SpdmConnectionPolicy {
+        challenge: false,
+        prefer_psk: true,
+        measurement_summary_hash_type:
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll,
+        ..Default::default()
+    };
+    assert_eq!(
+        start_connection(create_info(), &policy),
+        Ok(SpdmSessionState::SpdmSessionEstablished)
+    );
+}
+
+#[test]
+fn test_case1_start_connection_without_session_cap() {
+    let (mut rsp_config_info, rsp_provision_info) = create_info();
+    rsp_config_info.rsp_capabilities &= !(SpdmResponseCapabilityFlags::KEY_EX_CAP
+        | SpdmResponseCapabilityFlags::PSK_CAP_WITHOUT_CONTEXT
+        | SpdmResponseCapabilityFlags::PSK_CAP_WITH_CONTEXT);
+    assert_eq!(
+        start_connection(
+            (rsp_config_info, rsp_provision_info),
+            &SpdmConnectionPolicy::default()
+        ),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+}
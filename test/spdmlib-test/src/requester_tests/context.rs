@@ -2,17 +2,23 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
-use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::device_io::{
+    FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, FakeSpdmDeviceIoVectored, SharedBuffer,
+};
 use crate::common::secret_callback::*;
 use crate::common::transport::PciDoeTransportEncap;
 use crate::common::util::create_info;
 use codec::Writer;
 use spdmlib::common::session::{SpdmSession, SpdmSessionState};
-use spdmlib::common::SpdmCodec;
+use spdmlib::common::{
+    SpdmBufferConfig, SpdmCancelToken, SpdmCodec, SpdmConnectionState, SpdmMessageDirection,
+};
 use spdmlib::message::*;
 use spdmlib::protocol::*;
 use spdmlib::requester::RequesterContext;
 use spdmlib::{config, protocol, responder, secret};
+use std::cell::RefCell;
+use std::rc::Rc;
 
 #[test]
 fn test_case0_start_session() {
@@ -80,6 +86,74 @@ fn test_case0_start_session() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_case1_start_session_handshake_in_clear() {
+    let (mut rsp_config_info, rsp_provision_info) = create_info();
+    let (mut req_config_info, req_provision_info) = create_info();
+    rsp_config_info.rsp_capabilities |= SpdmResponseCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP;
+    req_config_info.req_capabilities |= SpdmRequestCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP;
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    assert!(requester.init_connection().is_ok());
+    assert!(requester
+        .common
+        .negotiate_info
+        .rsp_capabilities_sel
+        .contains(SpdmResponseCapabilityFlags::HANDSHAKE_IN_THE_CLEAR_CAP));
+
+    assert!(requester.send_receive_spdm_digest(None).is_ok());
+    assert!(requester.send_receive_spdm_certificate(None, 0).is_ok());
+
+    #[cfg(feature = "mut-auth")]
+    {
+        requester.common.negotiate_info.req_asym_sel = SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    }
+
+    // KEY_EXCHANGE, the encapsulated flow and FINISH all go in the clear
+    let session_id = requester
+        .start_session(
+            false,
+            0,
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll,
+        )
+        .unwrap();
+    assert!(!requester.common.is_session_handshake_in_clear(session_id));
+    assert_eq!(
+        requester
+            .common
+            .get_immutable_session_via_id(session_id)
+            .unwrap()
+            .get_session_state(),
+        SpdmSessionState::SpdmSessionEstablished
+    );
+    assert_eq!(requester.common.runtime_info.get_last_session_id(), None);
+
+    assert!(requester.send_receive_spdm_heartbeat(session_id).is_ok());
+}
+
 #[test]
 fn test_case0_get_next_half_session() {
     let (rsp_config_info, rsp_provision_info) = create_info();
@@ -308,3 +382,200 @@ fn test_case0_receive_secured_message() {
         .is_ok();
     assert!(status);
 }
+
+#[test]
+fn test_case0_set_buffer_config() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    assert!(requester
+        .common
+        .set_buffer_config(SpdmBufferConfig::with_msg_size(0x20, 64))
+        .is_err());
+    assert!(requester
+        .common
+        .set_buffer_config(SpdmBufferConfig::with_msg_size(0x200, 64))
+        .is_ok());
+    assert_eq!(requester.common.config_info.max_spdm_msg_size, 0x200);
+    assert_eq!(requester.common.config_info.data_transfer_size, 0x200);
+
+    assert!(requester.init_connection().is_ok());
+    assert_eq!(
+        requester.send_message(&[0u8; 0x201]),
+        Err(spdmlib::error::SPDM_STATUS_SEND_FAIL)
+    );
+}
+
+#[test]
+fn test_case0_cancel_token() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    let cancel_token = SpdmCancelToken::new();
+    requester
+        .common
+        .set_cancel_token(Some(cancel_token.clone()));
+    assert!(requester.init_connection().is_ok());
+
+    cancel_token.cancel();
+    assert_eq!(
+        requester.send_receive_spdm_version(),
+        Err(spdmlib::error::SPDM_STATUS_CANCELLED)
+    );
+    // the connection is torn down
+    assert_eq!(
+        requester.common.runtime_info.get_connection_state(),
+        SpdmConnectionState::SpdmConnectionNotStarted
+    );
+
+    cancel_token.reset();
+    assert!(requester.init_connection().is_ok());
+}
+
+#[test]
+fn test_case0_message_capture() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    let rsp_captured = Rc::new(RefCell::new(Vec::new()));
+    let rsp_log = rsp_captured.clone();
+    responder.common.set_message_capture(Some(Box::new(
+        move |direction: SpdmMessageDirection, session_id: Option<u32>, message: &[u8]| {
+            rsp_log
+                .borrow_mut()
+                .push((direction, session_id, message[1]))
+        },
+    )));
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    let req_captured = Rc::new(RefCell::new(Vec::new()));
+    let req_log = req_captured.clone();
+    requester.common.set_message_capture(Some(Box::new(
+        move |direction: SpdmMessageDirection, session_id: Option<u32>, message: &[u8]| {
+            req_log
+                .borrow_mut()
+                .push((direction, session_id, message[1]))
+        },
+    )));
+
+    assert!(requester.send_receive_spdm_version().is_ok());
+
+    let get_version = SpdmRequestResponseCode::SpdmRequestGetVersion.get_u8();
+    let version = SpdmRequestResponseCode::SpdmResponseVersion.get_u8();
+    assert_eq!(
+        *req_captured.borrow(),
+        vec![
+            (SpdmMessageDirection::Sent, None, get_version),
+            (SpdmMessageDirection::Received, None, version),
+        ]
+    );
+    assert_eq!(
+        *rsp_captured.borrow(),
+        vec![
+            (SpdmMessageDirection::Received, None, get_version),
+            (SpdmMessageDirection::Sent, None, version),
+        ]
+    );
+}
+
+#[test]
+fn test_case0_vectored_device_io() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let sizes = RefCell::new(Vec::new());
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester =
+        FakeSpdmDeviceIoVectored::new(&shared_buffer, &mut responder, &sizes);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    assert!(requester.send_receive_spdm_version().is_ok());
+
+    // GET_VERSION is sent and VERSION is received with the 8 bytes
+    // PCI DOE header in a buffer of its own.
+    let sizes = sizes.borrow();
+    assert_eq!(sizes.len(), 2);
+    assert_eq!(sizes[0], [8, 4 + 4]);
+    assert_eq!(sizes[1][0], 8);
+}
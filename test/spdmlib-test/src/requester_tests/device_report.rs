@@ -0,0 +1,204 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::*;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use spdmlib::common::SpdmConnectionState;
+use spdmlib::message::{SpdmMeasurementAttributes, SpdmMeasurementOperation};
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use spdmlib::{responder, secret};
+
+#[test]
+fn test_case0_compose_device_report() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    responder.common.negotiate_info.req_capabilities_sel = SpdmRequestCapabilityFlags::CERT_CAP;
+    responder.common.negotiate_info.rsp_capabilities_sel =
+        SpdmResponseCapabilityFlags::CERT_CAP | SpdmResponseCapabilityFlags::MEAS_CAP_SIG;
+    responder
+        .common
+        .negotiate_info
+        .measurement_specification_sel = SpdmMeasurementSpecification::DMTF;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    responder.common.reset_runtime_info();
+    responder.common.provision_info.my_cert_chain[0] = Some(get_rsp_cert_chain_buff());
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    requester.common.negotiate_info.req_capabilities_sel = SpdmRequestCapabilityFlags::CERT_CAP;
+    requester.common.negotiate_info.rsp_capabilities_sel =
+        SpdmResponseCapabilityFlags::CERT_CAP | SpdmResponseCapabilityFlags::MEAS_CAP_SIG;
+    requester
+        .common
+        .negotiate_info
+        .measurement_specification_sel = SpdmMeasurementSpecification::DMTF;
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    requester.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    requester.common.reset_runtime_info();
+
+    let mut measurement_record = SpdmMeasurementRecordStructure::default();
+
+    // no certificate chain
+    assert!(requester
+        .compose_device_report(0, &measurement_record)
+        .is_err());
+
+    requester.common.peer_info.peer_cert_chain[0] = Some(get_rsp_cert_chain_buff());
+    // no signed measurements
+    assert!(requester
+        .compose_device_report(0, &measurement_record)
+        .is_err());
+
+    let mut number_of_blocks = 0u8;
+    requester
+        .send_receive_spdm_measurement(
+            None,
+            0,
+            SpdmMeasurementAttributes::SIGNATURE_REQUESTED,
+            SpdmMeasurementOperation::SpdmMeasurementRequestAll,
+            &mut number_of_blocks,
+            &mut measurement_record,
+        )
+        .unwrap();
+
+    #[cfg(feature = "hashed-transcript-data")]
+    {
+        // Only the hash of L1/L2 is kept.
+        assert!(requester
+            .compose_device_report(0, &measurement_record)
+            .is_err());
+    }
+    #[cfg(not(feature = "hashed-transcript-data"))]
+    {
+        check_device_report(&requester, &measurement_record, number_of_blocks);
+
+        // Only the leaf is kept of a chain verified in portions.
+        let report = requester
+            .compose_device_report(0, &measurement_record)
+            .unwrap();
+        let leaf_cert = report.leaf_cert[..report.leaf_cert_size as usize].to_vec();
+        requester.common.peer_info.peer_cert_chain[0] = None;
+        requester.common.peer_info.peer_cert_chain_streamed[0] =
+            Some(spdmlib::common::SpdmStreamedCertChain {
+                leaf_cert: leaf_cert.clone(),
+                cert_count: 3,
+                ..Default::default()
+            });
+        let report = requester
+            .compose_device_report(0, &measurement_record)
+            .unwrap();
+        assert_eq!(
+            report.leaf_cert[..report.leaf_cert_size as usize],
+            leaf_cert[..]
+        );
+    }
+}
+
+#[cfg(not(feature = "hashed-transcript-data"))]
+fn check_device_report(
+    requester: &RequesterContext,
+    measurement_record: &SpdmMeasurementRecordStructure,
+    number_of_blocks: u8,
+) {
+    use codec::{Codec, Writer};
+    use spdmlib::common::ManagedBufferL1L2;
+    use spdmlib::requester::device_report::SpdmDeviceReport;
+    use spdmlib::{config, crypto};
+
+    let report = requester
+        .compose_device_report(0, measurement_record)
+        .unwrap();
+
+    let cert_chain = include_bytes!("../../../../test_key/ecp384/bundle_responder.certchain.der");
+    let (leaf_cert_begin, leaf_cert_end) =
+        crypto::cert_operation::get_cert_from_cert_chain(cert_chain, -1).unwrap();
+    assert_eq!(
+        report.leaf_cert[..report.leaf_cert_size as usize],
+        cert_chain[leaf_cert_begin..leaf_cert_end]
+    );
+    assert_eq!(report.spdm_version, SpdmVersion::SpdmVersion12);
+    assert_eq!(report.measurement_record.number_of_blocks, number_of_blocks);
+    assert!(!report.l1l2.as_ref().is_empty());
+    assert_eq!(
+        report.signature.data_size,
+        ECDSA_ECC_NIST_P384_KEY_SIZE as u16
+    );
+
+    let buffer = &mut [0u8; config::MAX_SPDM_CERT_CHAIN_DATA_SIZE
+        + config::MAX_SPDM_MEASUREMENT_RECORD_SIZE * 2
+        + 2048];
+    let mut writer = Writer::init(buffer);
+    let size = report.encode(&mut writer).unwrap();
+    assert_eq!(
+        size,
+        25 + report.leaf_cert_size as usize
+            + 4
+            + report.measurement_record.measurement_record_length.get() as usize
+            + SPDM_NONCE_SIZE
+            + 4
+            + report.l1l2.as_ref().len()
+            + ECDSA_ECC_NIST_P384_KEY_SIZE
+    );
+
+    let mut decoded = SpdmDeviceReport::read_bytes(&buffer[..size]).unwrap();
+    assert_eq!(decoded.leaf_cert_size, report.leaf_cert_size);
+    assert_eq!(
+        decoded.leaf_cert[..decoded.leaf_cert_size as usize],
+        report.leaf_cert[..report.leaf_cert_size as usize]
+    );
+    assert_eq!(decoded.base_asym_algo, report.base_asym_algo);
+    assert_eq!(decoded.requester_nonce.data, report.requester_nonce.data);
+    assert_eq!(decoded.l1l2.as_ref(), report.l1l2.as_ref());
+    assert!(requester.verify_device_report(&decoded).is_ok());
+
+    // The signature covers the whole L1/L2 transcript.
+    let mut l1l2 = report.l1l2.as_ref().to_vec();
+    l1l2[0] ^= 0xFF;
+    decoded.l1l2 = ManagedBufferL1L2::default();
+    decoded.l1l2.append_message(&l1l2).unwrap();
+    assert!(requester.verify_device_report(&decoded).is_err());
+
+    // The nonce must be the one in the signed transcript.
+    decoded.l1l2 = report.l1l2.clone();
+    decoded.requester_nonce.data[0] ^= 0xFF;
+    assert!(requester.verify_device_report(&decoded).is_err());
+
+    buffer[0] = 0xFF;
+    assert!(SpdmDeviceReport::read_bytes(&buffer[..size]).is_none());
+}
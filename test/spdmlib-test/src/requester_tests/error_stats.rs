@@ -0,0 +1,86 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::FakeSpdmDeviceIoScripted;
+use crate::common::transport::{encap, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use spdmlib::config;
+use spdmlib::message::SpdmErrorCode;
+use spdmlib::protocol::*;
+use spdmlib::requester::error_stats::{
+    register_error_response_policy, SpdmErrorResponsePolicy, SpdmErrorResponseStats,
+};
+use spdmlib::requester::RequesterContext;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static BUSY_EXCEEDED: AtomicU32 = AtomicU32::new(0);
+
+fn threshold_exceeded_cb(error_code: SpdmErrorCode, stats: &SpdmErrorResponseStats) {
+    assert_eq!(error_code, SpdmErrorCode::SpdmErrorBusy);
+    assert_eq!(stats.consecutive_busy_count, 3);
+    BUSY_EXCEEDED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn test_case0_error_response_stats() {
+    register_error_response_policy(SpdmErrorResponsePolicy {
+        busy_threshold: 3,
+        not_ready_threshold: 0,
+        threshold_exceeded_cb,
+    });
+
+    let (req_config_info, req_provision_info) = create_info();
+    let version = SpdmVersion::SpdmVersion12.get_u8();
+    let busy = encap(&[version, 0x7F, 0x03, 0x00]);
+    let not_ready = encap(&[version, 0x7F, 0x42, 0x00, 0x01, 0xE0, 0x01, 0x01]);
+    let digests = encap(&[version, 0x01, 0x00, 0x00]);
+
+    let responses = RefCell::new(VecDeque::from(vec![
+        busy.clone(),
+        busy.clone(),
+        not_ready,
+        busy.clone(),
+        busy.clone(),
+        busy.clone(),
+        busy,
+        digests,
+    ]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    // every scripted response is counted, none is retried
+    requester.respond_if_ready_retry = 0;
+
+    let receive_buffer = &mut [0u8; config::MAX_SPDM_MSG_SIZE];
+    for _ in 0..8 {
+        assert!(requester.receive_message(receive_buffer, false).is_ok());
+    }
+    assert_eq!(BUSY_EXCEEDED.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        *requester.get_error_response_stats(),
+        SpdmErrorResponseStats {
+            response_count: 8,
+            busy_count: 6,
+            not_ready_count: 1,
+            consecutive_busy_count: 0,
+            consecutive_not_ready_count: 0,
+        }
+    );
+
+    requester.reset_error_response_stats();
+    assert_eq!(
+        *requester.get_error_response_stats(),
+        SpdmErrorResponseStats::default()
+    );
+}
@@ -2,14 +2,17 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
-use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::device_io::{
+    FakeSpdmDeviceIo, FakeSpdmDeviceIoPipelined, FakeSpdmDeviceIoReceve, SharedBuffer,
+};
 use crate::common::secret_callback::*;
 use crate::common::transport::PciDoeTransportEncap;
 use crate::common::util::{create_info, get_rsp_cert_chain_buff};
-use spdmlib::common::SpdmConnectionState;
+use spdmlib::common::{SpdmBasicCertPolicy, SpdmConnectionState};
+use spdmlib::crypto::SpdmX509KeyUsage;
 use spdmlib::protocol::*;
 use spdmlib::requester::RequesterContext;
-use spdmlib::{responder, secret};
+use spdmlib::{crypto, responder, secret};
 
 #[test]
 #[cfg(feature = "hashed-transcript-data")]
@@ -65,4 +68,347 @@ fn test_case0_send_receive_spdm_certificate() {
 
     let status = requester.send_receive_spdm_certificate(None, 0).is_ok();
     assert!(status);
+
+    // The verified chain is exported as presented by the responder.
+    let expected = get_rsp_cert_chain_buff();
+    let peer_info = &requester.common.peer_info;
+    assert_eq!(peer_info.get_peer_cert_chain(0), Some(expected.as_ref()));
+    assert!(peer_info.get_peer_cert_chain(1).is_none());
+    let cert_chain_der = peer_info
+        .get_peer_cert_chain_der(0, SpdmBaseHashAlgo::TPM_ALG_SHA_384)
+        .unwrap();
+    assert_eq!(cert_chain_der, &expected.as_ref()[4 + SHA384_DIGEST_SIZE..]);
+    let certs: Vec<&[u8]> = peer_info
+        .get_peer_certs(0, SpdmBaseHashAlgo::TPM_ALG_SHA_384)
+        .unwrap()
+        .collect();
+    assert!(certs.len() > 1);
+    assert_eq!(certs.concat(), cert_chain_der);
+    let (leaf_begin, leaf_end) =
+        crypto::cert_operation::get_cert_from_cert_chain(cert_chain_der, -1).unwrap();
+    assert_eq!(
+        certs[certs.len() - 1],
+        &cert_chain_der[leaf_begin..leaf_end]
+    );
+
+    let leaf_info = peer_info
+        .get_peer_leaf_cert_info(0, SpdmBaseHashAlgo::TPM_ALG_SHA_384)
+        .unwrap();
+    assert!(!leaf_info.is_ca);
+    assert!(leaf_info.subject_common_name.is_some());
+    assert!(peer_info
+        .get_peer_leaf_cert_info(1, SpdmBaseHashAlgo::TPM_ALG_SHA_384)
+        .is_none());
+    // not a DICE alias certificate
+    assert_eq!(
+        peer_info
+            .get_peer_leaf_dice_tcb_info(0, SpdmBaseHashAlgo::TPM_ALG_SHA_384)
+            .map(|tcb_info| tcb_info.len()),
+        Some(0)
+    );
+}
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case2_send_receive_spdm_certificate_cert_policy() {
+    for (policy, expected) in [
+        (
+            SpdmBasicCertPolicy {
+                required_leaf_key_usage: SpdmX509KeyUsage::DIGITAL_SIGNATURE,
+                reject_unknown_critical_extensions: true,
+                ..Default::default()
+            },
+            true,
+        ),
+        (
+            SpdmBasicCertPolicy {
+                forbidden_leaf_key_usage: SpdmX509KeyUsage::KEY_ENCIPHERMENT,
+                ..Default::default()
+            },
+            false,
+        ),
+        (
+            SpdmBasicCertPolicy {
+                max_path_length: Some(0),
+                ..Default::default()
+            },
+            false,
+        ),
+    ] {
+        let (rsp_config_info, rsp_provision_info) = create_info();
+        let (req_config_info, req_provision_info) = create_info();
+
+        let shared_buffer = SharedBuffer::new();
+        let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+        let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+        secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+        let mut responder = responder::ResponderContext::new(
+            &mut device_io_responder,
+            pcidoe_transport_encap,
+            rsp_config_info,
+            rsp_provision_info,
+        );
+
+        responder.common.reset_runtime_info();
+        responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        responder.common.negotiate_info.base_asym_sel =
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+        responder.common.provision_info.my_cert_chain = [
+            Some(get_rsp_cert_chain_buff()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ];
+
+        responder
+            .common
+            .runtime_info
+            .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+        let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+        let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+        let mut requester = RequesterContext::new(
+            &mut device_io_requester,
+            pcidoe_transport_encap2,
+            req_config_info,
+            req_provision_info,
+        );
+
+        requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        requester.common.negotiate_info.base_asym_sel =
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+        requester.common.set_cert_policy(Some(Box::new(policy)));
+
+        let status = requester.send_receive_spdm_certificate(None, 0).is_ok();
+        assert_eq!(status, expected);
+    }
+}
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case3_send_receive_spdm_certificate_streaming() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    responder.common.reset_runtime_info();
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ];
+
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+
+    assert!(requester
+        .send_receive_spdm_certificate_streaming(None, 0)
+        .is_ok());
+
+    // Only the leaf and the chain hash are kept.
+    let expected = get_rsp_cert_chain_buff();
+    assert!(requester.common.peer_info.get_peer_cert_chain(0).is_none());
+    assert_eq!(
+        requester
+            .common
+            .get_peer_cert_chain_hash(0)
+            .unwrap()
+            .as_ref(),
+        crypto::hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, expected.as_ref())
+            .unwrap()
+            .as_ref()
+    );
+    let cert_chain_der = &expected.as_ref()[4 + SHA384_DIGEST_SIZE..];
+    let (leaf_begin, leaf_end) =
+        crypto::cert_operation::get_cert_from_cert_chain(cert_chain_der, -1).unwrap();
+    assert_eq!(
+        requester.common.get_peer_public_cert_der(0),
+        Some(&cert_chain_der[leaf_begin..leaf_end])
+    );
+
+    // The whole chain replaces the streamed one.
+    assert!(requester.send_receive_spdm_certificate(None, 0).is_ok());
+    assert!(requester.common.peer_info.peer_cert_chain_streamed[0].is_none());
+    assert_eq!(
+        requester.common.get_peer_public_cert_der(0),
+        Some(cert_chain_der)
+    );
+}
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case1_send_receive_spdm_certificate_pipelined() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (mut req_config_info, req_provision_info) = create_info();
+    req_config_info.max_outstanding_requests = 2;
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    responder.common.reset_runtime_info();
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ];
+
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIoPipelined::new(&shared_buffer, &mut responder);
+
+    {
+        let mut requester = RequesterContext::new(
+            &mut device_io_requester,
+            pcidoe_transport_encap2,
+            req_config_info,
+            req_provision_info,
+        );
+
+        requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        requester.common.negotiate_info.base_asym_sel =
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+
+        assert!(requester.send_receive_spdm_certificate(None, 0).is_ok());
+
+        let expected = get_rsp_cert_chain_buff();
+        let peer_cert_chain = requester.common.peer_info.peer_cert_chain[0]
+            .as_ref()
+            .unwrap();
+        assert_eq!(peer_cert_chain.data_size, expected.data_size);
+        assert_eq!(
+            peer_cert_chain.data[..peer_cert_chain.data_size as usize],
+            expected.data[..expected.data_size as usize]
+        );
+    }
+    assert_eq!(device_io_requester.max_outstanding, 2);
+}
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case4_send_receive_spdm_certificate_pipelined_error() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (mut req_config_info, req_provision_info) = create_info();
+    req_config_info.max_outstanding_requests = 2;
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    responder.common.reset_runtime_info();
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ];
+
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIoPipelined::new(&shared_buffer, &mut responder);
+    // the first response of the pipelined window
+    device_io_requester.corrupt_response = Some(1);
+
+    {
+        let mut requester = RequesterContext::new(
+            &mut device_io_requester,
+            pcidoe_transport_encap2,
+            req_config_info,
+            req_provision_info,
+        );
+
+        requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+        requester.common.negotiate_info.base_asym_sel =
+            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+
+        assert!(requester.send_receive_spdm_certificate(None, 0).is_err());
+        assert!(requester.common.peer_info.peer_cert_chain[0].is_none());
+    }
+    assert_eq!(device_io_requester.max_outstanding, 2);
+    // the response to the second pipelined request was drained
+    assert!(device_io_requester.responses.is_empty());
 }
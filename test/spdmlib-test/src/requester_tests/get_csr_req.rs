@@ -0,0 +1,136 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::FakeSpdmDeviceIoScripted;
+use crate::common::transport::{encap, PciDoeTransportEncap, PCI_DOE_MESSAGE_HEADER_SIZE};
+use crate::common::util::create_info;
+use spdmlib::error::{SPDM_STATUS_RESET_REQUIRED_PEER, SPDM_STATUS_UNSUPPORTED_CAP};
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+const REQUESTER_INFO: [u8; 4] = [0x30, 0x02, 0x05, 0x00];
+
+fn csr_response(version: SpdmVersion, csr: &[u8]) -> Vec<u8> {
+    let mut message = vec![version.get_u8(), 0x6D, 0x00, 0x00];
+    message.extend_from_slice(&(csr.len() as u16).to_le_bytes());
+    message.extend_from_slice(&[0, 0]);
+    message.extend_from_slice(csr);
+    encap(&message)
+}
+
+fn setup_csr_negotiated(requester: &mut RequesterContext, version: SpdmVersion) {
+    requester.common.negotiate_info.spdm_version_sel = version;
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CSR_CAP;
+}
+
+#[test]
+fn test_case0_send_spdm_get_csr() {
+    let (req_config_info, req_provision_info) = create_info();
+    let csr = [0x30u8, 0x82, 0x01, 0x00, 0xAA, 0xBB, 0xCC, 0xDD];
+
+    let responses = RefCell::new(VecDeque::from(vec![csr_response(
+        SpdmVersion::SpdmVersion12,
+        &csr,
+    )]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_csr_negotiated(&mut requester, SpdmVersion::SpdmVersion12);
+
+    let csr_buffer = &mut [0u8; 64];
+    let used = requester
+        .send_spdm_get_csr(None, 0, &REQUESTER_INFO, &[], csr_buffer)
+        .unwrap();
+    assert_eq!(&csr_buffer[..used], &csr);
+
+    let requests = requests.borrow();
+    let get_csr = &requests[0][PCI_DOE_MESSAGE_HEADER_SIZE..PCI_DOE_MESSAGE_HEADER_SIZE + 12];
+    assert_eq!(
+        get_csr[..8],
+        [0x12, 0xED, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(get_csr[8..], REQUESTER_INFO);
+}
+
+#[test]
+fn test_case1_send_spdm_get_csr() {
+    let (req_config_info, req_provision_info) = create_info();
+    let csr = [0x30u8, 0x82, 0x01, 0x00];
+
+    // ERROR(ResetRequired) assigns CSRTrackingTag 5, the CSR is returned after the reset.
+    let version = SpdmVersion::SpdmVersion13.get_u8();
+    let responses = RefCell::new(VecDeque::from(vec![
+        encap(&[version, 0x7F, 0x0C, 0x05]),
+        csr_response(SpdmVersion::SpdmVersion13, &csr),
+    ]));
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_csr_negotiated(&mut requester, SpdmVersion::SpdmVersion13);
+
+    let csr_buffer = &mut [0u8; 64];
+    assert_eq!(
+        requester.send_spdm_get_csr(None, 0, &REQUESTER_INFO, &[], csr_buffer),
+        Err(SPDM_STATUS_RESET_REQUIRED_PEER)
+    );
+    let csr_tracking_tag = requester.common.peer_info.peer_csr_tracking_tag;
+    assert_eq!(csr_tracking_tag, 5);
+
+    let used = requester
+        .send_spdm_get_csr(None, csr_tracking_tag, &REQUESTER_INFO, &[], csr_buffer)
+        .unwrap();
+    assert_eq!(&csr_buffer[..used], &csr);
+
+    let requests = requests.borrow();
+    assert_eq!(requests[1][PCI_DOE_MESSAGE_HEADER_SIZE + 3], 0x05 << 3);
+}
+
+#[test]
+fn test_case2_send_spdm_get_csr() {
+    let (req_config_info, req_provision_info) = create_info();
+
+    let responses = RefCell::new(VecDeque::new());
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+
+    // GET_CSR is not sent to a responder without CSR_CAP.
+    setup_csr_negotiated(&mut requester, SpdmVersion::SpdmVersion12);
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+    assert_eq!(
+        requester.send_spdm_get_csr(None, 0, &REQUESTER_INFO, &[], &mut [0u8; 64]),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+
+    // nor in SPDM 1.1
+    setup_csr_negotiated(&mut requester, SpdmVersion::SpdmVersion11);
+    assert_eq!(
+        requester.send_spdm_get_csr(None, 0, &REQUESTER_INFO, &[], &mut [0u8; 64]),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+    assert!(requests.borrow().is_empty());
+}
@@ -5,8 +5,9 @@
 use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
 use crate::common::secret_callback::*;
 use crate::common::transport::PciDoeTransportEncap;
-use crate::common::util::create_info;
-use spdmlib::common::SpdmConnectionState;
+use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use spdmlib::common::{SpdmCertChainAlgo, SpdmConnectionState};
+use spdmlib::error::SPDM_STATUS_UNSUPPORTED_CAP;
 use spdmlib::protocol::*;
 use spdmlib::requester::RequesterContext;
 use spdmlib::{config, responder, secret};
@@ -63,3 +64,180 @@ fn test_case0_send_receive_spdm_digest() {
     let status = requester.send_receive_spdm_digest(None).is_ok();
     assert!(status);
 }
+
+#[test]
+fn test_case1_send_receive_spdm_digest_with_pub_key() {
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_requester = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.rsp_capabilities_sel =
+        SpdmResponseCapabilityFlags::CHAL_CAP | SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP;
+
+    // There is no cert chain to retrieve from the responder.
+    assert_eq!(
+        requester.send_receive_spdm_digest(None),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+    assert_eq!(
+        requester.send_receive_spdm_certificate(None, 0),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+}
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case2_send_receive_spdm_digest_per_slot_algo() {
+    let (rsp_config_info, mut rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    // slot 2 holds the same chain, declared for other algorithms
+    rsp_provision_info.my_cert_chain_data[2] = rsp_provision_info.my_cert_chain_data[0].clone();
+    rsp_provision_info.my_cert_chain_algo[0] = Some(SpdmCertChainAlgo {
+        base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+        base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+    });
+    rsp_provision_info.my_cert_chain_algo[2] = Some(SpdmCertChainAlgo {
+        base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+        base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+    });
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    assert!(responder.common.construct_my_cert_chain().is_ok());
+    assert!(responder.common.provision_info.my_cert_chain[0].is_some());
+    assert!(responder.common.provision_info.my_cert_chain[2].is_none());
+    assert!(!responder.common.is_my_cert_chain_algo_negotiated(2, false));
+    let cert_chain_hash = spdmlib::crypto::hash::hash_all(
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+        responder.common.provision_info.my_cert_chain[0]
+            .as_ref()
+            .unwrap()
+            .as_ref(),
+    )
+    .unwrap();
+
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+
+    assert!(requester.send_receive_spdm_digest(None).is_ok());
+    assert_eq!(
+        requester.common.peer_info.peer_digest_slot_mask,
+        0b0000_0001
+    );
+    assert_eq!(
+        requester.common.peer_info.peer_digests[0].as_ref(),
+        cert_chain_hash.as_ref()
+    );
+}
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case3_enumerate_slots() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    responder.common.reset_runtime_info();
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+    ];
+
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+
+    let expected = get_rsp_cert_chain_buff();
+    let expected_hash =
+        spdmlib::crypto::hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, expected.as_ref())
+            .unwrap();
+
+    let slots = requester.enumerate_slots(None, false).unwrap();
+    assert_eq!(slots.slot_mask, 0b0000_1001);
+    assert_eq!(slots.slot_ids().collect::<Vec<u8>>(), [0, 3]);
+    assert!(!slots.is_populated(1));
+    assert!(slots.get_digest(1).is_none());
+    assert_eq!(
+        slots.get_digest(3).unwrap().as_ref(),
+        expected_hash.as_ref()
+    );
+    assert!(slots.get_cert_chain(0).is_none());
+
+    let slots = requester.enumerate_slots(None, true).unwrap();
+    for slot_id in slots.slot_ids() {
+        assert_eq!(slots.get_cert_chain(slot_id), Some(expected.as_ref()));
+        assert!(
+            requester
+                .get_slot_cert_info(slot_id)
+                .unwrap()
+                .cert_chain_verified
+        );
+    }
+    assert!(slots.get_cert_chain(1).is_none());
+}
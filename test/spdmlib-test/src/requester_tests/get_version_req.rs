@@ -6,6 +6,7 @@ use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedB
 use crate::common::secret_callback::*;
 use crate::common::transport::PciDoeTransportEncap;
 use crate::common::util::create_info;
+use spdmlib::protocol::SpdmVersion;
 use spdmlib::requester::RequesterContext;
 use spdmlib::{responder, secret};
 
@@ -40,3 +41,41 @@ fn test_case0_send_receive_spdm_version() {
     let status = requester.send_receive_spdm_version().is_ok();
     assert!(status);
 }
+
+#[test]
+fn test_case1_send_receive_spdm_version() {
+    for (req_version, expected) in [
+        (SpdmVersion::SpdmVersion13, SpdmVersion::SpdmVersion13),
+        (SpdmVersion::Unknown(0), SpdmVersion::SpdmVersion12),
+    ] {
+        let (rsp_config_info, rsp_provision_info) = create_info();
+        let (mut req_config_info, req_provision_info) = create_info();
+        req_config_info.spdm_version[3] = req_version;
+
+        let shared_buffer = SharedBuffer::new();
+        let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+        let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+        secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+        let mut responder = responder::ResponderContext::new(
+            &mut device_io_responder,
+            pcidoe_transport_encap,
+            rsp_config_info,
+            rsp_provision_info,
+        );
+
+        let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+        let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+        let mut requester = RequesterContext::new(
+            &mut device_io_requester,
+            pcidoe_transport_encap2,
+            req_config_info,
+            req_provision_info,
+        );
+
+        assert!(requester.send_receive_spdm_version().is_ok());
+        assert_eq!(requester.common.negotiate_info.spdm_version_sel, expected);
+    }
+}
@@ -6,8 +6,12 @@ use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedB
 use crate::common::secret_callback::*;
 use crate::common::transport::PciDoeTransportEncap;
 use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use core::sync::atomic::{AtomicU32, Ordering};
+use spdmlib::common::opaque_provider::{SpdmOpaqueElementList, SpdmOpaqueProvider};
 use spdmlib::common::SpdmConnectionState;
 use spdmlib::common::SpdmOpaqueSupport;
+use spdmlib::error::SpdmResult;
+use spdmlib::message::SpdmRequestResponseCode;
 use spdmlib::protocol::*;
 use spdmlib::requester::RequesterContext;
 use spdmlib::{responder, secret};
@@ -107,3 +111,158 @@ fn test_case0_send_receive_spdm_key_exchange() {
         .is_ok();
     assert!(status);
 }
+
+static KEY_EXCHANGE_ELEMENTS: AtomicU32 = AtomicU32::new(0);
+static KEY_EXCHANGE_RSP_ELEMENTS: AtomicU32 = AtomicU32::new(0);
+
+// An IANA element with the enterprise number of the sender.
+fn opaque_elements_cb(
+    _spdm_version: SpdmVersion,
+    request_response_code: SpdmRequestResponseCode,
+    elements: &mut SpdmOpaqueElementList,
+) -> SpdmResult {
+    match request_response_code {
+        SpdmRequestResponseCode::SpdmRequestKeyExchange => {
+            elements.push(0x04, &[0x00, 0x00, 0x01, 0x57], &[0x01, 0x02, 0x03])
+        }
+        SpdmRequestResponseCode::SpdmResponseKeyExchangeRsp => {
+            elements.push(0x04, &[0x00, 0x00, 0x01, 0x57], &[0x04, 0x05])
+        }
+        _ => Ok(()),
+    }
+}
+
+fn opaque_element_received_cb(
+    _spdm_version: SpdmVersion,
+    request_response_code: SpdmRequestResponseCode,
+    id: u8,
+    vendor_id: &[u8],
+    element_data: &[u8],
+) -> SpdmResult {
+    assert_eq!(id, 0x04);
+    assert_eq!(vendor_id, [0x00, 0x00, 0x01, 0x57]);
+    match request_response_code {
+        SpdmRequestResponseCode::SpdmRequestKeyExchange => {
+            assert_eq!(element_data, [0x01, 0x02, 0x03]);
+            KEY_EXCHANGE_ELEMENTS.fetch_add(1, Ordering::SeqCst);
+        }
+        SpdmRequestResponseCode::SpdmResponseKeyExchangeRsp => {
+            assert_eq!(element_data, [0x04, 0x05]);
+            KEY_EXCHANGE_RSP_ELEMENTS.fetch_add(1, Ordering::SeqCst);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[test]
+fn test_case1_key_exchange_opaque_elements() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    let opaque_provider = SpdmOpaqueProvider {
+        opaque_elements_cb,
+        opaque_element_received_cb,
+    };
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    responder.common.opaque_provider = Some(opaque_provider);
+
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ];
+
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.aead_sel = SpdmAeadAlgo::AES_128_GCM;
+    responder.common.negotiate_info.dhe_sel = SpdmDheAlgo::SECP_384_R1;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.negotiate_info.opaque_data_support = SpdmOpaqueSupport::OPAQUE_DATA_FMT1;
+    #[cfg(feature = "mut-auth")]
+    {
+        responder.common.negotiate_info.rsp_capabilities_sel |=
+            SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
+        responder.common.negotiate_info.req_capabilities_sel |=
+            SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
+    }
+
+    responder.common.reset_runtime_info();
+
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ];
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.opaque_provider = Some(opaque_provider);
+
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.aead_sel = SpdmAeadAlgo::AES_128_GCM;
+    requester.common.negotiate_info.dhe_sel = SpdmDheAlgo::SECP_384_R1;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    requester.common.negotiate_info.opaque_data_support = SpdmOpaqueSupport::OPAQUE_DATA_FMT1;
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    #[cfg(feature = "mut-auth")]
+    {
+        requester.common.negotiate_info.rsp_capabilities_sel |=
+            SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
+        requester.common.negotiate_info.req_capabilities_sel |=
+            SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
+    }
+
+    requester.common.reset_runtime_info();
+
+    requester.common.peer_info.peer_cert_chain[0] = Some(get_rsp_cert_chain_buff());
+
+    let measurement_summary_hash_type =
+        SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone;
+    let session_id = requester
+        .send_receive_spdm_key_exchange(0, measurement_summary_hash_type)
+        .unwrap();
+    assert_eq!(KEY_EXCHANGE_ELEMENTS.load(Ordering::SeqCst), 1);
+    assert_eq!(KEY_EXCHANGE_RSP_ELEMENTS.load(Ordering::SeqCst), 1);
+    // the secured message version is still found with other elements
+    assert_ne!(
+        requester
+            .common
+            .get_immutable_session_via_id(session_id)
+            .unwrap()
+            .secure_spdm_version_sel,
+        0
+    );
+}
@@ -4,10 +4,22 @@
 
 #![forbid(unsafe_code)]
 
+mod async_message_req;
+
+mod attestation_report;
+
+mod capability_check;
+
 mod challenge_req;
 
+mod chunk_get_req;
+
+mod connection_driver;
+
 mod context;
 
+mod device_report;
+
 mod end_session_req;
 
 #[cfg(feature = "mut-auth")]
@@ -22,12 +34,16 @@ mod encap_error;
 #[cfg(feature = "mut-auth")]
 mod encap_req;
 
+mod error_stats;
+
 mod finish_req;
 
 mod get_capabilities_req;
 
 mod get_certificate_req;
 
+mod get_csr_req;
+
 mod get_digests_req;
 
 mod get_measurements_req;
@@ -36,14 +52,25 @@ mod get_version_req;
 
 mod heartbeat_req;
 
+mod heartbeat_task;
+
 mod key_exchange_req;
 
 mod key_update_req;
 
 mod negotiate_algorithms_req;
 
+mod negotiation_report;
+
 mod psk_exchange_req;
 
 mod psk_finish_req;
 
+#[cfg(feature = "async-io")]
+mod session_stream;
+
+mod signature_offload;
+
+mod slot_cert_info;
+
 mod vendor_req;
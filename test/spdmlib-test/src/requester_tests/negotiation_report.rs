@@ -0,0 +1,141 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::*;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::create_info;
+use codec::{Codec, Writer};
+use spdmlib::protocol::*;
+use spdmlib::requester::negotiation_report::*;
+use spdmlib::requester::RequesterContext;
+use spdmlib::{responder, secret};
+
+fn negotiation_report(rsp_capabilities: SpdmResponseCapabilityFlags) -> SpdmNegotiationReport {
+    let (mut rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+    rsp_config_info.rsp_capabilities = rsp_capabilities;
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    assert!(requester.compose_negotiation_report().is_err());
+    assert!(requester.init_connection().is_ok());
+    requester.compose_negotiation_report().unwrap()
+}
+
+#[test]
+fn test_case0_negotiation_report_diff() {
+    let rsp_capabilities = create_info().0.rsp_capabilities;
+    let baseline = negotiation_report(rsp_capabilities);
+    assert!(baseline
+        .rsp_capabilities
+        .contains(SpdmResponseCapabilityFlags::MEAS_FRESH_CAP));
+
+    let buffer = &mut [0u8; 64];
+    let mut writer = Writer::init(buffer);
+    let used = baseline.encode(&mut writer).unwrap();
+    let stored_baseline = &buffer[..used];
+
+    // Same device, same firmware
+    let report = negotiation_report(rsp_capabilities);
+    let drift = report.diff_encoded(stored_baseline).unwrap();
+    assert!(drift.is_empty());
+    assert_eq!(drift, SpdmNegotiationDrift::default());
+
+    // The device stopped advertising MEAS_FRESH_CAP
+    let report = negotiation_report(rsp_capabilities - SpdmResponseCapabilityFlags::MEAS_FRESH_CAP);
+    let drift = report.diff_encoded(stored_baseline).unwrap();
+    assert!(!drift.is_empty());
+    assert_eq!(drift.changed, SpdmNegotiationDriftFields::RSP_CAPABILITIES);
+    assert_eq!(
+        drift.rsp_capabilities_removed,
+        SpdmResponseCapabilityFlags::MEAS_FRESH_CAP
+    );
+    assert!(drift.rsp_capabilities_added.is_empty());
+
+    let mut report = baseline;
+    report.base_hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_256;
+    assert_eq!(
+        report.diff(&baseline).changed,
+        SpdmNegotiationDriftFields::BASE_HASH_ALGO
+    );
+
+    assert!(report.diff_encoded(&stored_baseline[..used - 1]).is_none());
+}
+
+#[test]
+fn test_case0_get_vca_bytes() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    assert!(requester.get_vca_bytes().is_err());
+    assert!(requester.init_connection().is_ok());
+
+    let vca = requester.get_vca_bytes().unwrap();
+    let messages = requester
+        .common
+        .runtime_info
+        .vca_boundary
+        .split(vca)
+        .unwrap();
+    let request_response_codes = [0x84u8, 0x04, 0xE1, 0x61, 0xE3, 0x63];
+    assert_eq!(
+        requester.common.runtime_info.vca_boundary.count(),
+        request_response_codes.len()
+    );
+    for (message, code) in messages.iter().zip(request_response_codes.iter()) {
+        assert_eq!(message[1], *code);
+    }
+    assert_eq!(
+        messages[..request_response_codes.len()]
+            .iter()
+            .map(|message| message.len())
+            .sum::<usize>(),
+        vca.len()
+    );
+}
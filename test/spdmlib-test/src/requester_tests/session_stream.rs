@@ -0,0 +1,279 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{
+    FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, FakeSpdmDeviceIoScripted, SharedBuffer,
+};
+use crate::common::executor::{yield_now, SeededExecutor};
+use crate::common::secret_callback::*;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::create_info;
+use bytes::Bytes;
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::Stream;
+use futures_sink::Sink;
+use spdmlib::common::session::{SpdmSession, SpdmSessionState};
+use spdmlib::error::SPDM_STATUS_INVALID_STATE_LOCAL;
+use spdmlib::protocol::*;
+use spdmlib::requester::session_stream::SpdmSessionStream;
+use spdmlib::requester::RequesterContext;
+use spdmlib::{responder, secret};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+
+#[test]
+fn test_case0_session_stream_state() {
+    let (req_config_info, req_provision_info) = create_info();
+    let responses = RefCell::new(VecDeque::new());
+    let requests = RefCell::new(Vec::new());
+
+    let mut device_io_requester = FakeSpdmDeviceIoScripted::new(&responses, &requests);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+
+    let session_id = 0xFFFEFFFEu32;
+    assert_eq!(
+        SpdmSessionStream::new(&mut requester, session_id).err(),
+        Some(SPDM_STATUS_INVALID_STATE_LOCAL)
+    );
+
+    requester.common.session[0].setup(session_id).unwrap();
+    requester.common.session[0].set_session_state(SpdmSessionState::SpdmSessionEstablished);
+    let stream = SpdmSessionStream::new(&mut requester, session_id).unwrap();
+    assert_eq!(stream.session_id(), session_id);
+
+    let requester = stream.into_inner();
+    requester.common.session[0].set_session_state(SpdmSessionState::SpdmSessionHandshaking);
+    assert_eq!(
+        SpdmSessionStream::new(requester, session_id).err(),
+        Some(SPDM_STATUS_INVALID_STATE_LOCAL)
+    );
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn setup_session(session: &mut SpdmSession, session_id: u32) {
+    session.setup(session_id).unwrap();
+    session.set_crypto_param(
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+        SpdmDheAlgo::SECP_384_R1,
+        SpdmAeadAlgo::AES_256_GCM,
+        SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+    );
+    assert!(session
+        .set_dhe_secret(
+            SpdmVersion::SpdmVersion12,
+            SpdmDheFinalKeyStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+            }
+        )
+        .is_ok());
+    assert!(session
+        .generate_handshake_secret(
+            SpdmVersion::SpdmVersion12,
+            &SpdmDigestStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+            }
+        )
+        .is_ok());
+    assert!(session
+        .generate_data_secret(
+            SpdmVersion::SpdmVersion12,
+            &SpdmDigestStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+            }
+        )
+        .is_ok());
+    session.set_session_state(SpdmSessionState::SpdmSessionEstablished);
+}
+
+fn setup_responder(responder: &mut responder::ResponderContext, session_id: u32) {
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.session = gen_array_clone(SpdmSession::new(), 4);
+    setup_session(&mut responder.common.session[0], session_id);
+}
+
+fn setup_requester(requester: &mut RequesterContext, session_id: u32) {
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.session = gen_array_clone(SpdmSession::new(), 4);
+    setup_session(&mut requester.common.session[0], session_id);
+}
+
+#[test]
+fn test_case1_session_stream_round_trip() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let session_id = (0x11u32 << 16) + 0x11u32;
+    setup_responder(&mut responder, session_id);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_requester(&mut requester, session_id);
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut stream = SpdmSessionStream::new(&mut requester, session_id).unwrap();
+
+    // Nothing received yet, the stream waits instead of failing on ST1.
+    assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+
+    // The test transport does not tell application messages apart, so the
+    // responder answers the HEARTBEAT sent through the sink.
+    let heartbeat = Bytes::from_static(&[0x12, 0xE8, 0x00, 0x00]);
+    assert_eq!(
+        Pin::new(&mut stream).poll_ready(&mut cx),
+        Poll::Ready(Ok(()))
+    );
+    assert_eq!(Pin::new(&mut stream).start_send(heartbeat), Ok(()));
+    assert_eq!(
+        Pin::new(&mut stream).poll_flush(&mut cx),
+        Poll::Ready(Ok(()))
+    );
+
+    match Pin::new(&mut stream).poll_next(&mut cx) {
+        Poll::Ready(Some(Ok(heartbeat_ack))) => {
+            assert_eq!(&heartbeat_ack[..], &[0x12, 0x68, 0x00, 0x00]);
+        }
+        _ => panic!("HEARTBEAT_ACK is expected"),
+    }
+    assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+}
+
+const HEARTBEAT_COUNT: usize = 3;
+
+// Send HEARTBEAT_COUNT heartbeats through the session stream of requester,
+// logging id once each HEARTBEAT_ACK is received.
+async fn heartbeat_task(
+    requester: &mut RequesterContext<'_>,
+    session_id: u32,
+    id: u8,
+    log: &RefCell<Vec<u8>>,
+) {
+    let mut stream = SpdmSessionStream::new(requester, session_id).unwrap();
+    for _ in 0..HEARTBEAT_COUNT {
+        let heartbeat = Bytes::from_static(&[0x12, 0xE8, 0x00, 0x00]);
+        poll_fn(|cx| Pin::new(&mut stream).poll_ready(cx))
+            .await
+            .unwrap();
+        Pin::new(&mut stream).start_send(heartbeat).unwrap();
+        poll_fn(|cx| Pin::new(&mut stream).poll_flush(cx))
+            .await
+            .unwrap();
+        yield_now().await;
+        let heartbeat_ack = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&heartbeat_ack[..], &[0x12, 0x68, 0x00, 0x00]);
+        log.borrow_mut().push(id);
+    }
+}
+
+// Run two requesters, each talking to a responder of its own, interleaved by seed.
+fn run_heartbeat_interleaving(seed: u64) -> Vec<u8> {
+    let session_id = (0x11u32 << 16) + 0x11u32;
+    let log = RefCell::new(Vec::new());
+
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    setup_responder(&mut responder, session_id);
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_requester(&mut requester, session_id);
+
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+    let shared_buffer1 = SharedBuffer::new();
+    let mut device_io_responder1 = FakeSpdmDeviceIoReceve::new(&shared_buffer1);
+    let pcidoe_transport_encap3 = &mut PciDoeTransportEncap {};
+    let mut responder1 = responder::ResponderContext::new(
+        &mut device_io_responder1,
+        pcidoe_transport_encap3,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    setup_responder(&mut responder1, session_id);
+    let pcidoe_transport_encap4 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester1 = FakeSpdmDeviceIo::new(&shared_buffer1, &mut responder1);
+    let mut requester1 = RequesterContext::new(
+        &mut device_io_requester1,
+        pcidoe_transport_encap4,
+        req_config_info,
+        req_provision_info,
+    );
+    setup_requester(&mut requester1, session_id);
+
+    let mut executor = SeededExecutor::new(seed);
+    executor.spawn(heartbeat_task(&mut requester, session_id, 0, &log));
+    executor.spawn(heartbeat_task(&mut requester1, session_id, 1, &log));
+    assert!(executor.run(256));
+    drop(executor);
+    log.into_inner()
+}
+
+#[test]
+fn test_case2_session_stream_seeded_interleaving() {
+    let log = run_heartbeat_interleaving(0);
+    assert_eq!(log.len(), HEARTBEAT_COUNT * 2);
+    assert_eq!(log.iter().filter(|id| **id == 0).count(), HEARTBEAT_COUNT);
+    // the same seed replays the same interleaving
+    assert_eq!(run_heartbeat_interleaving(0), log);
+
+    let logs: Vec<Vec<u8>> = (0..16).map(run_heartbeat_interleaving).collect();
+    assert!(logs.iter().any(|other| *other != log));
+}
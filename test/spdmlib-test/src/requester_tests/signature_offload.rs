@@ -0,0 +1,108 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::crypto_callback::FAKE_RAND;
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::SECRET_ASYM_IMPL_INSTANCE;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use spdmlib::common::SpdmConnectionState;
+use spdmlib::error::SpdmResult;
+use spdmlib::message::SpdmRequestResponseCode;
+use spdmlib::protocol::*;
+use spdmlib::requester::signature_offload::{register_signature_offload, SpdmSignatureOffload};
+use spdmlib::requester::RequesterContext;
+use spdmlib::{config, crypto, responder, secret};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CHALLENGE_AUTH_VERIFIED: AtomicUsize = AtomicUsize::new(0);
+
+// Delegate to local verification, so other tests are not affected.
+#[allow(clippy::too_many_arguments)]
+fn fake_verify(
+    request_response_code: SpdmRequestResponseCode,
+    _slot_id: u8,
+    base_hash_algo: SpdmBaseHashAlgo,
+    base_asym_algo: SpdmBaseAsymAlgo,
+    public_cert_der: &[u8],
+    transcript_hash: &SpdmDigestStruct,
+    message: &[u8],
+    signature: &SpdmSignatureStruct,
+) -> SpdmResult {
+    assert_eq!(transcript_hash.data_size, base_hash_algo.get_size());
+    if request_response_code == SpdmRequestResponseCode::SpdmResponseChallengeAuth {
+        // SPDM 1.2 signs the signing prefix followed by the transcript hash
+        assert!(message.ends_with(transcript_hash.as_ref()));
+        CHALLENGE_AUTH_VERIFIED.fetch_add(1, Ordering::SeqCst);
+    }
+    crypto::asym_verify::verify(
+        base_hash_algo,
+        base_asym_algo,
+        public_cert_der,
+        message,
+        signature,
+    )
+}
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case0_signature_offload() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    crypto::rand::register(FAKE_RAND.clone());
+    register_signature_offload(SpdmSignatureOffload {
+        verify_cb: fake_verify,
+    });
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    responder.common.reset_runtime_info();
+    responder.common.provision_info.my_cert_chain[0] = Some(SpdmCertChainBuffer {
+        data_size: 512u16,
+        data: [0u8; 4 + SPDM_MAX_HASH_SIZE + config::MAX_SPDM_CERT_CHAIN_DATA_SIZE],
+    });
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.reset_runtime_info();
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    requester.common.peer_info.peer_cert_chain[0] = Some(get_rsp_cert_chain_buff());
+
+    let verified = CHALLENGE_AUTH_VERIFIED.load(Ordering::SeqCst);
+    assert!(requester
+        .send_receive_spdm_challenge(
+            0,
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+        )
+        .is_ok());
+    assert!(CHALLENGE_AUTH_VERIFIED.load(Ordering::SeqCst) > verified);
+}
@@ -0,0 +1,110 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::MySpdmDeviceIo;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use spdmlib::crypto;
+use spdmlib::message::{SpdmKeyPairInfo, SpdmKeyPairInfoResponsePayload};
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+
+#[test]
+fn test_case0_get_slot_cert_info() {
+    let (req_config_info, req_provision_info) = create_info();
+    let device_io = &mut MySpdmDeviceIo;
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut requester = RequesterContext::new(
+        device_io,
+        pcidoe_transport_encap,
+        req_config_info,
+        req_provision_info,
+    );
+
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+
+    assert!(requester.get_slot_cert_info(0).is_err());
+
+    let cert_chain = get_rsp_cert_chain_buff();
+    requester.common.peer_info.peer_digest_slot_mask = 0b0000_0011;
+    requester.common.peer_info.peer_digests[0] = crypto::hash::hash_all(
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+        &cert_chain.data[..cert_chain.data_size as usize],
+    )
+    .unwrap();
+    requester.common.peer_info.peer_cert_chain[0] = Some(cert_chain.clone());
+
+    let info = requester.get_slot_cert_info(0).unwrap();
+    assert_eq!(
+        info.cert_model,
+        SpdmCertificateModelType::SpdmCertModelTypeDeviceCert
+    );
+    assert!(info.cert_chain_verified);
+    assert!(info.is_usage_allowed(SpdmKeyUsageMask::MEASUREMENT_USE));
+
+    // slot 1 is populated but its cert chain is not retrieved
+    let info = requester.get_slot_cert_info(1).unwrap();
+    assert!(!info.cert_chain_verified);
+    assert!(requester.get_slot_cert_info(2).is_err());
+
+    requester.common.negotiate_info.rsp_capabilities_sel |=
+        SpdmResponseCapabilityFlags::ALIAS_CERT_CAP;
+    let slots = requester.get_all_slot_cert_info().unwrap();
+    assert_eq!(
+        slots[0].as_ref().unwrap().cert_model,
+        SpdmCertificateModelType::SpdmCertModelTypeAliasCert
+    );
+    assert!(slots[1].is_some());
+    assert!(slots[2].is_none());
+
+    // cert chain mismatching the digest
+    requester.common.peer_info.peer_cert_chain[1] = Some(cert_chain);
+    assert!(requester.get_slot_cert_info(1).is_err());
+    assert!(requester.get_all_slot_cert_info().is_err());
+
+    // multi-key connection reports the per slot info from DIGESTS
+    requester.common.peer_info.peer_cert_chain[1] = None;
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+    requester.common.negotiate_info.rsp_capabilities_sel =
+        SpdmResponseCapabilityFlags::CERT_CAP | SpdmResponseCapabilityFlags::MULTI_KEY_CAP_ONLY;
+    requester.common.peer_info.peer_key_pair_id[1] = 2;
+    requester.common.peer_info.peer_cert_model[1] =
+        SpdmCertificateModelType::SpdmCertModelTypeAliasCert;
+    requester.common.peer_info.peer_key_usage_mask[1] = SpdmKeyUsageMask::MEASUREMENT_USE;
+    let info = requester.get_slot_cert_info(1).unwrap();
+    assert_eq!(info.key_pair_id, 2);
+    assert_eq!(
+        info.cert_model,
+        SpdmCertificateModelType::SpdmCertModelTypeAliasCert
+    );
+    assert!(info.is_usage_allowed(SpdmKeyUsageMask::MEASUREMENT_USE));
+    assert!(!info.is_usage_allowed(SpdmKeyUsageMask::KEY_EX_USE));
+
+    // cross-check against the KEY_PAIR_INFO of the key pair
+    let mut key_pair_info = SpdmKeyPairInfoResponsePayload {
+        total_key_pairs: 2,
+        key_pair_id: 2,
+        key_pair_info: SpdmKeyPairInfo {
+            current_key_usage: SpdmKeyUsageMask::MEASUREMENT_USE | SpdmKeyUsageMask::CHALLENGE_USE,
+            assoc_cert_slot_mask: 0b0000_0010,
+            ..Default::default()
+        },
+    };
+    assert!(info.check_key_pair_info(&key_pair_info).is_ok());
+    key_pair_info.key_pair_info.assoc_cert_slot_mask = 0b0000_0001;
+    assert!(info.check_key_pair_info(&key_pair_info).is_err());
+    key_pair_info.key_pair_info.assoc_cert_slot_mask = 0b0000_0010;
+    key_pair_info.key_pair_info.current_key_usage = SpdmKeyUsageMask::CHALLENGE_USE;
+    assert!(info.check_key_pair_info(&key_pair_info).is_err());
+    key_pair_info.key_pair_info.current_key_usage = SpdmKeyUsageMask::MEASUREMENT_USE;
+    key_pair_info.key_pair_id = 1;
+    assert!(info.check_key_pair_info(&key_pair_info).is_err());
+    key_pair_info.key_pair_id = 2;
+    key_pair_info.total_key_pairs = 1;
+    assert!(info.check_key_pair_info(&key_pair_info).is_err());
+
+    // slot 0 reports no certificate model
+    assert!(requester.get_slot_cert_info(0).is_err());
+}
@@ -6,10 +6,14 @@ use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedB
 use crate::common::secret_callback::*;
 use crate::common::transport::PciDoeTransportEncap;
 use crate::common::util::create_info;
+use codec::Writer;
+use spdmlib::error::SPDM_STATUS_VENDOR_ERROR_PEER;
 use spdmlib::message::{
-    RegistryOrStandardsBodyID, VendorDefinedReqPayloadStruct, VendorIDStruct,
-    MAX_SPDM_VENDOR_DEFINED_VENDOR_ID_LEN,
+    RegistryOrStandardsBodyID, SpdmErrorResponseVendorExtData, SpdmVendorDefinedError,
+    VendorDefinedReqPayloadStruct, VendorIDStruct, MAX_SPDM_VENDOR_DEFINED_VENDOR_ID_LEN,
+    SPDM_ERROR_VENDOR_EXT_DATA_SIZE,
 };
+use spdmlib::protocol::SpdmVersion;
 use spdmlib::requester::RequesterContext;
 use spdmlib::responder::ResponderContext;
 use spdmlib::{config, secret};
@@ -63,3 +67,56 @@ fn test_case0_send_spdm_vendor_defined_request() {
         .is_ok();
     assert_eq!(status, false); //since vendor defined response payload is not implemented, so false is expected here.
 }
+
+#[test]
+fn test_case1_handle_spdm_vendor_defined_error() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+
+    let mut data = [0u8; SPDM_ERROR_VENDOR_EXT_DATA_SIZE];
+    data[..5].copy_from_slice(&[0x02, 0x86, 0x80, 0xde, 0xad]);
+    let vendor_error = SpdmVendorDefinedError {
+        error_data: RegistryOrStandardsBodyID::PCISIG.get_u16() as u8,
+        extended_data: SpdmErrorResponseVendorExtData { data_size: 5, data },
+    };
+    let mut error_buffer = [0u8; config::MAX_SPDM_MSG_SIZE];
+    let mut writer = Writer::init(&mut error_buffer);
+    responder.write_spdm_vendor_error(&vendor_error, &mut writer);
+    let used = writer.used();
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+
+    let status = requester.handle_spdm_vendor_defined_respond(None, &error_buffer[..used]);
+    assert_eq!(status.unwrap_err(), SPDM_STATUS_VENDOR_ERROR_PEER);
+    assert_eq!(
+        requester.common.peer_info.peer_vendor_error,
+        Some(vendor_error)
+    );
+
+    // a successful response clears the stale error
+    assert!(requester.send_receive_spdm_version().is_ok());
+    assert_eq!(requester.common.peer_info.peer_vendor_error, None);
+}
@@ -0,0 +1,58 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::create_info;
+use spdmlib::error::{
+    SPDM_STATUS_INVALID_CERT, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_UNSUPPORTED_CAP,
+};
+use spdmlib::protocol::*;
+use spdmlib::responder::ResponderContext;
+use spdmlib::secret::{self, SpdmSecretAliasCert};
+
+// The test certificates carry no DICE TcbInfo.
+fn generate_alias_cert_chain(
+    _spdm_version: SpdmVersion,
+    _base_hash_algo: SpdmBaseHashAlgo,
+    _base_asym_algo: SpdmBaseAsymAlgo,
+    _slot_id: u8,
+) -> Option<SpdmCertChainData> {
+    let (_, provision_info) = create_info();
+    provision_info.my_cert_chain_data[0].clone()
+}
+
+#[test]
+fn test_case0_regenerate_alias_cert_chain() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    secret::alias_cert::register(SpdmSecretAliasCert {
+        generate_alias_cert_chain_cb: generate_alias_cert_chain,
+    });
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    assert_eq!(
+        context.regenerate_alias_cert_chain(0),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+
+    context.common.config_info.rsp_capabilities |= SpdmResponseCapabilityFlags::ALIAS_CERT_CAP;
+    assert_eq!(
+        context.regenerate_alias_cert_chain(SPDM_MAX_SLOT_NUMBER as u8),
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    );
+    assert_eq!(
+        context.regenerate_alias_cert_chain(1),
+        Err(SPDM_STATUS_INVALID_CERT)
+    );
+    assert!(context.common.provision_info.my_cert_chain_data[1].is_none());
+}
@@ -7,7 +7,7 @@
 use crate::common::crypto_callback::FAKE_RAND;
 use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
 use crate::common::secret_callback::SECRET_ASYM_IMPL_INSTANCE;
-use crate::common::transport::PciDoeTransportEncap;
+use crate::common::transport::{PciDoeTransportEncap, PCI_DOE_MESSAGE_HEADER_SIZE};
 use crate::common::util::create_info;
 use codec::{Codec, Reader, Writer};
 use spdmlib::common::*;
@@ -70,6 +70,7 @@ fn test_case0_handle_spdm_challenge() {
         nonce: SpdmNonceStruct {
             data: [100u8; SPDM_NONCE_SIZE],
         },
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(value.spdm_encode(&mut context.common, &mut writer).is_ok());
 
@@ -156,3 +157,75 @@ fn test_case0_handle_spdm_challenge() {
         }
     }
 }
+
+#[test]
+#[cfg(feature = "hashed-transcript-data")]
+fn test_case1_handle_spdm_challenge_with_pub_key() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    crypto::rand::register(FAKE_RAND.clone());
+
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    let mut my_pub_key = SpdmCertChainData::default();
+    my_pub_key.data_size = 120;
+    my_pub_key.data[..120].copy_from_slice(&[0x5a; 120]);
+    context.common.provision_info.my_pub_key = Some(my_pub_key);
+
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    context.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let mut challenge = vec![
+        0x12,
+        SpdmRequestResponseCode::SpdmRequestChallenge.get_u8(),
+        SPDM_PUB_KEY_SLOT_ID,
+        0x00,
+    ];
+    challenge.extend_from_slice(&[0x11; SPDM_NONCE_SIZE]);
+    let buffer = &mut [0u8; config::SENDER_BUFFER_SIZE];
+
+    // The public key slot needs PUB_KEY_ID_CAP.
+    assert!(context.handle_spdm_challenge(&challenge).is_ok());
+    shared_buffer.get_buffer(buffer);
+    assert_eq!(
+        buffer[PCI_DOE_MESSAGE_HEADER_SIZE..PCI_DOE_MESSAGE_HEADER_SIZE + 4],
+        [
+            0x12,
+            SpdmRequestResponseCode::SpdmResponseError.get_u8(),
+            SpdmErrorCode::SpdmErrorInvalidRequest.get_u8(),
+            0x00
+        ]
+    );
+
+    context.common.negotiate_info.rsp_capabilities_sel |=
+        SpdmResponseCapabilityFlags::PUB_KEY_ID_CAP;
+    assert!(context.handle_spdm_challenge(&challenge).is_ok());
+    let used = shared_buffer.get_buffer(buffer);
+    let response = &buffer[PCI_DOE_MESSAGE_HEADER_SIZE..used];
+    // SlotID 0xF and an empty slot mask
+    assert_eq!(
+        response[..4],
+        [
+            0x12,
+            SpdmRequestResponseCode::SpdmResponseChallengeAuth.get_u8(),
+            0x0F,
+            0x00
+        ]
+    );
+    let pub_key_hash =
+        crypto::hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, &[0x5a; 120]).unwrap();
+    assert_eq!(&response[4..4 + SHA384_DIGEST_SIZE], pub_key_hash.as_ref());
+}
@@ -0,0 +1,150 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use spdmlib::protocol::*;
+use spdmlib::responder::ResponderContext;
+
+const LARGE_REQUEST_HANDLE: u8 = 0xA5;
+
+fn chunk_send(chunk_seq_no: u16, last: bool, large_message_size: u32, chunk: &[u8]) -> Vec<u8> {
+    let mut message = vec![
+        SpdmVersion::SpdmVersion12.get_u8(),
+        0x85,
+        last as u8,
+        LARGE_REQUEST_HANDLE,
+    ];
+    message.extend_from_slice(&chunk_seq_no.to_le_bytes());
+    message.extend_from_slice(&[0, 0]);
+    message.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+    if chunk_seq_no == 0 {
+        message.extend_from_slice(&large_message_size.to_le_bytes());
+    }
+    message.extend_from_slice(chunk);
+    message
+}
+
+fn setup_chunk_negotiated(responder: &mut ResponderContext) {
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    responder.common.negotiate_info.req_capabilities_sel = SpdmRequestCapabilityFlags::CHUNK_CAP;
+    responder.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CHUNK_CAP;
+    responder.common.negotiate_info.rsp_data_transfer_size_sel = 2;
+}
+
+// The sent message may be followed by PCI DOE padding.
+#[test]
+fn test_case0_handle_spdm_chunk_send() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_chunk_negotiated(&mut context);
+
+    // GET_VERSION delivered in two chunks.
+    let large_request = [SpdmVersion::SpdmVersion10.get_u8(), 0x84, 0x00, 0x00];
+
+    assert!(context
+        .dispatch_message(&chunk_send(0, false, 4, &large_request[..2]))
+        .is_ok());
+    let ack = sent_message(&shared_buffer);
+    assert_eq!(
+        ack[..6],
+        [0x12, 0x05, 0x00, LARGE_REQUEST_HANDLE, 0x00, 0x00]
+    );
+
+    assert!(context
+        .dispatch_message(&chunk_send(1, true, 0, &large_request[2..]))
+        .is_ok());
+    let ack = sent_message(&shared_buffer);
+    assert_eq!(
+        ack[..6],
+        [0x12, 0x05, 0x00, LARGE_REQUEST_HANDLE, 0x01, 0x00]
+    );
+    // VERSION response wrapped in the ack of the last chunk
+    assert_eq!(ack[6], SpdmVersion::SpdmVersion10.get_u8());
+    assert_eq!(ack[7], 0x04);
+}
+
+#[test]
+fn test_case1_handle_spdm_chunk_send() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_chunk_negotiated(&mut context);
+
+    let large_request = [0u8; 16];
+    assert!(context
+        .dispatch_message(&chunk_send(0, false, 16, &large_request[..8]))
+        .is_ok());
+    sent_message(&shared_buffer);
+
+    // ChunkSeqNo 1 is skipped, ERROR(InvalidRequest) is reported in the ack.
+    assert!(context
+        .dispatch_message(&chunk_send(2, true, 0, &large_request[8..]))
+        .is_ok());
+    let ack = sent_message(&shared_buffer);
+    assert_eq!(
+        ack[..6],
+        [0x12, 0x05, 0x01, LARGE_REQUEST_HANDLE, 0x02, 0x00]
+    );
+    assert_eq!(ack[6..10], [0x12, 0x7F, 0x01, 0x00]);
+
+    // The transfer is aborted, the next chunk is rejected too.
+    assert!(context
+        .dispatch_message(&chunk_send(1, true, 0, &large_request[8..]))
+        .is_ok());
+    let ack = sent_message(&shared_buffer);
+    assert_eq!(ack[2], 0x01);
+    assert_eq!(ack[8], 0x01);
+}
+
+#[test]
+fn test_case2_handle_spdm_chunk_send() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_chunk_negotiated(&mut context);
+
+    // LargeMessageSize above MaxSPDMmsgSize.
+    let large_message_size = context.common.config_info.max_spdm_msg_size + 1;
+    assert!(context
+        .dispatch_message(&chunk_send(0, false, large_message_size, &[0u8; 8]))
+        .is_ok());
+    let ack = sent_message(&shared_buffer);
+    assert_eq!(ack[2], 0x01);
+    assert_eq!(ack[6..10], [0x12, 0x7F, 0x0E, 0x00]);
+
+    // Without CHUNK_CAP the request is not supported.
+    context.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+    assert!(context
+        .dispatch_message(&chunk_send(0, false, 16, &[0u8; 8]))
+        .is_ok());
+    let error = sent_message(&shared_buffer);
+    assert_eq!(error, [0x12, 0x7F, 0x07, 0x85]);
+}
@@ -2,16 +2,17 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
-use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::device_io::{FakeSpdmDeviceIoDisconnect, FakeSpdmDeviceIoReceve, SharedBuffer};
 use crate::common::secret_callback::*;
 use crate::common::transport::*;
 use crate::common::util::create_info;
 use codec::{Codec, Writer};
-use spdmlib::common::session::{SpdmSession, SpdmSessionState};
+use spdmlib::common::session::{SpdmSession, SpdmSessionEvictionPolicy, SpdmSessionState};
 use spdmlib::common::*;
 use spdmlib::message::*;
 use spdmlib::protocol::*;
 use spdmlib::{config, responder, secret};
+use std::cell::Cell;
 
 #[test]
 fn test_case0_send_secured_message() {
@@ -161,6 +162,57 @@ fn test_case0_process_message() {
     let status = context.process_message(ST1, &[0]).is_err();
     assert!(status);
 }
+
+#[test]
+fn test_case0_process_message_peer_disconnect() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let disconnected = Cell::new(false);
+    let mut socket_io_transport = FakeSpdmDeviceIoDisconnect::new(&shared_buffer, &disconnected);
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    context.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+    let session_id = (0xffu32 << 16) + 0xffu32;
+    context.common.session = gen_array_clone(SpdmSession::new(), 4);
+    context.common.session[0].setup(session_id).unwrap();
+    context.common.session[0].set_crypto_param(
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+        SpdmDheAlgo::SECP_384_R1,
+        SpdmAeadAlgo::AES_256_GCM,
+        SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+    );
+    context.common.session[0].set_session_state(SpdmSessionState::SpdmSessionEstablished);
+
+    disconnected.set(true);
+    assert!(context.process_message(ST1, &[0]).is_err());
+
+    assert!(context.common.get_session_via_id(session_id).is_none());
+    assert_eq!(
+        context.common.session[0].get_session_state(),
+        SpdmSessionState::SpdmSessionNotStarted
+    );
+    assert_eq!(
+        context.common.runtime_info.get_connection_state(),
+        SpdmConnectionState::SpdmConnectionNotStarted
+    );
+    assert_eq!(
+        context.common.negotiate_info.base_hash_sel,
+        SpdmBaseHashAlgo::default()
+    );
+}
+
 #[test]
 fn test_case0_dispatch_secured_message() {
     let (config_info, provision_info) = create_info();
@@ -306,6 +358,111 @@ fn test_case0_dispatch_secured_message() {
     }
 }
 
+#[test]
+fn test_case0_session_eviction_policy() {
+    let (mut config_info, provision_info) = create_info();
+    config_info.max_session_count = 2;
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    assert_eq!(context.common.get_session_capacity(), 2);
+
+    for session_id in [0xFFFEFFFEu32, 0xFFFDFFFD] {
+        let session = context.common.get_next_avaiable_session().unwrap();
+        session.setup(session_id).unwrap();
+        session.set_session_state(SpdmSessionState::SpdmSessionHandshaking);
+    }
+    assert!(context.common.get_next_avaiable_session().is_none());
+    assert!(context.common.get_next_half_session_id(false).is_err());
+
+    // the default policy keeps the sessions
+    assert!(context.common.evict_session().is_none());
+
+    context.common.config_info.session_eviction_policy =
+        SpdmSessionEvictionPolicy::EvictLeastRecentlyUsedHandshaking;
+    context.common.mark_session_used(0xFFFEFFFE);
+    let evicted = context.common.evict_session().unwrap();
+    assert_eq!(evicted.get_session_id(), 0xFFFDFFFD);
+    assert!(context
+        .common
+        .get_immutable_session_via_id(0xFFFDFFFD)
+        .is_none());
+    assert!(context.common.get_next_half_session_id(false).is_ok());
+
+    // no new session, the evicted session is put back
+    assert_eq!(context.common.complete_session_eviction(evicted), None);
+    assert!(context
+        .common
+        .get_immutable_session_via_id(0xFFFDFFFD)
+        .is_some());
+
+    // a new session replaces the evicted session
+    let evicted = context.common.evict_session().unwrap();
+    let session = context.common.get_next_avaiable_session().unwrap();
+    session.setup(0xFFFCFFFC).unwrap();
+    session.set_session_state(SpdmSessionState::SpdmSessionHandshaking);
+    assert_eq!(
+        context.common.complete_session_eviction(evicted),
+        Some(0xFFFDFFFD)
+    );
+    assert!(context
+        .common
+        .get_immutable_session_via_id(0xFFFDFFFD)
+        .is_none());
+
+    // established sessions are never evicted
+    context
+        .common
+        .get_session_via_id(0xFFFCFFFC)
+        .unwrap()
+        .set_session_state(SpdmSessionState::SpdmSessionEstablished);
+    context.common.config_info.session_eviction_policy =
+        SpdmSessionEvictionPolicy::Custom(|_| Some(1));
+    assert!(context.common.evict_session().is_none());
+    context.common.config_info.session_eviction_policy =
+        SpdmSessionEvictionPolicy::Custom(|_| Some(0));
+    assert_eq!(
+        context.common.evict_session().unwrap().get_session_id(),
+        0xFFFEFFFE
+    );
+}
+
+#[test]
+fn test_case0_session_capacity_above_default() {
+    let (mut config_info, provision_info) = create_info();
+    let session_count = config::MAX_SPDM_SESSION_COUNT + 2;
+    config_info.max_session_count = session_count;
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    assert_eq!(context.common.get_session_capacity(), session_count);
+
+    for _ in 0..session_count {
+        let half_session_id = context.common.get_next_half_session_id(false).unwrap();
+        let session_id = ((half_session_id as u32) << 16) + 0xFFFE;
+        let session = context.common.get_next_avaiable_session().unwrap();
+        session.setup(session_id).unwrap();
+        session.set_session_state(SpdmSessionState::SpdmSessionHandshaking);
+    }
+    assert!(context.common.get_next_avaiable_session().is_none());
+    assert!(context.common.get_next_half_session_id(false).is_err());
+    assert_eq!(context.common.get_session_status().len(), session_count);
+}
+
 fn dispatch_secured_data(
     num: usize,
     status: bool,
@@ -0,0 +1,130 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use spdmlib::common::SpdmConnectionState;
+use spdmlib::protocol::*;
+use spdmlib::responder::ResponderContext;
+use spdmlib::secret::{self, SpdmCsrResult, SpdmSecretCsr};
+
+// Echo requester_info as the CSR, ask for a reset if it is empty.
+fn generate_csr_cb(
+    _spdm_version: SpdmVersion,
+    _base_hash_algo: SpdmBaseHashAlgo,
+    _base_asym_algo: SpdmBaseAsymAlgo,
+    csr_tracking_tag: u8,
+    requester_info: &[u8],
+    _opaque_data: &[u8],
+    csr: &mut [u8],
+) -> SpdmCsrResult {
+    if requester_info.is_empty() {
+        return SpdmCsrResult::ResetRequired(csr_tracking_tag);
+    }
+    csr[..requester_info.len()].copy_from_slice(requester_info);
+    SpdmCsrResult::Generated(requester_info.len())
+}
+
+fn get_csr(version: SpdmVersion, csr_tracking_tag: u8, requester_info: &[u8]) -> Vec<u8> {
+    // CSRTrackingTag is carried in RequestAttributes bits [5:3].
+    let mut message = vec![version.get_u8(), 0xED, 0x00, csr_tracking_tag << 3];
+    message.extend_from_slice(&(requester_info.len() as u16).to_le_bytes());
+    message.extend_from_slice(&[0, 0]);
+    message.extend_from_slice(requester_info);
+    message
+}
+
+fn setup_csr_negotiated(responder: &mut ResponderContext, version: SpdmVersion) {
+    secret::csr::register(SpdmSecretCsr { generate_csr_cb });
+    responder.common.negotiate_info.spdm_version_sel = version;
+    responder.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CSR_CAP;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+}
+
+// The sent message may be followed by PCI DOE padding.
+#[test]
+fn test_case0_handle_spdm_get_csr() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_csr_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+
+    let requester_info = [0x30, 0x03, 0x02, 0x01, 0x00];
+    assert!(context
+        .dispatch_message(&get_csr(SpdmVersion::SpdmVersion12, 0, &requester_info))
+        .is_ok());
+    let csr = sent_message(&shared_buffer);
+    assert_eq!(csr[..8], [0x12, 0x6D, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00]);
+    assert_eq!(csr[8..13], requester_info);
+}
+
+#[test]
+fn test_case1_handle_spdm_get_csr() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_csr_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+
+    // ERROR(ResetRequired), ErrorData is reserved before SPDM 1.3.
+    assert!(context
+        .dispatch_message(&get_csr(SpdmVersion::SpdmVersion12, 0, &[]))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x0C, 0x00]);
+
+    // SPDM 1.3 reports the CSRTrackingTag in ErrorData.
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+    assert!(context
+        .dispatch_message(&get_csr(SpdmVersion::SpdmVersion13, 0x05, &[]))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x13, 0x7F, 0x0C, 0x05]);
+}
+
+#[test]
+fn test_case2_handle_spdm_get_csr() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_csr_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+    context.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::empty();
+
+    assert!(context
+        .dispatch_message(&get_csr(SpdmVersion::SpdmVersion12, 0, &[0x30, 0x00]))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x07, 0x00]);
+
+    // GET_CSR is not defined before SPDM 1.2.
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
+    assert!(context
+        .dispatch_message(&get_csr(SpdmVersion::SpdmVersion11, 0, &[0x30, 0x00]))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x11, 0x7F, 0x07, 0x00]);
+}
@@ -0,0 +1,140 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::*;
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use core::sync::atomic::{AtomicBool, Ordering};
+use spdmlib::common::SpdmConnectionState;
+use spdmlib::message::*;
+use spdmlib::protocol::*;
+use spdmlib::responder::{ResponderContext, SpdmResponseReadiness, SpdmResponseReadyHandler};
+use spdmlib::secret;
+
+static MEASUREMENT_READY: AtomicBool = AtomicBool::new(false);
+
+fn check_response_ready_cb(
+    _spdm_version: SpdmVersion,
+    _session_id: Option<u32>,
+    _request: &[u8],
+) -> SpdmResponseReadiness {
+    if MEASUREMENT_READY.load(Ordering::SeqCst) {
+        SpdmResponseReadiness::Ready
+    } else {
+        SpdmResponseReadiness::NotReady {
+            rdt_exponent: 3,
+            rdtm: 2,
+        }
+    }
+}
+
+// The sent message may be followed by PCI DOE padding.
+#[test]
+fn test_case0_handle_spdm_respond_if_ready() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
+    context.response_ready_handler = Some(SpdmResponseReadyHandler {
+        check_response_ready_cb,
+    });
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
+    context.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.measurement_specification_sel =
+        SpdmMeasurementSpecification::DMTF;
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    // GET_MEASUREMENTS for the total number of measurement blocks.
+    assert!(context.dispatch_message(&[0x11, 0xE0, 0x00, 0x00]).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..8],
+        [0x11, 0x7F, 0x42, 0x00, 0x03, 0xE0, 0x00, 0x02]
+    );
+
+    // Still not ready, the token is kept.
+    assert!(context.dispatch_message(&[0x11, 0xFF, 0xE0, 0x00]).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..8],
+        [0x11, 0x7F, 0x42, 0x00, 0x03, 0xE0, 0x00, 0x02]
+    );
+
+    // Unknown token.
+    assert!(context.dispatch_message(&[0x11, 0xFF, 0xE0, 0x01]).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [0x11, 0x7F, SpdmErrorCode::SpdmErrorInvalidRequest.get_u8()]
+    );
+
+    MEASUREMENT_READY.store(true, Ordering::SeqCst);
+    assert!(context.dispatch_message(&[0x11, 0xFF, 0xE0, 0x00]).is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..2], [0x11, 0x60]);
+
+    // The deferred request has been answered.
+    assert!(context.dispatch_message(&[0x11, 0xFF, 0xE0, 0x00]).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [0x11, 0x7F, SpdmErrorCode::SpdmErrorInvalidRequest.get_u8()]
+    );
+}
+
+fn check_response_ready_as_recorded_cb(
+    _spdm_version: SpdmVersion,
+    _session_id: Option<u32>,
+    _request: &[u8],
+) -> SpdmResponseReadiness {
+    SpdmResponseReadiness::NotReadyAsRecorded
+}
+
+#[test]
+fn test_case1_handle_spdm_respond_if_ready() {
+    let (mut config_info, provision_info) = create_info();
+    config_info.rsp_ct_exponent = 12;
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    context.response_ready_handler = Some(SpdmResponseReadyHandler {
+        check_response_ready_cb: check_response_ready_as_recorded_cb,
+    });
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    // Nothing recorded without a time source, RDT is CT.
+    assert!(context.dispatch_message(&[0x11, 0xE0, 0x00, 0x00]).is_ok());
+    assert_eq!(context.crypto_latency.count, 0);
+    assert_eq!(
+        sent_message(&shared_buffer)[..8],
+        [0x11, 0x7F, 0x42, 0x00, 11, 0xE0, 0x00, 0x02]
+    );
+
+    assert_eq!(
+        SpdmResponseReadiness::not_ready_in(1000),
+        SpdmResponseReadiness::NotReady {
+            rdt_exponent: 9,
+            rdtm: 2
+        }
+    );
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use spdmlib::common::SpdmConnectionState;
+use spdmlib::error::{SpdmResult, SPDM_STATUS_BUFFER_FULL};
+use spdmlib::message::*;
+use spdmlib::protocol::*;
+use spdmlib::responder::dispatch::*;
+use spdmlib::responder::request_policy::SpdmRequestStates;
+use spdmlib::responder::ResponderContext;
+
+// The sent message may be followed by PCI DOE padding.
+fn handle_get_endpoint_info(
+    context: &mut ResponderContext,
+    _session_id: Option<u32>,
+    bytes: &[u8],
+) -> SpdmResult {
+    context.send_message(&[
+        bytes[0],
+        SpdmRequestResponseCode::SpdmResponseEndpointInfo.get_u8(),
+        0,
+        0,
+    ])
+}
+
+#[test]
+fn test_case0_register_request_handler() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+
+    let get_endpoint_info = [0x13, 0x87, 0x00, 0x00];
+
+    // No built-in handler
+    assert!(context.dispatch_message(&get_endpoint_info).is_err());
+
+    assert!(context
+        .register_request_handler(SpdmRequestDispatchEntry {
+            request_response_code: SpdmRequestResponseCode::SpdmRequestGetEndpointInfo,
+            states: SpdmRequestStates::OUT_OF_SESSION,
+            connection_state: SpdmConnectionState::SpdmConnectionNegotiated,
+            handler: handle_get_endpoint_info,
+        })
+        .is_ok());
+
+    // Connection not negotiated yet
+    assert!(context.dispatch_message(&get_endpoint_info).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [
+            0x13,
+            0x7F,
+            SpdmErrorCode::SpdmErrorUnexpectedRequest.get_u8()
+        ]
+    );
+
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+    assert!(context.dispatch_message(&get_endpoint_info).is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x13, 0x07, 0x00, 0x00]);
+
+    // Registered handlers take precedence over the built-in ones
+    assert!(context
+        .register_request_handler(SpdmRequestDispatchEntry {
+            request_response_code: SpdmRequestResponseCode::SpdmRequestGetVersion,
+            states: SpdmRequestStates::OUT_OF_SESSION,
+            connection_state: SpdmConnectionState::SpdmConnectionNotStarted,
+            handler: handle_get_endpoint_info,
+        })
+        .is_ok());
+    assert!(context.dispatch_message(&[0x10, 0x84, 0x00, 0x00]).is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x10, 0x07, 0x00, 0x00]);
+
+    context.unregister_request_handler(SpdmRequestResponseCode::SpdmRequestGetEndpointInfo);
+    assert!(context.dispatch_message(&get_endpoint_info).is_err());
+    context.unregister_request_handler(SpdmRequestResponseCode::SpdmRequestGetVersion);
+
+    for opcode in 0xC0u8..0xC0 + MAX_SPDM_REQUEST_HANDLER_COUNT as u8 {
+        assert!(context
+            .register_request_handler(SpdmRequestDispatchEntry {
+                request_response_code: SpdmRequestResponseCode::Unknown(opcode),
+                states: SpdmRequestStates::all(),
+                connection_state: SpdmConnectionState::SpdmConnectionNotStarted,
+                handler: handle_get_endpoint_info,
+            })
+            .is_ok());
+    }
+    assert_eq!(
+        context.register_request_handler(SpdmRequestDispatchEntry {
+            request_response_code: SpdmRequestResponseCode::SpdmRequestGetEndpointInfo,
+            states: SpdmRequestStates::OUT_OF_SESSION,
+            connection_state: SpdmConnectionState::SpdmConnectionNotStarted,
+            handler: handle_get_endpoint_info,
+        }),
+        Err(SPDM_STATUS_BUFFER_FULL)
+    );
+}
+
+#[test]
+fn test_case1_chunk_send_in_every_session_state() {
+    // CHUNK_SEND is not gated by session state, the reassembled request is.
+    let chunk_send = SPDM_REQUEST_DISPATCH_TABLE
+        .iter()
+        .find(|entry| entry.request_response_code == SpdmRequestResponseCode::SpdmRequestChunkSend)
+        .unwrap();
+    assert_eq!(chunk_send.states, SpdmRequestStates::all());
+}
@@ -272,6 +272,7 @@ fn write_spdm_get_digest_response(
                 },
                 SPDM_MAX_SLOT_NUMBER,
             ),
+            ..Default::default()
         }),
     };
     let _ = response
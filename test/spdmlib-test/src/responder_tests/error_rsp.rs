@@ -2,14 +2,36 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
-use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::device_io::{FakeSpdmDeviceIoOversized, FakeSpdmDeviceIoReceve, SharedBuffer};
 use crate::common::secret_callback::*;
-use crate::common::transport::PciDoeTransportEncap;
+use crate::common::transport::{sent_message, PciDoeTransportEncap, PCI_DOE_MESSAGE_HEADER_SIZE};
 use crate::common::util::create_info;
+use spdmlib::common::{SpdmConnectionState, SpdmTransportEncap, ST1};
+use spdmlib::config;
 use spdmlib::message::*;
 use spdmlib::protocol::*;
 use spdmlib::{responder, secret};
 
+fn setup_negotiated(responder: &mut responder::ResponderContext, version: SpdmVersion) {
+    responder.common.negotiate_info.spdm_version_sel = version;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+}
+
+// GET_DIGESTS padded to size.
+fn send_request(shared_buffer: &SharedBuffer, version: SpdmVersion, size: usize) {
+    let mut request = vec![0u8; size];
+    request[..4].copy_from_slice(&[version.get_u8(), 0x81, 0x00, 0x00]);
+    let mut transport_buffer = vec![0u8; size + PCI_DOE_MESSAGE_HEADER_SIZE + 4];
+    let used = PciDoeTransportEncap {}
+        .encap(&request, &mut transport_buffer, false)
+        .unwrap();
+    shared_buffer.set_buffer(&transport_buffer[..used]);
+}
+
 #[test]
 fn test_case0_send_spdm_error() {
     let (config_info, provision_info) = create_info();
@@ -28,3 +50,51 @@ fn test_case0_send_spdm_error() {
 
     context.send_spdm_error(SpdmErrorCode::SpdmErrorInvalidRequest, 0);
 }
+
+#[test]
+fn test_case1_request_larger_than_receive_buffer() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoOversized::new(&shared_buffer);
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+
+    shared_buffer.set_buffer(&[0u8; config::RECEIVER_BUFFER_SIZE + 4]);
+    assert_eq!(context.process_message(ST1, &[0]).ok(), Some(true));
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x0E, 0x00]);
+
+    // RequestTooLarge is not defined before SPDM 1.2.
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
+    shared_buffer.set_buffer(&[0u8; config::RECEIVER_BUFFER_SIZE + 4]);
+    assert_eq!(context.process_message(ST1, &[0]).ok(), Some(true));
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x11, 0x7F, 0x01, 0x00]);
+}
+
+#[test]
+fn test_case2_request_larger_than_data_transfer_size() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoOversized::new(&shared_buffer);
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+    // The peer accepts more than we do.
+    context.common.negotiate_info.req_data_transfer_size_sel =
+        (config::MAX_SPDM_MSG_SIZE * 2) as u32;
+    context.common.negotiate_info.rsp_data_transfer_size_sel = 64;
+
+    send_request(&shared_buffer, SpdmVersion::SpdmVersion12, 100);
+    assert_eq!(context.process_message(ST1, &[0]).ok(), Some(true));
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x0E, 0x00]);
+}
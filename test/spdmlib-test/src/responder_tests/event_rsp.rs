@@ -0,0 +1,218 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoAsync, SharedBuffer};
+use crate::common::secret_callback::*;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::create_info;
+use codec::Writer;
+use spdmlib::common::session::{SpdmSession, SpdmSessionState};
+use spdmlib::common::SpdmCodec;
+use spdmlib::error::{SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_INVALID_STATE_LOCAL};
+use spdmlib::message::*;
+use spdmlib::protocol::*;
+use spdmlib::{responder, secret};
+use std::cell::Cell;
+
+fn setup_session(context: &mut responder::ResponderContext, session_id: u32) {
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+    context.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::EVENT_CAP;
+    context.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context.common.session = gen_array_clone(SpdmSession::new(), 4);
+    context.common.session[0].setup(session_id).unwrap();
+    context.common.session[0].set_crypto_param(
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+        SpdmDheAlgo::SECP_384_R1,
+        SpdmAeadAlgo::AES_256_GCM,
+        SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+    );
+    assert!(context.common.session[0]
+        .set_dhe_secret(
+            SpdmVersion::SpdmVersion13,
+            SpdmDheFinalKeyStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+            }
+        )
+        .is_ok());
+    assert!(context.common.session[0]
+        .generate_handshake_secret(
+            SpdmVersion::SpdmVersion13,
+            &SpdmDigestStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+            }
+        )
+        .is_ok());
+    assert!(context.common.session[0]
+        .generate_data_secret(
+            SpdmVersion::SpdmVersion13,
+            &SpdmDigestStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+            }
+        )
+        .is_ok());
+    context.common.session[0].set_session_state(SpdmSessionState::SpdmSessionEstablished);
+}
+
+fn subscribe_request(
+    context: &mut responder::ResponderContext,
+    event_groups: &[SpdmEventGroup],
+) -> Vec<u8> {
+    let mut payload = SpdmSubscribeEventTypesRequestPayload {
+        event_group_count: event_groups.len() as u8,
+        ..Default::default()
+    };
+    payload.event_groups[..event_groups.len()].clone_from_slice(event_groups);
+    let request = SpdmMessage {
+        header: SpdmMessageHeader {
+            version: SpdmVersion::SpdmVersion13,
+            request_response_code: SpdmRequestResponseCode::SpdmRequestSubscribeEventTypes,
+        },
+        payload: SpdmMessagePayload::SpdmSubscribeEventTypesRequest(payload),
+    };
+    let bytes = &mut [0u8; 1024];
+    let mut writer = Writer::init(bytes);
+    let used = request
+        .spdm_encode(&mut context.common, &mut writer)
+        .unwrap();
+    bytes[..used].to_vec()
+}
+
+#[test]
+fn test_case0_subscribe_and_send_event() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let pending = Cell::new(false);
+    let mut device_io = FakeSpdmDeviceIoAsync::new(&shared_buffer, &pending);
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    let mut context = responder::ResponderContext::new(
+        &mut device_io,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    let session_id = (0xffu32 << 16) + 0xff;
+    setup_session(&mut context, session_id);
+    let group_id = SpdmEventGroupId::default();
+
+    // Nothing is sent before the requester subscribes.
+    assert_eq!(
+        context.send_event(
+            session_id,
+            &group_id,
+            SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED,
+            &[]
+        ),
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    );
+    assert!(!pending.get());
+
+    let get_supported = [
+        SpdmVersion::SpdmVersion13.get_u8(),
+        SpdmRequestResponseCode::SpdmRequestGetSupportedEventTypes.get_u8(),
+        0x00,
+        0x00,
+    ];
+    assert!(context
+        .handle_spdm_get_supported_event_types(session_id, &get_supported)
+        .is_ok());
+
+    let mut group = SpdmEventGroup::dmtf();
+    group.event_types[0] = SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED;
+    group.event_type_count = 1;
+    let bytes = subscribe_request(&mut context, &[group]);
+    assert!(context
+        .handle_spdm_subscribe_event_types(session_id, &bytes)
+        .is_ok());
+
+    assert!(context
+        .send_event(
+            session_id,
+            &group_id,
+            SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED,
+            &[0x01]
+        )
+        .is_ok());
+    assert!(pending.get());
+    assert_eq!(
+        context.send_event(
+            session_id,
+            &group_id,
+            SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED,
+            &[]
+        ),
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    );
+    assert_eq!(
+        context.send_event(
+            session_id + 1,
+            &group_id,
+            SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED,
+            &[]
+        ),
+        Err(SPDM_STATUS_INVALID_STATE_LOCAL)
+    );
+
+    // Unsubscribe from all.
+    let bytes = subscribe_request(&mut context, &[]);
+    assert!(context
+        .handle_spdm_subscribe_event_types(session_id, &bytes)
+        .is_ok());
+    assert_eq!(
+        context.send_event(
+            session_id,
+            &group_id,
+            SPDM_DMTF_EVENT_TYPE_MEASUREMENT_CHANGED,
+            &[]
+        ),
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    );
+}
+
+#[test]
+fn test_case1_subscribe_unsupported_event_type() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let pending = Cell::new(false);
+    let mut device_io = FakeSpdmDeviceIoAsync::new(&shared_buffer, &pending);
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    let mut context = responder::ResponderContext::new(
+        &mut device_io,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    let session_id = (0xffu32 << 16) + 0xff;
+    setup_session(&mut context, session_id);
+    let group_id = SpdmEventGroupId::default();
+
+    let mut supported = SpdmEventGroup::dmtf();
+    supported.event_types[0] = SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED;
+    supported.event_type_count = 1;
+    assert!(context.set_supported_event_types(&[supported]).is_ok());
+    assert_eq!(
+        context.set_supported_event_types(&[]),
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    );
+
+    // MEASUREMENT_CHANGED is no longer supported, the subscription is rejected.
+    let bytes = subscribe_request(&mut context, &[SpdmEventGroup::dmtf()]);
+    assert!(context
+        .handle_spdm_subscribe_event_types(session_id, &bytes)
+        .is_ok());
+    assert_eq!(
+        context.send_event(
+            session_id,
+            &group_id,
+            SPDM_DMTF_EVENT_TYPE_CERTIFICATE_CHANGED,
+            &[]
+        ),
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    );
+    assert!(!pending.get());
+}
@@ -61,6 +61,7 @@ fn test_case0_handle_spdm_finish() {
         measurement_summary_hash_type:
             SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll,
         nonce: SpdmNonceStruct { data: [100u8; 32] },
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(value.spdm_encode(&mut context.common, &mut writer).is_ok());
 
@@ -135,6 +136,7 @@ fn test_case1_handle_spdm_finish() {
         measurement_summary_hash_type:
             SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeAll,
         nonce: SpdmNonceStruct { data: [100u8; 32] },
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(value.spdm_encode(&mut context.common, &mut writer).is_ok());
 
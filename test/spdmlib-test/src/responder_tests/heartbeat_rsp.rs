@@ -76,3 +76,48 @@ fn test_case0_handle_spdm_heartbeat() {
 
     assert!(context.handle_spdm_heartbeat(session_id, bytes).is_ok());
 }
+
+#[test]
+fn test_case0_expire_idle_sessions() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+
+    let session_id = (0xffu32 << 16) + 0xff;
+    context.common.session = gen_array_clone(SpdmSession::new(), 4);
+    context.common.session[0].setup(session_id).unwrap();
+    context.common.session[0].heartbeat_period = 1;
+    context.common.session[0].set_session_state(SpdmSessionState::SpdmSessionEstablished);
+
+    // HBEAT_CAP is not negotiated
+    assert_eq!(context.expire_idle_sessions(10_000_000), 0);
+    assert_eq!(context.common.session[0].get_last_message_us(), None);
+
+    context.common.negotiate_info.req_capabilities_sel = SpdmRequestCapabilityFlags::HBEAT_CAP;
+    context.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::HBEAT_CAP;
+    // the watchdog starts
+    assert_eq!(context.expire_idle_sessions(10_000_000), 0);
+    assert_eq!(
+        context.common.session[0].get_last_message_us(),
+        Some(10_000_000)
+    );
+
+    context.common.session[0].touch(11_000_000);
+    assert_eq!(context.expire_idle_sessions(13_000_000), 0);
+    assert_eq!(context.expire_idle_sessions(13_000_001), 1);
+    assert_eq!(
+        context.common.session[0].get_session_state(),
+        SpdmSessionState::SpdmSessionNotStarted
+    );
+    assert_eq!(
+        context.common.get_session_via_id(session_id).is_none(),
+        true
+    );
+}
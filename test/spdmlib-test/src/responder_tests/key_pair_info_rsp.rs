@@ -0,0 +1,230 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use codec::Reader;
+use spdmlib::common::{SpdmCodec, SpdmConnectionState};
+use spdmlib::message::*;
+use spdmlib::protocol::*;
+use spdmlib::responder::ResponderContext;
+use spdmlib::secret::{SpdmSecretKeyPair, SpdmSetKeyPairInfoResult};
+
+// Key pair 2 needs a reset to apply any change.
+fn set_key_pair_info_cb(
+    _spdm_version: SpdmVersion,
+    _operation: SpdmKeyPairInfoOperation,
+    key_pair_id: u8,
+    _key_pair_info: &mut SpdmKeyPairInfo,
+) -> SpdmSetKeyPairInfoResult {
+    if key_pair_id == 2 {
+        SpdmSetKeyPairInfoResult::ResetRequired
+    } else {
+        SpdmSetKeyPairInfoResult::Applied
+    }
+}
+
+fn setup_key_pair_info_negotiated(responder: &mut ResponderContext) {
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion13;
+    responder.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP
+        | SpdmResponseCapabilityFlags::MULTI_KEY_CAP_ONLY
+        | SpdmResponseCapabilityFlags::GET_KEY_PAIR_INFO_CAP
+        | SpdmResponseCapabilityFlags::SET_KEY_PAIR_INFO_CAP;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+    responder.common.secret_provider.key_pair = Some(SpdmSecretKeyPair {
+        set_key_pair_info_cb,
+    });
+
+    // Key pair 1 is used for key exchange with slot 0, key pair 2 for
+    // challenge with slot 1.
+    responder.common.provision_info.key_pair_info[0] = Some(SpdmKeyPairInfo {
+        capabilities: SpdmKeyPairCapabilities::CERT_ASSOC_CAP
+            | SpdmKeyPairCapabilities::KEY_USAGE_CAP,
+        key_usage_capabilities: SpdmKeyUsageMask::KEY_EX_USE | SpdmKeyUsageMask::CHALLENGE_USE,
+        current_key_usage: SpdmKeyUsageMask::KEY_EX_USE,
+        asym_algo_capabilities: SpdmKeyPairAsymAlgo::ECC384,
+        current_asym_algo: SpdmKeyPairAsymAlgo::ECC384,
+        assoc_cert_slot_mask: 0b0000_0001,
+        ..Default::default()
+    });
+    responder.common.provision_info.key_pair_info[1] = Some(SpdmKeyPairInfo {
+        capabilities: SpdmKeyPairCapabilities::CERT_ASSOC_CAP,
+        key_usage_capabilities: SpdmKeyUsageMask::CHALLENGE_USE,
+        current_key_usage: SpdmKeyUsageMask::CHALLENGE_USE,
+        asym_algo_capabilities: SpdmKeyPairAsymAlgo::ECC384,
+        current_asym_algo: SpdmKeyPairAsymAlgo::ECC384,
+        assoc_cert_slot_mask: 0b0000_0010,
+        ..Default::default()
+    });
+}
+
+fn set_key_pair_info(key_pair_id: u8, key_usage: u16, assoc_cert_slot_mask: u8) -> Vec<u8> {
+    let mut message = vec![0x13, 0xFD, 0x00, 0x00, 0x00, key_pair_id];
+    message.extend_from_slice(&key_usage.to_le_bytes());
+    message.extend_from_slice(&0u32.to_le_bytes());
+    message.push(assoc_cert_slot_mask);
+    message
+}
+
+// The sent message may be followed by PCI DOE padding.
+#[test]
+fn test_case0_handle_spdm_get_key_pair_info() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_key_pair_info_negotiated(&mut context);
+
+    assert!(context
+        .dispatch_message(&[0x13, 0xFC, 0x00, 0x00, 0x02])
+        .is_ok());
+    let response = sent_message(&shared_buffer);
+    assert_eq!(response[..6], [0x13, 0x7C, 0x00, 0x00, 0x02, 0x02]);
+    let reader = &mut Reader::init(&response[4..]);
+    let key_pair_info =
+        SpdmKeyPairInfoResponsePayload::spdm_read(&mut context.common, reader).unwrap();
+    assert_eq!(
+        key_pair_info.key_pair_info.current_key_usage,
+        SpdmKeyUsageMask::CHALLENGE_USE
+    );
+    assert_eq!(
+        key_pair_info.key_pair_info.assoc_cert_slot_mask,
+        0b0000_0010
+    );
+
+    // Key pair 3 is not provisioned.
+    assert!(context
+        .dispatch_message(&[0x13, 0xFC, 0x00, 0x00, 0x03])
+        .is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [0x13, 0x7F, SpdmErrorCode::SpdmErrorInvalidRequest.get_u8()]
+    );
+}
+
+#[test]
+fn test_case1_handle_spdm_set_key_pair_info() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_key_pair_info_negotiated(&mut context);
+
+    // Switch key pair 1 to challenge and associate it with slot 2 too.
+    assert!(context
+        .dispatch_message(&set_key_pair_info(1, 0x0002, 0b0000_0101))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x13, 0x7D, 0x00, 0x00]);
+    let key_pair_info = context.common.provision_info.get_key_pair_info(1).unwrap();
+    assert_eq!(
+        key_pair_info.current_key_usage,
+        SpdmKeyUsageMask::CHALLENGE_USE
+    );
+    assert_eq!(key_pair_info.assoc_cert_slot_mask, 0b0000_0101);
+    assert_eq!(
+        context.common.provision_info.get_slot_key_pair_id(2),
+        Some(1)
+    );
+
+    // Slot 1 belongs to key pair 2.
+    assert!(context
+        .dispatch_message(&set_key_pair_info(1, 0x0000, 0b0000_0011))
+        .is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [0x13, 0x7F, SpdmErrorCode::SpdmErrorInvalidRequest.get_u8()]
+    );
+
+    // Key pair 2 cannot change the key usage.
+    assert!(context
+        .dispatch_message(&set_key_pair_info(2, 0x0001, 0b0000_0010))
+        .is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [0x13, 0x7F, SpdmErrorCode::SpdmErrorInvalidRequest.get_u8()]
+    );
+
+    // Key pair 2 needs a reset.
+    assert!(context
+        .dispatch_message(&set_key_pair_info(2, 0x0000, 0b0000_1010))
+        .is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [0x13, 0x7F, SpdmErrorCode::SpdmErrorResetRequired.get_u8()]
+    );
+    assert_eq!(context.common.provision_info.get_slot_key_pair_id(3), None);
+}
+
+#[test]
+fn test_case2_handle_spdm_key_pair_info_unsupported() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_key_pair_info_negotiated(&mut context);
+    context.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+
+    assert!(context
+        .dispatch_message(&[0x13, 0xFC, 0x00, 0x00, 0x01])
+        .is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [
+            0x13,
+            0x7F,
+            SpdmErrorCode::SpdmErrorUnsupportedRequest.get_u8()
+        ]
+    );
+    assert!(context
+        .dispatch_message(&set_key_pair_info(1, 0x0002, 0b0000_0001))
+        .is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [
+            0x13,
+            0x7F,
+            SpdmErrorCode::SpdmErrorUnsupportedRequest.get_u8()
+        ]
+    );
+
+    // SET_KEY_PAIR_INFO_CAP without a key pair callback for this context
+    setup_key_pair_info_negotiated(&mut context);
+    context.common.secret_provider.key_pair = None;
+    assert!(context
+        .dispatch_message(&set_key_pair_info(1, 0x0002, 0b0000_0001))
+        .is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer)[..3],
+        [
+            0x13,
+            0x7F,
+            SpdmErrorCode::SpdmErrorUnsupportedRequest.get_u8()
+        ]
+    );
+}
@@ -4,14 +4,15 @@
 
 use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
 use crate::common::secret_callback::*;
-use crate::common::transport::PciDoeTransportEncap;
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
 use crate::common::util::create_info;
 use codec::{Codec, Reader, Writer};
 use spdmlib::common::SpdmCodec;
 use spdmlib::common::SpdmConnectionState;
 use spdmlib::message::*;
 use spdmlib::protocol::*;
-use spdmlib::{responder, secret};
+use spdmlib::responder::MeasurementProvider;
+use spdmlib::{config, responder, secret};
 
 #[test]
 fn test_case0_handle_spdm_measurement() {
@@ -57,6 +58,7 @@ fn test_case0_handle_spdm_measurement() {
             data: [100u8; SPDM_NONCE_SIZE],
         },
         slot_id: 0,
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(value.spdm_encode(&mut context.common, &mut writer).is_ok());
 
@@ -154,6 +156,7 @@ fn test_case1_handle_spdm_measurement() {
             data: [100u8; SPDM_NONCE_SIZE],
         },
         slot_id: 0,
+        requester_context: SpdmReqContextStruct::default(),
     };
     assert!(value.spdm_encode(&mut context.common, &mut writer).is_ok());
 
@@ -209,3 +212,217 @@ fn test_case1_handle_spdm_measurement() {
         }
     }
 }
+
+// The sent message may be followed by PCI DOE padding.
+struct TestMeasurementProvider;
+
+impl MeasurementProvider for TestMeasurementProvider {
+    fn get_measurement_indices(&mut self) -> Vec<u8> {
+        vec![1, 0xFD]
+    }
+
+    fn get_measurement_block(
+        &mut self,
+        index: u8,
+        measurement_specification: SpdmMeasurementSpecification,
+        _measurement_hash_algo: SpdmMeasurementHashAlgo,
+        _raw_bit_stream: bool,
+        _fresh: bool,
+    ) -> Option<SpdmMeasurementBlockStructure> {
+        let mut value = [0u8; config::MAX_SPDM_MEASUREMENT_VALUE_LEN];
+        value[..SHA384_DIGEST_SIZE].copy_from_slice(&[index; SHA384_DIGEST_SIZE]);
+        Some(SpdmMeasurementBlockStructure {
+            index,
+            measurement_specification,
+            measurement_size: 3 + SHA384_DIGEST_SIZE as u16,
+            measurement: SpdmDmtfMeasurementStructure {
+                r#type: SpdmDmtfMeasurementType::SpdmDmtfMeasurementFirmware,
+                representation: SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementDigest,
+                value_size: SHA384_DIGEST_SIZE as u16,
+                value,
+            },
+        })
+    }
+}
+
+#[test]
+fn test_case2_handle_spdm_measurement_provider() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    context.set_measurement_provider(Some(Box::new(TestMeasurementProvider)));
+
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    context.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    context.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.measurement_specification_sel =
+        SpdmMeasurementSpecification::DMTF;
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    // the indices of the provider are not contiguous
+    assert!(context.dispatch_message(&[0x12, 0xE0, 0x00, 0xFD]).is_ok());
+    let response = sent_message(&shared_buffer);
+    assert_eq!(response[..2], [0x12, 0x60]);
+    // NumberOfBlocks, MeasurementRecordLength, then the block index
+    assert_eq!(response[4], 1);
+    assert_eq!(response[8], 0xFD);
+
+    assert!(context.dispatch_message(&[0x12, 0xE0, 0x00, 0xFF]).is_ok());
+    let response = sent_message(&shared_buffer);
+    assert_eq!(response[..2], [0x12, 0x60]);
+    assert_eq!(response[4], 2);
+    assert_eq!(response[8], 1);
+
+    assert!(context.dispatch_message(&[0x12, 0xE0, 0x00, 0x02]).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer),
+        [
+            0x12,
+            0x7F,
+            SpdmErrorCode::SpdmErrorInvalidRequest.get_u8(),
+            0x00
+        ]
+    );
+
+    // the default summary hash covers all the blocks
+    let mut provider = TestMeasurementProvider;
+    let mut blocks = Vec::new();
+    for index in [1, 0xFD] {
+        let block = provider
+            .get_measurement_block(
+                index,
+                SpdmMeasurementSpecification::DMTF,
+                SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+                false,
+                false,
+            )
+            .unwrap();
+        let mut buffer = [0u8; 1024];
+        let mut writer = Writer::init(&mut buffer);
+        assert!(block.encode(&mut writer).is_ok());
+        blocks.extend_from_slice(writer.used_slice());
+    }
+    assert_eq!(
+        provider
+            .generate_measurement_summary_hash(
+                SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+                SpdmMeasurementSpecification::DMTF,
+                SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+                SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeTcb,
+            )
+            .unwrap()
+            .as_ref(),
+        spdmlib::crypto::hash::hash_all(SpdmBaseHashAlgo::TPM_ALG_SHA_384, &blocks)
+            .unwrap()
+            .as_ref()
+    );
+}
+
+// Index 2 is a freeform manifest which can be returned raw.
+struct RawTestProvider;
+
+impl MeasurementProvider for RawTestProvider {
+    fn get_measurement_indices(&mut self) -> Vec<u8> {
+        vec![1, 2]
+    }
+
+    fn get_measurement_block(
+        &mut self,
+        index: u8,
+        _measurement_specification: SpdmMeasurementSpecification,
+        _measurement_hash_algo: SpdmMeasurementHashAlgo,
+        raw_bit_stream: bool,
+        _fresh: bool,
+    ) -> Option<SpdmMeasurementBlockStructure> {
+        let measurement = if raw_bit_stream {
+            SpdmDmtfMeasurementStructure::freeform_manifest(
+                SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementRawBit,
+                &[index; 300],
+            )?
+        } else {
+            SpdmDmtfMeasurementStructure::freeform_manifest(
+                SpdmDmtfMeasurementRepresentation::SpdmDmtfMeasurementDigest,
+                &[index; SHA384_DIGEST_SIZE],
+            )?
+        };
+        Some(SpdmMeasurementBlockStructure::new(index, measurement))
+    }
+}
+
+fn measurement_record(response: &[u8]) -> SpdmMeasurementRecordStructure {
+    let mut reader = Reader::init(&response[4..]);
+    let number_of_blocks = u8::read(&mut reader).unwrap();
+    let measurement_record_length = codec::u24::read(&mut reader).unwrap();
+    let mut record = SpdmMeasurementRecordStructure {
+        number_of_blocks,
+        measurement_record_length,
+        ..Default::default()
+    };
+    let length = measurement_record_length.get() as usize;
+    record.measurement_record_data[..length].copy_from_slice(reader.take(length).unwrap());
+    record
+}
+
+#[test]
+fn test_case3_handle_spdm_measurement_raw_bit_stream() {
+    let (mut config_info, provision_info) = create_info();
+    config_info.raw_bit_stream_measurement_indices[0] = 1 << 2;
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let mut context = responder::ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    context.set_measurement_provider(Some(Box::new(RawTestProvider)));
+
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    context.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.measurement_specification_sel =
+        SpdmMeasurementSpecification::DMTF;
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    // only the configured index is returned raw
+    assert!(context.dispatch_message(&[0x12, 0xE0, 0x02, 0xFF]).is_ok());
+    let blocks = measurement_record(&sent_message(&shared_buffer))
+        .get_measurement_blocks()
+        .unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(
+        blocks[0].measurement.get_measurement_value(),
+        Some(SpdmMeasurementValue::Digest(&[1; SHA384_DIGEST_SIZE]))
+    );
+    assert_eq!(
+        blocks[1].measurement.get_measurement_value(),
+        Some(SpdmMeasurementValue::RawBitStream(&[2; 300]))
+    );
+    assert_eq!(blocks[1].measurement_size, 303);
+
+    // without the request every index is a digest
+    assert!(context.dispatch_message(&[0x12, 0xE0, 0x00, 0x02]).is_ok());
+    let blocks = measurement_record(&sent_message(&shared_buffer))
+        .get_measurement_blocks()
+        .unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(
+        blocks[0].measurement.get_measurement_value(),
+        Some(SpdmMeasurementValue::Digest(&[2; SHA384_DIGEST_SIZE]))
+    );
+}
@@ -4,8 +4,14 @@
 
 #![forbid(unsafe_code)]
 
+mod alias_cert;
+
 mod challenge_rsp;
 
+mod chunk_send_rsp;
+
+mod csr_rsp;
+
 mod algorithm_rsp;
 
 mod capability_rsp;
@@ -17,8 +23,12 @@ mod certificate_rsp;
 //
 // mod context;
 
+mod deferred_rsp;
+
 mod digest_rsp;
 
+mod dispatch;
+
 #[cfg(feature = "mut-auth")]
 mod encap_get_certificate;
 
@@ -32,10 +42,14 @@ mod end_session_rsp;
 
 mod error_rsp;
 
+mod event_rsp;
+
 mod finish_rsp;
 
 mod heartbeat_rsp;
 
+mod key_pair_info_rsp;
+
 mod key_exchange_rsp;
 
 mod key_update_rsp;
@@ -46,6 +60,12 @@ mod psk_exchange_rsp;
 
 mod psk_finish_rsp;
 
+mod secure_version;
+
+mod set_certificate_rsp;
+
+mod sign_failure;
+
 mod vendor_rsp;
 
 mod version_rsp;
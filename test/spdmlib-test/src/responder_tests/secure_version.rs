@@ -0,0 +1,140 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::*;
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use core::sync::atomic::{AtomicU32, Ordering};
+use spdmlib::common::{SpdmConnectionState, SpdmOpaqueSupport, DMTF_SECURE_SPDM_VERSION_10};
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use spdmlib::responder::secure_version::SpdmSecureVersionSelector;
+use spdmlib::{responder, secret};
+
+static SELECTIONS: AtomicU32 = AtomicU32::new(0);
+
+// Selects the lowest version instead of the highest common one.
+fn select_secure_spdm_version_cb(
+    _spdm_version: SpdmVersion,
+    peer_versions: &[u8],
+    local_versions: &[u8],
+) -> Option<u8> {
+    assert!(peer_versions.contains(&DMTF_SECURE_SPDM_VERSION_10));
+    assert!(local_versions.contains(&DMTF_SECURE_SPDM_VERSION_10));
+    SELECTIONS.fetch_add(1, Ordering::SeqCst);
+    peer_versions
+        .iter()
+        .filter(|version| local_versions.contains(version))
+        .min()
+        .copied()
+}
+
+#[test]
+fn test_case0_secure_version_selector() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    // A registered selector would apply to the responders of every test.
+    responder.secure_version_selector = Some(SpdmSecureVersionSelector {
+        select_secure_spdm_version_cb,
+    });
+
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ];
+
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.aead_sel = SpdmAeadAlgo::AES_128_GCM;
+    responder.common.negotiate_info.dhe_sel = SpdmDheAlgo::SECP_384_R1;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.negotiate_info.opaque_data_support = SpdmOpaqueSupport::OPAQUE_DATA_FMT1;
+    #[cfg(feature = "mut-auth")]
+    {
+        responder.common.negotiate_info.rsp_capabilities_sel |=
+            SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
+        responder.common.negotiate_info.req_capabilities_sel |=
+            SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
+    }
+
+    responder.common.reset_runtime_info();
+
+    responder.common.provision_info.my_cert_chain = [
+        Some(get_rsp_cert_chain_buff()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ];
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.aead_sel = SpdmAeadAlgo::AES_128_GCM;
+    requester.common.negotiate_info.dhe_sel = SpdmDheAlgo::SECP_384_R1;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    requester.common.negotiate_info.opaque_data_support = SpdmOpaqueSupport::OPAQUE_DATA_FMT1;
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    #[cfg(feature = "mut-auth")]
+    {
+        requester.common.negotiate_info.rsp_capabilities_sel |=
+            SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
+        requester.common.negotiate_info.req_capabilities_sel |=
+            SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
+    }
+
+    requester.common.reset_runtime_info();
+
+    requester.common.peer_info.peer_cert_chain[0] = Some(get_rsp_cert_chain_buff());
+
+    let measurement_summary_hash_type =
+        SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone;
+    let session_id = requester
+        .send_receive_spdm_key_exchange(0, measurement_summary_hash_type)
+        .unwrap();
+    assert!(SELECTIONS.load(Ordering::SeqCst) > 0);
+    assert_eq!(
+        requester
+            .common
+            .get_immutable_session_via_id(session_id)
+            .unwrap()
+            .get_secure_spdm_version(),
+        DMTF_SECURE_SPDM_VERSION_10
+    );
+}
@@ -0,0 +1,224 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
+use crate::common::util::{create_info, get_rsp_cert_chain_buff};
+use spdmlib::common::provision_store::SpdmMemoryProvisionStore;
+use spdmlib::common::{SpdmConnectionState, SpdmProvisionStore};
+use spdmlib::protocol::*;
+use spdmlib::responder::ResponderContext;
+use spdmlib::secret::{self, SpdmSecretCertificate, SpdmSetCertificateResult};
+
+// Slot 7 needs a reset to apply the new cert chain.
+fn set_certificate_cb(
+    _spdm_version: SpdmVersion,
+    slot_id: u8,
+    _key_pair_id: u8,
+    _cert_chain: Option<&[u8]>,
+) -> SpdmSetCertificateResult {
+    if slot_id == 7 {
+        SpdmSetCertificateResult::ResetRequired
+    } else {
+        SpdmSetCertificateResult::Stored
+    }
+}
+
+fn set_certificate(version: SpdmVersion, slot_id: u8, cert_chain: &[u8]) -> Vec<u8> {
+    let mut message = vec![version.get_u8(), 0xEE, slot_id, 0x00];
+    message.extend_from_slice(cert_chain);
+    message
+}
+
+fn setup_set_certificate_negotiated(responder: &mut ResponderContext, version: SpdmVersion) {
+    secret::certificate::register(SpdmSecretCertificate { set_certificate_cb });
+    responder
+        .common
+        .config_info
+        .allow_set_certificate_out_of_session = true;
+    responder.common.negotiate_info.spdm_version_sel = version;
+    responder.common.negotiate_info.rsp_capabilities_sel =
+        SpdmResponseCapabilityFlags::CERT_CAP | SpdmResponseCapabilityFlags::SET_CERT_CAP;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+}
+
+// The sent message may be followed by PCI DOE padding.
+#[test]
+fn test_case0_handle_spdm_set_certificate() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_set_certificate_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+
+    let cert_chain = get_rsp_cert_chain_buff();
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion12,
+            1,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x6E, 0x01, 0x00]);
+    assert_eq!(
+        context.common.provision_info.my_cert_chain[1]
+            .as_ref()
+            .unwrap()
+            .as_ref(),
+        cert_chain.as_ref()
+    );
+    assert_eq!(
+        context.common.provision_info.my_cert_chain_data[1]
+            .as_ref()
+            .unwrap()
+            .as_ref(),
+        &cert_chain.as_ref()[4 + SHA384_DIGEST_SIZE..]
+    );
+
+    // The cert chain is written back to the provision store.
+    context
+        .common
+        .set_provision_store(Some(Box::new(SpdmMemoryProvisionStore::new(&[]).unwrap())));
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion12,
+            2,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x6E, 0x02, 0x00]);
+    let stored = context
+        .common
+        .provision_store
+        .as_mut()
+        .unwrap()
+        .load_cert_chain(2)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        stored.as_ref(),
+        &cert_chain.as_ref()[4 + SHA384_DIGEST_SIZE..]
+    );
+}
+
+#[test]
+fn test_case1_handle_spdm_set_certificate() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_set_certificate_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+
+    // The root hash does not match the root cert.
+    let mut cert_chain = get_rsp_cert_chain_buff();
+    cert_chain.data[4] ^= 0xFF;
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion12,
+            1,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x01, 0x00]);
+    assert!(context.common.provision_info.my_cert_chain[1].is_none());
+
+    // The leaf cert key does not match the negotiated base_asym_sel.
+    context.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256;
+    let cert_chain = get_rsp_cert_chain_buff();
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion12,
+            1,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x01, 0x00]);
+
+    // The persistence callback asks for a reset.
+    context.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion12,
+            7,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x0C, 0x00]);
+    assert!(context.common.provision_info.my_cert_chain[7].is_none());
+}
+
+#[test]
+fn test_case2_handle_spdm_set_certificate() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    setup_set_certificate_negotiated(&mut context, SpdmVersion::SpdmVersion12);
+    context.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::CERT_CAP;
+
+    let cert_chain = get_rsp_cert_chain_buff();
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion12,
+            1,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x07, 0x00]);
+
+    // SET_CERTIFICATE is not defined before SPDM 1.2.
+    context.common.negotiate_info.rsp_capabilities_sel =
+        SpdmResponseCapabilityFlags::CERT_CAP | SpdmResponseCapabilityFlags::SET_CERT_CAP;
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion11,
+            1,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x11, 0x7F, 0x07, 0x00]);
+
+    // Only in a session unless allowed by the configuration.
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    context
+        .common
+        .config_info
+        .allow_set_certificate_out_of_session = false;
+    assert!(context
+        .dispatch_message(&set_certificate(
+            SpdmVersion::SpdmVersion12,
+            1,
+            cert_chain.as_ref()
+        ))
+        .is_ok());
+    assert_eq!(sent_message(&shared_buffer)[..4], [0x12, 0x7F, 0x04, 0x00]);
+    assert!(context.common.provision_info.my_cert_chain[1].is_none());
+}
@@ -0,0 +1,133 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::*;
+use crate::common::transport::{sent_message, PciDoeTransportEncap};
+use crate::common::util::create_info;
+use core::sync::atomic::{AtomicU32, Ordering};
+use spdmlib::common::SpdmConnectionState;
+use spdmlib::message::*;
+use spdmlib::protocol::*;
+use spdmlib::responder::sign_failure::{
+    register_sign_failure_policy, SpdmSignFailureAction, SpdmSignFailurePolicy,
+    SpdmSignFailureStats,
+};
+use spdmlib::responder::ResponderContext;
+use spdmlib::secret::{self, SpdmSecretAsymSign};
+
+static SIGN_FAILURES: AtomicU32 = AtomicU32::new(0);
+
+fn sign_fail_cb(
+    _base_hash_algo: SpdmBaseHashAlgo,
+    _base_asym_algo: SpdmBaseAsymAlgo,
+    _data: &[u8],
+) -> Option<SpdmSignatureStruct> {
+    None
+}
+
+fn sign_failure_cb(
+    request_response_code: SpdmRequestResponseCode,
+    _slot_id: u8,
+    _stats: &SpdmSignFailureStats,
+) {
+    assert_eq!(
+        request_response_code,
+        SpdmRequestResponseCode::SpdmRequestGetMeasurements
+    );
+    SIGN_FAILURES.fetch_add(1, Ordering::SeqCst);
+}
+
+// Signed GET_MEASUREMENTS for the total number of measurement blocks.
+fn get_signed_measurements() -> Vec<u8> {
+    let mut message = vec![0x12, 0xE0, 0x01, 0x00];
+    message.extend_from_slice(&[0xAA; SPDM_NONCE_SIZE]);
+    message.push(0x00);
+    message
+}
+
+// The sent message may be followed by PCI DOE padding.
+#[test]
+fn test_case0_sign_failure_policy() {
+    let (config_info, provision_info) = create_info();
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let shared_buffer = SharedBuffer::new();
+    let mut socket_io_transport = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+
+    let mut context = ResponderContext::new(
+        &mut socket_io_transport,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
+    register_sign_failure_policy(SpdmSignFailurePolicy {
+        action: SpdmSignFailureAction::Busy,
+        quarantine_threshold: 2,
+        sign_failure_cb,
+    });
+    context.common.secret_provider.asym_sign = Some(SpdmSecretAsymSign {
+        sign_cb: sign_fail_cb,
+    });
+    context.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    context.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    context.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    context.common.negotiate_info.measurement_specification_sel =
+        SpdmMeasurementSpecification::DMTF;
+    assert!(context.common.construct_my_cert_chain().is_ok());
+    context
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    // Only the error is sent, not the response without signature.
+    assert!(context.dispatch_message(&get_signed_measurements()).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer),
+        [0x12, 0x7F, SpdmErrorCode::SpdmErrorBusy.get_u8(), 0x00]
+    );
+
+    // The second failure in a row quarantines slot 0.
+    assert!(context.dispatch_message(&get_signed_measurements()).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer),
+        [
+            0x12,
+            0x7F,
+            SpdmErrorCode::SpdmErrorUnspecified.get_u8(),
+            0x00
+        ]
+    );
+    assert_eq!(SIGN_FAILURES.load(Ordering::SeqCst), 2);
+
+    // The signer is not called for a quarantined slot.
+    assert!(context.dispatch_message(&get_signed_measurements()).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer),
+        [
+            0x12,
+            0x7F,
+            SpdmErrorCode::SpdmErrorUnspecified.get_u8(),
+            0x00
+        ]
+    );
+    assert_eq!(SIGN_FAILURES.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        context.get_sign_failure_stats(0),
+        Some(&SpdmSignFailureStats {
+            failure_count: 2,
+            consecutive_failure_count: 2,
+            quarantined: true,
+        })
+    );
+
+    context.release_quarantined_slot(0);
+    assert!(!context.get_sign_failure_stats(0).unwrap().quarantined);
+    assert!(context.dispatch_message(&get_signed_measurements()).is_ok());
+    assert_eq!(
+        sent_message(&shared_buffer),
+        [0x12, 0x7F, SpdmErrorCode::SpdmErrorBusy.get_u8(), 0x00]
+    );
+}
@@ -6,7 +6,9 @@ use crate::common::device_io::{FakeSpdmDeviceIoReceve, SharedBuffer};
 use crate::common::secret_callback::*;
 use crate::common::transport::PciDoeTransportEncap;
 use crate::common::util::create_info;
-use spdmlib::error::SpdmResult;
+use spdmlib::error::{
+    SpdmResult, SPDM_STATUS_BUFFER_FULL, SPDM_STATUS_INVALID_PARAMETER, SPDM_STATUS_UNSUPPORTED_CAP,
+};
 use spdmlib::message::VendorDefinedReqPayloadStruct;
 use spdmlib::message::*;
 use spdmlib::responder::ResponderContext;
@@ -62,3 +64,141 @@ fn test_case0_handle_spdm_vendor_defined_request() {
         assert!(false, "Not expected result!");
     }
 }
+
+#[test]
+fn test_case1_vendor_defined_registry() {
+    fn rsp_payload(tag: u8) -> VendorDefinedRspPayloadStruct {
+        let mut rsp = VendorDefinedRspPayloadStruct {
+            rsp_length: 1,
+            vendor_defined_rsp_payload: [0; config::MAX_SPDM_MSG_SIZE - 7 - 2],
+        };
+        rsp.vendor_defined_rsp_payload[0] = tag;
+        rsp
+    }
+    fn vendor_id(id: &[u8]) -> VendorIDStruct {
+        let mut vendor_id = VendorIDStruct {
+            len: id.len() as u8,
+            vendor_id: [0; MAX_SPDM_VENDOR_DEFINED_VENDOR_ID_LEN],
+        };
+        vendor_id.vendor_id[..id.len()].copy_from_slice(id);
+        vendor_id
+    }
+
+    let mut registry = VendorDefinedRegistry::new();
+    // two PCI-SIG protocols, selected by the protocol ID
+    assert!(registry
+        .register(
+            RegistryOrStandardsBodyID::PCISIG,
+            &vendor_id(&[0x01, 0x00]),
+            |_, req| {
+                if req.vendor_defined_req_payload[0] != 0 {
+                    return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+                }
+                Ok(rsp_payload(b'i'))
+            },
+        )
+        .is_ok());
+    assert!(registry
+        .register(
+            RegistryOrStandardsBodyID::PCISIG,
+            &vendor_id(&[0x01, 0x00]),
+            |_, req| {
+                if req.vendor_defined_req_payload[0] != 1 {
+                    return Err(SPDM_STATUS_UNSUPPORTED_CAP);
+                }
+                Ok(rsp_payload(b't'))
+            },
+        )
+        .is_ok());
+    assert!(registry
+        .register(
+            RegistryOrStandardsBodyID::IANA,
+            &vendor_id(&[0x57, 0x01, 0x00, 0x00]),
+            |_, _| Ok(rsp_payload(b'o')),
+        )
+        .is_ok());
+    assert_eq!(
+        registry.register(
+            RegistryOrStandardsBodyID::IANA,
+            &vendor_id(&[0; 5]),
+            |_, _| Ok(rsp_payload(b'o')),
+        ),
+        Err(SPDM_STATUS_INVALID_PARAMETER)
+    );
+
+    let mut req = VendorDefinedReqPayloadStruct {
+        req_length: 1,
+        vendor_defined_req_payload: [0; config::MAX_SPDM_MSG_SIZE - 7 - 2],
+    };
+    let dispatch = |registry: &VendorDefinedRegistry,
+                    standard_id,
+                    id: &[u8],
+                    req: &VendorDefinedReqPayloadStruct| {
+        registry
+            .dispatch(standard_id, &vendor_id(id), None, req)
+            .map(|rsp| rsp.vendor_defined_rsp_payload[0])
+    };
+    assert_eq!(
+        dispatch(
+            &registry,
+            RegistryOrStandardsBodyID::PCISIG,
+            &[0x01, 0x00],
+            &req
+        ),
+        Ok(b'i')
+    );
+    req.vendor_defined_req_payload[0] = 1;
+    assert_eq!(
+        dispatch(
+            &registry,
+            RegistryOrStandardsBodyID::PCISIG,
+            &[0x01, 0x00],
+            &req
+        ),
+        Ok(b't')
+    );
+    req.vendor_defined_req_payload[0] = 2;
+    assert_eq!(
+        dispatch(
+            &registry,
+            RegistryOrStandardsBodyID::PCISIG,
+            &[0x01, 0x00],
+            &req
+        ),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+    assert_eq!(
+        dispatch(
+            &registry,
+            RegistryOrStandardsBodyID::IANA,
+            &[0x57, 0x01, 0x00, 0x00],
+            &req
+        ),
+        Ok(b'o')
+    );
+    // the vendor ID is part of the key
+    assert_eq!(
+        dispatch(
+            &registry,
+            RegistryOrStandardsBodyID::PCISIG,
+            &[0x98, 0x1E],
+            &req
+        ),
+        Err(SPDM_STATUS_UNSUPPORTED_CAP)
+    );
+    assert!(!registry.is_registered(RegistryOrStandardsBodyID::CXL, &vendor_id(&[0x01, 0x00])));
+
+    for _ in 3..MAX_SPDM_VENDOR_DEFINED_HANDLER_COUNT {
+        assert!(registry
+            .register(RegistryOrStandardsBodyID::DMTF, &vendor_id(&[]), |_, _| Ok(
+                rsp_payload(b'd')
+            ))
+            .is_ok());
+    }
+    assert_eq!(
+        registry.register(RegistryOrStandardsBodyID::DMTF, &vendor_id(&[]), |_, _| Ok(
+            rsp_payload(b'd')
+        )),
+        Err(SPDM_STATUS_BUFFER_FULL)
+    );
+}
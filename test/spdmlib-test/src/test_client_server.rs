@@ -5,23 +5,42 @@
 use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
 use crate::common::secret_callback::SECRET_ASYM_IMPL_INSTANCE;
 use crate::common::transport::PciDoeTransportEncap;
-use crate::common::util::{get_rsp_cert_chain_buff, req_create_info, rsp_create_info};
+use crate::common::util::{
+    default_test_suite, get_rsp_cert_chain_buff_with_suite, req_create_info_with_suite,
+    rsp_create_info_with_suite, TestCryptoSuite, TEST_SUITE_ECP256,
+};
+use codec::{Codec, Writer};
+use spdmlib::common::audit_log::{
+    verify_negotiation_audit_log, SpdmNegotiationAuditLog, MAX_SPDM_VCA_MESSAGE_COUNT,
+};
+use spdmlib::message::SpdmRequestResponseCode;
 use spdmlib::protocol::{
-    SpdmMeasurementSummaryHashType, SpdmReqAsymAlgo, SpdmRequestCapabilityFlags,
-    SpdmResponseCapabilityFlags,
+    SpdmMeasurementSummaryHashType, SpdmRequestCapabilityFlags, SpdmResponseCapabilityFlags,
 };
 use spdmlib::requester;
+use spdmlib::requester::connection_state::SpdmRequesterState;
 use spdmlib::responder;
 
 #[test]
 fn intergration_client_server() {
+    client_server(default_test_suite());
+}
+
+#[test]
+fn intergration_client_server_ecp256() {
+    client_server(&TEST_SUITE_ECP256);
+}
+
+/// Connect a requester to a responder, both configured with suite, and run
+/// test on the requester.
+fn with_requester(suite: &TestCryptoSuite, test: impl FnOnce(&mut requester::RequesterContext)) {
     spdmlib::secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
 
     let shared_buffer = SharedBuffer::new();
     let device_io_responder = &mut FakeSpdmDeviceIoReceve::new(&shared_buffer);
     let transport_encap_responder = &mut PciDoeTransportEncap {};
 
-    let (config_info, provision_info) = rsp_create_info();
+    let (config_info, provision_info) = rsp_create_info_with_suite(suite);
     let mut responder_context = responder::ResponderContext::new(
         device_io_responder,
         transport_encap_responder,
@@ -40,7 +59,7 @@ fn intergration_client_server() {
     let device_io_requester = &mut FakeSpdmDeviceIo::new(&shared_buffer, &mut responder_context);
     let transport_encap_requester = &mut PciDoeTransportEncap {};
 
-    let (config_info, provision_info) = req_create_info();
+    let (config_info, provision_info) = req_create_info_with_suite(suite);
     let mut requester_context = requester::RequesterContext::new(
         device_io_requester,
         transport_encap_requester,
@@ -48,69 +67,199 @@ fn intergration_client_server() {
         provision_info,
     );
 
-    assert!(!requester_context.init_connection().is_err());
+    test(&mut requester_context);
+}
 
-    assert!(!requester_context.send_receive_spdm_digest(None).is_err());
+fn client_server(suite: &TestCryptoSuite) {
+    with_requester(suite, |requester_context| {
+        assert!(!requester_context.init_connection().is_err());
 
-    assert!(!requester_context
-        .send_receive_spdm_certificate(None, 0)
-        .is_err());
+        assert!(!requester_context.send_receive_spdm_digest(None).is_err());
 
-    #[cfg(feature = "mut-auth")]
-    {
-        requester_context.common.negotiate_info.rsp_capabilities_sel |=
-            SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
-        requester_context.common.negotiate_info.req_capabilities_sel |=
-            SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
-        requester_context.common.negotiate_info.req_asym_sel =
-            SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
-        requester_context.common.provision_info.my_cert_chain = [
-            Some(get_rsp_cert_chain_buff()),
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-        ];
-    }
+        assert!(!requester_context
+            .send_receive_spdm_certificate(None, 0)
+            .is_err());
 
-    let result = requester_context.start_session(
-        false,
-        0,
-        SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
-    );
-    assert!(result.is_ok());
-    if let Ok(session_id) = result {
-        log::info!(
-            "\nSession established ... session_id is {:0x?}\n",
-            session_id
+        #[cfg(feature = "mut-auth")]
+        {
+            requester_context.common.negotiate_info.rsp_capabilities_sel |=
+                SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
+            requester_context.common.negotiate_info.req_capabilities_sel |=
+                SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
+            requester_context.common.negotiate_info.req_asym_sel = suite.req_asym_algo;
+            requester_context.common.provision_info.my_cert_chain = [
+                Some(get_rsp_cert_chain_buff_with_suite(suite)),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ];
+        }
+
+        let result = requester_context.start_session(
+            false,
+            0,
+            SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
         );
-        log::info!("Key Information ...\n");
+        assert!(result.is_ok());
+        if let Ok(session_id) = result {
+            log::info!(
+                "\nSession established ... session_id is {:0x?}\n",
+                session_id
+            );
+            log::info!("Key Information ...\n");
+
+            let session = requester_context
+                .common
+                .get_session_via_id(session_id)
+                .expect("get session failed!");
+            let (request_direction, response_direction) = session.export_keys();
+            log::info!(
+                "request_direction.encryption_key {:0x?}\n",
+                request_direction.encryption_key.as_ref()
+            );
+            log::info!(
+                "request_direction.salt {:0x?}\n",
+                request_direction.salt.as_ref()
+            );
+            log::info!(
+                "response_direction.encryption_key {:0x?}\n",
+                response_direction.encryption_key.as_ref()
+            );
+            log::info!(
+                "response_direction.salt {:0x?}\n",
+                response_direction.salt.as_ref()
+            );
+        } else {
+            log::info!("\nSession session_id not got ????? \n");
+        }
+    });
+}
+
+#[test]
+fn intergration_negotiated_suite() {
+    for suite in [default_test_suite(), &TEST_SUITE_ECP256] {
+        with_requester(suite, |requester_context| {
+            assert!(requester_context.init_connection().is_ok());
+            assert_eq!(
+                requester_context.common.negotiate_info.base_hash_sel,
+                suite.base_hash_algo
+            );
+            assert_eq!(
+                requester_context.common.negotiate_info.aead_sel,
+                suite.aead_algo
+            );
+        });
+    }
+}
+
+#[test]
+fn intergration_negotiation_audit_log() {
+    let suite = default_test_suite();
+    with_requester(suite, |requester_context| {
+        assert!(requester_context.init_connection().is_ok());
 
-        let session = requester_context
+        let audit_log = requester_context
             .common
-            .get_session_via_id(session_id)
-            .expect("get session failed!");
-        let (request_direction, response_direction) = session.export_keys();
-        log::info!(
-            "request_direction.encryption_key {:0x?}\n",
-            request_direction.encryption_key.as_ref()
+            .export_negotiation_audit_log()
+            .unwrap();
+        assert_eq!(audit_log.message_count(), MAX_SPDM_VCA_MESSAGE_COUNT);
+        assert_eq!(audit_log.hash_algo(), suite.base_hash_algo);
+        let raw_messages = requester_context
+            .common
+            .runtime_info
+            .vca_boundary
+            .split(requester_context.common.runtime_info.message_a.as_ref())
+            .unwrap();
+        assert!(verify_negotiation_audit_log(&audit_log, &raw_messages).is_ok());
+
+        let mut persisted = [0u8; 1024];
+        let mut writer = Writer::init(&mut persisted);
+        assert!(audit_log.encode(&mut writer).is_ok());
+        let used = writer.used();
+        let restored = SpdmNegotiationAuditLog::read_bytes(&persisted[..used]).unwrap();
+        assert_eq!(restored.head(), audit_log.head());
+        assert!(verify_negotiation_audit_log(&restored, &raw_messages).is_ok());
+
+        // ALGORITHMS altered after it was logged
+        let algorithms = raw_messages
+            .iter()
+            .position(|message| {
+                message.get(1) == Some(&SpdmRequestResponseCode::SpdmResponseAlgorithms.get_u8())
+            })
+            .unwrap();
+        let mut altered_algorithms = raw_messages[algorithms].to_vec();
+        altered_algorithms[2] ^= 1;
+        let mut altered_messages = raw_messages;
+        altered_messages[algorithms] = &altered_algorithms;
+        assert!(verify_negotiation_audit_log(&restored, &altered_messages).is_err());
+
+        // a message missing
+        assert!(
+            verify_negotiation_audit_log(&restored, &raw_messages[..raw_messages.len() - 1])
+                .is_err()
         );
-        log::info!(
-            "request_direction.salt {:0x?}\n",
-            request_direction.salt.as_ref()
+    });
+}
+
+#[test]
+fn intergration_requester_state() {
+    let suite = default_test_suite();
+    with_requester(suite, |requester_context| {
+        assert_eq!(
+            requester_context.get_requester_state(),
+            SpdmRequesterState::NotStarted
         );
-        log::info!(
-            "response_direction.encryption_key {:0x?}\n",
-            response_direction.encryption_key.as_ref()
+        assert!(requester_context.init_connection().is_ok());
+        assert_eq!(
+            requester_context.get_requester_state(),
+            SpdmRequesterState::Negotiated
         );
-        log::info!(
-            "response_direction.salt {:0x?}\n",
-            response_direction.salt.as_ref()
+        assert!(requester_context.resume_connection().is_ok());
+
+        assert!(requester_context.send_receive_spdm_digest(None).is_ok());
+        assert!(requester_context
+            .send_receive_spdm_certificate(None, 0)
+            .is_ok());
+        assert_eq!(
+            requester_context.get_requester_state(),
+            SpdmRequesterState::AfterCertificate
         );
-    } else {
-        log::info!("\nSession session_id not got ????? \n");
-    }
+
+        assert!(requester_context
+            .send_receive_spdm_challenge(
+                0,
+                SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+            )
+            .is_ok());
+        assert_eq!(
+            requester_context.get_requester_state(),
+            SpdmRequesterState::Authenticated
+        );
+
+        #[cfg(feature = "mut-auth")]
+        {
+            requester_context.common.negotiate_info.rsp_capabilities_sel |=
+                SpdmResponseCapabilityFlags::MUT_AUTH_CAP;
+            requester_context.common.negotiate_info.req_capabilities_sel |=
+                SpdmRequestCapabilityFlags::MUT_AUTH_CAP;
+            requester_context.common.negotiate_info.req_asym_sel = suite.req_asym_algo;
+            requester_context.common.provision_info.my_cert_chain[0] =
+                Some(get_rsp_cert_chain_buff_with_suite(suite));
+        }
+        assert!(requester_context
+            .start_session(
+                false,
+                0,
+                SpdmMeasurementSummaryHashType::SpdmMeasurementSummaryHashTypeNone,
+            )
+            .is_ok());
+        assert_eq!(
+            requester_context.get_requester_state(),
+            SpdmRequesterState::SessionEstablished
+        );
+    });
 }
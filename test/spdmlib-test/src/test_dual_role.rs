@@ -0,0 +1,99 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::secret_callback::SECRET_ASYM_IMPL_INSTANCE;
+use crate::common::transport::{PciDoeTransportEncap, PCI_DOE_MESSAGE_HEADER_SIZE};
+use crate::common::util::{req_create_info, rsp_create_info};
+use spdmlib::common::{SpdmTransportEncap, ST1};
+use spdmlib::config;
+use spdmlib::dual_role::{current_role, DualRoleContext, SpdmRole};
+use spdmlib::message::*;
+use spdmlib::requester::connection_state::SpdmRequesterState;
+use spdmlib::{requester, responder};
+
+#[test]
+fn intergration_dual_role() {
+    spdmlib::secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+
+    // downstream device
+    let downstream_buffer = SharedBuffer::new();
+    let device_io_downstream = &mut FakeSpdmDeviceIoReceve::new(&downstream_buffer);
+    let transport_encap_downstream = &mut PciDoeTransportEncap {};
+    let (config_info, provision_info) = rsp_create_info();
+    let mut downstream_context = responder::ResponderContext::new(
+        device_io_downstream,
+        transport_encap_downstream,
+        config_info,
+        provision_info,
+    );
+
+    // dual role endpoint
+    let upstream_buffer = SharedBuffer::new();
+    let device_io_upstream = &mut FakeSpdmDeviceIoReceve::new(&upstream_buffer);
+    let transport_encap_upstream = &mut PciDoeTransportEncap {};
+    let (config_info, provision_info) = rsp_create_info();
+    let responder_context = responder::ResponderContext::new(
+        device_io_upstream,
+        transport_encap_upstream,
+        config_info,
+        provision_info,
+    );
+
+    let device_io_requester =
+        &mut FakeSpdmDeviceIo::new(&downstream_buffer, &mut downstream_context);
+    let transport_encap_requester = &mut PciDoeTransportEncap {};
+    let (config_info, provision_info) = req_create_info();
+    let requester_context = requester::RequesterContext::new(
+        device_io_requester,
+        transport_encap_requester,
+        config_info,
+        provision_info,
+    );
+
+    let mut dual_role = DualRoleContext::new(responder_context, requester_context);
+
+    // The downstream device is attested while acting as requester.
+    assert!(dual_role
+        .with_downstream(|requester| {
+            assert_eq!(current_role(), Some(SpdmRole::Requester));
+            requester.init_connection()
+        })
+        .is_ok());
+    assert_eq!(
+        dual_role.requester.get_requester_state(),
+        SpdmRequesterState::Negotiated
+    );
+    assert_eq!(current_role(), None);
+
+    // GET_VERSION from the upstream requester is served by the responder.
+    let request = [
+        0x10,
+        SpdmRequestResponseCode::SpdmRequestGetVersion.get_u8(),
+        0x00,
+        0x00,
+    ];
+    let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+    let used = PciDoeTransportEncap {}
+        .encap(&request, &mut transport_buffer, false)
+        .unwrap();
+    upstream_buffer.set_buffer(&transport_buffer[..used]);
+    assert_eq!(
+        dual_role.process_upstream_message(ST1, &[0]).ok(),
+        Some(true)
+    );
+    let used = upstream_buffer.get_buffer(&mut transport_buffer);
+    assert_eq!(
+        transport_buffer[PCI_DOE_MESSAGE_HEADER_SIZE + 1],
+        SpdmRequestResponseCode::SpdmResponseVersion.get_u8()
+    );
+    assert!(used > PCI_DOE_MESSAGE_HEADER_SIZE + 4);
+    assert_eq!(current_role(), None);
+
+    // Upstream events are sent while acting as responder.
+    dual_role.with_upstream(|_| {
+        assert_eq!(current_role(), Some(SpdmRole::Responder));
+    });
+    assert_eq!(dual_role.process_downstream_async_message(), Ok(false));
+}
@@ -0,0 +1,47 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::executor::{yield_now, SeededExecutor};
+use std::cell::RefCell;
+
+fn run_interleaving(seed: u64) -> Vec<u8> {
+    let order = RefCell::new(Vec::new());
+    {
+        let mut executor = SeededExecutor::new(seed);
+        for id in 0..2u8 {
+            let order = &order;
+            executor.spawn(async move {
+                for _ in 0..4 {
+                    order.borrow_mut().push(id);
+                    yield_now().await;
+                }
+            });
+        }
+        assert!(executor.run(64));
+    }
+    order.into_inner()
+}
+
+#[test]
+fn test_case0_seeded_executor() {
+    let order = run_interleaving(0);
+    assert_eq!(order.len(), 8);
+    assert_eq!(order.iter().filter(|id| **id == 0).count(), 4);
+    // the same seed replays the same interleaving
+    assert_eq!(run_interleaving(0), order);
+
+    let orders: Vec<Vec<u8>> = (0..16).map(run_interleaving).collect();
+    assert!(orders.iter().any(|other| *other != order));
+}
+
+#[test]
+fn test_case1_seeded_executor_max_polls() {
+    let mut executor = SeededExecutor::new(1);
+    executor.spawn(async {
+        loop {
+            yield_now().await;
+        }
+    });
+    assert!(!executor.run(16));
+}
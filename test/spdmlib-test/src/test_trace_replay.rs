@@ -0,0 +1,87 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+use crate::common::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use crate::common::transport::PciDoeTransportEncap;
+use crate::common::util::create_info;
+use crate::trace_replay::replay_trace;
+use spdmlib::common::SpdmMessageDirection;
+use spdmlib::requester::RequesterContext;
+use spdmlib::responder;
+use spdmlib_trace::{read_trace, SpdmTraceBuffer, SpdmTraceTransport, SpdmTraceWriter};
+
+#[test]
+fn test_case0_trace_replay() {
+    let trace = SpdmTraceBuffer::new();
+    {
+        let (rsp_config_info, rsp_provision_info) = create_info();
+        let (req_config_info, req_provision_info) = create_info();
+
+        let shared_buffer = SharedBuffer::new();
+        let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+        let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+        let mut responder = responder::ResponderContext::new(
+            &mut device_io_responder,
+            pcidoe_transport_encap,
+            rsp_config_info,
+            rsp_provision_info,
+        );
+
+        let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+        let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+        let mut requester = RequesterContext::new(
+            &mut device_io_requester,
+            pcidoe_transport_encap2,
+            req_config_info,
+            req_provision_info,
+        );
+        let writer = SpdmTraceWriter::new(trace.clone(), SpdmTraceTransport::PciDoe).unwrap();
+        requester.common.set_message_capture(Some(Box::new(writer)));
+
+        assert!(requester.init_connection().is_ok());
+    }
+
+    let (transport, records) = read_trace(&trace.contents()).unwrap();
+    assert_eq!(transport, SpdmTraceTransport::PciDoe);
+    assert_eq!(records.len(), 6);
+    assert_eq!(records[0].direction, SpdmMessageDirection::Sent);
+    assert!(records[0].is_request());
+
+    let (config_info, provision_info) = create_info();
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+
+    let report = replay_trace(&mut responder, &shared_buffer, &records);
+    assert_eq!(report.replayed_requests, 3);
+    assert_eq!(report.stopped_at, None);
+    assert!(report.mismatches.is_empty());
+
+    // a tampered response is reported
+    let mut tampered = records.clone();
+    let last = tampered[5].message.len() - 1;
+    tampered[5].message[last] ^= 0xFF;
+    let (config_info, provision_info) = create_info();
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    let report = replay_trace(&mut responder, &shared_buffer, &tampered);
+    assert_eq!(report.replayed_requests, 3);
+    assert_eq!(report.mismatches.len(), 1);
+    assert_eq!(report.mismatches[0].request_index, 4);
+}
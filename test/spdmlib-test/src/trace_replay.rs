@@ -0,0 +1,97 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Replay of a spdmlib-trace file against a responder. Every request sent in
+//! the clear is given to the responder and its responses are compared with
+//! the recorded ones. Secured messages cannot be replayed without the session
+//! keys, the replay stops at the first one.
+
+use crate::common::device_io::SharedBuffer;
+use spdmlib::common::{SpdmMessageDirection, ST1};
+use spdmlib::config;
+use spdmlib::responder::ResponderContext;
+use spdmlib_trace::SpdmTraceRecord;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpdmReplayMismatch {
+    /// Index in the trace of the request.
+    pub request_index: usize,
+    pub expected: Vec<Vec<u8>>,
+    pub actual: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpdmReplayReport {
+    pub replayed_requests: usize,
+    /// Index of the secured message the replay stopped at.
+    pub stopped_at: Option<usize>,
+    pub mismatches: Vec<SpdmReplayMismatch>,
+}
+
+/// Replay records against responder, whose device IO receives from and sends
+/// to shared_buffer, e.g. FakeSpdmDeviceIoReceve. The trace may have been
+/// captured on either side. The message capture of the responder is
+/// replaced during the replay.
+pub fn replay_trace(
+    responder: &mut ResponderContext,
+    shared_buffer: &SharedBuffer,
+    records: &[SpdmTraceRecord],
+) -> SpdmReplayReport {
+    let responses = Rc::new(RefCell::new(Vec::new()));
+    let captured = responses.clone();
+    responder.common.set_message_capture(Some(Box::new(
+        move |direction: SpdmMessageDirection, _session_id: Option<u32>, message: &[u8]| {
+            if direction == SpdmMessageDirection::Sent {
+                captured.borrow_mut().push(message.to_vec());
+            }
+        },
+    )));
+
+    let mut report = SpdmReplayReport::default();
+    let mut index = 0;
+    while index < records.len() {
+        let request = &records[index];
+        if request.is_secured() {
+            report.stopped_at = Some(index);
+            break;
+        }
+        if !request.is_request() {
+            index += 1;
+            continue;
+        }
+
+        let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+        let used = responder
+            .common
+            .transport_encap
+            .encap(&request.message, &mut transport_buffer, false)
+            .expect("request does not fit the transport");
+        shared_buffer.set_buffer(&transport_buffer[..used]);
+        let _ = responder.process_message(ST1, &[0]);
+        shared_buffer.take_all();
+        report.replayed_requests += 1;
+
+        let request_index = index;
+        let mut expected = Vec::new();
+        index += 1;
+        while index < records.len() && !records[index].is_secured() && !records[index].is_request()
+        {
+            expected.push(records[index].message.clone());
+            index += 1;
+        }
+        let actual: Vec<Vec<u8>> = responses.borrow_mut().drain(..).collect();
+        if actual != expected {
+            report.mismatches.push(SpdmReplayMismatch {
+                request_index,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    responder.common.set_message_capture(None);
+    report
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! The time source is registered for the whole process, this test runs in a
+//! binary of its own so that the other tests run without one.
+
+#![forbid(unsafe_code)]
+#![cfg(feature = "async-io")]
+
+use core::pin::Pin;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use core::task::{Context, Poll, Waker};
+use futures_core::Stream;
+use spdmlib::common::session::{SpdmSession, SpdmSessionState};
+use spdmlib::error::SPDM_STATUS_INVALID_STATE_LOCAL;
+use spdmlib::protocol::*;
+use spdmlib::requester::heartbeat_task::{SpdmHeartbeatEvent, SpdmHeartbeatStream};
+use spdmlib::requester::RequesterContext;
+use spdmlib::time::SpdmTime;
+use spdmlib::{responder, secret, time};
+use spdmlib_testutils::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use spdmlib_testutils::secret_callback::*;
+use spdmlib_testutils::transport::PciDoeTransportEncap;
+use spdmlib_testutils::util::create_info;
+use std::sync::Arc;
+use std::task::Wake;
+
+// A clock which only moves when the test advances it.
+static NOW_US: AtomicU64 = AtomicU64::new(0);
+static WAKE_AT_US: AtomicU64 = AtomicU64::new(u64::MAX);
+
+static TIME_IMPL: SpdmTime = SpdmTime::new(
+    |_us: usize| panic!("the heartbeat stream must not sleep"),
+    || NOW_US.load(Ordering::SeqCst),
+)
+.with_wake_at(|deadline_us: u64, waker: Waker| {
+    WAKE_AT_US.store(deadline_us, Ordering::SeqCst);
+    if deadline_us <= NOW_US.load(Ordering::SeqCst) {
+        waker.wake();
+    }
+});
+
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn setup_session(session: &mut SpdmSession, session_id: u32) {
+    session.setup(session_id).unwrap();
+    session.set_crypto_param(
+        SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+        SpdmDheAlgo::SECP_384_R1,
+        SpdmAeadAlgo::AES_256_GCM,
+        SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
+    );
+    assert!(session
+        .set_dhe_secret(
+            SpdmVersion::SpdmVersion12,
+            SpdmDheFinalKeyStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_DHE_KEY_SIZE])
+            }
+        )
+        .is_ok());
+    assert!(session
+        .generate_handshake_secret(
+            SpdmVersion::SpdmVersion12,
+            &SpdmDigestStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+            }
+        )
+        .is_ok());
+    assert!(session
+        .generate_data_secret(
+            SpdmVersion::SpdmVersion12,
+            &SpdmDigestStruct {
+                data_size: 5,
+                data: Box::new([100u8; SPDM_MAX_HASH_SIZE])
+            }
+        )
+        .is_ok());
+    session.set_session_state(SpdmSessionState::SpdmSessionEstablished);
+}
+
+#[test]
+fn test_case0_heartbeat_stream() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    time::register(TIME_IMPL.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+
+    let session_id = (0x11u32 << 16) + 0x11u32;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.session = gen_array_clone(SpdmSession::new(), 4);
+    setup_session(&mut responder.common.session[0], session_id);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.req_capabilities_sel = SpdmRequestCapabilityFlags::HBEAT_CAP;
+    requester.common.negotiate_info.rsp_capabilities_sel = SpdmResponseCapabilityFlags::HBEAT_CAP;
+    requester.common.session = gen_array_clone(SpdmSession::new(), 4);
+    setup_session(&mut requester.common.session[0], session_id);
+    requester.common.session[0].heartbeat_period = 2;
+
+    let waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let task_waker = Waker::from(waker.clone());
+    let mut cx = Context::from_waker(&task_waker);
+
+    let mut stream = SpdmHeartbeatStream::new(&mut requester, session_id).unwrap();
+
+    // Not due yet, the stream waits for the timer instead of sleeping.
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+    assert_eq!(WAKE_AT_US.load(Ordering::SeqCst), 1_000_000);
+    assert_eq!(waker.0.load(Ordering::SeqCst), 0);
+
+    NOW_US.store(1_000_000, Ordering::SeqCst);
+    assert_eq!(
+        Pin::new(&mut stream).poll_next(&mut cx),
+        Poll::Ready(Some(SpdmHeartbeatEvent::Acked {
+            next_due_us: 2_000_000
+        }))
+    );
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+    assert_eq!(WAKE_AT_US.load(Ordering::SeqCst), 2_000_000);
+
+    // The requester is free again between the heartbeats.
+    let requester = stream.into_inner();
+    requester.common.session[0].set_session_state(SpdmSessionState::SpdmSessionNotStarted);
+    let mut stream = SpdmHeartbeatStream::new(requester, session_id).unwrap();
+    assert_eq!(
+        Pin::new(&mut stream).poll_next(&mut cx),
+        Poll::Ready(Some(SpdmHeartbeatEvent::SessionLost(
+            SPDM_STATUS_INVALID_STATE_LOCAL
+        )))
+    );
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(None));
+}
@@ -0,0 +1,205 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! The time source is registered for the whole process, this test runs in a
+//! binary of its own so that the other tests run without one.
+
+#![forbid(unsafe_code)]
+
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use spdmlib::common::{timing, SpdmConnectionState};
+use spdmlib::message::{SpdmMeasurementAttributes, SpdmMeasurementOperation};
+use spdmlib::protocol::*;
+use spdmlib::requester::RequesterContext;
+use spdmlib::responder::{SpdmResponseReadiness, SpdmResponseReadyHandler};
+use spdmlib::time::SpdmTime;
+use spdmlib::{config, responder, secret, time};
+use spdmlib_testutils::device_io::{FakeSpdmDeviceIo, FakeSpdmDeviceIoReceve, SharedBuffer};
+use spdmlib_testutils::secret_callback::*;
+use spdmlib_testutils::transport::{PciDoeTransportEncap, PCI_DOE_MESSAGE_HEADER_SIZE};
+use spdmlib_testutils::util::{create_info, get_rsp_cert_chain_buff};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static NOT_READY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn check_response_ready_cb(
+    _spdm_version: SpdmVersion,
+    _session_id: Option<u32>,
+    _request: &[u8],
+) -> SpdmResponseReadiness {
+    if NOT_READY_COUNT.load(Ordering::SeqCst) == 0 {
+        SpdmResponseReadiness::Ready
+    } else {
+        NOT_READY_COUNT.fetch_sub(1, Ordering::SeqCst);
+        SpdmResponseReadiness::NotReady {
+            rdt_exponent: 3,
+            rdtm: 2,
+        }
+    }
+}
+
+// Microseconds since the first call.
+fn now_us() -> u64 {
+    static START: Mutex<Option<Instant>> = Mutex::new(None);
+    let mut start = START.lock().unwrap();
+    start.get_or_insert_with(Instant::now).elapsed().as_micros() as u64
+}
+
+static TIME_IMPL: SpdmTime = SpdmTime::new(
+    |us: usize| std::thread::sleep(Duration::from_micros(us as u64)),
+    now_us,
+);
+
+#[test]
+fn test_case0_receive_deferred_response() {
+    let (rsp_config_info, rsp_provision_info) = create_info();
+    let (req_config_info, req_provision_info) = create_info();
+
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::asym_sign::register(SECRET_ASYM_IMPL_INSTANCE.clone());
+    secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
+    time::register(TIME_IMPL.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        rsp_config_info,
+        rsp_provision_info,
+    );
+    responder.response_ready_handler = Some(SpdmResponseReadyHandler {
+        check_response_ready_cb,
+    });
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    responder
+        .common
+        .negotiate_info
+        .measurement_specification_sel = SpdmMeasurementSpecification::DMTF;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    responder.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    responder.common.reset_runtime_info();
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    let pcidoe_transport_encap2 = &mut PciDoeTransportEncap {};
+    let mut device_io_requester = FakeSpdmDeviceIo::new(&shared_buffer, &mut responder);
+
+    let mut requester = RequesterContext::new(
+        &mut device_io_requester,
+        pcidoe_transport_encap2,
+        req_config_info,
+        req_provision_info,
+    );
+    requester.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion12;
+    requester
+        .common
+        .negotiate_info
+        .measurement_specification_sel = SpdmMeasurementSpecification::DMTF;
+    requester.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    requester.common.negotiate_info.base_asym_sel = SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384;
+    requester.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    requester.common.peer_info.peer_cert_chain[0] = Some(get_rsp_cert_chain_buff());
+    requester.common.reset_runtime_info();
+
+    // two ERROR(ResponseNotReady) are retried with RESPOND_IF_READY
+    NOT_READY_COUNT.store(2, Ordering::SeqCst);
+    let mut total_number: u8 = 0;
+    let mut spdm_measurement_record_structure = SpdmMeasurementRecordStructure::default();
+    assert!(requester
+        .send_receive_spdm_measurement(
+            None,
+            0,
+            SpdmMeasurementAttributes::empty(),
+            SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber,
+            &mut total_number,
+            &mut spdm_measurement_record_structure,
+        )
+        .is_ok());
+    assert_ne!(total_number, 0);
+
+    // the responder is still not ready after the last retry
+    NOT_READY_COUNT.store(usize::MAX, Ordering::SeqCst);
+    assert!(requester
+        .send_receive_spdm_measurement(
+            None,
+            0,
+            SpdmMeasurementAttributes::empty(),
+            SpdmMeasurementOperation::SpdmMeasurementQueryTotalNumber,
+            &mut total_number,
+            &mut spdm_measurement_record_structure,
+        )
+        .is_err());
+    NOT_READY_COUNT.store(0, Ordering::SeqCst);
+}
+
+static RESPONSE_READY: AtomicBool = AtomicBool::new(true);
+
+fn check_response_ready_as_recorded_cb(
+    _spdm_version: SpdmVersion,
+    _session_id: Option<u32>,
+    _request: &[u8],
+) -> SpdmResponseReadiness {
+    if RESPONSE_READY.load(Ordering::SeqCst) {
+        SpdmResponseReadiness::Ready
+    } else {
+        SpdmResponseReadiness::NotReadyAsRecorded
+    }
+}
+
+#[test]
+fn test_case1_rdt_from_recorded_latency() {
+    let (config_info, provision_info) = create_info();
+    let shared_buffer = SharedBuffer::new();
+    let mut device_io_responder = FakeSpdmDeviceIoReceve::new(&shared_buffer);
+    let pcidoe_transport_encap = &mut PciDoeTransportEncap {};
+
+    secret::measurement::register(SECRET_MEASUREMENT_IMPL_INSTANCE.clone());
+    time::register(TIME_IMPL.clone());
+
+    let mut responder = responder::ResponderContext::new(
+        &mut device_io_responder,
+        pcidoe_transport_encap,
+        config_info,
+        provision_info,
+    );
+    responder.response_ready_handler = Some(SpdmResponseReadyHandler {
+        check_response_ready_cb: check_response_ready_as_recorded_cb,
+    });
+    responder.common.negotiate_info.spdm_version_sel = SpdmVersion::SpdmVersion11;
+    responder
+        .common
+        .negotiate_info
+        .measurement_specification_sel = SpdmMeasurementSpecification::DMTF;
+    responder.common.negotiate_info.base_hash_sel = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
+    responder.common.negotiate_info.measurement_hash_sel = SpdmMeasurementHashAlgo::TPM_ALG_SHA_384;
+    responder
+        .common
+        .runtime_info
+        .set_connection_state(SpdmConnectionState::SpdmConnectionNegotiated);
+
+    // the GET_MEASUREMENTS handler latency is recorded
+    assert!(responder
+        .dispatch_message(&[0x11, 0xE0, 0x00, 0x00])
+        .is_ok());
+    assert_eq!(responder.crypto_latency.count, 1);
+
+    RESPONSE_READY.store(false, Ordering::SeqCst);
+    assert!(responder
+        .dispatch_message(&[0x11, 0xE0, 0x00, 0x00])
+        .is_ok());
+    let (rdt_exponent, rdtm) = timing::rdt_from_latency(responder.crypto_latency.max_us);
+    let buffer = &mut [0u8; config::SENDER_BUFFER_SIZE];
+    let used = shared_buffer.get_buffer(buffer);
+    assert_eq!(
+        buffer[PCI_DOE_MESSAGE_HEADER_SIZE..used][..8],
+        [0x11, 0x7F, 0x42, 0x00, rdt_exponent, 0xE0, 0x00, rdtm]
+    );
+    RESPONSE_READY.store(true, Ordering::SeqCst);
+}
@@ -34,30 +34,31 @@ pub static FAKE_HKDF: SpdmHkdf = SpdmHkdf {
 pub static FAKE_CERT_OPERATION: SpdmCertOperation = SpdmCertOperation {
     get_cert_from_cert_chain_cb: fake_get_cert_from_cert_chain,
     verify_cert_chain_cb: fake_verify_cert_chain,
+    verify_cert_cb: fake_verify_cert,
 };
 
 fn fake_hmac(
-    _base_hash_algo: SpdmBaseHashAlgo,
+    base_hash_algo: SpdmBaseHashAlgo,
     _key: &[u8],
     _data: &[u8],
 ) -> Option<SpdmDigestStruct> {
     let tag = SpdmDigestStruct {
-        data_size: 48,
+        data_size: base_hash_algo.get_size(),
         data: Box::new([10u8; SPDM_MAX_HASH_SIZE]),
     };
     Some(tag)
 }
 
 fn fake_hmac_verify(
-    _base_hash_algo: SpdmBaseHashAlgo,
+    base_hash_algo: SpdmBaseHashAlgo,
     _key: &[u8],
     _data: &[u8],
     hmac: &SpdmDigestStruct,
 ) -> SpdmResult {
-    let SpdmDigestStruct { data_size, .. } = hmac;
-    match data_size {
-        48 => Ok(()),
-        _ => Err(SPDM_STATUS_VERIF_FAIL),
+    if hmac.data_size == base_hash_algo.get_size() {
+        Ok(())
+    } else {
+        Err(SPDM_STATUS_VERIF_FAIL)
     }
 }
 
@@ -165,7 +166,11 @@ fn fake_get_cert_from_cert_chain(cert_chain: &[u8], _index: isize) -> SpdmResult
     return Ok((0, cert_chain.len()));
 }
 
-fn fake_verify_cert_chain(_cert_chain: &[u8]) -> SpdmResult {
+fn fake_verify_cert_chain(_cert_chain: &[u8], _time: Option<u64>) -> SpdmResult {
+    Ok(())
+}
+
+fn fake_verify_cert(_cert: &[u8], _issuer_cert: &[u8], _time: Option<u64>) -> SpdmResult {
     Ok(())
 }
 
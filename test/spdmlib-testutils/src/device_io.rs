@@ -0,0 +1,471 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+#![allow(unused)]
+
+use spdmlib::common::{SpdmDeviceIo, SpdmDeviceIoEvent, ST1};
+use spdmlib::config;
+use spdmlib::error::{SpdmResult, SPDM_STATUS_ERROR_PEER};
+use spdmlib::responder;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::task::Waker;
+
+pub struct MySpdmDeviceIo;
+
+impl SpdmDeviceIo for MySpdmDeviceIo {
+    fn send(&mut self, _buffer: &[u8]) -> SpdmResult {
+        todo!()
+    }
+
+    fn receive(&mut self, _buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        todo!()
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        todo!()
+    }
+}
+
+pub struct FakeSpdmDeviceIo<'a> {
+    pub data: &'a SharedBuffer,
+    pub responder: &'a mut responder::ResponderContext<'a>,
+}
+
+impl<'a> FakeSpdmDeviceIo<'a> {
+    pub fn new(data: &'a SharedBuffer, responder: &'a mut responder::ResponderContext<'a>) -> Self {
+        FakeSpdmDeviceIo { data, responder }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIo<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        let len = self.data.get_buffer(read_buffer);
+        log::info!("requester receive RAW - {:02x?}\n", &read_buffer[0..len]);
+        Ok(len)
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(buffer);
+        log::info!("requester send    RAW - {:02x?}\n", buffer);
+
+        if self.responder.process_message(ST1, &[0]).is_err() {
+            return Err(SPDM_STATUS_ERROR_PEER);
+        }
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+
+    // The responder answers within send(), nothing arrives in between.
+    fn poll_receive_ready(&mut self, _waker: &Waker) -> bool {
+        !self.data.is_empty()
+    }
+}
+
+/// Like FakeSpdmDeviceIo, but keeps every response as a separate message so
+/// that the requester may send several requests before receiving.
+pub struct FakeSpdmDeviceIoPipelined<'a> {
+    pub data: &'a SharedBuffer,
+    pub responder: &'a mut responder::ResponderContext<'a>,
+    pub responses: VecDeque<Vec<u8>>,
+    pub max_outstanding: usize,
+    /// Index of the response whose SPDM request_response_code is corrupted.
+    pub corrupt_response: Option<usize>,
+    response_count: usize,
+}
+
+impl<'a> FakeSpdmDeviceIoPipelined<'a> {
+    pub fn new(data: &'a SharedBuffer, responder: &'a mut responder::ResponderContext<'a>) -> Self {
+        FakeSpdmDeviceIoPipelined {
+            data,
+            responder,
+            responses: VecDeque::new(),
+            max_outstanding: 0,
+            corrupt_response: None,
+            response_count: 0,
+        }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIoPipelined<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        let response = self.responses.pop_front().ok_or(0usize)?;
+        read_buffer[..response.len()].copy_from_slice(&response);
+        log::info!(
+            "requester receive RAW - {:02x?}\n",
+            &read_buffer[0..response.len()]
+        );
+        Ok(response.len())
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(buffer);
+        log::info!("requester send    RAW - {:02x?}\n", buffer);
+
+        if self.responder.process_message(ST1, &[0]).is_err() {
+            return Err(SPDM_STATUS_ERROR_PEER);
+        }
+        let mut response = [0u8; config::SENDER_BUFFER_SIZE];
+        let len = self.data.get_buffer(&mut response);
+        if self.corrupt_response == Some(self.response_count) {
+            // PCI DOE header (8 bytes) + SPDMVersion, then RequestResponseCode
+            response[9] = 0;
+        }
+        self.response_count += 1;
+        self.responses.push_back(response[..len].to_vec());
+        self.max_outstanding = self.max_outstanding.max(self.responses.len());
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        self.responses.clear();
+        Ok(())
+    }
+}
+
+pub struct SpdmDeviceIoReceve<'a> {
+    data: &'a SharedBuffer,
+    fuzzdata: &'a [u8],
+}
+
+impl<'a> SpdmDeviceIoReceve<'a> {
+    pub fn new(data: &'a SharedBuffer, fuzzdata: &'a [u8]) -> Self {
+        SpdmDeviceIoReceve { data, fuzzdata }
+    }
+}
+
+impl SpdmDeviceIo for SpdmDeviceIoReceve<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        let len = self.data.get_buffer(read_buffer);
+        log::info!("responder receive RAW - {:02x?}\n", &read_buffer[0..len]);
+        Ok(len)
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(self.fuzzdata);
+        log::info!("responder send    RAW - {:02x?}\n", buffer);
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+}
+
+pub struct FakeSpdmDeviceIoReceve<'a> {
+    data: &'a SharedBuffer,
+}
+
+impl<'a> FakeSpdmDeviceIoReceve<'a> {
+    pub fn new(data: &'a SharedBuffer) -> Self {
+        FakeSpdmDeviceIoReceve { data }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIoReceve<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        let len = self.data.get_buffer(read_buffer);
+        log::info!("responder receive RAW - {:02x?}\n", &read_buffer[0..len]);
+        Ok(len)
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(buffer);
+        log::info!("responder send    RAW - {:02x?}\n", buffer);
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+}
+
+/// Responder side device IO which reports a message larger than the receive
+/// buffer with its size, instead of truncating it.
+pub struct FakeSpdmDeviceIoOversized<'a> {
+    data: &'a SharedBuffer,
+}
+
+impl<'a> FakeSpdmDeviceIoOversized<'a> {
+    pub fn new(data: &'a SharedBuffer) -> Self {
+        FakeSpdmDeviceIoOversized { data }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIoOversized<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        let message = self.data.take_all();
+        if message.len() > read_buffer.len() {
+            return Err(message.len());
+        }
+        read_buffer[..message.len()].copy_from_slice(&message);
+        Ok(message.len())
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(buffer);
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+}
+
+/// Responder side device IO which reports a peer disconnect once disconnected is set.
+pub struct FakeSpdmDeviceIoDisconnect<'a> {
+    data: &'a SharedBuffer,
+    disconnected: &'a Cell<bool>,
+}
+
+impl<'a> FakeSpdmDeviceIoDisconnect<'a> {
+    pub fn new(data: &'a SharedBuffer, disconnected: &'a Cell<bool>) -> Self {
+        FakeSpdmDeviceIoDisconnect { data, disconnected }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIoDisconnect<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        if self.disconnected.get() {
+            return Err(0);
+        }
+        let len = self.data.get_buffer(read_buffer);
+        Ok(len)
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(buffer);
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+
+    fn poll_event(&mut self) -> SpdmDeviceIoEvent {
+        if self.disconnected.get() {
+            SpdmDeviceIoEvent::PeerDisconnected
+        } else {
+            SpdmDeviceIoEvent::None
+        }
+    }
+}
+
+/// Device IO which raises the async message status on send_async and
+/// reports it until the pending message is received.
+pub struct FakeSpdmDeviceIoAsync<'a> {
+    data: &'a SharedBuffer,
+    pending: &'a Cell<bool>,
+}
+
+impl<'a> FakeSpdmDeviceIoAsync<'a> {
+    pub fn new(data: &'a SharedBuffer, pending: &'a Cell<bool>) -> Self {
+        FakeSpdmDeviceIoAsync { data, pending }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIoAsync<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        let len = self.data.get_buffer(read_buffer);
+        self.pending.set(false);
+        Ok(len)
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(buffer);
+        Ok(())
+    }
+
+    fn send_async(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.data.set_buffer(buffer);
+        self.pending.set(true);
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+
+    fn poll_event(&mut self) -> SpdmDeviceIoEvent {
+        if self.pending.get() {
+            SpdmDeviceIoEvent::AsyncMessagePending
+        } else {
+            SpdmDeviceIoEvent::None
+        }
+    }
+}
+
+/// Device IO which replays scripted transport messages on receive and
+/// records every sent transport message.
+pub struct FakeSpdmDeviceIoScripted<'a> {
+    responses: &'a RefCell<VecDeque<Vec<u8>>>,
+    requests: &'a RefCell<Vec<Vec<u8>>>,
+}
+
+impl<'a> FakeSpdmDeviceIoScripted<'a> {
+    pub fn new(
+        responses: &'a RefCell<VecDeque<Vec<u8>>>,
+        requests: &'a RefCell<Vec<Vec<u8>>>,
+    ) -> Self {
+        FakeSpdmDeviceIoScripted {
+            responses,
+            requests,
+        }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIoScripted<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], _timeout: usize) -> Result<usize, usize> {
+        let response = self.responses.borrow_mut().pop_front().ok_or(0usize)?;
+        read_buffer[..response.len()].copy_from_slice(&response);
+        Ok(response.len())
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.requests.borrow_mut().push(buffer.to_vec());
+        Ok(())
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+}
+
+/// Like FakeSpdmDeviceIo, but vectored. The buffer sizes of each vectored
+/// send and receive are recorded.
+pub struct FakeSpdmDeviceIoVectored<'a> {
+    inner: FakeSpdmDeviceIo<'a>,
+    sizes: &'a RefCell<Vec<Vec<usize>>>,
+}
+
+impl<'a> FakeSpdmDeviceIoVectored<'a> {
+    pub fn new(
+        data: &'a SharedBuffer,
+        responder: &'a mut responder::ResponderContext<'a>,
+        sizes: &'a RefCell<Vec<Vec<usize>>>,
+    ) -> Self {
+        FakeSpdmDeviceIoVectored {
+            inner: FakeSpdmDeviceIo::new(data, responder),
+            sizes,
+        }
+    }
+}
+
+impl SpdmDeviceIo for FakeSpdmDeviceIoVectored<'_> {
+    fn receive(&mut self, read_buffer: &mut [u8], timeout: usize) -> Result<usize, usize> {
+        self.inner.receive(read_buffer, timeout)
+    }
+
+    fn send(&mut self, buffer: &[u8]) -> SpdmResult {
+        self.inner.send(buffer)
+    }
+
+    fn flush_all(&mut self) -> SpdmResult {
+        Ok(())
+    }
+
+    fn send_vectored(&mut self, buffers: &[&[u8]]) -> SpdmResult {
+        self.sizes
+            .borrow_mut()
+            .push(buffers.iter().map(|buffer| buffer.len()).collect());
+        self.inner.send(&buffers.concat())
+    }
+
+    fn receive_vectored(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        timeout: usize,
+    ) -> Result<usize, usize> {
+        self.sizes
+            .borrow_mut()
+            .push(buffers.iter().map(|buffer| buffer.len()).collect());
+        let mut contiguous = vec![0u8; buffers.iter().map(|buffer| buffer.len()).sum()];
+        let used = self.inner.receive(&mut contiguous, timeout)?;
+        let mut offset = 0;
+        for buffer in buffers.iter_mut() {
+            let len = buffer.len().min(used - offset);
+            buffer[..len].copy_from_slice(&contiguous[offset..(offset + len)]);
+            offset += len;
+        }
+        Ok(used)
+    }
+
+    fn is_vectored(&self) -> bool {
+        true
+    }
+}
+
+pub struct SharedBuffer {
+    queue: RefCell<VecDeque<u8>>,
+}
+
+impl SharedBuffer {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        SharedBuffer {
+            queue: RefCell::new(VecDeque::<u8>::new()),
+        }
+    }
+    pub fn set_buffer(&self, b: &[u8]) {
+        log::info!("send    {:02x?}\n", b);
+        let mut queue = self.queue.borrow_mut();
+        for i in b {
+            queue.push_back(*i);
+        }
+    }
+
+    pub fn get_buffer(&self, b: &mut [u8]) -> usize {
+        let mut queue = self.queue.borrow_mut();
+        let mut len = 0usize;
+        for i in b.iter_mut() {
+            if queue.is_empty() {
+                break;
+            }
+            *i = queue.pop_front().unwrap();
+            len += 1;
+        }
+        log::info!("recieve {:02x?}\n", &b[..len]);
+        len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.borrow().is_empty()
+    }
+
+    pub fn take_all(&self) -> Vec<u8> {
+        self.queue.borrow_mut().drain(..).collect()
+    }
+}
+
+#[test]
+fn test_fake_device_io() {
+    let buffer = SharedBuffer::new();
+    let mut server = FakeSpdmDeviceIoReceve::new(&buffer);
+    let mut client = FakeSpdmDeviceIoReceve::new(&buffer);
+    const SEND_DATA: &[u8] = &[1, 2];
+    client.send(SEND_DATA).unwrap();
+    let mut rev = [0u8, 64];
+    server.receive(&mut rev, ST1).unwrap();
+    assert_eq!(&rev[..=1], SEND_DATA)
+}
+
+#[test]
+fn test_fake_device_io_vectored() {
+    let buffer = SharedBuffer::new();
+    let mut server = FakeSpdmDeviceIoReceve::new(&buffer);
+    let mut client = FakeSpdmDeviceIoReceve::new(&buffer);
+    client.send_vectored(&[&[1, 2], &[], &[3, 4, 5]]).unwrap();
+
+    let mut header = [0u8; 2];
+    let mut body = [0u8; 8];
+    let used = server
+        .receive_vectored(&mut [&mut header, &mut body], ST1)
+        .unwrap();
+    assert_eq!(used, 5);
+    assert_eq!(header, [1, 2]);
+    assert_eq!(&body[..3], &[3, 4, 5]);
+}
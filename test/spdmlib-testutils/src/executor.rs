@@ -0,0 +1,94 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Deterministic single threaded executor for the async requester and
+//! responder paths. The next future to poll is picked by a seeded generator,
+//! so a fuzzer can explore interleavings and a seed replays one of them.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+type Task<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+pub struct SeededExecutor<'a> {
+    tasks: Vec<Option<Task<'a>>>,
+    state: u64,
+}
+
+impl<'a> SeededExecutor<'a> {
+    pub fn new(seed: u64) -> Self {
+        SeededExecutor {
+            tasks: Vec::new(),
+            state: seed,
+        }
+    }
+
+    pub fn spawn(&mut self, future: impl Future<Output = ()> + 'a) {
+        self.tasks.push(Some(Box::pin(future)));
+    }
+
+    // splitmix64
+    fn next_random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Poll one pending future picked by the seed at a time, until all of
+    /// them complete or max_polls polls are done.
+    /// Return true if all futures completed.
+    pub fn run(&mut self, max_polls: usize) -> bool {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        for _ in 0..max_polls {
+            let pending: Vec<usize> = self
+                .tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| task.is_some())
+                .map(|(index, _)| index)
+                .collect();
+            if pending.is_empty() {
+                return true;
+            }
+            let index = pending[(self.next_random() % pending.len() as u64) as usize];
+            if let Some(task) = self.tasks[index].as_mut() {
+                if task.as_mut().poll(&mut cx).is_ready() {
+                    self.tasks[index] = None;
+                }
+            }
+        }
+        self.tasks.iter().all(Option::is_none)
+    }
+}
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            Poll::Pending
+        }
+    }
+}
+
+/// Return Pending once, so that the executor may switch to another future.
+pub async fn yield_now() {
+    YieldNow(false).await
+}
@@ -0,0 +1,22 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Reusable harness for rust-spdm integration tests: in-memory device IO
+//! connecting a requester to a responder, a PCI DOE style transport,
+//! crypto and secret callbacks backed by the keys under `test_key`, and
+//! `create_info` helpers for the config and provision info.
+
+#![forbid(unsafe_code)]
+
+// TBD: need test different algorithm combinations
+pub const USE_ECDSA: bool = true;
+
+pub mod util;
+
+pub mod device_io;
+pub mod executor;
+pub mod transport;
+
+pub mod crypto_callback;
+pub mod secret_callback;
@@ -4,7 +4,7 @@
 
 #![allow(dead_code)]
 #![allow(unused_variables)]
-use crate::common::util::get_test_key_directory;
+use crate::util::get_test_key_directory;
 use codec::{u24, Codec, Writer};
 use spdmlib::common::key_schedule::SpdmKeySchedule;
 use spdmlib::config;
@@ -297,7 +297,11 @@ fn sign_ecdsa_asym_algo(
     data: &[u8],
 ) -> Option<SpdmSignatureStruct> {
     let crate_dir = get_test_key_directory();
-    let key_file_path = crate_dir.join("test_key/ecp384/end_responder.key.p8");
+    let key_file_path = if algorithm == &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING {
+        crate_dir.join("test_key/ecp256/end_responder.key.p8")
+    } else {
+        crate_dir.join("test_key/ecp384/end_responder.key.p8")
+    };
     let der_file = std::fs::read(key_file_path).expect("unable to read key der!");
     let key_bytes = der_file.as_slice();
 
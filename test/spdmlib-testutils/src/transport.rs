@@ -2,9 +2,11 @@
 //
 // SPDX-License-Identifier: BSD-2-Clause-Patent
 
+use crate::device_io::SharedBuffer;
 use codec::enum_builder;
 use codec::{Codec, Reader, Writer};
 use spdmlib::common::SpdmTransportEncap;
+use spdmlib::config;
 use spdmlib::error::{SpdmResult, SPDM_STATUS_DECAP_FAIL, SPDM_STATUS_ENCAP_FAIL};
 
 enum_builder! {
@@ -35,6 +37,8 @@ impl Default for PciDoeDataObjectType {
     }
 }
 
+pub const PCI_DOE_MESSAGE_HEADER_SIZE: usize = 8;
+
 #[derive(Debug, Clone, Default)]
 pub struct PciDoeMessageHeader {
     pub vendor_id: PciDoeVendorId,
@@ -160,6 +164,42 @@ impl SpdmTransportEncap for PciDoeTransportEncap {
         Ok((app_buffer.len(), false))
     }
 
+    fn get_header_size(&mut self, _secured_message: bool) -> usize {
+        PCI_DOE_MESSAGE_HEADER_SIZE
+    }
+
+    fn encap_in_place(
+        &mut self,
+        transport_buffer: &mut [u8],
+        spdm_size: usize,
+        secured_message: bool,
+    ) -> SpdmResult<usize> {
+        let aligned_payload_len = (spdm_size + 3) / 4 * 4;
+        if transport_buffer.len() < PCI_DOE_MESSAGE_HEADER_SIZE + aligned_payload_len {
+            return Err(SPDM_STATUS_ENCAP_FAIL);
+        }
+        for d in transport_buffer[(PCI_DOE_MESSAGE_HEADER_SIZE + spdm_size)
+            ..(PCI_DOE_MESSAGE_HEADER_SIZE + aligned_payload_len)]
+            .iter_mut()
+        {
+            *d = 0;
+        }
+        let mut writer = Writer::init(&mut transport_buffer[..PCI_DOE_MESSAGE_HEADER_SIZE]);
+        let pcidoe_header = PciDoeMessageHeader {
+            vendor_id: PciDoeVendorId::PciDoeVendorIdPciSig,
+            data_object_type: if secured_message {
+                PciDoeDataObjectType::PciDoeDataObjectTypeSecuredSpdm
+            } else {
+                PciDoeDataObjectType::PciDoeDataObjectTypeSpdm
+            },
+            payload_length: aligned_payload_len as u32,
+        };
+        pcidoe_header
+            .encode(&mut writer)
+            .map_err(|_| SPDM_STATUS_ENCAP_FAIL)?;
+        Ok(PCI_DOE_MESSAGE_HEADER_SIZE + aligned_payload_len)
+    }
+
     fn get_sequence_number_count(&mut self) -> u8 {
         0
     }
@@ -167,3 +207,20 @@ impl SpdmTransportEncap for PciDoeTransportEncap {
         0
     }
 }
+
+/// Wrap spdm_message in a PCI DOE header, ready for SharedBuffer::set_buffer.
+pub fn encap(spdm_message: &[u8]) -> Vec<u8> {
+    let mut transport_buffer = [0u8; config::SENDER_BUFFER_SIZE];
+    let used = PciDoeTransportEncap {}
+        .encap(spdm_message, &mut transport_buffer, false)
+        .unwrap();
+    transport_buffer[..used].to_vec()
+}
+
+/// Take the message sent into shared_buffer without its PCI DOE header.
+/// The message may be followed by PCI DOE padding.
+pub fn sent_message(shared_buffer: &SharedBuffer) -> Vec<u8> {
+    let buffer = &mut [0u8; config::SENDER_BUFFER_SIZE];
+    let used = shared_buffer.get_buffer(buffer);
+    buffer[PCI_DOE_MESSAGE_HEADER_SIZE..used].to_vec()
+}
@@ -4,10 +4,10 @@
 
 #![allow(unused)]
 
-use super::USE_ECDSA;
-use crate::common::device_io::MySpdmDeviceIo;
-use crate::common::secret_callback::SECRET_ASYM_IMPL_INSTANCE;
-use crate::common::transport::PciDoeTransportEncap;
+use crate::device_io::MySpdmDeviceIo;
+use crate::secret_callback::SECRET_ASYM_IMPL_INSTANCE;
+use crate::transport::PciDoeTransportEncap;
+use crate::USE_ECDSA;
 use codec::{Reader, Writer};
 use spdmlib::common::{
     SpdmCodec, SpdmConfigInfo, SpdmContext, SpdmOpaqueSupport, SpdmProvisionInfo,
@@ -19,12 +19,83 @@ use spdmlib::message::SpdmMessage;
 use spdmlib::protocol::*;
 use std::path::PathBuf;
 
+/// Algorithms and test_key directory of one crypto suite.
+pub struct TestCryptoSuite {
+    pub base_asym_algo: SpdmBaseAsymAlgo,
+    pub req_asym_algo: SpdmReqAsymAlgo,
+    pub base_hash_algo: SpdmBaseHashAlgo,
+    pub measurement_hash_algo: SpdmMeasurementHashAlgo,
+    pub dhe_algo: SpdmDheAlgo,
+    pub aead_algo: SpdmAeadAlgo,
+    pub key_dir: &'static str,
+}
+
+pub const TEST_SUITE_ECP384: TestCryptoSuite = TestCryptoSuite {
+    base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+    req_asym_algo: SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384,
+    base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+    measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+    dhe_algo: SpdmDheAlgo::SECP_384_R1,
+    aead_algo: SpdmAeadAlgo::AES_256_GCM,
+    key_dir: "test_key/ecp384",
+};
+
+/// Minimal suite of MCU class devices.
+pub const TEST_SUITE_ECP256: TestCryptoSuite = TestCryptoSuite {
+    base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+    req_asym_algo: SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P256,
+    base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_256,
+    measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_256,
+    dhe_algo: SpdmDheAlgo::SECP_256_R1,
+    aead_algo: SpdmAeadAlgo::AES_128_GCM,
+    key_dir: "test_key/ecp256",
+};
+
+pub const TEST_SUITE_RSA3072: TestCryptoSuite = TestCryptoSuite {
+    base_asym_algo: SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072,
+    req_asym_algo: SpdmReqAsymAlgo::TPM_ALG_RSASSA_3072,
+    base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
+    measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
+    dhe_algo: SpdmDheAlgo::SECP_384_R1,
+    aead_algo: SpdmAeadAlgo::AES_256_GCM,
+    key_dir: "test_key/rsa3072",
+};
+
+/// Suite of req_create_info() and rsp_create_info().
+pub fn default_test_suite() -> &'static TestCryptoSuite {
+    if USE_ECDSA {
+        &TEST_SUITE_ECP384
+    } else {
+        &TEST_SUITE_RSA3072
+    }
+}
+
+/// Read ca, inter and end_responder certificates of the suite.
+fn read_test_certs(suite: &TestCryptoSuite) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let key_dir = get_test_key_directory().join(suite.key_dir);
+    log::info!("{}", key_dir.display());
+    let ca_cert = std::fs::read(key_dir.join("ca.cert.der")).expect("unable to read ca cert!");
+    let inter_cert =
+        std::fs::read(key_dir.join("inter.cert.der")).expect("unable to read inter cert!");
+    let leaf_cert =
+        std::fs::read(key_dir.join("end_responder.cert.der")).expect("unable to read leaf cert!");
+    log::info!(
+        "total cert size - {:?} = {:?} + {:?} + {:?}",
+        ca_cert.len() + inter_cert.len() + leaf_cert.len(),
+        ca_cert.len(),
+        inter_cert.len(),
+        leaf_cert.len()
+    );
+    (ca_cert, inter_cert, leaf_cert)
+}
+
 pub fn create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
     let config_info = SpdmConfigInfo {
         spdm_version: [
             SpdmVersion::SpdmVersion10,
             SpdmVersion::SpdmVersion11,
             SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
         ],
         rsp_capabilities: SpdmResponseCapabilityFlags::CERT_CAP
             | SpdmResponseCapabilityFlags::CHAL_CAP
@@ -106,6 +177,10 @@ pub fn create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
         ],
         my_cert_chain: [None, None, None, None, None, None, None, None],
         peer_root_cert_data: Some(peer_root_cert_data),
+        key_pair_info: Default::default(),
+        my_pub_key: None,
+        peer_pub_key: None,
+        my_cert_chain_algo: Default::default(),
     };
 
     (config_info, provision_info)
@@ -136,6 +211,10 @@ pub fn new_spdm_message(value: SpdmMessage, mut context: SpdmContext) -> SpdmMes
 }
 
 pub fn req_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
+    req_create_info_with_suite(default_test_suite())
+}
+
+pub fn req_create_info_with_suite(suite: &TestCryptoSuite) -> (SpdmConfigInfo, SpdmProvisionInfo) {
     let req_capabilities = SpdmRequestCapabilityFlags::CERT_CAP
         | SpdmRequestCapabilityFlags::CHAL_CAP
         | SpdmRequestCapabilityFlags::ENCRYPT_CAP
@@ -157,23 +236,16 @@ pub fn req_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
             SpdmVersion::SpdmVersion10,
             SpdmVersion::SpdmVersion11,
             SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
         ],
         req_capabilities: req_capabilities,
         req_ct_exponent: 0,
         measurement_specification: SpdmMeasurementSpecification::DMTF,
-        base_asym_algo: if USE_ECDSA {
-            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
-        },
-        base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
-        dhe_algo: SpdmDheAlgo::SECP_384_R1,
-        aead_algo: SpdmAeadAlgo::AES_256_GCM,
-        req_asym_algo: if USE_ECDSA {
-            SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmReqAsymAlgo::TPM_ALG_RSASSA_3072
-        },
+        base_asym_algo: suite.base_asym_algo,
+        base_hash_algo: suite.base_hash_algo,
+        dhe_algo: suite.dhe_algo,
+        aead_algo: suite.aead_algo,
+        req_asym_algo: suite.req_asym_algo,
         key_schedule_algo: SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
         opaque_support: SpdmOpaqueSupport::OPAQUE_DATA_FMT1,
         data_transfer_size: config::MAX_SPDM_MSG_SIZE as u32,
@@ -185,36 +257,11 @@ pub fn req_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
         ..Default::default()
     };
 
-    let crate_dir = get_test_key_directory();
-    let ca_file_path = if USE_ECDSA {
-        crate_dir.join("test_key/ecp384/ca.cert.der")
-    } else {
-        crate_dir.join("test_key/rsa3072/ca.cert.der")
-    };
-    let ca_cert = std::fs::read(ca_file_path).expect("unable to read ca cert!");
-    let inter_file_path = if USE_ECDSA {
-        crate_dir.join("test_key/ecp384/inter.cert.der")
-    } else {
-        crate_dir.join("test_key/rsa3072/inter.cert.der")
-    };
-    let inter_cert = std::fs::read(inter_file_path).expect("unable to read inter cert!");
-    let leaf_file_path = if USE_ECDSA {
-        crate_dir.join("test_key/ecp384/end_responder.cert.der")
-    } else {
-        crate_dir.join("test_key/rsa3072/end_responder.cert.der")
-    };
-    let leaf_cert = std::fs::read(leaf_file_path).expect("unable to read leaf cert!");
+    let (ca_cert, inter_cert, leaf_cert) = read_test_certs(suite);
 
     let ca_len = ca_cert.len();
     let inter_len = inter_cert.len();
     let leaf_len = leaf_cert.len();
-    log::info!(
-        "total cert size - {:?} = {:?} + {:?} + {:?}",
-        ca_len + inter_len + leaf_len,
-        ca_len,
-        inter_len,
-        leaf_len
-    );
     peer_root_cert_data.data_size = (ca_len) as u16;
     peer_root_cert_data.data[0..ca_len].copy_from_slice(ca_cert.as_ref());
 
@@ -243,12 +290,20 @@ pub fn req_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
             ],
             my_cert_chain: [None, None, None, None, None, None, None, None],
             peer_root_cert_data: Some(peer_root_cert_data),
+            key_pair_info: Default::default(),
+            my_pub_key: None,
+            peer_pub_key: None,
+            my_cert_chain_algo: Default::default(),
         }
     } else {
         SpdmProvisionInfo {
             my_cert_chain_data: [None, None, None, None, None, None, None, None],
             my_cert_chain: [None, None, None, None, None, None, None, None],
             peer_root_cert_data: Some(peer_root_cert_data),
+            key_pair_info: Default::default(),
+            my_pub_key: None,
+            peer_pub_key: None,
+            my_cert_chain_algo: Default::default(),
         }
     };
 
@@ -256,6 +311,10 @@ pub fn req_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
 }
 
 pub fn rsp_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
+    rsp_create_info_with_suite(default_test_suite())
+}
+
+pub fn rsp_create_info_with_suite(suite: &TestCryptoSuite) -> (SpdmConfigInfo, SpdmProvisionInfo) {
     let rsp_capabilities = SpdmResponseCapabilityFlags::CERT_CAP
         | SpdmResponseCapabilityFlags::CHAL_CAP
         | SpdmResponseCapabilityFlags::MEAS_CAP_SIG
@@ -279,24 +338,17 @@ pub fn rsp_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
             SpdmVersion::SpdmVersion10,
             SpdmVersion::SpdmVersion11,
             SpdmVersion::SpdmVersion12,
+            SpdmVersion::SpdmVersion13,
         ],
         rsp_capabilities: rsp_capabilities,
         rsp_ct_exponent: 0,
         measurement_specification: SpdmMeasurementSpecification::DMTF,
-        measurement_hash_algo: SpdmMeasurementHashAlgo::TPM_ALG_SHA_384,
-        base_asym_algo: if USE_ECDSA {
-            SpdmBaseAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmBaseAsymAlgo::TPM_ALG_RSASSA_3072
-        },
-        base_hash_algo: SpdmBaseHashAlgo::TPM_ALG_SHA_384,
-        dhe_algo: SpdmDheAlgo::SECP_384_R1,
-        aead_algo: SpdmAeadAlgo::AES_256_GCM,
-        req_asym_algo: if USE_ECDSA {
-            SpdmReqAsymAlgo::TPM_ALG_ECDSA_ECC_NIST_P384
-        } else {
-            SpdmReqAsymAlgo::TPM_ALG_RSASSA_3072
-        },
+        measurement_hash_algo: suite.measurement_hash_algo,
+        base_asym_algo: suite.base_asym_algo,
+        base_hash_algo: suite.base_hash_algo,
+        dhe_algo: suite.dhe_algo,
+        aead_algo: suite.aead_algo,
+        req_asym_algo: suite.req_asym_algo,
         key_schedule_algo: SpdmKeyScheduleAlgo::SPDM_KEY_SCHEDULE,
         opaque_support: SpdmOpaqueSupport::OPAQUE_DATA_FMT1,
         data_transfer_size: config::MAX_SPDM_MSG_SIZE as u32,
@@ -310,37 +362,11 @@ pub fn rsp_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
         ..Default::default()
     };
 
-    let crate_dir = get_test_key_directory();
-    let ca_file_path = if USE_ECDSA {
-        crate_dir.join("test_key/ecp384/ca.cert.der")
-    } else {
-        crate_dir.join("test_key/rsa3072/ca.cert.der")
-    };
-    log::info!("{}", ca_file_path.display());
-    let ca_cert = std::fs::read(ca_file_path).expect("unable to read ca cert!");
-    let inter_file_path = if USE_ECDSA {
-        crate_dir.join("test_key/ecp384/inter.cert.der")
-    } else {
-        crate_dir.join("test_key/rsa3072/inter.cert.der")
-    };
-    let inter_cert = std::fs::read(inter_file_path).expect("unable to read inter cert!");
-    let leaf_file_path = if USE_ECDSA {
-        crate_dir.join("test_key/ecp384/end_responder.cert.der")
-    } else {
-        crate_dir.join("test_key/rsa3072/end_responder.cert.der")
-    };
-    let leaf_cert = std::fs::read(leaf_file_path).expect("unable to read leaf cert!");
+    let (ca_cert, inter_cert, leaf_cert) = read_test_certs(suite);
 
     let ca_len = ca_cert.len();
     let inter_len = inter_cert.len();
     let leaf_len = leaf_cert.len();
-    log::info!(
-        "total cert size - {:?} = {:?} + {:?} + {:?}",
-        ca_len + inter_len + leaf_len,
-        ca_len,
-        inter_len,
-        leaf_len
-    );
     my_cert_chain_data.data_size = (ca_len + inter_len + leaf_len) as u16;
     my_cert_chain_data.data[0..ca_len].copy_from_slice(ca_cert.as_ref());
     my_cert_chain_data.data[ca_len..(ca_len + inter_len)].copy_from_slice(inter_cert.as_ref());
@@ -360,6 +386,10 @@ pub fn rsp_create_info() -> (SpdmConfigInfo, SpdmProvisionInfo) {
         ],
         my_cert_chain: [None, None, None, None, None, None, None, None],
         peer_root_cert_data: None,
+        key_pair_info: Default::default(),
+        my_pub_key: None,
+        peer_pub_key: None,
+        my_cert_chain_algo: Default::default(),
     };
 
     (config_info, provision_info)
@@ -376,8 +406,16 @@ pub fn get_test_key_directory() -> PathBuf {
 }
 
 pub fn get_rsp_cert_chain_buff() -> SpdmCertChainBuffer {
-    let hash_algo = SpdmBaseHashAlgo::TPM_ALG_SHA_384;
-    let cert_chain = include_bytes!("../../../../test_key/ecp384/bundle_responder.certchain.der");
+    get_rsp_cert_chain_buff_with_suite(&TEST_SUITE_ECP384)
+}
+
+pub fn get_rsp_cert_chain_buff_with_suite(suite: &TestCryptoSuite) -> SpdmCertChainBuffer {
+    let hash_algo = suite.base_hash_algo;
+    let cert_chain_file_path = get_test_key_directory()
+        .join(suite.key_dir)
+        .join("bundle_responder.certchain.der");
+    let cert_chain = std::fs::read(cert_chain_file_path).expect("unable to read cert chain!");
+    let cert_chain = cert_chain.as_slice();
 
     let (root_cert_begin, root_cert_end) =
         crypto::cert_operation::get_cert_from_cert_chain(cert_chain, 0)
@@ -0,0 +1,288 @@
+// Copyright (c) 2023 Intel Corporation
+//
+// SPDX-License-Identifier: BSD-2-Clause-Patent
+
+//! Trace files of the SPDM messages of a context, written from its message
+//! capture hook and read back for replay.
+//!
+//! All the fields are little endian. The file starts with a header:
+//!
+//!   magic "SPDMTRC\0" | version u16 | transport u16 | reserved u32
+//!
+//! followed by one record per captured message:
+//!
+//!   timestamp_us u64 | direction u8 | flags u8 | reserved u16 |
+//!   session_id u32 | length u32 | message
+//!
+//! The timestamp counts from the creation of the writer. Flag bit 0 marks a
+//! secured message, session_id is 0 for a message sent in the clear.
+
+#![forbid(unsafe_code)]
+
+use std::cell::RefCell;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+use std::time::Instant;
+
+use spdmlib::common::{SpdmMessageCapture, SpdmMessageDirection};
+
+pub const SPDM_TRACE_MAGIC: [u8; 8] = *b"SPDMTRC\0";
+pub const SPDM_TRACE_VERSION: u16 = 1;
+
+const SPDM_TRACE_FLAG_SECURED: u8 = 0x1;
+const SPDM_TRACE_RECORD_HEADER_SIZE: usize = 20;
+// larger than any SPDM message, a bigger length means a corrupted file
+const SPDM_TRACE_MAX_MESSAGE_SIZE: usize = 0x100000;
+
+/// Transport the messages were carried on, informational only since the
+/// messages are recorded without transport header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdmTraceTransport {
+    Unknown,
+    PciDoe,
+    Mctp,
+    Storage,
+}
+
+impl SpdmTraceTransport {
+    pub fn get_u16(self) -> u16 {
+        match self {
+            SpdmTraceTransport::Unknown => 0,
+            SpdmTraceTransport::PciDoe => 1,
+            SpdmTraceTransport::Mctp => 2,
+            SpdmTraceTransport::Storage => 3,
+        }
+    }
+
+    pub fn from_u16(value: u16) -> Self {
+        match value {
+            1 => SpdmTraceTransport::PciDoe,
+            2 => SpdmTraceTransport::Mctp,
+            3 => SpdmTraceTransport::Storage,
+            _ => SpdmTraceTransport::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpdmTraceRecord {
+    pub timestamp_us: u64,
+    pub direction: SpdmMessageDirection,
+    /// Some for a secured message.
+    pub session_id: Option<u32>,
+    pub message: Vec<u8>,
+}
+
+impl SpdmTraceRecord {
+    pub fn is_secured(&self) -> bool {
+        self.session_id.is_some()
+    }
+
+    /// True for a request, whichever side captured it.
+    pub fn is_request(&self) -> bool {
+        self.message.len() >= 2 && self.message[1] & 0x80 != 0
+    }
+}
+
+/// Writes a trace, set it as the message capture of a context with
+/// SpdmContext::set_message_capture.
+pub struct SpdmTraceWriter<W: Write> {
+    writer: W,
+    start: Instant,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> SpdmTraceWriter<W> {
+    pub fn new(mut writer: W, transport: SpdmTraceTransport) -> io::Result<Self> {
+        writer.write_all(&SPDM_TRACE_MAGIC)?;
+        writer.write_all(&SPDM_TRACE_VERSION.to_le_bytes())?;
+        writer.write_all(&transport.get_u16().to_le_bytes())?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        Ok(SpdmTraceWriter {
+            writer,
+            start: Instant::now(),
+            error: None,
+        })
+    }
+
+    pub fn write_record(&mut self, record: &SpdmTraceRecord) -> io::Result<()> {
+        let mut header = [0u8; SPDM_TRACE_RECORD_HEADER_SIZE];
+        header[0..8].copy_from_slice(&record.timestamp_us.to_le_bytes());
+        header[8] = match record.direction {
+            SpdmMessageDirection::Sent => 0,
+            SpdmMessageDirection::Received => 1,
+        };
+        if record.is_secured() {
+            header[9] = SPDM_TRACE_FLAG_SECURED;
+        }
+        header[12..16].copy_from_slice(&record.session_id.unwrap_or(0).to_le_bytes());
+        header[16..20].copy_from_slice(&(record.message.len() as u32).to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&record.message)?;
+        self.writer.flush()
+    }
+
+    /// The first error met while capturing, which stops the capture.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> SpdmMessageCapture for SpdmTraceWriter<W> {
+    fn capture(
+        &mut self,
+        direction: SpdmMessageDirection,
+        session_id: Option<u32>,
+        message: &[u8],
+    ) {
+        if self.error.is_some() {
+            return;
+        }
+        let record = SpdmTraceRecord {
+            timestamp_us: self.start.elapsed().as_micros() as u64,
+            direction,
+            session_id,
+            message: message.to_vec(),
+        };
+        if let Err(error) = self.write_record(&record) {
+            self.error = Some(error);
+        }
+    }
+}
+
+/// In memory trace file, shared with the writer given to the context.
+#[derive(Debug, Clone, Default)]
+pub struct SpdmTraceBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SpdmTraceBuffer {
+    pub fn new() -> Self {
+        SpdmTraceBuffer::default()
+    }
+
+    pub fn contents(&self) -> Vec<u8> {
+        self.0.borrow().clone()
+    }
+}
+
+impl Write for SpdmTraceBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct SpdmTraceReader<R: Read> {
+    reader: R,
+    transport: SpdmTraceTransport,
+}
+
+impl<R: Read> SpdmTraceReader<R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 16];
+        reader.read_exact(&mut header)?;
+        if header[0..8] != SPDM_TRACE_MAGIC {
+            return Err(invalid_data("not a SPDM trace"));
+        }
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        if version != SPDM_TRACE_VERSION {
+            return Err(invalid_data("unsupported SPDM trace version"));
+        }
+        let transport = SpdmTraceTransport::from_u16(u16::from_le_bytes([header[10], header[11]]));
+        Ok(SpdmTraceReader { reader, transport })
+    }
+
+    pub fn transport(&self) -> SpdmTraceTransport {
+        self.transport
+    }
+
+    /// Return None at the end of the trace.
+    pub fn read_record(&mut self) -> io::Result<Option<SpdmTraceRecord>> {
+        let mut header = [0u8; SPDM_TRACE_RECORD_HEADER_SIZE];
+        match self.reader.read_exact(&mut header[..1]) {
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            result => result?,
+        }
+        self.reader.read_exact(&mut header[1..])?;
+
+        let mut timestamp_us = [0u8; 8];
+        timestamp_us.copy_from_slice(&header[0..8]);
+        let direction = match header[8] {
+            0 => SpdmMessageDirection::Sent,
+            1 => SpdmMessageDirection::Received,
+            _ => return Err(invalid_data("invalid direction")),
+        };
+        let session_id = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+        let length = u32::from_le_bytes([header[16], header[17], header[18], header[19]]) as usize;
+        if length > SPDM_TRACE_MAX_MESSAGE_SIZE {
+            return Err(invalid_data("message too large"));
+        }
+        let mut message = vec![0u8; length];
+        self.reader.read_exact(&mut message)?;
+
+        Ok(Some(SpdmTraceRecord {
+            timestamp_us: u64::from_le_bytes(timestamp_us),
+            direction,
+            session_id: if header[9] & SPDM_TRACE_FLAG_SECURED != 0 {
+                Some(session_id)
+            } else {
+                None
+            },
+            message,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for SpdmTraceReader<R> {
+    type Item = io::Result<SpdmTraceRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Read a whole trace.
+pub fn read_trace(trace: &[u8]) -> io::Result<(SpdmTraceTransport, Vec<SpdmTraceRecord>)> {
+    let reader = SpdmTraceReader::new(trace)?;
+    let transport = reader.transport();
+    let records = reader.collect::<io::Result<Vec<_>>>()?;
+    Ok((transport, records))
+}
+
+fn invalid_data(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+#[test]
+fn test_trace_round_trip() {
+    let buffer = SpdmTraceBuffer::new();
+    let mut writer = SpdmTraceWriter::new(buffer.clone(), SpdmTraceTransport::Mctp).unwrap();
+    writer.capture(SpdmMessageDirection::Sent, None, &[0x12, 0x84, 0, 0]);
+    writer.capture(
+        SpdmMessageDirection::Received,
+        Some(0xFFFEFFFE),
+        &[0x12, 0x66],
+    );
+    assert!(writer.take_error().is_none());
+
+    let (transport, records) = read_trace(&buffer.contents()).unwrap();
+    assert_eq!(transport, SpdmTraceTransport::Mctp);
+    assert_eq!(records.len(), 2);
+    assert!(records[0].is_request() && !records[0].is_secured());
+    assert_eq!(records[0].message, [0x12, 0x84, 0, 0]);
+    assert_eq!(records[1].direction, SpdmMessageDirection::Received);
+    assert_eq!(records[1].session_id, Some(0xFFFEFFFE));
+    assert!(!records[1].is_request());
+
+    let mut truncated = buffer.contents();
+    truncated.pop();
+    assert!(read_trace(&truncated).is_err());
+    assert!(read_trace(b"not a trace file").is_err());
+}